@@ -8,6 +8,7 @@ use std::time::Duration;
 
 use r2e::prelude::*;
 use r2e::r2e_observability::{Observability, ObservabilityConfig};
+use r2e::r2e_asyncapi::{AsyncApiConfig, AsyncApiPlugin};
 use r2e::r2e_openapi::{OpenApiConfig, OpenApiPlugin};
 use r2e::r2e_prometheus::Prometheus;
 use r2e::r2e_executor::Executor;
@@ -172,6 +173,11 @@ impl App for ExampleApp {
                     .with_description("Demo application showcasing all R2E features")
                     .with_docs_ui(true),
             ))
+            // `UserCreatedEvent` is documented from `#[emits]` on UserController.
+            .with(AsyncApiPlugin::new(AsyncApiConfig::new(
+                "R2E Example Events",
+                "0.1.1",
+            )))
             .on_start(|_state| async move {
                 tracing::info!("R2E example-app startup hook executed");
                 Ok(())
@@ -1,5 +1,5 @@
 use crate::error::AppError;
-use crate::models::{CreateUserRequest, User};
+use crate::models::{CreateUserRequest, User, UserCreatedEvent};
 use crate::services::UserService;
 use r2e::prelude::*;
 use r2e::r2e_rate_limit::RateLimit;
@@ -116,6 +116,7 @@ impl UserController {
     // Demo: cache_invalidate clears the "users" cache group on create
     #[post("/")]
     #[intercept(CacheInvalidate::group("users"))]
+    #[emits(UserCreatedEvent)]
     async fn create(&self, Json(body): Json<CreateUserRequest>) -> Json<User> {
         let user = self.user_service.create(body.name, body.email).await;
         Json(user)
@@ -158,6 +159,7 @@ impl UserController {
     // Demo: rate limiting at handler level with per-user key
     #[post("/rate-limited")]
     #[guard(RateLimit::per_user(5, 60))]
+    #[emits(UserCreatedEvent)]
     async fn create_rate_limited(&self, Json(body): Json<CreateUserRequest>) -> Json<User> {
         let user = self.user_service.create(body.name, body.email).await;
         Json(user)
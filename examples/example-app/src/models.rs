@@ -46,7 +46,7 @@ pub struct UserEntity {
 }
 
 /// Event emitted when a new user is created.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, JsonSchema)]
 pub struct UserCreatedEvent {
     pub user_id: u64,
     pub name: String,
@@ -54,13 +54,13 @@ pub struct UserCreatedEvent {
 }
 
 /// Point-to-point request asking for a greeting (request-reply demo).
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, JsonSchema)]
 pub struct GreetRequest {
     pub name: String,
 }
 
 /// Reply produced by the greeting responder.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, JsonSchema)]
 pub struct GreetReply {
     pub message: String,
 }
@@ -0,0 +1,181 @@
+//! `#[consumer]` and `#[emits(...)]` methods are recorded as `ConsumerInfo` /
+//! `EmitterInfo` at controller registration and rendered by `AsyncApiPlugin`
+//! at `/asyncapi.json`.
+
+use std::sync::Arc;
+
+use r2e::prelude::*;
+use r2e::r2e_asyncapi::{AsyncApiConfig, AsyncApiPlugin, AsyncApiServer};
+use r2e::r2e_events::{Event, LocalEventBus};
+use r2e_test::TestApp;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InvoiceIssued {
+    pub invoice_id: u64,
+    pub amount_cents: i64,
+}
+
+impl Event for InvoiceIssued {
+    fn topic() -> &'static str {
+        "billing.invoices"
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PaymentFailed {
+    pub invoice_id: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct QuoteAccepted {
+    pub sku: String,
+}
+
+impl Event for QuoteAccepted {}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct QuoteRequest {
+    pub sku: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Quote {
+    pub price_cents: i64,
+}
+
+#[controller]
+pub struct BillingConsumers {
+    #[inject]
+    event_bus: LocalEventBus,
+}
+
+#[routes]
+impl BillingConsumers {
+    /// Send the invoice e-mail.
+    #[consumer(bus = "event_bus")]
+    async fn on_invoice(&self, _event: Arc<InvoiceIssued>) {}
+
+    #[consumer(
+        bus = "event_bus",
+        topic = "billing.payments.failed",
+        retry = 3,
+        dlq = "billing.dlq"
+    )]
+    async fn on_payment_failed(&self, _event: Arc<PaymentFailed>) {}
+
+    /// Issue the invoice for an accepted quote.
+    #[consumer(bus = "event_bus")]
+    #[emits(InvoiceIssued)]
+    async fn on_quote_accepted(&self, _event: Arc<QuoteAccepted>) {}
+
+    #[consumer(bus = "event_bus")]
+    async fn quote(&self, _req: Arc<QuoteRequest>) -> Quote {
+        Quote { price_cents: 100 }
+    }
+}
+
+async fn document() -> Value {
+    let app = TestApp::from_builder(
+        AppBuilder::new()
+            .provide(LocalEventBus::new())
+            .build_state()
+            .await
+            .with(AsyncApiPlugin::new(
+                AsyncApiConfig::new("Billing events", "1.0.0")
+                    .with_server(AsyncApiServer::kafka("production", "kafka:9092"))
+                    .publishes::<QuoteAccepted>(),
+            ))
+            .register_controller::<BillingConsumers>(),
+    );
+    let resp = app.get("/asyncapi.json").send().await;
+    resp.assert_ok();
+    resp.json()
+}
+
+#[r2e::test]
+async fn subscriber_uses_event_topic_override_and_doc_comment() {
+    let doc = document().await;
+
+    let op = &doc["operations"]["BillingConsumers_on_invoice"];
+    assert_eq!(op["action"], "receive");
+    assert_eq!(op["summary"], "Send the invoice e-mail.");
+    assert_eq!(op["channel"]["$ref"], "#/channels/billing_invoices");
+    assert_eq!(
+        doc["channels"]["billing_invoices"]["address"],
+        "billing.invoices"
+    );
+    assert_eq!(
+        doc["channels"]["billing_invoices"]["bindings"]["kafka"]["topic"],
+        "billing.invoices"
+    );
+    assert_eq!(
+        doc["components"]["schemas"]["InvoiceIssued"]["properties"]["amount_cents"]["type"],
+        "integer"
+    );
+}
+
+#[r2e::test]
+async fn explicit_topic_and_dead_letter_channel() {
+    let doc = document().await;
+
+    assert_eq!(
+        doc["operations"]["BillingConsumers_on_payment_failed"]["channel"]["$ref"],
+        "#/channels/billing_payments_failed"
+    );
+    let dlq = &doc["operations"]["BillingConsumers_on_payment_failed_dead_letter"];
+    assert_eq!(dlq["action"], "send");
+    assert_eq!(dlq["channel"]["$ref"], "#/channels/billing_dlq");
+}
+
+#[r2e::test]
+async fn responder_documents_request_and_reply() {
+    let doc = document().await;
+
+    let request_channel = doc["channels"]
+        .as_object()
+        .unwrap()
+        .iter()
+        .find(|(_, c)| c["messages"].get("QuoteRequest").is_some() && !c["address"].is_null())
+        .map(|(_, c)| c["address"].as_str().unwrap().to_string())
+        .expect("request channel");
+    assert!(request_channel.ends_with("QuoteRequest.requests"));
+
+    let reply = &doc["operations"]["BillingConsumers_quote"]["reply"];
+    assert_eq!(
+        reply["address"]["location"],
+        "$message.header#/r2e-reply-to"
+    );
+    assert!(doc["components"]["schemas"].get("Quote").is_some());
+}
+
+#[r2e::test]
+async fn declared_emitters_are_send_operations() {
+    let doc = document().await;
+    // `#[emits]` resolves the overridden `Event::topic()`, like consumers do.
+    let op = &doc["operations"]["publish_billing_invoices"];
+    assert_eq!(op["action"], "send");
+    assert_eq!(op["summary"], "Issue the invoice for an accepted quote.");
+    assert_eq!(
+        doc["channels"]["billing_invoices"]["address"],
+        "billing.invoices"
+    );
+
+    // `publishes` lands on the same channel the consumer receives on.
+    let received = &doc["operations"]["BillingConsumers_on_quote_accepted"]["channel"];
+    let published = doc["operations"]
+        .as_object()
+        .unwrap()
+        .iter()
+        .find(|(id, op)| {
+            id.starts_with("publish_")
+                && op["messages"][0]["$ref"]
+                    .as_str()
+                    .is_some_and(|m| m.ends_with("/QuoteAccepted"))
+        })
+        .map(|(_, op)| &op["channel"])
+        .expect("send operation declared with `publishes`");
+    assert_eq!(received, published);
+}
@@ -0,0 +1,158 @@
+//! Consumer tests without sleeps: `EventRecorder` captures what the app emits,
+//! injects events into `#[consumer]` methods and reports their results —
+//! including `retry` / `dlq` policies, run on a paused clock.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use r2e::prelude::*;
+use r2e::r2e_events::{EventMetadata, HandlerResult, LocalEventBus};
+use r2e::{App, BootableApp};
+use r2e_test::{EventRecorder, TestApp};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentReceived {
+    pub invoice_id: u64,
+    pub amount_cents: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvoicePaid {
+    pub invoice_id: u64,
+}
+
+#[controller]
+pub struct PaymentConsumers {
+    #[inject]
+    event_bus: LocalEventBus,
+    #[inject]
+    attempts: Arc<AtomicUsize>,
+}
+
+#[routes]
+impl PaymentConsumers {
+    #[consumer(bus = "event_bus", filter = "is_live", retry = 2, dlq = "payments.dlq")]
+    async fn on_payment(&self, event: Arc<PaymentReceived>) -> Result<(), String> {
+        self.attempts.fetch_add(1, Ordering::SeqCst);
+        if event.amount_cents <= 0 {
+            return Err(format!("invalid amount {}", event.amount_cents));
+        }
+        self.event_bus
+            .emit(InvoicePaid {
+                invoice_id: event.invoice_id,
+            })
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    fn is_live(&self, meta: &EventMetadata) -> bool {
+        !meta.headers.contains_key("x-replay")
+    }
+}
+
+struct PaymentsApp;
+
+impl App for PaymentsApp {
+    type Env = ();
+
+    async fn setup() {}
+
+    async fn build(b: AppBuilder, _env: ()) -> impl BootableApp {
+        b.provide(LocalEventBus::new())
+            .provide(Arc::new(AtomicUsize::new(0)))
+            .build_state()
+            .await
+            .register_controller::<PaymentConsumers>()
+    }
+}
+
+async fn app() -> (TestApp, Arc<AtomicUsize>) {
+    let app = TestApp::boot::<PaymentsApp>().await;
+    let attempts = app.bean::<Arc<AtomicUsize>>();
+    (app, attempts)
+}
+
+#[r2e::test]
+async fn consumer_emits_follow_up_event() {
+    let (app, _) = app().await;
+    let events = app.events();
+
+    app.bean::<LocalEventBus>()
+        .emit(PaymentReceived {
+            invoice_id: 7,
+            amount_cents: 1_500,
+        })
+        .await
+        .unwrap();
+
+    let paid = events
+        .await_event::<InvoicePaid>(|e| e.invoice_id == 7, Duration::from_secs(5))
+        .await;
+    assert_eq!(paid.invoice_id, 7);
+    events.assert_emitted_times::<InvoicePaid>(1);
+}
+
+#[r2e::test]
+async fn filtered_event_reaches_no_handler() {
+    let (app, attempts) = app().await;
+    let events = app.events();
+
+    let results = events
+        .deliver_with(
+            PaymentReceived {
+                invoice_id: 1,
+                amount_cents: 100,
+            },
+            EventMetadata::new().with_header("x-replay", "1"),
+        )
+        .await;
+
+    assert!(results.is_empty());
+    assert_eq!(attempts.load(Ordering::SeqCst), 0);
+    events.assert_not_emitted::<InvoicePaid>();
+}
+
+#[r2e::test(flavor = "current_thread", start_paused = true)]
+async fn failing_consumer_is_retried_then_dead_lettered() {
+    let (app, attempts) = app().await;
+    let events = app.events();
+
+    let results = events
+        .deliver(PaymentReceived {
+            invoice_id: 2,
+            amount_cents: 0,
+        })
+        .await;
+
+    assert_eq!(
+        results,
+        vec![HandlerResult::Nack("invalid amount 0".to_string())]
+    );
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    let dead_letters = events.dead_letters();
+    assert_eq!(dead_letters.len(), 1);
+    assert_eq!(dead_letters[0].topic, "payments.dlq");
+    events.assert_not_emitted::<InvoicePaid>();
+}
+
+#[r2e::test]
+async fn recorder_bus_captures_from_the_start() {
+    let events = EventRecorder::new();
+    let bus = events.bus();
+    let app = TestApp::boot_with::<PaymentsApp>(|b| b.override_bean(bus)).await;
+
+    app.bean::<LocalEventBus>()
+        .emit(PaymentReceived {
+            invoice_id: 3,
+            amount_cents: 42,
+        })
+        .await
+        .unwrap();
+
+    events.assert_emitted::<PaymentReceived>();
+    events
+        .await_event::<InvoicePaid>(|e| e.invoice_id == 3, Duration::from_secs(5))
+        .await;
+}
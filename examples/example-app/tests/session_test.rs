@@ -0,0 +1,85 @@
+//! Server-side sessions end to end: a user signed in through the `Sessions`
+//! plugin is a `SessionIdentity`, checked by `#[roles]` like a JWT user.
+
+use r2e::prelude::*;
+use r2e::r2e_security::RoleBasedIdentity;
+use r2e_test::{SameSite, TestApp};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+pub struct AdminUser {
+    id: String,
+    roles: Vec<String>,
+}
+
+impl Identity for AdminUser {
+    fn sub(&self) -> &str {
+        &self.id
+    }
+}
+
+impl RoleBasedIdentity for AdminUser {
+    fn roles(&self) -> &[String] {
+        &self.roles
+    }
+}
+
+impl SessionIdentity for AdminUser {}
+
+#[controller(path = "/admin")]
+pub struct AdminController;
+
+#[routes]
+impl AdminController {
+    #[post("/login/{id}")]
+    async fn login(
+        &self,
+        session: Session,
+        Path(id): Path<String>,
+    ) -> Result<StatusCode, HttpError> {
+        let roles = if id == "ada" {
+            vec!["admin".into()]
+        } else {
+            Vec::new()
+        };
+        session.login(&AdminUser { id, roles })?;
+        Ok(StatusCode::NO_CONTENT)
+    }
+
+    #[get("/users")]
+    #[roles("admin")]
+    async fn users(&self, #[inject(identity)] user: AdminUser) -> String {
+        format!("users, for {}", user.id)
+    }
+}
+
+async fn app() -> TestApp {
+    TestApp::from_builder(
+        AppBuilder::new()
+            .build_state()
+            .await
+            .with(Sessions::new("an application secret of 32+ bytes"))
+            .register_controller::<AdminController>(),
+    )
+}
+
+#[r2e::test]
+async fn roles_apply_to_session_users() {
+    let app = app().await;
+    app.get("/admin/users").send().await.assert_unauthorized();
+
+    let admin = app.session();
+    admin
+        .post("/admin/login/ada")
+        .send()
+        .await
+        .assert_cookie_http_only("session")
+        .assert_cookie_same_site("session", SameSite::Lax);
+    let resp = admin.get("/admin/users").send().await;
+    resp.assert_ok();
+    assert_eq!(resp.text(), "users, for ada");
+
+    let guest = app.session();
+    guest.post("/admin/login/bob").send().await;
+    guest.get("/admin/users").send().await.assert_forbidden();
+}
@@ -0,0 +1,210 @@
+//! `#[transactional]` on bean service methods and `#[consumer]` handlers.
+//!
+//! The transaction is bound to the task: a nested `#[transactional]` call
+//! joins it, `Err` / `Nack` roll it back, and `mandatory` refuses to run
+//! without one.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use r2e::prelude::*;
+use r2e::r2e_events::{EventBus, HandlerResult, LocalEventBus};
+use sqlx::{sqlite::SqlitePoolOptions, Row, Sqlite, SqlitePool};
+
+async fn pool() -> SqlitePool {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    sqlx::query("CREATE TABLE orders(id INTEGER PRIMARY KEY, item TEXT NOT NULL)")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("CREATE TABLE stock(item TEXT PRIMARY KEY, reserved INTEGER NOT NULL)")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
+async fn count(pool: &SqlitePool, table: &str) -> i64 {
+    sqlx::query(sqlx::AssertSqlSafe(format!(
+        "SELECT COUNT(*) AS count FROM {table}"
+    )))
+    .fetch_one(pool)
+    .await
+    .unwrap()
+    .get("count")
+}
+
+async fn execute(sql: &'static str, item: &str) -> Result<(), HttpError> {
+    let mut tx = current_tx::<Sqlite>().await?;
+    sqlx::query(sql)
+        .bind(item)
+        .execute(tx.connection())
+        .await
+        .map_err(|error| HttpError::internal(error.to_string()))?;
+    Ok(())
+}
+
+// ─── Services ───
+
+#[derive(Clone)]
+pub struct StockService {
+    pool: SqlitePool,
+}
+
+#[bean]
+impl StockService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    #[transactional(propagation = mandatory)]
+    pub async fn reserve(&self, item: &str) -> Result<(), HttpError> {
+        execute("INSERT INTO stock(item, reserved) VALUES (?, 1)", item).await?;
+        if item == "sold-out" {
+            return Err(HttpError::internal("sold out"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct OrderService {
+    pool: SqlitePool,
+    stock: StockService,
+}
+
+#[bean]
+impl OrderService {
+    pub fn new(pool: SqlitePool, stock: StockService) -> Self {
+        Self { pool, stock }
+    }
+
+    #[transactional]
+    pub async fn place(&self, item: &str) -> Result<(), HttpError> {
+        execute("INSERT INTO orders(item) VALUES (?)", item).await?;
+        self.stock.reserve(item).await
+    }
+}
+
+// ─── Consumer ───
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct OrderPlaced {
+    item: String,
+}
+
+#[derive(Clone)]
+pub struct OrderProjection {
+    bus: LocalEventBus,
+    pool: SqlitePool,
+}
+
+#[bean]
+impl OrderProjection {
+    pub fn new(bus: LocalEventBus, pool: SqlitePool) -> Self {
+        Self { bus, pool }
+    }
+
+    #[consumer(bus = "bus")]
+    #[transactional]
+    async fn on_order(&self, event: Arc<OrderPlaced>) -> HandlerResult {
+        if let Err(error) = execute("INSERT INTO orders(item) VALUES (?)", &event.item).await {
+            return HandlerResult::Nack(error.to_string());
+        }
+        if event.item == "poison" {
+            return HandlerResult::Nack("rejected".into());
+        }
+        HandlerResult::Ack
+    }
+}
+
+// ─── Tests ───
+
+#[r2e::test]
+async fn nested_service_call_joins_and_commits() {
+    let app = AppBuilder::new()
+        .provide(pool().await)
+        .register::<StockService>()
+        .register::<OrderService>()
+        .build_state()
+        .await;
+    let orders = app.state().bean::<OrderService>().unwrap();
+    let pool = app.state().bean::<SqlitePool>().unwrap();
+
+    orders.place("book").await.unwrap();
+
+    assert_eq!(count(&pool, "orders").await, 1);
+    assert_eq!(count(&pool, "stock").await, 1);
+}
+
+#[r2e::test]
+async fn failing_participant_rolls_back_whole_transaction() {
+    let app = AppBuilder::new()
+        .provide(pool().await)
+        .register::<StockService>()
+        .register::<OrderService>()
+        .build_state()
+        .await;
+    let orders = app.state().bean::<OrderService>().unwrap();
+    let pool = app.state().bean::<SqlitePool>().unwrap();
+
+    assert!(orders.place("sold-out").await.is_err());
+
+    assert_eq!(count(&pool, "orders").await, 0);
+    assert_eq!(count(&pool, "stock").await, 0);
+}
+
+#[r2e::test]
+async fn mandatory_without_transaction_is_rejected() {
+    let app = AppBuilder::new()
+        .provide(pool().await)
+        .register::<StockService>()
+        .build_state()
+        .await;
+    let stock = app.state().bean::<StockService>().unwrap();
+
+    assert!(stock.reserve("book").await.is_err());
+    assert_eq!(
+        count(&app.state().bean::<SqlitePool>().unwrap(), "stock").await,
+        0
+    );
+}
+
+#[r2e::test]
+async fn consumer_nack_rolls_back() {
+    let bus = LocalEventBus::new();
+    let pool = pool().await;
+    let _router = AppBuilder::new()
+        .provide(bus.clone())
+        .provide(pool.clone())
+        .register::<OrderProjection>()
+        .build_state()
+        .await
+        .build_with_consumers()
+        .await;
+
+    bus.emit(OrderPlaced {
+        item: "book".into(),
+    })
+    .await
+    .unwrap();
+    bus.emit(OrderPlaced {
+        item: "poison".into(),
+    })
+    .await
+    .unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let items: Vec<String> = sqlx::query("SELECT item FROM orders")
+        .fetch_all(&pool)
+        .await
+        .unwrap()
+        .iter()
+        .map(|row| row.get("item"))
+        .collect();
+    assert_eq!(items, vec!["book".to_string()]);
+}
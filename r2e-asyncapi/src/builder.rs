@@ -0,0 +1,477 @@
+use r2e_core::meta::{ConsumerInfo, EmitterInfo};
+use r2e_events::backend::{event_topic_name, request_topic, HEADER_REPLY_TO};
+use r2e_events::Event;
+use serde_json::{json, Map, Value};
+
+/// Broker protocol of an [`AsyncApiServer`], with the settings its channel
+/// bindings need.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BrokerProtocol {
+    /// Apache Kafka (`r2e-events-kafka`).
+    Kafka,
+    /// RabbitMQ (`r2e-events-rabbitmq`): every topic is a routing key on one
+    /// topic exchange.
+    Amqp { exchange: String },
+    /// Apache Pulsar (`r2e-events-pulsar`): topics live under `topic_prefix`
+    /// (`persistent://public/default/` by default).
+    Pulsar { topic_prefix: String },
+    /// Apache Iggy (`r2e-events-iggy`). AsyncAPI defines no Iggy bindings.
+    Iggy,
+}
+
+impl BrokerProtocol {
+    /// The AsyncAPI `protocol` value.
+    pub fn name(&self) -> &'static str {
+        match self {
+            BrokerProtocol::Kafka => "kafka",
+            BrokerProtocol::Amqp { .. } => "amqp",
+            BrokerProtocol::Pulsar { .. } => "pulsar",
+            BrokerProtocol::Iggy => "iggy",
+        }
+    }
+
+    /// Channel binding for a topic, keyed by protocol name.
+    fn channel_binding(&self, address: &str) -> Option<Value> {
+        match self {
+            BrokerProtocol::Kafka => Some(json!({
+                "topic": address,
+                "bindingVersion": "0.5.0",
+            })),
+            BrokerProtocol::Amqp { exchange } => Some(json!({
+                "is": "routingKey",
+                "exchange": { "name": exchange, "type": "topic", "durable": true },
+                "bindingVersion": "0.3.0",
+            })),
+            BrokerProtocol::Pulsar { topic_prefix } => {
+                let (persistence, namespace) = topic_prefix
+                    .trim_end_matches('/')
+                    .split_once("://")
+                    .unwrap_or(("persistent", "public/default"));
+                Some(json!({
+                    "namespace": namespace,
+                    "persistence": persistence,
+                    "bindingVersion": "0.1.0",
+                }))
+            }
+            BrokerProtocol::Iggy => None,
+        }
+    }
+}
+
+/// A broker listed under the document's `servers`.
+///
+/// Each server also contributes its protocol's bindings to every channel.
+#[derive(Debug, Clone)]
+pub struct AsyncApiServer {
+    pub name: String,
+    pub host: String,
+    pub protocol: BrokerProtocol,
+    pub description: Option<String>,
+}
+
+impl AsyncApiServer {
+    pub fn new(name: &str, host: &str, protocol: BrokerProtocol) -> Self {
+        Self {
+            name: name.to_string(),
+            host: host.to_string(),
+            protocol,
+            description: None,
+        }
+    }
+
+    /// A Kafka cluster (`host` is a bootstrap server, e.g. `kafka:9092`).
+    pub fn kafka(name: &str, host: &str) -> Self {
+        Self::new(name, host, BrokerProtocol::Kafka)
+    }
+
+    /// A RabbitMQ broker routing events through `exchange` (the backend
+    /// default is `r2e-events`).
+    pub fn rabbitmq(name: &str, host: &str, exchange: &str) -> Self {
+        Self::new(
+            name,
+            host,
+            BrokerProtocol::Amqp {
+                exchange: exchange.to_string(),
+            },
+        )
+    }
+
+    /// A Pulsar cluster whose topics live under `topic_prefix`.
+    pub fn pulsar(name: &str, host: &str, topic_prefix: &str) -> Self {
+        Self::new(
+            name,
+            host,
+            BrokerProtocol::Pulsar {
+                topic_prefix: topic_prefix.to_string(),
+            },
+        )
+    }
+
+    /// An Iggy server.
+    pub fn iggy(name: &str, host: &str) -> Self {
+        Self::new(name, host, BrokerProtocol::Iggy)
+    }
+
+    pub fn with_description(mut self, desc: &str) -> Self {
+        self.description = Some(desc.to_string());
+        self
+    }
+}
+
+/// An event type the application emits.
+///
+/// Controller methods declare theirs with `#[emits(...)]`, collected at
+/// registration; emits from elsewhere (beans, background services) are
+/// declared on [`AsyncApiConfig`].
+#[derive(Debug, Clone)]
+pub struct PublishedEvent {
+    pub topic: String,
+    pub event_type: String,
+    pub event_schema: Option<Value>,
+    pub summary: Option<String>,
+}
+
+impl From<&EmitterInfo> for PublishedEvent {
+    fn from(emitter: &EmitterInfo) -> Self {
+        Self {
+            topic: emitter.topic.clone(),
+            event_type: emitter.event_type.clone(),
+            event_schema: emitter.event_schema.clone(),
+            summary: emitter.summary.clone(),
+        }
+    }
+}
+
+impl PublishedEvent {
+    pub fn with_summary(mut self, summary: &str) -> Self {
+        self.summary = Some(summary.to_string());
+        self
+    }
+}
+
+/// Configuration for the generated AsyncAPI document.
+pub struct AsyncApiConfig {
+    pub title: String,
+    pub version: String,
+    pub description: Option<String>,
+    pub(crate) servers: Vec<AsyncApiServer>,
+    pub(crate) published: Vec<PublishedEvent>,
+}
+
+impl AsyncApiConfig {
+    pub fn new(title: &str, version: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            version: version.to_string(),
+            description: None,
+            servers: Vec::new(),
+            published: Vec::new(),
+        }
+    }
+
+    pub fn with_description(mut self, desc: &str) -> Self {
+        self.description = Some(desc.to_string());
+        self
+    }
+
+    /// Add a broker to `servers`; its protocol bindings are added to every
+    /// channel.
+    pub fn with_server(mut self, server: AsyncApiServer) -> Self {
+        self.servers.push(server);
+        self
+    }
+
+    /// Document that the application emits `E` on its topic, resolved like a
+    /// consumer's: an overridden `Event::topic()`, else the sanitized type
+    /// name the bus falls back to.
+    ///
+    /// For an event registered under another name (a backend
+    /// `.topic::<E>(..)`), use [`publishes_on`](Self::publishes_on).
+    /// Controller methods can declare their events with `#[emits(...)]`
+    /// instead.
+    pub fn publishes<E: Event + schemars::JsonSchema>(self) -> Self {
+        let topic = event_topic_name(std::any::type_name::<E>(), Some(E::topic()));
+        self.publishes_on::<E>(&topic)
+    }
+
+    /// Document that the application emits `E` on `topic` (an event type
+    /// registered under an explicit topic name).
+    pub fn publishes_on<E: schemars::JsonSchema>(self, topic: &str) -> Self {
+        let schema = schemars::SchemaGenerator::default().into_root_schema_for::<E>();
+        self.with_published(PublishedEvent {
+            topic: topic.to_string(),
+            event_type: E::schema_name().into_owned(),
+            event_schema: Some(Value::from(schema)),
+            summary: None,
+        })
+    }
+
+    /// Document an emitted event from a hand-built [`PublishedEvent`].
+    pub fn with_published(mut self, event: PublishedEvent) -> Self {
+        self.published.push(event);
+        self
+    }
+
+    /// Document the events controllers declared with `#[emits(...)]`.
+    pub fn with_emitters(mut self, emitters: &[EmitterInfo]) -> Self {
+        self.published
+            .extend(emitters.iter().map(PublishedEvent::from));
+        self
+    }
+}
+
+/// Recursively rewrite `$ref` paths from schemars format to AsyncAPI components format.
+fn sanitize_schema(value: &mut Value) {
+    match value {
+        Value::Object(obj) => {
+            if let Some(Value::String(ref_str)) = obj.get_mut("$ref") {
+                if ref_str.starts_with("#/$defs/") {
+                    *ref_str = ref_str.replace("#/$defs/", "#/components/schemas/");
+                }
+            }
+            for (_, v) in obj.iter_mut() {
+                sanitize_schema(v);
+            }
+        }
+        Value::Array(arr) => {
+            for v in arr.iter_mut() {
+                sanitize_schema(v);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Insert a payload schema into `components/schemas`, promoting `$defs`.
+///
+/// Types without a `JsonSchema` impl are documented as a generic object.
+fn insert_schema(schemas: &mut Map<String, Value>, name: &str, root_schema: &Option<Value>) {
+    if schemas.contains_key(name) {
+        return;
+    }
+    let Some(root) = root_schema else {
+        schemas.insert(name.to_string(), json!({ "type": "object" }));
+        return;
+    };
+    let mut schema = root.clone();
+    if let Some(obj) = schema.as_object_mut() {
+        obj.remove("$schema");
+        if let Some(Value::Object(defs)) = obj.remove("$defs") {
+            for (def_name, mut def_schema) in defs {
+                sanitize_schema(&mut def_schema);
+                schemas.entry(def_name).or_insert(def_schema);
+            }
+        }
+    }
+    sanitize_schema(&mut schema);
+    schemas.insert(name.to_string(), schema);
+}
+
+/// AsyncAPI component/channel keys must match `^[A-Za-z0-9_\-]+$` (channels)
+/// or `^[A-Za-z0-9.\-_]+$` (components); map everything else to `_`.
+fn key(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Accumulates channels, operations and components while walking metadata.
+struct Document<'a> {
+    servers: &'a [AsyncApiServer],
+    channels: Map<String, Value>,
+    operations: Map<String, Value>,
+    messages: Map<String, Value>,
+    schemas: Map<String, Value>,
+}
+
+impl Document<'_> {
+    /// Register `message_type` as a component message and return its key.
+    fn message(&mut self, message_type: &str, schema: &Option<Value>) -> String {
+        let msg_key = key(message_type);
+        insert_schema(&mut self.schemas, message_type, schema);
+        self.messages.entry(msg_key.clone()).or_insert_with(|| {
+            json!({
+                "name": message_type,
+                "contentType": "application/json",
+                "payload": { "$ref": format!("#/components/schemas/{message_type}") },
+            })
+        });
+        msg_key
+    }
+
+    /// Ensure a channel for `address` carrying `msg_key` and return its id.
+    ///
+    /// `address: None` declares a dynamic channel (a reply channel whose
+    /// address is read from the request at runtime).
+    fn channel(&mut self, id: String, address: Option<&str>, msg_key: &str) -> String {
+        let servers = self.servers;
+        let channel = self.channels.entry(id.clone()).or_insert_with(|| {
+            let mut channel = Map::new();
+            channel.insert("address".into(), json!(address));
+            channel.insert("messages".into(), json!({}));
+            if let Some(address) = address {
+                let bindings: Map<String, Value> = servers
+                    .iter()
+                    .filter_map(|s| {
+                        s.protocol
+                            .channel_binding(address)
+                            .map(|b| (s.protocol.name().to_string(), b))
+                    })
+                    .collect();
+                if !bindings.is_empty() {
+                    channel.insert("bindings".into(), Value::Object(bindings));
+                }
+            }
+            Value::Object(channel)
+        });
+        channel["messages"][msg_key] =
+            json!({ "$ref": format!("#/components/messages/{msg_key}") });
+        id
+    }
+
+    fn operation(
+        &mut self,
+        operation_id: &str,
+        action: &str,
+        channel_id: &str,
+        msg_key: &str,
+    ) -> &mut Map<String, Value> {
+        let op = self.operations.entry(key(operation_id)).or_insert_with(|| {
+            json!({
+                "action": action,
+                "channel": { "$ref": format!("#/channels/{channel_id}") },
+                "messages": [
+                    { "$ref": format!("#/channels/{channel_id}/messages/{msg_key}") }
+                ],
+            })
+        });
+        op.as_object_mut().expect("operation is an object")
+    }
+}
+
+fn describe(op: &mut Map<String, Value>, summary: &Option<String>, description: &Option<String>) {
+    if let Some(summary) = summary {
+        op.insert("summary".into(), json!(summary));
+    }
+    if let Some(description) = description {
+        op.insert("description".into(), json!(description));
+    }
+}
+
+/// Build an AsyncAPI 3.0.0 JSON document from config and consumer metadata.
+///
+/// - every subscriber becomes a `receive` operation on its topic's channel;
+/// - a responder receives on `<topic>.requests` and declares a `reply` whose
+///   address is the request's `r2e-reply-to` header;
+/// - a `dlq = "..."` consumer adds a `send` operation on the dead-letter
+///   channel;
+/// - every published event (`#[emits(...)]` metadata, added with
+///   [`AsyncApiConfig::with_emitters`], and [`AsyncApiConfig::publishes`]
+///   declarations) becomes a `send` operation.
+///
+/// Payload schemas come from schemars and live under `components/schemas`.
+pub fn build_asyncapi(config: &AsyncApiConfig, consumers: &[ConsumerInfo]) -> Value {
+    let mut doc = Document {
+        servers: &config.servers,
+        channels: Map::new(),
+        operations: Map::new(),
+        messages: Map::new(),
+        schemas: Map::new(),
+    };
+
+    for consumer in consumers {
+        let msg = doc.message(&consumer.event_type, &consumer.event_schema);
+
+        match &consumer.reply_type {
+            None => {
+                let channel = doc.channel(key(&consumer.topic), Some(&consumer.topic), &msg);
+                let op = doc.operation(&consumer.operation_id, "receive", &channel, &msg);
+                describe(op, &consumer.summary, &consumer.description);
+            }
+            Some(reply_type) => {
+                let address = request_topic(&consumer.topic);
+                let channel = doc.channel(key(&address), Some(&address), &msg);
+                let reply_msg = doc.message(reply_type, &consumer.reply_schema);
+                let reply_channel = doc.channel(format!("{channel}_reply"), None, &reply_msg);
+                let op = doc.operation(&consumer.operation_id, "receive", &channel, &msg);
+                describe(op, &consumer.summary, &consumer.description);
+                op.insert(
+                    "reply".into(),
+                    json!({
+                        "address": { "location": format!("$message.header#/{HEADER_REPLY_TO}") },
+                        "channel": { "$ref": format!("#/channels/{reply_channel}") },
+                        "messages": [
+                            { "$ref": format!("#/channels/{reply_channel}/messages/{reply_msg}") }
+                        ],
+                    }),
+                );
+            }
+        }
+
+        if let Some(dlq) = &consumer.dead_letter_topic {
+            let channel = doc.channel(key(dlq), Some(dlq), &msg);
+            let op_id = format!("{}_dead_letter", consumer.operation_id);
+            let op = doc.operation(&op_id, "send", &channel, &msg);
+            let retries = consumer
+                .max_retries
+                .map(|n| format!(" after {n} failed attempts"))
+                .unwrap_or_default();
+            op.insert(
+                "summary".into(),
+                json!(format!(
+                    "Dead-letters `{}` events `{}` could not process{retries}",
+                    consumer.event_type, consumer.operation_id
+                )),
+            );
+        }
+    }
+
+    for published in &config.published {
+        let msg = doc.message(&published.event_type, &published.event_schema);
+        let channel = doc.channel(key(&published.topic), Some(&published.topic), &msg);
+        let op_id = format!("publish_{channel}");
+        let op = doc.operation(&op_id, "send", &channel, &msg);
+        describe(op, &published.summary, &None);
+    }
+
+    let mut info: Map<String, Value> = Map::new();
+    info.insert("title".into(), json!(config.title));
+    info.insert("version".into(), json!(config.version));
+    if let Some(ref desc) = config.description {
+        info.insert("description".into(), json!(desc));
+    }
+
+    let mut document: Map<String, Value> = Map::new();
+    document.insert("asyncapi".into(), json!("3.0.0"));
+    document.insert("info".into(), Value::Object(info));
+    document.insert("defaultContentType".into(), json!("application/json"));
+    if !config.servers.is_empty() {
+        let servers: Map<String, Value> = config
+            .servers
+            .iter()
+            .map(|s| {
+                let mut server = Map::new();
+                server.insert("host".into(), json!(s.host));
+                server.insert("protocol".into(), json!(s.protocol.name()));
+                if let Some(ref desc) = s.description {
+                    server.insert("description".into(), json!(desc));
+                }
+                (key(&s.name), Value::Object(server))
+            })
+            .collect();
+        document.insert("servers".into(), Value::Object(servers));
+    }
+    document.insert("channels".into(), Value::Object(doc.channels));
+    document.insert("operations".into(), Value::Object(doc.operations));
+    document.insert(
+        "components".into(),
+        json!({ "messages": doc.messages, "schemas": doc.schemas }),
+    );
+    Value::Object(document)
+}
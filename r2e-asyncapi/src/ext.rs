@@ -0,0 +1,44 @@
+use crate::{asyncapi_routes, AsyncApiConfig};
+use r2e_core::meta::{ConsumerInfo, EmitterInfo};
+use r2e_core::Plugin;
+
+/// Plugin that serves an AsyncAPI document for the registered controllers'
+/// `#[consumer]` and `#[emits(...)]` methods.
+///
+/// # Example
+///
+/// ```ignore
+/// use r2e_asyncapi::{AsyncApiConfig, AsyncApiPlugin, AsyncApiServer};
+///
+/// AppBuilder::new()
+///     .build_state()
+///     .await
+///     .with(AsyncApiPlugin::new(
+///         AsyncApiConfig::new("Orders events", "1.0.0")
+///             .with_server(AsyncApiServer::kafka("production", "kafka:9092"))
+///             .publishes::<OrderPlaced>(),
+///     ))
+/// ```
+pub struct AsyncApiPlugin {
+    config: AsyncApiConfig,
+}
+
+impl AsyncApiPlugin {
+    /// Create a new AsyncAPI plugin with the given configuration.
+    pub fn new(config: AsyncApiConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Plugin for AsyncApiPlugin {
+    fn install<T: Clone + Send + Sync + 'static>(
+        self,
+        app: r2e_core::AppBuilder<T>,
+    ) -> r2e_core::AppBuilder<T> {
+        let config = self.config;
+        app.with_meta_registry_consumer(move |registry| {
+            let config = config.with_emitters(registry.get_or_empty::<EmitterInfo>());
+            asyncapi_routes::<T>(config, registry.get_or_empty::<ConsumerInfo>())
+        })
+    }
+}
@@ -0,0 +1,36 @@
+use r2e_core::http::response::IntoResponse;
+use r2e_core::http::routing::get;
+use r2e_core::http::Router;
+use r2e_core::meta::ConsumerInfo;
+
+use crate::builder::{build_asyncapi, AsyncApiConfig};
+
+/// Build an `axum::Router` that serves `/asyncapi.json` and `/asyncapi.yaml`.
+///
+/// The document is rendered once, when the router is built.
+pub fn asyncapi_routes<T: Clone + Send + Sync + 'static>(
+    config: AsyncApiConfig,
+    consumers: &[ConsumerInfo],
+) -> Router<T> {
+    let document = build_asyncapi(&config, consumers);
+    let json = serde_json::to_string_pretty(&document)
+        .expect("AsyncAPI document is a serde_json::Value and serializes infallibly");
+    let yaml = serde_yaml::to_string(&document)
+        .expect("AsyncAPI document is a serde_json::Value and serializes infallibly");
+
+    Router::<T>::new()
+        .route(
+            "/asyncapi.json",
+            get(move || {
+                let json = json.clone();
+                async move { ([("content-type", "application/json")], json).into_response() }
+            }),
+        )
+        .route(
+            "/asyncapi.yaml",
+            get(move || {
+                let yaml = yaml.clone();
+                async move { ([("content-type", "application/yaml")], yaml).into_response() }
+            }),
+        )
+}
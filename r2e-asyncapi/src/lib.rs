@@ -0,0 +1,47 @@
+//! AsyncAPI 3.0 document generation for R2E.
+//!
+//! The event-side counterpart of `r2e-openapi`: the `#[routes]` macro records a
+//! [`ConsumerInfo`](r2e_core::meta::ConsumerInfo) for every `#[consumer]`
+//! method and an [`EmitterInfo`](r2e_core::meta::EmitterInfo) for every event
+//! type an `#[emits(...)]` method declares, and this crate renders them as an
+//! AsyncAPI 3.0 document served at `/asyncapi.json` and `/asyncapi.yaml`.
+//!
+//! - **Channels** are the topics the bus actually uses: the consumer's
+//!   `topic = "..."`, else an overridden `Event::topic()`, else the sanitized
+//!   type name. Responders listen on `<topic>.requests` and reply to the
+//!   address carried by the request's `r2e-reply-to` header; `dlq = "..."`
+//!   consumers add the dead-letter channel.
+//! - **Payloads** are schemars schemas. Derive `JsonSchema` on event types
+//!   (`schemars` must be a direct dependency, as for `r2e-openapi`); types
+//!   without it are documented as a generic object.
+//! - **Bindings** come from the configured [`AsyncApiServer`]s (Kafka, AMQP,
+//!   Pulsar), so the document matches the backend the app deploys with.
+//!
+//! # Usage
+//!
+//! ```ignore
+//! use r2e::r2e_asyncapi::{AsyncApiConfig, AsyncApiPlugin, AsyncApiServer};
+//!
+//! AppBuilder::new()
+//!     .build_state().await
+//!     .with(AsyncApiPlugin::new(
+//!         AsyncApiConfig::new("Order events", "1.0.0")
+//!             .with_server(AsyncApiServer::kafka("production", "kafka:9092"))
+//!             // Events emitted outside controllers' `#[emits]` methods:
+//!             .publishes::<AuditEntry>(),
+//!     ))
+//!     .register_controller::<OrderConsumers>()
+//!     .serve("0.0.0.0:3000").await.unwrap();
+//! ```
+//!
+//! Only controller consumers are collected; `#[consumer]` methods on beans are
+//! not registered through `MetaRegistry`.
+
+mod builder;
+mod ext;
+mod handlers;
+
+pub use builder::{build_asyncapi, AsyncApiConfig, AsyncApiServer, BrokerProtocol, PublishedEvent};
+pub use ext::AsyncApiPlugin;
+pub use handlers::asyncapi_routes;
+pub use schemars;
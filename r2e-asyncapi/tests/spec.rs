@@ -0,0 +1,316 @@
+use http::Request;
+use http_body_util::BodyExt;
+use r2e_asyncapi::{asyncapi_routes, build_asyncapi, AsyncApiConfig, AsyncApiServer};
+use r2e_core::http::body::Body;
+use r2e_core::meta::{ConsumerInfo, EmitterInfo};
+use r2e_events::Event;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+// ── Helpers ─────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+struct OrderPlaced {
+    id: u64,
+    lines: Vec<OrderLine>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+struct OrderLine {
+    sku: String,
+}
+
+impl Event for OrderPlaced {
+    fn topic() -> &'static str {
+        "orders.placed"
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+struct Shipped {
+    id: u64,
+}
+
+impl Event for Shipped {}
+
+fn schema_of<T: JsonSchema>() -> Option<Value> {
+    Some(serde_json::to_value(schemars::schema_for!(T)).unwrap())
+}
+
+fn subscriber(op: &str, topic: &str) -> ConsumerInfo {
+    ConsumerInfo {
+        owner: "OrderConsumers".to_string(),
+        operation_id: op.to_string(),
+        summary: None,
+        description: None,
+        topic: topic.to_string(),
+        event_type: "OrderPlaced".to_string(),
+        event_schema: schema_of::<OrderPlaced>(),
+        reply_type: None,
+        reply_schema: None,
+        max_retries: None,
+        dead_letter_topic: None,
+    }
+}
+
+fn config() -> AsyncApiConfig {
+    AsyncApiConfig::new("Order events", "1.0.0")
+}
+
+// ── Document ────────────────────────────────────────────────────────────────
+
+#[test]
+fn document_header() {
+    let doc = build_asyncapi(&config().with_description("Events"), &[]);
+    assert_eq!(doc["asyncapi"], "3.0.0");
+    assert_eq!(doc["info"]["title"], "Order events");
+    assert_eq!(doc["info"]["version"], "1.0.0");
+    assert_eq!(doc["info"]["description"], "Events");
+    assert_eq!(doc["defaultContentType"], "application/json");
+    assert!(doc.get("servers").is_none());
+}
+
+#[test]
+fn subscriber_becomes_receive_operation() {
+    let mut consumer = subscriber("OrderConsumers_on_placed", "orders.placed");
+    consumer.summary = Some("Reserve stock".to_string());
+    let doc = build_asyncapi(&config(), &[consumer]);
+
+    assert_eq!(doc["channels"]["orders_placed"]["address"], "orders.placed");
+    assert_eq!(
+        doc["channels"]["orders_placed"]["messages"]["OrderPlaced"]["$ref"],
+        "#/components/messages/OrderPlaced"
+    );
+
+    let op = &doc["operations"]["OrderConsumers_on_placed"];
+    assert_eq!(op["action"], "receive");
+    assert_eq!(op["channel"]["$ref"], "#/channels/orders_placed");
+    assert_eq!(
+        op["messages"][0]["$ref"],
+        "#/channels/orders_placed/messages/OrderPlaced"
+    );
+    assert_eq!(op["summary"], "Reserve stock");
+}
+
+#[test]
+fn payload_schemas_promote_defs() {
+    let doc = build_asyncapi(&config(), &[subscriber("op", "orders.placed")]);
+
+    let message = &doc["components"]["messages"]["OrderPlaced"];
+    assert_eq!(
+        message["payload"]["$ref"],
+        "#/components/schemas/OrderPlaced"
+    );
+
+    let schemas = &doc["components"]["schemas"];
+    assert!(schemas["OrderPlaced"].get("$defs").is_none());
+    assert!(schemas["OrderPlaced"].get("$schema").is_none());
+    assert_eq!(
+        schemas["OrderPlaced"]["properties"]["lines"]["items"]["$ref"],
+        "#/components/schemas/OrderLine"
+    );
+    assert_eq!(schemas["OrderLine"]["properties"]["sku"]["type"], "string");
+}
+
+#[test]
+fn schemaless_event_is_generic_object() {
+    let mut consumer = subscriber("op", "legacy");
+    consumer.event_type = "Legacy".to_string();
+    consumer.event_schema = None;
+    let doc = build_asyncapi(&config(), &[consumer]);
+    assert_eq!(
+        doc["components"]["schemas"]["Legacy"],
+        json!({ "type": "object" })
+    );
+}
+
+#[test]
+fn consumers_on_same_topic_share_a_channel() {
+    let doc = build_asyncapi(
+        &config(),
+        &[
+            subscriber("Billing_on_placed", "orders.placed"),
+            subscriber("Stock_on_placed", "orders.placed"),
+        ],
+    );
+    assert_eq!(doc["channels"].as_object().unwrap().len(), 1);
+    assert_eq!(doc["operations"].as_object().unwrap().len(), 2);
+}
+
+#[test]
+fn responder_declares_request_channel_and_reply() {
+    let mut consumer = subscriber("Quotes_quote", "orders.placed");
+    consumer.reply_type = Some("Shipped".to_string());
+    consumer.reply_schema = schema_of::<Shipped>();
+    let doc = build_asyncapi(&config(), &[consumer]);
+
+    assert_eq!(
+        doc["channels"]["orders_placed_requests"]["address"],
+        "orders.placed.requests"
+    );
+    let reply_channel = &doc["channels"]["orders_placed_requests_reply"];
+    assert!(reply_channel["address"].is_null());
+    assert!(reply_channel["messages"].get("Shipped").is_some());
+
+    let reply = &doc["operations"]["Quotes_quote"]["reply"];
+    assert_eq!(
+        reply["address"]["location"],
+        "$message.header#/r2e-reply-to"
+    );
+    assert_eq!(
+        reply["channel"]["$ref"],
+        "#/channels/orders_placed_requests_reply"
+    );
+    assert_eq!(
+        reply["messages"][0]["$ref"],
+        "#/channels/orders_placed_requests_reply/messages/Shipped"
+    );
+    assert!(doc["components"]["schemas"].get("Shipped").is_some());
+}
+
+#[test]
+fn dead_letter_topic_adds_send_operation() {
+    let mut consumer = subscriber("Billing_on_placed", "orders.placed");
+    consumer.max_retries = Some(3);
+    consumer.dead_letter_topic = Some("orders.placed.dlq".to_string());
+    let doc = build_asyncapi(&config(), &[consumer]);
+
+    let op = &doc["operations"]["Billing_on_placed_dead_letter"];
+    assert_eq!(op["action"], "send");
+    assert_eq!(op["channel"]["$ref"], "#/channels/orders_placed_dlq");
+    assert!(op["summary"]
+        .as_str()
+        .unwrap()
+        .contains("after 3 failed attempts"));
+    assert_eq!(
+        doc["channels"]["orders_placed_dlq"]["address"],
+        "orders.placed.dlq"
+    );
+}
+
+#[test]
+fn published_events_become_send_operations() {
+    let doc = build_asyncapi(
+        &config().publishes::<OrderPlaced>().publishes::<Shipped>(),
+        &[],
+    );
+
+    // An overridden `Event::topic()` wins, as for consumers.
+    assert_eq!(doc["operations"]["publish_orders_placed"]["action"], "send");
+    assert_eq!(doc["channels"]["orders_placed"]["address"], "orders.placed");
+
+    // Otherwise the sanitized type name, as the bus does by default.
+    let shipped_topic = r2e_events::backend::sanitize_topic_name(std::any::type_name::<Shipped>());
+    let shipped = doc["channels"]
+        .as_object()
+        .unwrap()
+        .values()
+        .find(|c| c["address"] == json!(shipped_topic));
+    assert!(shipped.is_some(), "no channel for {shipped_topic}");
+}
+
+#[test]
+fn emitters_become_send_operations() {
+    let emitter = EmitterInfo {
+        owner: "OrderController".to_string(),
+        operation_id: "OrderController_place".to_string(),
+        summary: Some("Place an order.".to_string()),
+        topic: "orders.placed".to_string(),
+        event_type: "OrderPlaced".to_string(),
+        event_schema: schema_of::<OrderPlaced>(),
+    };
+    let doc = build_asyncapi(
+        &config().with_emitters(&[emitter]),
+        &[subscriber("OrderConsumers_on_order", "orders.placed")],
+    );
+
+    let op = &doc["operations"]["publish_orders_placed"];
+    assert_eq!(op["action"], "send");
+    assert_eq!(op["summary"], "Place an order.");
+    assert_eq!(
+        op["channel"],
+        doc["operations"]["OrderConsumers_on_order"]["channel"]
+    );
+}
+
+#[test]
+fn publishes_on_uses_explicit_topic() {
+    let doc = build_asyncapi(&config().publishes_on::<Shipped>("shipping.events"), &[]);
+    assert_eq!(
+        doc["channels"]["shipping_events"]["address"],
+        "shipping.events"
+    );
+    assert!(doc["components"]["messages"].get("Shipped").is_some());
+}
+
+#[test]
+fn servers_and_channel_bindings() {
+    let config = config()
+        .with_server(AsyncApiServer::kafka("production", "kafka:9092").with_description("Main"))
+        .with_server(AsyncApiServer::rabbitmq(
+            "rabbit",
+            "rabbit:5672",
+            "r2e-events",
+        ))
+        .with_server(AsyncApiServer::pulsar(
+            "pulsar",
+            "pulsar:6650",
+            "persistent://tenant/ns/",
+        ))
+        .with_server(AsyncApiServer::iggy("iggy", "iggy:8090"));
+    let doc = build_asyncapi(&config, &[subscriber("op", "orders.placed")]);
+
+    assert_eq!(doc["servers"]["production"]["protocol"], "kafka");
+    assert_eq!(doc["servers"]["production"]["host"], "kafka:9092");
+    assert_eq!(doc["servers"]["production"]["description"], "Main");
+    assert_eq!(doc["servers"]["iggy"]["protocol"], "iggy");
+
+    let bindings = &doc["channels"]["orders_placed"]["bindings"];
+    assert_eq!(bindings["kafka"]["topic"], "orders.placed");
+    assert_eq!(bindings["amqp"]["is"], "routingKey");
+    assert_eq!(bindings["amqp"]["exchange"]["name"], "r2e-events");
+    assert_eq!(bindings["pulsar"]["namespace"], "tenant/ns");
+    assert_eq!(bindings["pulsar"]["persistence"], "persistent");
+    assert!(bindings.get("iggy").is_none());
+}
+
+// ── Routes ──────────────────────────────────────────────────────────────────
+
+async fn get(path: &str) -> (http::StatusCode, String, String) {
+    let router: r2e_core::http::Router =
+        asyncapi_routes::<()>(config(), &[subscriber("op", "orders.placed")]);
+    let req = Request::builder().uri(path).body(Body::empty()).unwrap();
+    let response = router.oneshot(req).await.unwrap();
+    let status = response.status();
+    let content_type = response.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .to_string();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (
+        status,
+        content_type,
+        String::from_utf8(body.to_vec()).unwrap(),
+    )
+}
+
+#[r2e_core::test]
+async fn serves_json_document() {
+    let (status, content_type, body) = get("/asyncapi.json").await;
+    assert_eq!(status, 200);
+    assert_eq!(content_type, "application/json");
+    let doc: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(doc["asyncapi"], "3.0.0");
+    assert!(doc["operations"].get("op").is_some());
+}
+
+#[r2e_core::test]
+async fn serves_yaml_document() {
+    let (status, content_type, body) = get("/asyncapi.yaml").await;
+    assert_eq!(status, 200);
+    assert_eq!(content_type, "application/yaml");
+    assert!(body.contains("asyncapi: 3.0.0"));
+    assert!(body.contains("address: orders.placed"));
+}
@@ -0,0 +1,1536 @@
+use colored::Colorize;
+use serde_json::Value;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Environment variable read by `OpenApiPlugin`: write the spec to this path
+/// and exit instead of serving.
+const EXPORT_ENV: &str = "R2E_OPENAPI_EXPORT";
+
+/// Language of a generated client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    Rust,
+    TypeScript,
+}
+
+impl Lang {
+    /// Output file used when `--out` is not given.
+    pub fn default_file(self) -> &'static str {
+        match self {
+            Lang::Rust => "client.rs",
+            Lang::TypeScript => "client.ts",
+        }
+    }
+}
+
+impl std::str::FromStr for Lang {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "rust" | "rs" => Ok(Lang::Rust),
+            "typescript" | "ts" => Ok(Lang::TypeScript),
+            other => Err(format!(
+                "unknown client language '{other}' (expected rust or typescript)"
+            )),
+        }
+    }
+}
+
+/// Generate a typed client — `r2e generate client --lang rust|typescript`.
+///
+/// The spec is read from `spec` when given (a file written by the app's
+/// `/openapi.json`). Otherwise the app is run with `R2E_OPENAPI_EXPORT` set:
+/// `OpenApiPlugin` writes the spec once the route registry is built and
+/// exits before serving, so no listener is bound.
+///
+/// The output is deterministic — types and operations are emitted in a
+/// stable order with no timestamps — so regenerating only diffs on real API
+/// changes.
+pub fn run(
+    lang: Lang,
+    spec: Option<&Path>,
+    out: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let spec: Value = match spec {
+        Some(path) => serde_json::from_str(&fs::read_to_string(path)?)?,
+        None => export_spec()?,
+    };
+
+    let code = match lang {
+        Lang::Rust => rust_client(&spec),
+        Lang::TypeScript => typescript_client(&spec),
+    };
+    let out = out
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from(lang.default_file()));
+    if let Some(parent) = out.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&out, code)?;
+
+    println!(
+        "{} Generated client: {} ({} operations)",
+        "✓".green(),
+        out.display().to_string().cyan(),
+        operations(&spec).len()
+    );
+    Ok(())
+}
+
+/// Run the app in export mode and read back the spec it writes.
+fn export_spec() -> Result<Value, Box<dyn std::error::Error>> {
+    if !Path::new("Cargo.toml").exists() {
+        return Err(
+            "Cargo.toml not found — run from the project root or pass --spec <openapi.json>".into(),
+        );
+    }
+    let path = std::env::current_dir()?.join("target/r2e/openapi.json");
+    fs::create_dir_all(path.parent().unwrap())?;
+    let _ = fs::remove_file(&path);
+
+    println!(
+        "{} Exporting the OpenAPI spec through the application",
+        "->".blue()
+    );
+    let status = Command::new("cargo")
+        .args(["run", "--quiet"])
+        .env(EXPORT_ENV, &path)
+        .status()?;
+    if !status.success() {
+        return Err(format!("the application failed to export its spec ({status})").into());
+    }
+    let json = fs::read_to_string(&path).map_err(|_| {
+        "the application exited without exporting a spec — is OpenApiPlugin installed?"
+    })?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+// ── Spec model ──────────────────────────────────────────────────────────
+
+/// One operation of the spec, as the generators see it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Operation {
+    pub id: String,
+    pub method: String,
+    pub path: String,
+    pub summary: Option<String>,
+    pub deprecated: bool,
+    pub path_params: Vec<Param>,
+    pub query_params: Vec<Param>,
+    /// `in: header` parameters (`Idempotency-Key`, `If-Match`, ...).
+    pub header_params: Vec<Param>,
+    /// `in: cookie` parameters, sent together in one `Cookie` header.
+    pub cookie_params: Vec<Param>,
+    /// JSON request body schema and whether it is required.
+    pub body: Option<(Value, bool)>,
+    /// A non-JSON request body the generators cannot type.
+    pub unsupported_body: Option<String>,
+    pub response: ResponseKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    pub name: String,
+    pub schema: Value,
+    pub required: bool,
+    pub description: Option<String>,
+    /// `explode: false` arrays are sent comma-separated.
+    pub explode: bool,
+}
+
+/// Body of the operation's success response.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResponseKind {
+    Empty,
+    Json(Value),
+    Text,
+    EventStream,
+}
+
+const METHODS: [&str; 7] = ["get", "put", "post", "delete", "patch", "head", "options"];
+
+/// Operations of `spec`, ordered by path then method.
+pub fn operations(spec: &Value) -> Vec<Operation> {
+    let Some(paths) = spec.get("paths").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    let mut ops = Vec::new();
+    for (path, item) in paths {
+        for method in METHODS {
+            if let Some(op) = item.get(method) {
+                ops.push(operation(path, method, op));
+            }
+        }
+    }
+    ops
+}
+
+fn operation(path: &str, method: &str, op: &Value) -> Operation {
+    let mut path_params = Vec::new();
+    let mut query_params = Vec::new();
+    let mut header_params = Vec::new();
+    let mut cookie_params = Vec::new();
+    for param in op
+        .get("parameters")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let parsed = Param {
+            name: str_field(param, "name").unwrap_or_default(),
+            schema: param.get("schema").cloned().unwrap_or(Value::Null),
+            required: param.get("required").and_then(Value::as_bool) == Some(true),
+            description: str_field(param, "description"),
+            explode: param.get("explode").and_then(Value::as_bool) != Some(false),
+        };
+        match param.get("in").and_then(Value::as_str) {
+            Some("path") => path_params.push(parsed),
+            Some("query") => query_params.push(parsed),
+            Some("header") => header_params.push(parsed),
+            Some("cookie") => cookie_params.push(parsed),
+            _ => {}
+        }
+    }
+    // Path params follow their order in the template.
+    path_params.sort_by_key(|p| path.find(&format!("{{{}}}", p.name)));
+
+    let mut body = None;
+    let mut unsupported_body = None;
+    if let Some(request_body) = op.get("requestBody") {
+        let required = request_body.get("required").and_then(Value::as_bool) == Some(true);
+        let content = request_body.get("content").and_then(Value::as_object);
+        match content.and_then(|c| c.get("application/json")) {
+            Some(json) => {
+                body = Some((json.get("schema").cloned().unwrap_or(Value::Null), required))
+            }
+            None => {
+                unsupported_body = content.and_then(|c| c.keys().next().cloned());
+            }
+        }
+    }
+
+    let success = op
+        .get("responses")
+        .and_then(Value::as_object)
+        .and_then(|responses| {
+            responses
+                .iter()
+                .filter(|(status, _)| status.starts_with('2'))
+                .min_by(|a, b| a.0.cmp(b.0))
+                .map(|(_, response)| response)
+        });
+    let response = match success
+        .and_then(|r| r.get("content"))
+        .and_then(Value::as_object)
+    {
+        None => ResponseKind::Empty,
+        Some(content) => {
+            if let Some(json) = content.get("application/json") {
+                ResponseKind::Json(json.get("schema").cloned().unwrap_or(Value::Null))
+            } else if content.contains_key("text/event-stream") {
+                ResponseKind::EventStream
+            } else if content.is_empty() {
+                ResponseKind::Empty
+            } else {
+                ResponseKind::Text
+            }
+        }
+    };
+
+    Operation {
+        id: str_field(op, "operationId")
+            .unwrap_or_else(|| format!("{method}_{}", path.replace(['/', '{', '}'], "_"))),
+        method: method.to_uppercase(),
+        path: path.to_string(),
+        summary: str_field(op, "summary"),
+        deprecated: op.get("deprecated").and_then(Value::as_bool) == Some(true),
+        path_params,
+        query_params,
+        header_params,
+        cookie_params,
+        body,
+        unsupported_body,
+        response,
+    }
+}
+
+impl Operation {
+    /// Whether the operation takes header or cookie parameters.
+    fn has_headers(&self) -> bool {
+        !self.header_params.is_empty() || !self.cookie_params.is_empty()
+    }
+
+    /// Header then cookie parameters.
+    fn header_and_cookie_params(&self) -> impl Iterator<Item = &Param> {
+        self.header_params.iter().chain(&self.cookie_params)
+    }
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(str::to_owned)
+}
+
+/// Named schemas of `components/schemas`, in name order.
+fn schemas(spec: &Value) -> Vec<(&String, &Value)> {
+    spec.pointer("/components/schemas")
+        .and_then(Value::as_object)
+        .map(|schemas| schemas.iter().collect())
+        .unwrap_or_default()
+}
+
+fn header_line(spec: &Value) -> String {
+    let title = spec
+        .pointer("/info/title")
+        .and_then(Value::as_str)
+        .unwrap_or("API");
+    let version = spec
+        .pointer("/info/version")
+        .and_then(Value::as_str)
+        .unwrap_or("");
+    format!("Generated by `r2e generate client` from {title} {version}. Do not edit.")
+        .replace("  ", " ")
+}
+
+/// Split an identifier (`UserController_list`, `created-at`, `HTTPServer`)
+/// into lowercase words.
+fn words(name: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let chars: Vec<char> = name.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        let boundary = c.is_uppercase()
+            && !current.is_empty()
+            && (chars[i - 1].is_lowercase()
+                || chars[i - 1].is_numeric()
+                || chars.get(i + 1).is_some_and(|n| n.is_lowercase()));
+        if boundary {
+            words.push(std::mem::take(&mut current));
+        }
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn pascal_case(name: &str) -> String {
+    let name: String = words(name).iter().map(|w| capitalize(w)).collect();
+    match name.chars().next() {
+        Some(c) if c.is_alphabetic() => name,
+        _ => format!("T{name}"),
+    }
+}
+
+/// Names the generated code defines or relies on; schemas with these names
+/// get a `Schema` suffix.
+const RESERVED_TYPES: [&str; 14] = [
+    "ApiError",
+    "Array",
+    "Box",
+    "Client",
+    "ClientOptions",
+    "Error",
+    "Option",
+    "Promise",
+    "Record",
+    "Response",
+    "Result",
+    "SseEvent",
+    "String",
+    "Vec",
+];
+
+/// Generated name of the `components/schemas` entry `name`.
+fn type_name(name: &str) -> String {
+    let ty = pascal_case(name);
+    if RESERVED_TYPES.contains(&ty.as_str()) {
+        format!("{ty}Schema")
+    } else {
+        ty
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let name = words(name).join("_");
+    match name.chars().next() {
+        Some(c) if c.is_alphabetic() || c == '_' => name,
+        _ => format!("_{name}"),
+    }
+}
+
+fn camel_case(name: &str) -> String {
+    let words = words(name);
+    let mut out = words.first().cloned().unwrap_or_default();
+    for word in words.iter().skip(1) {
+        out.push_str(&capitalize(word));
+    }
+    match out.chars().next() {
+        Some(c) if c.is_alphabetic() => out,
+        _ => format!("_{out}"),
+    }
+}
+
+/// Name of a `$ref` target, if `schema` is a reference.
+fn ref_name(schema: &Value) -> Option<&str> {
+    schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|r| r.rsplit('/').next())
+}
+
+/// `(inner, nullable)`: unwraps `["T", "null"]` types and
+/// `anyOf: [T, {"type": "null"}]` (schemars' `Option<T>`).
+fn strip_null(schema: &Value) -> (Value, bool) {
+    if let Some(Value::Array(types)) = schema.get("type") {
+        let non_null: Vec<&Value> = types.iter().filter(|t| *t != "null").collect();
+        if non_null.len() < types.len() {
+            let mut inner = schema.clone();
+            inner["type"] = match non_null.as_slice() {
+                [single] => (*single).clone(),
+                _ => Value::Array(non_null.into_iter().cloned().collect()),
+            };
+            return (inner, true);
+        }
+    }
+    for key in ["anyOf", "oneOf"] {
+        if let Some(Value::Array(variants)) = schema.get(key) {
+            let is_null = |v: &&Value| v.get("type").and_then(Value::as_str) == Some("null");
+            if variants.len() == 2 && variants.iter().any(|v| is_null(&v)) {
+                let inner = variants.iter().find(|v| !is_null(v)).unwrap();
+                return (inner.clone(), true);
+            }
+        }
+    }
+    (schema.clone(), false)
+}
+
+/// `allOf: [{"$ref": ...}]` wrappers are the referenced type.
+fn single_all_of(schema: &Value) -> Option<&Value> {
+    match schema.get("allOf") {
+        Some(Value::Array(parts)) if parts.len() == 1 => parts.first(),
+        _ => None,
+    }
+}
+
+fn string_enum(schema: &Value) -> Option<Vec<&str>> {
+    let values = schema.get("enum")?.as_array()?;
+    values.iter().map(Value::as_str).collect()
+}
+
+fn doc_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines().map(str::trim_end)
+}
+
+// ── Rust ────────────────────────────────────────────────────────────────
+
+const RUST_KEYWORDS: [&str; 38] = [
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+fn rust_ident(name: &str) -> String {
+    let ident = snake_case(name);
+    if RUST_KEYWORDS.contains(&ident.as_str()) {
+        format!("r#{ident}")
+    } else {
+        ident
+    }
+}
+
+/// Rust type of a schema. `Option` is handled by the caller.
+fn rust_type(schema: &Value) -> String {
+    let (schema, nullable) = strip_null(schema);
+    let ty = rust_type_inner(&schema);
+    if nullable {
+        format!("Option<{ty}>")
+    } else {
+        ty
+    }
+}
+
+fn rust_type_inner(schema: &Value) -> String {
+    if let Some(name) = ref_name(schema) {
+        return type_name(name);
+    }
+    if let Some(inner) = single_all_of(schema) {
+        return rust_type(inner);
+    }
+    match schema.get("type").and_then(Value::as_str) {
+        Some("string") => "String".into(),
+        Some("integer") => match schema.get("format").and_then(Value::as_str) {
+            Some("int8") => "i8".into(),
+            Some("int16") => "i16".into(),
+            Some("int32") => "i32".into(),
+            Some("uint8") => "u8".into(),
+            Some("uint16") => "u16".into(),
+            Some("uint32") => "u32".into(),
+            Some("uint64" | "uint") => "u64".into(),
+            _ => "i64".into(),
+        },
+        Some("number") => match schema.get("format").and_then(Value::as_str) {
+            Some("float") => "f32".into(),
+            _ => "f64".into(),
+        },
+        Some("boolean") => "bool".into(),
+        Some("array") => format!(
+            "Vec<{}>",
+            schema
+                .get("items")
+                .map(rust_type)
+                .unwrap_or_else(|| "serde_json::Value".into())
+        ),
+        Some("object") => match schema.get("additionalProperties") {
+            Some(value @ Value::Object(_)) if schema.get("properties").is_none() => {
+                format!("std::collections::BTreeMap<String, {}>", rust_type(value))
+            }
+            _ => "serde_json::Value".into(),
+        },
+        _ => "serde_json::Value".into(),
+    }
+}
+
+fn rust_doc(out: &mut String, indent: &str, text: Option<&str>) {
+    if let Some(text) = text {
+        for line in doc_lines(text) {
+            let _ = writeln!(
+                out,
+                "{indent}///{}{line}",
+                if line.is_empty() { "" } else { " " }
+            );
+        }
+    }
+}
+
+fn rust_schema_item(out: &mut String, name: &str, schema: &Value) {
+    let type_name = type_name(name);
+    rust_doc(out, "", schema.get("description").and_then(Value::as_str));
+
+    if let Some(values) = string_enum(schema) {
+        out.push_str(
+            "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]\n",
+        );
+        let _ = writeln!(out, "pub enum {type_name} {{");
+        for value in values {
+            let _ = writeln!(out, "    #[serde(rename = {value:?})]");
+            let _ = writeln!(out, "    {},", pascal_case(value));
+        }
+        out.push_str("}\n\n");
+        return;
+    }
+
+    let properties = schema.get("properties").and_then(Value::as_object);
+    let is_object = schema.get("type").and_then(Value::as_str) == Some("object");
+    let Some(properties) = properties.filter(|_| is_object) else {
+        let _ = writeln!(out, "pub type {type_name} = {};\n", rust_type(schema));
+        return;
+    };
+
+    let required = required_set(schema);
+    out.push_str("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n");
+    let _ = writeln!(out, "pub struct {type_name} {{");
+    for (prop, prop_schema) in properties {
+        rust_doc(
+            out,
+            "    ",
+            prop_schema.get("description").and_then(Value::as_str),
+        );
+        let field = rust_ident(prop);
+        if field.trim_start_matches("r#") != prop {
+            let _ = writeln!(out, "    #[serde(rename = {prop:?})]");
+        }
+        let mut ty = rust_type(prop_schema);
+        if !required.contains(&prop.as_str()) {
+            out.push_str("    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n");
+            if !ty.starts_with("Option<") {
+                ty = format!("Option<{ty}>");
+            }
+        }
+        let _ = writeln!(out, "    pub {field}: {ty},");
+    }
+    out.push_str("}\n\n");
+}
+
+fn required_set(schema: &Value) -> Vec<&str> {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+/// Rust argument type of a path parameter.
+fn rust_path_arg(schema: &Value) -> String {
+    match rust_type(schema).as_str() {
+        ty @ ("i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64" | "f32" | "f64"
+        | "bool") => ty.to_string(),
+        _ => "&str".into(),
+    }
+}
+
+fn rust_query_struct(out: &mut String, op: &Operation) -> String {
+    let name = format!("{}Query", pascal_case(&op.id));
+    let _ = writeln!(
+        out,
+        "/// Query parameters of [`Client::{}`].",
+        rust_ident(&op.id)
+    );
+    out.push_str("#[derive(Debug, Clone, Default, PartialEq, Serialize)]\n");
+    let _ = writeln!(out, "pub struct {name} {{");
+    for param in &op.query_params {
+        rust_doc(out, "    ", param.description.as_deref());
+        let mut ty = rust_type(&param.schema);
+        if !param.required && !ty.starts_with("Option<") && !ty.starts_with("Vec<") {
+            ty = format!("Option<{ty}>");
+        }
+        let _ = writeln!(out, "    pub {}: {ty},", rust_ident(&param.name));
+    }
+    out.push_str("}\n\n");
+    let _ = writeln!(out, "impl {name} {{");
+    out.push_str("    fn pairs(&self) -> Vec<(&'static str, String)> {\n");
+    out.push_str("        let mut pairs = Vec::new();\n");
+    for param in &op.query_params {
+        let _ = writeln!(
+            out,
+            "        push_query(&mut pairs, {:?}, serde_json::to_value(&self.{}).unwrap_or_default(), {});",
+            param.name,
+            rust_ident(&param.name),
+            param.explode
+        );
+    }
+    out.push_str("        pairs\n    }\n}\n\n");
+    name
+}
+
+fn rust_headers_struct(out: &mut String, op: &Operation) -> String {
+    let name = format!("{}Headers", pascal_case(&op.id));
+    let _ = writeln!(
+        out,
+        "/// Header and cookie parameters of [`Client::{}`].",
+        rust_ident(&op.id)
+    );
+    out.push_str("#[derive(Debug, Clone, Default, PartialEq, Serialize)]\n");
+    let _ = writeln!(out, "pub struct {name} {{");
+    for param in op.header_and_cookie_params() {
+        rust_doc(out, "    ", param.description.as_deref());
+        let mut ty = rust_type(&param.schema);
+        if !param.required && !ty.starts_with("Option<") && !ty.starts_with("Vec<") {
+            ty = format!("Option<{ty}>");
+        }
+        let _ = writeln!(out, "    pub {}: {ty},", rust_ident(&param.name));
+    }
+    out.push_str("}\n\n");
+    let _ = writeln!(out, "impl {name} {{");
+    out.push_str("    fn pairs(&self) -> Vec<(&'static str, String)> {\n");
+    out.push_str("        let mut pairs = Vec::new();\n");
+    for param in &op.header_params {
+        let _ = writeln!(
+            out,
+            "        push_query(&mut pairs, {:?}, serde_json::to_value(&self.{}).unwrap_or_default(), false);",
+            param.name,
+            rust_ident(&param.name)
+        );
+    }
+    if !op.cookie_params.is_empty() {
+        out.push_str("        let mut cookies = Vec::new();\n");
+        for param in &op.cookie_params {
+            let _ = writeln!(
+                out,
+                "        push_query(&mut cookies, {:?}, serde_json::to_value(&self.{}).unwrap_or_default(), false);",
+                param.name,
+                rust_ident(&param.name)
+            );
+        }
+        out.push_str("        push_cookies(&mut pairs, &cookies);\n");
+    }
+    out.push_str("        pairs\n    }\n}\n\n");
+    name
+}
+
+/// Generate a Rust client module for `spec`.
+///
+/// Depends on `reqwest` (with the `json` feature), `serde` and `serde_json`;
+/// SSE operations also need `reqwest`'s `stream` feature and `futures-util`.
+pub fn rust_client(spec: &Value) -> String {
+    let ops = operations(spec);
+    let has_sse = ops
+        .iter()
+        .any(|op| op.response == ResponseKind::EventStream);
+
+    let mut out = String::new();
+    let _ = writeln!(out, "//! {}", header_line(spec));
+    out.push_str("//!\n//! Requires `reqwest` (feature `json`), `serde` and `serde_json`");
+    if has_sse {
+        out.push_str(",\n//! plus `futures-util` and the `stream` feature of `reqwest` for SSE");
+    }
+    out.push_str(
+        ".\n\n#![allow(dead_code, clippy::all)]\n\nuse serde::{Deserialize, Serialize};\n\n",
+    );
+
+    out.push_str("// ── Types ──\n\n");
+    for (name, schema) in schemas(spec) {
+        rust_schema_item(&mut out, name, schema);
+    }
+
+    out.push_str("// ── Operations ──\n\n");
+    let mut methods = String::new();
+    for op in &ops {
+        if let Some(content_type) = &op.unsupported_body {
+            let _ = writeln!(
+                methods,
+                "    // {}: `{content_type}` request bodies are not supported.\n",
+                op.id
+            );
+            continue;
+        }
+        let query_struct = (!op.query_params.is_empty()).then(|| rust_query_struct(&mut out, op));
+        let headers_struct = op.has_headers().then(|| rust_headers_struct(&mut out, op));
+        rust_method(
+            &mut methods,
+            op,
+            query_struct.as_deref(),
+            headers_struct.as_deref(),
+        );
+    }
+
+    out.push_str(RUST_RUNTIME);
+    out.push_str("\nimpl Client {\n");
+    out.push_str(&methods);
+    out.push_str("}\n");
+    if has_sse {
+        out.push_str(RUST_SSE_RUNTIME);
+    }
+    out.trim_end().to_string() + "\n"
+}
+
+fn rust_method(
+    out: &mut String,
+    op: &Operation,
+    query_struct: Option<&str>,
+    headers_struct: Option<&str>,
+) {
+    rust_doc(out, "    ", op.summary.as_deref());
+    let _ = writeln!(out, "    ///\n    /// `{} {}`", op.method, op.path);
+    if op.deprecated {
+        out.push_str("    #[deprecated]\n");
+    }
+
+    let mut args = vec!["&self".to_string()];
+    for param in &op.path_params {
+        args.push(format!(
+            "{}: {}",
+            rust_ident(&param.name),
+            rust_path_arg(&param.schema)
+        ));
+    }
+    if let Some((schema, required)) = &op.body {
+        let ty = rust_type(schema);
+        args.push(if *required {
+            format!("body: &{ty}")
+        } else {
+            format!("body: Option<&{ty}>")
+        });
+    }
+    if let Some(query) = query_struct {
+        args.push(format!("query: &{query}"));
+    }
+    if let Some(headers) = headers_struct {
+        args.push(format!("headers: &{headers}"));
+    }
+    let ret = match &op.response {
+        ResponseKind::Empty => "()".to_string(),
+        ResponseKind::Json(schema) => rust_type(schema),
+        ResponseKind::Text => "String".to_string(),
+        ResponseKind::EventStream => {
+            "impl futures_util::Stream<Item = Result<SseEvent, Error>>".to_string()
+        }
+    };
+    let _ = writeln!(
+        out,
+        "    pub async fn {}({}) -> Result<{ret}, Error> {{",
+        rust_ident(&op.id),
+        args.join(", ")
+    );
+
+    let mut template = op.path.clone();
+    let mut format_args = Vec::new();
+    for param in &op.path_params {
+        template = template.replace(&format!("{{{}}}", param.name), "{}");
+        format_args.push(format!("encode(&{}.to_string())", rust_ident(&param.name)));
+    }
+    if format_args.is_empty() {
+        let _ = writeln!(out, "        let path = {template:?};");
+    } else {
+        let _ = writeln!(
+            out,
+            "        let path = format!({template:?}, {});",
+            format_args.join(", ")
+        );
+    }
+    let query = if query_struct.is_some() {
+        "&query.pairs()"
+    } else {
+        "&[]"
+    };
+    let _ = writeln!(
+        out,
+        "        let request = self.request(reqwest::Method::{}, &path, {query});",
+        op.method
+    );
+    if headers_struct.is_some() {
+        out.push_str(
+            "        let request = headers\n            .pairs()\n            .into_iter()\n            .fold(request, |request, (name, value)| request.header(name, value));\n",
+        );
+    }
+    match &op.body {
+        Some((_, true)) => out.push_str("        let request = request.json(body);\n"),
+        Some((_, false)) => out.push_str(
+            "        let request = match body {\n            Some(body) => request.json(body),\n            None => request,\n        };\n",
+        ),
+        None => {}
+    }
+    match &op.response {
+        ResponseKind::Empty => out.push_str("        self.send(request).await?;\n        Ok(())\n"),
+        ResponseKind::Json(_) => {
+            out.push_str("        Ok(self.send(request).await?.json().await?)\n")
+        }
+        ResponseKind::Text => out.push_str("        Ok(self.send(request).await?.text().await?)\n"),
+        ResponseKind::EventStream => out.push_str(
+            "        let request = request.header(reqwest::header::ACCEPT, \"text/event-stream\");\n        Ok(sse_stream(self.send(request).await?))\n",
+        ),
+    }
+    out.push_str("    }\n\n");
+}
+
+const RUST_RUNTIME: &str = r#"// ── Client ──
+
+/// Error returned by [`Client`] operations.
+#[derive(Debug)]
+pub enum Error {
+    /// The request could not be sent or the response body not decoded.
+    Http(reqwest::Error),
+    /// The server answered with a non-success status.
+    Status { status: u16, body: String },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Http(err) => write!(f, "{err}"),
+            Error::Status { status, body } => write!(f, "HTTP {status}: {body}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Error::Http(err)
+    }
+}
+
+/// Typed API client.
+#[derive(Debug, Clone)]
+pub struct Client {
+    base_url: String,
+    http: reqwest::Client,
+    bearer_token: Option<String>,
+}
+
+impl Client {
+    /// Client for the API served at `base_url` (e.g. `http://localhost:8080`).
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            http: reqwest::Client::new(),
+            bearer_token: None,
+        }
+    }
+
+    /// Use a preconfigured `reqwest::Client` (timeouts, default headers, ...).
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Send `Authorization: Bearer <token>` with every request.
+    pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+
+    /// Replace or clear the bearer token.
+    pub fn set_bearer_token(&mut self, token: Option<String>) {
+        self.bearer_token = token;
+    }
+
+    fn request(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> reqwest::RequestBuilder {
+        let mut url = format!("{}{}", self.base_url, path);
+        for (i, (key, value)) in query.iter().enumerate() {
+            url.push(if i == 0 { '?' } else { '&' });
+            url.push_str(&encode(key));
+            url.push('=');
+            url.push_str(&encode(value));
+        }
+        let request = self.http.request(method, url);
+        match &self.bearer_token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, Error> {
+        let response = request.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(response)
+        } else {
+            let body = response.text().await.unwrap_or_default();
+            Err(Error::Status {
+                status: status.as_u16(),
+                body,
+            })
+        }
+    }
+}
+
+/// Percent-encode a path segment or query component.
+fn encode(segment: &str) -> String {
+    let mut out = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
+
+/// Append a query (or header) value: arrays repeat the key (or join with
+/// commas when not exploded), `null` is skipped.
+fn push_query(pairs: &mut Vec<(&'static str, String)>, key: &'static str, value: serde_json::Value, explode: bool) {
+    fn scalar(value: &serde_json::Value) -> Option<String> {
+        match value {
+            serde_json::Value::Null => None,
+            serde_json::Value::String(s) => Some(s.clone()),
+            other => Some(other.to_string()),
+        }
+    }
+    match value {
+        serde_json::Value::Array(items) if explode => {
+            pairs.extend(items.iter().filter_map(scalar).map(|item| (key, item)));
+        }
+        serde_json::Value::Array(items) => {
+            if !items.is_empty() {
+                let joined: Vec<String> = items.iter().filter_map(scalar).collect();
+                pairs.push((key, joined.join(",")));
+            }
+        }
+        other => pairs.extend(scalar(&other).map(|item| (key, item))),
+    }
+}
+
+/// Send cookie parameters as one `Cookie` header.
+fn push_cookies(pairs: &mut Vec<(&'static str, String)>, cookies: &[(&'static str, String)]) {
+    if !cookies.is_empty() {
+        let cookie: Vec<String> = cookies.iter().map(|(name, value)| format!("{name}={value}")).collect();
+        pairs.push(("cookie", cookie.join("; ")));
+    }
+}
+"#;
+
+const RUST_SSE_RUNTIME: &str = r#"
+/// One server-sent event.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SseEvent {
+    pub event: Option<String>,
+    pub data: String,
+    pub id: Option<String>,
+}
+
+fn sse_stream(response: reqwest::Response) -> impl futures_util::Stream<Item = Result<SseEvent, Error>> {
+    let bytes = Box::pin(response.bytes_stream());
+    futures_util::stream::unfold((bytes, String::new()), |(mut bytes, mut buffer)| async move {
+        loop {
+            if let Some(end) = buffer.find("\n\n") {
+                let block: String = buffer.drain(..end + 2).collect();
+                if let Some(event) = parse_sse_block(&block) {
+                    return Some((Ok(event), (bytes, buffer)));
+                }
+                continue;
+            }
+            match futures_util::StreamExt::next(&mut bytes).await {
+                Some(Ok(chunk)) => buffer.push_str(&String::from_utf8_lossy(&chunk).replace("\r\n", "\n")),
+                Some(Err(err)) => return Some((Err(Error::Http(err)), (bytes, buffer))),
+                None => return None,
+            }
+        }
+    })
+}
+
+fn parse_sse_block(block: &str) -> Option<SseEvent> {
+    let mut event = SseEvent::default();
+    let mut has_data = false;
+    for line in block.lines() {
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => event.event = Some(value.to_string()),
+            "id" => event.id = Some(value.to_string()),
+            "data" => {
+                if has_data {
+                    event.data.push('\n');
+                }
+                event.data.push_str(value);
+                has_data = true;
+            }
+            _ => {}
+        }
+    }
+    has_data.then_some(event)
+}
+"#;
+
+// ── TypeScript ──────────────────────────────────────────────────────────
+
+fn ts_type(schema: &Value) -> String {
+    let (schema, nullable) = strip_null(schema);
+    let ty = ts_type_inner(&schema);
+    if nullable {
+        format!("{ty} | null")
+    } else {
+        ty
+    }
+}
+
+/// `ts_type` wrapped in parentheses when it is a union or intersection.
+fn ts_type_atom(schema: &Value) -> String {
+    let ty = ts_type(schema);
+    if ty.contains(" | ") || ty.contains(" & ") {
+        format!("({ty})")
+    } else {
+        ty
+    }
+}
+
+fn ts_type_inner(schema: &Value) -> String {
+    if let Some(name) = ref_name(schema) {
+        return type_name(name);
+    }
+    if let Some(values) = string_enum(schema) {
+        return values
+            .iter()
+            .map(|v| format!("{v:?}"))
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+    if let Some(value) = schema.get("const") {
+        return value.to_string();
+    }
+    for (key, separator) in [("oneOf", " | "), ("anyOf", " | "), ("allOf", " & ")] {
+        if let Some(Value::Array(parts)) = schema.get(key) {
+            return parts
+                .iter()
+                .map(ts_type_atom)
+                .collect::<Vec<_>>()
+                .join(separator);
+        }
+    }
+    match schema.get("type").and_then(Value::as_str) {
+        Some("string") => "string".into(),
+        Some("integer" | "number") => "number".into(),
+        Some("boolean") => "boolean".into(),
+        Some("array") => format!(
+            "{}[]",
+            schema
+                .get("items")
+                .map(ts_type_atom)
+                .unwrap_or_else(|| "unknown".into())
+        ),
+        Some("object") => match schema.get("properties").and_then(Value::as_object) {
+            Some(properties) => {
+                let required = required_set(schema);
+                let fields: Vec<String> = properties
+                    .iter()
+                    .map(|(name, prop)| {
+                        let optional = if required.contains(&name.as_str()) {
+                            ""
+                        } else {
+                            "?"
+                        };
+                        format!("{}{optional}: {}", ts_property(name), ts_type(prop))
+                    })
+                    .collect();
+                format!("{{ {} }}", fields.join("; "))
+            }
+            None => match schema.get("additionalProperties") {
+                Some(value @ Value::Object(_)) => format!("Record<string, {}>", ts_type(value)),
+                _ => "Record<string, unknown>".into(),
+            },
+        },
+        _ => "unknown".into(),
+    }
+}
+
+fn ts_property(name: &str) -> String {
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+    if valid {
+        name.to_string()
+    } else {
+        format!("{name:?}")
+    }
+}
+
+fn ts_doc(out: &mut String, indent: &str, text: Option<&str>) {
+    let Some(text) = text else { return };
+    let lines: Vec<&str> = doc_lines(text).collect();
+    if let [line] = lines.as_slice() {
+        let _ = writeln!(out, "{indent}/** {line} */");
+        return;
+    }
+    let _ = writeln!(out, "{indent}/**");
+    for line in lines {
+        let _ = writeln!(
+            out,
+            "{indent} *{}{line}",
+            if line.is_empty() { "" } else { " " }
+        );
+    }
+    let _ = writeln!(out, "{indent} */");
+}
+
+fn ts_schema_item(out: &mut String, name: &str, schema: &Value) {
+    let type_name = type_name(name);
+    ts_doc(out, "", schema.get("description").and_then(Value::as_str));
+    let properties = schema.get("properties").and_then(Value::as_object);
+    let is_object = schema.get("type").and_then(Value::as_str) == Some("object");
+    let Some(properties) = properties.filter(|_| is_object) else {
+        let _ = writeln!(out, "export type {type_name} = {};\n", ts_type(schema));
+        return;
+    };
+    let required = required_set(schema);
+    let _ = writeln!(out, "export interface {type_name} {{");
+    for (prop, prop_schema) in properties {
+        ts_doc(
+            out,
+            "  ",
+            prop_schema.get("description").and_then(Value::as_str),
+        );
+        let optional = if required.contains(&prop.as_str()) {
+            ""
+        } else {
+            "?"
+        };
+        let _ = writeln!(
+            out,
+            "  {}{optional}: {};",
+            ts_property(prop),
+            ts_type(prop_schema)
+        );
+    }
+    out.push_str("}\n\n");
+}
+
+fn ts_query_interface(out: &mut String, op: &Operation) -> String {
+    let name = format!("{}Query", pascal_case(&op.id));
+    let _ = writeln!(
+        out,
+        "/** Query parameters of `Client.{}`. */",
+        camel_case(&op.id)
+    );
+    let _ = writeln!(out, "export interface {name} {{");
+    for param in &op.query_params {
+        ts_doc(out, "  ", param.description.as_deref());
+        let optional = if param.required { "" } else { "?" };
+        let _ = writeln!(
+            out,
+            "  {}{optional}: {};",
+            ts_property(&param.name),
+            ts_type(&param.schema)
+        );
+    }
+    out.push_str("}\n\n");
+    name
+}
+
+fn ts_headers_interface(out: &mut String, op: &Operation) -> String {
+    let name = format!("{}Headers", pascal_case(&op.id));
+    let _ = writeln!(
+        out,
+        "/** Header and cookie parameters of `Client.{}`. */",
+        camel_case(&op.id)
+    );
+    let _ = writeln!(out, "export interface {name} {{");
+    for param in op.header_and_cookie_params() {
+        ts_doc(out, "  ", param.description.as_deref());
+        let optional = if param.required { "" } else { "?" };
+        let _ = writeln!(
+            out,
+            "  {}{optional}: {};",
+            ts_property(&param.name),
+            ts_type(&param.schema)
+        );
+    }
+    out.push_str("}\n\n");
+    name
+}
+
+/// Generate a TypeScript client module for `spec`, built on `fetch`.
+pub fn typescript_client(spec: &Value) -> String {
+    let ops = operations(spec);
+    let mut out = String::new();
+    let _ = writeln!(out, "// {}\n", header_line(spec));
+
+    out.push_str("// ── Types ──\n\n");
+    for (name, schema) in schemas(spec) {
+        ts_schema_item(&mut out, name, schema);
+    }
+
+    out.push_str("// ── Operations ──\n\n");
+    let mut methods = String::new();
+    for op in &ops {
+        if let Some(content_type) = &op.unsupported_body {
+            let _ = writeln!(
+                methods,
+                "  // {}: `{content_type}` request bodies are not supported.\n",
+                op.id
+            );
+            continue;
+        }
+        let query = (!op.query_params.is_empty()).then(|| ts_query_interface(&mut out, op));
+        let headers = op.has_headers().then(|| ts_headers_interface(&mut out, op));
+        ts_method(&mut methods, op, query.as_deref(), headers.as_deref());
+    }
+
+    out.push_str(TS_RUNTIME);
+    out.push_str(&methods);
+    out.push_str("}\n");
+    if ops
+        .iter()
+        .any(|op| op.response == ResponseKind::EventStream)
+    {
+        out.push_str(TS_SSE_RUNTIME);
+    }
+    out.trim_end().to_string() + "\n"
+}
+
+fn ts_method(out: &mut String, op: &Operation, query: Option<&str>, headers: Option<&str>) {
+    let mut doc = op.summary.clone().unwrap_or_default();
+    if !doc.is_empty() {
+        doc.push_str("\n\n");
+    }
+    let _ = write!(doc, "`{} {}`", op.method, op.path);
+    if op.deprecated {
+        doc.push_str("\n\n@deprecated");
+    }
+    ts_doc(out, "  ", Some(&doc));
+
+    let mut args = Vec::new();
+    for param in &op.path_params {
+        let ty = match ts_type(&param.schema).as_str() {
+            "number" => "number",
+            "boolean" => "boolean",
+            _ => "string",
+        };
+        args.push(format!("{}: {ty}", camel_case(&param.name)));
+    }
+    if let Some((schema, required)) = &op.body {
+        let ty = ts_type(schema);
+        args.push(match (required, query.is_some() || headers.is_some()) {
+            (true, _) => format!("body: {ty}"),
+            (false, true) => format!("body: {ty} | undefined"),
+            (false, false) => format!("body?: {ty}"),
+        });
+    }
+    if let Some(query) = query {
+        let all_optional = op.query_params.iter().all(|p| !p.required);
+        args.push(if all_optional {
+            format!("query: {query} = {{}}")
+        } else {
+            format!("query: {query}")
+        });
+    }
+    if let Some(headers) = headers {
+        let all_optional = op.header_and_cookie_params().all(|p| !p.required);
+        args.push(if all_optional {
+            format!("headers: {headers} = {{}}")
+        } else {
+            format!("headers: {headers}")
+        });
+    }
+
+    let mut path = op.path.clone();
+    for param in &op.path_params {
+        path = path.replace(
+            &format!("{{{}}}", param.name),
+            &format!(
+                "${{encodeURIComponent(String({}))}}",
+                camel_case(&param.name)
+            ),
+        );
+    }
+    let query_arg = if query.is_some() {
+        let entries: Vec<String> = op
+            .query_params
+            .iter()
+            .map(|p| {
+                let access = format!("query{}", ts_accessor(&p.name));
+                if p.explode {
+                    format!("{}: {access}", ts_property(&p.name))
+                } else {
+                    format!("{}: {access}?.join(\",\")", ts_property(&p.name))
+                }
+            })
+            .collect();
+        format!("{{ {} }}", entries.join(", "))
+    } else {
+        "undefined".to_string()
+    };
+    let body_arg = if op.body.is_some() {
+        "body"
+    } else {
+        "undefined"
+    };
+    let headers_arg = headers.map(|_| {
+        let entries = |params: &[Param]| {
+            params
+                .iter()
+                .map(|p| format!("{}: headers{}", ts_property(&p.name), ts_accessor(&p.name)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut parts = Vec::new();
+        if !op.header_params.is_empty() {
+            parts.push(format!("headers: {{ {} }}", entries(&op.header_params)));
+        }
+        if !op.cookie_params.is_empty() {
+            parts.push(format!("cookies: {{ {} }}", entries(&op.cookie_params)));
+        }
+        format!(", {{ {} }}", parts.join(", "))
+    });
+    let name = camel_case(&op.id);
+    let call = |accept: &str| {
+        let params = match (&headers_arg, accept.is_empty()) {
+            (Some(headers), _) => headers.as_str(),
+            (None, false) => ", undefined",
+            (None, true) => "",
+        };
+        format!(
+            "this.send({:?}, `{path}`, {query_arg}, {body_arg}{params}{accept})",
+            op.method
+        )
+    };
+
+    match &op.response {
+        ResponseKind::EventStream => {
+            let _ = writeln!(
+                out,
+                "  async *{name}({}): AsyncGenerator<SseEvent> {{",
+                args.join(", ")
+            );
+            let _ = writeln!(
+                out,
+                "    yield* sseEvents(await {});",
+                call(", \"text/event-stream\"")
+            );
+        }
+        response => {
+            let (ret, body) = match response {
+                ResponseKind::Json(schema) => (
+                    ts_type(schema),
+                    format!(
+                        "return (await (await {}).json()) as {};",
+                        call(""),
+                        ts_type(schema)
+                    ),
+                ),
+                ResponseKind::Text => (
+                    "string".into(),
+                    format!("return (await {}).text();", call("")),
+                ),
+                _ => ("void".into(), format!("await {};", call(""))),
+            };
+            let _ = writeln!(
+                out,
+                "  async {name}({}): Promise<{ret}> {{",
+                args.join(", ")
+            );
+            let _ = writeln!(out, "    {body}");
+        }
+    }
+    out.push_str("  }\n\n");
+}
+
+fn ts_accessor(name: &str) -> String {
+    let property = ts_property(name);
+    if property.starts_with('"') {
+        format!("[{property}]")
+    } else {
+        format!(".{property}")
+    }
+}
+
+const TS_RUNTIME: &str = r#"// ── Client ──
+
+export interface ClientOptions {
+  /** Sent as `Authorization: Bearer <token>`. */
+  token?: string;
+  /** Extra headers sent with every request. */
+  headers?: Record<string, string>;
+  /** `fetch` implementation (defaults to the global one). */
+  fetch?: typeof fetch;
+}
+
+/** Error thrown when the server answers with a non-success status. */
+export class ApiError extends Error {
+  constructor(
+    readonly status: number,
+    readonly body: string,
+  ) {
+    super(`HTTP ${status}: ${body}`);
+  }
+}
+
+type QueryValue = string | number | boolean | null | undefined | Array<string | number | boolean>;
+
+/** Header and cookie parameters of one request. */
+interface RequestParams {
+  headers?: Record<string, QueryValue>;
+  cookies?: Record<string, QueryValue>;
+}
+
+function paramValue(value: QueryValue): string | undefined {
+  if (value === undefined || value === null) return undefined;
+  return Array.isArray(value) ? value.join(",") : String(value);
+}
+
+/** Typed API client. */
+export class Client {
+  private readonly baseUrl: string;
+
+  constructor(
+    baseUrl: string,
+    private options: ClientOptions = {},
+  ) {
+    this.baseUrl = baseUrl.replace(/\/+$/, "");
+  }
+
+  /** Replace or clear the bearer token. */
+  setToken(token: string | undefined): void {
+    this.options = { ...this.options, token };
+  }
+
+  private async send(
+    method: string,
+    path: string,
+    query: Record<string, QueryValue> | undefined,
+    body: unknown,
+    params: RequestParams = {},
+    accept = "application/json",
+  ): Promise<Response> {
+    const params = new URLSearchParams();
+    for (const [key, value] of Object.entries(query ?? {})) {
+      if (value === undefined || value === null) continue;
+      for (const item of Array.isArray(value) ? value : [value]) params.append(key, String(item));
+    }
+    const search = params.toString();
+    const headers: Record<string, string> = { accept, ...this.options.headers };
+    if (this.options.token) headers.authorization = `Bearer ${this.options.token}`;
+    for (const [key, value] of Object.entries(params.headers ?? {})) {
+      const text = paramValue(value);
+      if (text !== undefined) headers[key] = text;
+    }
+    const cookies = Object.entries(params.cookies ?? {}).flatMap(([key, value]) => {
+      const text = paramValue(value);
+      return text === undefined ? [] : [`${key}=${text}`];
+    });
+    if (cookies.length > 0) headers.cookie = cookies.join("; ");
+    if (body !== undefined) headers["content-type"] = "application/json";
+    const response = await (this.options.fetch ?? fetch)(
+      `${this.baseUrl}${path}${search ? `?${search}` : ""}`,
+      { method, headers, body: body === undefined ? undefined : JSON.stringify(body) },
+    );
+    if (!response.ok) throw new ApiError(response.status, await response.text());
+    return response;
+  }
+
+"#;
+
+const TS_SSE_RUNTIME: &str = r#"
+/** One server-sent event. */
+export interface SseEvent {
+  event?: string;
+  data: string;
+  id?: string;
+}
+
+async function* sseEvents(response: Response): AsyncGenerator<SseEvent> {
+  const reader = response.body!.pipeThrough(new TextDecoderStream()).getReader();
+  let buffer = "";
+  for (;;) {
+    const { value, done } = await reader.read();
+    if (done) return;
+    buffer += value.replace(/\r\n/g, "\n");
+    let end: number;
+    while ((end = buffer.indexOf("\n\n")) >= 0) {
+      const event = parseSseBlock(buffer.slice(0, end));
+      buffer = buffer.slice(end + 2);
+      if (event) yield event;
+    }
+  }
+}
+
+function parseSseBlock(block: string): SseEvent | undefined {
+  const event: SseEvent = { data: "" };
+  let hasData = false;
+  for (const line of block.split("\n")) {
+    const colon = line.indexOf(":");
+    const field = colon < 0 ? line : line.slice(0, colon);
+    let value = colon < 0 ? "" : line.slice(colon + 1);
+    if (value.startsWith(" ")) value = value.slice(1);
+    if (field === "event") event.event = value;
+    else if (field === "id") event.id = value;
+    else if (field === "data") {
+      event.data = hasData ? `${event.data}\n${value}` : value;
+      hasData = true;
+    }
+  }
+  return hasData ? event : undefined;
+}
+"#;
@@ -0,0 +1,180 @@
+use colored::Colorize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::templates::to_snake_case;
+
+/// Run a migration command (`migrate`, `revert` or `status`) through the app.
+///
+/// Runs `cargo run` in the current project with `R2E_MIGRATE_COMMAND` set.
+/// The app's `DataSource` (SQLx) or `DieselMigrations` plugin picks the
+/// command up while `build_state()` runs, executes it against the datasource
+/// named by `R2E_MIGRATE_DATASOURCE` (`default` when `datasource` is `None`)
+/// and exits instead of serving. The command therefore uses the app's own
+/// `application.yaml`, profile overlay, secrets and embedded migrations.
+///
+/// Returns an error if `Cargo.toml` is missing or the app exits with a
+/// failure status.
+pub fn run(
+    command: &str,
+    datasource: Option<&str>,
+    profile: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !Path::new("Cargo.toml").exists() {
+        return Err("Cargo.toml not found — run `r2e db` from the project root".into());
+    }
+
+    println!(
+        "{} Running `{}` through the application{}",
+        "->".blue(),
+        command.green(),
+        profile
+            .map(|profile| format!(" (profile {})", profile.cyan()))
+            .unwrap_or_default()
+    );
+
+    let mut cmd = Command::new("cargo");
+    cmd.args(["run", "--quiet"])
+        .env("R2E_MIGRATE_COMMAND", command)
+        .env("R2E_MIGRATE_DATASOURCE", datasource.unwrap_or("default"));
+    if let Some(profile) = profile {
+        cmd.env("R2E_PROFILE", profile);
+    }
+
+    let status = cmd.status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("`r2e db {command}` failed ({status})").into())
+    }
+}
+
+/// Migration file layout, picked from the project's data backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationStyle {
+    /// `{version}_{name}.sql`, or `.up.sql` / `.down.sql` when reversible.
+    Sqlx { reversible: bool },
+    /// `{yyyy-mm-dd-hhmmss}_{name}/up.sql` and `down.sql`.
+    Diesel,
+}
+
+/// Create an empty migration in `migrations/` — `r2e db new <name>`.
+///
+/// Diesel projects (see [`uses_diesel`]) get Diesel's directory layout;
+/// everything else gets SQLx files. Creates `migrations/` if needed.
+pub fn new(name: &str, reversible: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let cargo = fs::read_to_string("Cargo.toml").unwrap_or_default();
+    let style = if uses_diesel(&cargo) {
+        MigrationStyle::Diesel
+    } else {
+        MigrationStyle::Sqlx { reversible }
+    };
+
+    let created = create_migration(Path::new("migrations"), name, style, chrono::Utc::now())?;
+    for path in &created {
+        println!("  {} {}", "✓".green(), path.display());
+    }
+    Ok(())
+}
+
+/// Whether a `Cargo.toml` declares a Diesel data backend: a `diesel` or
+/// `r2e-data-diesel` dependency, or an `r2e` dependency with one of its Diesel
+/// features. Target-specific dependency tables count too; a manifest that does
+/// not parse declares nothing.
+pub fn uses_diesel(cargo_toml: &str) -> bool {
+    let Ok(doc) = cargo_toml.parse::<toml_edit::DocumentMut>() else {
+        return false;
+    };
+    let mut tables: Vec<&dyn toml_edit::TableLike> = Vec::new();
+    if let Some(deps) = doc.get("dependencies").and_then(|d| d.as_table_like()) {
+        tables.push(deps);
+    }
+    if let Some(targets) = doc.get("target").and_then(|t| t.as_table_like()) {
+        tables.extend(
+            targets
+                .iter()
+                .filter_map(|(_, target)| target.get("dependencies"))
+                .filter_map(|deps| deps.as_table_like()),
+        );
+    }
+
+    tables.iter().any(|deps| {
+        deps.iter().any(|(name, dep)| match name {
+            "diesel" | "r2e-data-diesel" => true,
+            "r2e" => dep
+                .get("features")
+                .and_then(|features| features.as_array())
+                .is_some_and(|features| {
+                    features
+                        .iter()
+                        .filter_map(|f| f.as_str())
+                        .any(|f| f == "data-diesel" || f.starts_with("diesel-"))
+                }),
+            _ => false,
+        })
+    })
+}
+
+/// Write the files of a new migration under `dir`, stamped with `now`, and
+/// return their paths.
+pub fn create_migration(
+    dir: &Path,
+    name: &str,
+    style: MigrationStyle,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let slug = to_snake_case(name)
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_");
+    if slug.is_empty() {
+        return Err("migration name must not be empty".into());
+    }
+    fs::create_dir_all(dir)?;
+
+    let files = match style {
+        MigrationStyle::Sqlx { reversible: false } => {
+            let version = now.format("%Y%m%d%H%M%S");
+            vec![(
+                dir.join(format!("{version}_{slug}.sql")),
+                "-- Add migration script here\n",
+            )]
+        }
+        MigrationStyle::Sqlx { reversible: true } => {
+            let version = now.format("%Y%m%d%H%M%S");
+            vec![
+                (
+                    dir.join(format!("{version}_{slug}.up.sql")),
+                    "-- Add up migration script here\n",
+                ),
+                (
+                    dir.join(format!("{version}_{slug}.down.sql")),
+                    "-- Add down migration script here\n",
+                ),
+            ]
+        }
+        MigrationStyle::Diesel => {
+            let folder = dir.join(format!("{}_{slug}", now.format("%Y-%m-%d-%H%M%S")));
+            fs::create_dir_all(&folder)?;
+            vec![
+                (folder.join("up.sql"), "-- Your SQL goes here\n"),
+                (
+                    folder.join("down.sql"),
+                    "-- This file should undo anything in `up.sql`\n",
+                ),
+            ]
+        }
+    };
+
+    let mut created = Vec::new();
+    for (path, content) in files {
+        if path.exists() {
+            return Err(format!("{} already exists", path.display()).into());
+        }
+        fs::write(&path, content)?;
+        created.push(path);
+    }
+    Ok(created)
+}
@@ -9,6 +9,19 @@
 /// events, scheduler, cache, rate-limit, utils, prometheus, grpc, test.
 pub mod add;
 
+/// Typed client generation — `r2e generate client --lang rust|typescript`.
+///
+/// Reads the OpenAPI spec (from `--spec` or by running the app with
+/// `R2E_OPENAPI_EXPORT` set) and emits a single-file Rust or TypeScript SDK.
+pub mod client;
+
+/// Database migrations — `r2e db migrate|revert|status|new`.
+///
+/// `migrate`, `revert` and `status` run the app with `R2E_MIGRATE_COMMAND`
+/// set, so they use its own datasource config and embedded migrations;
+/// `new` creates an empty SQLx or Diesel migration in `migrations/`.
+pub mod db;
+
 /// Development server — `r2e dev`.
 ///
 /// Wraps `cargo watch` with R2E-specific defaults (watched paths,
@@ -31,7 +44,8 @@ pub mod doctor;
 
 /// Code generation — `r2e generate`.
 ///
-/// Subcommands: `controller`, `service`, `crud`, `middleware`, `grpc-service`.
+/// Subcommands: `controller`, `service`, `crud`, `middleware`, `grpc-service`
+/// (`client` is implemented in [`client`]).
 /// Generates skeleton source files and updates `mod.rs` declarations.
 pub mod generate;
 
@@ -7,8 +7,9 @@
 //! | Command | Description |
 //! |---------|-------------|
 //! | `r2e new <name>` | Create a new R2E project with optional features |
-//! | `r2e generate` | Generate controllers, services, CRUD, middleware, gRPC |
+//! | `r2e generate` | Generate controllers, services, CRUD, middleware, gRPC, API clients |
 //! | `r2e add <ext>` | Add an R2E extension to Cargo.toml |
+//! | `r2e db <action>` | Apply, revert, inspect or create database migrations |
 //! | `r2e dev` | Start development server with hot-reload |
 //! | `r2e doctor` | Run project health diagnostics |
 //! | `r2e routes` | List all declared routes from source |
@@ -20,7 +21,9 @@
 //!
 //! - [`commands::new_project`] — project scaffolding (`r2e new`)
 //! - [`commands::generate`] — code generation (`r2e generate`)
+//! - [`commands::client`] — typed API clients (`r2e generate client`)
 //! - [`commands::add`] — extension management (`r2e add`)
+//! - [`commands::db`] — database migrations (`r2e db`)
 //! - [`commands::dev`] — development server (`r2e dev`)
 //! - [`commands::doctor`] — project diagnostics (`r2e doctor`)
 //! - [`commands::routes`] — route listing (`r2e routes`)
@@ -1,7 +1,8 @@
 mod commands;
 
 use clap::{Parser, Subcommand};
-use commands::{add, dev, docs, doctor, generate, new_project, routes};
+use commands::{add, client, db, dev, docs, doctor, generate, new_project, routes};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(
@@ -52,6 +53,11 @@ enum Commands {
         /// Extension name (e.g. security, data-sqlx, openapi, events, scheduler)
         extension: String,
     },
+    /// Apply, revert, inspect or create database migrations
+    Db {
+        #[command(subcommand)]
+        action: DbAction,
+    },
     /// Start the dev server with Subsecond hot-reload
     Dev {
         /// Server port (forwarded as R2E_PORT env var)
@@ -78,6 +84,45 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand)]
+enum DbAction {
+    /// Apply pending migrations
+    Migrate {
+        /// Named datasource to target (default: the `datasource` section)
+        #[arg(long)]
+        datasource: Option<String>,
+        /// Config profile to load (forwarded as R2E_PROFILE env var)
+        #[arg(long)]
+        profile: Option<String>,
+    },
+    /// Revert the last applied migration
+    Revert {
+        /// Named datasource to target (default: the `datasource` section)
+        #[arg(long)]
+        datasource: Option<String>,
+        /// Config profile to load (forwarded as R2E_PROFILE env var)
+        #[arg(long)]
+        profile: Option<String>,
+    },
+    /// List applied and pending migrations
+    Status {
+        /// Named datasource to target (default: the `datasource` section)
+        #[arg(long)]
+        datasource: Option<String>,
+        /// Config profile to load (forwarded as R2E_PROFILE env var)
+        #[arg(long)]
+        profile: Option<String>,
+    },
+    /// Create an empty migration in migrations/
+    New {
+        /// Migration name (e.g. add_email_to_users)
+        name: String,
+        /// Create separate up/down files (SQLx projects)
+        #[arg(long)]
+        reversible: bool,
+    },
+}
+
 #[derive(Subcommand)]
 enum GenerateKind {
     /// Generate a new controller
@@ -111,6 +156,18 @@ enum GenerateKind {
         #[arg(long, default_value = "myapp")]
         package: String,
     },
+    /// Generate a typed API client from the OpenAPI spec
+    Client {
+        /// Client language: rust or typescript
+        #[arg(long)]
+        lang: client::Lang,
+        /// Read the spec from this file instead of exporting it from the app
+        #[arg(long)]
+        spec: Option<PathBuf>,
+        /// Output file (defaults to client.rs / client.ts)
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
 }
 
 fn main() {
@@ -144,8 +201,26 @@ fn main() {
             GenerateKind::Crud { name, fields } => generate::crud(&name, &fields),
             GenerateKind::Middleware { name } => generate::middleware(&name),
             GenerateKind::GrpcService { name, package } => generate::grpc_service(&name, &package),
+            GenerateKind::Client { lang, spec, out } => {
+                client::run(lang, spec.as_deref(), out.as_deref())
+            }
         },
         Commands::Add { extension } => add::run(&extension),
+        Commands::Db { action } => match action {
+            DbAction::Migrate {
+                datasource,
+                profile,
+            } => db::run("migrate", datasource.as_deref(), profile.as_deref()),
+            DbAction::Revert {
+                datasource,
+                profile,
+            } => db::run("revert", datasource.as_deref(), profile.as_deref()),
+            DbAction::Status {
+                datasource,
+                profile,
+            } => db::run("status", datasource.as_deref(), profile.as_deref()),
+            DbAction::New { name, reversible } => db::new(&name, reversible),
+        },
         Commands::Dev { port, features } => dev::run(port, features),
         Commands::Doctor => doctor::run(),
         Commands::Routes => routes::run(),
@@ -0,0 +1,255 @@
+use r2e_cli::commands::client::{
+    operations, run, rust_client, typescript_client, Lang, ResponseKind,
+};
+use serde_json::{json, Value};
+use std::fs;
+use tempfile::TempDir;
+
+fn spec() -> Value {
+    json!({
+        "openapi": "3.0.3",
+        "info": { "title": "Shop", "version": "1.2.0" },
+        "paths": {
+            "/users/{id}": {
+                "get": {
+                    "operationId": "UserController_get",
+                    "summary": "Fetch one user",
+                    "parameters": [
+                        { "name": "id", "in": "path", "required": true,
+                          "schema": { "type": "integer", "format": "int64" } }
+                    ],
+                    "responses": {
+                        "200": { "content": { "application/json": {
+                            "schema": { "$ref": "#/components/schemas/User" } } } },
+                        "404": { "description": "Not found" }
+                    }
+                },
+                "delete": {
+                    "operationId": "UserController_delete",
+                    "parameters": [
+                        { "name": "id", "in": "path", "required": true,
+                          "schema": { "type": "integer", "format": "int64" } }
+                    ],
+                    "responses": { "204": { "description": "Deleted" } }
+                }
+            },
+            "/users": {
+                "get": {
+                    "operationId": "UserController_list",
+                    "parameters": [
+                        { "name": "role", "in": "query", "required": false,
+                          "schema": { "$ref": "#/components/schemas/Role" } },
+                        { "name": "ids", "in": "query", "required": false, "explode": false,
+                          "schema": { "type": "array", "items": { "type": "integer" } } }
+                    ],
+                    "responses": { "200": { "content": { "application/json": {
+                        "schema": { "type": "array",
+                                    "items": { "$ref": "#/components/schemas/User" } } } } } }
+                },
+                "post": {
+                    "operationId": "UserController_create",
+                    "requestBody": { "required": true, "content": { "application/json": {
+                        "schema": { "$ref": "#/components/schemas/User" } } } },
+                    "responses": { "201": { "content": { "application/json": {
+                        "schema": { "$ref": "#/components/schemas/User" } } } } }
+                }
+            },
+            "/events": {
+                "get": {
+                    "operationId": "EventController_stream",
+                    "responses": { "200": { "content": {
+                        "text/event-stream": { "schema": { "type": "string" } } } } }
+                }
+            },
+            "/orders": {
+                "post": {
+                    "operationId": "OrderController_place",
+                    "parameters": [
+                        { "name": "Idempotency-Key", "in": "header", "required": false,
+                          "description": "Retry key.", "schema": { "type": "string" } },
+                        { "name": "Api-Version", "in": "header", "required": true,
+                          "schema": { "type": "integer" } },
+                        { "name": "session", "in": "cookie", "required": true,
+                          "schema": { "type": "string" } }
+                    ],
+                    "responses": { "202": { "description": "Accepted" } }
+                }
+            },
+            "/upload": {
+                "post": {
+                    "operationId": "UploadController_upload",
+                    "requestBody": { "content": { "multipart/form-data": {} } },
+                    "responses": { "200": { "description": "OK" } }
+                }
+            }
+        },
+        "components": { "schemas": {
+            "User": {
+                "type": "object",
+                "description": "A registered user.",
+                "required": ["id", "display-name", "role"],
+                "properties": {
+                    "id": { "type": "integer", "format": "int64" },
+                    "display-name": { "type": "string" },
+                    "role": { "$ref": "#/components/schemas/Role" },
+                    "type": { "type": ["string", "null"] },
+                    "email": { "type": "string", "description": "Contact address." }
+                }
+            },
+            "Role": { "type": "string", "enum": ["admin", "member"] },
+            "String": { "type": "string" }
+        } }
+    })
+}
+
+#[test]
+fn operations_are_ordered_by_path_then_method() {
+    let ops = operations(&spec());
+    let ids: Vec<_> = ops.iter().map(|op| op.id.as_str()).collect();
+    assert_eq!(
+        ids,
+        [
+            "EventController_stream",
+            "OrderController_place",
+            "UploadController_upload",
+            "UserController_list",
+            "UserController_create",
+            "UserController_get",
+            "UserController_delete",
+        ]
+    );
+    assert_eq!(ops[0].response, ResponseKind::EventStream);
+    assert_eq!(ops[1].header_params.len(), 2);
+    assert_eq!(ops[1].cookie_params[0].name, "session");
+    assert_eq!(
+        ops[2].unsupported_body.as_deref(),
+        Some("multipart/form-data")
+    );
+    assert!(!ops[3].query_params[1].explode);
+    assert_eq!(ops[6].response, ResponseKind::Empty);
+}
+
+#[test]
+fn rust_client_has_types_and_one_method_per_operation() {
+    let code = rust_client(&spec());
+
+    assert!(code.starts_with("//! Generated by `r2e generate client` from Shop 1.2.0."));
+    assert!(code.contains("/// A registered user.\n#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\npub struct User {"));
+    assert!(code.contains("    #[serde(rename = \"display-name\")]\n    pub display_name: String,"));
+    assert!(code.contains("    pub r#type: Option<String>,"));
+    assert!(code.contains(
+        "    /// Contact address.\n    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n    pub email: Option<String>,"
+    ));
+    assert!(code.contains("pub enum Role {\n    #[serde(rename = \"admin\")]\n    Admin,"));
+    // Schemas named like a Rust or runtime type are renamed.
+    assert!(code.contains("pub type StringSchema = String;"));
+
+    assert!(code.contains(
+        "pub struct UserControllerListQuery {\n    pub role: Option<Role>,\n    pub ids: Vec<i64>,"
+    ));
+    assert!(code.contains("push_query(&mut pairs, \"ids\", serde_json::to_value(&self.ids).unwrap_or_default(), false);"));
+    assert!(code.contains(
+        "pub async fn user_controller_get(&self, id: i64) -> Result<User, Error> {\n        let path = format!(\"/users/{}\", encode(&id.to_string()));"
+    ));
+    assert!(code.contains(
+        "pub async fn user_controller_create(&self, body: &User) -> Result<User, Error> {"
+    ));
+    assert!(
+        code.contains("pub async fn user_controller_delete(&self, id: i64) -> Result<(), Error> {")
+    );
+    assert!(code.contains("query: &UserControllerListQuery) -> Result<Vec<User>, Error> {"));
+    assert!(code.contains("pub fn with_bearer_token("));
+    assert!(code.contains(
+        "// UploadController_upload: `multipart/form-data` request bodies are not supported."
+    ));
+
+    assert!(code.contains(
+        "pub async fn event_controller_stream(&self) -> Result<impl futures_util::Stream<Item = Result<SseEvent, Error>>, Error> {"
+    ));
+    assert!(code.contains("pub struct SseEvent {"));
+}
+
+#[test]
+fn header_and_cookie_params_become_arguments() {
+    let code = rust_client(&spec());
+    assert!(code.contains(
+        "pub struct OrderControllerPlaceHeaders {\n    /// Retry key.\n    pub idempotency_key: Option<String>,\n    pub api_version: i64,\n    pub session: String,\n}"
+    ));
+    assert!(code.contains("push_query(&mut pairs, \"Idempotency-Key\", serde_json::to_value(&self.idempotency_key).unwrap_or_default(), false);"));
+    assert!(code.contains("push_query(&mut cookies, \"session\", serde_json::to_value(&self.session).unwrap_or_default(), false);\n        push_cookies(&mut pairs, &cookies);"));
+    assert!(code.contains(
+        "pub async fn order_controller_place(&self, headers: &OrderControllerPlaceHeaders) -> Result<(), Error> {"
+    ));
+    assert!(code.contains(".fold(request, |request, (name, value)| request.header(name, value));"));
+
+    let code = typescript_client(&spec());
+    assert!(code.contains(
+        "export interface OrderControllerPlaceHeaders {\n  /** Retry key. */\n  \"Idempotency-Key\"?: string;\n  \"Api-Version\": number;\n  session: string;\n}"
+    ));
+    assert!(code.contains(
+        "  async orderControllerPlace(headers: OrderControllerPlaceHeaders): Promise<void> {\n    await this.send(\"POST\", `/orders`, undefined, undefined, { headers: { \"Idempotency-Key\": headers[\"Idempotency-Key\"], \"Api-Version\": headers[\"Api-Version\"] }, cookies: { session: headers.session } });"
+    ));
+    assert!(code.contains("if (cookies.length > 0) headers.cookie = cookies.join(\"; \");"));
+}
+
+#[test]
+fn rust_client_omits_sse_runtime_without_sse_routes() {
+    let mut spec = spec();
+    spec["paths"].as_object_mut().unwrap().remove("/events");
+
+    let code = rust_client(&spec);
+    assert!(!code.contains("SseEvent"));
+    assert!(!code.contains("futures_util"));
+}
+
+#[test]
+fn typescript_client_has_types_and_one_method_per_operation() {
+    let code = typescript_client(&spec());
+
+    assert!(code.starts_with("// Generated by `r2e generate client` from Shop 1.2.0."));
+    assert!(code.contains("/** A registered user. */\nexport interface User {\n  \"display-name\": string;\n  /** Contact address. */\n  email?: string;\n  id: number;\n  role: Role;\n  type?: string | null;\n}"));
+    assert!(code.contains("export type Role = \"admin\" | \"member\";"));
+    assert!(code.contains(
+        "export interface UserControllerListQuery {\n  role?: Role;\n  ids?: number[];\n}"
+    ));
+
+    assert!(code.contains(
+        "  async userControllerList(query: UserControllerListQuery = {}): Promise<User[]> {\n    return (await (await this.send(\"GET\", `/users`, { role: query.role, ids: query.ids?.join(\",\") }, undefined)).json()) as User[];"
+    ));
+    assert!(code.contains(
+        "  async userControllerGet(id: number): Promise<User> {\n    return (await (await this.send(\"GET\", `/users/${encodeURIComponent(String(id))}`, undefined, undefined)).json()) as User;"
+    ));
+    assert!(code.contains("  async userControllerDelete(id: number): Promise<void> {"));
+    assert!(code.contains("  async userControllerCreate(body: User): Promise<User> {"));
+    assert!(code.contains("  async *eventControllerStream(): AsyncGenerator<SseEvent> {"));
+    assert!(code.contains("headers.authorization = `Bearer ${this.options.token}`"));
+}
+
+#[test]
+fn output_is_deterministic() {
+    let spec = spec();
+    assert_eq!(rust_client(&spec), rust_client(&spec));
+    assert_eq!(typescript_client(&spec), typescript_client(&spec));
+}
+
+#[test]
+fn run_writes_the_client_from_a_spec_file() {
+    let tmp = TempDir::new().unwrap();
+    let spec_path = tmp.path().join("openapi.json");
+    fs::write(&spec_path, spec().to_string()).unwrap();
+    let out = tmp.path().join("sdk/client.ts");
+
+    run(Lang::TypeScript, Some(&spec_path), Some(&out)).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&out).unwrap(),
+        typescript_client(&spec())
+    );
+}
+
+#[test]
+fn lang_accepts_short_names() {
+    assert_eq!("rs".parse::<Lang>(), Ok(Lang::Rust));
+    assert_eq!("typescript".parse::<Lang>(), Ok(Lang::TypeScript));
+    assert!("go".parse::<Lang>().is_err());
+}
@@ -0,0 +1,92 @@
+use chrono::TimeZone;
+use r2e_cli::commands::db::{create_migration, uses_diesel, MigrationStyle};
+use std::fs;
+use tempfile::TempDir;
+
+fn at() -> chrono::DateTime<chrono::Utc> {
+    chrono::Utc.with_ymd_and_hms(2024, 3, 1, 12, 30, 5).unwrap()
+}
+
+#[test]
+fn sqlx_migration_is_a_single_timestamped_file() {
+    let tmp = TempDir::new().unwrap();
+    let dir = tmp.path().join("migrations");
+
+    let created = create_migration(
+        &dir,
+        "AddEmail to users",
+        MigrationStyle::Sqlx { reversible: false },
+        at(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        created,
+        vec![dir.join("20240301123005_add_email_to_users.sql")]
+    );
+}
+
+#[test]
+fn reversible_sqlx_migration_has_up_and_down_files() {
+    let tmp = TempDir::new().unwrap();
+
+    let created = create_migration(
+        tmp.path(),
+        "add_email",
+        MigrationStyle::Sqlx { reversible: true },
+        at(),
+    )
+    .unwrap();
+
+    let names: Vec<_> = created
+        .iter()
+        .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "20240301123005_add_email.up.sql",
+            "20240301123005_add_email.down.sql"
+        ]
+    );
+}
+
+#[test]
+fn diesel_migration_is_a_directory() {
+    let tmp = TempDir::new().unwrap();
+
+    create_migration(tmp.path(), "create_posts", MigrationStyle::Diesel, at()).unwrap();
+
+    let folder = tmp.path().join("2024-03-01-123005_create_posts");
+    assert!(fs::read_to_string(folder.join("up.sql")).is_ok());
+    assert!(fs::read_to_string(folder.join("down.sql")).is_ok());
+}
+
+#[test]
+fn refuses_to_overwrite_an_existing_migration() {
+    let tmp = TempDir::new().unwrap();
+    let style = MigrationStyle::Sqlx { reversible: false };
+
+    create_migration(tmp.path(), "init", style, at()).unwrap();
+    let error = create_migration(tmp.path(), "init", style, at()).unwrap_err();
+
+    assert!(error.to_string().contains("already exists"));
+}
+
+#[test]
+fn diesel_is_detected_from_dependency_keys() {
+    assert!(uses_diesel("[dependencies]\nr2e-data-diesel = \"0.1\"\n"));
+    assert!(uses_diesel(
+        "[target.'cfg(unix)'.dependencies]\ndiesel = { version = \"2\" }\n"
+    ));
+    assert!(uses_diesel(
+        "[dependencies]\nr2e = { version = \"0.1\", features = [\"diesel-postgres\"] }\n"
+    ));
+
+    // Mentions of "diesel" outside dependency keys do not count.
+    assert!(!uses_diesel(
+        "[package]\nname = \"diesel-free\"\ndescription = \"no diesel here\"\n\n[dependencies]\nr2e-data-sqlx = \"0.1\"\n"
+    ));
+    assert!(!uses_diesel("[dev-dependencies]\ndiesel = \"2\"\n"));
+    assert!(!uses_diesel("not toml ["));
+}
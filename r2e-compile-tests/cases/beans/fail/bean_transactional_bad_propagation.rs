@@ -0,0 +1,26 @@
+//! `#[transactional(propagation = ...)]` only accepts `required`,
+//! `requires_new` and `mandatory`.
+
+use r2e::prelude::*;
+
+#[derive(Clone)]
+pub struct Pool;
+
+#[derive(Clone)]
+pub struct Ledger {
+    pool: Pool,
+}
+
+#[bean]
+impl Ledger {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+
+    #[transactional(propagation = nested)]
+    async fn post(&self) -> Result<(), HttpError> {
+        Ok(())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+//! `#[transactional]` on a sync method — rejected: the body runs inside a
+//! task-bound transaction scope, so it must be an `async fn`.
+
+use r2e::prelude::*;
+
+#[derive(Clone)]
+pub struct Ledger {
+    pool: Pool,
+}
+
+#[derive(Clone)]
+pub struct Pool;
+
+#[bean]
+impl Ledger {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+
+    #[transactional]
+    fn post(&self) -> Result<(), HttpError> {
+        Ok(())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,54 @@
+//! `#[transactional]` with a user-defined `TransactionManager` and a custom
+//! pool field, on bean methods returning `Result` and `()`.
+
+use r2e::prelude::*;
+use r2e::r2e_core::transaction::{Isolation, TransactionManager, TxError, TxOptions};
+
+pub struct Memory;
+
+#[derive(Clone)]
+pub struct MemoryStore;
+
+pub struct MemoryTx {
+    pub isolation: Option<Isolation>,
+}
+
+impl TransactionManager<Memory> for MemoryStore {
+    type Tx = MemoryTx;
+
+    async fn begin(&self, options: TxOptions) -> Result<MemoryTx, TxError> {
+        Ok(MemoryTx {
+            isolation: options.isolation,
+        })
+    }
+
+    async fn commit(&self, _tx: MemoryTx) -> Result<(), TxError> {
+        Ok(())
+    }
+
+    async fn rollback(&self, _tx: MemoryTx) -> Result<(), TxError> {
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct Ledger {
+    store: MemoryStore,
+}
+
+#[bean]
+impl Ledger {
+    pub fn new(store: MemoryStore) -> Self {
+        Self { store }
+    }
+
+    #[transactional(pool = "store", isolation = serializable, read_only)]
+    pub async fn balance(&self, account: &str) -> Result<u64, HttpError> {
+        Ok(account.len() as u64)
+    }
+
+    #[transactional(pool = "store", propagation = requires_new)]
+    pub async fn audit(&self) {}
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+use r2e::prelude::*;
+use r2e::r2e_events::LocalEventBus;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AccountUpdated {
+    pub account_id: u64,
+}
+
+#[controller]
+pub struct AccountProjector {
+    #[inject]
+    event_bus: LocalEventBus,
+}
+
+#[routes]
+impl AccountProjector {
+    // Only `partition_key` and `header:<name>` are ordering keys.
+    #[consumer(bus = "event_bus", ordered_by = "account_id")]
+    async fn on_update(&self, event: Arc<AccountUpdated>) {
+        let _ = event.account_id;
+    }
+}
+
+fn main() {}
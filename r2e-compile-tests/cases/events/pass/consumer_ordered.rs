@@ -0,0 +1,29 @@
+use r2e::prelude::*;
+use r2e::r2e_events::LocalEventBus;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AccountUpdated {
+    pub account_id: u64,
+}
+
+#[controller]
+pub struct AccountProjector {
+    #[inject]
+    event_bus: LocalEventBus,
+}
+
+#[routes]
+impl AccountProjector {
+    #[consumer(bus = "event_bus", ordered_by = "partition_key", retry = 2)]
+    async fn on_update(&self, event: Arc<AccountUpdated>) {
+        let _ = event.account_id;
+    }
+
+    #[consumer(bus = "event_bus", ordered_by = "header:tenant")]
+    async fn on_tenant_update(&self, event: Arc<AccountUpdated>) {
+        let _ = event.account_id;
+    }
+}
+
+fn main() {}
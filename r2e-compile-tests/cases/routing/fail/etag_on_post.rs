@@ -0,0 +1,18 @@
+//! `#[etag]` guards reads and modifications of an existing resource; a
+//! POST has no version to compare against.
+
+use r2e::prelude::*;
+
+#[controller]
+pub struct MyController {}
+
+#[routes]
+impl MyController {
+    #[post("/documents")]
+    #[etag]
+    async fn create(&self) -> StatusCode {
+        StatusCode::CREATED
+    }
+}
+
+fn main() {}
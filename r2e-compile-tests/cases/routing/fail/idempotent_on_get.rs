@@ -0,0 +1,17 @@
+//! `#[idempotent]` protects retried writes; a GET is already safe to retry.
+
+use r2e::prelude::*;
+
+#[controller]
+pub struct MyController {}
+
+#[routes]
+impl MyController {
+    #[get("/payments")]
+    #[idempotent]
+    async fn list(&self) -> StatusCode {
+        StatusCode::OK
+    }
+}
+
+fn main() {}
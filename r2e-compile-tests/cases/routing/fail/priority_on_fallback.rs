@@ -0,0 +1,17 @@
+//! An unmatched request has no route, so a fallback cannot carry a priority.
+
+use r2e::prelude::*;
+
+#[controller]
+pub struct MyController {}
+
+#[routes]
+impl MyController {
+    #[fallback]
+    #[priority(background)]
+    async fn not_found(&self) -> &'static str {
+        "not found"
+    }
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+//! `#[priority]` takes one of `critical`, `normal` or `background`.
+
+use r2e::prelude::*;
+
+#[controller]
+pub struct MyController {}
+
+#[routes]
+impl MyController {
+    #[get("/report")]
+    #[priority(urgent)]
+    async fn report(&self) -> &'static str {
+        "report"
+    }
+}
+
+fn main() {}
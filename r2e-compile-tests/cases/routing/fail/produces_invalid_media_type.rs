@@ -0,0 +1,18 @@
+//! `#[produces]` / `#[consumes]` take concrete `type/subtype` media types —
+//! wildcards belong in the client's `Accept` header.
+
+use r2e::prelude::*;
+
+#[controller]
+pub struct MyController {}
+
+#[routes]
+impl MyController {
+    #[get("/report")]
+    #[produces("application/json", "text/*")]
+    async fn report(&self) -> Negotiated<Vec<u32>> {
+        Negotiated(vec![1, 2, 3])
+    }
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+//! An SSE stream always speaks `text/event-stream`; there is nothing to
+//! negotiate.
+
+use r2e::prelude::*;
+
+#[controller]
+pub struct MyController {}
+
+#[routes]
+impl MyController {
+    #[sse("/events")]
+    #[produces("application/json")]
+    async fn events(&self) {}
+}
+
+fn main() {}
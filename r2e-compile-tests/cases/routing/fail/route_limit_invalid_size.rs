@@ -0,0 +1,17 @@
+//! `#[body_limit]` takes a byte count or a size with a B/KB/MB/GB suffix.
+
+use r2e::prelude::*;
+
+#[controller]
+pub struct MyController {}
+
+#[routes]
+impl MyController {
+    #[post("/upload")]
+    #[body_limit("50TB")]
+    async fn upload(&self, body: r2e::http::Bytes) -> String {
+        body.len().to_string()
+    }
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+//! `#[timeout]`, `#[body_limit]`, and `#[max_concurrent]` bound one
+//! request/response exchange; an SSE stream outlives it.
+
+use r2e::prelude::*;
+
+#[controller]
+pub struct MyController {}
+
+#[routes]
+impl MyController {
+    #[sse("/events")]
+    #[timeout("5s")]
+    async fn events(&self) {}
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+//! `#[since]` narrows the `versions` of the controller, which declares none.
+
+use r2e::prelude::*;
+
+#[controller(path = "/users")]
+pub struct MyController {}
+
+#[routes]
+impl MyController {
+    #[get("/")]
+    #[since(2)]
+    async fn list(&self) -> StatusCode {
+        StatusCode::OK
+    }
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+//! `#[template]` always answers with HTML, so there is nothing to negotiate.
+
+use r2e::prelude::*;
+
+#[derive(serde::Serialize)]
+pub struct Page {
+    title: String,
+}
+
+#[controller]
+pub struct MyController {}
+
+#[routes]
+impl MyController {
+    #[get("/page")]
+    #[template("page.html")]
+    #[produces("application/json")]
+    async fn page(&self) -> Page {
+        Page {
+            title: "home".into(),
+        }
+    }
+}
+
+fn main() {}
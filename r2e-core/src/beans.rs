@@ -553,6 +553,10 @@ pub struct BeanRegistry {
     /// skip hooks for provided values pinned from the previous cycle (their
     /// post-construct already ran on that same instance).
     provided_post_constructs: Vec<(TypeId, PostConstructFn)>,
+    /// Hooks run once the graph is resolved, **before** any post-construct
+    /// (right after the decorator fills). See
+    /// [`register_resolved_hook`](Self::register_resolved_hook).
+    resolved_hooks: Vec<PostConstructFn>,
     /// Pre-destroy disposer builders for provided/plugin beans. Materialized
     /// against the resolved graph at the end of `resolve` and carried on the
     /// [`BeanContext`] for the builder to drain into the shutdown sequence.
@@ -759,6 +763,7 @@ impl BeanRegistry {
             provided: HashMap::new(),
             pinned: HashSet::new(),
             provided_post_constructs: Vec::new(),
+            resolved_hooks: Vec::new(),
             disposers: Vec::new(),
             scheduled_sources: Vec::new(),
             event_subscribers: Vec::new(),
@@ -957,6 +962,26 @@ impl BeanRegistry {
         ));
     }
 
+    /// Register a hook that runs once the graph is resolved, before every
+    /// post-construct hook.
+    ///
+    /// The hook gets the resolved context (pinned overrides included) and
+    /// returns the work to await, so it can clone the beans it needs — e.g. a
+    /// pool to apply database migrations to before a bean's
+    /// `#[post_construct]` queries. Hooks run in registration order; an error
+    /// fails [`resolve`](Self::resolve) with `BeanError::PostConstruct`.
+    pub fn register_resolved_hook<F>(&mut self, hook: F)
+    where
+        F: FnOnce(&BeanContext) -> crate::lifecycle::LifecycleFuture<'static> + Send + 'static,
+    {
+        self.resolved_hooks.push(Box::new(|ctx: BeanContext| {
+            Box::pin(async move {
+                hook(&ctx).await?;
+                Ok(ctx)
+            })
+        }));
+    }
+
     /// Register a bean as a scheduled-task source.
     ///
     /// Called from generated `after_register` when a `#[bean]` impl carries
@@ -1384,6 +1409,7 @@ impl BeanRegistry {
 
         // Lift the lifecycle hooks out before the bean fields are consumed.
         let provided_post_constructs = std::mem::take(&mut self.provided_post_constructs);
+        let resolved_hooks = std::mem::take(&mut self.resolved_hooks);
         let disposer_builders = std::mem::take(&mut self.disposers);
         let deco_fills = std::mem::take(&mut self.deco_fills);
 
@@ -1447,6 +1473,14 @@ impl BeanRegistry {
             fill(&ctx);
         }
 
+        // Resolved-graph hooks (migrations and the like) precede every
+        // post-construct hook.
+        for hook in resolved_hooks {
+            ctx = hook(ctx)
+                .await
+                .map_err(|e| BeanError::PostConstruct(e.to_string()))?;
+        }
+
         // Run factory-bean post-construct hooks in topological order.
         for pc_fn in factory_pc_fns {
             ctx = pc_fn(ctx)
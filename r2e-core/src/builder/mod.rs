@@ -201,6 +201,9 @@ struct BuilderConfig {
     last_plugin_name: Option<&'static str>,
     /// Whether to install the pre-routing trailing-slash normalization rewrite.
     normalize_path: bool,
+    /// Header or media-type API version selection, installed as a
+    /// pre-routing rewrite.
+    api_versioning: Option<crate::versioning::ApiVersioning>,
     /// Whether the DevReload plugin has been applied (prevents double-install).
     dev_reload_applied: bool,
     /// Maximum time allowed for shutdown hooks to complete before force-exiting.
@@ -18,6 +18,7 @@ impl AppBuilder<NoState, TNil, TNil, TNil> {
                 plugin_data: HashMap::new(),
                 last_plugin_name: None,
                 normalize_path: false,
+                api_versioning: None,
                 dev_reload_applied: false,
                 shutdown_grace_period: None,
                 active_profile: "default".to_string(),
@@ -38,6 +38,10 @@ pub struct PreparedApp<T: Clone + Send + Sync + 'static> {
     /// `Ok(Some(n))` → SO_REUSEPORT sharded serving with `n` workers.
     /// `Err(msg)` → invalid config value, surfaced as an error at `run()` time.
     pub(super) workers: Result<Option<usize>, String>,
+    /// Parsed `server.tls` config. `Ok(None)` → plaintext HTTP (default).
+    /// `Err(msg)` → invalid section or unloadable certificates, surfaced as an
+    /// error at `run()` time.
+    pub(super) tls: Result<Option<crate::tls::TlsServer>, String>,
     #[cfg(feature = "quic")]
     pub(super) quic_server_config:
         Option<(std::net::SocketAddr, r2e_http::quic::quinn::ServerConfig)>,
@@ -117,6 +121,18 @@ impl<T: Clone + Send + Sync + 'static> PreparedApp<T> {
         self.workers.as_ref().copied().map_err(|s| s.as_str())
     }
 
+    /// The parsed `server.tls` (native HTTPS) configuration.
+    ///
+    /// `Ok(None)` → plaintext HTTP (default). `Err(msg)` → the section was
+    /// invalid or its certificates could not be loaded; this error is
+    /// returned by [`run()`](Self::run).
+    pub fn tls(&self) -> Result<Option<&crate::tls::TlsServer>, &str> {
+        self.tls
+            .as_ref()
+            .map(Option::as_ref)
+            .map_err(|s| s.as_str())
+    }
+
     /// Start listening and serving requests.
     ///
     /// Registers event consumers, runs startup hooks, binds the TCP listener,
@@ -220,6 +236,10 @@ impl<T: Clone + Send + Sync + 'static> PreparedApp<T> {
         #[cfg(not(feature = "dev-reload"))]
         let skip_lifecycle = false;
 
+        // Resolve `server.tls` before any lifecycle work: requested-but-broken
+        // TLS must fail startup rather than serve plaintext.
+        let tls = std::mem::replace(&mut self.tls, Ok(None))?;
+
         // Cancelled when graceful shutdown begins (after drain hooks). Serve
         // hooks receive it via `ServeContext`; the HTTP/QUIC/sharded serving
         // paths observe it as their graceful-shutdown signal.
@@ -359,6 +379,20 @@ impl<T: Clone + Send + Sync + 'static> PreparedApp<T> {
             service_handles.push(quic_handle);
         }
 
+        // Certificate hot-reload. The watcher stops on graceful shutdown, or
+        // when this future is dropped (a dev-reload hot-patch), via the guard.
+        #[cfg(feature = "tls")]
+        let _tls_watch_guard = tls.as_ref().and_then(|tls| {
+            let interval = tls.reload_interval?;
+            let token = cancel_token.child_token();
+            let watch = tls
+                .config
+                .clone()
+                .watch(interval, token.clone().cancelled_owned());
+            service_handles.push(crate::rt::spawn(watch));
+            Some(token.drop_guard())
+        });
+
         let cancel_for_shutdown = cancel_token.clone();
         let shutdown_future = async move {
             // Cancel-on-drop: the token must fire even if a drain or plugin
@@ -392,6 +426,15 @@ impl<T: Clone + Send + Sync + 'static> PreparedApp<T> {
         // Only this middle section differs between strategies; the lifecycle
         // start above and the shutdown phase below are shared.
         let serve_result: Result<(), Box<dyn std::error::Error>> = match strategy {
+            #[cfg(feature = "tls")]
+            ServeStrategy::Single(listener) if tls.is_some() => {
+                info!(addr = %self.addr, "R2E server listening (TLS)");
+                let listener = tls.as_ref().unwrap().listener(listener, self.tcp_nodelay);
+                crate::http::serve(listener, crate::http::tls::TlsMakeService::new(self.router))
+                    .with_graceful_shutdown(shutdown_future)
+                    .await
+                    .map_err(|e| -> Box<dyn std::error::Error> { Box::new(e) })
+            }
             ServeStrategy::Single(listener) => {
                 info!(addr = %self.addr, "R2E server listening");
                 let svc = self
@@ -451,11 +494,12 @@ impl<T: Clone + Send + Sync + 'static> PreparedApp<T> {
                 // threads, so run it on a blocking task to avoid stalling the
                 // main runtime (which must keep driving the shutdown future).
                 let join = crate::rt::spawn_blocking(move || {
-                    crate::sharded::serve_sharded(
+                    crate::sharded::serve_sharded_with_tls(
                         router,
                         &addrs,
                         workers,
                         tcp_nodelay,
+                        tls,
                         control_plane,
                         cancel_for_workers,
                     )
@@ -476,7 +520,10 @@ impl<T: Clone + Send + Sync + 'static> PreparedApp<T> {
                 unix,
                 not(any(target_os = "solaris", target_os = "illumos", target_os = "cygwin"))
             )))]
-            ServeStrategy::Sharded { .. } => Err(crate::sharded::UNSUPPORTED_PLATFORM_MSG.into()),
+            ServeStrategy::Sharded { .. } => {
+                let _ = tls;
+                Err(crate::sharded::UNSUPPORTED_PLATFORM_MSG.into())
+            }
         };
         serve_result?;
 
@@ -91,6 +91,16 @@ impl<T: Clone + Send + Sync + 'static> AppBuilder<T> {
         self
     }
 
+    /// Select API versions of versioned controllers with a header or a
+    /// vendor media type, through a pre-routing rewrite to the `/v{n}` path.
+    pub(crate) fn set_api_versioning(
+        mut self,
+        versioning: crate::versioning::ApiVersioning,
+    ) -> Self {
+        self.shared.api_versioning = Some(versioning);
+        self
+    }
+
     /// Returns a reference to the loaded [`R2eConfig`], if any.
     ///
     /// This is available after [`load_config()`](AppBuilder::load_config) or
@@ -431,6 +441,15 @@ impl<T: Clone + Send + Sync + 'static> AppBuilder<T> {
             .and_then(|boxed| boxed.downcast_ref::<D>())
     }
 
+    /// Remove and return plugin data by type.
+    pub(crate) fn take_plugin_data<D: Any + Send + Sync + 'static>(&mut self) -> Option<D> {
+        self.shared
+            .plugin_data
+            .remove(&TypeId::of::<D>())
+            .and_then(|boxed| boxed.downcast::<D>().ok())
+            .map(|boxed| *boxed)
+    }
+
     /// Registration backend with all witnesses explicit: the public face is
     /// [`RegisterController::register_controller`](super::RegisterController::register_controller),
     /// which infers `W` (extraction markers) and `DepIdx` (dependency indices).
@@ -494,6 +513,7 @@ impl<T: Clone + Send + Sync + 'static> AppBuilder<T> {
     where
         C: Controller<T, W>,
     {
+        self.meta_registry.set_config(self.shared.config.clone());
         C::register_meta(&mut self.meta_registry);
 
         // Auto-validate config keys and sections declared on this controller
@@ -679,6 +699,19 @@ impl<T: Clone + Send + Sync + 'static> AppBuilder<T> {
         self
     }
 
+    /// Register a metadata consumer that reads the whole [`MetaRegistry`].
+    ///
+    /// Like [`with_meta_consumer`](Self::with_meta_consumer), for consumers
+    /// that combine several metadata types (e.g. AsyncAPI reads both
+    /// `ConsumerInfo` and `EmitterInfo`).
+    pub fn with_meta_registry_consumer<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&MetaRegistry) -> crate::http::Router<T> + Send + 'static,
+    {
+        self.meta_consumers.push(Box::new(f));
+        self
+    }
+
     /// Assemble the final `axum::Router` from all registered routes and layers.
     ///
     /// Startup lifecycle work is NOT run here: consumer registrations AND
@@ -713,7 +746,8 @@ impl<T: Clone + Send + Sync + 'static> AppBuilder<T> {
         built.router
     }
 
-    fn build_inner(self) -> BuiltApp<T> {
+    fn build_inner(mut self) -> BuiltApp<T> {
+        let meta_observers = self.take_plugin_data::<crate::meta::MetaObservers>();
         let state = self.state;
 
         let mut router = crate::http::Router::new();
@@ -729,6 +763,9 @@ impl<T: Clone + Send + Sync + 'static> AppBuilder<T> {
             let consumer_router = consumer(&meta_registry);
             router = router.merge(consumer_router);
         }
+        if let Some(observers) = meta_observers {
+            observers.run(&meta_registry);
+        }
 
         // Apply the application state.
         let mut app = router.with_state(state.clone());
@@ -740,6 +777,13 @@ impl<T: Clone + Send + Sync + 'static> AppBuilder<T> {
             app = layer_fn(app);
         }
 
+        // The API version rewrite (`/users` + `Api-Version: 2` →
+        // `/v2/users`) is pre-routing too, inside the trailing-slash
+        // normalization so it matches the trimmed path.
+        if let Some(versioning) = self.shared.api_versioning {
+            app = versioning.wrap(app, meta_registry.get_or_empty::<crate::meta::RouteInfo>());
+        }
+
         // Install trailing-slash normalization as a genuine pre-routing URI
         // rewrite: `/users/1/` is trimmed to `/users/1` BEFORE routing, so
         // the meaningful routing happens once and `MatchedPath` reaches every
@@ -862,6 +906,10 @@ impl<T: Clone + Send + Sync + 'static> AppBuilder<T> {
         // is carried on `PreparedApp` and surfaced at `run()` time.
         let workers = crate::sharded::parse_workers(this.shared.config.as_ref());
 
+        // Parse `server.tls` the same way: certificate load errors surface at
+        // `run()` time.
+        let tls = crate::tls::parse_tls(this.shared.config.as_ref());
+
         // Stop-handle resolution: explicit `with_stop_handle` wins, then a
         // `StopHandle` bean from the graph (so `.provide(stop.clone())` alone
         // is enough to wire an admin stop endpoint — a provided-but-unwired
@@ -912,6 +960,7 @@ impl<T: Clone + Send + Sync + 'static> AppBuilder<T> {
             shutdown_grace_period,
             tcp_nodelay,
             workers,
+            tls,
             #[cfg(feature = "quic")]
             quic_server_config,
         }
@@ -0,0 +1,500 @@
+//! Conditional requests: `#[etag]`, [`ETag`], [`Versioned`] and
+//! [`Preconditions`].
+//!
+//! ```ignore
+//! #[routes]
+//! #[etag]
+//! impl DocumentController {
+//!     #[get("/documents/{id}")]
+//!     async fn get(&self, Path(id): Path<u64>) -> Result<Tagged<Document>, HttpError> {
+//!         Ok(Tagged(self.repo.find(id).await?))
+//!     }
+//!
+//!     #[put("/documents/{id}")]
+//!     async fn update(
+//!         &self,
+//!         Path(id): Path<u64>,
+//!         pre: Preconditions,
+//!         Json(patch): Json<DocumentPatch>,
+//!     ) -> Result<Tagged<Document>, HttpError> {
+//!         let current = self.repo.find(id).await?;
+//!         pre.check(&current)?; // 412 when the client's copy is stale
+//!         Ok(Tagged(self.repo.update(current, patch).await?))
+//!     }
+//! }
+//! ```
+//!
+//! On `GET` and `HEAD`, the `#[etag]` layer tags a `200` response with the
+//! `ETag` set by the handler ([`Tagged`]), or else with a strong hash of
+//! the body, and answers `304 Not Modified` when `If-None-Match` (or
+//! `If-Modified-Since`) shows the client's copy is current. On `PUT`,
+//! `PATCH` and `DELETE` it answers `428 Precondition Required` unless the
+//! request carries `If-Match` or `If-Unmodified-Since`; the handler compares
+//! them with the current entity through [`Preconditions`]. Other methods
+//! pass through.
+
+use std::convert::Infallible;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::http::body::{to_bytes, Body};
+use crate::http::extract::FromRequestParts;
+use crate::http::header::{
+    HeaderMap, HeaderName, HeaderValue, CACHE_CONTROL, CONTENT_LOCATION, DATE, ETAG, EXPIRES,
+    IF_MATCH, IF_MODIFIED_SINCE, IF_NONE_MATCH, IF_UNMODIFIED_SINCE, LAST_MODIFIED, VARY,
+};
+use crate::http::middleware::{from_fn, Next};
+use crate::http::response::{IntoResponse, Response};
+use crate::http::routing::MethodRouter;
+use crate::http::{Json, Method, Parts, Request, StatusCode};
+use crate::meta::{ErrorResponseInfo, ParamInfo, ParamLocation};
+use crate::HttpError;
+
+/// An entity tag: `"v42"` (strong) or `W/"v42"` (weak).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ETag {
+    weak: bool,
+    tag: String,
+}
+
+impl ETag {
+    /// A strong tag. A value that is not a valid opaque tag (empty, or with
+    /// quotes, spaces or non-ASCII characters) is replaced by its hash.
+    pub fn strong(tag: impl Into<String>) -> Self {
+        let tag = tag.into();
+        if is_opaque_tag(&tag) {
+            Self { weak: false, tag }
+        } else {
+            Self::for_bytes(tag.as_bytes())
+        }
+    }
+
+    /// A weak tag, for representations that are equivalent but not
+    /// byte-identical. Sanitized like [`strong`](Self::strong).
+    pub fn weak(tag: impl Into<String>) -> Self {
+        Self {
+            weak: true,
+            ..Self::strong(tag)
+        }
+    }
+
+    /// The strong tag `#[etag]` computes for a response body: a truncated
+    /// SHA-256 of the bytes.
+    pub fn for_bytes(bytes: &[u8]) -> Self {
+        let digest = Sha256::digest(bytes);
+        Self {
+            weak: false,
+            tag: URL_SAFE_NO_PAD.encode(&digest[..16]),
+        }
+    }
+
+    /// The tag of `value` rendered as `Json<T>` — what `#[etag]` computes
+    /// for a handler returning `Json(value)`.
+    pub fn of_json<T: Serialize + ?Sized>(value: &T) -> Result<Self, serde_json::Error> {
+        Ok(Self::for_bytes(&serde_json::to_vec(value)?))
+    }
+
+    /// Parse a single `"tag"` or `W/"tag"`.
+    pub fn parse(s: &str) -> Option<Self> {
+        match Self::parse_prefix(s.trim())? {
+            (etag, "") => Some(etag),
+            _ => None,
+        }
+    }
+
+    pub fn is_weak(&self) -> bool {
+        self.weak
+    }
+
+    /// The opaque tag, without quotes or `W/`.
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Strong comparison (`If-Match`): both tags strong and identical.
+    pub fn strong_eq(&self, other: &ETag) -> bool {
+        !self.weak && !other.weak && self.tag == other.tag
+    }
+
+    /// Weak comparison (`If-None-Match`): identical tags, weak or not.
+    pub fn weak_eq(&self, other: &ETag) -> bool {
+        self.tag == other.tag
+    }
+
+    /// Parse one tag at the start of `s`, returning the rest.
+    fn parse_prefix(s: &str) -> Option<(Self, &str)> {
+        let (weak, s) = match s.strip_prefix("W/") {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let s = s.strip_prefix('"')?;
+        let end = s.find('"')?;
+        let tag = &s[..end];
+        if !tag.chars().all(is_etagc) {
+            return None;
+        }
+        let etag = Self {
+            weak,
+            tag: tag.to_string(),
+        };
+        Some((etag, &s[end + 1..]))
+    }
+}
+
+impl fmt::Display for ETag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.weak {
+            f.write_str("W/")?;
+        }
+        write!(f, "\"{}\"", self.tag)
+    }
+}
+
+fn is_etagc(c: char) -> bool {
+    c == '!' || ('#'..='~').contains(&c)
+}
+
+fn is_opaque_tag(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(is_etagc)
+}
+
+/// An entity with a version, compared against `If-Match` by
+/// [`Preconditions::check`] and sent as `ETag` by [`Tagged`].
+///
+/// ```ignore
+/// impl Versioned for Document {
+///     fn version(&self) -> String {
+///         self.revision.to_string()
+///     }
+///
+///     fn last_modified(&self) -> Option<SystemTime> {
+///         Some(self.updated_at.into())
+///     }
+/// }
+/// ```
+pub trait Versioned {
+    /// Changes whenever the entity does: a revision counter, an update
+    /// timestamp, a content hash.
+    fn version(&self) -> String;
+
+    /// When the entity last changed, for `Last-Modified` and
+    /// `If-Unmodified-Since`.
+    fn last_modified(&self) -> Option<SystemTime> {
+        None
+    }
+
+    /// The entity tag. Strong, from [`version`](Self::version), by default.
+    fn etag(&self) -> ETag {
+        ETag::strong(self.version())
+    }
+}
+
+/// A [`Versioned`] entity rendered as JSON with its `ETag` and, when known,
+/// `Last-Modified` headers.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Tagged<T>(pub T);
+
+impl<T: Versioned + Serialize> IntoResponse for Tagged<T> {
+    fn into_response(self) -> Response {
+        let mut response = Json(&self.0).into_response();
+        if !response.status().is_success() {
+            return response;
+        }
+        let headers = response.headers_mut();
+        if let Ok(etag) = HeaderValue::from_str(&self.0.etag().to_string()) {
+            headers.insert(ETAG, etag);
+        }
+        if let Some(modified) = self.0.last_modified() {
+            if let Ok(date) = HeaderValue::from_str(&httpdate::fmt_http_date(modified)) {
+                headers.insert(LAST_MODIFIED, date);
+            }
+        }
+        response
+    }
+}
+
+/// `*` or a list of entity tags, from `If-Match` / `If-None-Match`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TagList {
+    Any,
+    Tags(Vec<ETag>),
+}
+
+impl TagList {
+    /// `None` when the header is absent. Malformed tags are skipped.
+    fn from_headers(headers: &HeaderMap, name: HeaderName) -> Option<Self> {
+        let mut values = headers.get_all(name).iter().peekable();
+        values.peek()?;
+        let mut tags = Vec::new();
+        for value in values {
+            let Ok(mut rest) = value.to_str() else {
+                continue;
+            };
+            if rest.trim() == "*" {
+                return Some(TagList::Any);
+            }
+            loop {
+                rest = rest.trim_start_matches(|c: char| c == ',' || c.is_ascii_whitespace());
+                match ETag::parse_prefix(rest) {
+                    Some((etag, tail)) => {
+                        tags.push(etag);
+                        rest = tail;
+                    }
+                    None => break,
+                }
+            }
+        }
+        Some(TagList::Tags(tags))
+    }
+
+    fn matches(&self, current: &ETag, eq: fn(&ETag, &ETag) -> bool) -> bool {
+        match self {
+            TagList::Any => true,
+            TagList::Tags(tags) => tags.iter().any(|tag| eq(tag, current)),
+        }
+    }
+}
+
+fn http_date(headers: &HeaderMap, name: HeaderName) -> Option<SystemTime> {
+    let value = headers.get(name)?.to_str().ok()?;
+    httpdate::parse_http_date(value).ok()
+}
+
+/// HTTP dates have a one-second resolution.
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// The `If-Match` / `If-Unmodified-Since` preconditions of a request, to
+/// check against the current entity before modifying it.
+///
+/// Without either header every check passes; on `#[etag]` routes the
+/// layer has already answered `428` in that case.
+#[derive(Debug, Clone, Default)]
+pub struct Preconditions {
+    if_match: Option<TagList>,
+    if_unmodified_since: Option<SystemTime>,
+}
+
+impl Preconditions {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        Self {
+            if_match: TagList::from_headers(headers, IF_MATCH),
+            if_unmodified_since: http_date(headers, IF_UNMODIFIED_SINCE),
+        }
+    }
+
+    /// Whether the request carries no precondition.
+    pub fn is_empty(&self) -> bool {
+        self.if_match.is_none() && self.if_unmodified_since.is_none()
+    }
+
+    /// `412 Precondition Failed` unless the client's copy of `current` is
+    /// up to date.
+    pub fn check<V: Versioned + ?Sized>(&self, current: &V) -> Result<(), HttpError> {
+        self.check_etag(&current.etag(), current.last_modified())
+    }
+
+    /// [`check`](Self::check) against an explicit tag, e.g.
+    /// `ETag::of_json(&current)` on a route tagged by body hash.
+    ///
+    /// `If-Match` uses the strong comparison; `If-Unmodified-Since` is only
+    /// evaluated without `If-Match` and when `last_modified` is known.
+    pub fn check_etag(
+        &self,
+        current: &ETag,
+        last_modified: Option<SystemTime>,
+    ) -> Result<(), HttpError> {
+        let fresh = match (&self.if_match, self.if_unmodified_since, last_modified) {
+            (Some(if_match), _, _) => if_match.matches(current, ETag::strong_eq),
+            (None, Some(since), Some(modified)) => unix_secs(modified) <= unix_secs(since),
+            _ => true,
+        };
+        if fresh {
+            Ok(())
+        } else {
+            Err(HttpError::from_status(
+                StatusCode::PRECONDITION_FAILED,
+                "Precondition failed: the resource has been modified",
+            ))
+        }
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Preconditions {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_headers(&parts.headers))
+    }
+}
+
+fn is_safe(method: &Method) -> bool {
+    *method == Method::GET || *method == Method::HEAD
+}
+
+fn is_modifying(method: &Method) -> bool {
+    *method == Method::PUT || *method == Method::PATCH || *method == Method::DELETE
+}
+
+/// Documented responses of an `#[etag]` route: `304` on `GET`, `412` and
+/// `428` on `PUT` / `PATCH` / `DELETE`.
+pub fn error_responses(method: &str) -> Vec<ErrorResponseInfo> {
+    match Method::from_bytes(method.as_bytes()) {
+        Ok(m) if is_safe(&m) => vec![ErrorResponseInfo::new(304, "Not modified")],
+        Ok(m) if is_modifying(&m) => vec![
+            ErrorResponseInfo::new(412, "Precondition failed"),
+            ErrorResponseInfo::new(428, "Precondition required"),
+        ],
+        _ => Vec::new(),
+    }
+}
+
+/// Documented conditional headers of an `#[etag]` route.
+pub fn params(method: &str) -> Vec<ParamInfo> {
+    let header = |name: &str, description: &str| ParamInfo {
+        name: name.to_string(),
+        location: ParamLocation::Header,
+        param_type: "string".to_string(),
+        required: false,
+        schema: None,
+        description: Some(description.to_string()),
+        example: None,
+        style: None,
+        explode: None,
+    };
+    match Method::from_bytes(method.as_bytes()) {
+        Ok(m) if is_safe(&m) => vec![header(
+            "If-None-Match",
+            "ETag of a cached copy; answered with 304 while it is current",
+        )],
+        Ok(m) if is_modifying(&m) => vec![header(
+            "If-Match",
+            "ETag of the copy being modified; 412 when stale, 428 when neither \
+             If-Match nor If-Unmodified-Since is sent",
+        )],
+        _ => Vec::new(),
+    }
+}
+
+/// Wrap an `#[etag]` route in the conditional-request layer.
+#[doc(hidden)]
+pub fn __apply<S>(router: MethodRouter<S>) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    router.layer(from_fn(evaluate))
+}
+
+async fn evaluate(req: Request, next: Next) -> Response {
+    let method = req.method().clone();
+    if is_modifying(&method) {
+        if Preconditions::from_headers(req.headers()).is_empty() {
+            return HttpError::from_status(
+                StatusCode::PRECONDITION_REQUIRED,
+                "Precondition required: send If-Match with the ETag of the current representation",
+            )
+            .into_response();
+        }
+        return next.run(req).await;
+    }
+    if !is_safe(&method) {
+        return next.run(req).await;
+    }
+
+    let if_none_match = TagList::from_headers(req.headers(), IF_NONE_MATCH);
+    let if_modified_since = http_date(req.headers(), IF_MODIFIED_SINCE);
+    let response = next.run(req).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let (etag, body) = match parts.headers.get(ETAG) {
+        Some(value) => match value.to_str().ok().and_then(ETag::parse) {
+            Some(etag) => (etag, body),
+            None => return Response::from_parts(parts, body),
+        },
+        None => {
+            let bytes = match to_bytes(body, usize::MAX).await {
+                Ok(bytes) => bytes,
+                Err(error) => {
+                    tracing::error!(error = %error, "failed to buffer response for #[etag]");
+                    return HttpError::internal("Failed to read the response body").into_response();
+                }
+            };
+            let etag = ETag::for_bytes(&bytes);
+            if let Ok(value) = HeaderValue::from_str(&etag.to_string()) {
+                parts.headers.insert(ETAG, value);
+            }
+            (etag, Body::from(bytes))
+        }
+    };
+
+    let not_modified = match (&if_none_match, if_modified_since) {
+        (Some(if_none_match), _) => if_none_match.matches(&etag, ETag::weak_eq),
+        (None, Some(since)) => http_date(&parts.headers, LAST_MODIFIED)
+            .is_some_and(|modified| unix_secs(modified) <= unix_secs(since)),
+        (None, None) => false,
+    };
+    if !not_modified {
+        return Response::from_parts(parts, body);
+    }
+
+    // RFC 9110 §15.4.5: keep the headers a 200 would have carried for caches.
+    let mut response = StatusCode::NOT_MODIFIED.into_response();
+    for name in [
+        ETAG,
+        CACHE_CONTROL,
+        CONTENT_LOCATION,
+        DATE,
+        EXPIRES,
+        LAST_MODIFIED,
+        VARY,
+    ] {
+        for value in parts.headers.get_all(&name) {
+            response.headers_mut().append(name.clone(), value.clone());
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tag_lists() {
+        let mut headers = HeaderMap::new();
+        headers.append(IF_NONE_MATCH, HeaderValue::from_static("W/\"a,1\", \"b\""));
+        headers.append(IF_NONE_MATCH, HeaderValue::from_static("\"c\""));
+        let list = TagList::from_headers(&headers, IF_NONE_MATCH).unwrap();
+        assert_eq!(
+            list,
+            TagList::Tags(vec![
+                ETag::weak("a,1"),
+                ETag::strong("b"),
+                ETag::strong("c")
+            ])
+        );
+        assert!(list.matches(&ETag::strong("a,1"), ETag::weak_eq));
+        assert!(!list.matches(&ETag::strong("a,1"), ETag::strong_eq));
+        assert_eq!(TagList::from_headers(&headers, IF_MATCH), None);
+    }
+
+    #[test]
+    fn sanitizes_versions_that_are_not_opaque_tags() {
+        assert_eq!(ETag::strong("v42").to_string(), "\"v42\"");
+        assert_eq!(ETag::weak("v42").to_string(), "W/\"v42\"");
+        let hashed = ETag::strong("2024-01-01 12:00:00");
+        assert_eq!(hashed, ETag::for_bytes(b"2024-01-01 12:00:00"));
+        assert_eq!(ETag::parse(&hashed.to_string()), Some(hashed));
+    }
+}
@@ -89,7 +89,7 @@ pub const DEFAULT_CONFIG_FILE: &str = "application.yaml";
 /// Derive the profile overlay file for a base config file:
 /// `<stem>-<profile>.<ext>` next to the base file (`patina.yaml` + `test`
 /// → `patina-test.yaml`; an extension-less base gets `-<profile>` appended).
-fn profile_file_for(base: &Path, profile: &str) -> std::path::PathBuf {
+pub(crate) fn profile_file_for(base: &Path, profile: &str) -> std::path::PathBuf {
     let stem = base
         .file_stem()
         .map(|s| s.to_string_lossy())
@@ -2,7 +2,11 @@
 //!
 //! When enabled via `.with(DevReload)`, the server exposes:
 //! - `GET /__r2e_dev/status` — Returns `"dev"` so tooling/scripts can
-//!   detect that the server is running in dev mode.
+//!   detect that the server is running in dev mode. With
+//!   `Accept: application/json` it returns a JSON object that also carries
+//!   the sections plugins contributed through
+//!   [`DeferredContext::add_dev_status`](crate::DeferredContext::add_dev_status)
+//!   (e.g. the applied database migrations).
 //! - `GET /__r2e_dev/ping` — Returns a timestamp; can be polled by a
 //!   browser script to detect when the server has restarted (the PID or
 //!   boot-time changes).
@@ -20,7 +24,8 @@ use crate::http::routing::get;
 use crate::http::Request;
 use crate::http::Response;
 use crate::http::Router;
-use std::sync::OnceLock;
+use crate::http::{header::ACCEPT, HeaderMap};
+use std::sync::{Arc, OnceLock};
 use std::time::SystemTime;
 
 #[cfg(feature = "dev-reload")]
@@ -303,18 +308,66 @@ fn boot_time() -> u64 {
     })
 }
 
+/// A section of the JSON dev status, computed on each request.
+type DevStatusFn = Arc<dyn Fn() -> serde_json::Value + Send + Sync>;
+
+/// Dev-status sections contributed by plugins through
+/// [`DeferredContext::add_dev_status`](crate::DeferredContext::add_dev_status),
+/// stored in plugin data until the [`DevReload`](crate::plugins::DevReload)
+/// plugin drains them.
+#[doc(hidden)]
+#[derive(Default)]
+pub struct DevStatusContributions {
+    sections: Vec<(String, DevStatusFn)>,
+}
+
+impl DevStatusContributions {
+    pub(crate) fn push(
+        &mut self,
+        name: String,
+        section: impl Fn() -> serde_json::Value + Send + Sync + 'static,
+    ) {
+        self.sections.push((name, Arc::new(section)));
+    }
+}
+
 /// Create a router with dev-mode endpoints.
 ///
 /// Intended to be merged into the main application via the
 /// [`DevReload`](crate::plugins::DevReload) plugin.
 pub fn dev_routes<T: Clone + Send + Sync + 'static>() -> Router<T> {
+    dev_routes_with(DevStatusContributions::default())
+}
+
+pub(crate) fn dev_routes_with<T: Clone + Send + Sync + 'static>(
+    contributions: DevStatusContributions,
+) -> Router<T> {
+    let sections = Arc::new(contributions.sections);
     Router::new()
-        .route("/__r2e_dev/status", get(status_handler))
+        .route(
+            "/__r2e_dev/status",
+            get(move |headers: HeaderMap| status_handler(headers, sections.clone())),
+        )
         .route("/__r2e_dev/ping", get(ping_handler))
 }
 
-async fn status_handler() -> impl IntoResponse {
-    "dev"
+async fn status_handler(headers: HeaderMap, sections: Arc<Vec<(String, DevStatusFn)>>) -> Response {
+    let wants_json = headers
+        .get(ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|accept| accept.contains("application/json"));
+    if !wants_json {
+        return "dev".into_response();
+    }
+    let mut body = serde_json::Map::new();
+    body.insert("status".into(), "dev".into());
+    body.insert("boot_time".into(), boot_time().into());
+    let contributed = sections
+        .iter()
+        .map(|(name, section)| (name.clone(), section()))
+        .collect::<serde_json::Map<_, _>>();
+    body.insert("sections".into(), contributed.into());
+    crate::http::Json(serde_json::Value::Object(body)).into_response()
 }
 
 async fn ping_handler() -> impl IntoResponse {
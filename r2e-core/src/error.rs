@@ -3,6 +3,8 @@ use std::sync::Arc;
 
 use crate::http::response::{IntoResponse, Response};
 use crate::http::{Json, StatusCode};
+use crate::meta::ErrorResponseInfo;
+use crate::problem::Problem;
 
 // ── Efficient error body serialization ────────────────────────────────
 
@@ -12,12 +14,16 @@ struct ErrorBody<'a> {
 }
 
 /// Helper to create a JSON error response with a standard `{ "error": message }` body.
+///
+/// The response carries a [`Problem`] with `message` as its detail, rendered
+/// instead of the body when the [`ProblemDetails`](crate::problem::ProblemDetails)
+/// plugin is installed.
 pub fn error_response(status: StatusCode, message: impl Into<String>) -> Response {
     let message = message.into();
     let body = ErrorBody { error: &message };
     let bytes = serde_json::to_vec(&body)
         .unwrap_or_else(|_| br#"{"error":"internal serialization error"}"#.to_vec());
-    (
+    let response = (
         status,
         [(
             crate::http::header::CONTENT_TYPE,
@@ -25,9 +31,41 @@ pub fn error_response(status: StatusCode, message: impl Into<String>) -> Respons
         )],
         bytes,
     )
-        .into_response()
+        .into_response();
+    Problem::new(status).with_detail(message).attach(response)
+}
+
+// ── ErrorResponses: OpenAPI metadata for #[derive(ApiError)] ──────────
+
+/// The error responses a type can render, for OpenAPI spec generation.
+/// Auto-implemented by `#[derive(ApiError)]`: one entry per variant, and the
+/// inner type's entries for `#[error(transparent)]` variants.
+pub trait ErrorResponses {
+    fn error_responses() -> Vec<ErrorResponseInfo>;
+}
+
+// Autoref specialization: generated code calls `error_responses()` on any
+// handler error type (or transparent variant field) and gets an empty vec
+// for types without the trait.
+
+#[doc(hidden)]
+pub struct __ErrorResponsesProbe<T>(pub core::marker::PhantomData<T>);
+
+impl<T: ErrorResponses> __ErrorResponsesProbe<T> {
+    pub fn error_responses(&self) -> Vec<ErrorResponseInfo> {
+        T::error_responses()
+    }
+}
+
+#[doc(hidden)]
+pub trait __NoErrorResponses {
+    fn error_responses(&self) -> Vec<ErrorResponseInfo> {
+        Vec::new()
+    }
 }
 
+impl<T> __NoErrorResponses for &__ErrorResponsesProbe<T> {}
+
 // ── HttpError ─────────────────────────────────────────────────────────
 
 #[non_exhaustive]
@@ -188,13 +226,12 @@ impl IntoResponse for HttpError {
     fn into_response(self) -> Response {
         match self {
             HttpError::Validation(resp) => {
-                let body = serde_json::json!({
-                    "error": "Validation failed",
-                    "details": resp.errors,
-                });
-                (StatusCode::BAD_REQUEST, Json(body)).into_response()
+                crate::validation::validation_error_response(StatusCode::BAD_REQUEST, &resp)
+            }
+            HttpError::Custom { status, body } => {
+                let problem = custom_problem(status, &body);
+                problem.attach((status, Json(body)).into_response())
             }
-            HttpError::Custom { status, body } => (status, Json(body)).into_response(),
             HttpError::WithSource {
                 status, message, ..
             } => error_response(status, message),
@@ -213,6 +250,31 @@ impl IntoResponse for HttpError {
     }
 }
 
+/// The problem for a `Custom` body: `error` (or `message`) becomes the
+/// detail, the other members extension members.
+fn custom_problem(status: StatusCode, body: &serde_json::Value) -> Problem {
+    let mut problem = Problem::new(status);
+    match body {
+        serde_json::Value::Object(members) => {
+            for (key, value) in members {
+                match (key.as_str(), value) {
+                    ("error" | "message", serde_json::Value::String(detail))
+                        if problem.detail.is_none() =>
+                    {
+                        problem.detail = Some(detail.clone());
+                    }
+                    _ => {
+                        problem.extensions.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        serde_json::Value::String(detail) => problem.detail = Some(detail.clone()),
+        _ => {}
+    }
+    problem
+}
+
 // ── Display ───────────────────────────────────────────────────────────
 
 impl std::fmt::Display for HttpError {
@@ -164,6 +164,25 @@ impl Default for HealthBuilder {
     }
 }
 
+/// Health indicators contributed by plugins through
+/// [`DeferredContext::add_health_check`](crate::DeferredContext::add_health_check),
+/// stored in plugin data until the advanced health plugin drains them.
+#[doc(hidden)]
+#[derive(Default)]
+pub struct HealthContributions {
+    checks: Vec<Box<dyn HealthIndicatorErased>>,
+}
+
+impl HealthContributions {
+    pub(crate) fn push<H: HealthIndicator>(&mut self, indicator: H) {
+        self.checks.push(Box::new(indicator));
+    }
+
+    pub(crate) fn into_checks(self) -> Vec<Box<dyn HealthIndicatorErased>> {
+        self.checks
+    }
+}
+
 /// Shared state for health check handlers.
 #[doc(hidden)]
 pub struct HealthState {
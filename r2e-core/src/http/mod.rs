@@ -6,6 +6,8 @@ pub mod ws;
 pub use r2e_http::multipart;
 #[cfg(feature = "quic")]
 pub use r2e_http::quic;
+#[cfg(feature = "tls")]
+pub use r2e_http::tls;
 
 pub use r2e_http::{
     serve, Body, Bytes, ConnectInfo, DefaultBodyLimit, Error, Extension, Form, FromRef,
@@ -0,0 +1,102 @@
+use std::fmt;
+
+/// A BCP 47 language tag such as `fr` or `fr-CA`, normalized to a lowercase
+/// language and an uppercase region.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Locale(String);
+
+impl Locale {
+    /// Parse `tag` (`fr`, `fr-ca`, `fr_CA`). Returns `None` for anything that
+    /// is not an alphanumeric tag, including the `*` wildcard.
+    pub fn parse(tag: &str) -> Option<Self> {
+        let mut normalized = String::with_capacity(tag.len());
+        for (i, subtag) in tag.trim().split(['-', '_']).enumerate() {
+            if subtag.is_empty()
+                || subtag.len() > 8
+                || !subtag.bytes().all(|b| b.is_ascii_alphanumeric())
+            {
+                return None;
+            }
+            if i == 0 {
+                normalized.push_str(&subtag.to_ascii_lowercase());
+            } else {
+                normalized.push('-');
+                if subtag.len() == 2 {
+                    normalized.push_str(&subtag.to_ascii_uppercase());
+                } else {
+                    normalized.push_str(&subtag.to_ascii_lowercase());
+                }
+            }
+        }
+        Some(Self(normalized))
+    }
+
+    /// The tag, e.g. `fr-CA`.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The language subtag, e.g. `fr` for `fr-CA`.
+    pub fn language(&self) -> &str {
+        self.0.split('-').next().unwrap_or(&self.0)
+    }
+
+    /// The tags to look a message up in, most specific first: `fr-CA`, `fr`.
+    pub(crate) fn fallbacks(&self) -> impl Iterator<Item = &str> {
+        let tag = self.as_str();
+        std::iter::successors(Some(tag), |tag| {
+            tag.rsplit_once('-').map(|(parent, _)| parent)
+        })
+    }
+
+    /// The best of `supported` for this locale: the same tag, then a locale
+    /// of the same language. Any locale is accepted when `supported` is empty.
+    pub(crate) fn negotiate(&self, supported: &[Locale]) -> Option<Locale> {
+        if supported.is_empty() {
+            return Some(self.clone());
+        }
+        self.fallbacks()
+            .find_map(|tag| supported.iter().find(|s| s.as_str() == tag))
+            .or_else(|| supported.iter().find(|s| s.language() == self.language()))
+            .cloned()
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for Locale {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// The languages of an `Accept-Language` header, by decreasing preference.
+/// Entries with `q=0`, wildcards and malformed tags are skipped.
+pub(crate) fn accept_language(header: &str) -> Vec<Locale> {
+    let mut ranked: Vec<(u16, usize, Locale)> = header
+        .split(',')
+        .enumerate()
+        .filter_map(|(position, entry)| {
+            let mut params = entry.split(';');
+            let locale = Locale::parse(params.next()?)?;
+            let quality = params
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map_or(Some(1000), parse_quality)?;
+            (quality > 0).then_some((quality, position, locale))
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    ranked.into_iter().map(|(_, _, locale)| locale).collect()
+}
+
+/// A `q` value in thousandths.
+fn parse_quality(q: &str) -> Option<u16> {
+    let q: f32 = q.trim().parse().ok()?;
+    (0.0..=1.0)
+        .contains(&q)
+        .then(|| (q * 1000.0).round() as u16)
+}
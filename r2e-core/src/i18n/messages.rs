@@ -0,0 +1,426 @@
+use std::any::TypeId;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::{Display, Write as _};
+use std::path::Path;
+use std::sync::Arc;
+
+use super::Locale;
+use crate::beans::{Bean, BeanContext, BeanRegistry, Registrable};
+use crate::config::{profile_file_for, ConfigError, R2eConfig};
+use crate::type_list::{TCons, TNil};
+
+/// Base name of the bundle files: `messages.properties`,
+/// `messages_fr.properties`, `messages_fr_CA.properties`.
+const BUNDLE_NAME: &str = "messages";
+
+/// Translated messages, by locale and key.
+///
+/// Loaded from `.properties` bundles in the `i18n.dir` directory (`i18n` by
+/// default), next to `application.yaml`:
+///
+/// ```text
+/// i18n/
+///   messages.properties         # fallback for every locale
+///   messages_fr.properties
+///   messages_fr_CA.properties   # overrides messages_fr for fr-CA
+///   messages_fr-dev.properties  # profile overlay, when `dev` is active
+/// ```
+///
+/// A lookup tries the most specific bundle first (`fr-CA`, then `fr`, then
+/// `messages.properties`). Messages use `{name}` placeholders:
+///
+/// ```text
+/// user.not_found = Utilisateur {id} introuvable
+/// ```
+///
+/// Register it with `.register::<Messages>()` after `load_config`, or build
+/// one in code with [`new`](Self::new) and [`with_bundle`](Self::with_bundle)
+/// and `.provide()` it.
+#[derive(Clone)]
+pub struct Messages {
+    inner: Arc<Inner>,
+}
+
+#[derive(Clone)]
+struct Inner {
+    default_locale: Locale,
+    /// Bundles by tag; `""` is the fallback bundle.
+    bundles: HashMap<String, HashMap<String, String>>,
+}
+
+impl Messages {
+    /// Empty messages with `default_locale` as the locale of requests that
+    /// name no supported one.
+    ///
+    /// # Panics
+    ///
+    /// If `default_locale` is not a language tag.
+    pub fn new(default_locale: &str) -> Self {
+        let default_locale = Locale::parse(default_locale)
+            .unwrap_or_else(|| panic!("invalid default locale `{default_locale}`"));
+        Self {
+            inner: Arc::new(Inner {
+                default_locale,
+                bundles: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Add the messages of `properties` to the bundle of `locale` (`""` for
+    /// the fallback bundle), overriding keys it already has.
+    ///
+    /// # Panics
+    ///
+    /// If `locale` is neither empty nor a language tag.
+    pub fn with_bundle(mut self, locale: &str, properties: &str) -> Self {
+        let tag = if locale.is_empty() {
+            String::new()
+        } else {
+            Locale::parse(locale)
+                .unwrap_or_else(|| panic!("invalid locale `{locale}`"))
+                .as_str()
+                .to_string()
+        };
+        let inner = Arc::make_mut(&mut self.inner);
+        inner
+            .bundles
+            .entry(tag)
+            .or_default()
+            .extend(parse_properties(properties));
+        self
+    }
+
+    /// Load the bundles named by `config`: the `i18n.dir` directory (default
+    /// `i18n`), the `i18n.default-locale` (default `en`), and the overlays of
+    /// the active `r2e.profile`. A missing directory gives empty messages.
+    pub fn load(config: &R2eConfig) -> Result<Self, ConfigError> {
+        let dir: String = config.get_or("i18n.dir", "i18n".to_string());
+        let default_locale: String = config.get_or("i18n.default-locale", "en".to_string());
+        let profile: Option<String> = config.try_get("r2e.profile");
+        Self::load_from(dir, &default_locale, profile.as_deref())
+    }
+
+    /// Load the bundles of `dir`, overlaying the `-{profile}` files of
+    /// `profile` (`messages_fr-dev.properties` over `messages_fr.properties`).
+    pub fn load_from(
+        dir: impl AsRef<Path>,
+        default_locale: &str,
+        profile: Option<&str>,
+    ) -> Result<Self, ConfigError> {
+        let dir = dir.as_ref();
+        let mut messages = Self::new(default_locale);
+        if !dir.is_dir() {
+            return Ok(messages);
+        }
+        let read = |path: &Path| {
+            std::fs::read_to_string(path)
+                .map_err(|e| ConfigError::Load(format!("{}: {e}", path.display())))
+        };
+        let entries = std::fs::read_dir(dir)
+            .map_err(|e| ConfigError::Load(format!("{}: {e}", dir.display())))?;
+        for entry in entries {
+            let path = entry
+                .map_err(|e| ConfigError::Load(format!("{}: {e}", dir.display())))?
+                .path();
+            let Some(locale) = bundle_locale(&path) else {
+                continue;
+            };
+            messages = messages.with_bundle(&locale, &read(&path)?);
+            if let Some(profile) = profile.filter(|p| *p != "default") {
+                let overlay = profile_file_for(&path, profile);
+                if overlay.is_file() {
+                    messages = messages.with_bundle(&locale, &read(&overlay)?);
+                }
+            }
+        }
+        Ok(messages)
+    }
+
+    /// The locale of requests that name no supported one.
+    pub fn default_locale(&self) -> &Locale {
+        &self.inner.default_locale
+    }
+
+    /// The locales with a bundle, and the default locale.
+    pub fn locales(&self) -> Vec<Locale> {
+        let mut locales: Vec<Locale> = self
+            .inner
+            .bundles
+            .keys()
+            .filter_map(|tag| Locale::parse(tag))
+            .collect();
+        if !locales.contains(&self.inner.default_locale) {
+            locales.push(self.inner.default_locale.clone());
+        }
+        locales.sort();
+        locales
+    }
+
+    /// The raw message of `key` for `locale`, before interpolation.
+    pub fn get(&self, locale: &Locale, key: &str) -> Option<&str> {
+        locale
+            .fallbacks()
+            .chain([""])
+            .find_map(|tag| self.inner.bundles.get(tag)?.get(key))
+            .map(String::as_str)
+    }
+
+    /// Whether some bundle has `key`.
+    pub fn contains(&self, key: &str) -> bool {
+        self.inner
+            .bundles
+            .values()
+            .any(|bundle| bundle.contains_key(key))
+    }
+
+    /// The message of `key` for `locale` with its `{name}` placeholders
+    /// replaced by `args`, or `key` itself when no bundle has it.
+    ///
+    /// ```ignore
+    /// messages.format(&locale, "user.greeting", &[("name", &user.name)])
+    /// ```
+    pub fn format(&self, locale: &Locale, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        self.try_format(locale, key, args)
+            .unwrap_or_else(|| key.to_string())
+    }
+
+    /// [`format`](Self::format), or `None` when no bundle has `key`.
+    pub fn try_format(
+        &self,
+        locale: &Locale,
+        key: &str,
+        args: &[(&str, &dyn Display)],
+    ) -> Option<String> {
+        let message = self.get(locale, key)?;
+        Some(interpolate(message, |name| {
+            args.iter()
+                .find(|(arg, _)| *arg == name)
+                .map(|(_, value)| value.to_string())
+        }))
+    }
+
+    /// [`try_format`](Self::try_format) with owned arguments.
+    pub(crate) fn try_format_owned(
+        &self,
+        locale: &Locale,
+        key: &str,
+        args: &[(Cow<'static, str>, String)],
+    ) -> Option<String> {
+        let message = self.get(locale, key)?;
+        Some(interpolate(message, |name| {
+            args.iter()
+                .find(|(arg, _)| arg == name)
+                .map(|(_, value)| value.clone())
+        }))
+    }
+}
+
+impl std::fmt::Debug for Messages {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Messages")
+            .field("default_locale", &self.inner.default_locale)
+            .field("locales", &self.locales())
+            .finish()
+    }
+}
+
+impl Bean for Messages {
+    type Deps = TCons<R2eConfig, TNil>;
+
+    fn dependencies() -> Vec<(TypeId, &'static str)> {
+        vec![(TypeId::of::<R2eConfig>(), "R2eConfig")]
+    }
+
+    fn config_keys() -> Vec<(&'static str, &'static str, bool)> {
+        vec![
+            ("i18n.dir", "String", false),
+            ("i18n.default-locale", "String", false),
+        ]
+    }
+
+    fn build(ctx: &BeanContext) -> Self {
+        Messages::load(&ctx.get::<R2eConfig>())
+            .unwrap_or_else(|e| panic!("failed to load the i18n bundles: {e}"))
+    }
+}
+
+impl Registrable for Messages {
+    type Provided = Self;
+    type Deps = TCons<R2eConfig, TNil>;
+
+    fn register_into(registry: &mut BeanRegistry) {
+        registry.register::<Self>();
+    }
+}
+
+/// The locale of a bundle file: `""` for `messages.properties`, `fr-CA` for
+/// `messages_fr_CA.properties`. Profile overlays and other files are `None`.
+fn bundle_locale(path: &Path) -> Option<String> {
+    if path.extension()? != "properties" {
+        return None;
+    }
+    let stem = path.file_stem()?.to_str()?;
+    if stem == BUNDLE_NAME {
+        return Some(String::new());
+    }
+    let tag = stem.strip_prefix(BUNDLE_NAME)?.strip_prefix('_')?;
+    if tag.contains('-') {
+        return None;
+    }
+    Locale::parse(tag).map(|locale| locale.as_str().to_string())
+}
+
+/// Parse `.properties` content: `key = value` or `key: value` lines, `#`
+/// and `!` comments, `\` line continuations and `\n`, `\t`, `\uXXXX`
+/// escapes. Files are read as UTF-8.
+fn parse_properties(content: &str) -> impl Iterator<Item = (String, String)> + '_ {
+    let mut lines = content.lines();
+    std::iter::from_fn(move || loop {
+        let first = lines.next()?.trim_start();
+        if first.is_empty() || first.starts_with('#') || first.starts_with('!') {
+            continue;
+        }
+        let mut logical = String::from(first);
+        while ends_with_continuation(&logical) {
+            logical.pop();
+            match lines.next() {
+                Some(next) => logical.push_str(next.trim_start()),
+                None => break,
+            }
+        }
+        let split = find_separator(&logical);
+        let (key, value) = match split {
+            Some(at) => (&logical[..at], logical[at + 1..].trim_start()),
+            None => (logical.as_str(), ""),
+        };
+        return Some((unescape(key.trim_end()), unescape(value)));
+    })
+}
+
+/// Whether `line` ends with an odd number of backslashes.
+fn ends_with_continuation(line: &str) -> bool {
+    line.bytes().rev().take_while(|b| *b == b'\\').count() % 2 == 1
+}
+
+/// The byte offset of the first unescaped `=` or `:`.
+fn find_separator(line: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '=' | ':' => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn unescape(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    Some(decoded) => out.push(decoded),
+                    None => {
+                        out.push_str("\\u");
+                        out.push_str(&hex);
+                    }
+                }
+            }
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+/// Replace the `{name}` placeholders of `message` with `arg(name)`; unknown
+/// placeholders are kept, `{{` and `}}` are literal braces.
+fn interpolate(message: &str, arg: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(message.len());
+    let mut rest = message;
+    while let Some(at) = rest.find(['{', '}']) {
+        out.push_str(&rest[..at]);
+        let tail = &rest[at..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            out.push_str(&tail[..1]);
+            rest = &tail[2..];
+        } else if let Some(end) = tail.starts_with('{').then(|| tail.find('}')).flatten() {
+            let name = &tail[1..end];
+            match arg(name) {
+                Some(value) => out.push_str(&value),
+                None => {
+                    let _ = write!(out, "{{{name}}}");
+                }
+            }
+            rest = &tail[end + 1..];
+        } else {
+            out.push_str(&tail[..1]);
+            rest = &tail[1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_properties() {
+        let parsed: Vec<_> = parse_properties(
+            "# comment\n! also\n\nplain = value\ncolon: x = y\nescaped\\=key = a\\tb\nlong = one \\\n    two\nunicode = caf\\u00e9\nempty\n",
+        )
+        .collect();
+        assert_eq!(
+            parsed,
+            [
+                ("plain".into(), "value".into()),
+                ("colon".into(), "x = y".into()),
+                ("escaped=key".into(), "a\tb".into()),
+                ("long".into(), "one two".into()),
+                ("unicode".into(), "café".into()),
+                ("empty".into(), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn interpolates_named_placeholders() {
+        let arg = |name: &str| (name == "n").then(|| "3".to_string());
+        assert_eq!(interpolate("{n} items", arg), "3 items");
+        assert_eq!(interpolate("{missing} {{n}}", arg), "{missing} {n}");
+        assert_eq!(interpolate("open { brace", arg), "open { brace");
+    }
+
+    #[test]
+    fn bundle_file_names() {
+        assert_eq!(
+            bundle_locale(Path::new("i18n/messages.properties")),
+            Some(String::new())
+        );
+        assert_eq!(
+            bundle_locale(Path::new("i18n/messages_fr_ca.properties")),
+            Some("fr-CA".into())
+        );
+        assert_eq!(
+            bundle_locale(Path::new("i18n/messages_fr-dev.properties")),
+            None
+        );
+        assert_eq!(bundle_locale(Path::new("i18n/other_fr.properties")), None);
+        assert_eq!(bundle_locale(Path::new("i18n/messages_fr.yaml")), None);
+    }
+}
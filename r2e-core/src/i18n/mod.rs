@@ -0,0 +1,432 @@
+//! Internationalization: the request [`Locale`], [`Messages`] bundles, and
+//! translated error responses.
+//!
+//! ```ignore
+//! AppBuilder::new()
+//!     .load_config::<()>()
+//!     .register::<Messages>()          // i18n/messages*.properties
+//!     .build_state()
+//!     .await
+//!     .with(I18n::new())
+//!     .register_controller::<UserController>()
+//!
+//! #[routes]
+//! impl UserController {
+//!     #[get("/hello")]
+//!     async fn hello(&self, locale: Locale) -> String {
+//!         self.messages.format(&locale, "greeting", &[("name", &"Ada")])
+//!     }
+//! }
+//! ```
+//!
+//! The [`I18n`] plugin picks the locale of each request from, in order:
+//!
+//! 1. the `lang` query parameter;
+//! 2. the `lang` cookie;
+//! 3. the `locale` claim of the authenticated identity;
+//! 4. the `Accept-Language` header;
+//! 5. the default locale of the [`Messages`] bean.
+//!
+//! Only supported locales are picked — the locales of the bundles, or the
+//! ones given to [`I18n::supported`]. A request for `fr-CA` gets `fr` when
+//! only `fr` is supported.
+//!
+//! With a [`Messages`] bean, the plugin also translates error responses:
+//!
+//! | Response | Message key |
+//! |----------|-------------|
+//! | `#[derive(ApiError)]` variant with `code = "..."` | the code, with the fields of the message as `{name}` arguments |
+//! | Validation failure | `validation.failed`, and `validation.{code}` for each field error (`{field}`, `{min}`, `{max}`, ...) |
+//! | Any other error, e.g. `HttpError::not_found("user.not_found")` | the message itself, when it is a key of the bundles |
+//!
+//! A response with no translation is left as it is.
+
+mod locale;
+mod messages;
+
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::sync::{Arc, OnceLock};
+
+use serde_json::Value;
+
+use crate::builder::AppBuilder;
+use crate::http::extract::{FromRequestParts, OptionalFromRequestParts};
+use crate::http::header::{HeaderValue, ACCEPT_LANGUAGE, CONTENT_LANGUAGE, CONTENT_LENGTH};
+use crate::http::middleware::{from_fn, Next};
+use crate::http::response::Response;
+use crate::http::{Body, Parts, Request};
+use crate::params::cookie_value;
+use crate::plugin::Plugin;
+use crate::problem::Problem;
+use crate::validation::FieldErrorArgs;
+use crate::HttpError;
+
+pub use self::locale::Locale;
+pub use self::messages::Messages;
+
+/// The message key of an error response and its arguments, attached as a
+/// response extension. `#[derive(ApiError)]` attaches one for variants with
+/// `code = "..."`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageKey {
+    pub key: Cow<'static, str>,
+    pub args: Vec<(Cow<'static, str>, String)>,
+}
+
+impl MessageKey {
+    pub fn new(key: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            key: key.into(),
+            args: Vec::new(),
+        }
+    }
+
+    /// Add a `{name}` argument.
+    pub fn arg(mut self, name: impl Into<Cow<'static, str>>, value: impl Display) -> Self {
+        self.args.push((name.into(), value.to_string()));
+        self
+    }
+
+    /// Attach this key to `response`, replacing any previous one.
+    pub fn attach(self, mut response: Response) -> Response {
+        response.extensions_mut().insert(self);
+        response
+    }
+}
+
+// ── Locale resolution ──────────────────────────────────────────────────
+
+/// How the [`I18n`] plugin resolves locales.
+struct Settings {
+    messages: Option<Messages>,
+    default_locale: Locale,
+    supported: Vec<Locale>,
+    query_param: String,
+    cookie: String,
+    claim: String,
+}
+
+/// The locale sources of the running request.
+struct LocaleScope {
+    settings: Arc<Settings>,
+    /// From the query parameter or the cookie.
+    requested: Option<Locale>,
+    /// From the identity claim, recorded once the caller is authenticated.
+    claimed: OnceLock<Locale>,
+    /// From `Accept-Language`.
+    accepted: Option<Locale>,
+}
+
+impl LocaleScope {
+    fn new(settings: Arc<Settings>, req: &Request) -> Self {
+        let supported = &settings.supported;
+        let pick = |tag: &str| Locale::parse(tag)?.negotiate(supported);
+        let from_query = (!settings.query_param.is_empty())
+            .then(|| req.uri().query())
+            .flatten()
+            .and_then(|query| {
+                form_urlencoded::parse(query.as_bytes())
+                    .find(|(name, _)| name == settings.query_param.as_str())
+                    .and_then(|(_, tag)| pick(&tag))
+            });
+        let requested = from_query.or_else(|| {
+            (!settings.cookie.is_empty())
+                .then(|| cookie_value(req.headers(), &settings.cookie))
+                .flatten()
+                .and_then(pick)
+        });
+        let accepted = req
+            .headers()
+            .get(ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|header| {
+                locale::accept_language(header)
+                    .iter()
+                    .find_map(|locale| locale.negotiate(supported))
+            });
+        Self {
+            settings,
+            requested,
+            claimed: OnceLock::new(),
+            accepted,
+        }
+    }
+
+    fn resolve(&self) -> Locale {
+        self.requested
+            .as_ref()
+            .or(self.claimed.get())
+            .or(self.accepted.as_ref())
+            .unwrap_or(&self.settings.default_locale)
+            .clone()
+    }
+}
+
+tokio::task_local! {
+    static SCOPE: Arc<LocaleScope>;
+}
+
+impl Locale {
+    /// The locale of the running request, when the [`I18n`] plugin is
+    /// installed.
+    pub fn current() -> Option<Locale> {
+        SCOPE.try_with(|scope| scope.resolve()).ok()
+    }
+}
+
+/// Record the claims of the authenticated caller: their locale claim, when
+/// supported, becomes the request's locale unless the query or the cookie
+/// chose one. Called by identity extractors; a no-op without the [`I18n`]
+/// plugin.
+pub fn record_claims(claims: &Value) {
+    let _ = SCOPE.try_with(|scope| {
+        let settings = &scope.settings;
+        if settings.claim.is_empty() {
+            return;
+        }
+        let locale = claims
+            .get(&settings.claim)
+            .and_then(Value::as_str)
+            .and_then(Locale::parse)
+            .and_then(|locale| locale.negotiate(&settings.supported));
+        if let Some(locale) = locale {
+            let _ = scope.claimed.set(locale);
+        }
+    });
+}
+
+/// The locale of the request, resolved by the [`I18n`] plugin. Without the
+/// plugin, `Locale` fails with a 500 and `Option<Locale>` is `None`.
+impl<S: Send + Sync> FromRequestParts<S> for Locale {
+    type Rejection = HttpError;
+
+    async fn from_request_parts(_parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Locale::current().ok_or_else(|| HttpError::internal("Locale requires the I18n plugin"))
+    }
+}
+
+impl<S: Send + Sync> OptionalFromRequestParts<S> for Locale {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(
+        _parts: &mut Parts,
+        _state: &S,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        Ok(Locale::current())
+    }
+}
+
+// ── Plugin ─────────────────────────────────────────────────────────────
+
+/// Plugin that resolves the [`Locale`] of each request and translates error
+/// responses with the [`Messages`] bean. See the [module docs](self).
+///
+/// ```ignore
+/// .with(I18n::new().cookie("locale").claim("preferred_language"))
+/// ```
+pub struct I18n {
+    default_locale: Option<String>,
+    supported: Option<Vec<String>>,
+    query_param: String,
+    cookie: String,
+    claim: String,
+}
+
+impl I18n {
+    /// Resolve from the `lang` query parameter, the `lang` cookie, the
+    /// `locale` claim and `Accept-Language`.
+    pub fn new() -> Self {
+        Self {
+            default_locale: None,
+            supported: None,
+            query_param: "lang".into(),
+            cookie: "lang".into(),
+            claim: "locale".into(),
+        }
+    }
+
+    /// The query parameter naming the locale; `""` turns it off.
+    pub fn query_param(mut self, name: impl Into<String>) -> Self {
+        self.query_param = name.into();
+        self
+    }
+
+    /// The cookie naming the locale; `""` turns it off.
+    pub fn cookie(mut self, name: impl Into<String>) -> Self {
+        self.cookie = name.into();
+        self
+    }
+
+    /// The identity claim naming the locale; `""` turns it off.
+    pub fn claim(mut self, name: impl Into<String>) -> Self {
+        self.claim = name.into();
+        self
+    }
+
+    /// The locale when the request names no supported one (default: the
+    /// default locale of the [`Messages`] bean, or `en`).
+    pub fn default_locale(mut self, tag: impl Into<String>) -> Self {
+        self.default_locale = Some(tag.into());
+        self
+    }
+
+    /// The locales requests can pick (default: the locales of the
+    /// [`Messages`] bean, or any locale without one).
+    pub fn supported<I>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.supported = Some(tags.into_iter().map(Into::into).collect());
+        self
+    }
+}
+
+impl Default for I18n {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn parse_locale(tag: &str) -> Locale {
+    Locale::parse(tag).unwrap_or_else(|| panic!("I18n: invalid locale `{tag}`"))
+}
+
+impl Plugin for I18n {
+    fn install<T: Clone + Send + Sync + 'static>(self, app: AppBuilder<T>) -> AppBuilder<T> {
+        let messages = app.bean_context().try_get::<Messages>();
+        let default_locale = match (&self.default_locale, &messages) {
+            (Some(tag), _) => parse_locale(tag),
+            (None, Some(messages)) => messages.default_locale().clone(),
+            (None, None) => parse_locale("en"),
+        };
+        let supported = match (&self.supported, &messages) {
+            (Some(tags), _) => tags.iter().map(|tag| parse_locale(tag)).collect(),
+            (None, Some(messages)) => messages.locales(),
+            (None, None) => Vec::new(),
+        };
+        let settings = Arc::new(Settings {
+            messages,
+            default_locale,
+            supported,
+            query_param: self.query_param,
+            cookie: self.cookie,
+            claim: self.claim,
+        });
+        app.with_layer_fn(move |router| {
+            router.layer(from_fn(move |req: Request, next: Next| {
+                i18n_middleware(settings.clone(), req, next)
+            }))
+        })
+    }
+}
+
+async fn i18n_middleware(settings: Arc<Settings>, req: Request, next: Next) -> Response {
+    let scope = Arc::new(LocaleScope::new(settings.clone(), &req));
+    let response = SCOPE.scope(scope.clone(), next.run(req)).await;
+    match &settings.messages {
+        Some(messages) if response.extensions().get::<Problem>().is_some() => {
+            translate(messages, &scope.resolve(), response).await
+        }
+        _ => response,
+    }
+}
+
+// ── Error translation ──────────────────────────────────────────────────
+
+/// Translate the message and field errors of an error response to `locale`,
+/// in its [`Problem`] and in its JSON body.
+async fn translate(messages: &Messages, locale: &Locale, response: Response) -> Response {
+    let Some(problem) = response.extensions().get::<Problem>() else {
+        return response;
+    };
+    let detail = match response.extensions().get::<MessageKey>() {
+        Some(key) => messages.try_format_owned(locale, &key.key, &key.args),
+        None => problem
+            .detail
+            .as_deref()
+            .and_then(|detail| messages.try_format(locale, detail, &[])),
+    };
+    let field_messages = field_messages(messages, locale, &response, problem);
+    if detail.is_none() && field_messages.iter().all(Option::is_none) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    if let Some(problem) = parts.extensions.get_mut::<Problem>() {
+        if let Some(detail) = &detail {
+            problem.detail = Some(detail.clone());
+        }
+        if let Some(errors) = problem.extensions.get_mut("errors") {
+            patch_field_messages(errors, &field_messages);
+        }
+    }
+    let Ok(bytes) = crate::http::body::to_bytes(body, usize::MAX).await else {
+        return Response::from_parts(parts, Body::empty());
+    };
+    let bytes = match serde_json::from_slice::<Value>(&bytes) {
+        Ok(Value::Object(mut fields)) => {
+            for name in ["error", "detail"] {
+                if let (Some(Value::String(message)), Some(detail)) =
+                    (fields.get_mut(name), &detail)
+                {
+                    message.clone_from(detail);
+                }
+            }
+            for name in ["details", "errors"] {
+                if let Some(errors) = fields.get_mut(name) {
+                    patch_field_messages(errors, &field_messages);
+                }
+            }
+            serde_json::to_vec(&Value::Object(fields)).unwrap_or_else(|_| bytes.to_vec())
+        }
+        _ => bytes.to_vec(),
+    };
+    parts.headers.remove(CONTENT_LENGTH);
+    if let Ok(language) = HeaderValue::from_str(locale.as_str()) {
+        parts.headers.insert(CONTENT_LANGUAGE, language);
+    }
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+/// The translated message of each field error of a validation response.
+fn field_messages(
+    messages: &Messages,
+    locale: &Locale,
+    response: &Response,
+    problem: &Problem,
+) -> Vec<Option<String>> {
+    let Some(Value::Array(errors)) = problem.extensions.get("errors") else {
+        return Vec::new();
+    };
+    let rule_args = response.extensions().get::<FieldErrorArgs>();
+    errors
+        .iter()
+        .enumerate()
+        .map(|(i, error)| {
+            let code = error.get("code")?.as_str()?;
+            let field = error.get("field").and_then(Value::as_str).unwrap_or("");
+            let mut args = vec![(Cow::Borrowed("field"), field.to_string())];
+            if let Some(rule_args) = rule_args.and_then(|all| all.0.get(i)) {
+                args.extend(
+                    rule_args
+                        .iter()
+                        .map(|(name, value)| (Cow::Borrowed(*name), value.clone())),
+                );
+            }
+            messages.try_format_owned(locale, &format!("validation.{code}"), &args)
+        })
+        .collect()
+}
+
+fn patch_field_messages(errors: &mut Value, translated: &[Option<String>]) {
+    let Value::Array(errors) = errors else {
+        return;
+    };
+    for (error, message) in errors.iter_mut().zip(translated) {
+        if let (Some(Value::String(current)), Some(message)) = (error.get_mut("message"), message) {
+            current.clone_from(message);
+        }
+    }
+}
@@ -0,0 +1,600 @@
+//! `Idempotency-Key` handling: `#[idempotent]` and [`IdempotencyStore`].
+//!
+//! ```ignore
+//! #[routes]
+//! impl PaymentController {
+//!     #[post("/payments")]
+//!     #[idempotent(ttl = "24h")]
+//!     async fn charge(&self, Json(charge): Json<Charge>) -> Result<Json<Payment>, HttpError> {
+//!         Ok(Json(self.gateway.charge(charge).await?))
+//!     }
+//! }
+//!
+//! AppBuilder::new().provide(InMemoryIdempotencyStore::shared())
+//! ```
+//!
+//! A request to an `#[idempotent]` route that carries an `Idempotency-Key`
+//! header runs the handler once. The key is scoped by the identity `sub`
+//! (anonymous callers share one scope), and the first response — status,
+//! headers and body — is stored for the `ttl` (24 hours by default) and
+//! replayed to every retry with `Idempotent-Replayed: true`. A retry that
+//! arrives while the first request is still running gets `409 Conflict`; the
+//! same key sent with a different method, path or body gets
+//! `422 Unprocessable Entity`. Requests without the header run normally.
+//! The keyed request body is buffered up to the route's body limit (axum's
+//! 2 MB default without `#[body_limit]`); a larger one gets `413`.
+//!
+//! The key is claimed by a guard that runs after every other guard of the
+//! route, so rejected requests never claim it. `5xx` responses, panics and
+//! cancelled requests release the key instead of storing a response, so the
+//! client can retry.
+//!
+//! The store is the `Arc<dyn IdempotencyStore>` bean: the in-process
+//! [`InMemoryIdempotencyStore`], a `CacheStore` adapter from `r2e-utils` or
+//! the SQL store from `r2e-data-sqlx` for a fleet of instances.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::beans::BeanContext;
+use crate::decorator::DecoratorSpec;
+use crate::error::error_response;
+use crate::guards::{Guard, GuardContext, Identity};
+use crate::http::body::{to_bytes, Body};
+use crate::http::header::{
+    HeaderMap, HeaderName, HeaderValue, CONNECTION, CONTENT_LENGTH, DATE, TRANSFER_ENCODING,
+};
+use crate::http::middleware::{from_fn, Next};
+use crate::http::response::{IntoResponse, Response};
+use crate::http::routing::MethodRouter;
+use crate::http::{Bytes, FromRequest, Method, Request, StatusCode};
+use crate::meta::{ErrorResponseInfo, ParamInfo, ParamLocation};
+use crate::type_list::{TCons, TNil};
+use crate::HttpError;
+
+/// The request header carrying the client's key.
+pub const IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
+
+/// The response header marking a replayed response.
+pub const IDEMPOTENT_REPLAYED: HeaderName = HeaderName::from_static("idempotent-replayed");
+
+/// Longest accepted `Idempotency-Key`.
+pub const MAX_KEY_LEN: usize = 255;
+
+/// Boxed future returned by [`IdempotencyStore`] methods.
+pub type StoreFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, StoreError>> + Send + 'a>>;
+
+/// A failure of the idempotency store. The request is answered with `503`.
+#[derive(Debug, Clone)]
+pub struct StoreError(pub String);
+
+impl StoreError {
+    pub fn new(message: impl std::fmt::Display) -> Self {
+        Self(message.to_string())
+    }
+}
+
+impl std::fmt::Display for StoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "idempotency store error: {}", self.0)
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+/// A response stored under an idempotency key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredResponse {
+    pub status: u16,
+    /// Response headers, in order. Values that are not valid UTF-8 are not
+    /// stored.
+    pub headers: Vec<(String, String)>,
+    #[serde(with = "base64_body")]
+    pub body: Vec<u8>,
+}
+
+impl StoredResponse {
+    /// Rebuild the response, marked with `Idempotent-Replayed: true`.
+    pub fn replay(&self) -> Response {
+        let mut response = Body::from(self.body.clone()).into_response();
+        *response.status_mut() = StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK);
+        let headers = response.headers_mut();
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::try_from(name.as_str()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.append(name, value);
+            }
+        }
+        headers.insert(IDEMPOTENT_REPLAYED, HeaderValue::from_static("true"));
+        response
+    }
+
+    fn capture(status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Self {
+        let headers = headers
+            .iter()
+            .filter(|(name, _)| {
+                ![CONNECTION, CONTENT_LENGTH, DATE, TRANSFER_ENCODING].contains(name)
+            })
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        Self {
+            status: status.as_u16(),
+            headers,
+            body: body.to_vec(),
+        }
+    }
+}
+
+/// What a store holds under a key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum IdempotencyRecord {
+    /// The first request with the key is still running.
+    InFlight { fingerprint: String },
+    /// The first request finished with `response`.
+    Completed {
+        fingerprint: String,
+        response: StoredResponse,
+    },
+}
+
+impl IdempotencyRecord {
+    /// Fingerprint of the request that claimed the key: a hash of its
+    /// method, path, query and body.
+    pub fn fingerprint(&self) -> &str {
+        match self {
+            IdempotencyRecord::InFlight { fingerprint }
+            | IdempotencyRecord::Completed { fingerprint, .. } => fingerprint,
+        }
+    }
+
+    /// JSON encoding, for stores that keep opaque bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("idempotency records serialize to JSON")
+    }
+
+    /// Decode [`to_bytes`](Self::to_bytes) output.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        serde_json::from_slice(bytes).map_err(StoreError::new)
+    }
+}
+
+/// Storage for idempotency keys, provided as an `Arc<dyn IdempotencyStore>`
+/// bean.
+///
+/// Keys arrive already scoped by identity. Entries expire `ttl` after
+/// [`try_begin`](Self::try_begin); an in-flight entry left behind by a
+/// crashed instance blocks its key with `409` until then.
+pub trait IdempotencyStore: Send + Sync + 'static {
+    /// Claim `key` for a request with `fingerprint`. Returns `None` when the
+    /// key was free (it is now in flight), or the existing record. Must be
+    /// atomic: of two concurrent calls for a free key, one gets `None`.
+    fn try_begin<'a>(
+        &'a self,
+        key: &'a str,
+        fingerprint: &'a str,
+        ttl: Duration,
+    ) -> StoreFuture<'a, Option<IdempotencyRecord>>;
+
+    /// Store the response of the request that claimed `key`.
+    fn complete<'a>(
+        &'a self,
+        key: &'a str,
+        response: StoredResponse,
+        ttl: Duration,
+    ) -> StoreFuture<'a, ()>;
+
+    /// Free `key` without a response, so the next request runs the handler.
+    fn release<'a>(&'a self, key: &'a str) -> StoreFuture<'a, ()>;
+}
+
+/// In-process store backed by a `DashMap`. Expired entries are evicted when
+/// their key is used again and by a sweep every 1024 claims.
+#[derive(Clone, Default)]
+pub struct InMemoryIdempotencyStore {
+    entries: Arc<DashMap<String, (IdempotencyRecord, Instant)>>,
+    claims: Arc<std::sync::atomic::AtomicUsize>,
+}
+
+impl InMemoryIdempotencyStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ready-to-provide store bean: `Arc<dyn IdempotencyStore>`.
+    ///
+    /// ```ignore
+    /// AppBuilder::new().provide(InMemoryIdempotencyStore::shared())
+    /// ```
+    pub fn shared() -> Arc<dyn IdempotencyStore> {
+        Arc::new(Self::new())
+    }
+
+    fn sweep(&self) {
+        let claims = self
+            .claims
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        if claims % 1024 == 1023 {
+            let now = Instant::now();
+            self.entries.retain(|_, (_, expires)| *expires > now);
+        }
+    }
+}
+
+impl IdempotencyStore for InMemoryIdempotencyStore {
+    fn try_begin<'a>(
+        &'a self,
+        key: &'a str,
+        fingerprint: &'a str,
+        ttl: Duration,
+    ) -> StoreFuture<'a, Option<IdempotencyRecord>> {
+        self.sweep();
+        let now = Instant::now();
+        let in_flight = IdempotencyRecord::InFlight {
+            fingerprint: fingerprint.to_string(),
+        };
+        let existing = match self.entries.entry(key.to_string()) {
+            Entry::Occupied(mut entry) if entry.get().1 <= now => {
+                entry.insert((in_flight, now + ttl));
+                None
+            }
+            Entry::Occupied(entry) => Some(entry.get().0.clone()),
+            Entry::Vacant(entry) => {
+                entry.insert((in_flight, now + ttl));
+                None
+            }
+        };
+        Box::pin(std::future::ready(Ok(existing)))
+    }
+
+    fn complete<'a>(
+        &'a self,
+        key: &'a str,
+        response: StoredResponse,
+        ttl: Duration,
+    ) -> StoreFuture<'a, ()> {
+        if let Some(mut entry) = self.entries.get_mut(key) {
+            let fingerprint = entry.0.fingerprint().to_string();
+            *entry = (
+                IdempotencyRecord::Completed {
+                    fingerprint,
+                    response,
+                },
+                Instant::now() + ttl,
+            );
+        }
+        Box::pin(std::future::ready(Ok(())))
+    }
+
+    fn release<'a>(&'a self, key: &'a str) -> StoreFuture<'a, ()> {
+        self.entries.remove(key);
+        Box::pin(std::future::ready(Ok(())))
+    }
+}
+
+/// Spec of the guard `#[idempotent(ttl = "...")]` adds to a route: claims
+/// the request's key in the `Arc<dyn IdempotencyStore>` bean.
+pub struct Idempotent {
+    ttl: Duration,
+}
+
+impl Idempotent {
+    /// Keep stored responses for `ttl`.
+    pub fn ttl(ttl: Duration) -> Self {
+        Self { ttl }
+    }
+}
+
+impl DecoratorSpec for Idempotent {
+    type Product = IdempotencyGuard;
+    type Deps = TCons<Arc<dyn IdempotencyStore>, TNil>;
+
+    fn build(self, ctx: &BeanContext) -> IdempotencyGuard {
+        IdempotencyGuard {
+            store: ctx.get::<Arc<dyn IdempotencyStore>>(),
+            ttl: self.ttl,
+        }
+    }
+}
+
+/// Claims the `Idempotency-Key` of the current request, or answers with
+/// the stored response, `409` or `422`. Built from [`Idempotent`].
+pub struct IdempotencyGuard {
+    store: Arc<dyn IdempotencyStore>,
+    ttl: Duration,
+}
+
+impl<I: Identity> Guard<I> for IdempotencyGuard {
+    fn check(
+        &self,
+        ctx: &GuardContext<'_, I>,
+    ) -> impl Future<Output = Result<(), Response>> + Send {
+        let pending = PENDING.try_with(Arc::clone).ok();
+        let sub = ctx.identity_sub().unwrap_or_default();
+        let store = self.store.clone();
+        let ttl = self.ttl;
+        let scoped = pending
+            .as_ref()
+            .map(|pending| format!("{}:{}:{}", sub.len(), sub, pending.key));
+        async move {
+            let (Some(pending), Some(key)) = (pending, scoped) else {
+                return Ok(());
+            };
+            let existing = store
+                .try_begin(&key, &pending.fingerprint, ttl)
+                .await
+                .map_err(unavailable)?;
+            match existing {
+                None => {
+                    *pending.claim.lock().unwrap() = Some(Claim {
+                        store,
+                        key,
+                        ttl,
+                        settled: false,
+                    });
+                    Ok(())
+                }
+                Some(record) if record.fingerprint() != pending.fingerprint => Err(error_response(
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    "Idempotency-Key already used for a different request",
+                )),
+                Some(IdempotencyRecord::InFlight { .. }) => Err(error_response(
+                    StatusCode::CONFLICT,
+                    "A request with this Idempotency-Key is already in progress",
+                )),
+                Some(IdempotencyRecord::Completed { response, .. }) => Err(response.replay()),
+            }
+        }
+    }
+}
+
+fn unavailable(error: StoreError) -> Response {
+    tracing::error!(error = %error, "idempotency store unavailable");
+    error_response(
+        StatusCode::SERVICE_UNAVAILABLE,
+        "Idempotency store unavailable",
+    )
+}
+
+tokio::task_local! {
+    static PENDING: Arc<Pending>;
+}
+
+/// The key and fingerprint of the request being handled, shared between
+/// the layer and the guard.
+struct Pending {
+    key: String,
+    fingerprint: String,
+    claim: Mutex<Option<Claim>>,
+}
+
+/// A key claimed by the guard. Released on drop unless settled, so a panic
+/// or a cancelled request frees the key.
+struct Claim {
+    store: Arc<dyn IdempotencyStore>,
+    key: String,
+    ttl: Duration,
+    settled: bool,
+}
+
+impl Claim {
+    async fn complete(mut self, response: StoredResponse) {
+        self.settled = true;
+        if let Err(error) = self.store.complete(&self.key, response, self.ttl).await {
+            tracing::error!(error = %error, "failed to store the idempotent response");
+        }
+    }
+
+    async fn release(mut self) {
+        self.settled = true;
+        if let Err(error) = self.store.release(&self.key).await {
+            tracing::error!(error = %error, "failed to release the idempotency key");
+        }
+    }
+}
+
+impl Drop for Claim {
+    fn drop(&mut self) {
+        if self.settled {
+            return;
+        }
+        let store = self.store.clone();
+        let key = std::mem::take(&mut self.key);
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(async move {
+                let _ = store.release(&key).await;
+            });
+        }
+    }
+}
+
+/// Documented responses of an `#[idempotent]` route.
+pub fn error_responses() -> Vec<ErrorResponseInfo> {
+    vec![
+        ErrorResponseInfo::new(409, "Request with this Idempotency-Key in progress"),
+        ErrorResponseInfo::new(413, "Request body too large"),
+        ErrorResponseInfo::new(422, "Idempotency-Key reused for a different request"),
+    ]
+}
+
+/// The documented `Idempotency-Key` header of an `#[idempotent]` route.
+pub fn params() -> Vec<ParamInfo> {
+    vec![ParamInfo {
+        name: "Idempotency-Key".to_string(),
+        location: ParamLocation::Header,
+        param_type: "string".to_string(),
+        required: false,
+        schema: None,
+        description: Some(
+            "Unique key of this operation; retries with the same key replay the first response"
+                .to_string(),
+        ),
+        example: None,
+        style: None,
+        explode: None,
+    }]
+}
+
+/// Wrap an `#[idempotent]` route in the layer that fingerprints keyed
+/// requests and stores their response.
+#[doc(hidden)]
+pub fn __apply<S>(router: MethodRouter<S>) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    router.layer(from_fn(evaluate))
+}
+
+async fn evaluate(req: Request, next: Next) -> Response {
+    if matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return next.run(req).await;
+    }
+    let key = match req.headers().get(IDEMPOTENCY_KEY) {
+        None => return next.run(req).await,
+        Some(value) => match value.to_str() {
+            Ok(key) if is_valid_key(key) => key.to_string(),
+            _ => {
+                return HttpError::bad_request(format!(
+                    "Invalid Idempotency-Key: expected 1 to {MAX_KEY_LEN} visible ASCII characters"
+                ))
+                .into_response()
+            }
+        },
+    };
+
+    // Buffer through the `Bytes` extractor so the route's body limit applies
+    // (`#[body_limit]`, an app-wide `DefaultBodyLimit`, or axum's 2 MB).
+    let (parts, body) = req.into_parts();
+    let body = match Bytes::from_request(Request::from_parts(parts.clone(), body), &()).await {
+        Ok(body) => body,
+        Err(rejection) if rejection.status() == StatusCode::PAYLOAD_TOO_LARGE => {
+            return HttpError::from_status(StatusCode::PAYLOAD_TOO_LARGE, "Request body too large")
+                .into_response()
+        }
+        Err(_) => return HttpError::bad_request("Failed to read the request body").into_response(),
+    };
+    let pending = Arc::new(Pending {
+        key,
+        fingerprint: fingerprint(&parts.method, &parts.uri, &body),
+        claim: Mutex::new(None),
+    });
+    let req = Request::from_parts(parts, Body::from(body));
+    let response = PENDING.scope(pending.clone(), next.run(req)).await;
+
+    let Some(claim) = pending.claim.lock().unwrap().take() else {
+        return response;
+    };
+    if response.status().is_server_error() {
+        claim.release().await;
+        return response;
+    }
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(error) => {
+            tracing::error!(error = %error, "failed to buffer response for #[idempotent]");
+            claim.release().await;
+            return HttpError::internal("Failed to read the response body").into_response();
+        }
+    };
+    claim
+        .complete(StoredResponse::capture(parts.status, &parts.headers, &body))
+        .await;
+    Response::from_parts(parts, Body::from(body))
+}
+
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty() && key.len() <= MAX_KEY_LEN && key.bytes().all(|b| b.is_ascii_graphic())
+}
+
+fn fingerprint(method: &Method, uri: &crate::http::Uri, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str());
+    hasher.update(b"\n");
+    hasher.update(uri.path_and_query().map_or("", |p| p.as_str()));
+    hasher.update(b"\n");
+    hasher.update(body);
+    STANDARD.encode(hasher.finalize())
+}
+
+mod base64_body {
+    use super::*;
+
+    pub fn serialize<S: serde::Serializer>(body: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(body))
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        STANDARD.decode(encoded).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn in_memory_store_claims_each_key_once() {
+        let store = InMemoryIdempotencyStore::new();
+        let ttl = Duration::from_secs(60);
+        assert_eq!(store.try_begin("k", "f", ttl).await.unwrap(), None);
+        assert_eq!(
+            store.try_begin("k", "f", ttl).await.unwrap(),
+            Some(IdempotencyRecord::InFlight {
+                fingerprint: "f".into()
+            })
+        );
+
+        let response = StoredResponse {
+            status: 201,
+            headers: vec![("content-type".into(), "application/json".into())],
+            body: b"{}".to_vec(),
+        };
+        store.complete("k", response.clone(), ttl).await.unwrap();
+        let record = store.try_begin("k", "f", ttl).await.unwrap().unwrap();
+        assert_eq!(
+            record,
+            IdempotencyRecord::Completed {
+                fingerprint: "f".into(),
+                response
+            }
+        );
+        assert_eq!(
+            IdempotencyRecord::from_bytes(&record.to_bytes()).unwrap(),
+            record
+        );
+
+        store.release("k").await.unwrap();
+        assert_eq!(store.try_begin("k", "g", ttl).await.unwrap(), None);
+        assert_eq!(
+            store
+                .try_begin("expired", "f", Duration::ZERO)
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(store.try_begin("expired", "f", ttl).await.unwrap(), None);
+    }
+
+    #[test]
+    fn keys_are_visible_ascii() {
+        assert!(is_valid_key("4f1c-9b2e"));
+        assert!(!is_valid_key(""));
+        assert!(!is_valid_key("two words"));
+        assert!(!is_valid_key(&"k".repeat(MAX_KEY_LEN + 1)));
+    }
+}
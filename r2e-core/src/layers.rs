@@ -1,4 +1,3 @@
-use crate::http::response::IntoResponse;
 use crate::http::StatusCode;
 use crate::tracing_config::{LogFormat, TracingConfig};
 use tower_http::catch_panic::CatchPanicLayer;
@@ -117,6 +116,5 @@ pub fn normalize_path_router(router: crate::http::Router) -> crate::http::Router
 }
 
 fn panic_handler(_err: Box<dyn std::any::Any + Send>) -> crate::http::Response {
-    let body = serde_json::json!({ "error": "Internal server error" });
-    (StatusCode::INTERNAL_SERVER_ERROR, crate::http::Json(body)).into_response()
+    crate::error::error_response(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
 }
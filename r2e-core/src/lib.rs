@@ -1,5 +1,6 @@
 pub mod beans;
 pub mod builder;
+pub mod conditional;
 pub mod config;
 pub mod controller;
 pub mod decorator;
@@ -10,6 +11,8 @@ pub mod extract;
 pub mod guards;
 pub mod health;
 pub mod http;
+pub mod i18n;
+pub mod idempotency;
 pub mod interceptors;
 pub mod late;
 pub mod layers;
@@ -20,23 +23,32 @@ pub mod meta;
 pub mod module;
 #[cfg(feature = "multipart")]
 pub mod multipart;
+pub mod negotiation;
 pub mod pagination;
 pub mod params;
 pub mod plugin;
 pub mod plugins;
 pub mod prelude;
+pub mod problem;
+pub mod request_context;
 pub mod request_id;
+pub mod route_limits;
 pub mod rt;
 pub mod scheduled_source;
 pub mod secure_headers;
 pub mod service;
+pub mod session;
 pub mod sharded;
 pub mod sse;
 pub mod state;
+pub mod templating;
+pub mod tls;
 pub mod tracing_config;
+pub mod transaction;
 pub mod type_list;
 pub mod types;
 pub mod validation;
+pub mod versioning;
 #[cfg(feature = "ws")]
 pub mod ws;
 
@@ -62,7 +74,7 @@ pub use controller::{ContextConstruct, Controller, EndpointDeps};
 pub use decorator::{
     BeanDecoFill, Decorate, DecoratorSpec, HasDecoSlot, SelfBuilt, SharedDecoSlot,
 };
-pub use error::{HttpError, HttpErrorExt};
+pub use error::{ErrorResponses, HttpError, HttpErrorExt};
 pub use event_subscriber::EventSubscriber;
 pub use extract::{
     assert_unambiguous_extractor, BeanExtract, FromRequestPartsVia, OptionalFromRequestPartsVia,
@@ -83,18 +95,23 @@ pub use managed::{
 };
 pub use meta::MetaRegistry;
 pub use module::FeatureModule;
-pub use pagination::{Page, Pageable};
+pub use pagination::{
+    CursorCodec, CursorPage, CursorPageable, Filter, KeysetSort, Page, Pageable, QueryFields, Sort,
+};
 pub use plugin::{
     DeferredAction, DeferredContext, Plugin, PluginInstallContext, PreStatePlugin,
     RawPreStatePlugin,
 };
 pub use plugins::{AdvancedHealth, ConfiguredTracing};
+pub use problem::{Problem, ProblemDetails};
+pub use request_context::RequestContext;
 pub use request_id::{RequestId, RequestIdPlugin};
 pub use scheduled_source::ScheduledSource;
 pub use secure_headers::SecureHeaders;
 pub use service::ServiceComponent;
 pub use state::R2eState;
 pub use tracing_config::{LogFormat, SpanEvents, TracingConfig};
+pub use transaction::{Isolation, Propagation, TxError, TxOptions};
 pub use type_list::{
     AllSatisfied, BeanAccess, BeanLookup, BuildHList, Contains, ControllerTuple, HCons, HNil,
     HasBean, Here, PluginDeps, TAppend, TCons, TNil, There,
@@ -1,7 +1,8 @@
+use crate::config::R2eConfig;
 use serde::Serialize;
 use serde_json::Value;
 use std::any::{Any, TypeId};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// A generic, type-erased metadata registry.
 ///
@@ -14,6 +15,7 @@ use std::collections::HashMap;
 #[derive(Default)]
 pub struct MetaRegistry {
     inner: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+    config: Option<R2eConfig>,
 }
 
 impl MetaRegistry {
@@ -54,6 +56,17 @@ impl MetaRegistry {
         self.get::<M>().unwrap_or(&[])
     }
 
+    /// Attach the application config, so metadata can reflect config
+    /// overrides (e.g. `r2e.routes.<Controller>.<method>.timeout`).
+    pub fn set_config(&mut self, config: Option<R2eConfig>) {
+        self.config = config;
+    }
+
+    /// The application config, when one was loaded.
+    pub fn config(&self) -> Option<&R2eConfig> {
+        self.config.as_ref()
+    }
+
     /// Get or create the `Vec<M>` entry for a given type.
     fn entry<M: Any + Send + Sync>(&mut self) -> &mut Vec<M> {
         self.inner
@@ -64,8 +77,44 @@ impl MetaRegistry {
     }
 }
 
+/// Read-only registry observers contributed by plugins through
+/// [`DeferredContext::observe_meta`](crate::DeferredContext::observe_meta),
+/// stored in plugin data until `build()` runs them after the meta consumers.
+#[doc(hidden)]
+#[derive(Default)]
+pub struct MetaObservers {
+    observers: Vec<MetaObserver>,
+}
+
+type MetaObserver = Box<dyn FnOnce(&MetaRegistry) + Send + Sync>;
+
+impl MetaObservers {
+    pub(crate) fn push<M, F>(&mut self, f: F)
+    where
+        M: Any + Send + Sync,
+        F: FnOnce(&[M]) + Send + Sync + 'static,
+    {
+        self.observers
+            .push(Box::new(move |registry| f(registry.get_or_empty::<M>())));
+    }
+
+    pub(crate) fn run(self, registry: &MetaRegistry) {
+        for observer in self.observers {
+            observer(registry);
+        }
+    }
+}
+
 // ── Metadata types (moved from openapi.rs) ──────────────────────────────────
 
+/// The [`RouteInfo::extensions`] key carrying a route's `#[priority(...)]`
+/// (`"critical"`, `"normal"` or `"background"`), read by load shedders.
+pub const PRIORITY_EXTENSION: &str = "x-r2e-priority";
+
+/// The [`RouteInfo::extensions`] key carrying the API version a route of a
+/// `#[controller(versions = "...")]` serves; see [`RouteInfo::api_version`].
+pub const API_VERSION_EXTENSION: &str = "x-r2e-api-version";
+
 /// Metadata about a single route, collected at compile time.
 #[derive(Debug, Clone, Serialize)]
 pub struct RouteInfo {
@@ -79,8 +128,17 @@ pub struct RouteInfo {
     /// Request body media type. `None` means `application/json`.
     pub request_body_content_type: Option<String>,
     pub request_body_required: bool,
+    /// Request body media types from `#[consumes]`, in declaration order.
+    /// Empty means the single `request_body_content_type`.
+    pub consumes: Vec<String>,
     pub response_type: Option<String>,
     pub response_schema: Option<Value>,
+    /// Response media type. `None` means `application/json` when there is
+    /// a response type; `#[sse]` routes carry `text/event-stream`.
+    pub response_content_type: Option<String>,
+    /// Response media types from `#[produces]`, in declaration order.
+    /// Empty means the single `response_content_type`.
+    pub produces: Vec<String>,
     pub response_status: u16,
     /// The Rust return-type name of a **successful** response body that could
     /// not be auto-mapped to an OpenAPI schema (an `impl Trait` return, or a
@@ -92,11 +150,31 @@ pub struct RouteInfo {
     /// emit a once-at-boot warning naming the route and offending type instead
     /// of silently documenting the response without a body.
     pub response_unmapped: Option<String>,
+    /// Error responses beyond the generic ones: the variants of the
+    /// handler's `#[derive(ApiError)]` error type, and `429` for
+    /// rate-limited routes.
+    pub error_responses: Vec<ErrorResponseInfo>,
     pub params: Vec<ParamInfo>,
     pub roles: Vec<String>,
     pub tag: Option<String>,
     pub deprecated: bool,
     pub has_auth: bool,
+    /// Vendor extensions (`x-...`) added to the operation object, e.g. the
+    /// per-route limits from `#[timeout]`, `#[body_limit]` and
+    /// `#[max_concurrent]`, or the [`PRIORITY_EXTENSION`] set by `#[priority]`.
+    pub extensions: BTreeMap<String, Value>,
+}
+
+impl RouteInfo {
+    /// The API version this route serves, or `None` for a route of an
+    /// unversioned controller. A versioned endpoint has one `RouteInfo` per
+    /// version, with the `/v{n}` path.
+    pub fn api_version(&self) -> Option<u32> {
+        self.extensions
+            .get(API_VERSION_EXTENSION)?
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+    }
 }
 
 /// Describes a multipart form type as a JSON Schema object for OpenAPI.
@@ -116,6 +194,46 @@ pub trait MultipartSchema {
     fn multipart_schema() -> Value;
 }
 
+/// A documented error response of a route.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ErrorResponseInfo {
+    pub status: u16,
+    pub description: String,
+    /// Name of the body schema: `ErrorResponse` (`{"error": "..."}`) or
+    /// `ValidationErrorResponse`.
+    pub schema: String,
+    /// RFC 9457 problem type URI, from `#[error(type = "...")]`.
+    pub problem_type: Option<String>,
+}
+
+impl ErrorResponseInfo {
+    /// A `{"error": "..."}` response.
+    pub fn new(status: u16, description: impl Into<String>) -> Self {
+        Self {
+            status,
+            description: description.into(),
+            schema: "ErrorResponse".to_string(),
+            problem_type: None,
+        }
+    }
+
+    /// A `{"error": "Validation failed", "details": [...]}` response.
+    pub fn validation(status: u16, description: impl Into<String>) -> Self {
+        Self {
+            status,
+            description: description.into(),
+            schema: "ValidationErrorResponse".to_string(),
+            problem_type: None,
+        }
+    }
+
+    /// Set the problem type URI documented for this response.
+    pub fn with_problem_type(mut self, problem_type: impl Into<String>) -> Self {
+        self.problem_type = Some(problem_type.into());
+        self
+    }
+}
+
 /// Metadata about a route parameter.
 #[derive(Debug, Clone, Serialize)]
 pub struct ParamInfo {
@@ -123,6 +241,18 @@ pub struct ParamInfo {
     pub location: ParamLocation,
     pub param_type: String,
     pub required: bool,
+    /// Full JSON schema of the parameter. When `None`, the spec renders
+    /// `{"type": param_type}`.
+    pub schema: Option<Value>,
+    /// Human-readable description (from the field's doc comment).
+    pub description: Option<String>,
+    /// Example value shown in the spec.
+    pub example: Option<Value>,
+    /// OpenAPI serialization style (`form`, `simple`, ...), for arrays.
+    pub style: Option<String>,
+    /// Whether array values are sent as repeated keys (`true`) or a single
+    /// delimited value (`false`).
+    pub explode: Option<bool>,
 }
 
 /// Where a parameter is located in the HTTP request.
@@ -132,4 +262,52 @@ pub enum ParamLocation {
     Path,
     Query,
     Header,
+    Cookie,
+}
+
+/// Metadata about a single `#[consumer]` method, collected at compile time.
+///
+/// The event-side counterpart of [`RouteInfo`]: the `#[routes]` macro pushes
+/// one per consumer method at controller registration, and AsyncAPI
+/// generation (`r2e-asyncapi`) turns them into channels and operations.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsumerInfo {
+    /// Controller that declares the consumer.
+    pub owner: String,
+    pub operation_id: String,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    /// Topic the consumer listens on: the `topic = "..."` attribute, else an
+    /// overridden `Event::topic()`, else the sanitized type name the bus
+    /// falls back to.
+    pub topic: String,
+    pub event_type: String,
+    pub event_schema: Option<Value>,
+    /// Reply payload type of a request-reply responder. `None` for fan-out
+    /// subscribers.
+    pub reply_type: Option<String>,
+    pub reply_schema: Option<Value>,
+    /// `retry = N` attribute.
+    pub max_retries: Option<u32>,
+    /// `dlq = "..."` attribute.
+    pub dead_letter_topic: Option<String>,
+}
+
+/// Metadata about an event type a controller method emits, collected at
+/// compile time from `#[emits(...)]`.
+///
+/// The producer-side counterpart of [`ConsumerInfo`], pushed one per emitted
+/// type at controller registration.
+#[derive(Debug, Clone, Serialize)]
+pub struct EmitterInfo {
+    /// Controller that declares the emitting method.
+    pub owner: String,
+    /// `<Controller>_<method>` of the emitting method.
+    pub operation_id: String,
+    pub summary: Option<String>,
+    /// Topic the event is emitted on, resolved like [`ConsumerInfo::topic`]:
+    /// an overridden `Event::topic()`, else the sanitized type name.
+    pub topic: String,
+    pub event_type: String,
+    pub event_schema: Option<Value>,
 }
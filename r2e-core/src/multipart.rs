@@ -4,7 +4,7 @@ use bytes::{Bytes, BytesMut};
 
 use crate::http::extract::{FromRequest, Request};
 use crate::http::response::{IntoResponse, Response};
-use crate::http::{Json, StatusCode};
+use crate::http::StatusCode;
 
 /// Re-export the raw Axum multipart extractor for advanced use cases.
 pub use crate::http::multipart::Multipart;
@@ -91,8 +91,7 @@ impl IntoResponse for MultipartError {
             }
             _ => StatusCode::BAD_REQUEST,
         };
-        let body = serde_json::json!({ "error": self.to_string() });
-        (status, Json(body)).into_response()
+        crate::error::error_response(status, self.to_string())
     }
 }
 
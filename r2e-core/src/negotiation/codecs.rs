@@ -0,0 +1,68 @@
+//! Built-in codecs: JSON, forms and (with the `cbor` feature) CBOR.
+
+use super::{CodecError, Decode, Encode, MediaTypeCodec};
+
+/// `application/json`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonCodec;
+
+impl MediaTypeCodec for JsonCodec {
+    fn media_type(&self) -> &str {
+        "application/json"
+    }
+
+    fn encode(&self, value: &Encode<'_>) -> Result<Vec<u8>, CodecError> {
+        Ok(serde_json::to_vec(value)?)
+    }
+
+    fn decode(&self, body: &[u8], target: &mut Decode<'_>) -> Result<(), CodecError> {
+        let mut deserializer = serde_json::Deserializer::from_slice(body);
+        target.deserialize(&mut deserializer)?;
+        Ok(deserializer.end()?)
+    }
+}
+
+/// `application/x-www-form-urlencoded`. Flat structs only.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FormCodec;
+
+impl MediaTypeCodec for FormCodec {
+    fn media_type(&self) -> &str {
+        "application/x-www-form-urlencoded"
+    }
+
+    fn encode(&self, value: &Encode<'_>) -> Result<Vec<u8>, CodecError> {
+        Ok(serde_urlencoded::to_string(value)?.into_bytes())
+    }
+
+    fn decode(&self, body: &[u8], target: &mut Decode<'_>) -> Result<(), CodecError> {
+        target.deserialize(serde_urlencoded::Deserializer::new(form_urlencoded::parse(
+            body,
+        )))
+    }
+}
+
+/// `application/cbor` (`cbor` feature).
+#[cfg(feature = "cbor")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CborCodec;
+
+#[cfg(feature = "cbor")]
+impl MediaTypeCodec for CborCodec {
+    fn media_type(&self) -> &str {
+        "application/cbor"
+    }
+
+    fn encode(&self, value: &Encode<'_>) -> Result<Vec<u8>, CodecError> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(value, &mut bytes).map_err(|e| e.to_string())?;
+        Ok(bytes)
+    }
+
+    fn decode(&self, body: &[u8], target: &mut Decode<'_>) -> Result<(), CodecError> {
+        // ciborium only deserializes into owned types, so go through a
+        // self-describing intermediate value.
+        let value: serde_json::Value = ciborium::from_reader(body).map_err(|e| e.to_string())?;
+        target.deserialize(value)
+    }
+}
@@ -0,0 +1,414 @@
+//! `text/csv` (`csv` feature): one row per record, a header row of field
+//! names.
+
+use std::fmt;
+
+use serde::de::value::{Error as DeError, StrDeserializer};
+use serde::de::{self, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use serde::forward_to_deserialize_any;
+use serde_json::Value;
+
+use super::{CodecError, Decode, Encode, MediaTypeCodec};
+
+/// `text/csv`.
+///
+/// Encodes a record (struct or map) or a sequence of records; nested values
+/// are written as JSON text. Decodes into a sequence of records, or into a
+/// single record when the body has exactly one row. Cells parse into the
+/// target field types (`"42"` into a `u32`, an empty cell into `None`).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CsvCodec;
+
+impl MediaTypeCodec for CsvCodec {
+    fn media_type(&self) -> &str {
+        "text/csv"
+    }
+
+    fn encode(&self, value: &Encode<'_>) -> Result<Vec<u8>, CodecError> {
+        // Round-trip through JSON text to read the records with their
+        // fields in declaration order.
+        let json = serde_json::to_vec(value)?;
+        let records = match serde_json::from_slice::<Records>(&json) {
+            Ok(Records::Many(records)) => records,
+            Ok(Records::One(record)) => vec![record],
+            Err(_) => return Err("CSV bodies must be a record or a sequence of records".into()),
+        };
+
+        let mut writer = ::csv::Writer::from_writer(Vec::new());
+        if let Some(first) = records.first() {
+            let header: Vec<&str> = first.0.iter().map(|(name, _)| name.as_str()).collect();
+            writer.write_record(&header)?;
+            for record in &records {
+                writer.write_record(header.iter().map(|name| cell(record.get(name))))?;
+            }
+        }
+        Ok(writer.into_inner().map_err(|e| e.to_string())?)
+    }
+
+    fn decode(&self, body: &[u8], target: &mut Decode<'_>) -> Result<(), CodecError> {
+        let mut reader = ::csv::Reader::from_reader(body);
+        let headers: Vec<String> = reader.headers()?.iter().map(str::to_owned).collect();
+        let rows = reader
+            .records()
+            .map(|row| Ok(row?.iter().map(str::to_owned).collect()))
+            .collect::<Result<Vec<Vec<String>>, ::csv::Error>>()?;
+        target.deserialize(Table { headers, rows })
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum Records {
+    Many(Vec<Record>),
+    One(Record),
+}
+
+/// A record's fields in order.
+struct Record(Vec<(String, Value)>);
+
+impl Record {
+    fn get(&self, name: &str) -> Option<&Value> {
+        self.0.iter().find(|(n, _)| n == name).map(|(_, v)| v)
+    }
+}
+
+impl<'de> de::Deserialize<'de> for Record {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RecordVisitor;
+
+        impl<'de> Visitor<'de> for RecordVisitor {
+            type Value = Record;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a record")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Record, A::Error> {
+                let mut fields = Vec::new();
+                while let Some(field) = map.next_entry()? {
+                    fields.push(field);
+                }
+                Ok(Record(fields))
+            }
+        }
+
+        deserializer.deserialize_map(RecordVisitor)
+    }
+}
+
+fn cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// A parsed CSV body, deserialized as a sequence of records.
+struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl<'de> de::Deserializer<'de> for Table {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_seq(Rows {
+            headers: &self.headers,
+            rows: self.rows.iter(),
+        })
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.rows.as_slice() {
+            [row] => Row {
+                headers: &self.headers,
+                cells: row,
+            }
+            .deserialize_map(visitor),
+            rows => Err(de::Error::custom(format!(
+                "expected exactly one CSV record, found {}",
+                rows.len()
+            ))),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.deserialize_map(visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct enum identifier ignored_any
+    }
+}
+
+struct Rows<'a> {
+    headers: &'a [String],
+    rows: std::slice::Iter<'a, Vec<String>>,
+}
+
+impl<'de> SeqAccess<'de> for Rows<'_> {
+    type Error = DeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, DeError> {
+        self.rows
+            .next()
+            .map(|cells| {
+                seed.deserialize(Row {
+                    headers: self.headers,
+                    cells,
+                })
+            })
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.rows.len())
+    }
+}
+
+/// One record: a map of header → cell, or a sequence of cells for tuples.
+struct Row<'a> {
+    headers: &'a [String],
+    cells: &'a [String],
+}
+
+impl<'de> de::Deserializer<'de> for Row<'_> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_map(Fields {
+            fields: self.headers.iter().zip(self.cells),
+            value: None,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_seq(Cells(self.cells.iter()))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct enum identifier
+        ignored_any
+    }
+}
+
+struct Fields<'a, I: Iterator<Item = (&'a String, &'a String)>> {
+    fields: I,
+    value: Option<&'a str>,
+}
+
+impl<'a, 'de, I: Iterator<Item = (&'a String, &'a String)>> MapAccess<'de> for Fields<'a, I> {
+    type Error = DeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, DeError> {
+        match self.fields.next() {
+            Some((name, value)) => {
+                self.value = Some(value);
+                let name: StrDeserializer<'_, DeError> = name.as_str().into_deserializer();
+                seed.deserialize(name).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, DeError> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| de::Error::custom("CSV value requested before its header"))?;
+        seed.deserialize(Cell(value))
+    }
+}
+
+struct Cells<'a>(std::slice::Iter<'a, String>);
+
+impl<'de> SeqAccess<'de> for Cells<'_> {
+    type Error = DeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, DeError> {
+        self.0
+            .next()
+            .map(|cell| seed.deserialize(Cell(cell)))
+            .transpose()
+    }
+}
+
+/// One CSV cell, parsed into whatever type the target asks for.
+struct Cell<'a>(&'a str);
+
+macro_rules! parse_cell {
+    ($($method:ident => $visit:ident: $ty:ty),* $(,)?) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+            match self.0.trim().parse::<$ty>() {
+                Ok(value) => visitor.$visit(value),
+                Err(_) => Err(de::Error::custom(format!(
+                    "invalid {} value '{}'",
+                    stringify!($ty),
+                    self.0
+                ))),
+            }
+        }
+    )*};
+}
+
+impl<'de> de::Deserializer<'de> for Cell<'_> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_str(self.0)
+    }
+
+    parse_cell! {
+        deserialize_bool => visit_bool: bool,
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_i128 => visit_i128: i128,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64,
+        deserialize_u128 => visit_u128: u128,
+        deserialize_f32 => visit_f32: f32,
+        deserialize_f64 => visit_f64: f64,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        if self.0.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        let variant: StrDeserializer<'_, DeError> = self.0.into_deserializer();
+        visitor.visit_enum(variant)
+    }
+
+    forward_to_deserialize_any! {
+        char str string bytes byte_buf unit_struct seq tuple tuple_struct map
+        struct identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::negotiation::{decode, encode};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Line {
+        sku: String,
+        quantity: u32,
+        note: Option<String>,
+    }
+
+    fn lines() -> Vec<Line> {
+        vec![
+            Line {
+                sku: "A-1".into(),
+                quantity: 2,
+                note: None,
+            },
+            Line {
+                sku: "B,2".into(),
+                quantity: 10,
+                note: Some("gift".into()),
+            },
+        ]
+    }
+
+    #[test]
+    fn encodes_records_in_field_order() {
+        let csv = encode(&CsvCodec, &lines()).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "sku,quantity,note\nA-1,2,\n\"B,2\",10,gift\n"
+        );
+    }
+
+    #[test]
+    fn decodes_typed_records() {
+        let body = b"sku,quantity,note\nA-1,2,\n\"B,2\",10,gift\n";
+        let decoded: Vec<Line> = decode(&CsvCodec, body).unwrap();
+        assert_eq!(decoded, lines());
+
+        let single: Line = decode(&CsvCodec, b"sku,quantity,note\nA-1,2,\n").unwrap();
+        assert_eq!(single, lines().remove(0));
+
+        let err = decode::<Vec<Line>>(&CsvCodec, b"sku,quantity,note\nA-1,many,\n").unwrap_err();
+        assert!(
+            err.to_string().contains("invalid u32 value 'many'"),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,574 @@
+//! Content negotiation: `#[produces]` / `#[consumes]`, the [`Negotiated`]
+//! responder and the [`Body`] extractor.
+//!
+//! ```ignore
+//! use r2e::negotiation::{Body, Negotiated};
+//!
+//! #[routes]
+//! impl ReportController {
+//!     #[get("/reports")]
+//!     #[produces("application/json", "text/csv")]
+//!     async fn list(&self) -> Negotiated<Vec<Report>> { ... }
+//!
+//!     #[post("/reports")]
+//!     #[consumes("application/json", "application/x-www-form-urlencoded")]
+//!     async fn create(&self, Body(report): Body<NewReport>) -> Json<Report> { ... }
+//! }
+//! ```
+//!
+//! The `#[routes]` macro wraps a route declaring either attribute in a
+//! negotiation layer. It answers `406 Not Acceptable` when the `Accept`
+//! header matches none of the produced media types, and `415 Unsupported
+//! Media Type` when the `Content-Type` is not consumed. The produced media
+//! types are tried in declaration order, so the first one is the default
+//! for `Accept: */*` or a missing `Accept`.
+//!
+//! Encoding and decoding go through the [`MediaTypeCodec`]s of the
+//! [`MediaTypeCodecs`] bean — JSON and `application/x-www-form-urlencoded`
+//! built in, `text/csv` and `application/cbor` behind the `csv` and `cbor`
+//! features. Provide your own registry to add formats:
+//!
+//! ```ignore
+//! AppBuilder::new()
+//!     .provide(MediaTypeCodecs::default().with(YamlCodec))
+//! ```
+//!
+//! Without the attributes, [`Negotiated<T>`] renders JSON and [`Body<T>`]
+//! reads JSON, like `Json<T>`.
+
+mod codecs;
+#[cfg(feature = "csv")]
+mod csv;
+
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::beans::BeanContext;
+use crate::http::extract::{FromRequest, Request};
+use crate::http::header::{HeaderValue, VARY};
+use crate::http::middleware::{from_fn, Next};
+use crate::http::response::{IntoResponse, Response};
+use crate::http::routing::MethodRouter;
+use crate::http::{Bytes, StatusCode, ACCEPT, CONTENT_LENGTH, CONTENT_TYPE};
+use crate::meta::ErrorResponseInfo;
+use crate::HttpError;
+
+#[cfg(feature = "csv")]
+pub use self::csv::CsvCodec;
+#[cfg(feature = "cbor")]
+pub use codecs::CborCodec;
+pub use codecs::{FormCodec, JsonCodec};
+
+/// Error returned by a [`MediaTypeCodec`].
+pub type CodecError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Reads and writes one media type.
+///
+/// Codecs work on serde data: [`encode`](Self::encode) receives a value that
+/// implements `Serialize`, and [`decode`](Self::decode) hands a serde
+/// `Deserializer` over the body to the handler's type.
+///
+/// ```ignore
+/// struct YamlCodec;
+///
+/// impl MediaTypeCodec for YamlCodec {
+///     fn media_type(&self) -> &str {
+///         "application/yaml"
+///     }
+///
+///     fn encode(&self, value: &Encode<'_>) -> Result<Vec<u8>, CodecError> {
+///         Ok(serde_yaml::to_string(value)?.into_bytes())
+///     }
+///
+///     fn decode(&self, body: &[u8], target: &mut Decode<'_>) -> Result<(), CodecError> {
+///         target.deserialize(serde_yaml::Deserializer::from_slice(body))
+///     }
+/// }
+/// ```
+pub trait MediaTypeCodec: Send + Sync + 'static {
+    /// The media type, lowercase and without parameters (e.g. `"text/csv"`).
+    fn media_type(&self) -> &str;
+
+    /// Serialize a response body.
+    fn encode(&self, value: &Encode<'_>) -> Result<Vec<u8>, CodecError>;
+
+    /// Deserialize a request body by passing a deserializer over it to
+    /// [`Decode::deserialize`].
+    fn decode(&self, body: &[u8], target: &mut Decode<'_>) -> Result<(), CodecError>;
+}
+
+/// The value a [`MediaTypeCodec`] encodes. Implements `Serialize`.
+pub struct Encode<'a>(&'a dyn erased_serde::Serialize);
+
+impl Serialize for Encode<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        erased_serde::serialize(self.0, serializer)
+    }
+}
+
+type DecodeSink<'a> = dyn for<'de> FnMut(&mut dyn erased_serde::Deserializer<'de>) -> Result<(), erased_serde::Error>
+    + 'a;
+
+/// The type a [`MediaTypeCodec`] decodes into.
+pub struct Decode<'a> {
+    sink: &'a mut DecodeSink<'a>,
+}
+
+impl Decode<'_> {
+    /// Deserialize the target type from `deserializer`.
+    pub fn deserialize<'de, D>(&mut self, deserializer: D) -> Result<(), CodecError>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mut erased = <dyn erased_serde::Deserializer>::erase(deserializer);
+        (self.sink)(&mut erased).map_err(Into::into)
+    }
+}
+
+/// Encode `value` with `codec`.
+pub fn encode<T: Serialize>(codec: &dyn MediaTypeCodec, value: &T) -> Result<Vec<u8>, CodecError> {
+    codec.encode(&Encode(value))
+}
+
+/// Decode a `T` from `body` with `codec`.
+pub fn decode<T: DeserializeOwned>(
+    codec: &dyn MediaTypeCodec,
+    body: &[u8],
+) -> Result<T, CodecError> {
+    let mut decoded = None;
+    let mut sink = |deserializer: &mut dyn erased_serde::Deserializer<'_>| {
+        decoded = Some(erased_serde::deserialize::<T>(deserializer)?);
+        Ok(())
+    };
+    codec.decode(body, &mut Decode { sink: &mut sink })?;
+    decoded.ok_or_else(|| format!("the {} codec decoded nothing", codec.media_type()).into())
+}
+
+/// The codecs available to `#[produces]` / `#[consumes]` routes, looked up by
+/// media type.
+///
+/// Routes use the `MediaTypeCodecs` bean when the app provides one, the
+/// [`default`](Self::default) set otherwise.
+#[derive(Clone)]
+pub struct MediaTypeCodecs {
+    codecs: Arc<Vec<Arc<dyn MediaTypeCodec>>>,
+}
+
+impl MediaTypeCodecs {
+    /// A registry without any codec.
+    pub fn empty() -> Self {
+        Self {
+            codecs: Arc::new(Vec::new()),
+        }
+    }
+
+    /// Add `codec`, replacing any codec for the same media type.
+    pub fn with(mut self, codec: impl MediaTypeCodec) -> Self {
+        let codec: Arc<dyn MediaTypeCodec> = Arc::new(codec);
+        let codecs = Arc::make_mut(&mut self.codecs);
+        match codecs
+            .iter_mut()
+            .find(|c| c.media_type().eq_ignore_ascii_case(codec.media_type()))
+        {
+            Some(existing) => *existing = codec,
+            None => codecs.push(codec),
+        }
+        self
+    }
+
+    /// The codec for `media_type` (parameters such as `charset` are ignored).
+    pub fn get(&self, media_type: &str) -> Option<Arc<dyn MediaTypeCodec>> {
+        let essence = essence(media_type);
+        self.codecs
+            .iter()
+            .find(|c| c.media_type().eq_ignore_ascii_case(essence))
+            .cloned()
+    }
+
+    /// The registered media types, in registration order.
+    pub fn media_types(&self) -> impl Iterator<Item = &str> {
+        self.codecs.iter().map(|c| c.media_type())
+    }
+}
+
+impl Default for MediaTypeCodecs {
+    /// JSON and form codecs, plus CSV and CBOR when the `csv` / `cbor`
+    /// features are enabled.
+    fn default() -> Self {
+        let codecs = Self::empty().with(JsonCodec).with(FormCodec);
+        #[cfg(feature = "csv")]
+        let codecs = codecs.with(CsvCodec);
+        #[cfg(feature = "cbor")]
+        let codecs = codecs.with(CborCodec);
+        codecs
+    }
+}
+
+impl std::fmt::Debug for MediaTypeCodecs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.media_types()).finish()
+    }
+}
+
+tokio::task_local! {
+    /// The codec chosen from `Accept` for the running `#[produces]` route.
+    static RESPONSE_CODEC: Arc<dyn MediaTypeCodec>;
+}
+
+/// A response body encoded in the media type negotiated from the `Accept`
+/// header among the route's `#[produces]` types. JSON on routes without
+/// `#[produces]`.
+///
+/// Responds `500` when encoding fails (e.g. a nested value in a CSV row).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Negotiated<T>(pub T);
+
+impl<T: Serialize> IntoResponse for Negotiated<T> {
+    fn into_response(self) -> Response {
+        let codec = RESPONSE_CODEC
+            .try_with(Arc::clone)
+            .unwrap_or_else(|_| Arc::new(JsonCodec));
+        match encode(codec.as_ref(), &self.0) {
+            Ok(bytes) => {
+                let content_type = HeaderValue::from_str(codec.media_type())
+                    .unwrap_or_else(|_| HeaderValue::from_static("application/octet-stream"));
+                ([(CONTENT_TYPE, content_type)], bytes).into_response()
+            }
+            Err(error) => {
+                tracing::error!(
+                    media_type = codec.media_type(),
+                    error = %error,
+                    "failed to encode negotiated response"
+                );
+                HttpError::internal("Failed to encode the response").into_response()
+            }
+        }
+    }
+}
+
+/// The codec matching the request `Content-Type`, set by the negotiation
+/// layer of a `#[consumes]` route.
+#[derive(Clone)]
+struct RequestCodec(Arc<dyn MediaTypeCodec>);
+
+/// A request body decoded by the codec of its `Content-Type`, among the
+/// route's `#[consumes]` types. JSON only on routes without `#[consumes]`.
+///
+/// Responds `415` for an unsupported `Content-Type` and `400` when the body
+/// does not decode. Like `Json<T>`, the inner value is validated when it
+/// derives `garde::Validate`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Body<T>(pub T);
+
+impl<T, S> FromRequest<S> for Body<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let codec = match req.extensions().get::<RequestCodec>() {
+            Some(RequestCodec(codec)) => codec.clone(),
+            None => match content_type(&req) {
+                Some(ct) if is_json(ct) => Arc::new(JsonCodec),
+                _ => {
+                    return Err(HttpError::from_status(
+                        StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                        "Expected request with `Content-Type: application/json`",
+                    )
+                    .into_response())
+                }
+            },
+        };
+        let bytes = Bytes::from_request(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        decode(codec.as_ref(), &bytes).map(Body).map_err(|error| {
+            HttpError::bad_request(format!(
+                "Failed to parse the request body as {}: {error}",
+                codec.media_type()
+            ))
+            .into_response()
+        })
+    }
+}
+
+/// The `406` / `415` responses a route with these attributes can produce.
+pub fn error_responses(produces: &[&str], consumes: &[&str]) -> Vec<ErrorResponseInfo> {
+    let mut errors = Vec::new();
+    if !produces.is_empty() {
+        errors.push(ErrorResponseInfo::new(406, "Not acceptable"));
+    }
+    if !consumes.is_empty() {
+        errors.push(ErrorResponseInfo::new(415, "Unsupported media type"));
+    }
+    errors
+}
+
+/// Wrap a `#[produces]` / `#[consumes]` route in the negotiation layer.
+/// Every produced media type needs a codec in the `MediaTypeCodecs` bean;
+/// a missing one aborts startup. Used by `#[routes]`-generated code.
+#[doc(hidden)]
+pub fn __apply<S>(
+    ctx: &BeanContext,
+    route: &str,
+    produces: &[&str],
+    consumes: &[&str],
+    router: MethodRouter<S>,
+) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let codecs = ctx.try_get::<MediaTypeCodecs>().unwrap_or_default();
+    let produces = produces
+        .iter()
+        .map(|media_type| {
+            codecs.get(media_type).unwrap_or_else(|| {
+                panic!(
+                    "route `{route}`: no MediaTypeCodec registered for #[produces(\"{media_type}\")] \
+                     — add one to the MediaTypeCodecs bean"
+                )
+            })
+        })
+        .collect();
+    // A consumed type without a codec is still accepted: the handler may read
+    // it with another extractor (e.g. `TypedMultipart`).
+    let consumes = consumes
+        .iter()
+        .map(|media_type| (media_type.to_string(), codecs.get(media_type)))
+        .collect();
+    let negotiator = Arc::new(Negotiator { produces, consumes });
+    router.layer(from_fn(move |req: Request, next: Next| {
+        let negotiator = negotiator.clone();
+        async move { negotiator.run(req, next).await }
+    }))
+}
+
+struct Negotiator {
+    produces: Vec<Arc<dyn MediaTypeCodec>>,
+    consumes: Vec<(String, Option<Arc<dyn MediaTypeCodec>>)>,
+}
+
+impl Negotiator {
+    async fn run(&self, mut req: Request, next: Next) -> Response {
+        if !self.consumes.is_empty() {
+            match content_type(&req) {
+                Some(ct) => {
+                    let essence = essence(ct);
+                    match self
+                        .consumes
+                        .iter()
+                        .find(|(media_type, _)| media_type.eq_ignore_ascii_case(essence))
+                    {
+                        Some((_, Some(codec))) => {
+                            req.extensions_mut().insert(RequestCodec(codec.clone()));
+                        }
+                        Some((_, None)) => {}
+                        None => return self.unsupported(&format!("`{essence}`")),
+                    }
+                }
+                None if has_body(&req) => return self.unsupported("a body without Content-Type"),
+                None => {}
+            }
+        }
+
+        if self.produces.is_empty() {
+            return next.run(req).await;
+        }
+        let accept = req
+            .headers()
+            .get_all(ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .collect::<Vec<_>>()
+            .join(",");
+        let Some(codec) = negotiate(&accept, &self.produces) else {
+            let offered: Vec<&str> = self.produces.iter().map(|c| c.media_type()).collect();
+            return HttpError::from_status(
+                StatusCode::NOT_ACCEPTABLE,
+                format!(
+                    "Not acceptable: this endpoint produces {}",
+                    offered.join(", ")
+                ),
+            )
+            .into_response();
+        };
+        let mut response = RESPONSE_CODEC.scope(codec.clone(), next.run(req)).await;
+        response
+            .headers_mut()
+            .append(VARY, HeaderValue::from_static("accept"));
+        response
+    }
+
+    fn unsupported(&self, what: &str) -> Response {
+        let consumed: Vec<&str> = self.consumes.iter().map(|(m, _)| m.as_str()).collect();
+        HttpError::from_status(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            format!(
+                "Unsupported media type {what}: this endpoint consumes {}",
+                consumed.join(", ")
+            ),
+        )
+        .into_response()
+    }
+}
+
+/// Pick the offered codec the `Accept` header prefers: highest quality
+/// first, then declaration order. An empty header accepts the first one.
+fn negotiate<'a>(
+    accept: &str,
+    offered: &'a [Arc<dyn MediaTypeCodec>],
+) -> Option<&'a Arc<dyn MediaTypeCodec>> {
+    let ranges: Vec<(&str, f32)> = accept
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let media_range = parts.next()?.trim();
+            if media_range.is_empty() {
+                return None;
+            }
+            let mut quality = 1.0;
+            for param in parts {
+                if let Some((name, value)) = param.split_once('=') {
+                    if name.trim().eq_ignore_ascii_case("q") {
+                        quality = value.trim().parse::<f32>().ok()?.clamp(0.0, 1.0);
+                    }
+                }
+            }
+            Some((media_range, quality))
+        })
+        .collect();
+    if ranges.is_empty() {
+        return offered.first();
+    }
+
+    let mut best: Option<(&Arc<dyn MediaTypeCodec>, f32)> = None;
+    for codec in offered {
+        // The most specific matching range sets the quality.
+        let quality = ranges
+            .iter()
+            .filter_map(|(range, q)| specificity(range, codec.media_type()).map(|s| (s, *q)))
+            .max_by(|(a, qa), (b, qb)| a.cmp(b).then(qa.total_cmp(qb)))
+            .map(|(_, q)| q)
+            .unwrap_or(0.0);
+        if quality > 0.0 && best.is_none_or(|(_, q)| quality > q) {
+            best = Some((codec, quality));
+        }
+    }
+    best.map(|(codec, _)| codec)
+}
+
+/// How specifically `range` (`*/*`, `type/*` or `type/subtype`) matches
+/// `media_type`, or `None` when it does not.
+fn specificity(range: &str, media_type: &str) -> Option<u8> {
+    if range == "*/*" {
+        return Some(0);
+    }
+    let (range_type, range_subtype) = range.split_once('/')?;
+    let (media_type, subtype) = media_type.split_once('/')?;
+    if !range_type.eq_ignore_ascii_case(media_type) {
+        return None;
+    }
+    if range_subtype == "*" {
+        Some(1)
+    } else if range_subtype.eq_ignore_ascii_case(subtype) {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+fn content_type(req: &Request) -> Option<&str> {
+    req.headers().get(CONTENT_TYPE)?.to_str().ok()
+}
+
+/// The media type without parameters: `text/csv; charset=utf-8` → `text/csv`.
+fn essence(media_type: &str) -> &str {
+    media_type.split(';').next().unwrap_or_default().trim()
+}
+
+fn is_json(content_type: &str) -> bool {
+    let essence = essence(content_type);
+    essence.eq_ignore_ascii_case("application/json")
+        || essence.to_ascii_lowercase().ends_with("+json")
+}
+
+fn has_body(req: &Request) -> bool {
+    match req.headers().get(CONTENT_LENGTH) {
+        Some(length) => length.to_str().map_or(true, |length| length.trim() != "0"),
+        None => req.headers().contains_key("transfer-encoding"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offered(media_types: &[&str]) -> Vec<Arc<dyn MediaTypeCodec>> {
+        let codecs = MediaTypeCodecs::empty()
+            .with(JsonCodec)
+            .with(FormCodec)
+            .with(Named("text/csv"));
+        media_types.iter().map(|m| codecs.get(m).unwrap()).collect()
+    }
+
+    struct Named(&'static str);
+
+    impl MediaTypeCodec for Named {
+        fn media_type(&self) -> &str {
+            self.0
+        }
+        fn encode(&self, _: &Encode<'_>) -> Result<Vec<u8>, CodecError> {
+            Ok(Vec::new())
+        }
+        fn decode(&self, _: &[u8], _: &mut Decode<'_>) -> Result<(), CodecError> {
+            Ok(())
+        }
+    }
+
+    fn chosen(accept: &str, media_types: &[&str]) -> Option<String> {
+        let offered = offered(media_types);
+        negotiate(accept, &offered).map(|c| c.media_type().to_string())
+    }
+
+    #[test]
+    fn negotiates_by_quality_then_declaration_order() {
+        let json_csv = ["application/json", "text/csv"];
+        assert_eq!(chosen("", &json_csv).as_deref(), Some("application/json"));
+        assert_eq!(
+            chosen("*/*", &json_csv).as_deref(),
+            Some("application/json")
+        );
+        assert_eq!(chosen("text/csv", &json_csv).as_deref(), Some("text/csv"));
+        assert_eq!(chosen("text/*", &json_csv).as_deref(), Some("text/csv"));
+        assert_eq!(
+            chosen("application/json;q=0.5, text/csv", &json_csv).as_deref(),
+            Some("text/csv")
+        );
+        assert_eq!(
+            chosen("text/csv;q=0, */*", &json_csv).as_deref(),
+            Some("application/json")
+        );
+        assert_eq!(chosen("application/xml", &json_csv), None);
+        assert_eq!(chosen("*/*;q=0", &json_csv), None);
+    }
+
+    #[test]
+    fn registry_lookup_ignores_case_and_parameters() {
+        let codecs = MediaTypeCodecs::default();
+        assert!(codecs.get("Application/JSON; charset=utf-8").is_some());
+        assert!(codecs.get("application/xml").is_none());
+
+        let replaced = codecs.with(Named("application/json"));
+        assert_eq!(
+            replaced
+                .media_types()
+                .filter(|m| *m == "application/json")
+                .count(),
+            1
+        );
+    }
+}
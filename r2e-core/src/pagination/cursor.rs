@@ -0,0 +1,722 @@
+//! Keyset (cursor) pagination.
+//!
+//! Offset pagination scans and discards `page * size` rows and shifts when
+//! rows are inserted concurrently. Keyset pagination instead continues from
+//! the sort key of the last row seen: `WHERE (created_at, id) > (?, ?)`.
+//!
+//! - [`CursorPageable`] extracts `?cursor=...&limit=...`;
+//! - [`CursorCodec`] signs and verifies the opaque cursors, so clients cannot
+//!   forge positions;
+//! - [`KeysetSort`] describes the sort key; the data backends turn it and a
+//!   [`CursorPosition`] into a `WHERE` clause
+//!   (`r2e_data_sqlx::push_keyset`, `r2e_data_diesel::keyset_filter`);
+//! - [`CursorPage<T>`] carries the rows and the `next` / `prev` cursors, and
+//!   sends them as RFC 8288 `Link` headers.
+//!
+//! ```ignore
+//! #[get("/")]
+//! async fn list(&self, pageable: CursorPageable) -> Result<CursorPage<User>, HttpError> {
+//!     let sort = KeysetSort::new().asc("created_at").asc("id");
+//!     let position = pageable.position(&self.cursors)?;
+//!     let users = self.users.list(&sort, position.as_ref(), pageable.fetch_limit()).await?;
+//!     Ok(CursorPage::new(users, &pageable, position.as_ref(), &self.cursors, |user| {
+//!         (user.created_at, user.id)
+//!     }))
+//! }
+//! ```
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::Sha256;
+
+use crate::http::extract::FromRequestParts;
+use crate::http::header::{HeaderValue, Parts, LINK};
+use crate::http::response::{IntoResponse, Response};
+use crate::http::Json;
+use crate::meta::{ParamInfo, ParamLocation};
+use crate::params::{parse_query_string, ParamError, ParamsMetadata};
+use crate::HttpError;
+
+/// Limit used when the request has no `limit` parameter (capped at `MAX`).
+pub const DEFAULT_CURSOR_LIMIT: u32 = 20;
+
+/// Keyset pagination parameters: `?cursor=<opaque>&limit=<n>`.
+///
+/// `MAX` is the largest accepted `limit` (default 100); a `limit` of 0 or
+/// above `MAX` is rejected with `400 Bad Request`. The cursor is kept as sent
+/// and verified by [`position`](Self::position).
+#[derive(Debug, Clone)]
+pub struct CursorPageable<const MAX: u32 = 100> {
+    /// The signed cursor from the previous page, if any.
+    pub cursor: Option<String>,
+    pub limit: u32,
+    /// Request path and the query pairs other than `cursor` / `limit`, the
+    /// base of the `Link` urls.
+    link_base: String,
+}
+
+impl<const MAX: u32> CursorPageable<MAX> {
+    /// A first page of `limit` rows, for calls outside a request.
+    pub fn new(limit: u32) -> Self {
+        Self {
+            cursor: None,
+            limit: limit.min(MAX),
+            link_base: String::new(),
+        }
+    }
+
+    /// Rows to fetch: one more than `limit`, to tell whether another page
+    /// follows.
+    pub fn fetch_limit(&self) -> u32 {
+        self.limit.saturating_add(1)
+    }
+
+    /// Verify and decode the cursor. `None` for the first page.
+    ///
+    /// A cursor that is malformed or was not signed by `codec` is a
+    /// `400 Bad Request`.
+    pub fn position(&self, codec: &CursorCodec) -> Result<Option<CursorPosition>, HttpError> {
+        self.cursor
+            .as_deref()
+            .map(|cursor| {
+                codec
+                    .decode(cursor)
+                    .ok_or_else(|| HttpError::bad_request("invalid pagination cursor"))
+            })
+            .transpose()
+    }
+}
+
+impl<const MAX: u32> Default for CursorPageable<MAX> {
+    fn default() -> Self {
+        Self::new(DEFAULT_CURSOR_LIMIT)
+    }
+}
+
+impl<S: Send + Sync, const MAX: u32> FromRequestParts<S> for CursorPageable<MAX> {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let mut cursor = None;
+        let mut limit = None;
+        let mut others = form_urlencoded::Serializer::new(String::new());
+        for (key, value) in parse_query_string(parts.uri.query()) {
+            match key.as_str() {
+                "cursor" => cursor = Some(value),
+                "limit" => limit = Some(value),
+                _ => {
+                    others.append_pair(&key, &value);
+                }
+            }
+        }
+
+        let limit = match limit {
+            None => DEFAULT_CURSOR_LIMIT.min(MAX),
+            Some(raw) => match raw.parse::<u32>() {
+                Ok(limit) if (1..=MAX).contains(&limit) => limit,
+                _ => {
+                    return Err(ParamError {
+                        message: format!(
+                        "Invalid query parameter 'limit': expected an integer between 1 and {MAX}"
+                    ),
+                    }
+                    .into_response())
+                }
+            },
+        };
+
+        let others = others.finish();
+        let link_base = if others.is_empty() {
+            format!("{}?", parts.uri.path())
+        } else {
+            format!("{}?{others}&", parts.uri.path())
+        };
+        Ok(Self {
+            cursor: cursor.filter(|cursor| !cursor.is_empty()),
+            limit,
+            link_base,
+        })
+    }
+}
+
+impl<const MAX: u32> ParamsMetadata for CursorPageable<MAX> {
+    fn param_infos() -> Vec<ParamInfo> {
+        vec![
+            ParamInfo {
+                name: "cursor".to_string(),
+                location: ParamLocation::Query,
+                param_type: "string".to_string(),
+                required: false,
+                schema: None,
+                description: Some(
+                    "Opaque position from the `next` or `prev` link of the previous page; \
+                     omit for the first page"
+                        .to_string(),
+                ),
+                example: None,
+                style: None,
+                explode: None,
+            },
+            ParamInfo {
+                name: "limit".to_string(),
+                location: ParamLocation::Query,
+                param_type: "integer".to_string(),
+                required: false,
+                schema: Some(json!({
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": MAX,
+                    "default": DEFAULT_CURSOR_LIMIT.min(MAX),
+                })),
+                description: Some("Rows per page".to_string()),
+                example: None,
+                style: None,
+                explode: None,
+            },
+        ]
+    }
+}
+
+/// Sort direction of a [`KeysetSort`] column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+impl SortDirection {
+    pub fn as_sql(self) -> &'static str {
+        match self {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        }
+    }
+
+    fn reversed(self) -> Self {
+        match self {
+            SortDirection::Asc => SortDirection::Desc,
+            SortDirection::Desc => SortDirection::Asc,
+        }
+    }
+}
+
+/// One column of a [`KeysetSort`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeysetColumn {
+    pub name: String,
+    pub direction: SortDirection,
+    /// SQL type the cursor value is cast to, e.g. `timestamptz`.
+    pub cast: Option<String>,
+}
+
+/// The sort key of a keyset-paginated query, most significant column first.
+///
+/// The columns must identify a row — end with a unique column such as the
+/// primary key — and hold no `NULL`s. Column names are written into the SQL
+/// as given: they must come from code, not from the request.
+///
+/// ```ignore
+/// KeysetSort::new().desc("created_at").cast("timestamptz").desc("id")
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeysetSort {
+    columns: Vec<KeysetColumn>,
+}
+
+impl KeysetSort {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn asc(self, column: impl Into<String>) -> Self {
+        self.column(column.into(), SortDirection::Asc)
+    }
+
+    pub fn desc(self, column: impl Into<String>) -> Self {
+        self.column(column.into(), SortDirection::Desc)
+    }
+
+    /// Cast the cursor value of the last added column to `sql_type`.
+    ///
+    /// Cursor values are bound as JSON scalars: strings, integers, floats or
+    /// booleans. A column of another type, such as a PostgreSQL timestamp,
+    /// needs the cast to compare with the bound text.
+    pub fn cast(mut self, sql_type: impl Into<String>) -> Self {
+        let sql_type = sql_type.into();
+        assert_identifier(&sql_type.replace(' ', "_"));
+        self.columns
+            .last_mut()
+            .expect("KeysetSort::cast called before adding a column")
+            .cast = Some(sql_type);
+        self
+    }
+
+    fn column(mut self, name: String, direction: SortDirection) -> Self {
+        assert_identifier(&name);
+        self.columns.push(KeysetColumn {
+            name,
+            direction,
+            cast: None,
+        });
+        self
+    }
+
+    pub fn columns(&self) -> &[KeysetColumn] {
+        &self.columns
+    }
+
+    /// The `ORDER BY` list (without the keywords) to fetch the page at
+    /// `position`: the sort itself, or its reverse when paging backwards.
+    pub fn order_by(&self, position: Option<&CursorPosition>) -> String {
+        let backwards = position.is_some_and(|position| position.direction == Direction::Before);
+        self.columns
+            .iter()
+            .map(|column| {
+                let direction = if backwards {
+                    column.direction.reversed()
+                } else {
+                    column.direction
+                };
+                format!("{} {}", column.name, direction.as_sql())
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// The keyset condition for `position`, as a list of alternatives — the
+    /// `OR` of `AND`s a backend renders:
+    ///
+    /// `(a > ?) OR (a = ? AND b > ?) OR ...`
+    ///
+    /// Fails if the cursor does not carry one scalar per column.
+    pub fn conditions<'a>(
+        &'a self,
+        position: &'a CursorPosition,
+    ) -> Result<Vec<Vec<KeysetComparison<'a>>>, HttpError> {
+        if position.keys.len() != self.columns.len()
+            || position
+                .keys
+                .iter()
+                .any(|key| key.is_null() || key.is_array() || key.is_object())
+        {
+            return Err(HttpError::bad_request("invalid pagination cursor"));
+        }
+        let backwards = position.direction == Direction::Before;
+        Ok((0..self.columns.len())
+            .map(|last| {
+                self.columns[..=last]
+                    .iter()
+                    .zip(&position.keys)
+                    .enumerate()
+                    .map(|(index, (column, value))| {
+                        let operator = if index < last {
+                            "="
+                        } else {
+                            match (column.direction, backwards) {
+                                (SortDirection::Asc, false) | (SortDirection::Desc, true) => ">",
+                                (SortDirection::Asc, true) | (SortDirection::Desc, false) => "<",
+                            }
+                        };
+                        KeysetComparison {
+                            column,
+                            operator,
+                            value,
+                        }
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+/// `column operator value`, one term of a keyset condition.
+#[derive(Debug, Clone, Copy)]
+pub struct KeysetComparison<'a> {
+    pub column: &'a KeysetColumn,
+    /// `=`, `<` or `>`.
+    pub operator: &'static str,
+    /// A string, number or boolean.
+    pub value: &'a Value,
+}
+
+fn assert_identifier(name: &str) {
+    assert!(
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.'),
+        "keyset column `{name}` must be a plain SQL identifier"
+    );
+}
+
+/// Which side of the cursor row a page lies on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    /// Rows after the cursor, in sort order (`next`).
+    #[serde(rename = "a")]
+    After,
+    /// Rows before the cursor (`prev`).
+    #[serde(rename = "b")]
+    Before,
+}
+
+/// A decoded cursor: the sort key of a boundary row and the direction to
+/// page in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CursorPosition {
+    #[serde(rename = "d")]
+    pub direction: Direction,
+    /// One value per [`KeysetSort`] column.
+    #[serde(rename = "k")]
+    pub keys: Vec<Value>,
+}
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Signs and verifies cursors with HMAC-SHA256.
+///
+/// A cursor is `base64url(json) "." base64url(signature)`. Share the key
+/// between all instances of the app, and keep it out of the source:
+///
+/// ```ignore
+/// #[bean]
+/// impl UserService {
+///     pub fn new(#[config("pagination.cursor-secret")] secret: String) -> Self {
+///         Self { cursors: CursorCodec::new(secret) }
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct CursorCodec {
+    key: std::sync::Arc<[u8]>,
+}
+
+impl CursorCodec {
+    pub fn new(key: impl AsRef<[u8]>) -> Self {
+        Self {
+            key: key.as_ref().into(),
+        }
+    }
+
+    fn mac(&self) -> HmacSha256 {
+        HmacSha256::new_from_slice(&self.key).expect("HMAC accepts keys of any length")
+    }
+
+    pub fn encode(&self, position: &CursorPosition) -> String {
+        let payload = serde_json::to_vec(position).expect("cursor positions serialize");
+        let mut mac = self.mac();
+        mac.update(&payload);
+        let signature = mac.finalize().into_bytes();
+        format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(&payload),
+            URL_SAFE_NO_PAD.encode(signature)
+        )
+    }
+
+    /// The position in `cursor`, or `None` if it is malformed or its
+    /// signature does not match.
+    pub fn decode(&self, cursor: &str) -> Option<CursorPosition> {
+        let (payload, signature) = cursor.split_once('.')?;
+        let payload = URL_SAFE_NO_PAD.decode(payload).ok()?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).ok()?;
+        let mut mac = self.mac();
+        mac.update(&payload);
+        mac.verify_slice(&signature).ok()?;
+        serde_json::from_slice(&payload).ok()
+    }
+}
+
+impl std::fmt::Debug for CursorCodec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CursorCodec").finish_non_exhaustive()
+    }
+}
+
+/// A page of keyset-paginated results.
+///
+/// Serializes as `{"content": [...], "limit": n, "next": "...", "prev": "..."}`.
+/// Returned from a handler, it also sets a `Link` header with the `next` and
+/// `prev` urls (RFC 8288), keeping the request's other query parameters.
+#[derive(Debug, Clone, Serialize)]
+pub struct CursorPage<T> {
+    pub content: Vec<T>,
+    pub limit: u32,
+    /// Cursor of the following page, `None` on the last page.
+    pub next: Option<String>,
+    /// Cursor of the preceding page, `None` on the first page.
+    pub prev: Option<String>,
+    #[serde(skip)]
+    link_base: String,
+}
+
+impl<T> CursorPage<T> {
+    /// Build the page from the rows fetched for `position`.
+    ///
+    /// `rows` holds up to [`fetch_limit`](CursorPageable::fetch_limit) rows
+    /// in the order of [`KeysetSort::order_by`]; the extra row only tells
+    /// that another page follows. `key` returns a row's sort key, one value
+    /// per sort column — usually a tuple.
+    pub fn new<K, F, const MAX: u32>(
+        mut rows: Vec<T>,
+        pageable: &CursorPageable<MAX>,
+        position: Option<&CursorPosition>,
+        codec: &CursorCodec,
+        key: F,
+    ) -> Self
+    where
+        K: Serialize,
+        F: Fn(&T) -> K,
+    {
+        let limit = pageable.limit as usize;
+        let more = rows.len() > limit;
+        rows.truncate(limit);
+        let backwards = position.is_some_and(|position| position.direction == Direction::Before);
+        if backwards {
+            rows.reverse();
+        }
+
+        let cursor = |row: Option<&T>, direction| {
+            row.map(|row| {
+                codec.encode(&CursorPosition {
+                    direction,
+                    keys: key_values(key(row)),
+                })
+            })
+        };
+        // Paging forward, a previous page exists when we came from a cursor;
+        // paging backward, a next page always does.
+        let (has_next, has_prev) = if backwards {
+            (true, more)
+        } else {
+            (more, position.is_some())
+        };
+        Self {
+            next: has_next
+                .then(|| cursor(rows.last(), Direction::After))
+                .flatten(),
+            prev: has_prev
+                .then(|| cursor(rows.first(), Direction::Before))
+                .flatten(),
+            content: rows,
+            limit: pageable.limit,
+            link_base: pageable.link_base.clone(),
+        }
+    }
+
+    /// The RFC 8288 `Link` header value, `None` without a `next` or `prev`
+    /// page.
+    pub fn link_header(&self) -> Option<String> {
+        let links: Vec<String> = [("next", &self.next), ("prev", &self.prev)]
+            .into_iter()
+            .filter_map(|(rel, cursor)| {
+                cursor.as_ref().map(|cursor| {
+                    format!(
+                        "<{}cursor={cursor}&limit={}>; rel=\"{rel}\"",
+                        self.link_base, self.limit
+                    )
+                })
+            })
+            .collect();
+        (!links.is_empty()).then(|| links.join(", "))
+    }
+}
+
+fn key_values<K: Serialize>(key: K) -> Vec<Value> {
+    match serde_json::to_value(key).expect("cursor keys serialize to JSON") {
+        Value::Array(values) => values,
+        value => vec![value],
+    }
+}
+
+impl<T: Serialize> IntoResponse for CursorPage<T> {
+    fn into_response(self) -> Response {
+        let link = self.link_header();
+        let mut response = Json(self).into_response();
+        if let Some(value) = link.and_then(|link| HeaderValue::from_str(&link).ok()) {
+            response.headers_mut().insert(LINK, value);
+        }
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::StatusCode;
+
+    async fn extract(uri: &str) -> Result<CursorPageable<50>, Response> {
+        let (mut parts, ()) = crate::http::Request::get(uri)
+            .body(())
+            .unwrap()
+            .into_parts();
+        CursorPageable::<50>::from_request_parts(&mut parts, &()).await
+    }
+
+    fn sort() -> KeysetSort {
+        KeysetSort::new().desc("created_at").asc("id")
+    }
+
+    #[test]
+    fn codec_rejects_tampered_and_foreign_cursors() {
+        let codec = CursorCodec::new("secret");
+        let position = CursorPosition {
+            direction: Direction::After,
+            keys: vec!["2024-01-01".into(), 7.into()],
+        };
+        let cursor = codec.encode(&position);
+        assert_eq!(codec.decode(&cursor), Some(position));
+
+        let forged = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(br#"{"d":"a","k":["2024-01-01",1]}"#),
+            cursor.split_once('.').unwrap().1
+        );
+        assert_eq!(codec.decode(&forged), None);
+        assert_eq!(CursorCodec::new("other").decode(&cursor), None);
+        assert_eq!(codec.decode("garbage"), None);
+    }
+
+    #[test]
+    fn conditions_expand_the_keyset_per_direction() {
+        let sort = sort();
+        let render = |direction| {
+            let position = CursorPosition {
+                direction,
+                keys: vec!["t".into(), 3.into()],
+            };
+            sort.conditions(&position)
+                .unwrap()
+                .iter()
+                .map(|terms| {
+                    terms
+                        .iter()
+                        .map(|term| {
+                            format!("{} {} {}", term.column.name, term.operator, term.value)
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" AND ")
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            render(Direction::After),
+            ["created_at < \"t\"", "created_at = \"t\" AND id > 3"]
+        );
+        assert_eq!(
+            render(Direction::Before),
+            ["created_at > \"t\"", "created_at = \"t\" AND id < 3"]
+        );
+        assert_eq!(sort.order_by(None), "created_at DESC, id ASC");
+        let before = CursorPosition {
+            direction: Direction::Before,
+            keys: vec!["t".into(), 3.into()],
+        };
+        assert_eq!(sort.order_by(Some(&before)), "created_at ASC, id DESC");
+
+        let short = CursorPosition {
+            direction: Direction::After,
+            keys: vec![3.into()],
+        };
+        assert!(sort.conditions(&short).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "plain SQL identifier")]
+    fn rejects_non_identifier_columns() {
+        KeysetSort::new().asc("id; DROP TABLE users");
+    }
+
+    #[tokio::test]
+    async fn extractor_validates_limit_and_keeps_other_parameters() {
+        let pageable = extract("/users?status=active&limit=10&cursor=abc")
+            .await
+            .unwrap();
+        assert_eq!(pageable.limit, 10);
+        assert_eq!(pageable.cursor.as_deref(), Some("abc"));
+        assert_eq!(pageable.link_base, "/users?status=active&");
+        assert_eq!(extract("/users").await.unwrap().limit, 20);
+
+        for uri in ["/users?limit=0", "/users?limit=51", "/users?limit=ten"] {
+            let rejected = extract(uri).await.unwrap_err();
+            assert_eq!(rejected.status(), StatusCode::BAD_REQUEST);
+        }
+
+        let codec = CursorCodec::new("secret");
+        let error = pageable.position(&codec).unwrap_err();
+        assert_eq!(error.into_response().status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn documents_the_limit_bounds() {
+        let infos = CursorPageable::<50>::param_infos();
+        assert!(infos[0].description.is_some());
+        assert_eq!(
+            infos[1].schema,
+            Some(json!({ "type": "integer", "minimum": 1, "maximum": 50, "default": 20 }))
+        );
+    }
+
+    #[tokio::test]
+    async fn pages_forward_and_back_with_links() {
+        let codec = CursorCodec::new("secret");
+        let sort = KeysetSort::new().asc("id");
+
+        // First page: ids 1..=3 plus the extra row telling that more follow.
+        let first_request = extract("/items?limit=3").await.unwrap();
+        let first = CursorPage::new(vec![1, 2, 3, 4], &first_request, None, &codec, |id| *id);
+        assert_eq!(first.content, [1, 2, 3]);
+        assert!(first.prev.is_none());
+        let next = codec.decode(first.next.as_deref().unwrap()).unwrap();
+        assert_eq!(next.keys, [Value::from(3)]);
+        assert_eq!(
+            first.link_header().unwrap(),
+            format!(
+                "</items?cursor={}&limit=3>; rel=\"next\"",
+                first.next.as_ref().unwrap()
+            )
+        );
+
+        // Second (last) page.
+        let second_request = extract(&format!("/items?limit=3&cursor={}", first.next.unwrap()))
+            .await
+            .unwrap();
+        let position = second_request.position(&codec).unwrap();
+        assert_eq!(sort.order_by(position.as_ref()), "id ASC");
+        let second = CursorPage::new(
+            vec![4, 5],
+            &second_request,
+            position.as_ref(),
+            &codec,
+            |id| *id,
+        );
+        assert!(second.next.is_none());
+        let prev = codec.decode(second.prev.as_deref().unwrap()).unwrap();
+        assert_eq!(prev.direction, Direction::Before);
+        assert_eq!(prev.keys, [Value::from(4)]);
+
+        // Back from the second page: rows arrive in reverse order.
+        let back_request = extract(&format!("/items?limit=3&cursor={}", second.prev.unwrap()))
+            .await
+            .unwrap();
+        let position = back_request.position(&codec).unwrap();
+        assert_eq!(sort.order_by(position.as_ref()), "id DESC");
+        let back = CursorPage::new(
+            vec![3, 2, 1],
+            &back_request,
+            position.as_ref(),
+            &codec,
+            |id| *id,
+        );
+        assert_eq!(back.content, [1, 2, 3]);
+        assert!(back.prev.is_none());
+        assert!(back.next.is_some());
+
+        let response = back.into_response();
+        let link = response.headers()[LINK].to_str().unwrap();
+        assert!(link.ends_with("&limit=3>; rel=\"next\""), "{link}");
+    }
+}
@@ -0,0 +1,660 @@
+//! Whitelisted sort and filter parameters for list endpoints.
+//!
+//! The allowed fields are declared on a DTO with `#[derive(QueryFields)]`:
+//!
+//! ```ignore
+//! #[derive(Serialize, QueryFields)]
+//! #[serde(rename_all = "camelCase")]
+//! pub struct User {
+//!     #[sortable]
+//!     #[filterable(ops = "eq,in")]
+//!     pub status: String,
+//!     #[sortable]
+//!     #[filterable(ops = "lt,gte", cast = "timestamptz")]
+//!     pub created_at: String,
+//!     #[sortable(column = "u.name")]
+//!     pub name: String,
+//! }
+//! ```
+//!
+//! [`Sort<User>`] then extracts `?sort=-createdAt,name` and [`Filter<User>`]
+//! extracts `?filter[status][in]=active,blocked&filter[createdAt][lt]=2024-01-01`.
+//! Both are handler parameters on their own or `#[params]` fields of a
+//! `#[derive(Params)]` struct. Unknown fields, operators that were not
+//! allowed and unparseable values are rejected with a `400` listing every
+//! offending parameter (`HttpError::Validation`).
+//!
+//! Only the declared column names ever reach the SQL; values are bound by
+//! the data backends (`r2e_data_sqlx::push_filter`,
+//! `r2e_data_diesel::field_filter`).
+
+use std::marker::PhantomData;
+
+use serde_json::{json, Value};
+
+use super::SortDirection;
+use crate::http::extract::FromRequestParts;
+use crate::http::header::Parts;
+use crate::http::response::{IntoResponse, Response};
+use crate::meta::{ParamInfo, ParamLocation};
+use crate::params::{parse_query_string, prefixed_key, ParamsMetadata, PrefixedExtract};
+use crate::validation::{FieldError, ValidationErrorResponse};
+use crate::HttpError;
+
+/// The sortable and filterable fields of a DTO.
+///
+/// Implemented by `#[derive(QueryFields)]`.
+pub trait QueryFields {
+    /// Fields accepted by [`Sort`].
+    const SORT: &'static [SortField];
+    /// Fields accepted by [`Filter`].
+    const FILTER: &'static [FilterField];
+}
+
+/// A field clients may sort by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortField {
+    /// Name in the query string.
+    pub name: &'static str,
+    /// SQL column (or expression) to order by.
+    pub column: &'static str,
+}
+
+/// A field clients may filter on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FilterField {
+    /// Name in the query string.
+    pub name: &'static str,
+    /// SQL column compared against the bound value.
+    pub column: &'static str,
+    pub kind: FieldKind,
+    /// Allowed operators; the first one applies to `filter[name]=value`.
+    pub ops: &'static [FilterOp],
+    /// SQL type the bound value is cast to, e.g. `timestamptz`.
+    pub cast: Option<&'static str>,
+}
+
+/// How filter values are parsed and bound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    String,
+    Integer,
+    Number,
+    Boolean,
+}
+
+impl FieldKind {
+    /// The OpenAPI type name.
+    pub fn openapi_type(self) -> &'static str {
+        match self {
+            FieldKind::String => "string",
+            FieldKind::Integer => "integer",
+            FieldKind::Number => "number",
+            FieldKind::Boolean => "boolean",
+        }
+    }
+
+    fn parse(self, raw: &str) -> Option<Value> {
+        match self {
+            FieldKind::String => Some(Value::String(raw.to_owned())),
+            FieldKind::Integer => raw.parse::<i64>().ok().map(Value::from),
+            FieldKind::Number => raw.parse::<f64>().ok().map(Value::from),
+            FieldKind::Boolean => raw.parse::<bool>().ok().map(Value::Bool),
+        }
+    }
+}
+
+/// A filter comparison operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    /// Membership in a comma-separated list.
+    In,
+}
+
+impl FilterOp {
+    /// Every operator, in declaration order.
+    pub const ALL: &'static [FilterOp] = &[
+        FilterOp::Eq,
+        FilterOp::Ne,
+        FilterOp::Lt,
+        FilterOp::Lte,
+        FilterOp::Gt,
+        FilterOp::Gte,
+        FilterOp::In,
+    ];
+
+    /// Name in the query string: `filter[field][<name>]`.
+    pub fn name(self) -> &'static str {
+        match self {
+            FilterOp::Eq => "eq",
+            FilterOp::Ne => "ne",
+            FilterOp::Lt => "lt",
+            FilterOp::Lte => "lte",
+            FilterOp::Gt => "gt",
+            FilterOp::Gte => "gte",
+            FilterOp::In => "in",
+        }
+    }
+
+    /// The SQL operator.
+    pub fn as_sql(self) -> &'static str {
+        match self {
+            FilterOp::Eq => "=",
+            FilterOp::Ne => "<>",
+            FilterOp::Lt => "<",
+            FilterOp::Lte => "<=",
+            FilterOp::Gt => ">",
+            FilterOp::Gte => ">=",
+            FilterOp::In => "IN",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|op| op.name() == name)
+    }
+}
+
+fn rejection(errors: Vec<FieldError>) -> Response {
+    HttpError::Validation(ValidationErrorResponse { errors }).into_response()
+}
+
+fn field_error(field: impl Into<String>, code: &str, message: String) -> FieldError {
+    FieldError {
+        field: field.into(),
+        message,
+        code: code.to_string(),
+    }
+}
+
+// ── Sort ────────────────────────────────────────────────────────────────
+
+/// One validated `ORDER BY` term.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortOrder {
+    pub field: &'static str,
+    pub column: &'static str,
+    pub direction: SortDirection,
+}
+
+/// Validated `?sort=-createdAt,name` parameter, restricted to the
+/// `#[sortable]` fields of `T`.
+///
+/// A leading `-` sorts descending. The parameter may also be repeated
+/// (`?sort=-createdAt&sort=name`).
+pub struct Sort<T> {
+    orders: Vec<SortOrder>,
+    _fields: PhantomData<fn() -> T>,
+}
+
+impl<T> Sort<T> {
+    /// The requested orders, first to last.
+    pub fn orders(&self) -> &[SortOrder] {
+        &self.orders
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    /// `created_at DESC, name ASC`, or `None` when no sort was requested.
+    pub fn order_by(&self) -> Option<String> {
+        if self.orders.is_empty() {
+            return None;
+        }
+        let terms: Vec<String> = self
+            .orders
+            .iter()
+            .map(|order| format!("{} {}", order.column, order.direction.as_sql()))
+            .collect();
+        Some(terms.join(", "))
+    }
+}
+
+impl<T: QueryFields> Sort<T> {
+    /// Validate sort specs such as `-createdAt,name` against `T::SORT`.
+    pub fn parse<'a>(specs: impl IntoIterator<Item = &'a str>) -> Result<Self, Vec<FieldError>> {
+        let mut orders = Vec::new();
+        let mut errors = Vec::new();
+        for term in specs.into_iter().flat_map(|spec| spec.split(',')) {
+            let term = term.trim();
+            let (name, direction) = match term.strip_prefix('-') {
+                Some(name) => (name, SortDirection::Desc),
+                None => (term.strip_prefix('+').unwrap_or(term), SortDirection::Asc),
+            };
+            if name.is_empty() {
+                continue;
+            }
+            match T::SORT.iter().find(|field| field.name == name) {
+                Some(field) if !orders.iter().any(|o: &SortOrder| o.field == field.name) => {
+                    orders.push(SortOrder {
+                        field: field.name,
+                        column: field.column,
+                        direction,
+                    });
+                }
+                Some(_) => {}
+                None => errors.push(field_error(
+                    "sort",
+                    "unknown_field",
+                    format!("cannot sort by '{name}'"),
+                )),
+            }
+        }
+        if errors.is_empty() {
+            Ok(Self {
+                orders,
+                _fields: PhantomData,
+            })
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl<T> Default for Sort<T> {
+    fn default() -> Self {
+        Self {
+            orders: Vec::new(),
+            _fields: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for Sort<T> {
+    fn clone(&self) -> Self {
+        Self {
+            orders: self.orders.clone(),
+            _fields: PhantomData,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Sort<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Sort").field(&self.orders).finish()
+    }
+}
+
+impl<S: Send + Sync, T: QueryFields> PrefixedExtract<S> for Sort<T> {
+    async fn extract_prefixed(
+        parts: &mut Parts,
+        _state: &S,
+        prefix: &str,
+    ) -> Result<Self, Response> {
+        let key = prefixed_key(prefix, "sort");
+        let pairs = parse_query_string(parts.uri.query());
+        let specs = pairs
+            .iter()
+            .filter(|(k, _)| k.as_str() == key.as_ref())
+            .map(|(_, v)| v.as_str());
+        Self::parse(specs).map_err(rejection)
+    }
+}
+
+impl<S: Send + Sync, T: QueryFields> FromRequestParts<S> for Sort<T> {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        <Self as PrefixedExtract<S>>::extract_prefixed(parts, state, "").await
+    }
+}
+
+impl<T: QueryFields> ParamsMetadata for Sort<T> {
+    fn param_infos() -> Vec<ParamInfo> {
+        let values: Vec<String> = T::SORT
+            .iter()
+            .flat_map(|field| [field.name.to_string(), format!("-{}", field.name)])
+            .collect();
+        vec![ParamInfo {
+            name: "sort".to_string(),
+            location: ParamLocation::Query,
+            param_type: "array".to_string(),
+            required: false,
+            schema: Some(json!({
+                "type": "array",
+                "items": { "type": "string", "enum": values },
+            })),
+            description: None,
+            example: None,
+            style: Some("form".to_string()),
+            explode: Some(false),
+        }]
+    }
+}
+
+// ── Filter ──────────────────────────────────────────────────────────────
+
+/// One validated filter condition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterCondition {
+    pub field: &'static str,
+    pub column: &'static str,
+    pub op: FilterOp,
+    /// A string, number or boolean; an array of them for [`FilterOp::In`].
+    pub value: Value,
+    pub cast: Option<&'static str>,
+}
+
+/// Validated `?filter[field][op]=value` parameters, restricted to the
+/// `#[filterable]` fields of `T` and their operators.
+///
+/// `filter[field]=value` uses the field's first operator. `in` takes a
+/// comma-separated list (or a repeated parameter). All conditions apply
+/// (`AND`).
+pub struct Filter<T> {
+    conditions: Vec<FilterCondition>,
+    _fields: PhantomData<fn() -> T>,
+}
+
+impl<T> Filter<T> {
+    pub fn conditions(&self) -> &[FilterCondition] {
+        &self.conditions
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.conditions.is_empty()
+    }
+}
+
+impl<T: QueryFields> Filter<T> {
+    /// Validate `(key, value)` query pairs against `T::FILTER`. Keys other
+    /// than `{prefix}filter[...]` are ignored.
+    pub fn parse<'a>(
+        pairs: impl IntoIterator<Item = (&'a str, &'a str)>,
+        prefix: &str,
+    ) -> Result<Self, Vec<FieldError>> {
+        let start = prefixed_key(prefix, "filter[");
+        let mut conditions: Vec<FilterCondition> = Vec::new();
+        let mut errors = Vec::new();
+        for (key, raw) in pairs {
+            let Some(rest) = key.strip_prefix(start.as_ref()) else {
+                continue;
+            };
+            let Some((name, op)) = parse_filter_key(rest) else {
+                errors.push(field_error(
+                    key,
+                    "invalid_filter",
+                    "expected filter[field] or filter[field][op]".to_string(),
+                ));
+                continue;
+            };
+            let Some(field) = T::FILTER.iter().find(|field| field.name == name) else {
+                errors.push(field_error(
+                    key,
+                    "unknown_field",
+                    format!("cannot filter on '{name}'"),
+                ));
+                continue;
+            };
+            let op = match op {
+                None => field.ops.first().copied(),
+                Some(op) => FilterOp::from_name(op).filter(|op| field.ops.contains(op)),
+            };
+            let Some(op) = op else {
+                let allowed: Vec<&str> = field.ops.iter().map(|op| op.name()).collect();
+                errors.push(field_error(
+                    key,
+                    "unsupported_operator",
+                    format!("'{name}' supports {}", allowed.join(", ")),
+                ));
+                continue;
+            };
+
+            let value = if op == FilterOp::In {
+                raw.split(',')
+                    .map(|item| field.kind.parse(item.trim()))
+                    .collect::<Option<Vec<_>>>()
+                    .map(Value::Array)
+            } else {
+                field.kind.parse(raw)
+            };
+            let Some(value) = value else {
+                errors.push(field_error(
+                    key,
+                    "invalid_value",
+                    format!("expected {}", field.kind.openapi_type()),
+                ));
+                continue;
+            };
+
+            let existing = conditions
+                .iter_mut()
+                .find(|c| c.field == field.name && c.op == FilterOp::In && op == FilterOp::In);
+            match (existing, value) {
+                (Some(condition), Value::Array(items)) => {
+                    if let Value::Array(values) = &mut condition.value {
+                        values.extend(items);
+                    }
+                }
+                (_, value) => conditions.push(FilterCondition {
+                    field: field.name,
+                    column: field.column,
+                    op,
+                    value,
+                    cast: field.cast,
+                }),
+            }
+        }
+        if errors.is_empty() {
+            Ok(Self {
+                conditions,
+                _fields: PhantomData,
+            })
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Split `name]` or `name][op]` (what follows `filter[`).
+fn parse_filter_key(rest: &str) -> Option<(&str, Option<&str>)> {
+    let (name, tail) = rest.split_once(']')?;
+    if name.is_empty() {
+        return None;
+    }
+    if tail.is_empty() {
+        return Some((name, None));
+    }
+    let op = tail.strip_prefix('[')?.strip_suffix(']')?;
+    Some((name, Some(op)))
+}
+
+impl<T> Default for Filter<T> {
+    fn default() -> Self {
+        Self {
+            conditions: Vec::new(),
+            _fields: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for Filter<T> {
+    fn clone(&self) -> Self {
+        Self {
+            conditions: self.conditions.clone(),
+            _fields: PhantomData,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Filter<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Filter").field(&self.conditions).finish()
+    }
+}
+
+impl<S: Send + Sync, T: QueryFields> PrefixedExtract<S> for Filter<T> {
+    async fn extract_prefixed(
+        parts: &mut Parts,
+        _state: &S,
+        prefix: &str,
+    ) -> Result<Self, Response> {
+        let pairs = parse_query_string(parts.uri.query());
+        let pairs = pairs.iter().map(|(k, v)| (k.as_str(), v.as_str()));
+        Self::parse(pairs, prefix).map_err(rejection)
+    }
+}
+
+impl<S: Send + Sync, T: QueryFields> FromRequestParts<S> for Filter<T> {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        <Self as PrefixedExtract<S>>::extract_prefixed(parts, state, "").await
+    }
+}
+
+impl<T: QueryFields> ParamsMetadata for Filter<T> {
+    fn param_infos() -> Vec<ParamInfo> {
+        let mut infos = Vec::new();
+        for field in T::FILTER {
+            let item = json!({ "type": field.kind.openapi_type() });
+            for (index, op) in field.ops.iter().enumerate() {
+                let schema = if *op == FilterOp::In {
+                    json!({ "type": "array", "items": item })
+                } else {
+                    item.clone()
+                };
+                let name = if index == 0 {
+                    format!("filter[{}]", field.name)
+                } else {
+                    format!("filter[{}][{}]", field.name, op.name())
+                };
+                let is_list = *op == FilterOp::In;
+                infos.push(ParamInfo {
+                    name,
+                    location: ParamLocation::Query,
+                    param_type: schema["type"].as_str().unwrap_or("string").to_string(),
+                    required: false,
+                    schema: Some(schema),
+                    description: None,
+                    example: None,
+                    style: is_list.then(|| "form".to_string()),
+                    explode: is_list.then_some(false),
+                });
+            }
+        }
+        infos
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct User;
+
+    impl QueryFields for User {
+        const SORT: &'static [SortField] = &[
+            SortField {
+                name: "createdAt",
+                column: "created_at",
+            },
+            SortField {
+                name: "name",
+                column: "name",
+            },
+        ];
+        const FILTER: &'static [FilterField] = &[
+            FilterField {
+                name: "status",
+                column: "status",
+                kind: FieldKind::String,
+                ops: &[FilterOp::Eq, FilterOp::In],
+                cast: None,
+            },
+            FilterField {
+                name: "age",
+                column: "age",
+                kind: FieldKind::Integer,
+                ops: &[FilterOp::Gte, FilterOp::Lt],
+                cast: None,
+            },
+        ];
+    }
+
+    #[test]
+    fn parses_sort_specs() {
+        let sort = Sort::<User>::parse(["-createdAt,name", "name"]).unwrap();
+        assert_eq!(
+            sort.order_by().as_deref(),
+            Some("created_at DESC, name ASC")
+        );
+        assert!(Sort::<User>::parse([""]).unwrap().order_by().is_none());
+
+        let errors = Sort::<User>::parse(["name,password;--"]).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, "unknown_field");
+    }
+
+    #[test]
+    fn parses_filters_with_default_and_explicit_ops() {
+        let filter = Filter::<User>::parse(
+            [
+                ("filter[status][in]", "active,blocked"),
+                ("filter[status][in]", "new"),
+                ("filter[age]", "18"),
+                ("page", "2"),
+            ],
+            "",
+        )
+        .unwrap();
+        let conditions = filter.conditions();
+        assert_eq!(conditions.len(), 2);
+        assert_eq!(conditions[0].op, FilterOp::In);
+        assert_eq!(conditions[0].value, json!(["active", "blocked", "new"]));
+        assert_eq!(conditions[1].op, FilterOp::Gte);
+        assert_eq!(conditions[1].value, json!(18));
+    }
+
+    #[test]
+    fn rejects_every_invalid_filter() {
+        let errors = Filter::<User>::parse(
+            [
+                ("filter[password]", "x"),
+                ("filter[age][in]", "1,2"),
+                ("filter[age][lt]", "old"),
+                ("filter[status", "x"),
+            ],
+            "",
+        )
+        .unwrap_err();
+        let codes: Vec<&str> = errors.iter().map(|e| e.code.as_str()).collect();
+        assert_eq!(
+            codes,
+            [
+                "unknown_field",
+                "unsupported_operator",
+                "invalid_value",
+                "invalid_filter"
+            ]
+        );
+    }
+
+    #[test]
+    fn documents_allowed_values() {
+        let sort = Sort::<User>::param_infos();
+        assert_eq!(
+            sort[0].schema.as_ref().unwrap()["items"]["enum"],
+            json!(["createdAt", "-createdAt", "name", "-name"])
+        );
+        let names: Vec<String> = Filter::<User>::param_infos()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "filter[status]",
+                "filter[status][in]",
+                "filter[age]",
+                "filter[age][lt]"
+            ]
+        );
+    }
+}
@@ -1,6 +1,21 @@
+//! Offset pagination ([`Pageable`] / [`Page<T>`]), keyset pagination
+//! ([`CursorPageable`] / [`CursorPage<T>`], see [`cursor`]) and whitelisted
+//! sort and filter parameters ([`Sort<T>`] / [`Filter<T>`], see [`fields`]).
+
 use r2e_macros::Params;
 use serde::{Deserialize, Serialize};
 
+pub mod cursor;
+pub mod fields;
+
+pub use cursor::{
+    CursorCodec, CursorPage, CursorPageable, CursorPosition, KeysetSort, SortDirection,
+};
+pub use fields::{
+    FieldKind, Filter, FilterCondition, FilterField, FilterOp, QueryFields, Sort, SortField,
+    SortOrder,
+};
+
 /// Zero-based pagination parameters extracted from query parameters.
 #[derive(Debug, Clone, Deserialize, Params)]
 pub struct Pageable {
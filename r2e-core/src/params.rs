@@ -1,5 +1,5 @@
 use crate::http::response::{IntoResponse, Response};
-use crate::http::{Json, StatusCode};
+use crate::http::StatusCode;
 
 /// Error type for parameter extraction failures in `#[derive(Params)]`.
 #[derive(Debug)]
@@ -15,8 +15,7 @@ impl std::fmt::Display for ParamError {
 
 impl IntoResponse for ParamError {
     fn into_response(self) -> Response {
-        let body = serde_json::json!({ "error": self.message });
-        (StatusCode::BAD_REQUEST, Json(body)).into_response()
+        crate::error::error_response(StatusCode::BAD_REQUEST, self.message)
     }
 }
 
@@ -36,9 +35,23 @@ pub fn parse_query_string(query: Option<&str>) -> Vec<(String, String)> {
     }
 }
 
+/// Find a cookie by name in the request's `Cookie` headers.
+pub fn cookie_value<'a>(headers: &'a crate::http::HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(crate::http::header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .find_map(|pair| {
+            let (key, value) = pair.trim().split_once('=')?;
+            (key == name).then_some(value)
+        })
+}
+
 // ── ParamsMetadata: OpenAPI parameter metadata for #[derive(Params)] ──
 
-use crate::meta::ParamInfo;
+use crate::meta::{ParamInfo, ParamLocation};
+use serde_json::Value;
 
 /// Trait for types that expose parameter metadata for OpenAPI spec generation.
 /// Auto-implemented by `#[derive(Params)]`.
@@ -70,6 +83,103 @@ pub trait __NoParamsMeta {
 
 impl<T> __NoParamsMeta for &__ParamMetaProbe<T> {}
 
+/// Probe serializing a default or example value when its type is `Serialize`.
+#[doc(hidden)]
+pub struct __ValueProbe<'a, T>(pub &'a T);
+
+impl<T: serde::Serialize> __ValueProbe<'_, T> {
+    pub fn value(&self) -> Option<Value> {
+        serde_json::to_value(self.0).ok()
+    }
+}
+
+#[doc(hidden)]
+pub trait __NoValue {
+    fn value(&self) -> Option<Value> {
+        None
+    }
+}
+
+impl<T> __NoValue for &__ValueProbe<'_, T> {}
+
+/// Assemble a parameter schema: the type's JSON schema (or the static
+/// `fallback` when it has none), plus validation `constraints` and `default`.
+#[doc(hidden)]
+pub fn __param_schema(
+    schema: Option<Value>,
+    fallback: Value,
+    constraints: Value,
+    default: Option<Value>,
+) -> Value {
+    let mut schema = match schema {
+        Some(Value::Object(mut obj)) => {
+            obj.remove("$schema");
+            obj.remove("title");
+            Value::Object(obj)
+        }
+        Some(other) => other,
+        None => fallback,
+    };
+    if let Some(obj) = schema.as_object_mut() {
+        if let Value::Object(constraints) = constraints {
+            obj.extend(constraints);
+        }
+        if let Some(default) = default {
+            obj.insert("default".into(), default);
+        }
+    }
+    schema
+}
+
+/// Query parameters described by the JSON schema of a `Query<T>` type:
+/// one per property, required when listed in the schema's `required`.
+pub fn query_param_infos(schema: &Value) -> Vec<ParamInfo> {
+    let Some(Value::Object(properties)) = schema.get("properties") else {
+        return Vec::new();
+    };
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let defs = schema.get("$defs");
+    properties
+        .iter()
+        .map(|(name, property)| {
+            let mut property = property.clone();
+            let description = property
+                .as_object_mut()
+                .and_then(|obj| obj.remove("description"))
+                .and_then(|d| d.as_str().map(str::to_owned));
+            // Keep `$ref` targets resolvable; the spec builder promotes them.
+            if let (Some(defs), Some(obj)) = (defs, property.as_object_mut()) {
+                obj.insert("$defs".into(), defs.clone());
+            }
+            let param_type = match property.get("type") {
+                Some(Value::String(ty)) => ty.clone(),
+                Some(Value::Array(types)) => types
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .find(|ty| *ty != "null")
+                    .unwrap_or("string")
+                    .to_owned(),
+                _ => "string".to_owned(),
+            };
+            ParamInfo {
+                name: name.clone(),
+                location: ParamLocation::Query,
+                param_type,
+                required: required.contains(&name.as_str()),
+                schema: Some(property),
+                description,
+                example: None,
+                style: None,
+                explode: None,
+            }
+        })
+        .collect()
+}
+
 // ── PrefixedExtract: core extraction trait for nested Params support ──
 
 /// Trait for Params types that support prefixed extraction.
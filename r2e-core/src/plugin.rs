@@ -143,6 +143,23 @@ impl<'a> PluginInstallContext<'a> {
             .push(Box::new(|reg| reg.register_provided_post_construct::<B>()));
     }
 
+    /// Run `hook` during `build_state()`, once the bean graph is resolved and
+    /// **before** every post-construct hook.
+    ///
+    /// For work other beans' `#[post_construct]` hooks rely on, such as
+    /// applying database migrations. The hook reads what it needs from the
+    /// resolved context — so a pinned test override is what it sees — and
+    /// returns the future to await; an error fails `build_state()`.
+    pub fn on_resolved<F>(&mut self, hook: F)
+    where
+        F: FnOnce(&crate::beans::BeanContext) -> crate::lifecycle::LifecycleFuture<'static>
+            + Send
+            + 'static,
+    {
+        self.registry_ops
+            .push(Box::new(move |reg| reg.register_resolved_hook(hook)));
+    }
+
     /// Register a [`PreDestroy`](crate::PreDestroy) disposal hook for one of
     /// this plugin's `Provided` beans, run during graceful shutdown.
     ///
@@ -241,6 +258,41 @@ impl<'a> PluginInstallContext<'a> {
         self.sugar.push(Box::new(move |dctx| dctx.store_data(data)));
     }
 
+    /// Read the registered metadata of type `M` at `build()`. Sugar for a
+    /// [`DeferredContext::observe_meta`] call.
+    ///
+    /// Buffered; see the ordering note on [`add_deferred`](Self::add_deferred).
+    pub fn observe_meta<M, F>(&mut self, f: F)
+    where
+        M: Any + Send + Sync,
+        F: FnOnce(&[M]) + Send + Sync + 'static,
+    {
+        self.sugar
+            .push(Box::new(move |dctx| dctx.observe_meta::<M, F>(f)));
+    }
+
+    /// Contribute a health indicator to `/health` and `/health/ready`. Sugar
+    /// for a [`DeferredContext::add_health_check`] call.
+    ///
+    /// Buffered; see the ordering note on [`add_deferred`](Self::add_deferred).
+    pub fn add_health_check<H: crate::health::HealthIndicator>(&mut self, indicator: H) {
+        self.sugar
+            .push(Box::new(move |dctx| dctx.add_health_check(indicator)));
+    }
+
+    /// Contribute a section to the JSON dev status. Sugar for a
+    /// [`DeferredContext::add_dev_status`] call.
+    ///
+    /// Buffered; see the ordering note on [`add_deferred`](Self::add_deferred).
+    pub fn add_dev_status<F>(&mut self, name: impl Into<String>, section: F)
+    where
+        F: Fn() -> serde_json::Value + Send + Sync + 'static,
+    {
+        let name = name.into();
+        self.sugar
+            .push(Box::new(move |dctx| dctx.add_dev_status(name, section)));
+    }
+
     /// Add a serve hook that runs when the server starts. Sugar for a
     /// [`DeferredContext::on_serve`] call.
     ///
@@ -999,4 +1051,64 @@ impl DeferredContext<'_> {
         self.async_shutdown_hooks
             .push(Box::new(move || Box::pin(hook())));
     }
+
+    /// Read the registered metadata of type `M` (e.g.
+    /// [`RouteInfo`](crate::meta::RouteInfo)) when the app is built.
+    ///
+    /// The read-only counterpart of
+    /// [`AppBuilder::with_meta_consumer`](crate::builder::AppBuilder::with_meta_consumer)
+    /// for plugins: `f` runs once in `build()`, after every controller has
+    /// registered its metadata and before any [`add_layer`](Self::add_layer)
+    /// layer is applied — so a layer can capture a shared table that `f`
+    /// fills in (e.g. per-route settings keyed by path template).
+    pub fn observe_meta<M, F>(&mut self, f: F)
+    where
+        M: Any + Send + Sync,
+        F: FnOnce(&[M]) + Send + Sync + 'static,
+    {
+        self.plugin_data
+            .entry(std::any::TypeId::of::<crate::meta::MetaObservers>())
+            .or_insert_with(|| Box::new(crate::meta::MetaObservers::default()))
+            .downcast_mut::<crate::meta::MetaObservers>()
+            .expect("MetaObservers type mismatch in plugin_data")
+            .push::<M, F>(f);
+    }
+
+    /// Contribute a [`HealthIndicator`](crate::health::HealthIndicator) to the
+    /// advanced health plugin.
+    ///
+    /// Indicators accumulate in plugin data across plugins and are picked up
+    /// when [`Health::builder()...build()`](crate::plugins::Health::builder) is
+    /// installed (it is a post-state plugin, so it always sees them). They are
+    /// checked after the builder's own checks, by `/health` and — unless the
+    /// indicator opts out — by `/health/ready`. The simple `Health` plugin
+    /// ignores them.
+    pub fn add_health_check<H: crate::health::HealthIndicator>(&mut self, indicator: H) {
+        self.plugin_data
+            .entry(std::any::TypeId::of::<crate::health::HealthContributions>())
+            .or_insert_with(|| Box::new(crate::health::HealthContributions::default()))
+            .downcast_mut::<crate::health::HealthContributions>()
+            .expect("HealthContributions type mismatch in plugin_data")
+            .push(indicator);
+    }
+
+    /// Contribute a section to the JSON form of `/__r2e_dev/status`
+    /// (requested with `Accept: application/json`).
+    ///
+    /// `section` is evaluated on every request, so it can report state that
+    /// changes after boot. Sections are collected by the
+    /// [`DevReload`](crate::plugins::DevReload) plugin, which `prepare()`
+    /// installs after every plugin in dev-reload builds; without it they are
+    /// dropped.
+    pub fn add_dev_status<F>(&mut self, name: impl Into<String>, section: F)
+    where
+        F: Fn() -> serde_json::Value + Send + Sync + 'static,
+    {
+        self.plugin_data
+            .entry(std::any::TypeId::of::<crate::dev::DevStatusContributions>())
+            .or_insert_with(|| Box::new(crate::dev::DevStatusContributions::default()))
+            .downcast_mut::<crate::dev::DevStatusContributions>()
+            .expect("DevStatusContributions type mismatch in plugin_data")
+            .push(name.into(), section);
+    }
 }
@@ -174,9 +174,13 @@ impl AdvancedHealth {
 }
 
 impl Plugin for AdvancedHealth {
-    fn install<T: Clone + Send + Sync + 'static>(self, app: AppBuilder<T>) -> AppBuilder<T> {
+    fn install<T: Clone + Send + Sync + 'static>(self, mut app: AppBuilder<T>) -> AppBuilder<T> {
         use std::sync::Arc;
-        let state = Arc::new(crate::health::HealthState::new(self.checks, self.cache_ttl));
+        let mut checks = self.checks;
+        if let Some(contributed) = app.take_plugin_data::<crate::health::HealthContributions>() {
+            checks.extend(contributed.into_checks());
+        }
+        let state = Arc::new(crate::health::HealthState::new(checks, self.cache_ttl));
         let s1 = state.clone();
         app.register_routes(
             crate::http::Router::new()
@@ -210,7 +214,8 @@ impl Plugin for ErrorHandling {
 /// Dev-mode reload endpoints plugin.
 ///
 /// Adds `/__r2e_dev/status` and `/__r2e_dev/ping` endpoints for
-/// tooling and browser scripts to detect server restarts.
+/// tooling and browser scripts to detect server restarts. The JSON form of
+/// the status endpoint includes the sections contributed by plugins.
 ///
 /// Also adds a `Cache-Control: no-store` layer to prevent browsers
 /// from caching API responses during development (which would cause
@@ -223,7 +228,10 @@ impl Plugin for DevReload {
             return app;
         }
         app.mark_dev_reload_applied();
-        app.register_routes(crate::dev::dev_routes())
+        let sections = app
+            .take_plugin_data::<crate::dev::DevStatusContributions>()
+            .unwrap_or_default();
+        app.register_routes(crate::dev::dev_routes_with(sections))
             .with_layer_fn(|router| {
                 router.layer(crate::http::middleware::from_fn(
                     crate::dev::dev_headers_middleware,
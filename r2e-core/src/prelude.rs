@@ -44,6 +44,9 @@ pub use r2e_macros::{sse, ws};
 // Event & scheduling attributes
 pub use r2e_macros::{consumer, scheduled};
 
+// Transactions
+pub use r2e_macros::transactional;
+
 // gRPC attribute
 pub use r2e_macros::grpc_routes;
 
@@ -79,6 +82,7 @@ pub use crate::lifecycle::StopHandle;
 // a `get` method on every type, which would shadow inherent `get`s reached
 // through `Deref` (e.g. `Arc<DashMap>::get`). Import it explicitly where
 // needed: `use r2e_core::type_list::BeanAccess;`.
+pub use crate::conditional::{ETag, Preconditions, Tagged, Versioned};
 pub use crate::config::{
     ConfigError, ConfigProperties, ConfigValidationDetail, ConfigValue, FromConfigValue,
     NoChildren, PluginConfig, PropertyMeta, R2eConfig,
@@ -93,22 +97,32 @@ pub use crate::guards::{
     Guard, GuardContext, GuardError, Identity, NoIdentity, PathParam, PathParams, PreAuthGuard,
     PreAuthGuardContext,
 };
+pub use crate::i18n::{I18n, Locale, MessageKey, Messages};
+pub use crate::idempotency::{IdempotencyStore, InMemoryIdempotencyStore};
 pub use crate::interceptors::{Interceptor, InterceptorContext};
 pub use crate::managed::{
     ManagedContext, ManagedErr, ManagedOutcome, ManagedOutcomeKind, ManagedResource,
 };
 pub use crate::module::FeatureModule;
-pub use crate::pagination::{Page, Pageable};
+pub use crate::negotiation::{MediaTypeCodec, MediaTypeCodecs, Negotiated};
+pub use crate::pagination::{
+    CursorCodec, CursorPage, CursorPageable, Filter, KeysetSort, Page, Pageable, QueryFields, Sort,
+};
 pub use crate::plugin::Plugin;
 pub use crate::plugins::{
     AdvancedHealth, ConfiguredTracing, Cors, DevReload, ErrorHandling, Health, NormalizePath,
     Tracing,
 };
+pub use crate::problem::ProblemDetails;
+pub use crate::request_context::RequestContext;
 pub use crate::request_id::{RequestId, RequestIdPlugin};
 pub use crate::scheduled_source::ScheduledSource;
 pub use crate::secure_headers::SecureHeaders;
+pub use crate::session::{InMemorySessionStore, Session, SessionIdentity, SessionStore, Sessions};
+pub use crate::templating::{CsrfToken, TemplateEngine};
 pub use crate::tracing_config::{LogFormat, SpanEvents, TracingConfig};
 pub use crate::type_list::BeanLookup;
+pub use crate::versioning::{ApiVersion, ApiVersioning};
 
 // ── Type aliases ──────────────────────────────────────────────────────────
 
@@ -140,7 +154,7 @@ pub use crate::http::response::{
 pub use crate::http::middleware::{from_fn, Next};
 
 pub use crate::validation::Validate;
-pub use r2e_macros::Params;
+pub use r2e_macros::{Params, QueryFields};
 
 // SSE broadcaster + typed topics + per-key rooms
 pub use crate::sse::{
@@ -0,0 +1,220 @@
+//! RFC 9457 Problem Details — an opt-in `application/problem+json` error format.
+//!
+//! Every error response built by R2E (`HttpError`, `#[derive(ApiError)]`
+//! types, validation failures, guard and rate-limit rejections) carries a
+//! [`Problem`] describing itself as a response extension. By default the
+//! extension is ignored and the body stays `{"error": "..."}`. Installing the
+//! [`ProblemDetails`] plugin re-renders those responses as:
+//!
+//! ```json
+//! {
+//!     "type": "https://errors.example.com/user-not-found",
+//!     "title": "Not Found",
+//!     "status": 404,
+//!     "detail": "User 42 not found",
+//!     "instance": "3f2c0a7e-5d1b-4a8e-9c43-0b7f1e2d6a91"
+//! }
+//! ```
+//!
+//! `instance` is the request id (see [`RequestIdPlugin`](crate::RequestIdPlugin)),
+//! and extension members (e.g. `errors` for validation failures) are merged
+//! at the top level.
+//!
+//! # Usage
+//!
+//! ```ignore
+//! AppBuilder::new()
+//!     .build_state()
+//!     .await
+//!     .with(RequestIdPlugin)
+//!     .with(ProblemDetails)
+//!     // ...
+//! ```
+
+use serde_json::{Map, Value};
+
+use crate::builder::AppBuilder;
+use crate::http::header::{HeaderValue, CONTENT_LENGTH, CONTENT_TYPE};
+use crate::http::response::Response;
+use crate::http::{Body, StatusCode};
+use crate::plugin::Plugin;
+use crate::request_id::RequestId;
+
+/// Media type of Problem Details bodies.
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// Problem Details of an error response, attached as a response extension.
+///
+/// `type_uri` defaults to `about:blank` and `title` to the status' reason
+/// phrase when rendered.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    pub type_uri: Option<String>,
+    pub title: Option<String>,
+    pub status: StatusCode,
+    pub detail: Option<String>,
+    pub extensions: Map<String, Value>,
+}
+
+impl Problem {
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            type_uri: None,
+            title: None,
+            status,
+            detail: None,
+            extensions: Map::new(),
+        }
+    }
+
+    pub fn with_type(mut self, type_uri: impl Into<String>) -> Self {
+        self.type_uri = Some(type_uri.into());
+        self
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Add an extension member. Members named like a standard field are
+    /// dropped when rendering.
+    pub fn with_extension(mut self, key: impl Into<String>, value: Value) -> Self {
+        self.extensions.insert(key.into(), value);
+        self
+    }
+
+    /// Attach this problem to `response`, replacing any previous one.
+    pub fn attach(self, mut response: Response) -> Response {
+        response.extensions_mut().insert(self);
+        response
+    }
+
+    /// The `application/problem+json` body.
+    pub fn to_json(&self, instance: Option<&str>) -> Value {
+        let mut body = Map::new();
+        body.insert(
+            "type".into(),
+            Value::from(self.type_uri.as_deref().unwrap_or("about:blank")),
+        );
+        let title = self
+            .title
+            .as_deref()
+            .or_else(|| self.status.canonical_reason())
+            .unwrap_or("Error");
+        body.insert("title".into(), Value::from(title));
+        body.insert("status".into(), Value::from(self.status.as_u16()));
+        if let Some(detail) = &self.detail {
+            body.insert("detail".into(), Value::from(detail.as_str()));
+        }
+        if let Some(instance) = instance {
+            body.insert("instance".into(), Value::from(instance));
+        }
+        for (key, value) in &self.extensions {
+            if !body.contains_key(key) {
+                body.insert(key.clone(), value.clone());
+            }
+        }
+        Value::Object(body)
+    }
+}
+
+/// Set the `type` of the problem attached to `response`. Used by
+/// `#[derive(ApiError)]` for `#[error(type = "...")]`.
+pub fn with_problem_type(mut response: Response, type_uri: &str) -> Response {
+    if let Some(problem) = response.extensions_mut().get_mut::<Problem>() {
+        problem.type_uri = Some(type_uri.to_string());
+    }
+    response
+}
+
+/// Plugin that renders error responses as RFC 9457 Problem Details.
+///
+/// ```ignore
+/// .with(ProblemDetails)
+/// ```
+pub struct ProblemDetails;
+
+impl Plugin for ProblemDetails {
+    fn install<T: Clone + Send + Sync + 'static>(self, app: AppBuilder<T>) -> AppBuilder<T> {
+        app.with_layer_fn(|router| {
+            router.layer(crate::http::middleware::from_fn(problem_details_middleware))
+        })
+    }
+}
+
+async fn problem_details_middleware(
+    req: crate::http::Request,
+    next: crate::http::middleware::Next,
+) -> Response {
+    let request_id = req.extensions().get::<RequestId>().map(|id| id.0.clone());
+    let response = next.run(req).await;
+    let Some(problem) = response.extensions().get::<Problem>().cloned() else {
+        return response;
+    };
+
+    // The request id layer may sit inside this one; it echoes the id on
+    // the response either way.
+    let instance = request_id.or_else(|| {
+        response
+            .headers()
+            .get("x-request-id")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned)
+    });
+    render(&problem, instance.as_deref(), response)
+}
+
+fn render(problem: &Problem, instance: Option<&str>, response: Response) -> Response {
+    let (mut parts, _) = response.into_parts();
+    let bytes = serde_json::to_vec(&problem.to_json(instance))
+        .unwrap_or_else(|_| br#"{"type":"about:blank","status":500}"#.to_vec());
+    parts
+        .headers
+        .insert(CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON));
+    parts.headers.remove(CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn renders_defaults_and_extensions() {
+        let problem = Problem::new(StatusCode::NOT_FOUND)
+            .with_detail("User 42 not found")
+            .with_extension("status", json!("ignored"))
+            .with_extension("user_id", json!(42));
+
+        assert_eq!(
+            problem.to_json(Some("req-1")),
+            json!({
+                "type": "about:blank",
+                "title": "Not Found",
+                "status": 404,
+                "detail": "User 42 not found",
+                "instance": "req-1",
+                "user_id": 42,
+            })
+        );
+    }
+
+    #[test]
+    fn explicit_type_and_title_win() {
+        let problem = Problem::new(StatusCode::CONFLICT)
+            .with_type("https://errors.example.com/duplicate")
+            .with_title("Duplicate");
+
+        let body = problem.to_json(None);
+        assert_eq!(body["type"], "https://errors.example.com/duplicate");
+        assert_eq!(body["title"], "Duplicate");
+        assert!(body.get("instance").is_none());
+    }
+}
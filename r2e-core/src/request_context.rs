@@ -0,0 +1,91 @@
+//! Ambient request context — the request id and authenticated subject of the
+//! work currently running, available without threading them through every call.
+//!
+//! Handlers receive [`RequestId`](crate::RequestId) and the identity as
+//! parameters, but code they call (an event emit, a service several layers
+//! down) usually does not. [`RequestContext`] makes both reachable from
+//! anywhere inside the request's task:
+//!
+//! - the [`RequestIdPlugin`](crate::RequestIdPlugin) middleware scopes a
+//!   context carrying the request id around the rest of the stack;
+//! - identity extractors call [`RequestContext::record_subject`] once the
+//!   caller is authenticated;
+//! - the event bus reads [`RequestContext::current`] when emitting and scopes a
+//!   context rebuilt from the event metadata around each consumer, so the ids
+//!   survive a hop through a broker.
+//!
+//! The context is a tokio task-local: it does not follow [`rt::spawn`](crate::rt::spawn)
+//! into new tasks — re-scope it there with [`RequestContext::scope`] if needed.
+
+use std::future::Future;
+use std::sync::OnceLock;
+
+tokio::task_local! {
+    static CURRENT: RequestContext;
+}
+
+/// Request id and authenticated subject of the running request (or of the
+/// request that emitted the event a consumer is processing).
+#[derive(Debug, Clone, Default)]
+pub struct RequestContext {
+    request_id: Option<String>,
+    // Set-once so an extractor can record it after the middleware scoped the
+    // context, through the shared reference the task-local hands out.
+    subject: OnceLock<String>,
+}
+
+impl RequestContext {
+    /// Create an empty context.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the request id.
+    pub fn with_request_id(mut self, id: impl Into<String>) -> Self {
+        self.request_id = Some(id.into());
+        self
+    }
+
+    /// Set the authenticated subject.
+    pub fn with_subject(self, subject: impl Into<String>) -> Self {
+        let _ = self.subject.set(subject.into());
+        self
+    }
+
+    /// The request id, if any.
+    pub fn request_id(&self) -> Option<&str> {
+        self.request_id.as_deref()
+    }
+
+    /// The authenticated subject (`sub`), if any.
+    pub fn subject(&self) -> Option<&str> {
+        self.subject.get().map(String::as_str)
+    }
+
+    /// Whether the context carries neither a request id nor a subject.
+    pub fn is_empty(&self) -> bool {
+        self.request_id.is_none() && self.subject.get().is_none()
+    }
+
+    /// Run `fut` with this context as the current one.
+    pub async fn scope<F: Future>(self, fut: F) -> F::Output {
+        CURRENT.scope(self, fut).await
+    }
+
+    /// A snapshot of the current context, or `None` outside any scope.
+    pub fn current() -> Option<RequestContext> {
+        CURRENT.try_with(Clone::clone).ok()
+    }
+
+    /// Record the authenticated subject on the current context.
+    ///
+    /// No-op outside a scope, or when a subject was already recorded (the
+    /// first authenticated identity of a request wins).
+    pub fn record_subject(subject: &str) {
+        let _ = CURRENT.try_with(|ctx| {
+            if ctx.subject.get().is_none() {
+                let _ = ctx.subject.set(subject.to_owned());
+            }
+        });
+    }
+}
@@ -5,6 +5,7 @@
 //! 1. Reads `X-Request-Id` from the incoming request headers; if absent, generates a UUID v4.
 //! 2. Stores the ID as an Axum request extension (extractable in handlers).
 //! 3. Copies the ID into the response `X-Request-Id` header.
+//! 4. Scopes a [`RequestContext`] carrying the ID around the rest of the stack.
 //!
 //! # Usage
 //!
@@ -32,6 +33,7 @@ use crate::http::{HeaderName, HeaderValue};
 
 use crate::builder::AppBuilder;
 use crate::plugin::Plugin;
+use crate::request_context::RequestContext;
 
 static X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
 
@@ -84,9 +86,14 @@ async fn request_id_middleware(
         fresh_request_id()
     };
 
-    req.extensions_mut().insert(RequestId(id));
+    req.extensions_mut().insert(RequestId(id.clone()));
 
-    let mut response = next.run(req).await;
+    // Scope the ambient context so code below the handler signature (event
+    // emits in particular) can pick up the id and the authenticated subject.
+    let mut response = RequestContext::new()
+        .with_request_id(id)
+        .scope(next.run(req))
+        .await;
     response
         .headers_mut()
         .insert(X_REQUEST_ID.clone(), header_val);
@@ -0,0 +1,320 @@
+//! Per-route request limits: `#[timeout]`, `#[body_limit]`, `#[max_concurrent]`.
+//!
+//! The `#[routes]` macro wraps each HTTP route in a limit layer built from
+//! its attributes (method-level, falling back to the impl-level defaults).
+//! Every route can be tuned from config without recompiling:
+//!
+//! ```yaml
+//! r2e:
+//!   routes:
+//!     "UserController.upload":
+//!       timeout: 2m          # integer seconds or "500ms" / "30s" / "5m"
+//!       body_limit: 200MB    # integer bytes or "512KB" / "50MB" / "1GB"
+//!       max_concurrent: 4
+//! ```
+//!
+//! A config value of `0` removes the limit; for `body_limit` that also lifts
+//! axum's 2 MB default. Violations are answered through
+//! [`HttpError`]: `408` when the handler exceeds the timeout, `413` when the
+//! body exceeds the limit and `503` when all concurrency slots are taken.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::Value;
+use tokio::sync::Semaphore;
+use tokio_stream::StreamExt;
+
+use crate::beans::BeanContext;
+use crate::config::R2eConfig;
+use crate::http::body::Body;
+use crate::http::middleware::{from_fn, Next};
+use crate::http::routing::MethodRouter;
+use crate::http::{DefaultBodyLimit, IntoResponse, Request, Response, StatusCode, CONTENT_LENGTH};
+use crate::meta::ErrorResponseInfo;
+use crate::HttpError;
+
+/// Config prefix of per-route overrides: `r2e.routes.<Controller>.<method>`.
+pub const CONFIG_PREFIX: &str = "r2e.routes";
+
+/// The limits of one route. `None` means unbounded, except for
+/// `body_limit` where it leaves axum's 2 MB extractor default in place.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RouteLimits {
+    /// Maximum handler time before answering `408 Request Timeout`.
+    pub timeout: Option<Duration>,
+    /// Request body bound. Replaces axum's 2 MB default body limit for
+    /// extractors such as `Json` and `Bytes`.
+    pub body_limit: Option<BodyLimit>,
+    /// Maximum number of concurrent handler invocations before answering
+    /// `503 Service Unavailable`.
+    pub max_concurrent: Option<usize>,
+}
+
+/// The request body bound of a route.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyLimit {
+    /// At most this many bytes before answering `413 Payload Too Large`.
+    Max(usize),
+    /// No bound at all, not even axum's default — a config value of `0`.
+    Unbounded,
+}
+
+impl BodyLimit {
+    fn max(self) -> Option<usize> {
+        match self {
+            BodyLimit::Max(limit) => Some(limit),
+            BodyLimit::Unbounded => None,
+        }
+    }
+}
+
+impl RouteLimits {
+    /// Whether no limit is set.
+    pub fn is_empty(&self) -> bool {
+        self.timeout.is_none() && self.body_limit.is_none() && self.max_concurrent.is_none()
+    }
+
+    /// Apply the `r2e.routes.<route>.*` config overrides, where `route` is
+    /// `Controller.method`. Returns an error naming the key on an invalid
+    /// value.
+    pub fn with_overrides(self, config: Option<&R2eConfig>, route: &str) -> Result<Self, String> {
+        let Some(config) = config else {
+            return Ok(self);
+        };
+        let key = |field: &str| format!("{CONFIG_PREFIX}.{route}.{field}");
+        let mut limits = self;
+
+        if let Some(timeout) = config
+            .get_opt::<Duration>(&key("timeout"))
+            .map_err(|e| e.to_string())?
+        {
+            limits.timeout = (!timeout.is_zero()).then_some(timeout);
+        }
+        if let Some(raw) = config
+            .get_opt::<String>(&key("body_limit"))
+            .map_err(|e| e.to_string())?
+        {
+            let bytes = parse_size(&raw).ok_or_else(|| {
+                format!(
+                    "invalid `{}`: expected bytes or a size like \"50MB\", got '{raw}'",
+                    key("body_limit")
+                )
+            })?;
+            limits.body_limit = Some(match bytes {
+                0 => BodyLimit::Unbounded,
+                bytes => BodyLimit::Max(bytes),
+            });
+        }
+        if let Some(max) = config
+            .get_opt::<usize>(&key("max_concurrent"))
+            .map_err(|e| e.to_string())?
+        {
+            limits.max_concurrent = (max > 0).then_some(max);
+        }
+        Ok(limits)
+    }
+
+    /// OpenAPI vendor extensions describing the limits:
+    /// `x-r2e-timeout-ms`, `x-r2e-body-limit` (bytes), `x-r2e-max-concurrent`.
+    pub fn openapi_extensions(&self) -> BTreeMap<String, Value> {
+        let mut extensions = BTreeMap::new();
+        if let Some(timeout) = self.timeout {
+            extensions.insert(
+                "x-r2e-timeout-ms".to_string(),
+                Value::from(timeout.as_millis() as u64),
+            );
+        }
+        if let Some(limit) = self.body_limit.and_then(BodyLimit::max) {
+            extensions.insert("x-r2e-body-limit".to_string(), Value::from(limit));
+        }
+        if let Some(max) = self.max_concurrent {
+            extensions.insert("x-r2e-max-concurrent".to_string(), Value::from(max));
+        }
+        extensions
+    }
+
+    /// The `408` / `413` / `503` responses these limits can produce.
+    pub fn error_responses(&self) -> Vec<ErrorResponseInfo> {
+        let mut errors = Vec::new();
+        if self.timeout.is_some() {
+            errors.push(ErrorResponseInfo::new(408, "Request timed out"));
+        }
+        if self.body_limit.and_then(BodyLimit::max).is_some() {
+            errors.push(ErrorResponseInfo::new(413, "Request body too large"));
+        }
+        if self.max_concurrent.is_some() {
+            errors.push(ErrorResponseInfo::new(503, "Too many concurrent requests"));
+        }
+        errors
+    }
+
+    /// Wrap a method router in the limit layer. A no-op when no limit is set.
+    pub fn apply<S>(self, router: MethodRouter<S>) -> MethodRouter<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        if self.is_empty() {
+            return router;
+        }
+        let router = match self.body_limit {
+            Some(BodyLimit::Max(limit)) => router.layer(DefaultBodyLimit::max(limit)),
+            Some(BodyLimit::Unbounded) => router.layer(DefaultBodyLimit::disable()),
+            None => router,
+        };
+        let enforcer = Arc::new(Enforcer {
+            timeout: self.timeout,
+            body_limit: self.body_limit.and_then(BodyLimit::max),
+            permits: self.max_concurrent.map(|n| Arc::new(Semaphore::new(n))),
+        });
+        router.layer(from_fn(move |req: Request, next: Next| {
+            let enforcer = enforcer.clone();
+            async move { enforcer.run(req, next).await }
+        }))
+    }
+}
+
+/// Resolve the config overrides of `route` and wrap `router`. Invalid
+/// overrides abort startup. Used by `#[routes]`-generated code.
+#[doc(hidden)]
+pub fn __apply<S>(
+    ctx: &BeanContext,
+    route: &str,
+    limits: RouteLimits,
+    router: MethodRouter<S>,
+) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let config = ctx.try_get::<R2eConfig>();
+    match limits.with_overrides(config.as_ref(), route) {
+        Ok(limits) => limits.apply(router),
+        Err(e) => panic!("route `{route}`: {e}"),
+    }
+}
+
+/// Limits with config overrides, for route metadata. An invalid override
+/// falls back to the attribute values; [`__apply`] reports it.
+#[doc(hidden)]
+pub fn __resolve(config: Option<&R2eConfig>, route: &str, limits: RouteLimits) -> RouteLimits {
+    limits.with_overrides(config, route).unwrap_or(limits)
+}
+
+struct Enforcer {
+    timeout: Option<Duration>,
+    body_limit: Option<usize>,
+    permits: Option<Arc<Semaphore>>,
+}
+
+impl Enforcer {
+    async fn run(&self, req: Request, next: Next) -> Response {
+        // Held until the handler has produced its response.
+        let _permit = match &self.permits {
+            Some(permits) => match permits.clone().try_acquire_owned() {
+                Ok(permit) => Some(permit),
+                Err(_) => {
+                    return HttpError::from_status(
+                        StatusCode::SERVICE_UNAVAILABLE,
+                        "Too many concurrent requests",
+                    )
+                    .into_response()
+                }
+            },
+            None => None,
+        };
+
+        let (req, overflow) = match self.body_limit {
+            Some(limit) => {
+                if content_length(&req).is_some_and(|len| len > limit as u64) {
+                    return payload_too_large(limit);
+                }
+                let overflow = Arc::new(AtomicBool::new(false));
+                (limit_body(req, limit, overflow.clone()), Some(overflow))
+            }
+            None => (req, None),
+        };
+
+        let response = match self.timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, next.run(req)).await {
+                Ok(response) => response,
+                Err(_) => {
+                    return HttpError::from_status(StatusCode::REQUEST_TIMEOUT, "Request timed out")
+                        .into_response()
+                }
+            },
+            None => next.run(req).await,
+        };
+
+        // A chunked body crossed the limit while the handler read it: whatever
+        // the handler or extractor answered, report it as 413.
+        match (overflow, self.body_limit) {
+            (Some(overflow), Some(limit)) if overflow.load(Ordering::Relaxed) => {
+                payload_too_large(limit)
+            }
+            _ => response,
+        }
+    }
+}
+
+fn content_length(req: &Request) -> Option<u64> {
+    req.headers()
+        .get(CONTENT_LENGTH)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+}
+
+fn payload_too_large(limit: usize) -> Response {
+    HttpError::from_status(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        format!("Request body exceeds {limit} bytes"),
+    )
+    .into_response()
+}
+
+/// Count body bytes as they are read, failing the stream (and flagging
+/// `overflow`) once more than `limit` bytes arrived.
+fn limit_body(req: Request, limit: usize, overflow: Arc<AtomicBool>) -> Request {
+    let (parts, body) = req.into_parts();
+    let mut read = 0usize;
+    let stream = body.into_data_stream().map(move |chunk| {
+        let chunk = chunk?;
+        read = read.saturating_add(chunk.len());
+        if read > limit {
+            overflow.store(true, Ordering::Relaxed);
+            return Err(BodyLimitExceeded.into());
+        }
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>(chunk)
+    });
+    Request::from_parts(parts, Body::from_stream(stream))
+}
+
+#[derive(Debug)]
+struct BodyLimitExceeded;
+
+impl std::fmt::Display for BodyLimitExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("request body limit exceeded")
+    }
+}
+
+impl std::error::Error for BodyLimitExceeded {}
+
+/// Parse `1024`, `512KB`, `50MB`, `1GiB`... into bytes (binary units).
+fn parse_size(input: &str) -> Option<usize> {
+    let s = input.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, suffix) = s.split_at(split);
+    let number: usize = number.parse().ok()?;
+    let multiplier: usize = match suffix.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1 << 10,
+        "m" | "mb" | "mib" => 1 << 20,
+        "g" | "gb" | "gib" => 1 << 30,
+        _ => return None,
+    };
+    number.checked_mul(multiplier)
+}
@@ -0,0 +1,156 @@
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Length of an AES-GCM nonce.
+const NONCE_LEN: usize = 12;
+
+/// The `SameSite` attribute of the session cookie.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+impl SameSite {
+    fn as_str(self) -> &'static str {
+        match self {
+            SameSite::Strict => "Strict",
+            SameSite::Lax => "Lax",
+            SameSite::None => "None",
+        }
+    }
+}
+
+/// A new random session id: 32 bytes, base64url.
+pub(crate) fn new_id() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Encrypts and authenticates session ids with AES-256-GCM, so the cookie
+/// neither reveals the id nor accepts a forged or altered one.
+///
+/// The key is derived from the application secret with HMAC-SHA256, and the
+/// cookie name is bound as associated data.
+#[derive(Clone)]
+pub(crate) struct CookieCodec {
+    cipher: Aes256Gcm,
+    name: String,
+}
+
+impl CookieCodec {
+    pub(crate) fn new(secret: &[u8], name: &str) -> Self {
+        let mut mac =
+            <Hmac<Sha256> as Mac>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+        mac.update(b"r2e session cookie");
+        let key = mac.finalize().into_bytes();
+        Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)),
+            name: name.to_string(),
+        }
+    }
+
+    /// `base64url(nonce || ciphertext)` of `id`.
+    pub(crate) fn seal(&self, id: &str) -> String {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let payload = Payload {
+            msg: id.as_bytes(),
+            aad: self.name.as_bytes(),
+        };
+        let sealed = self
+            .cipher
+            .encrypt(&nonce, payload)
+            .expect("AES-GCM encrypts short ids");
+        let mut value = nonce.to_vec();
+        value.extend_from_slice(&sealed);
+        URL_SAFE_NO_PAD.encode(value)
+    }
+
+    /// The id sealed in `value`, or `None` if it is malformed or was not
+    /// sealed with this key.
+    pub(crate) fn open(&self, value: &str) -> Option<String> {
+        let bytes = URL_SAFE_NO_PAD.decode(value).ok()?;
+        if bytes.len() <= NONCE_LEN {
+            return None;
+        }
+        let (nonce, sealed) = bytes.split_at(NONCE_LEN);
+        let payload = Payload {
+            msg: sealed,
+            aad: self.name.as_bytes(),
+        };
+        let id = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), payload)
+            .ok()?;
+        String::from_utf8(id).ok()
+    }
+}
+
+/// The attributes of the session cookie.
+#[derive(Clone)]
+pub(crate) struct CookieAttributes {
+    pub(crate) name: String,
+    pub(crate) path: String,
+    pub(crate) domain: Option<String>,
+    pub(crate) secure: bool,
+    pub(crate) same_site: SameSite,
+}
+
+impl CookieAttributes {
+    /// `Set-Cookie` value holding `value`, kept until the browser closes.
+    pub(crate) fn set(&self, value: &str) -> String {
+        self.render(value, None)
+    }
+
+    /// `Set-Cookie` value deleting the cookie.
+    pub(crate) fn remove(&self) -> String {
+        self.render("", Some(0))
+    }
+
+    fn render(&self, value: &str, max_age: Option<u64>) -> String {
+        let mut cookie = format!("{}={value}; Path={}", self.name, self.path);
+        if let Some(domain) = &self.domain {
+            cookie.push_str("; Domain=");
+            cookie.push_str(domain);
+        }
+        if let Some(max_age) = max_age {
+            cookie.push_str(&format!("; Max-Age={max_age}"));
+        }
+        cookie.push_str("; HttpOnly; SameSite=");
+        cookie.push_str(self.same_site.as_str());
+        if self.secure {
+            cookie.push_str("; Secure");
+        }
+        cookie
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealed_ids_open_with_the_same_key_and_name() {
+        let codec = CookieCodec::new(&[7; 32], "session");
+        let id = new_id();
+        let value = codec.seal(&id);
+        assert!(!value.contains(&id));
+        assert_ne!(codec.seal(&id), value);
+        assert_eq!(codec.open(&value), Some(id));
+
+        assert_eq!(CookieCodec::new(&[8; 32], "session").open(&value), None);
+        assert_eq!(CookieCodec::new(&[7; 32], "other").open(&value), None);
+        let mut tampered = value.into_bytes();
+        let middle = tampered.len() / 2;
+        tampered[middle] = if tampered[middle] == b'A' { b'B' } else { b'A' };
+        assert_eq!(codec.open(std::str::from_utf8(&tampered).unwrap()), None);
+        assert_eq!(codec.open("not base64!"), None);
+    }
+}
@@ -0,0 +1,314 @@
+//! Server-side sessions: the [`Sessions`] plugin, the [`Session`]
+//! extractor, and session-backed identities.
+//!
+//! ```ignore
+//! AppBuilder::new()
+//!     .provide(InMemorySessionStore::shared())
+//!     .build_state()
+//!     .await
+//!     .with(Sessions::new(secret).idle_timeout(Duration::from_secs(1800)))
+//!     .register_controller::<AdminController>()
+//!
+//! #[routes]
+//! impl AdminController {
+//!     #[post("/login")]
+//!     async fn login(&self, session: Session, Form(form): Form<LoginForm>) -> Result<Redirect, HttpError> {
+//!         let user = self.users.authenticate(&form.email, &form.password).await?;
+//!         session.login(&user)?;
+//!         session.flash("notice", format!("Welcome back, {}", user.name));
+//!         Ok(Redirect::to("/admin"))
+//!     }
+//!
+//!     #[get("/users")]
+//!     #[roles("admin")]
+//!     async fn users(&self, user: AdminUser, session: Session) -> ... { ... }
+//! }
+//! ```
+//!
+//! The session data lives in the `Arc<dyn SessionStore>` bean — the
+//! in-process [`InMemorySessionStore`], a `CacheStore` adapter from
+//! `r2e-utils`, or the SQL store from `r2e-data-sqlx`. The browser only
+//! holds the session id, encrypted and authenticated with AES-256-GCM under
+//! the application secret.
+//!
+//! A session expires after the idle timeout without requests, and after
+//! the absolute timeout whatever its use. [`Session::renew`] gives the
+//! session a new id — [`Session::login`] does it, so an id planted before
+//! login is useless after it.
+//!
+//! Every request gets a CSRF token: the session's, or a new one. It is put
+//! in the request extensions as a [`CsrfToken`](crate::templating::CsrfToken),
+//! where `#[template]` pages find it, and returned by
+//! [`Session::csrf_token`] to check submitted forms.
+
+mod cookie;
+mod plugin;
+mod store;
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::extract::{FromRequestPartsVia, OptionalFromRequestPartsVia};
+use crate::guards::Identity;
+use crate::http::extract::{FromRequestParts, OptionalFromRequestParts};
+use crate::http::Parts;
+use crate::request_context::RequestContext;
+use crate::HttpError;
+
+pub use self::cookie::SameSite;
+pub use self::plugin::Sessions;
+pub use self::store::{InMemorySessionStore, SessionRecord, SessionStore, StoreError, StoreFuture};
+
+/// Session key of the CSRF token.
+const CSRF_KEY: &str = "_csrf";
+/// Session key of the pending flash messages.
+const FLASH_KEY: &str = "_flash";
+
+/// A one-time message for the next page, e.g. "User created".
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Flash {
+    /// Free-form category such as `notice` or `error`.
+    pub level: String,
+    pub message: String,
+}
+
+/// The session of the current request.
+///
+/// Values are stored as JSON. Changes are saved when the response is sent;
+/// a request that changes nothing only refreshes the idle timeout.
+///
+/// Without the [`Sessions`] plugin, `Session` fails with a 500 and
+/// `Option<Session>` is `None`.
+#[derive(Clone)]
+pub struct Session {
+    state: Arc<Mutex<SessionState>>,
+}
+
+struct SessionState {
+    /// `None` until a new session is first saved.
+    id: Option<String>,
+    record: SessionRecord,
+    /// Data was written by the application.
+    changed: bool,
+    renew: bool,
+    destroyed: bool,
+}
+
+impl Session {
+    fn new(id: Option<String>, record: SessionRecord) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(SessionState {
+                id,
+                record,
+                changed: false,
+                renew: false,
+                destroyed: false,
+            })),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, SessionState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// The value under `key`, or `None` if it is missing or is not a `T`.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let value = self.lock().record.data.get(key)?.clone();
+        serde_json::from_value(value).ok()
+    }
+
+    /// Store `value` under `key`, replacing the previous value.
+    pub fn insert(&self, key: impl Into<String>, value: impl Serialize) -> Result<(), HttpError> {
+        let value = serde_json::to_value(value)
+            .map_err(|e| HttpError::internal(format!("session value: {e}")))?;
+        let mut state = self.lock();
+        state.record.data.insert(key.into(), value);
+        state.changed = true;
+        Ok(())
+    }
+
+    /// Remove the value under `key` and return it, if it is a `T`.
+    pub fn remove<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let mut state = self.lock();
+        let value = state.record.data.remove(key)?;
+        state.changed = true;
+        serde_json::from_value(value).ok()
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.lock().record.data.contains_key(key)
+    }
+
+    /// Add a flash message, shown by the next call to [`flashes`](Self::flashes).
+    pub fn flash(&self, level: impl Into<String>, message: impl Into<String>) {
+        let mut state = self.lock();
+        let flashes = state
+            .record
+            .data
+            .entry(FLASH_KEY)
+            .or_insert_with(|| Value::Array(Vec::new()));
+        if let Value::Array(flashes) = flashes {
+            flashes.push(serde_json::json!({ "level": level.into(), "message": message.into() }));
+        }
+        state.changed = true;
+    }
+
+    /// Take the pending flash messages: they are removed from the session.
+    pub fn flashes(&self) -> Vec<Flash> {
+        self.remove(FLASH_KEY).unwrap_or_default()
+    }
+
+    /// The CSRF token of the session, to compare with the one a form sends.
+    pub fn csrf_token(&self) -> String {
+        self.lock()
+            .record
+            .data
+            .get(CSRF_KEY)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    /// Move the session to a new id, with a new CSRF token, when the
+    /// response is sent. Call it when the privilege level changes.
+    pub fn renew(&self) {
+        let mut state = self.lock();
+        state.renew = true;
+        state.changed = true;
+        state
+            .record
+            .data
+            .insert(CSRF_KEY.into(), Value::String(cookie::new_id()));
+    }
+
+    /// Delete the session and its cookie when the response is sent.
+    pub fn destroy(&self) {
+        self.lock().destroyed = true;
+    }
+
+    /// Sign `user` in: renew the session and store the user, read back by
+    /// extracting `U`.
+    pub fn login<U: SessionIdentity>(&self, user: &U) -> Result<(), HttpError> {
+        self.renew();
+        self.insert(U::SESSION_KEY, user)
+    }
+
+    /// Sign out: [`destroy`](Self::destroy) the session.
+    pub fn logout(&self) {
+        self.destroy();
+    }
+}
+
+impl std::fmt::Debug for Session {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Session").finish_non_exhaustive()
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Session {
+    type Rejection = HttpError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<Session>()
+            .cloned()
+            .ok_or_else(|| HttpError::internal("Session requires the Sessions plugin"))
+    }
+}
+
+impl<S: Send + Sync> OptionalFromRequestParts<S> for Session {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        Ok(parts.extensions.get::<Session>().cloned())
+    }
+}
+
+// ── Session-backed identity ────────────────────────────────────────────
+
+/// A user type signed in with [`Session::login`] and extracted from the
+/// session.
+///
+/// Implementing it makes the type an extractor: a handler or controller
+/// taking it gets the signed-in user, or a `401` without one. With
+/// `RoleBasedIdentity` from `r2e-security`, it works with `#[roles]`.
+///
+/// ```ignore
+/// #[derive(Serialize, Deserialize)]
+/// pub struct AdminUser { id: String, roles: Vec<String> }
+///
+/// impl Identity for AdminUser {
+///     fn sub(&self) -> &str { &self.id }
+/// }
+/// impl RoleBasedIdentity for AdminUser {
+///     fn roles(&self) -> &[String] { &self.roles }
+/// }
+/// impl SessionIdentity for AdminUser {}
+/// ```
+///
+/// The type must not also implement axum's `FromRequestParts`: it would
+/// have two extraction routes (see [`crate::extract`]).
+pub trait SessionIdentity: Identity + Serialize + DeserializeOwned + Send + 'static {
+    /// The session key the user is stored under.
+    const SESSION_KEY: &'static str = "_identity";
+}
+
+/// Extraction marker of [`SessionIdentity`] types.
+pub struct ViaSession;
+
+fn signed_in<U: SessionIdentity>(parts: &Parts) -> Result<Option<U>, HttpError> {
+    let session = parts
+        .extensions
+        .get::<Session>()
+        .ok_or_else(|| HttpError::internal("session identities require the Sessions plugin"))?;
+    let user = session.get::<U>(U::SESSION_KEY);
+    if let Some(user) = &user {
+        RequestContext::record_subject(user.sub());
+        if let Some(claims) = user.claims() {
+            crate::i18n::record_claims(claims);
+        }
+    }
+    Ok(user)
+}
+
+impl<S, U> FromRequestPartsVia<S, ViaSession> for U
+where
+    S: Send + Sync,
+    U: SessionIdentity,
+{
+    type Rejection = HttpError;
+
+    async fn from_request_parts_via(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        signed_in(parts)?.ok_or_else(|| HttpError::Unauthorized("Not signed in".into()))
+    }
+}
+
+impl<S, U> OptionalFromRequestPartsVia<S, ViaSession> for U
+where
+    S: Send + Sync,
+    U: SessionIdentity,
+{
+    type Rejection = HttpError;
+
+    async fn from_request_parts_via(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        match parts.extensions.get::<Session>() {
+            Some(_) => signed_in(parts),
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use super::cookie::{self, CookieAttributes, CookieCodec, SameSite};
+use super::store::{unix_millis, InMemorySessionStore, SessionRecord, SessionStore, StoreError};
+use super::{Session, CSRF_KEY};
+use crate::builder::AppBuilder;
+use crate::error::error_response;
+use crate::http::header::{HeaderValue, CONTENT_TYPE, SET_COOKIE};
+use crate::http::middleware::{from_fn, Next};
+use crate::http::response::Response;
+use crate::http::{Request, StatusCode};
+use crate::params::cookie_value;
+use crate::plugin::Plugin;
+use crate::templating::CsrfToken;
+
+/// Shortest accepted application secret.
+const MIN_SECRET_LEN: usize = 32;
+
+/// Plugin that loads the [`Session`] of each request from its cookie and
+/// saves it with the response. See the [module docs](super).
+///
+/// The store is the `Arc<dyn SessionStore>` bean, or an
+/// [`InMemorySessionStore`] when there is none.
+///
+/// ```ignore
+/// .with(
+///     Sessions::new(config.get::<String>("session.secret")?)
+///         .cookie_name("admin_session")
+///         .idle_timeout(Duration::from_secs(15 * 60))
+///         .absolute_timeout(Duration::from_secs(8 * 3600)),
+/// )
+/// ```
+pub struct Sessions {
+    secret: Vec<u8>,
+    cookie: CookieAttributes,
+    idle_timeout: Duration,
+    absolute_timeout: Duration,
+}
+
+impl Sessions {
+    /// Sessions whose cookies are sealed with `secret`, at least 32 bytes.
+    /// Share it between all instances of the app and keep it out of the
+    /// source; changing it signs everyone out.
+    ///
+    /// Defaults: cookie `session` on `/`, `Secure`, `SameSite=Lax`, 30
+    /// minutes idle timeout, 24 hours absolute timeout.
+    ///
+    /// # Panics
+    ///
+    /// If `secret` is shorter than 32 bytes.
+    pub fn new(secret: impl AsRef<[u8]>) -> Self {
+        let secret = secret.as_ref();
+        assert!(
+            secret.len() >= MIN_SECRET_LEN,
+            "Sessions: the secret must be at least {MIN_SECRET_LEN} bytes"
+        );
+        Self {
+            secret: secret.to_vec(),
+            cookie: CookieAttributes {
+                name: "session".into(),
+                path: "/".into(),
+                domain: None,
+                secure: true,
+                same_site: SameSite::Lax,
+            },
+            idle_timeout: Duration::from_secs(30 * 60),
+            absolute_timeout: Duration::from_secs(24 * 3600),
+        }
+    }
+
+    pub fn cookie_name(mut self, name: impl Into<String>) -> Self {
+        self.cookie.name = name.into();
+        self
+    }
+
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.cookie.path = path.into();
+        self
+    }
+
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        self.cookie.domain = Some(domain.into());
+        self
+    }
+
+    /// Send the cookie over HTTPS only (default). Turn it off for local
+    /// development over plain HTTP.
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.cookie.secure = secure;
+        self
+    }
+
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.cookie.same_site = same_site;
+        self
+    }
+
+    /// End sessions unused for `timeout`.
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = timeout;
+        self
+    }
+
+    /// End sessions `timeout` after they were created, even in use.
+    pub fn absolute_timeout(mut self, timeout: Duration) -> Self {
+        self.absolute_timeout = timeout;
+        self
+    }
+}
+
+/// How the plugin serves sessions.
+struct Settings {
+    store: Arc<dyn SessionStore>,
+    codec: CookieCodec,
+    cookie: CookieAttributes,
+    idle_timeout: Duration,
+    absolute_timeout: Duration,
+}
+
+impl Plugin for Sessions {
+    fn install<T: Clone + Send + Sync + 'static>(self, app: AppBuilder<T>) -> AppBuilder<T> {
+        let store = app
+            .bean_context()
+            .try_get::<Arc<dyn SessionStore>>()
+            .unwrap_or_else(InMemorySessionStore::shared);
+        let settings = Arc::new(Settings {
+            store,
+            codec: CookieCodec::new(&self.secret, &self.cookie.name),
+            cookie: self.cookie,
+            idle_timeout: self.idle_timeout,
+            absolute_timeout: self.absolute_timeout,
+        });
+        app.with_layer_fn(move |router| {
+            router.layer(from_fn(move |req: Request, next: Next| {
+                sessions_middleware(settings.clone(), req, next)
+            }))
+        })
+    }
+}
+
+impl Settings {
+    /// Whether `record` has passed a timeout at `now`.
+    fn expired(&self, record: &SessionRecord, now: u64) -> bool {
+        let idle = now.saturating_sub(record.accessed_at);
+        let age = now.saturating_sub(record.created_at);
+        idle >= self.idle_timeout.as_millis() as u64
+            || age >= self.absolute_timeout.as_millis() as u64
+    }
+
+    /// How long the store keeps `record`: until its first timeout.
+    fn ttl(&self, record: &SessionRecord, now: u64) -> Duration {
+        let age = Duration::from_millis(now.saturating_sub(record.created_at));
+        self.idle_timeout
+            .min(self.absolute_timeout.saturating_sub(age))
+    }
+
+    /// The id sealed in the request's cookie.
+    fn cookie_id(&self, req: &Request) -> Option<String> {
+        cookie_value(req.headers(), &self.cookie.name).and_then(|value| self.codec.open(value))
+    }
+
+    /// The live session `id`, if any.
+    async fn load(
+        &self,
+        id: Option<String>,
+        now: u64,
+    ) -> Result<Option<(String, SessionRecord)>, StoreError> {
+        let Some(id) = id else {
+            return Ok(None);
+        };
+        match self.store.load(&id).await? {
+            Some(record) if !self.expired(&record, now) => Ok(Some((id, record))),
+            Some(_) => {
+                self.store.delete(&id).await?;
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Save or delete the session after the handler ran, and set the cookie.
+    async fn commit(
+        &self,
+        session: Session,
+        mut response: Response,
+    ) -> Result<Response, StoreError> {
+        let (old_id, record, changed, renew, destroyed) = {
+            let state = session.lock();
+            (
+                state.id.clone(),
+                state.record.clone(),
+                state.changed,
+                state.renew,
+                state.destroyed,
+            )
+        };
+
+        if destroyed {
+            if let Some(id) = old_id {
+                self.store.delete(&id).await?;
+                self.set_cookie(&mut response, self.cookie.remove());
+            }
+            return Ok(response);
+        }
+
+        // A new session is kept once the application writes to it, or when
+        // the response is a page that may embed its CSRF token.
+        if old_id.is_none() && !changed && !is_html(&response) {
+            return Ok(response);
+        }
+
+        let id = match old_id {
+            Some(id) if !renew => id,
+            old => {
+                if let Some(old) = old {
+                    self.store.delete(&old).await?;
+                }
+                let id = cookie::new_id();
+                self.set_cookie(&mut response, self.cookie.set(&self.codec.seal(&id)));
+                id
+            }
+        };
+        let now = unix_millis();
+        let record = SessionRecord {
+            accessed_at: now,
+            ..record
+        };
+        self.store
+            .save(&id, &record, self.ttl(&record, now))
+            .await?;
+        Ok(response)
+    }
+
+    fn set_cookie(&self, response: &mut Response, cookie: String) {
+        if let Ok(value) = HeaderValue::from_str(&cookie) {
+            response.headers_mut().append(SET_COOKIE, value);
+        }
+    }
+}
+
+fn is_html(response: &Response) -> bool {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/html"))
+}
+
+fn unavailable(error: StoreError) -> Response {
+    tracing::error!(%error, "session store failure");
+    error_response(StatusCode::SERVICE_UNAVAILABLE, "Session store unavailable")
+}
+
+async fn sessions_middleware(settings: Arc<Settings>, mut req: Request, next: Next) -> Response {
+    let now = unix_millis();
+    let (id, mut record) = match settings.load(settings.cookie_id(&req), now).await {
+        Ok(Some((id, record))) => (Some(id), record),
+        Ok(None) => (None, SessionRecord::new(now)),
+        Err(error) => return unavailable(error),
+    };
+    let csrf = match record.data.get(CSRF_KEY).and_then(Value::as_str) {
+        Some(token) => token.to_string(),
+        None => {
+            let token = cookie::new_id();
+            record
+                .data
+                .insert(CSRF_KEY.into(), Value::String(token.clone()));
+            token
+        }
+    };
+
+    let session = Session::new(id, record);
+    req.extensions_mut().insert(session.clone());
+    req.extensions_mut().insert(CsrfToken(csrf));
+    let response = next.run(req).await;
+    match settings.commit(session, response).await {
+        Ok(response) => response,
+        Err(error) => unavailable(error),
+    }
+}
@@ -0,0 +1,163 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Boxed future returned by [`SessionStore`] methods.
+pub type StoreFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, StoreError>> + Send + 'a>>;
+
+/// A failure of the session store. The request is answered with `503`.
+#[derive(Debug, Clone)]
+pub struct StoreError(pub String);
+
+impl StoreError {
+    pub fn new(message: impl std::fmt::Display) -> Self {
+        Self(message.to_string())
+    }
+}
+
+impl std::fmt::Display for StoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "session store error: {}", self.0)
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+/// The data of a session and its timestamps, in unix milliseconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub data: Map<String, Value>,
+    /// When the session was created, for the absolute timeout.
+    pub created_at: u64,
+    /// When the session was last used, for the idle timeout.
+    pub accessed_at: u64,
+}
+
+impl SessionRecord {
+    /// An empty session created at `now`.
+    pub fn new(now: u64) -> Self {
+        Self {
+            data: Map::new(),
+            created_at: now,
+            accessed_at: now,
+        }
+    }
+
+    /// JSON encoding, for stores that keep opaque bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("session records serialize to JSON")
+    }
+
+    /// Decode [`to_bytes`](Self::to_bytes) output.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        serde_json::from_slice(bytes).map_err(StoreError::new)
+    }
+}
+
+/// The current time in unix milliseconds.
+pub(crate) fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+/// Storage for sessions, provided as an `Arc<dyn SessionStore>` bean.
+///
+/// Ids are random and never leave the server unencrypted. A record saved
+/// with `ttl` may be dropped once it has passed; the timeouts are also
+/// checked on load, so a store that keeps expired records is still correct.
+pub trait SessionStore: Send + Sync + 'static {
+    /// The session `id`, or `None` if it does not exist or has expired.
+    fn load<'a>(&'a self, id: &'a str) -> StoreFuture<'a, Option<SessionRecord>>;
+
+    /// Create or replace the session `id`, kept for `ttl`.
+    fn save<'a>(
+        &'a self,
+        id: &'a str,
+        record: &'a SessionRecord,
+        ttl: Duration,
+    ) -> StoreFuture<'a, ()>;
+
+    /// Remove the session `id`.
+    fn delete<'a>(&'a self, id: &'a str) -> StoreFuture<'a, ()>;
+}
+
+/// In-process store backed by a `DashMap`. Expired sessions are evicted when
+/// loaded and by a sweep every 1024 saves. Sessions are lost on restart and
+/// not shared between instances.
+#[derive(Clone, Default)]
+pub struct InMemorySessionStore {
+    entries: Arc<DashMap<String, (SessionRecord, Instant)>>,
+    saves: Arc<AtomicUsize>,
+}
+
+impl InMemorySessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ready-to-provide store bean: `Arc<dyn SessionStore>`.
+    ///
+    /// ```ignore
+    /// AppBuilder::new().provide(InMemorySessionStore::shared())
+    /// ```
+    pub fn shared() -> Arc<dyn SessionStore> {
+        Arc::new(Self::new())
+    }
+
+    /// Number of stored sessions, expired ones included until evicted.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn sweep(&self) {
+        if self.saves.fetch_add(1, Ordering::Relaxed) % 1024 == 1023 {
+            let now = Instant::now();
+            self.entries.retain(|_, (_, expires)| *expires > now);
+        }
+    }
+}
+
+impl SessionStore for InMemorySessionStore {
+    fn load<'a>(&'a self, id: &'a str) -> StoreFuture<'a, Option<SessionRecord>> {
+        let now = Instant::now();
+        let record = match self.entries.get(id) {
+            Some(entry) if entry.1 > now => Some(entry.0.clone()),
+            Some(entry) => {
+                drop(entry);
+                self.entries
+                    .remove_if(id, |_, (_, expires)| *expires <= now);
+                None
+            }
+            None => None,
+        };
+        Box::pin(std::future::ready(Ok(record)))
+    }
+
+    fn save<'a>(
+        &'a self,
+        id: &'a str,
+        record: &'a SessionRecord,
+        ttl: Duration,
+    ) -> StoreFuture<'a, ()> {
+        self.sweep();
+        self.entries
+            .insert(id.to_string(), (record.clone(), Instant::now() + ttl));
+        Box::pin(std::future::ready(Ok(())))
+    }
+
+    fn delete<'a>(&'a self, id: &'a str) -> StoreFuture<'a, ()> {
+        self.entries.remove(id);
+        Box::pin(std::future::ready(Ok(())))
+    }
+}
@@ -164,6 +164,30 @@ mod imp {
         tcp_nodelay: bool,
         control_plane: tokio::runtime::Handle,
         cancel_token: CancellationToken,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        serve_sharded_with_tls(
+            router,
+            addrs,
+            workers,
+            tcp_nodelay,
+            None,
+            control_plane,
+            cancel_token,
+        )
+    }
+
+    /// [`serve_sharded`], terminating TLS in every worker when `tls` is set.
+    ///
+    /// All workers share one [`TlsServer`](crate::tls::TlsServer) config, so a
+    /// certificate reload is picked up by every listener at once.
+    pub fn serve_sharded_with_tls(
+        router: crate::http::Router,
+        addrs: &[SocketAddr],
+        workers: usize,
+        tcp_nodelay: bool,
+        tls: Option<crate::tls::TlsServer>,
+        control_plane: tokio::runtime::Handle,
+        cancel_token: CancellationToken,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         // Pre-create the listeners on the main thread so that a bind failure
         // surfaces synchronously as a run error (rather than from inside a
@@ -204,6 +228,7 @@ mod imp {
             let router = router.clone();
             let child_token = cancel_token.child_token();
             let control_plane = control_plane.clone();
+            let tls = tls.clone();
             let handle = std::thread::Builder::new()
                 .name(format!("r2e-worker-{i}"))
                 .spawn(move || -> Result<(), String> {
@@ -221,8 +246,20 @@ mod imp {
                         // context.
                         let listener = tokio::net::TcpListener::from_std(std_listener)
                             .map_err(|e| format!("failed to adopt worker listener: {e}"))?;
-                        let svc = router.into_make_service_with_connect_info::<SocketAddr>();
                         let shutdown = child_token.cancelled_owned();
+                        #[cfg(feature = "tls")]
+                        if let Some(tls) = tls {
+                            return crate::http::serve(
+                                tls.listener(listener, tcp_nodelay),
+                                crate::http::tls::TlsMakeService::new(router),
+                            )
+                            .with_graceful_shutdown(shutdown)
+                            .await
+                            .map_err(|e| format!("worker serve error: {e}"));
+                        }
+                        #[cfg(not(feature = "tls"))]
+                        let _ = tls;
+                        let svc = router.into_make_service_with_connect_info::<SocketAddr>();
                         let serve_result = if tcp_nodelay {
                             use crate::http::ListenerExt as _;
                             crate::http::serve(
@@ -284,4 +321,4 @@ mod imp {
     unix,
     not(any(target_os = "solaris", target_os = "illumos", target_os = "cygwin"))
 ))]
-pub use imp::serve_sharded;
+pub use imp::{serve_sharded, serve_sharded_with_tls};
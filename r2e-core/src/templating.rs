@@ -0,0 +1,233 @@
+//! Server-side HTML: `#[template]` routes and the [`TemplateEngine`] bean.
+//!
+//! ```ignore
+//! #[derive(Serialize)]
+//! struct UserList {
+//!     users: Vec<User>,
+//! }
+//!
+//! #[routes]
+//! impl AdminController {
+//!     #[get("/users")]
+//!     #[template("users/list.html")]
+//!     async fn list(&self) -> UserList {
+//!         UserList { users: self.repo.all().await }
+//!     }
+//! }
+//!
+//! AppBuilder::new().provide(r2e_templates::Templates::new::<Views>().shared())
+//! ```
+//!
+//! The handler returns the template context — any `Serialize` value, or a
+//! `Result` of one — and the route answers with the rendered page as
+//! `text/html`. Errors returned by the handler are sent as they are.
+//!
+//! Besides the handler's fields, every template sees these globals (a
+//! context field of the same name wins):
+//!
+//! | Global | Value |
+//! |--------|-------|
+//! | `identity` | `{ sub, email, claims }` of the authenticated caller, or `none` |
+//! | `request_id` | The [`RequestId`](crate::RequestId) of the request, or `none` |
+//! | `csrf_token` | The [`CsrfToken`] of the request, or `none` |
+//!
+//! The engine is the `Arc<dyn TemplateEngine>` bean; `r2e-templates`
+//! provides one on MiniJinja. Every `#[template]` name is checked against it
+//! at startup, so a missing or broken template aborts the boot instead of
+//! failing the first request.
+
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::beans::BeanContext;
+use crate::decorator::DecoratorSpec;
+use crate::guards::{Guard, GuardContext, Identity};
+use crate::http::header::{HeaderValue, CONTENT_TYPE};
+use crate::http::middleware::{from_fn, Next};
+use crate::http::response::{IntoResponse, Response};
+use crate::http::routing::MethodRouter;
+use crate::http::Request;
+use crate::request_context::RequestContext;
+use crate::request_id::RequestId;
+use crate::type_list::{TCons, TNil};
+use crate::HttpError;
+
+/// A failure of the template engine: a missing template, a syntax error or
+/// a rendering error.
+#[derive(Debug, Clone)]
+pub struct TemplateError(pub String);
+
+impl TemplateError {
+    pub fn new(message: impl std::fmt::Display) -> Self {
+        Self(message.to_string())
+    }
+}
+
+impl std::fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// Renders named templates, provided as an `Arc<dyn TemplateEngine>` bean.
+pub trait TemplateEngine: Send + Sync + 'static {
+    /// Render the template `name` with `context`, a JSON object holding the
+    /// handler's fields and the globals.
+    fn render(&self, name: &str, context: &Value) -> Result<String, TemplateError>;
+
+    /// Check that `name` exists and compiles. Called once at startup for
+    /// every `#[template]` route.
+    fn check(&self, name: &str) -> Result<(), TemplateError>;
+}
+
+/// The CSRF token of the current request, exposed to templates as
+/// `csrf_token`. Session or CSRF middleware puts it in the request
+/// extensions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsrfToken(pub String);
+
+/// What a `#[template]` route renders: set by its layer, read when the
+/// handler's value becomes a response.
+struct RenderScope {
+    engine: Arc<dyn TemplateEngine>,
+    template: &'static str,
+    globals: Mutex<Map<String, Value>>,
+}
+
+tokio::task_local! {
+    static SCOPE: Arc<RenderScope>;
+}
+
+/// Spec of the guard `#[template]` adds to a route: records the caller's
+/// identity for the `identity` global, and requires the
+/// `Arc<dyn TemplateEngine>` bean.
+pub struct Templated;
+
+impl DecoratorSpec for Templated {
+    type Product = TemplateGlobals;
+    type Deps = TCons<Arc<dyn TemplateEngine>, TNil>;
+
+    fn build(self, _ctx: &BeanContext) -> TemplateGlobals {
+        TemplateGlobals
+    }
+}
+
+/// Records the identity of a `#[template]` request. Built from
+/// [`Templated`]; never rejects.
+pub struct TemplateGlobals;
+
+impl<I: Identity> Guard<I> for TemplateGlobals {
+    fn check(
+        &self,
+        ctx: &GuardContext<'_, I>,
+    ) -> impl std::future::Future<Output = Result<(), Response>> + Send {
+        if let (Some(identity), Ok(scope)) = (ctx.identity, SCOPE.try_with(Arc::clone)) {
+            let identity = serde_json::json!({
+                "sub": identity.sub(),
+                "email": identity.email(),
+                "claims": identity.claims(),
+            });
+            scope
+                .globals
+                .lock()
+                .unwrap()
+                .insert("identity".to_string(), identity);
+        }
+        std::future::ready(Ok(()))
+    }
+}
+
+/// The value returned by a `#[template]` handler, rendered into the
+/// route's template when turned into a response. Used by
+/// `#[routes]`-generated code.
+#[doc(hidden)]
+pub struct Render<T>(pub T);
+
+impl<T: Serialize> IntoResponse for Render<T> {
+    fn into_response(self) -> Response {
+        let Ok(scope) = SCOPE.try_with(Arc::clone) else {
+            tracing::error!("template rendered outside of a #[template] route");
+            return HttpError::internal("Failed to render the page").into_response();
+        };
+        let context = match serde_json::to_value(&self.0) {
+            Ok(Value::Object(fields)) => fields,
+            Ok(Value::Null) => Map::new(),
+            Ok(_) => {
+                tracing::error!(
+                    template = scope.template,
+                    "a #[template] context must serialize to a map"
+                );
+                return HttpError::internal("Failed to render the page").into_response();
+            }
+            Err(error) => {
+                tracing::error!(template = scope.template, error = %error, "failed to serialize template context");
+                return HttpError::internal("Failed to render the page").into_response();
+            }
+        };
+        let mut globals = std::mem::take(&mut *scope.globals.lock().unwrap());
+        globals.extend(context);
+        match scope.engine.render(scope.template, &Value::Object(globals)) {
+            Ok(html) => (
+                [(
+                    CONTENT_TYPE,
+                    HeaderValue::from_static("text/html; charset=utf-8"),
+                )],
+                html,
+            )
+                .into_response(),
+            Err(error) => {
+                tracing::error!(template = scope.template, error = %error, "failed to render template");
+                HttpError::internal("Failed to render the page").into_response()
+            }
+        }
+    }
+}
+
+/// Wrap a `#[template]` route in the layer that sets up its rendering.
+/// The template is checked against the `Arc<dyn TemplateEngine>` bean; a
+/// missing or invalid one aborts startup. Used by `#[routes]`-generated
+/// code.
+#[doc(hidden)]
+pub fn __apply<S>(
+    ctx: &BeanContext,
+    route: &str,
+    template: &'static str,
+    router: MethodRouter<S>,
+) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let engine = ctx.get::<Arc<dyn TemplateEngine>>();
+    if let Err(error) = engine.check(template) {
+        panic!("route `{route}`: #[template(\"{template}\")]: {error}");
+    }
+    router.layer(from_fn(move |req: Request, next: Next| {
+        let scope = Arc::new(RenderScope {
+            engine: engine.clone(),
+            template,
+            globals: Mutex::new(request_globals(&req)),
+        });
+        SCOPE.scope(scope, next.run(req))
+    }))
+}
+
+fn request_globals(req: &Request) -> Map<String, Value> {
+    let request_id = req
+        .extensions()
+        .get::<RequestId>()
+        .map(|id| id.0.clone())
+        .or_else(|| RequestContext::current()?.request_id().map(str::to_string));
+    let csrf_token = req
+        .extensions()
+        .get::<CsrfToken>()
+        .map(|token| token.0.clone());
+    let mut globals = Map::new();
+    globals.insert("identity".to_string(), Value::Null);
+    globals.insert("request_id".to_string(), request_id.into());
+    globals.insert("csrf_token".to_string(), csrf_token.into());
+    globals
+}
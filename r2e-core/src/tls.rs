@@ -0,0 +1,147 @@
+//! Native TLS (HTTPS over TCP) configured from the `server.tls` section.
+//!
+//! ```yaml
+//! server:
+//!   tls:
+//!     cert: certs/server.pem        # PEM chain, leaf first
+//!     key: certs/server.key
+//!     client_ca: certs/clients.pem  # optional: enables mTLS
+//!     client_auth: required         # "required" (default with client_ca) or "optional"
+//!     alpn: [h2, http/1.1]          # default
+//!     reload_interval: 30s          # certificate file polling, 0 disables
+//!     handshake_timeout: 10s
+//! ```
+//!
+//! Both the single-listener and the sharded (`server.workers`) serving paths
+//! terminate TLS. Certificates are re-read when the files change; established
+//! connections keep their session. The serving machinery lives in
+//! [`r2e_http::tls`]; this module only turns the config into a [`TlsServer`].
+//!
+//! Without the `tls` feature, a configured `server.tls` section is a hard
+//! error at `run()` time — never a silent fallback to plaintext.
+
+use crate::config::R2eConfig;
+use std::time::Duration;
+
+#[cfg(feature = "tls")]
+use crate::http::tls::{ClientAuth, TlsConfig, TlsSettings, DEFAULT_HANDSHAKE_TIMEOUT};
+
+/// Default polling interval for certificate hot-reload.
+pub const DEFAULT_RELOAD_INTERVAL: Duration = Duration::from_secs(30);
+
+/// TLS termination settings resolved from `server.tls`.
+#[cfg(feature = "tls")]
+#[derive(Debug, Clone)]
+pub struct TlsServer {
+    /// Shared, reloadable rustls configuration.
+    pub config: TlsConfig,
+    /// How often the certificate files are checked for changes; `None`
+    /// disables hot-reload.
+    pub reload_interval: Option<Duration>,
+    /// Bound on a single TLS handshake.
+    pub handshake_timeout: Duration,
+}
+
+#[cfg(feature = "tls")]
+impl TlsServer {
+    /// Wrap an accepted-connection listener so it terminates TLS.
+    pub fn listener(
+        &self,
+        tcp: tokio::net::TcpListener,
+        tcp_nodelay: bool,
+    ) -> crate::http::tls::TlsListener {
+        crate::http::tls::TlsListener::new(tcp, self.config.clone())
+            .with_tcp_nodelay(tcp_nodelay)
+            .with_handshake_timeout(self.handshake_timeout)
+    }
+}
+
+/// Uninhabited without the `tls` feature: [`parse_tls`] never returns one.
+#[cfg(not(feature = "tls"))]
+#[derive(Debug, Clone)]
+pub enum TlsServer {}
+
+/// Parse the `server.tls` configuration section.
+///
+/// - section absent → `Ok(None)` (plaintext HTTP, unchanged default)
+/// - `cert` and `key` set → `Ok(Some(..))`, with the certificates loaded
+///
+/// Missing files, invalid PEM, an unknown `client_auth` value, or a
+/// `server.tls` section without the `tls` feature are errors.
+pub fn parse_tls(config: Option<&R2eConfig>) -> Result<Option<TlsServer>, String> {
+    let Some(config) = config else {
+        return Ok(None);
+    };
+    if !config.has_prefix("server.tls") {
+        return Ok(None);
+    }
+    imp::parse(config)
+}
+
+#[cfg(not(feature = "tls"))]
+mod imp {
+    use super::*;
+
+    pub(super) fn parse(_config: &R2eConfig) -> Result<Option<TlsServer>, String> {
+        Err("server.tls is configured but the `tls` feature is not enabled".to_string())
+    }
+}
+
+#[cfg(feature = "tls")]
+mod imp {
+    use super::*;
+
+    pub(super) fn parse(config: &R2eConfig) -> Result<Option<TlsServer>, String> {
+        let path = |key: &str| config.try_get::<std::path::PathBuf>(&format!("server.tls.{key}"));
+        let (Some(cert), Some(key)) = (path("cert"), path("key")) else {
+            return Err("server.tls requires both server.tls.cert and server.tls.key".to_string());
+        };
+        let mut settings = TlsSettings::new(cert, key);
+
+        let client_auth = config.try_get::<String>("server.tls.client_auth");
+        match (path("client_ca"), client_auth.as_deref()) {
+            (Some(ca), None | Some("required")) => {
+                settings = settings.with_client_ca(ca, ClientAuth::Required);
+            }
+            (Some(ca), Some("optional")) => {
+                settings = settings.with_client_ca(ca, ClientAuth::Optional);
+            }
+            (None, None) => {}
+            (None, Some(_)) => {
+                return Err("server.tls.client_auth requires server.tls.client_ca".to_string());
+            }
+            (Some(_), Some(other)) => {
+                return Err(format!(
+                    "server.tls.client_auth must be \"required\" or \"optional\", got \"{other}\""
+                ));
+            }
+        }
+
+        if config.contains_key("server.tls.alpn") {
+            let alpn = config
+                .try_get::<Vec<String>>("server.tls.alpn")
+                .ok_or("server.tls.alpn must be a list of protocol names")?;
+            settings = settings.with_alpn(alpn);
+        }
+
+        let duration = |key: &str, default: Duration| -> Result<Duration, String> {
+            let full = format!("server.tls.{key}");
+            if !config.contains_key(&full) {
+                return Ok(default);
+            }
+            config
+                .try_get::<Duration>(&full)
+                .ok_or_else(|| format!("{full} must be a duration like \"30s\""))
+        };
+        let reload_interval = Some(duration("reload_interval", DEFAULT_RELOAD_INTERVAL)?)
+            .filter(|interval| !interval.is_zero());
+        let handshake_timeout = duration("handshake_timeout", DEFAULT_HANDSHAKE_TIMEOUT)?;
+
+        let config = TlsConfig::load(settings).map_err(|e| e.to_string())?;
+        Ok(Some(TlsServer {
+            config,
+            reload_interval,
+            handshake_timeout,
+        }))
+    }
+}
@@ -0,0 +1,333 @@
+//! Task-bound transactions for `#[transactional]` methods.
+//!
+//! `#[managed]` transactions only exist for HTTP route parameters. A method
+//! annotated with `#[transactional]` — a `#[bean]` service method, a
+//! `#[consumer]`, a `#[scheduled]` job, or a route — instead runs its body
+//! through [`transactional`], which binds the transaction to the current
+//! tokio task:
+//!
+//! - the transaction is stored in a task-local scope keyed by its type, so a
+//!   nested `#[transactional]` call on the same task **joins** it
+//!   ([`Propagation::Required`]) instead of opening a second one;
+//! - code inside the scope reaches it with [`current`] (backends wrap this,
+//!   e.g. `r2e_data_sqlx::current_tx`);
+//! - when the outermost body returns, the transaction commits if the
+//!   [`TransactionOutcome`] says so (`Ok`, `()`, an event `Ack`) and rolls
+//!   back otherwise. A joined participant that fails marks the whole
+//!   transaction rollback-only, and the outer commit then fails with
+//!   [`TxError::RollbackOnly`];
+//! - a panic or a cancelled future drops the transaction, which the backends
+//!   turn into a rollback (SQLx drop rollback, Diesel discards the pooled
+//!   connection).
+//!
+//! Like [`RequestContext`](crate::RequestContext), the scope does not follow
+//! [`rt::spawn`](crate::rt::spawn): work moved to another task runs outside
+//! the transaction.
+//!
+//! A [`TxGuard`] returned by [`current`] locks the transaction; drop it
+//! before calling another method that uses the same transaction, or that
+//! call waits forever.
+
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+use crate::HttpError;
+
+tokio::task_local! {
+    static SCOPE: TxScope;
+}
+
+/// How a `#[transactional]` method relates to a transaction already bound to
+/// the task.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Propagation {
+    /// Join the current transaction, or begin one if there is none.
+    #[default]
+    Required,
+    /// Always begin a new, independent transaction; the current one (if any)
+    /// is suspended until the method returns.
+    RequiresNew,
+    /// Join the current transaction; fail with [`TxError::NoTransaction`]
+    /// when there is none.
+    Mandatory,
+}
+
+/// SQL transaction isolation level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Isolation {
+    ReadUncommitted,
+    ReadCommitted,
+    RepeatableRead,
+    Serializable,
+}
+
+impl Isolation {
+    /// The level as written in `SET TRANSACTION ISOLATION LEVEL ...`.
+    pub fn as_sql(self) -> &'static str {
+        match self {
+            Isolation::ReadUncommitted => "READ UNCOMMITTED",
+            Isolation::ReadCommitted => "READ COMMITTED",
+            Isolation::RepeatableRead => "REPEATABLE READ",
+            Isolation::Serializable => "SERIALIZABLE",
+        }
+    }
+}
+
+/// Options of a `#[transactional]` method.
+///
+/// `isolation` and `read_only` apply when a transaction is begun; a method
+/// that joins an existing transaction inherits its settings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TxOptions {
+    pub propagation: Propagation,
+    /// `None` keeps the database default.
+    pub isolation: Option<Isolation>,
+    pub read_only: bool,
+}
+
+impl TxOptions {
+    /// `Required` propagation, default isolation, read-write.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn propagation(mut self, propagation: Propagation) -> Self {
+        self.propagation = propagation;
+        self
+    }
+
+    pub fn isolation(mut self, isolation: Isolation) -> Self {
+        self.isolation = Some(isolation);
+        self
+    }
+
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+}
+
+/// Error raised by the transaction machinery itself (as opposed to the
+/// method body).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxError {
+    /// `Propagation::Mandatory` with no transaction bound to the task.
+    NoTransaction(&'static str),
+    /// The body succeeded but a joined participant failed, so the
+    /// transaction was rolled back instead of committed.
+    RollbackOnly,
+    /// The backend failed to begin, commit, or roll back.
+    Backend(String),
+}
+
+impl TxError {
+    /// Wrap a backend error.
+    pub fn backend(error: impl fmt::Display) -> Self {
+        TxError::Backend(error.to_string())
+    }
+}
+
+impl fmt::Display for TxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TxError::NoTransaction(tx) => {
+                write!(
+                    f,
+                    "no `{tx}` transaction is active (propagation = mandatory)"
+                )
+            }
+            TxError::RollbackOnly => f.write_str(
+                "transaction rolled back: a participating call failed and marked it rollback-only",
+            ),
+            TxError::Backend(message) => write!(f, "transaction error: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for TxError {}
+
+impl From<TxError> for HttpError {
+    fn from(error: TxError) -> Self {
+        HttpError::internal(error.to_string())
+    }
+}
+
+/// Begins and finishes transactions for [`transactional`].
+///
+/// `B` is a marker type owned by the implementing crate. It lets a backend
+/// implement this trait directly on a foreign pool type (`sqlx::Pool<DB>`,
+/// a Diesel r2d2 pool) and is inferred at the call site.
+pub trait TransactionManager<B>: Send + Sync {
+    /// The transaction handle stored in the task scope. Its type is the scope
+    /// key: one transaction per `Tx` type can be active on a task.
+    type Tx: Send + 'static;
+
+    fn begin(&self, options: TxOptions) -> impl Future<Output = Result<Self::Tx, TxError>> + Send;
+
+    fn commit(&self, tx: Self::Tx) -> impl Future<Output = Result<(), TxError>> + Send;
+
+    fn rollback(&self, tx: Self::Tx) -> impl Future<Output = Result<(), TxError>> + Send;
+}
+
+/// Decides whether a `#[transactional]` method's return value commits, and
+/// how a transaction failure is reported through it.
+pub trait TransactionOutcome: Sized {
+    fn should_commit(&self) -> bool;
+
+    /// Build the value returned when the transaction could not be begun or
+    /// committed.
+    fn from_tx_error(error: TxError) -> Self;
+}
+
+impl TransactionOutcome for () {
+    fn should_commit(&self) -> bool {
+        true
+    }
+
+    /// A `()` method has nowhere to report the failure: it is logged.
+    fn from_tx_error(error: TxError) -> Self {
+        tracing::error!(error = %error, "transactional method failed");
+    }
+}
+
+impl<T, E: From<TxError>> TransactionOutcome for Result<T, E> {
+    fn should_commit(&self) -> bool {
+        self.is_ok()
+    }
+
+    fn from_tx_error(error: TxError) -> Self {
+        Err(error.into())
+    }
+}
+
+/// Transactions bound to the task, by `Tx` type. Immutable: entering a new
+/// transaction scopes a copy with the extra entry.
+#[derive(Clone, Default)]
+struct TxScope {
+    slots: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+struct Slot<T> {
+    tx: Arc<Mutex<Option<T>>>,
+    rollback_only: AtomicBool,
+}
+
+fn current_slot<T: Send + 'static>() -> Option<Arc<Slot<T>>> {
+    SCOPE
+        .try_with(|scope| scope.slots.get(&TypeId::of::<T>()).cloned())
+        .ok()
+        .flatten()
+        .and_then(|slot| slot.downcast::<Slot<T>>().ok())
+}
+
+/// Run `body` inside a transaction of `manager`, according to `options`.
+///
+/// This is what `#[transactional]` expands to; see the [module docs](self).
+pub async fn transactional<B, M, F, R>(manager: &M, options: TxOptions, body: F) -> R
+where
+    M: TransactionManager<B>,
+    F: Future<Output = R>,
+    R: TransactionOutcome,
+{
+    let existing = current_slot::<M::Tx>();
+    match (options.propagation, existing) {
+        (Propagation::Required | Propagation::Mandatory, Some(slot)) => {
+            let result = body.await;
+            if !result.should_commit() {
+                slot.rollback_only.store(true, Ordering::Relaxed);
+            }
+            result
+        }
+        (Propagation::Mandatory, None) => {
+            R::from_tx_error(TxError::NoTransaction(type_name::<M::Tx>()))
+        }
+        _ => run_new(manager, options, body).await,
+    }
+}
+
+async fn run_new<B, M, F, R>(manager: &M, options: TxOptions, body: F) -> R
+where
+    M: TransactionManager<B>,
+    F: Future<Output = R>,
+    R: TransactionOutcome,
+{
+    let tx = match manager.begin(options).await {
+        Ok(tx) => tx,
+        Err(error) => return R::from_tx_error(error),
+    };
+    let slot = Arc::new(Slot {
+        tx: Arc::new(Mutex::new(Some(tx))),
+        rollback_only: AtomicBool::new(false),
+    });
+    let mut scope = SCOPE.try_with(Clone::clone).unwrap_or_default();
+    scope.slots.insert(
+        TypeId::of::<M::Tx>(),
+        slot.clone() as Arc<dyn Any + Send + Sync>,
+    );
+
+    // A panic or cancellation unwinds through here and drops `slot`, and with
+    // it the transaction: the backend's drop path rolls back.
+    let result = SCOPE.scope(scope, body).await;
+
+    let Some(tx) = slot.tx.lock().await.take() else {
+        return result;
+    };
+    let rollback_only = slot.rollback_only.load(Ordering::Relaxed);
+    if result.should_commit() && !rollback_only {
+        return match manager.commit(tx).await {
+            Ok(()) => result,
+            Err(error) => R::from_tx_error(error),
+        };
+    }
+    if let Err(error) = manager.rollback(tx).await {
+        tracing::warn!(error = %error, "transaction rollback failed");
+    }
+    if result.should_commit() {
+        R::from_tx_error(TxError::RollbackOnly)
+    } else {
+        result
+    }
+}
+
+/// Exclusive access to the transaction bound to the task, from [`current`].
+pub struct TxGuard<T> {
+    guard: OwnedMutexGuard<Option<T>>,
+}
+
+impl<T> Deref for TxGuard<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.guard
+            .as_ref()
+            .expect("transaction used after its #[transactional] scope ended")
+    }
+}
+
+impl<T> DerefMut for TxGuard<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.guard
+            .as_mut()
+            .expect("transaction used after its #[transactional] scope ended")
+    }
+}
+
+/// Lock the `T` transaction bound to the current task, or `None` outside any
+/// `#[transactional]` scope for it.
+pub async fn current<T: Send + 'static>() -> Option<TxGuard<T>> {
+    let slot = current_slot::<T>()?;
+    let guard = slot.tx.clone().lock_owned().await;
+    guard.is_some().then_some(TxGuard { guard })
+}
+
+/// Whether a `T` transaction is bound to the current task.
+pub fn is_active<T: Send + 'static>() -> bool {
+    current_slot::<T>().is_some()
+}
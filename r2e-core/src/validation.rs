@@ -1,10 +1,20 @@
+use std::borrow::Cow;
+use std::fmt::Display;
+
 use crate::http::response::{IntoResponse, Response};
 use crate::http::{Json, StatusCode};
+use garde::i18n::{
+    DefaultI18n, I18n, InvalidCreditCard, InvalidEmail, InvalidPhoneNumber, InvalidUrl, IpKind,
+};
 use serde::Serialize;
 
 // ── Error types ────────────────────────────────────────────
 
 /// A field-level validation error.
+///
+/// Errors from `garde` rules carry the rule as `code` (`length_lower_than`,
+/// `email_invalid`, ...); the [`I18n`](crate::i18n::I18n) plugin translates
+/// `message` with the `validation.{code}` message key.
 #[derive(Debug, Clone, Serialize)]
 pub struct FieldError {
     pub field: String,
@@ -46,8 +56,7 @@ where
     T::Context: Default,
 {
     fn __maybe_validate(&self) -> Result<(), Box<Response>> {
-        self.0
-            .validate()
+        garde::with_i18n(CodedMessages, || self.0.validate())
             .map_err(|report| Box::new(convert_garde_report(&report)))
     }
 }
@@ -71,9 +80,28 @@ struct ValidationErrorBody<'a> {
     details: &'a [FieldError],
 }
 
+/// Render `errors` as `{ "error": "Validation failed", "details": [...] }`
+/// with the given status. As Problem Details, the field errors are the
+/// `errors` extension member.
+pub fn validation_error_response(status: StatusCode, errors: &ValidationErrorResponse) -> Response {
+    let body = ValidationErrorBody {
+        error: "Validation failed",
+        details: &errors.errors,
+    };
+    let problem = crate::problem::Problem::new(status)
+        .with_detail("Validation failed")
+        .with_extension(
+            "errors",
+            serde_json::to_value(&errors.errors).unwrap_or_default(),
+        );
+    let response = problem.attach((status, Json(body)).into_response());
+    crate::i18n::MessageKey::new("validation.failed").attach(response)
+}
+
 fn convert_garde_report(report: &garde::Report) -> Response {
     let iter = report.iter();
     let mut field_errors: Vec<FieldError> = Vec::with_capacity(iter.size_hint().0);
+    let mut field_args = Vec::with_capacity(field_errors.capacity());
 
     for (path, error) in iter {
         let rendered = path.to_string();
@@ -82,18 +110,217 @@ fn convert_garde_report(report: &garde::Report) -> Response {
         } else {
             rendered
         };
+        let (code, args, message) = match decode(error.message()) {
+            Some(coded) => coded,
+            None => ("validation", Vec::new(), error.message()),
+        };
         field_errors.push(FieldError {
             field,
-            message: error.message().to_owned(),
-            code: "validation".to_string(),
+            message: message.to_owned(),
+            code: code.to_owned(),
         });
+        field_args.push(args);
     }
 
-    let body = ValidationErrorBody {
-        error: "Validation failed",
-        details: &field_errors,
-    };
-    (StatusCode::BAD_REQUEST, Json(body)).into_response()
+    let mut response = validation_error_response(
+        StatusCode::BAD_REQUEST,
+        &ValidationErrorResponse {
+            errors: field_errors,
+        },
+    );
+    response.extensions_mut().insert(FieldErrorArgs(field_args));
+    response
+}
+
+/// The arguments of each field error of a validation response, for the
+/// `validation.{code}` messages. Only set on `garde` failures.
+#[derive(Clone)]
+pub(crate) struct FieldErrorArgs(pub(crate) Vec<Vec<(&'static str, String)>>);
+
+// ── Rule codes ─────────────────────────────────────────────
+
+/// Separates the parts of a coded `garde` message: code, arguments, text.
+const CODED: char = '\u{0}';
+/// Separates two arguments of a coded message.
+const ARG: char = '\u{1}';
+/// Separates the name and value of an argument.
+const ARG_VALUE: char = '\u{2}';
+
+/// `garde` messages for [`convert_garde_report`]: each carries the rule
+/// name, its arguments and the default English text.
+struct CodedMessages;
+
+fn coded(code: &str, args: &[(&str, &dyn Display)], text: Cow<'static, str>) -> Cow<'static, str> {
+    let mut message = format!("{CODED}{code}{CODED}");
+    for (i, (name, value)) in args.iter().enumerate() {
+        if i > 0 {
+            message.push(ARG);
+        }
+        message.push_str(&format!("{name}{ARG_VALUE}{value}"));
+    }
+    message.push(CODED);
+    message.push_str(&text);
+    message.into()
+}
+
+/// The code, arguments and text of a [`CodedMessages`] message.
+type Coded<'a> = (&'a str, Vec<(&'static str, String)>, &'a str);
+
+/// `(code, args, text)` of a [`CodedMessages`] message.
+fn decode(message: &str) -> Option<Coded<'_>> {
+    let mut parts = message.strip_prefix(CODED)?.splitn(3, CODED);
+    let (code, args, text) = (parts.next()?, parts.next()?, parts.next()?);
+    let args = args
+        .split(ARG)
+        .filter(|arg| !arg.is_empty())
+        .filter_map(|arg| {
+            let (name, value) = arg.split_once(ARG_VALUE)?;
+            Some((arg_name(name), value.to_owned()))
+        })
+        .collect();
+    Some((code, args, text))
+}
+
+fn arg_name(name: &str) -> &'static str {
+    match name {
+        "min" => "min",
+        "max" => "max",
+        "pattern" => "pattern",
+        "reason" => "reason",
+        "kind" => "kind",
+        "other" => "other",
+        _ => "arg",
+    }
+}
+
+impl I18n for CodedMessages {
+    fn length_lower_than(&self, min: usize) -> Cow<'static, str> {
+        coded(
+            "length_lower_than",
+            &[("min", &min)],
+            DefaultI18n.length_lower_than(min),
+        )
+    }
+
+    fn length_greater_than(&self, max: usize) -> Cow<'static, str> {
+        coded(
+            "length_greater_than",
+            &[("max", &max)],
+            DefaultI18n.length_greater_than(max),
+        )
+    }
+
+    fn range_lower_than(&self, min: &dyn Display) -> Cow<'static, str> {
+        coded(
+            "range_lower_than",
+            &[("min", min)],
+            DefaultI18n.range_lower_than(min),
+        )
+    }
+
+    fn range_greater_than(&self, max: &dyn Display) -> Cow<'static, str> {
+        coded(
+            "range_greater_than",
+            &[("max", max)],
+            DefaultI18n.range_greater_than(max),
+        )
+    }
+
+    fn credit_card_invalid(&self, reason: InvalidCreditCard) -> Cow<'static, str> {
+        coded(
+            "credit_card_invalid",
+            &[("reason", &reason)],
+            DefaultI18n.credit_card_invalid(reason),
+        )
+    }
+
+    fn pattern_no_match(&self, pattern: &dyn Display) -> Cow<'static, str> {
+        coded(
+            "pattern_no_match",
+            &[("pattern", pattern)],
+            DefaultI18n.pattern_no_match(pattern),
+        )
+    }
+
+    fn contains_missing(&self, pattern: &dyn Display) -> Cow<'static, str> {
+        coded(
+            "contains_missing",
+            &[("pattern", pattern)],
+            DefaultI18n.contains_missing(pattern),
+        )
+    }
+
+    fn url_invalid(&self, reason: InvalidUrl) -> Cow<'static, str> {
+        coded(
+            "url_invalid",
+            &[("reason", &reason)],
+            DefaultI18n.url_invalid(reason),
+        )
+    }
+
+    fn prefix_missing(&self, pattern: &dyn Display) -> Cow<'static, str> {
+        coded(
+            "prefix_missing",
+            &[("pattern", pattern)],
+            DefaultI18n.prefix_missing(pattern),
+        )
+    }
+
+    fn suffix_missing(&self, pattern: &dyn Display) -> Cow<'static, str> {
+        coded(
+            "suffix_missing",
+            &[("pattern", pattern)],
+            DefaultI18n.suffix_missing(pattern),
+        )
+    }
+
+    fn phone_number_invalid(&self, reason: InvalidPhoneNumber) -> Cow<'static, str> {
+        coded(
+            "phone_number_invalid",
+            &[("reason", &reason)],
+            DefaultI18n.phone_number_invalid(reason),
+        )
+    }
+
+    fn ip_invalid(&self, kind: IpKind) -> Cow<'static, str> {
+        coded(
+            "ip_invalid",
+            &[("kind", &kind)],
+            DefaultI18n.ip_invalid(kind),
+        )
+    }
+
+    fn matches_field_mismatch(&self, field: &dyn Display) -> Cow<'static, str> {
+        coded(
+            "matches_field_mismatch",
+            &[("other", field)],
+            DefaultI18n.matches_field_mismatch(field),
+        )
+    }
+
+    fn email_invalid(&self, reason: InvalidEmail) -> Cow<'static, str> {
+        coded(
+            "email_invalid",
+            &[("reason", &reason)],
+            DefaultI18n.email_invalid(reason),
+        )
+    }
+
+    fn ascii_invalid(&self) -> Cow<'static, str> {
+        coded("ascii_invalid", &[], DefaultI18n.ascii_invalid())
+    }
+
+    fn alphanumeric_invalid(&self) -> Cow<'static, str> {
+        coded(
+            "alphanumeric_invalid",
+            &[],
+            DefaultI18n.alphanumeric_invalid(),
+        )
+    }
+
+    fn required_not_set(&self) -> Cow<'static, str> {
+        coded("required_not_set", &[], DefaultI18n.required_not_set())
+    }
 }
 
 // Re-export garde::Validate for convenience.
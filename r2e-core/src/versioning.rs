@@ -0,0 +1,590 @@
+//! API versioning: `#[controller(versions = "...")]`, `#[since]`,
+//! `#[until]`, `#[deprecated_in]`, the [`ApiVersion`] extractor and the
+//! [`ApiVersioning`] plugin.
+//!
+//! ```ignore
+//! #[controller(path = "/users", versions = "1..=2")]
+//! pub struct UserController { #[inject] repo: UserRepo }
+//!
+//! #[routes]
+//! impl UserController {
+//!     #[get("/{id}")]
+//!     #[until(1)]
+//!     async fn get_v1(&self, Path(id): Path<u64>) -> Json<UserV1> { ... }
+//!
+//!     #[get("/{id}")]
+//!     #[since(2)]
+//!     async fn get(&self, Path(id): Path<u64>) -> Json<User> { ... }
+//!
+//!     #[get("/")]
+//!     #[deprecated_in(2, sunset = "2027-01-01")]
+//!     async fn list(&self, version: ApiVersion) -> Json<Vec<User>> { ... }
+//! }
+//! ```
+//!
+//! Every route of a versioned controller is registered once per version it
+//! serves, under `/v{n}` + the controller path: `GET /v1/users/{id}` runs
+//! `get_v1`, `GET /v2/users/{id}` runs `get`, and `list` answers on both
+//! `/v1/users` and `/v2/users`. `#[since]` and `#[until]` narrow a method to
+//! part of the controller's range; both bounds are inclusive. The handler
+//! reads the version it was called for through [`ApiVersion`].
+//!
+//! From the version given to `#[deprecated_in]` on, responses carry a
+//! `Deprecation` header (RFC 9745) and, with `sunset`, a `Sunset` header
+//! (RFC 8594), and the OpenAPI operation is marked `deprecated`.
+//!
+//! The URL prefix is always served. The [`ApiVersioning`] plugin lets
+//! clients keep the unprefixed URL and pick the version with a header or a
+//! vendor media type instead.
+
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::builder::AppBuilder;
+use crate::http::extract::{FromRequestParts, OptionalFromRequestParts};
+use crate::http::header::{HeaderName, HeaderValue, ACCEPT, VARY};
+use crate::http::middleware::{from_fn, Next};
+use crate::http::response::{IntoResponse, Response};
+use crate::http::routing::{Handler, MethodRouter};
+use crate::http::{Parts, Request, Router, Uri};
+use crate::meta::{RouteInfo, API_VERSION_EXTENSION};
+use crate::plugin::Plugin;
+use crate::HttpError;
+
+/// The response header marking a deprecated endpoint (RFC 9745).
+pub const DEPRECATION: HeaderName = HeaderName::from_static("deprecation");
+
+/// The response header announcing when an endpoint goes away (RFC 8594).
+pub const SUNSET: HeaderName = HeaderName::from_static("sunset");
+
+/// The API version a request was routed to.
+///
+/// Extract it in a handler of a versioned controller to serve several
+/// versions from one method. On an unversioned route, `ApiVersion` fails
+/// with `500` and `Option<ApiVersion>` is `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ApiVersion(pub u32);
+
+impl fmt::Display for ApiVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}", self.0)
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ApiVersion {
+    type Rejection = HttpError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<ApiVersion>()
+            .copied()
+            .ok_or_else(|| {
+                HttpError::internal(
+                    "ApiVersion is only available on routes of a versioned controller",
+                )
+            })
+    }
+}
+
+impl<S: Send + Sync> OptionalFromRequestParts<S> for ApiVersion {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        Ok(parts.extensions.get::<ApiVersion>().copied())
+    }
+}
+
+/// The `#[since]`, `#[until]` and `#[deprecated_in]` values of a route.
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RouteVersions {
+    pub since: Option<u32>,
+    pub until: Option<u32>,
+    pub deprecated_in: Option<u32>,
+    /// `#[deprecated_in(date = "...")]`, in seconds since the Unix epoch.
+    pub deprecation_date: Option<u64>,
+    /// `#[deprecated_in(sunset = "...")]`, in seconds since the Unix epoch.
+    pub sunset: Option<u64>,
+}
+
+impl RouteVersions {
+    /// The versions this route serves within the controller's range.
+    fn range(&self, (low, high): (u32, u32)) -> RangeInclusive<u32> {
+        let since = match self.since {
+            Some(since) if since > low => since,
+            _ => low,
+        };
+        let until = match self.until {
+            Some(until) if until < high => until,
+            _ => high,
+        };
+        since..=until
+    }
+
+    fn is_deprecated(&self, version: u32) -> bool {
+        self.deprecated_in.is_some_and(|from| version >= from)
+    }
+
+    /// `Deprecation` and `Sunset` values.
+    fn headers(&self) -> (HeaderValue, Option<HeaderValue>) {
+        let deprecation = match self.deprecation_date {
+            Some(secs) => HeaderValue::from_str(&format!("@{secs}"))
+                .expect("an integer is a valid header value"),
+            None => HeaderValue::from_static("true"),
+        };
+        let sunset = self.sunset.map(|secs| {
+            HeaderValue::from_str(&httpdate::fmt_http_date(
+                UNIX_EPOCH + Duration::from_secs(secs),
+            ))
+            .expect("an HTTP-date is a valid header value")
+        });
+        (deprecation, sunset)
+    }
+}
+
+/// Route registration for a controller: each route is registered once per
+/// version under `/v{n}` + the controller path, or nested under the path
+/// alone when the controller is unversioned.
+#[doc(hidden)]
+pub struct VersionedRoutes<S> {
+    router: Router<S>,
+    prefix: Option<&'static str>,
+    versions: Option<(u32, u32)>,
+}
+
+impl<S> VersionedRoutes<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    pub fn new(prefix: Option<&'static str>, versions: Option<(u32, u32)>) -> Self {
+        Self {
+            router: Router::new(),
+            prefix,
+            versions,
+        }
+    }
+
+    pub fn route(
+        mut self,
+        path: &str,
+        route: RouteVersions,
+        method_router: MethodRouter<S>,
+    ) -> Self {
+        let Some(declared) = self.versions else {
+            self.router = self.router.route(path, method_router);
+            return self;
+        };
+        let prefix = self.prefix.unwrap_or("").trim_end_matches('/');
+        for version in route.range(declared) {
+            let mut versioned_path = format!("/v{version}{prefix}");
+            // `nest` maps a `/` route to the bare prefix; do the same.
+            if path != "/" {
+                versioned_path.push_str(path);
+            }
+            let deprecation = route.is_deprecated(version).then(|| route.headers());
+            let method_router = method_router.clone().layer(from_fn(move |req, next| {
+                serve_version(ApiVersion(version), deprecation.clone(), req, next)
+            }));
+            self.router = self.router.route(&versioned_path, method_router);
+        }
+        self
+    }
+
+    pub fn fallback<H, T>(mut self, handler: H) -> Self
+    where
+        H: Handler<T, S>,
+        T: 'static,
+    {
+        self.router = self.router.fallback(handler);
+        self
+    }
+
+    pub fn into_router(self) -> Router<S> {
+        match (self.versions, self.prefix) {
+            (None, Some(prefix)) if prefix != "/" => Router::new().nest(prefix, self.router),
+            _ => self.router,
+        }
+    }
+}
+
+async fn serve_version(
+    version: ApiVersion,
+    deprecation: Option<(HeaderValue, Option<HeaderValue>)>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    req.extensions_mut().insert(version);
+    let mut response = next.run(req).await;
+    if let Some((deprecation, sunset)) = deprecation {
+        let headers = response.headers_mut();
+        headers.insert(DEPRECATION, deprecation);
+        if let Some(sunset) = sunset {
+            headers.insert(SUNSET, sunset);
+        }
+    }
+    response
+}
+
+/// One `RouteInfo` per version the route serves, with the `/v{n}` path.
+#[doc(hidden)]
+pub fn __expand(
+    declared: Option<(u32, u32)>,
+    route: RouteVersions,
+    info: RouteInfo,
+) -> Vec<RouteInfo> {
+    let Some(declared) = declared else {
+        return vec![info];
+    };
+    route
+        .range(declared)
+        .map(|version| {
+            let mut info = info.clone();
+            info.path = format!("/v{version}{}", info.path);
+            info.operation_id = format!("{}_v{version}", info.operation_id);
+            info.deprecated |= route.is_deprecated(version);
+            info.extensions
+                .insert(API_VERSION_EXTENSION.to_string(), version.into());
+            info
+        })
+        .collect()
+}
+
+/// How clients pick the API version of a versioned controller.
+///
+/// ```ignore
+/// AppBuilder::new()
+///     .build_state()
+///     .await
+///     .with(ApiVersioning::header("Api-Version").default_version(1))
+///     .register_controller::<UserController>()
+/// ```
+///
+/// | Strategy | Request | Routed to |
+/// |----------|---------|-----------|
+/// | [`url_prefix`](Self::url_prefix) (no plugin) | `GET /v2/users/7` | `/v2/users/7` |
+/// | [`header`](Self::header) | `GET /users/7` + `Api-Version: 2` | `/v2/users/7` |
+/// | [`media_type`](Self::media_type) | `GET /users/7` + `Accept: application/vnd.acme.v2+json` | `/v2/users/7` |
+///
+/// The header and media-type strategies rewrite the path before routing,
+/// only when it matches a versioned route. Without a version in the
+/// request, the [`default_version`](Self::default_version) is used, or
+/// else the latest version serving the path. The media-type strategy also
+/// replaces the vendor type in `Accept` with its suffix
+/// (`application/json`), so `#[produces]` negotiation keeps working.
+/// Rewritten responses carry `Vary` with the header they depend on. The
+/// `/v{n}` URLs stay reachable either way.
+///
+/// Routes that have no OpenAPI metadata — `#[any]` and `{*wildcard}`
+/// paths — are only reachable through their `/v{n}` URL.
+pub struct ApiVersioning {
+    strategy: Strategy,
+    default_version: Option<u32>,
+}
+
+enum Strategy {
+    UrlPrefix,
+    Header(HeaderName),
+    MediaType(String),
+}
+
+impl ApiVersioning {
+    /// Versions are selected by the `/v{n}` path prefix only. The default.
+    pub fn url_prefix() -> Self {
+        Self {
+            strategy: Strategy::UrlPrefix,
+            default_version: None,
+        }
+    }
+
+    /// Versions are selected by a request header holding the version
+    /// number, such as `Api-Version: 2`.
+    ///
+    /// # Panics
+    ///
+    /// If `name` is not a valid header name.
+    pub fn header(name: &str) -> Self {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .unwrap_or_else(|_| panic!("invalid API version header name: {name:?}"));
+        Self {
+            strategy: Strategy::Header(name),
+            default_version: None,
+        }
+    }
+
+    /// Versions are selected by a vendor media type in `Accept`:
+    /// `media_type("application/vnd.acme")` reads the `2` of
+    /// `application/vnd.acme.v2+json`.
+    pub fn media_type(vendor_type: &str) -> Self {
+        Self {
+            strategy: Strategy::MediaType(vendor_type.to_ascii_lowercase()),
+            default_version: None,
+        }
+    }
+
+    /// The version of requests that name none. Without it, they get the
+    /// latest version serving the path.
+    pub fn default_version(mut self, version: u32) -> Self {
+        self.default_version = Some(version);
+        self
+    }
+
+    /// Wrap the built router in the pre-routing rewrite, from the versioned
+    /// routes in `routes`.
+    pub(crate) fn wrap(self, router: Router, routes: &[RouteInfo]) -> Router {
+        if matches!(self.strategy, Strategy::UrlPrefix) {
+            return router;
+        }
+        let templates: Vec<(u32, Vec<Segment>)> = routes
+            .iter()
+            .filter_map(|route| {
+                let version = route.api_version()?;
+                let path = route.path.strip_prefix(&format!("/v{version}"))?;
+                Some((version, parse_template(path)))
+            })
+            .collect();
+        if templates.is_empty() {
+            return router;
+        }
+        let rewriter = Arc::new(Rewriter {
+            strategy: self.strategy,
+            default_version: self.default_version,
+            templates,
+        });
+        // Same wrap-and-re-embed as `layers::normalize_path_router`: a
+        // `Router::layer` runs after routing and could not change the match.
+        use tower::Layer as _;
+        let svc = from_fn(move |req: Request, next: Next| {
+            let rewriter = rewriter.clone();
+            async move { rewriter.run(req, next).await }
+        })
+        .layer(router);
+        Router::new().fallback_service(svc)
+    }
+}
+
+impl Plugin for ApiVersioning {
+    fn install<T: Clone + Send + Sync + 'static>(self, app: AppBuilder<T>) -> AppBuilder<T> {
+        app.set_api_versioning(self)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Literal(String),
+    Param,
+    Rest,
+}
+
+fn parse_template(path: &str) -> Vec<Segment> {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            if segment.starts_with("{*") {
+                Segment::Rest
+            } else if segment.starts_with('{') {
+                Segment::Param
+            } else {
+                Segment::Literal(segment.to_string())
+            }
+        })
+        .collect()
+}
+
+fn matches(template: &[Segment], path: &[&str]) -> bool {
+    let mut path = path.iter();
+    for segment in template {
+        match segment {
+            Segment::Rest => return true,
+            Segment::Param => {
+                if path.next().is_none() {
+                    return false;
+                }
+            }
+            Segment::Literal(literal) => {
+                if path.next() != Some(&literal.as_str()) {
+                    return false;
+                }
+            }
+        }
+    }
+    path.next().is_none()
+}
+
+struct Rewriter {
+    strategy: Strategy,
+    default_version: Option<u32>,
+    templates: Vec<(u32, Vec<Segment>)>,
+}
+
+impl Rewriter {
+    async fn run(&self, mut req: Request, next: Next) -> Response {
+        let segments: Vec<&str> = req
+            .uri()
+            .path()
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        let served: Vec<u32> = self
+            .templates
+            .iter()
+            .filter(|(_, template)| matches(template, &segments))
+            .map(|(version, _)| *version)
+            .collect();
+        if served.is_empty() {
+            return next.run(req).await;
+        }
+
+        let (requested, vary) = match &self.strategy {
+            Strategy::UrlPrefix => return next.run(req).await,
+            Strategy::Header(name) => match req.headers().get(name) {
+                None => (None, name.as_str()),
+                Some(value) => match value.to_str().ok().and_then(|v| v.trim().parse().ok()) {
+                    Some(version) => (Some(version), name.as_str()),
+                    None => {
+                        return HttpError::bad_request(format!(
+                            "Invalid {name} header: expected a version number"
+                        ))
+                        .into_response()
+                    }
+                },
+            },
+            Strategy::MediaType(vendor_type) => {
+                (rewrite_accept(&mut req, vendor_type), ACCEPT.as_str())
+            }
+        };
+        let version = match requested.or(self.default_version) {
+            Some(version) => served.contains(&version).then_some(version),
+            None => served.iter().max().copied(),
+        };
+        let vary = HeaderValue::from_str(vary).expect("a header name is a valid header value");
+
+        if let Some(version) = version {
+            let uri = req.uri();
+            let path_and_query = match uri.query() {
+                Some(query) => format!("/v{version}{}?{query}", uri.path()),
+                None => format!("/v{version}{}", uri.path()),
+            };
+            let mut parts = uri.clone().into_parts();
+            parts.path_and_query = path_and_query.parse().ok();
+            if let Ok(uri) = Uri::from_parts(parts) {
+                *req.uri_mut() = uri;
+            }
+        }
+        let mut response = next.run(req).await;
+        response.headers_mut().append(VARY, vary);
+        response
+    }
+}
+
+/// The version named by `vendor_type.v{n}[+suffix]` in `Accept`, with that
+/// media range replaced by `application/{suffix}` (`*/*` without a suffix).
+fn rewrite_accept(req: &mut Request, vendor_type: &str) -> Option<u32> {
+    let accept = req.headers().get(ACCEPT)?.to_str().ok()?;
+    let mut version = None;
+    let ranges: Vec<String> = accept
+        .split(',')
+        .map(|range| {
+            let range = range.trim();
+            let (media_type, params) = match range.split_once(';') {
+                Some((media_type, params)) => (media_type.trim(), Some(params)),
+                None => (range, None),
+            };
+            let lower = media_type.to_ascii_lowercase();
+            let Some(rest) = lower
+                .strip_prefix(vendor_type)
+                .and_then(|rest| rest.strip_prefix(".v"))
+            else {
+                return range.to_string();
+            };
+            let (number, suffix) = match rest.split_once('+') {
+                Some((number, suffix)) => (number, Some(suffix)),
+                None => (rest, None),
+            };
+            let Ok(number) = number.parse::<u32>() else {
+                return range.to_string();
+            };
+            version.get_or_insert(number);
+            let replacement = match suffix {
+                Some(suffix) => format!("application/{suffix}"),
+                None => "*/*".to_string(),
+            };
+            match params {
+                Some(params) => format!("{replacement};{params}"),
+                None => replacement,
+            }
+        })
+        .collect();
+    if version.is_some() {
+        if let Ok(value) = HeaderValue::from_str(&ranges.join(", ")) {
+            req.headers_mut().insert(ACCEPT, value);
+        }
+    }
+    version
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_route_serves_its_part_of_the_controller_range() {
+        let route = RouteVersions {
+            since: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(route.range((1, 3)), 2..=3);
+        let route = RouteVersions {
+            until: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(route.range((1, 3)), 1..=1);
+        assert_eq!(RouteVersions::default().range((1, 3)), 1..=3);
+    }
+
+    #[test]
+    fn templates_match_parameters_and_wildcards() {
+        let path = ["users", "7", "posts"];
+        assert!(matches(&parse_template("/users/{id}/posts"), &path));
+        assert!(matches(&parse_template("/users/{*rest}"), &path));
+        assert!(!matches(&parse_template("/users/{id}"), &path));
+        assert!(!matches(&parse_template("/users/{id}/posts/{post}"), &path));
+        assert!(matches(&parse_template("/users/"), &["users"]));
+    }
+
+    #[test]
+    fn vendor_media_types_name_the_version() {
+        let mut req = Request::builder()
+            .header(ACCEPT, "text/html, application/vnd.acme.v2+json;q=0.9")
+            .body(crate::http::Body::empty())
+            .unwrap();
+        assert_eq!(rewrite_accept(&mut req, "application/vnd.acme"), Some(2));
+        assert_eq!(req.headers()[ACCEPT], "text/html, application/json;q=0.9");
+
+        let mut req = Request::builder()
+            .header(ACCEPT, "application/vnd.other.v2+json")
+            .body(crate::http::Body::empty())
+            .unwrap();
+        assert_eq!(rewrite_accept(&mut req, "application/vnd.acme"), None);
+    }
+
+    #[test]
+    fn deprecation_headers_use_structured_and_http_dates() {
+        let route = RouteVersions {
+            deprecated_in: Some(2),
+            deprecation_date: Some(1_767_225_600),
+            sunset: Some(1_798_761_600),
+            ..Default::default()
+        };
+        let (deprecation, sunset) = route.headers();
+        assert_eq!(deprecation, "@1767225600");
+        assert_eq!(sunset.unwrap(), "Fri, 01 Jan 2027 00:00:00 GMT");
+        assert!(!route.is_deprecated(1));
+        assert!(route.is_deprecated(3));
+    }
+}
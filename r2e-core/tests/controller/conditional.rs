@@ -0,0 +1,248 @@
+//! `#[etag]`: `304` from `If-None-Match` on GETs (body hash or `Tagged`
+//! version), `428` / `412` from `If-Match` on modifying routes.
+
+use crate::support::{body_string, raw, send};
+use r2e_core::http::{Body, Router, StatusCode};
+use r2e_core::meta::{ErrorResponseInfo, RouteInfo};
+use r2e_core::prelude::*;
+use r2e_core::AppBuilder;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Document {
+    revision: u32,
+    title: String,
+}
+
+impl Versioned for Document {
+    fn version(&self) -> String {
+        format!("r{}", self.revision)
+    }
+
+    fn last_modified(&self) -> Option<SystemTime> {
+        Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000 + u64::from(self.revision)))
+    }
+}
+
+#[derive(Clone)]
+pub struct Store(Arc<Mutex<Document>>);
+
+#[derive(Deserialize)]
+pub struct Rename {
+    title: String,
+}
+
+#[controller(path = "/docs")]
+pub struct DocumentController {
+    #[inject]
+    store: Store,
+}
+
+#[routes]
+#[etag]
+impl DocumentController {
+    #[get("/current")]
+    async fn get(&self) -> Tagged<Document> {
+        Tagged(self.store.0.lock().unwrap().clone())
+    }
+
+    #[get("/title")]
+    async fn title(&self) -> Json<String> {
+        Json(self.store.0.lock().unwrap().title.clone())
+    }
+
+    #[put("/current")]
+    async fn rename(
+        &self,
+        pre: Preconditions,
+        Json(rename): Json<Rename>,
+    ) -> Result<Tagged<Document>, HttpError> {
+        let mut doc = self.store.0.lock().unwrap();
+        pre.check(&*doc)?;
+        doc.revision += 1;
+        doc.title = rename.title;
+        Ok(Tagged(doc.clone()))
+    }
+
+    #[post("/drafts")]
+    async fn draft(&self) -> StatusCode {
+        StatusCode::CREATED
+    }
+}
+
+async fn router() -> Router {
+    AppBuilder::new()
+        .provide(Store(Arc::new(Mutex::new(Document {
+            revision: 1,
+            title: "draft".into(),
+        }))))
+        .build_state()
+        .await
+        .register_controller::<DocumentController>()
+        .build()
+}
+
+#[r2e_core::test]
+async fn get_answers_304_while_the_tag_is_current() {
+    let app = router().await;
+
+    let resp = raw(app.clone(), "GET", "/docs/current", &[], Body::empty()).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["etag"], "\"r1\"");
+    assert_eq!(
+        resp.headers()["last-modified"],
+        "Tue, 14 Nov 2023 22:13:21 GMT"
+    );
+
+    let resp = raw(
+        app.clone(),
+        "GET",
+        "/docs/current",
+        &[("if-none-match", "\"r0\", W/\"r1\"")],
+        Body::empty(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(resp.headers()["etag"], "\"r1\"");
+    assert_eq!(body_string(resp).await, "");
+
+    let resp = raw(
+        app.clone(),
+        "GET",
+        "/docs/current",
+        &[("if-modified-since", "Tue, 14 Nov 2023 22:13:21 GMT")],
+        Body::empty(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+
+    let (status, body) = send(
+        app,
+        "GET",
+        "/docs/current",
+        &[("if-none-match", "\"r0\"")],
+        Body::empty(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, r#"{"revision":1,"title":"draft"}"#);
+}
+
+#[r2e_core::test]
+async fn untagged_responses_are_tagged_by_body_hash() {
+    let app = router().await;
+
+    let resp = raw(app.clone(), "GET", "/docs/title", &[], Body::empty()).await;
+    let etag = resp.headers()["etag"].to_str().unwrap().to_string();
+    assert_eq!(etag, ETag::of_json("draft").unwrap().to_string());
+    assert_eq!(body_string(resp).await, "\"draft\"");
+
+    let resp = raw(
+        app,
+        "GET",
+        "/docs/title",
+        &[("if-none-match", &etag)],
+        Body::empty(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+}
+
+#[r2e_core::test]
+async fn modifications_require_a_current_precondition() {
+    let app = router().await;
+    let put = |headers: &'static [(&'static str, &'static str)]| {
+        send(
+            app.clone(),
+            "PUT",
+            "/docs/current",
+            headers,
+            Body::from(r#"{"title":"final"}"#),
+        )
+    };
+
+    let (status, body) = put(&[("content-type", "application/json")]).await;
+    assert_eq!(status, StatusCode::PRECONDITION_REQUIRED);
+    assert_eq!(
+        body,
+        r#"{"error":"Precondition required: send If-Match with the ETag of the current representation"}"#
+    );
+
+    let (status, _) = put(&[
+        ("content-type", "application/json"),
+        ("if-match", "W/\"r1\""),
+    ])
+    .await;
+    assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+
+    let (status, _) = put(&[
+        ("content-type", "application/json"),
+        ("if-unmodified-since", "Tue, 14 Nov 2023 22:13:20 GMT"),
+    ])
+    .await;
+    assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+
+    let (status, body) = put(&[("content-type", "application/json"), ("if-match", "\"r1\"")]).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, r#"{"revision":2,"title":"final"}"#);
+
+    // The lost update: a second writer still holding r1 is refused.
+    let (status, body) = put(&[("content-type", "application/json"), ("if-match", "\"r1\"")]).await;
+    assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+    assert_eq!(
+        body,
+        r#"{"error":"Precondition failed: the resource has been modified"}"#
+    );
+
+    let (status, _) = put(&[("content-type", "application/json"), ("if-match", "*")]).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // POST routes inherit the impl-level #[etag] as a no-op.
+    let (status, _) = send(app, "POST", "/docs/drafts", &[], Body::empty()).await;
+    assert_eq!(status, StatusCode::CREATED);
+}
+
+#[r2e_core::test]
+async fn route_info_documents_conditional_responses() {
+    let seen: Arc<Mutex<Vec<RouteInfo>>> = Arc::default();
+    let sink = seen.clone();
+    let _router = AppBuilder::new()
+        .provide(Store(Arc::new(Mutex::new(Document {
+            revision: 1,
+            title: "draft".into(),
+        }))))
+        .build_state()
+        .await
+        .register_controller::<DocumentController>()
+        .with_meta_consumer::<RouteInfo, _>(move |routes| {
+            sink.lock().unwrap().extend_from_slice(routes);
+            Router::new()
+        })
+        .build();
+
+    let routes = seen.lock().unwrap();
+    let route = |method: &str| {
+        routes
+            .iter()
+            .find(|r| r.method == method && r.path == "/docs/current")
+            .unwrap()
+    };
+
+    let get = route("GET");
+    assert_eq!(get.response_type.as_deref(), Some("Document"));
+    assert!(get
+        .error_responses
+        .contains(&ErrorResponseInfo::new(304, "Not modified")));
+    assert!(get.params.iter().any(|p| p.name == "If-None-Match"));
+
+    let put = route("PUT");
+    assert!(put
+        .error_responses
+        .contains(&ErrorResponseInfo::new(412, "Precondition failed")));
+    assert!(put
+        .error_responses
+        .contains(&ErrorResponseInfo::new(428, "Precondition required")));
+    assert!(put.params.iter().any(|p| p.name == "If-Match"));
+}
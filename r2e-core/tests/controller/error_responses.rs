@@ -0,0 +1,85 @@
+//! `RouteInfo.error_responses`: the `#[routes]` macro documents the variants
+//! of a handler's `#[derive(ApiError)]` error type, and `429` for routes
+//! guarded by a `RateLimit`.
+
+use r2e_core::http::response::Response;
+use r2e_core::meta::{ErrorResponseInfo, RouteInfo};
+use r2e_core::prelude::*;
+use r2e_core::{Guard, GuardContext, Identity, SelfBuilt};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, ApiError)]
+pub enum ItemError {
+    #[error(status = NOT_FOUND, message = "Item not found")]
+    NotFound,
+    #[error(status = CONFLICT)]
+    Duplicate,
+}
+
+/// Stand-in for `r2e_rate_limit::RateLimit`: detection goes by the guard
+/// type's name.
+struct RateLimit;
+
+impl SelfBuilt for RateLimit {}
+
+impl<I: Identity> Guard<I> for RateLimit {
+    fn check(
+        &self,
+        _ctx: &GuardContext<'_, I>,
+    ) -> impl std::future::Future<Output = Result<(), Response>> + Send {
+        std::future::ready(Ok(()))
+    }
+}
+
+#[controller]
+struct ItemController {}
+
+#[routes]
+impl ItemController {
+    #[get("/items/{id}")]
+    async fn show(&self, Path(id): Path<u64>) -> Result<Json<u64>, ItemError> {
+        Ok(Json(id))
+    }
+
+    #[get("/items")]
+    #[guard(RateLimit)]
+    async fn list(&self) -> Json<Vec<u64>> {
+        Json(Vec::new())
+    }
+}
+
+#[r2e_core::test]
+async fn routes_carry_declared_error_responses() {
+    let seen: Arc<Mutex<Vec<RouteInfo>>> = Arc::default();
+    let sink = seen.clone();
+    let _router = r2e_core::AppBuilder::new()
+        .build_state()
+        .await
+        .register_controller::<ItemController>()
+        .with_meta_consumer::<RouteInfo, _>(move |routes| {
+            sink.lock().unwrap().extend_from_slice(routes);
+            r2e_core::http::Router::new()
+        })
+        .build();
+
+    let routes = seen.lock().unwrap();
+    let errors = |path: &str| {
+        routes
+            .iter()
+            .find(|r| r.path == path)
+            .unwrap()
+            .error_responses
+            .clone()
+    };
+    assert_eq!(
+        errors("/items/{id}"),
+        vec![
+            ErrorResponseInfo::new(404, "Item not found"),
+            ErrorResponseInfo::new(409, "Duplicate"),
+        ]
+    );
+    assert_eq!(
+        errors("/items"),
+        vec![ErrorResponseInfo::new(429, "Too many requests")]
+    );
+}
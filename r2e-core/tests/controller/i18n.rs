@@ -0,0 +1,313 @@
+//! `I18n`: the request `Locale` from the query, the cookie, the identity
+//! claim and `Accept-Language`, and error responses translated with the
+//! `Messages` bean — `#[error(code = "...")]` variants, `HttpError`
+//! messages used as keys, and validation field errors.
+
+use crate::support::{body_string, raw, raw_get_with, send_get, send_get_with, write_file};
+use r2e_core::http::extract::FromRequestParts;
+use r2e_core::http::header::Parts;
+use r2e_core::http::{Body, Router, StatusCode};
+use r2e_core::prelude::*;
+use r2e_core::AppBuilder;
+use serde::Deserialize;
+
+const EN: &str = "
+greeting = Hello {name}
+user.not_found = User {0} not found
+order.too_large = Order of {quantity} items exceeds {limit}
+user.banned = This account is banned
+validation.failed = Invalid request
+validation.length_lower_than = {field} needs {min} characters or more
+";
+
+const FR: &str = "
+greeting = Bonjour {name}
+user.not_found = Utilisateur {0} introuvable
+order.too_large = Commande de {quantity} articles au-delà de {limit}
+user.banned = Ce compte est suspendu
+validation.failed = Requête invalide
+validation.length_lower_than = {field} doit faire au moins {min} caractères
+";
+
+fn messages() -> Messages {
+    Messages::new("en")
+        .with_bundle("en", EN)
+        .with_bundle("fr", FR)
+}
+
+#[derive(Debug, ApiError)]
+pub enum ShopError {
+    #[error(status = NOT_FOUND, message = "User {0} not found", code = "user.not_found")]
+    UnknownUser(String),
+    #[error(
+        status = BAD_REQUEST,
+        message = "Order of {quantity} items exceeds {limit}",
+        code = "order.too_large"
+    )]
+    TooLarge { quantity: u32, limit: u32 },
+    #[error(status = CONFLICT, message = "Out of stock")]
+    OutOfStock,
+}
+
+/// An identity whose claims are the JSON of the `x-claims` header, recorded
+/// like the security extractors do.
+pub struct Claims;
+
+impl<S: Send + Sync> FromRequestParts<S> for Claims {
+    type Rejection = HttpError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let claims = parts
+            .headers
+            .get("x-claims")
+            .and_then(|v| serde_json::from_slice(v.as_bytes()).ok())
+            .ok_or_else(|| HttpError::unauthorized("no claims"))?;
+        r2e_core::i18n::record_claims(&claims);
+        Ok(Claims)
+    }
+}
+
+#[derive(Deserialize, garde::Validate)]
+pub struct NewUser {
+    #[garde(length(min = 3))]
+    name: String,
+}
+
+#[controller(path = "/")]
+pub struct ShopController {
+    #[inject]
+    messages: Messages,
+}
+
+#[routes]
+impl ShopController {
+    #[get("/hello")]
+    async fn hello(&self, locale: Locale) -> String {
+        self.messages
+            .format(&locale, "greeting", &[("name", &"Ada")])
+    }
+
+    #[get("/me")]
+    async fn me(&self, _user: Claims, locale: Locale) -> String {
+        locale.to_string()
+    }
+
+    #[get("/users/{name}")]
+    async fn user(&self, Path(name): Path<String>) -> Result<String, ShopError> {
+        Err(ShopError::UnknownUser(name))
+    }
+
+    #[get("/orders")]
+    async fn order(&self) -> Result<String, ShopError> {
+        Err(ShopError::TooLarge {
+            quantity: 12,
+            limit: 10,
+        })
+    }
+
+    #[get("/stock")]
+    async fn stock(&self) -> Result<String, ShopError> {
+        Err(ShopError::OutOfStock)
+    }
+
+    #[get("/banned")]
+    async fn banned(&self) -> Result<String, HttpError> {
+        Err(HttpError::forbidden("user.banned"))
+    }
+
+    #[post("/users")]
+    async fn create(&self, Json(user): Json<NewUser>) -> String {
+        user.name
+    }
+}
+
+#[controller(path = "/plain")]
+pub struct PlainController;
+
+#[routes]
+impl PlainController {
+    #[get("/")]
+    async fn locale(&self, locale: Option<Locale>) -> String {
+        format!("{locale:?}")
+    }
+}
+
+async fn router(i18n: I18n) -> Router {
+    AppBuilder::new()
+        .provide(messages())
+        .build_state()
+        .await
+        .with(i18n)
+        .register_controller::<ShopController>()
+        .build()
+}
+
+async fn json(resp: r2e_core::http::response::Response) -> serde_json::Value {
+    serde_json::from_str(&body_string(resp).await).unwrap()
+}
+
+#[r2e_core::test]
+async fn locale_follows_query_cookie_accept_language_then_default() {
+    let app = router(I18n::new()).await;
+    let fr = [("accept-language", "de, fr;q=0.8, en;q=0.5")];
+
+    assert_eq!(send_get(app.clone(), "/hello").await.1, "Hello Ada");
+    assert_eq!(
+        send_get_with(app.clone(), "/hello", &fr).await.1,
+        "Bonjour Ada"
+    );
+    assert_eq!(
+        send_get_with(app.clone(), "/hello", &[("cookie", "theme=dark; lang=fr")])
+            .await
+            .1,
+        "Bonjour Ada"
+    );
+    assert_eq!(
+        send_get_with(app.clone(), "/hello?lang=en", &[("cookie", "lang=fr")])
+            .await
+            .1,
+        "Hello Ada"
+    );
+    // Unsupported locales are ignored.
+    assert_eq!(
+        send_get_with(app, "/hello?lang=de", &fr).await.1,
+        "Bonjour Ada"
+    );
+}
+
+#[r2e_core::test]
+async fn regional_locales_fall_back_to_their_language() {
+    let app = router(I18n::new()).await;
+    assert_eq!(
+        send_get(app.clone(), "/hello?lang=fr_CA").await.1,
+        "Bonjour Ada"
+    );
+    assert_eq!(
+        send_get_with(app, "/hello", &[("accept-language", "fr-BE")])
+            .await
+            .1,
+        "Bonjour Ada"
+    );
+}
+
+#[r2e_core::test]
+async fn sources_can_be_renamed_or_turned_off() {
+    let app = router(I18n::new().query_param("").cookie("locale")).await;
+    assert_eq!(send_get(app.clone(), "/hello?lang=fr").await.1, "Hello Ada");
+    assert_eq!(
+        send_get_with(app, "/hello", &[("cookie", "locale=fr")])
+            .await
+            .1,
+        "Bonjour Ada"
+    );
+}
+
+#[r2e_core::test]
+async fn locale_without_the_plugin() {
+    let app = AppBuilder::new()
+        .build_state()
+        .await
+        .register_controller::<PlainController>()
+        .build();
+    assert_eq!(send_get(app, "/plain").await.1, "None");
+
+    let app = AppBuilder::new()
+        .build_state()
+        .await
+        .with(I18n::new().supported(["en", "fr"]))
+        .register_controller::<PlainController>()
+        .build();
+    assert_eq!(
+        send_get(app, "/plain?lang=fr").await.1,
+        r#"Some(Locale("fr"))"#
+    );
+}
+
+#[r2e_core::test]
+async fn api_errors_are_translated_by_code() {
+    let app = router(I18n::new()).await;
+
+    let resp = raw_get_with(app.clone(), "/users/ada?lang=fr", &[]).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    assert_eq!(resp.headers()["content-language"], "fr");
+    assert_eq!(json(resp).await["error"], "Utilisateur ada introuvable");
+
+    let resp = raw_get_with(app.clone(), "/orders?lang=fr", &[]).await;
+    assert_eq!(
+        json(resp).await["error"],
+        "Commande de 12 articles au-delà de 10"
+    );
+
+    // English keeps the same text; variants without a code are untouched.
+    let resp = raw_get_with(app.clone(), "/users/ada", &[]).await;
+    assert_eq!(json(resp).await["error"], "User ada not found");
+    let resp = raw_get_with(app, "/stock?lang=fr", &[]).await;
+    assert!(resp.headers().get("content-language").is_none());
+    assert_eq!(json(resp).await["error"], "Out of stock");
+}
+
+#[r2e_core::test]
+async fn http_error_messages_are_looked_up_as_keys() {
+    let app = router(I18n::new()).await;
+    let resp = raw_get_with(app, "/banned?lang=fr", &[]).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    assert_eq!(json(resp).await["error"], "Ce compte est suspendu");
+}
+
+#[r2e_core::test]
+async fn validation_errors_are_translated_by_rule() {
+    let app = router(I18n::new()).await;
+    let resp = raw(
+        app,
+        "POST",
+        "/users?lang=fr",
+        &[("content-type", "application/json")],
+        Body::from(r#"{"name":"al"}"#),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body = json(resp).await;
+    assert_eq!(body["error"], "Requête invalide");
+    assert_eq!(body["details"][0]["code"], "length_lower_than");
+    assert_eq!(
+        body["details"][0]["message"],
+        "name doit faire au moins 3 caractères"
+    );
+}
+
+#[r2e_core::test]
+async fn identity_claim_picks_the_locale() {
+    let app = router(I18n::new()).await;
+    let headers = [
+        ("x-claims", r#"{"locale":"fr-CA"}"#),
+        ("accept-language", "en"),
+    ];
+    assert_eq!(send_get_with(app.clone(), "/me", &headers).await.1, "fr");
+    // The query still wins over the claim.
+    assert_eq!(send_get_with(app, "/me?lang=en", &headers).await.1, "en");
+}
+
+#[test]
+fn bundles_load_with_profile_overlays() {
+    let dir = tempfile::tempdir().unwrap();
+    write_file(
+        dir.path(),
+        "messages.properties",
+        "greeting = Hello\nfarewell = Bye\n",
+    );
+    write_file(dir.path(), "messages_fr.properties", "greeting = Bonjour\n");
+    write_file(
+        dir.path(),
+        "messages_fr-dev.properties",
+        "greeting = Salut\n",
+    );
+
+    let messages = Messages::load_from(dir.path(), "en", None).unwrap();
+    let fr = Locale::parse("fr").unwrap();
+    assert_eq!(messages.get(&fr, "greeting"), Some("Bonjour"));
+    assert_eq!(messages.get(&fr, "farewell"), Some("Bye"));
+
+    let messages = Messages::load_from(dir.path(), "en", Some("dev")).unwrap();
+    assert_eq!(messages.get(&fr, "greeting"), Some("Salut"));
+    assert_eq!(messages.format(&fr, "missing", &[]), "missing");
+}
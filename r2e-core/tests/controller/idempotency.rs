@@ -0,0 +1,286 @@
+//! `#[idempotent]`: stored responses replayed per `Idempotency-Key` and
+//! subject, `409` for a duplicate in flight, `422` for a reused key,
+//! released keys after a server error, and `413` for an oversized body.
+
+use crate::fixtures::Subject;
+use crate::support::{body_string, raw, send};
+use r2e_core::http::{Body, Router, StatusCode};
+use r2e_core::meta::{ErrorResponseInfo, RouteInfo};
+use r2e_core::prelude::*;
+use r2e_core::AppBuilder;
+use serde::Deserialize;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+#[derive(Clone, Default)]
+pub struct Ledger {
+    charges: Arc<AtomicU32>,
+    started: Arc<Notify>,
+    release: Arc<Notify>,
+}
+
+#[derive(Deserialize)]
+pub struct Charge {
+    amount: u32,
+}
+
+#[controller(path = "/payments")]
+pub struct PaymentController {
+    #[inject]
+    ledger: Ledger,
+    #[inject(identity)]
+    user: Subject,
+}
+
+#[routes]
+impl PaymentController {
+    #[post("/")]
+    #[idempotent(ttl = "1h")]
+    async fn charge(&self, Json(charge): Json<Charge>) -> (StatusCode, Json<String>) {
+        let id = self.ledger.charges.fetch_add(1, Ordering::SeqCst) + 1;
+        (
+            StatusCode::CREATED,
+            Json(format!("{} paid {} (#{id})", self.user.0, charge.amount)),
+        )
+    }
+
+    #[post("/slow")]
+    #[idempotent]
+    async fn slow(&self) -> StatusCode {
+        self.ledger.started.notify_one();
+        self.ledger.release.notified().await;
+        StatusCode::ACCEPTED
+    }
+
+    #[post("/notes")]
+    #[idempotent]
+    #[body_limit("1KB")]
+    async fn note(&self, body: String) -> StatusCode {
+        self.ledger
+            .charges
+            .fetch_add(body.len() as u32, Ordering::SeqCst);
+        StatusCode::CREATED
+    }
+
+    #[post("/flaky")]
+    #[idempotent]
+    async fn flaky(&self) -> Result<StatusCode, HttpError> {
+        match self.ledger.charges.fetch_add(1, Ordering::SeqCst) {
+            0 => Err(HttpError::internal("gateway down")),
+            _ => Ok(StatusCode::CREATED),
+        }
+    }
+}
+
+async fn router(ledger: Ledger) -> Router {
+    AppBuilder::new()
+        .provide(ledger)
+        .provide(InMemoryIdempotencyStore::shared())
+        .build_state()
+        .await
+        .register_controller::<PaymentController>()
+        .build()
+}
+
+fn charge(
+    app: &Router,
+    user: &'static str,
+    key: Option<&'static str>,
+    amount: u32,
+) -> impl std::future::Future<Output = r2e_core::http::Response> {
+    let mut headers = vec![("content-type", "application/json"), ("x-user", user)];
+    headers.extend(key.map(|key| ("idempotency-key", key)));
+    let app = app.clone();
+    async move {
+        raw(
+            app,
+            "POST",
+            "/payments",
+            &headers,
+            Body::from(format!(r#"{{"amount":{amount}}}"#)),
+        )
+        .await
+    }
+}
+
+#[r2e_core::test]
+async fn retries_replay_the_first_response() {
+    let ledger = Ledger::default();
+    let app = router(ledger.clone()).await;
+
+    let first = charge(&app, "ada", Some("k-1"), 10).await;
+    assert_eq!(first.status(), StatusCode::CREATED);
+    assert!(!first.headers().contains_key("idempotent-replayed"));
+    assert_eq!(body_string(first).await, r#""ada paid 10 (#1)""#);
+
+    let retry = charge(&app, "ada", Some("k-1"), 10).await;
+    assert_eq!(retry.status(), StatusCode::CREATED);
+    assert_eq!(retry.headers()["idempotent-replayed"], "true");
+    assert_eq!(retry.headers()["content-type"], "application/json");
+    assert_eq!(body_string(retry).await, r#""ada paid 10 (#1)""#);
+    assert_eq!(ledger.charges.load(Ordering::SeqCst), 1);
+
+    // Keys are scoped by subject; requests without a key always run.
+    let other = charge(&app, "bob", Some("k-1"), 10).await;
+    assert_eq!(body_string(other).await, r#""bob paid 10 (#2)""#);
+    charge(&app, "ada", None, 10).await;
+    charge(&app, "ada", None, 10).await;
+    assert_eq!(ledger.charges.load(Ordering::SeqCst), 4);
+}
+
+#[r2e_core::test]
+async fn a_reused_key_with_another_body_is_rejected() {
+    let app = router(Ledger::default()).await;
+
+    charge(&app, "ada", Some("k-2"), 10).await;
+    let resp = charge(&app, "ada", Some("k-2"), 99).await;
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        body_string(resp).await,
+        r#"{"error":"Idempotency-Key already used for a different request"}"#
+    );
+
+    let (status, _) = send(
+        app,
+        "POST",
+        "/payments",
+        &[
+            ("content-type", "application/json"),
+            ("x-user", "ada"),
+            ("idempotency-key", "not a key"),
+        ],
+        Body::from(r#"{"amount":10}"#),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[r2e_core::test]
+async fn a_duplicate_in_flight_conflicts() {
+    let ledger = Ledger::default();
+    let app = router(ledger.clone()).await;
+    const HEADERS: &[(&str, &str)] = &[("x-user", "ada"), ("idempotency-key", "k-3")];
+
+    let first = tokio::spawn(send(
+        app.clone(),
+        "POST",
+        "/payments/slow",
+        HEADERS,
+        Body::empty(),
+    ));
+    ledger.started.notified().await;
+
+    let (status, body) = send(
+        app.clone(),
+        "POST",
+        "/payments/slow",
+        HEADERS,
+        Body::empty(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(
+        body,
+        r#"{"error":"A request with this Idempotency-Key is already in progress"}"#
+    );
+
+    ledger.release.notify_one();
+    assert_eq!(first.await.unwrap().0, StatusCode::ACCEPTED);
+    let resp = raw(app, "POST", "/payments/slow", HEADERS, Body::empty()).await;
+    assert_eq!(resp.status(), StatusCode::ACCEPTED);
+    assert_eq!(resp.headers()["idempotent-replayed"], "true");
+}
+
+#[r2e_core::test]
+async fn server_errors_release_the_key() {
+    let app = router(Ledger::default()).await;
+    let headers = [("x-user", "ada"), ("idempotency-key", "k-4")];
+
+    let (status, _) = send(
+        app.clone(),
+        "POST",
+        "/payments/flaky",
+        &headers,
+        Body::empty(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    let resp = raw(
+        app.clone(),
+        "POST",
+        "/payments/flaky",
+        &headers,
+        Body::empty(),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    assert!(!resp.headers().contains_key("idempotent-replayed"));
+    let resp = raw(app, "POST", "/payments/flaky", &headers, Body::empty()).await;
+    assert_eq!(resp.headers()["idempotent-replayed"], "true");
+}
+
+#[r2e_core::test]
+async fn an_oversized_body_is_rejected_before_claiming_the_key() {
+    let ledger = Ledger::default();
+    let app = router(ledger.clone()).await;
+    let post = |path: &'static str, body: Vec<u8>| {
+        raw(
+            app.clone(),
+            "POST",
+            path,
+            &[("x-user", "ada"), ("idempotency-key", "k-big")],
+            Body::from(body),
+        )
+    };
+
+    // Without `#[body_limit]`, axum's 2 MB default applies.
+    let resp = post("/payments", vec![b' '; 2 * 1024 * 1024 + 1]).await;
+    assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(
+        body_string(resp).await,
+        r#"{"error":"Request body too large"}"#
+    );
+    let resp = post("/payments/notes", vec![b'a'; 1025]).await;
+    assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(ledger.charges.load(Ordering::SeqCst), 0);
+
+    // The key was not claimed: a body within the limit runs.
+    let resp = post("/payments/notes", vec![b'a'; 1024]).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    assert_eq!(ledger.charges.load(Ordering::SeqCst), 1024);
+}
+
+#[r2e_core::test]
+async fn route_info_documents_the_key() {
+    let seen: Arc<Mutex<Vec<RouteInfo>>> = Arc::default();
+    let sink = seen.clone();
+    let _router = AppBuilder::new()
+        .provide(Ledger::default())
+        .provide(InMemoryIdempotencyStore::shared())
+        .build_state()
+        .await
+        .register_controller::<PaymentController>()
+        .with_meta_consumer::<RouteInfo, _>(move |routes| {
+            sink.lock().unwrap().extend_from_slice(routes);
+            Router::new()
+        })
+        .build();
+
+    let routes = seen.lock().unwrap();
+    let route = routes.iter().find(|r| r.path == "/payments/").unwrap();
+    assert!(route.error_responses.contains(&ErrorResponseInfo::new(
+        409,
+        "Request with this Idempotency-Key in progress"
+    )));
+    assert!(route.error_responses.contains(&ErrorResponseInfo::new(
+        422,
+        "Idempotency-Key reused for a different request"
+    )));
+    let key = route
+        .params
+        .iter()
+        .find(|p| p.name == "Idempotency-Key")
+        .unwrap();
+    assert!(!key.required);
+}
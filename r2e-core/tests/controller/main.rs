@@ -6,10 +6,19 @@
 mod support;
 
 mod anonymous;
+mod conditional;
 mod config;
 mod core_path;
+mod error_responses;
 mod facade;
 mod fixtures;
+mod i18n;
+mod idempotency;
+mod negotiation;
 mod proxy_routes;
+mod route_limits;
 mod scope;
+mod session;
+mod templating;
 mod tuple;
+mod versioning;
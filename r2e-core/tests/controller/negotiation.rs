@@ -0,0 +1,290 @@
+//! `#[produces]` / `#[consumes]`: `Negotiated<T>` rendered from `Accept`,
+//! `Body<T>` decoded from `Content-Type`, 406 / 415, a custom codec in the
+//! `MediaTypeCodecs` bean and the media types carried in `RouteInfo`.
+
+use crate::support::{body_string, raw, send};
+use r2e_core::http::{Body as HttpBody, Router, StatusCode};
+use r2e_core::meta::{ErrorResponseInfo, RouteInfo};
+use r2e_core::negotiation::{
+    Body, CodecError, Decode, Encode, JsonCodec, MediaTypeCodec, MediaTypeCodecs, Negotiated,
+};
+use r2e_core::prelude::*;
+use r2e_core::AppBuilder;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Report {
+    name: String,
+    rows: u32,
+}
+
+/// `application/yaml`, registered by the tests on top of the defaults.
+struct YamlCodec;
+
+impl MediaTypeCodec for YamlCodec {
+    fn media_type(&self) -> &str {
+        "application/yaml"
+    }
+
+    fn encode(&self, value: &Encode<'_>) -> Result<Vec<u8>, CodecError> {
+        Ok(serde_yaml::to_string(value)?.into_bytes())
+    }
+
+    fn decode(&self, body: &[u8], target: &mut Decode<'_>) -> Result<(), CodecError> {
+        target.deserialize(serde_yaml::Deserializer::from_slice(body))
+    }
+}
+
+#[controller(path = "/reports")]
+pub struct ReportController {}
+
+#[routes]
+#[produces("application/json", "application/yaml")]
+impl ReportController {
+    #[get("/")]
+    async fn get(&self) -> Negotiated<Report> {
+        Negotiated(Report {
+            name: "sales".into(),
+            rows: 3,
+        })
+    }
+
+    #[post("/")]
+    #[consumes("application/json", "application/x-www-form-urlencoded")]
+    async fn create(&self, Body(report): Body<Report>) -> Negotiated<Report> {
+        Negotiated(report)
+    }
+
+    #[post("/plain")]
+    #[produces("application/json")]
+    async fn plain(&self, Body(report): Body<Report>) -> Negotiated<Report> {
+        Negotiated(report)
+    }
+}
+
+#[controller(path = "/unnegotiated")]
+pub struct DefaultController {}
+
+#[routes]
+impl DefaultController {
+    #[post("/")]
+    async fn echo(&self, Body(report): Body<Report>) -> Negotiated<Report> {
+        Negotiated(report)
+    }
+}
+
+#[controller(path = "/xml")]
+pub struct XmlController {}
+
+#[routes]
+impl XmlController {
+    #[get("/")]
+    #[produces("application/xml")]
+    async fn get(&self) -> Negotiated<Report> {
+        unreachable!()
+    }
+}
+
+async fn router() -> Router {
+    AppBuilder::new()
+        .provide(MediaTypeCodecs::default().with(YamlCodec))
+        .build_state()
+        .await
+        .register_controller::<ReportController>()
+        .register_controller::<DefaultController>()
+        .build()
+}
+
+#[r2e_core::test]
+async fn produces_renders_the_accepted_media_type() {
+    let app = router().await;
+
+    let resp = raw(app.clone(), "GET", "/reports", &[], HttpBody::empty()).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "application/json");
+    assert_eq!(resp.headers()["vary"], "accept");
+    assert_eq!(body_string(resp).await, r#"{"name":"sales","rows":3}"#);
+
+    let resp = raw(
+        app.clone(),
+        "GET",
+        "/reports",
+        &[("accept", "application/json;q=0.5, application/yaml")],
+        HttpBody::empty(),
+    )
+    .await;
+    assert_eq!(resp.headers()["content-type"], "application/yaml");
+    assert_eq!(body_string(resp).await, "name: sales\nrows: 3\n");
+
+    let (status, body) = send(
+        app,
+        "GET",
+        "/reports",
+        &[("accept", "text/html")],
+        HttpBody::empty(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_ACCEPTABLE);
+    assert_eq!(
+        body,
+        r#"{"error":"Not acceptable: this endpoint produces application/json, application/yaml"}"#
+    );
+}
+
+#[r2e_core::test]
+async fn consumes_decodes_each_listed_media_type() {
+    let app = router().await;
+
+    let (status, body) = send(
+        app.clone(),
+        "POST",
+        "/reports",
+        &[("content-type", "application/x-www-form-urlencoded")],
+        HttpBody::from("name=q3&rows=12"),
+    )
+    .await;
+    assert_eq!(
+        (status, body.as_str()),
+        (StatusCode::OK, r#"{"name":"q3","rows":12}"#)
+    );
+
+    let (status, body) = send(
+        app.clone(),
+        "POST",
+        "/reports",
+        &[("content-type", "application/json; charset=utf-8")],
+        HttpBody::from(r#"{"name":"q4","rows":1}"#),
+    )
+    .await;
+    assert_eq!(
+        (status, body.as_str()),
+        (StatusCode::OK, r#"{"name":"q4","rows":1}"#)
+    );
+
+    let (status, body) = send(
+        app.clone(),
+        "POST",
+        "/reports",
+        &[("content-type", "text/plain")],
+        HttpBody::from("q4"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    assert_eq!(
+        body,
+        r#"{"error":"Unsupported media type `text/plain`: this endpoint consumes application/json, application/x-www-form-urlencoded"}"#
+    );
+
+    let (status, _) = send(
+        app,
+        "POST",
+        "/reports",
+        &[("content-type", "application/x-www-form-urlencoded")],
+        HttpBody::from("name=q3&rows=many"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[r2e_core::test]
+async fn body_without_consumes_reads_json_only() {
+    let app = router().await;
+
+    let (status, body) = send(
+        app.clone(),
+        "POST",
+        "/reports/plain",
+        &[("content-type", "application/json")],
+        HttpBody::from(r#"{"name":"q1","rows":7}"#),
+    )
+    .await;
+    assert_eq!(
+        (status, body.as_str()),
+        (StatusCode::OK, r#"{"name":"q1","rows":7}"#)
+    );
+
+    let (status, _) = send(
+        app,
+        "POST",
+        "/reports/plain",
+        &[("content-type", "application/x-www-form-urlencoded")],
+        HttpBody::from("name=q1&rows=7"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
+
+#[r2e_core::test]
+async fn negotiated_without_produces_renders_json() {
+    let resp = raw(
+        router().await,
+        "POST",
+        "/unnegotiated",
+        &[
+            ("content-type", "application/json"),
+            ("accept", "application/yaml"),
+        ],
+        HttpBody::from(r#"{"name":"q2","rows":5}"#),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "application/json");
+    assert!(!resp.headers().contains_key("vary"));
+    assert_eq!(body_string(resp).await, r#"{"name":"q2","rows":5}"#);
+}
+
+#[r2e_core::test]
+#[should_panic(expected = "no MediaTypeCodec registered for #[produces(\"application/xml\")]")]
+async fn produces_without_a_codec_fails_startup() {
+    let _ = AppBuilder::new()
+        .build_state()
+        .await
+        .register_controller::<XmlController>()
+        .build();
+}
+
+#[r2e_core::test]
+async fn route_info_lists_media_types() {
+    let seen: Arc<Mutex<Vec<RouteInfo>>> = Arc::default();
+    let sink = seen.clone();
+    let _router = AppBuilder::new()
+        .provide(MediaTypeCodecs::empty().with(JsonCodec).with(YamlCodec))
+        .build_state()
+        .await
+        .register_controller::<ReportController>()
+        .with_meta_consumer::<RouteInfo, _>(move |routes| {
+            sink.lock().unwrap().extend_from_slice(routes);
+            Router::new()
+        })
+        .build();
+
+    let routes = seen.lock().unwrap();
+    let route = |method: &str, path: &str| {
+        routes
+            .iter()
+            .find(|r| r.method == method && r.path == path)
+            .unwrap()
+            .clone()
+    };
+
+    let create = route("POST", "/reports/");
+    assert_eq!(create.produces, ["application/json", "application/yaml"]);
+    assert_eq!(
+        create.consumes,
+        ["application/json", "application/x-www-form-urlencoded"]
+    );
+    assert_eq!(create.request_body_type.as_deref(), Some("Report"));
+    assert_eq!(create.response_type.as_deref(), Some("Report"));
+    assert_eq!(
+        create.error_responses,
+        vec![
+            ErrorResponseInfo::new(406, "Not acceptable"),
+            ErrorResponseInfo::new(415, "Unsupported media type"),
+        ]
+    );
+
+    let plain = route("POST", "/reports/plain");
+    assert_eq!(plain.produces, ["application/json"]);
+    assert!(plain.consumes.is_empty());
+}
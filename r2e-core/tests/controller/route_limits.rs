@@ -0,0 +1,232 @@
+//! `#[timeout]`, `#[body_limit]` and `#[max_concurrent]`: per-route layers
+//! answering 408 / 413 / 503, impl-level defaults, `r2e.routes.*` config
+//! overrides and the limits carried in `RouteInfo`.
+
+use crate::support::{body_string, send, send_get};
+use r2e_core::config::{ConfigValue, R2eConfig};
+use r2e_core::http::{Body, Bytes, Router, StatusCode};
+use r2e_core::meta::{ErrorResponseInfo, RouteInfo};
+use r2e_core::prelude::*;
+use r2e_core::AppBuilder;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+static STARTED: Notify = Notify::const_new();
+static RELEASE: Notify = Notify::const_new();
+
+#[controller(path = "/limits")]
+pub struct LimitController {}
+
+#[routes]
+#[timeout("50ms")]
+impl LimitController {
+    #[get("/slow")]
+    async fn slow(&self) -> &'static str {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        "done"
+    }
+
+    #[get("/patient")]
+    #[timeout("5s")]
+    async fn patient(&self) -> &'static str {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        "done"
+    }
+
+    #[post("/upload")]
+    #[body_limit("1KB")]
+    async fn upload(&self, body: Bytes) -> String {
+        body.len().to_string()
+    }
+
+    #[get("/exclusive")]
+    #[max_concurrent(1)]
+    #[timeout(5)]
+    async fn exclusive(&self) -> &'static str {
+        STARTED.notify_one();
+        RELEASE.notified().await;
+        "done"
+    }
+}
+
+async fn router(config: R2eConfig) -> Router {
+    AppBuilder::new()
+        .override_config(config)
+        .load_config::<()>()
+        .build_state()
+        .await
+        .register_controller::<LimitController>()
+        .build()
+}
+
+#[r2e_core::test]
+async fn timeout_answers_408() {
+    let (status, body) = send_get(router(R2eConfig::empty()).await, "/limits/slow").await;
+    assert_eq!(status, StatusCode::REQUEST_TIMEOUT);
+    assert_eq!(body, r#"{"error":"Request timed out"}"#);
+}
+
+#[r2e_core::test]
+async fn method_timeout_overrides_controller_default() {
+    let (status, body) = send_get(router(R2eConfig::empty()).await, "/limits/patient").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "done");
+}
+
+#[r2e_core::test]
+async fn body_limit_rejects_large_content_length() {
+    let app = router(R2eConfig::empty()).await;
+    let (status, body) = send(
+        app.clone(),
+        "POST",
+        "/limits/upload",
+        &[],
+        Body::from(vec![0u8; 512]),
+    )
+    .await;
+    assert_eq!((status, body.as_str()), (StatusCode::OK, "512"));
+
+    let (status, body) = send(
+        app,
+        "POST",
+        "/limits/upload",
+        &[],
+        Body::from(vec![0u8; 2048]),
+    )
+    .await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(body, r#"{"error":"Request body exceeds 1024 bytes"}"#);
+}
+
+#[r2e_core::test]
+async fn body_limit_rejects_large_chunked_body() {
+    let chunks = (0..4).map(|_| Ok::<_, std::io::Error>(Bytes::from(vec![0u8; 512])));
+    let body = Body::from_stream(tokio_stream::iter(chunks));
+    let (status, _) = send(
+        router(R2eConfig::empty()).await,
+        "POST",
+        "/limits/upload",
+        &[],
+        body,
+    )
+    .await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[r2e_core::test]
+async fn max_concurrent_answers_503_when_saturated() {
+    let app = router(R2eConfig::empty()).await;
+    let first = tokio::spawn(send_get(app.clone(), "/limits/exclusive"));
+    STARTED.notified().await;
+
+    let (status, body) = send_get(app, "/limits/exclusive").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body, r#"{"error":"Too many concurrent requests"}"#);
+
+    RELEASE.notify_one();
+    let (status, _) = first.await.unwrap();
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[r2e_core::test]
+async fn config_overrides_route_limits() {
+    let mut config = R2eConfig::empty();
+    config.set(
+        "r2e.routes.LimitController.slow.timeout",
+        ConfigValue::Integer(0),
+    );
+    config.set(
+        "r2e.routes.LimitController.upload.body_limit",
+        ConfigValue::String("4KB".into()),
+    );
+    let app = router(config).await;
+
+    let (status, _) = send_get(app.clone(), "/limits/slow").await;
+    assert_eq!(status, StatusCode::OK);
+    let resp = crate::support::raw(
+        app,
+        "POST",
+        "/limits/upload",
+        &[],
+        Body::from(vec![0u8; 2048]),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(body_string(resp).await, "2048");
+}
+
+#[r2e_core::test]
+async fn zero_body_limit_override_lifts_the_default_limit_too() {
+    let mut config = R2eConfig::empty();
+    config.set(
+        "r2e.routes.LimitController.upload.body_limit",
+        ConfigValue::Integer(0),
+    );
+    let app = router(config).await;
+
+    // Beyond both the route's 1 KB and axum's 2 MB default.
+    let size = 3 << 20;
+    let resp = crate::support::raw(
+        app,
+        "POST",
+        "/limits/upload",
+        &[],
+        Body::from(vec![0u8; size]),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(body_string(resp).await, size.to_string());
+}
+
+#[r2e_core::test]
+#[should_panic(expected = "r2e.routes.LimitController.upload.body_limit")]
+async fn invalid_config_override_fails_startup() {
+    let mut config = R2eConfig::empty();
+    config.set(
+        "r2e.routes.LimitController.upload.body_limit",
+        ConfigValue::String("lots".into()),
+    );
+    let _ = router(config).await;
+}
+
+#[r2e_core::test]
+async fn route_info_documents_limits() {
+    let seen: Arc<Mutex<Vec<RouteInfo>>> = Arc::default();
+    let sink = seen.clone();
+    let mut config = R2eConfig::empty();
+    config.set(
+        "r2e.routes.LimitController.exclusive.max_concurrent",
+        ConfigValue::Integer(8),
+    );
+    let _router = AppBuilder::new()
+        .override_config(config)
+        .load_config::<()>()
+        .build_state()
+        .await
+        .register_controller::<LimitController>()
+        .with_meta_consumer::<RouteInfo, _>(move |routes| {
+            sink.lock().unwrap().extend_from_slice(routes);
+            Router::new()
+        })
+        .build();
+
+    let routes = seen.lock().unwrap();
+    let route = |path: &str| routes.iter().find(|r| r.path == path).unwrap().clone();
+
+    let upload = route("/limits/upload");
+    assert_eq!(upload.extensions["x-r2e-timeout-ms"], 50);
+    assert_eq!(upload.extensions["x-r2e-body-limit"], 1024);
+    assert!(!upload.extensions.contains_key("x-r2e-max-concurrent"));
+    assert_eq!(
+        upload.error_responses,
+        vec![
+            ErrorResponseInfo::new(408, "Request timed out"),
+            ErrorResponseInfo::new(413, "Request body too large"),
+        ]
+    );
+
+    let exclusive = route("/limits/exclusive");
+    assert_eq!(exclusive.extensions["x-r2e-timeout-ms"], 5000);
+    assert_eq!(exclusive.extensions["x-r2e-max-concurrent"], 8);
+}
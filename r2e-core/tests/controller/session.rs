@@ -0,0 +1,377 @@
+//! `Sessions`: typed session values and flash messages kept across
+//! requests by an encrypted cookie, the CSRF token, id rotation on login,
+//! session-backed identities, idle / absolute timeouts, and store failures.
+
+use crate::support::{body_string, raw};
+use r2e_core::http::response::Response;
+use r2e_core::http::{Body, Router, StatusCode};
+use r2e_core::prelude::*;
+use r2e_core::session::{Flash, SessionRecord, StoreError, StoreFuture};
+use r2e_core::AppBuilder;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+const SECRET: &str = "0123456789abcdef0123456789abcdef";
+
+#[derive(Serialize, Deserialize)]
+pub struct Member {
+    id: String,
+}
+
+impl Identity for Member {
+    fn sub(&self) -> &str {
+        &self.id
+    }
+}
+
+impl SessionIdentity for Member {}
+
+#[controller(path = "/")]
+pub struct ShopController;
+
+#[routes]
+impl ShopController {
+    #[post("/cart/{item}")]
+    async fn add(&self, session: Session, Path(item): Path<String>) -> Result<String, HttpError> {
+        let mut cart: Vec<String> = session.get("cart").unwrap_or_default();
+        cart.push(item);
+        session.insert("cart", &cart)?;
+        Ok(cart.join(","))
+    }
+
+    #[get("/cart")]
+    async fn cart(&self, session: Session) -> String {
+        session
+            .get::<Vec<String>>("cart")
+            .unwrap_or_default()
+            .join(",")
+    }
+
+    #[get("/form")]
+    async fn form(&self, Extension(token): Extension<CsrfToken>) -> Html<String> {
+        Html(format!("<input name=\"csrf_token\" value=\"{}\">", token.0))
+    }
+
+    #[post("/form")]
+    async fn submit(&self, session: Session, body: String) -> StatusCode {
+        if body == session.csrf_token() {
+            StatusCode::NO_CONTENT
+        } else {
+            StatusCode::FORBIDDEN
+        }
+    }
+
+    #[post("/flash")]
+    async fn flash(&self, session: Session) -> StatusCode {
+        session.flash("notice", "Saved");
+        StatusCode::SEE_OTHER
+    }
+
+    #[get("/flashes")]
+    async fn flashes(&self, session: Session) -> Json<Vec<Flash>> {
+        Json(session.flashes())
+    }
+
+    #[post("/login/{id}")]
+    async fn login(
+        &self,
+        session: Session,
+        Path(id): Path<String>,
+    ) -> Result<StatusCode, HttpError> {
+        session.login(&Member { id })?;
+        Ok(StatusCode::NO_CONTENT)
+    }
+
+    #[post("/logout")]
+    async fn logout(&self, session: Session) -> StatusCode {
+        session.logout();
+        StatusCode::NO_CONTENT
+    }
+
+    #[get("/me")]
+    async fn me(&self, #[inject(identity)] user: Member) -> String {
+        user.id
+    }
+
+    #[get("/whoami")]
+    async fn whoami(&self, #[inject(identity)] user: Option<Member>) -> String {
+        user.map_or_else(|| "anonymous".into(), |user| user.id)
+    }
+}
+
+#[controller(path = "/plain")]
+pub struct PlainController;
+
+#[routes]
+impl PlainController {
+    #[get("/")]
+    async fn session(&self, session: Option<Session>) -> String {
+        session.is_some().to_string()
+    }
+}
+
+async fn router(sessions: Sessions, store: Arc<dyn SessionStore>) -> Router {
+    AppBuilder::new()
+        .provide(store)
+        .build_state()
+        .await
+        .with(sessions)
+        .register_controller::<ShopController>()
+        .build()
+}
+
+async fn app() -> (Router, InMemorySessionStore) {
+    let store = InMemorySessionStore::new();
+    let router = router(Sessions::new(SECRET), Arc::new(store.clone())).await;
+    (router, store)
+}
+
+/// Send a request with `cookie`, returning the response and the new
+/// session cookie, if any.
+async fn call(
+    app: &Router,
+    method: &str,
+    path: &str,
+    cookie: Option<&str>,
+) -> (Response, Option<String>) {
+    let headers: Vec<(&str, &str)> = cookie.map(|c| ("cookie", c)).into_iter().collect();
+    let resp = raw(app.clone(), method, path, &headers, Body::empty()).await;
+    let set = resp
+        .headers()
+        .get("set-cookie")
+        .map(|v| v.to_str().unwrap().split(';').next().unwrap().to_string());
+    (resp, set)
+}
+
+async fn text(app: &Router, method: &str, path: &str, cookie: Option<&str>) -> String {
+    body_string(call(app, method, path, cookie).await.0).await
+}
+
+#[r2e_core::test]
+async fn values_are_kept_across_requests() {
+    let (app, store) = app().await;
+
+    let (resp, cookie) = call(&app, "POST", "/cart/apple", None).await;
+    let set_cookie = resp.headers()["set-cookie"].to_str().unwrap().to_string();
+    assert!(set_cookie.starts_with("session="));
+    assert!(set_cookie.ends_with("; Path=/; HttpOnly; SameSite=Lax; Secure"));
+    let cookie = cookie.unwrap();
+    assert_eq!(store.len(), 1);
+
+    let (resp, set) = call(&app, "POST", "/cart/pear", Some(&cookie)).await;
+    assert_eq!(body_string(resp).await, "apple,pear");
+    assert_eq!(set, None);
+    assert_eq!(
+        text(&app, "GET", "/cart", Some(&cookie)).await,
+        "apple,pear"
+    );
+    assert_eq!(store.len(), 1);
+}
+
+#[r2e_core::test]
+async fn untouched_sessions_are_not_stored() {
+    let (app, store) = app().await;
+    let (resp, set) = call(&app, "GET", "/cart", None).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(set, None);
+    assert!(store.is_empty());
+}
+
+#[r2e_core::test]
+async fn forged_cookies_start_a_new_session() {
+    let (app, _) = app().await;
+    let (_, cookie) = call(&app, "POST", "/cart/apple", None).await;
+    let cookie = cookie.unwrap();
+
+    let forged = format!("{}x", cookie);
+    assert_eq!(text(&app, "GET", "/cart", Some(&forged)).await, "");
+    assert_eq!(text(&app, "GET", "/cart", Some("session=abc")).await, "");
+
+    // Another secret cannot read the cookie.
+    let other = router(
+        Sessions::new("another secret of at least 32 bytes"),
+        InMemorySessionStore::shared(),
+    )
+    .await;
+    assert_eq!(text(&other, "GET", "/cart", Some(&cookie)).await, "");
+}
+
+#[r2e_core::test]
+async fn pages_get_the_csrf_token_of_the_session() {
+    let (app, _) = app().await;
+    let (resp, cookie) = call(&app, "GET", "/form", None).await;
+    let cookie = cookie.expect("HTML pages keep their session");
+    let page = body_string(resp).await;
+    let token = page.split('"').nth(3).unwrap().to_string();
+    assert_eq!(token.len(), 43);
+
+    let submit = |body: String, cookie: String| {
+        let app = app.clone();
+        async move {
+            raw(
+                app,
+                "POST",
+                "/form",
+                &[("cookie", &cookie)],
+                Body::from(body),
+            )
+            .await
+            .status()
+        }
+    };
+    assert_eq!(
+        submit(token.clone(), cookie.clone()).await,
+        StatusCode::NO_CONTENT
+    );
+    assert_eq!(
+        submit("guess".into(), cookie.clone()).await,
+        StatusCode::FORBIDDEN
+    );
+
+    // The token stays the same for the session.
+    let (resp, _) = call(&app, "GET", "/form", Some(&cookie)).await;
+    assert!(body_string(resp).await.contains(&token));
+}
+
+#[r2e_core::test]
+async fn flash_messages_are_shown_once() {
+    let (app, _) = app().await;
+    let (_, cookie) = call(&app, "POST", "/flash", None).await;
+    let cookie = cookie.unwrap();
+
+    assert_eq!(
+        text(&app, "GET", "/flashes", Some(&cookie)).await,
+        r#"[{"level":"notice","message":"Saved"}]"#
+    );
+    assert_eq!(text(&app, "GET", "/flashes", Some(&cookie)).await, "[]");
+}
+
+#[r2e_core::test]
+async fn login_rotates_the_session_id() {
+    let (app, store) = app().await;
+    let (_, anonymous) = call(&app, "POST", "/cart/apple", None).await;
+    let anonymous = anonymous.unwrap();
+
+    let (resp, _) = call(&app, "GET", "/me", Some(&anonymous)).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        text(&app, "GET", "/whoami", Some(&anonymous)).await,
+        "anonymous"
+    );
+
+    let (resp, signed_in) = call(&app, "POST", "/login/ada", Some(&anonymous)).await;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    let signed_in = signed_in.expect("login sets a new cookie");
+    assert_ne!(signed_in, anonymous);
+    assert_eq!(store.len(), 1);
+
+    assert_eq!(text(&app, "GET", "/me", Some(&signed_in)).await, "ada");
+    assert_eq!(text(&app, "GET", "/whoami", Some(&signed_in)).await, "ada");
+    // The data moved with the session; the old id is gone.
+    assert_eq!(text(&app, "GET", "/cart", Some(&signed_in)).await, "apple");
+    assert_eq!(text(&app, "GET", "/cart", Some(&anonymous)).await, "");
+}
+
+#[r2e_core::test]
+async fn logout_destroys_the_session() {
+    let (app, store) = app().await;
+    let (_, cookie) = call(&app, "POST", "/login/ada", None).await;
+    let cookie = cookie.unwrap();
+
+    let (resp, _) = call(&app, "POST", "/logout", Some(&cookie)).await;
+    let set_cookie = resp.headers()["set-cookie"].to_str().unwrap();
+    assert!(set_cookie.starts_with("session=; Path=/; Max-Age=0"));
+    assert!(store.is_empty());
+
+    let (resp, _) = call(&app, "GET", "/me", Some(&cookie)).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[r2e_core::test]
+async fn idle_sessions_expire() {
+    let app = router(
+        Sessions::new(SECRET).idle_timeout(Duration::from_millis(200)),
+        InMemorySessionStore::shared(),
+    )
+    .await;
+    let (_, cookie) = call(&app, "POST", "/cart/apple", None).await;
+    let cookie = cookie.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(120)).await;
+    assert_eq!(text(&app, "GET", "/cart", Some(&cookie)).await, "apple");
+    tokio::time::sleep(Duration::from_millis(120)).await;
+    // Still alive: the previous request reset the idle timer.
+    assert_eq!(text(&app, "GET", "/cart", Some(&cookie)).await, "apple");
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert_eq!(text(&app, "GET", "/cart", Some(&cookie)).await, "");
+}
+
+#[r2e_core::test]
+async fn sessions_end_at_the_absolute_timeout() {
+    let app = router(
+        Sessions::new(SECRET).absolute_timeout(Duration::from_millis(300)),
+        InMemorySessionStore::shared(),
+    )
+    .await;
+    let (_, cookie) = call(&app, "POST", "/cart/apple", None).await;
+    let cookie = cookie.unwrap();
+
+    for _ in 0..2 {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(text(&app, "GET", "/cart", Some(&cookie)).await, "apple");
+    }
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(text(&app, "GET", "/cart", Some(&cookie)).await, "");
+}
+
+/// A store that is always down.
+struct DownStore;
+
+impl SessionStore for DownStore {
+    fn load<'a>(&'a self, _id: &'a str) -> StoreFuture<'a, Option<SessionRecord>> {
+        Box::pin(async { Err(StoreError::new("connection refused")) })
+    }
+
+    fn save<'a>(
+        &'a self,
+        _id: &'a str,
+        _record: &'a SessionRecord,
+        _ttl: Duration,
+    ) -> StoreFuture<'a, ()> {
+        Box::pin(async { Err(StoreError::new("connection refused")) })
+    }
+
+    fn delete<'a>(&'a self, _id: &'a str) -> StoreFuture<'a, ()> {
+        Box::pin(async { Err(StoreError::new("connection refused")) })
+    }
+}
+
+#[r2e_core::test]
+async fn store_failures_are_503() {
+    let app = router(Sessions::new(SECRET), Arc::new(DownStore)).await;
+    let (resp, set) = call(&app, "POST", "/cart/apple", None).await;
+    assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(set, None);
+}
+
+#[r2e_core::test]
+async fn session_without_the_plugin() {
+    let app = AppBuilder::new()
+        .build_state()
+        .await
+        .register_controller::<PlainController>()
+        .build();
+    assert_eq!(text(&app, "GET", "/plain", None).await, "false");
+}
+
+#[test]
+#[should_panic(expected = "at least 32 bytes")]
+fn short_secrets_are_rejected() {
+    let _ = Sessions::new("too short");
+}
+
+#[test]
+fn session_identities_have_one_extraction_route() {
+    r2e_core::assert_unambiguous_extractor::<(), Member, _>();
+}
@@ -0,0 +1,195 @@
+//! `#[template]`: the returned context rendered by the `TemplateEngine`
+//! bean with the `identity` / `request_id` / `csrf_token` globals, errors
+//! passed through, and unknown templates rejected at startup.
+
+use crate::fixtures::Subject;
+use crate::support::{raw_get_with, send_get, send_get_with};
+use r2e_core::http::middleware::Next;
+use r2e_core::http::response::Response;
+use r2e_core::http::{Request, Router, StatusCode};
+use r2e_core::meta::RouteInfo;
+use r2e_core::prelude::*;
+use r2e_core::templating::TemplateError;
+use r2e_core::AppBuilder;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+
+/// Renders `<name>|<context as JSON>`, for the templates it knows.
+struct EchoEngine;
+
+impl TemplateEngine for EchoEngine {
+    fn render(&self, name: &str, context: &serde_json::Value) -> Result<String, TemplateError> {
+        Ok(format!("{name}|{context}"))
+    }
+
+    fn check(&self, name: &str) -> Result<(), TemplateError> {
+        match name {
+            "users/list.html" | "users/show.html" => Ok(()),
+            _ => Err(TemplateError::new(format!("template `{name}` not found"))),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct UserList {
+    users: Vec<&'static str>,
+}
+
+#[derive(Serialize)]
+pub struct UserPage {
+    name: String,
+    request_id: &'static str,
+}
+
+async fn with_csrf(mut req: Request, next: Next) -> Response {
+    req.extensions_mut().insert(CsrfToken("t0k3n".into()));
+    next.run(req).await
+}
+
+#[controller(path = "/admin")]
+pub struct AdminController {
+    #[inject(identity)]
+    user: Subject,
+}
+
+#[routes]
+impl AdminController {
+    #[get("/users")]
+    #[template("users/list.html")]
+    #[middleware(with_csrf)]
+    async fn list(&self) -> UserList {
+        UserList {
+            users: vec!["ada", "bob"],
+        }
+    }
+
+    #[get("/users/{name}")]
+    #[template("users/show.html")]
+    async fn show(&self, Path(name): Path<String>) -> Result<UserPage, HttpError> {
+        if name == "nobody" {
+            return Err(HttpError::not_found("no such user"));
+        }
+        Ok(UserPage {
+            name,
+            request_id: "from the handler",
+        })
+    }
+}
+
+#[controller(path = "/broken")]
+pub struct BrokenController;
+
+#[routes]
+impl BrokenController {
+    #[get("/")]
+    #[template("missing.html")]
+    async fn page(&self) -> UserList {
+        UserList { users: vec![] }
+    }
+}
+
+fn engine() -> Arc<dyn TemplateEngine> {
+    Arc::new(EchoEngine)
+}
+
+async fn router() -> Router {
+    AppBuilder::new()
+        .provide(engine())
+        .build_state()
+        .await
+        .with(RequestIdPlugin)
+        .register_controller::<AdminController>()
+        .build()
+}
+
+fn rendered(body: &str) -> (&str, serde_json::Value) {
+    let (name, context) = body.split_once('|').unwrap();
+    (name, serde_json::from_str(context).unwrap())
+}
+
+#[r2e_core::test]
+async fn renders_the_context_with_the_globals() {
+    let app = router().await;
+    let resp = raw_get_with(
+        app.clone(),
+        "/admin/users",
+        &[("x-user", "ada"), ("x-request-id", "req-1")],
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "text/html; charset=utf-8");
+
+    let body = crate::support::body_string(resp).await;
+    let (name, context) = rendered(&body);
+    assert_eq!(name, "users/list.html");
+    assert_eq!(
+        context,
+        serde_json::json!({
+            "users": ["ada", "bob"],
+            "identity": { "sub": "ada", "email": null, "claims": null },
+            "request_id": "req-1",
+            "csrf_token": "t0k3n",
+        })
+    );
+}
+
+#[r2e_core::test]
+async fn context_fields_win_over_globals_and_errors_pass_through() {
+    let app = router().await;
+
+    let (status, body) = send_get_with(app.clone(), "/admin/users/bob", &[("x-user", "ada")]).await;
+    assert_eq!(status, StatusCode::OK);
+    let (name, context) = rendered(&body);
+    assert_eq!(name, "users/show.html");
+    assert_eq!(context["name"], "bob");
+    assert_eq!(context["request_id"], "from the handler");
+    assert_eq!(context["csrf_token"], serde_json::Value::Null);
+
+    let (status, body) =
+        send_get_with(app.clone(), "/admin/users/nobody", &[("x-user", "ada")]).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(body.contains("no such user"));
+
+    assert_eq!(
+        send_get(app, "/admin/users").await.0,
+        StatusCode::UNAUTHORIZED
+    );
+}
+
+#[r2e_core::test]
+async fn an_unknown_template_aborts_startup() {
+    let app = AppBuilder::new().provide(engine()).build_state().await;
+    let boot = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        app.register_controller::<BrokenController>().build()
+    }));
+    let panic = boot.unwrap_err();
+    let message = panic.downcast_ref::<String>().unwrap();
+    assert_eq!(
+        message,
+        "route `BrokenController.page`: #[template(\"missing.html\")]: template `missing.html` not found"
+    );
+}
+
+#[r2e_core::test]
+async fn the_operation_documents_html() {
+    let seen: Arc<Mutex<Vec<RouteInfo>>> = Arc::default();
+    let sink = seen.clone();
+    let _router = AppBuilder::new()
+        .provide(engine())
+        .build_state()
+        .await
+        .register_controller::<AdminController>()
+        .with_meta_consumer::<RouteInfo, _>(move |routes| {
+            sink.lock().unwrap().extend_from_slice(routes);
+            Router::new()
+        })
+        .build();
+
+    let routes = seen.lock().unwrap();
+    let list = routes
+        .iter()
+        .find(|r| r.operation_id == "AdminController_list")
+        .unwrap();
+    assert_eq!(list.response_content_type.as_deref(), Some("text/html"));
+    assert_eq!(list.response_type, None);
+}
@@ -0,0 +1,186 @@
+//! `#[controller(versions = "...")]`: one registration per version under
+//! `/v{n}`, `#[since]` / `#[until]` ranges, `Deprecation` / `Sunset`
+//! headers, and the header and media-type strategies of `ApiVersioning`.
+
+use crate::support::{raw_get_with, send_get, send_get_with};
+use r2e_core::http::{Router, StatusCode};
+use r2e_core::meta::RouteInfo;
+use r2e_core::prelude::*;
+use r2e_core::AppBuilder;
+use std::sync::{Arc, Mutex};
+
+#[controller(path = "/users", versions = "1..=3")]
+pub struct UserController;
+
+#[routes]
+impl UserController {
+    #[get("/{id}")]
+    #[until(1)]
+    async fn get_v1(&self, Path(id): Path<u32>) -> String {
+        format!("user {id} (legacy)")
+    }
+
+    #[get("/{id}")]
+    #[since(2)]
+    async fn get(&self, Path(id): Path<u32>, version: ApiVersion) -> String {
+        format!("user {id} ({version})")
+    }
+
+    #[get("/")]
+    #[deprecated_in(2, date = "2026-01-01", sunset = "2027-01-01")]
+    async fn list(&self) -> &'static str {
+        "users"
+    }
+}
+
+#[controller(path = "/health")]
+pub struct HealthController;
+
+#[routes]
+impl HealthController {
+    #[get("/")]
+    async fn health(&self, version: Option<ApiVersion>) -> String {
+        format!("{version:?}")
+    }
+}
+
+async fn router(versioning: Option<ApiVersioning>) -> Router {
+    let app = AppBuilder::new()
+        .build_state()
+        .await
+        .register_controller::<UserController>()
+        .register_controller::<HealthController>();
+    match versioning {
+        Some(versioning) => app.with(versioning).build(),
+        None => app.build(),
+    }
+}
+
+#[r2e_core::test]
+async fn each_version_is_served_under_its_prefix() {
+    let app = router(None).await;
+
+    assert_eq!(
+        send_get(app.clone(), "/v1/users/7").await,
+        (StatusCode::OK, "user 7 (legacy)".into())
+    );
+    assert_eq!(
+        send_get(app.clone(), "/v2/users/7").await,
+        (StatusCode::OK, "user 7 (v2)".into())
+    );
+    assert_eq!(send_get(app.clone(), "/v3/users/7").await.1, "user 7 (v3)");
+    assert_eq!(
+        send_get(app.clone(), "/v4/users/7").await.0,
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        send_get(app.clone(), "/users/7").await.0,
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(send_get(app, "/health").await.1, "None");
+}
+
+#[r2e_core::test]
+async fn deprecated_versions_carry_deprecation_and_sunset() {
+    let app = router(None).await;
+
+    let v1 = raw_get_with(app.clone(), "/v1/users", &[]).await;
+    assert_eq!(v1.status(), StatusCode::OK);
+    assert!(!v1.headers().contains_key("deprecation"));
+
+    for path in ["/v2/users", "/v3/users"] {
+        let resp = raw_get_with(app.clone(), path, &[]).await;
+        assert_eq!(resp.headers()["deprecation"], "@1767225600");
+        assert_eq!(resp.headers()["sunset"], "Fri, 01 Jan 2027 00:00:00 GMT");
+    }
+}
+
+#[r2e_core::test]
+async fn the_header_strategy_rewrites_unprefixed_paths() {
+    let app = router(Some(
+        ApiVersioning::header("Api-Version").default_version(1),
+    ))
+    .await;
+
+    let resp = raw_get_with(app.clone(), "/users/7", &[("api-version", "2")]).await;
+    assert_eq!(resp.headers()["vary"], "api-version");
+    assert_eq!(
+        send_get_with(app.clone(), "/users/7", &[("api-version", "2")]).await,
+        (StatusCode::OK, "user 7 (v2)".into())
+    );
+    assert_eq!(send_get(app.clone(), "/users/7").await.1, "user 7 (legacy)");
+    assert_eq!(
+        send_get_with(app.clone(), "/users/7", &[("api-version", "two")])
+            .await
+            .0,
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        send_get_with(app.clone(), "/users/7", &[("api-version", "9")])
+            .await
+            .0,
+        StatusCode::NOT_FOUND
+    );
+    // The canonical URL and unversioned routes are untouched.
+    assert_eq!(send_get(app.clone(), "/v3/users/7").await.1, "user 7 (v3)");
+    assert_eq!(
+        send_get_with(app, "/health", &[("api-version", "2")])
+            .await
+            .1,
+        "None"
+    );
+}
+
+#[r2e_core::test]
+async fn the_media_type_strategy_reads_accept() {
+    let app = router(Some(ApiVersioning::media_type("application/vnd.acme"))).await;
+
+    assert_eq!(
+        send_get_with(
+            app.clone(),
+            "/users/7",
+            &[("accept", "application/vnd.acme.v2+json")]
+        )
+        .await
+        .1,
+        "user 7 (v2)"
+    );
+    // No version requested: the latest one serving the path.
+    assert_eq!(send_get(app, "/users/7").await.1, "user 7 (v3)");
+}
+
+#[r2e_core::test]
+async fn route_info_has_one_entry_per_version() {
+    let seen: Arc<Mutex<Vec<RouteInfo>>> = Arc::default();
+    let sink = seen.clone();
+    let _router = AppBuilder::new()
+        .build_state()
+        .await
+        .register_controller::<UserController>()
+        .with_meta_consumer::<RouteInfo, _>(move |routes| {
+            sink.lock().unwrap().extend_from_slice(routes);
+            Router::new()
+        })
+        .build();
+
+    let routes = seen.lock().unwrap();
+    let mut paths: Vec<(String, Option<u32>, bool)> = routes
+        .iter()
+        .map(|r| (r.path.clone(), r.api_version(), r.deprecated))
+        .collect();
+    paths.sort();
+    assert_eq!(
+        paths,
+        vec![
+            ("/v1/users/".into(), Some(1), false),
+            ("/v1/users/{id}".into(), Some(1), false),
+            ("/v2/users/".into(), Some(2), true),
+            ("/v2/users/{id}".into(), Some(2), false),
+            ("/v3/users/".into(), Some(3), true),
+            ("/v3/users/{id}".into(), Some(3), false),
+        ]
+    );
+    assert!(routes
+        .iter()
+        .any(|r| r.operation_id == "UserController_get_v2"));
+}
@@ -171,6 +171,26 @@ async fn provided_post_construct_runs_after_factory_post_construct() {
     assert_eq!(*log.lock().unwrap(), vec!["factory", "provided"]);
 }
 
+#[r2e_core::test]
+async fn resolved_hook_runs_before_every_post_construct() {
+    let log: Log = Arc::new(Mutex::new(Vec::new()));
+
+    let mut reg = BeanRegistry::new();
+    reg.provide(log.clone());
+    reg.register::<FactoryInit>();
+    reg.register_resolved_hook(|ctx| {
+        let log: Log = ctx.get();
+        Box::pin(async move {
+            log.lock().unwrap().push("resolved");
+            Ok(())
+        })
+    });
+
+    reg.resolve().await.unwrap();
+
+    assert_eq!(*log.lock().unwrap(), vec!["resolved", "factory"]);
+}
+
 #[derive(Clone)]
 struct FailingProvided;
 
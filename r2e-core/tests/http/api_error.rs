@@ -238,3 +238,78 @@ fn source_some_for_from_variant() {
     let err: MixedError = io_err.into();
     assert!(std::error::Error::source(&err).is_some());
 }
+
+// ── ErrorResponses: per-variant OpenAPI metadata ────────────────────────
+
+#[derive(Debug, ApiError)]
+pub enum AccountError {
+    #[error(status = NOT_FOUND, message = "Account not found")]
+    NotFound,
+
+    #[error(status = UNPROCESSABLE_ENTITY)]
+    Invalid(r2e_core::validation::ValidationErrorResponse),
+
+    #[error(status = 429)]
+    Throttled,
+
+    #[error(transparent)]
+    Conflict(#[from] UnitError),
+}
+
+#[test]
+fn error_responses_list_every_variant() {
+    use r2e_core::meta::ErrorResponseInfo;
+    use r2e_core::ErrorResponses;
+
+    assert_eq!(
+        AccountError::error_responses(),
+        vec![
+            ErrorResponseInfo::new(404, "Account not found"),
+            ErrorResponseInfo::validation(422, "Invalid"),
+            ErrorResponseInfo::new(429, "Throttled"),
+            ErrorResponseInfo::new(409, "Already exists"),
+        ]
+    );
+}
+
+#[r2e_core::test]
+async fn validation_variant_renders_validation_body() {
+    let err = AccountError::Invalid(r2e_core::validation::ValidationErrorResponse {
+        errors: vec![r2e_core::validation::FieldError {
+            field: "email".into(),
+            message: "invalid email".into(),
+            code: "email".into(),
+        }],
+    });
+
+    let (status, body) = error_parts(err).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "error": "Validation failed",
+            "details": [{ "field": "email", "message": "invalid email", "code": "email" }],
+        })
+    );
+}
+
+#[r2e_core::test]
+async fn documented_variants_render_their_status() {
+    let (status, body) = error_parts(AccountError::NotFound).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"], "Account not found");
+
+    let (status, body) = error_parts(AccountError::Throttled).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(body["error"], "Throttled");
+}
+
+#[test]
+fn validation_variant_is_documented_with_validation_schema() {
+    use r2e_core::ErrorResponses;
+
+    let responses = AccountError::error_responses();
+    let invalid = responses.iter().find(|r| r.status == 422).unwrap();
+    assert_eq!(invalid.schema, "ValidationErrorResponse");
+    assert_eq!(invalid.description, "Invalid");
+}
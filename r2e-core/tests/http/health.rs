@@ -179,3 +179,36 @@ async fn advanced_health_readiness_filters() {
     let json: serde_json::Value = serde_json::from_str(&body).unwrap();
     assert_eq!(json["status"], "UP");
 }
+
+// ── Plugin-contributed indicators ────────────────────────────────────────
+
+use r2e_core::plugin::{PluginInstallContext, PreStatePlugin};
+
+/// Contributes [`AlwaysDown`] the way a datasource plugin contributes its
+/// connection check.
+struct ContributesCheck;
+
+impl PreStatePlugin for ContributesCheck {
+    type Provided = ();
+    type Deps = ();
+    type Config = ();
+
+    fn install(&mut self, ctx: &mut PluginInstallContext<'_>) {
+        ctx.add_health_check(AlwaysDown);
+    }
+}
+
+#[r2e_core::test]
+async fn advanced_health_includes_plugin_contributed_checks() {
+    let router = AppBuilder::new()
+        .plugin(ContributesCheck)
+        .build_state()
+        .await
+        .with(Health::builder().check(AlwaysUp).build())
+        .build();
+    let (status, body) = send_get(router, "/health/ready").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["checks"][0]["name"], "up-check");
+    assert_eq!(json["checks"][1]["name"], "down-check");
+}
@@ -0,0 +1,103 @@
+//! `#[derive(QueryFields)]` with the `Sort<T>` / `Filter<T>` extractors,
+//! standalone and nested in a `#[derive(Params)]` struct.
+
+use r2e_core::http::body::Body;
+use r2e_core::http::extract::FromRequestParts;
+use r2e_core::http::{Request, StatusCode};
+use r2e_core::pagination::{FilterOp, Pageable};
+use r2e_core::params::ParamsMetadata;
+use r2e_core::prelude::{Filter, Params, QueryFields, Sort};
+use serde::Serialize;
+
+#[derive(Serialize, QueryFields)]
+#[serde(rename_all = "camelCase")]
+#[allow(dead_code)]
+struct User {
+    #[sortable]
+    #[filterable(ops = "eq,in")]
+    status: String,
+    #[sortable(column = "u.created_at")]
+    #[filterable(ops = "lt,gte", cast = "timestamptz")]
+    created_at: String,
+    #[filterable]
+    age: u32,
+    password: String,
+}
+
+#[derive(Params)]
+struct ListUsers {
+    #[params]
+    page: Pageable,
+    #[params]
+    sort: Sort<User>,
+    #[params]
+    filter: Filter<User>,
+}
+
+async fn extract<T: FromRequestParts<()>>(uri: &str) -> Result<T, T::Rejection> {
+    let (mut parts, _) = Request::builder()
+        .uri(uri)
+        .body(Body::empty())
+        .unwrap()
+        .into_parts();
+    T::from_request_parts(&mut parts, &()).await
+}
+
+#[tokio::test]
+async fn extracts_sort_and_filter_alongside_pagination() {
+    let Ok(params) = extract::<ListUsers>(
+        "/users?page=1&sort=-createdAt,status&filter[status][in]=a,b&filter[createdAt][lt]=2024-01-01&filter[age]=30",
+    )
+    .await
+    else {
+        panic!("valid query rejected");
+    };
+    assert_eq!(params.page.page, 1);
+    assert_eq!(
+        params.sort.order_by().as_deref(),
+        Some("u.created_at DESC, status ASC")
+    );
+    let conditions = params.filter.conditions();
+    assert_eq!(conditions.len(), 3);
+    assert_eq!(conditions[1].column, "created_at");
+    assert_eq!(conditions[1].op, FilterOp::Lt);
+    assert_eq!(conditions[1].cast, Some("timestamptz"));
+    assert_eq!(conditions[2].value, serde_json::json!(30));
+}
+
+#[tokio::test]
+async fn rejects_fields_that_are_not_whitelisted() {
+    let Err(response) = extract::<Sort<User>>("/users?sort=password").await else {
+        panic!("unknown sort field accepted");
+    };
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let Err(response) =
+        extract::<Filter<User>>("/users?filter[password]=x&filter[age][lt]=3").await
+    else {
+        panic!("unknown filter accepted");
+    };
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = r2e_core::http::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["details"].as_array().unwrap().len(), 2);
+    assert_eq!(body["details"][0]["field"], "filter[password]");
+}
+
+#[test]
+fn documents_whitelisted_values() {
+    let infos = ListUsers::param_infos();
+    // `Pageable` documents its raw `sort` string too; the whitelisted one is a list.
+    let sort = infos
+        .iter()
+        .find(|p| p.name == "sort" && p.param_type == "array")
+        .unwrap();
+    assert_eq!(
+        sort.schema.as_ref().unwrap()["items"]["enum"],
+        serde_json::json!(["status", "-status", "createdAt", "-createdAt"])
+    );
+    assert!(infos.iter().any(|p| p.name == "filter[createdAt][gte]"));
+    assert!(infos.iter().all(|p| !p.name.contains("password")));
+}
@@ -9,10 +9,13 @@ mod api_error;
 mod error;
 mod extract;
 mod health;
+mod list_query;
 mod managed;
 #[cfg(feature = "multipart")]
 mod multipart;
+mod params;
 mod plugins;
+mod problem;
 mod request_id;
 mod secure_headers;
 mod sse;
@@ -0,0 +1,116 @@
+//! `#[derive(Params)]`: cookie and `Vec` query fields, and the OpenAPI
+//! parameter metadata (descriptions, examples, defaults, static schemas).
+
+use r2e_core::http::body::Body;
+use r2e_core::http::extract::FromRequestParts;
+use r2e_core::http::header::COOKIE;
+use r2e_core::http::{Request, StatusCode};
+use r2e_core::meta::ParamLocation;
+use r2e_core::params::ParamsMetadata;
+use r2e_core::prelude::Params;
+use serde_json::json;
+
+#[derive(Params)]
+struct Search {
+    /// Tags to match.
+    #[query]
+    tag: Vec<String>,
+    /// Comma-separated user ids.
+    #[query(explode = false)]
+    ids: Vec<u64>,
+    #[query]
+    #[param(default = 20u32, example = 50)]
+    limit: u32,
+    #[cookie(name = "sid")]
+    session: Option<String>,
+    #[cookie]
+    theme: String,
+}
+
+async fn extract<T: FromRequestParts<()>>(
+    uri: &str,
+    cookie: Option<&str>,
+) -> Result<T, T::Rejection> {
+    let mut request = Request::builder().uri(uri);
+    if let Some(cookie) = cookie {
+        request = request.header(COOKIE, cookie);
+    }
+    let (mut parts, _) = request.body(Body::empty()).unwrap().into_parts();
+    T::from_request_parts(&mut parts, &()).await
+}
+
+#[tokio::test]
+async fn extracts_vec_query_and_cookie_fields() {
+    let Ok(search) = extract::<Search>(
+        "/search?tag=a&tag=b&ids=1,2&ids=3",
+        Some("theme=dark; sid=abc"),
+    )
+    .await
+    else {
+        panic!("valid request rejected");
+    };
+    assert_eq!(search.tag, vec!["a", "b"]);
+    assert_eq!(search.ids, vec![1, 2, 3]);
+    assert_eq!(search.limit, 20);
+    assert_eq!(search.session.as_deref(), Some("abc"));
+    assert_eq!(search.theme, "dark");
+}
+
+#[tokio::test]
+async fn vec_fields_default_to_empty() {
+    let Ok(search) = extract::<Search>("/search", Some("theme=light")).await else {
+        panic!("valid request rejected");
+    };
+    assert!(search.tag.is_empty());
+    assert!(search.ids.is_empty());
+    assert_eq!(search.session, None);
+}
+
+#[tokio::test]
+async fn rejects_missing_cookie_and_bad_list_items() {
+    let Err(response) = extract::<Search>("/search", None).await else {
+        panic!("missing cookie accepted");
+    };
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let Err(response) = extract::<Search>("/search?ids=1,x", Some("theme=dark")).await else {
+        panic!("invalid id accepted");
+    };
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn metadata_describes_each_parameter() {
+    let infos = Search::param_infos();
+    let names: Vec<_> = infos.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["tag", "ids", "limit", "sid", "theme"]);
+
+    let tag = &infos[0];
+    assert_eq!(tag.description.as_deref(), Some("Tags to match."));
+    assert!(!tag.required);
+    assert_eq!(
+        tag.schema,
+        Some(json!({ "type": "array", "items": { "type": "string" } }))
+    );
+    assert_eq!(
+        (tag.style.as_deref(), tag.explode),
+        (Some("form"), Some(true))
+    );
+
+    let ids = &infos[1];
+    assert_eq!(ids.explode, Some(false));
+    assert_eq!(ids.schema.as_ref().unwrap()["items"]["format"], "int64");
+
+    let limit = &infos[2];
+    assert!(!limit.required);
+    assert_eq!(limit.example, Some(json!(50)));
+    assert_eq!(
+        limit.schema,
+        Some(json!({ "type": "integer", "format": "int32", "default": 20 }))
+    );
+    assert_eq!((limit.style.as_deref(), limit.explode), (None, None));
+
+    assert_eq!(infos[3].location, ParamLocation::Cookie);
+    assert!(!infos[3].required);
+    assert!(infos[4].required);
+}
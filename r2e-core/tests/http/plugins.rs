@@ -3,6 +3,7 @@
 
 use r2e_core::builder::AppBuilder;
 use r2e_core::http::{Body, Request, StatusCode};
+use r2e_core::plugin::{PluginInstallContext, PreStatePlugin};
 use r2e_core::plugins::{Cors, DevReload, ErrorHandling, Health, NormalizePath};
 
 use crate::support::{raw, raw_get_with, send_get};
@@ -56,6 +57,111 @@ async fn dev_reload_status() {
     assert_eq!(body, "dev");
 }
 
+/// Contributes a dev-status section, as a datasource plugin reports its
+/// applied migrations.
+struct ReportsStatus;
+
+impl PreStatePlugin for ReportsStatus {
+    type Provided = ();
+    type Deps = ();
+    type Config = ();
+
+    fn install(&mut self, ctx: &mut PluginInstallContext<'_>) {
+        ctx.add_dev_status("migrations", || serde_json::json!({ "applied": [1, 2] }));
+    }
+}
+
+#[r2e_core::test]
+async fn dev_reload_status_json_includes_plugin_sections() {
+    let router = AppBuilder::new()
+        .plugin(ReportsStatus)
+        .build_state()
+        .await
+        .with(DevReload)
+        .build();
+    let resp = raw_get_with(
+        router,
+        "/__r2e_dev/status",
+        &[("accept", "application/json")],
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = r2e_core::http::body::to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["status"], "dev");
+    assert_eq!(json["sections"]["migrations"]["applied"][1], 2);
+}
+
+// ── Plugin meta observers ───────────────────────────────────────────────
+
+use r2e_core::meta::{RouteInfo, PRIORITY_EXTENSION};
+use r2e_core::prelude::*;
+use std::sync::{Arc, Mutex};
+
+#[controller(path = "/jobs")]
+pub struct JobController {}
+
+#[routes]
+#[priority(background)]
+impl JobController {
+    #[get("/")]
+    async fn list(&self) -> &'static str {
+        "jobs"
+    }
+
+    #[post("/")]
+    #[priority(critical)]
+    async fn submit(&self) -> &'static str {
+        "queued"
+    }
+}
+
+/// Reads the route table the way a load shedder looks up `#[priority]`.
+struct ObservesRoutes(Arc<Mutex<Vec<(String, String)>>>);
+
+impl PreStatePlugin for ObservesRoutes {
+    type Provided = ();
+    type Deps = ();
+    type Config = ();
+
+    fn install(&mut self, ctx: &mut PluginInstallContext<'_>) {
+        let seen = self.0.clone();
+        ctx.observe_meta::<RouteInfo, _>(move |routes| {
+            seen.lock().unwrap().extend(routes.iter().map(|route| {
+                (
+                    format!("{} {}", route.method, route.path),
+                    route.extensions[PRIORITY_EXTENSION]
+                        .as_str()
+                        .unwrap()
+                        .to_string(),
+                )
+            }));
+        });
+    }
+}
+
+#[r2e_core::test]
+async fn plugins_observe_route_metadata_at_build() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let _router = AppBuilder::new()
+        .plugin(ObservesRoutes(seen.clone()))
+        .build_state()
+        .await
+        .register_controller::<JobController>()
+        .build();
+    let mut seen = seen.lock().unwrap().clone();
+    seen.sort();
+    assert_eq!(
+        seen,
+        vec![
+            ("GET /jobs/".to_string(), "background".to_string()),
+            ("POST /jobs/".to_string(), "critical".to_string()),
+        ]
+    );
+}
+
 #[r2e_core::test]
 async fn dev_reload_ping() {
     let router = build_app().with(DevReload).build();
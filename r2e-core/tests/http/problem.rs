@@ -0,0 +1,161 @@
+use http_body_util::BodyExt;
+use r2e_core::builder::AppBuilder;
+use r2e_core::http::response::Response;
+use r2e_core::http::routing::get;
+use r2e_core::http::{Json, Router, StatusCode};
+use r2e_core::prelude::*;
+use r2e_core::validation::{FieldError, ValidationErrorResponse};
+use serde_json::{json, Value};
+
+use crate::support::raw_get_with;
+
+#[derive(Debug, ApiError)]
+pub enum OrderError {
+    #[error(
+        status = NOT_FOUND,
+        message = "Order {0} not found",
+        type = "https://errors.example.com/order-not-found"
+    )]
+    NotFound(u64),
+
+    #[error(status = CONFLICT)]
+    AlreadyShipped,
+}
+
+fn routes() -> Router<()> {
+    Router::new()
+        .route(
+            "/http",
+            get(|| async { Err::<(), _>(HttpError::not_found("User 42 not found")) }),
+        )
+        .route(
+            "/typed",
+            get(|| async { Err::<(), _>(OrderError::NotFound(7)) }),
+        )
+        .route(
+            "/untyped",
+            get(|| async { Err::<(), _>(OrderError::AlreadyShipped) }),
+        )
+        .route(
+            "/validation",
+            get(|| async {
+                Err::<(), _>(HttpError::Validation(ValidationErrorResponse {
+                    errors: vec![FieldError {
+                        field: "email".into(),
+                        message: "invalid email".into(),
+                        code: "email".into(),
+                    }],
+                }))
+            }),
+        )
+        .route(
+            "/custom",
+            get(|| async {
+                Err::<(), _>(HttpError::Custom {
+                    status: StatusCode::CONFLICT,
+                    body: json!({ "error": "duplicate", "field": "email" }),
+                })
+            }),
+        )
+        .route("/ok", get(|| async { Json(json!({ "ok": true })) }))
+}
+
+async fn fetch(router: Router, path: &str) -> (Response, Value) {
+    let resp = raw_get_with(router, path, &[("x-request-id", "req-1")]).await;
+    let (parts, body) = resp.into_parts();
+    let bytes = body.collect().await.unwrap().to_bytes();
+    let json = serde_json::from_slice(&bytes).unwrap();
+    (
+        Response::from_parts(parts, ()).map(|_| Default::default()),
+        json,
+    )
+}
+
+fn problem_app() -> Router {
+    AppBuilder::new()
+        .with_state(())
+        .register_routes(routes())
+        .with(ProblemDetails)
+        .with(RequestIdPlugin)
+        .build()
+}
+
+fn content_type(resp: &Response) -> &str {
+    resp.headers()["content-type"].to_str().unwrap()
+}
+
+#[r2e_core::test]
+async fn http_error_renders_problem_json() {
+    let (resp, body) = fetch(problem_app(), "/http").await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    assert_eq!(content_type(&resp), "application/problem+json");
+    assert_eq!(
+        body,
+        json!({
+            "type": "about:blank",
+            "title": "Not Found",
+            "status": 404,
+            "detail": "User 42 not found",
+            "instance": "req-1",
+        })
+    );
+}
+
+#[r2e_core::test]
+async fn api_error_type_is_rendered() {
+    let (_, body) = fetch(problem_app(), "/typed").await;
+    assert_eq!(body["type"], "https://errors.example.com/order-not-found");
+    assert_eq!(body["detail"], "Order 7 not found");
+
+    let (_, body) = fetch(problem_app(), "/untyped").await;
+    assert_eq!(body["type"], "about:blank");
+    assert_eq!(body["title"], "Conflict");
+    assert_eq!(body["detail"], "Already shipped");
+}
+
+#[r2e_core::test]
+async fn validation_errors_become_an_extension_member() {
+    let (resp, body) = fetch(problem_app(), "/validation").await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(body["detail"], "Validation failed");
+    assert_eq!(body["errors"][0]["field"], "email");
+    assert!(body.get("details").is_none());
+}
+
+#[r2e_core::test]
+async fn custom_body_members_are_kept() {
+    let (_, body) = fetch(problem_app(), "/custom").await;
+    assert_eq!(body["status"], 409);
+    assert_eq!(body["detail"], "duplicate");
+    assert_eq!(body["field"], "email");
+}
+
+#[r2e_core::test]
+async fn successful_responses_are_untouched() {
+    let (resp, body) = fetch(problem_app(), "/ok").await;
+    assert_eq!(content_type(&resp), "application/json");
+    assert_eq!(body, json!({ "ok": true }));
+}
+
+#[r2e_core::test]
+async fn legacy_body_without_the_plugin() {
+    let router = AppBuilder::new()
+        .with_state(())
+        .register_routes(routes())
+        .build();
+    let (resp, body) = fetch(router, "/http").await;
+    assert_eq!(content_type(&resp), "application/json");
+    assert_eq!(body, json!({ "error": "User 42 not found" }));
+}
+
+#[test]
+fn problem_type_is_documented() {
+    use r2e_core::ErrorResponses;
+
+    let responses = OrderError::error_responses();
+    assert_eq!(
+        responses[0].problem_type.as_deref(),
+        Some("https://errors.example.com/order-not-found")
+    );
+    assert_eq!(responses[1].problem_type, None);
+}
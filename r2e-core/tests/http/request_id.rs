@@ -53,3 +53,49 @@ async fn request_id_propagated() {
         .unwrap();
     assert_eq!(req_id, "test-123");
 }
+
+// ── Ambient RequestContext ─────────────────────────────────────────────────
+
+use r2e_core::RequestContext;
+
+#[r2e_core::test]
+async fn request_id_scoped_as_request_context() {
+    use r2e_core::http::routing::get;
+
+    let router = build_app()
+        .register_routes(r2e_core::http::Router::new().route(
+            "/ctx",
+            get(|| async {
+                RequestContext::record_subject("alice");
+                let ctx = RequestContext::current().expect("context scoped by the middleware");
+                format!(
+                    "{}/{}",
+                    ctx.request_id().unwrap_or("-"),
+                    ctx.subject().unwrap_or("-")
+                )
+            }),
+        ))
+        .with(RequestIdPlugin)
+        .build();
+    let resp = raw_get_with(router, "/ctx", &[("x-request-id", "test-123")]).await;
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    assert_eq!(&body[..], b"test-123/alice");
+}
+
+#[r2e_core::test]
+async fn request_context_absent_outside_scope() {
+    assert!(RequestContext::current().is_none());
+    // No scope: recording is a no-op rather than a panic.
+    RequestContext::record_subject("alice");
+
+    let ctx = RequestContext::new().with_request_id("r-1");
+    let seen = ctx
+        .scope(async {
+            RequestContext::record_subject("alice");
+            RequestContext::record_subject("bob");
+            RequestContext::current().unwrap()
+        })
+        .await;
+    assert_eq!(seen.request_id(), Some("r-1"));
+    assert_eq!(seen.subject(), Some("alice"), "first recorded subject wins");
+}
@@ -1,10 +1,11 @@
 //! Runtime & serving surface: the `rt` task facade, SO_REUSEPORT sharded
-//! serving, socket options, tracing subscriber configuration, and the
-//! dev-reload partial rebuild.
+//! serving, native TLS, socket options, tracing subscriber configuration,
+//! and the dev-reload partial rebuild.
 
 #[cfg(feature = "dev-reload")]
 mod dev_reload;
 mod rt;
 mod sharded;
 mod tcp_nodelay;
+mod tls;
 mod tracing_config;
@@ -0,0 +1,193 @@
+//! Tests for native TLS serving (`server.tls`).
+//!
+//! Config parsing errors are checked with and without the `tls` feature; the
+//! HTTPS round-trips (single listener and sharded) need the feature.
+
+use r2e_core::builder::AppBuilder;
+use r2e_core::config::R2eConfig;
+use r2e_core::tls::parse_tls;
+
+#[test]
+fn parse_tls_absent_is_none() {
+    let config = R2eConfig::from_yaml_str("server:\n  port: 3000\n").unwrap();
+    assert!(parse_tls(Some(&config)).unwrap().is_none());
+    assert!(parse_tls(None).unwrap().is_none());
+}
+
+#[cfg(not(feature = "tls"))]
+#[test]
+fn parse_tls_without_feature_is_error() {
+    let config =
+        R2eConfig::from_yaml_str("server:\n  tls:\n    cert: a.pem\n    key: a.key\n").unwrap();
+    let err = parse_tls(Some(&config)).unwrap_err();
+    assert!(err.contains("`tls` feature"), "got: {err}");
+}
+
+#[test]
+fn prepared_app_tls_error_fails_run() {
+    let config = R2eConfig::from_yaml_str("server:\n  tls:\n    cert: missing.pem\n").unwrap();
+    let app = AppBuilder::new()
+        .override_config(config)
+        .load_config::<()>()
+        .with_state(())
+        .prepare("127.0.0.1:0");
+    assert!(app.tls().is_err());
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    assert!(rt.block_on(app.run()).is_err());
+}
+
+#[cfg(feature = "tls")]
+mod https {
+    use super::*;
+    use r2e_core::http::routing::get;
+    use r2e_core::http::tls::rustls;
+    use r2e_core::http::tls::tokio_rustls::TlsConnector;
+    use rcgen::{CertificateParams, KeyPair};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tempfile::TempDir;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    /// Self-signed `localhost` certificate written to a temp dir; returns
+    /// the dir and the DER the client must trust.
+    fn self_signed() -> (TempDir, Vec<u8>) {
+        let dir = TempDir::new().unwrap();
+        let key = KeyPair::generate().unwrap();
+        let cert = CertificateParams::new(vec!["localhost".to_string()])
+            .unwrap()
+            .self_signed(&key)
+            .unwrap();
+        std::fs::write(dir.path().join("server.pem"), cert.pem()).unwrap();
+        std::fs::write(dir.path().join("server.key"), key.serialize_pem()).unwrap();
+        (dir, cert.der().to_vec())
+    }
+
+    fn tls_yaml(dir: &TempDir, extra: &str) -> String {
+        format!(
+            "server:\n{extra}  tls:\n    cert: {}\n    key: {}\n",
+            dir.path().join("server.pem").display(),
+            dir.path().join("server.key").display(),
+        )
+    }
+
+    async fn https_ping(addr: &str, trusted: &[u8]) -> Result<String, String> {
+        let mut roots = rustls::RootCertStore::empty();
+        roots
+            .add(trusted.to_vec().into())
+            .map_err(|e| e.to_string())?;
+        let config = rustls::ClientConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+        let tcp = TcpStream::connect(addr).await.map_err(|e| e.to_string())?;
+        let mut stream = TlsConnector::from(Arc::new(config))
+            .connect("localhost".try_into().unwrap(), tcp)
+            .await
+            .map_err(|e| e.to_string())?;
+        stream
+            .write_all(b"GET /ping HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .map_err(|e| e.to_string())?;
+        let mut buf = Vec::new();
+        stream
+            .read_to_end(&mut buf)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(String::from_utf8_lossy(&buf).into_owned())
+    }
+
+    async fn serve_and_ping(yaml: String, addr: String, trusted: Vec<u8>) {
+        let config = R2eConfig::from_yaml_str(&yaml).unwrap();
+        let app = AppBuilder::new()
+            .override_config(config)
+            .load_config::<()>()
+            .with_state(())
+            .register_routes(r2e_core::http::Router::new().route("/ping", get(|| async { "pong" })))
+            .prepare(&addr);
+        let tls = app.tls().unwrap().unwrap();
+        assert_eq!(tls.reload_interval, Some(Duration::from_secs(30)));
+        let stop = app.stop_handle();
+        let server = tokio::spawn(async move { app.run().await.map_err(|e| e.to_string()) });
+
+        let mut response = Err(String::new());
+        for _ in 0..100 {
+            response = https_ping(&addr, &trusted).await;
+            if response.is_ok() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        let response = response.expect("HTTPS server did not become ready");
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(response.ends_with("pong"), "{response}");
+
+        stop.stop();
+        tokio::time::timeout(Duration::from_secs(10), server)
+            .await
+            .expect("server did not stop")
+            .unwrap()
+            .unwrap();
+    }
+
+    fn free_port() -> u16 {
+        let l = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        l.local_addr().unwrap().port()
+    }
+
+    #[test]
+    fn parse_tls_requires_cert_and_key() {
+        let config = R2eConfig::from_yaml_str("server:\n  tls:\n    cert: a.pem\n").unwrap();
+        let err = parse_tls(Some(&config)).unwrap_err();
+        assert!(err.contains("server.tls.key"), "got: {err}");
+    }
+
+    #[test]
+    fn parse_tls_rejects_unknown_client_auth() {
+        let (dir, _) = self_signed();
+        let yaml = format!(
+            "{}    client_ca: {}\n    client_auth: sometimes\n",
+            tls_yaml(&dir, ""),
+            dir.path().join("server.pem").display()
+        );
+        let err = parse_tls(Some(&R2eConfig::from_yaml_str(&yaml).unwrap())).unwrap_err();
+        assert!(err.contains("\"required\" or \"optional\""), "got: {err}");
+    }
+
+    #[test]
+    fn parse_tls_reload_interval_zero_disables_reload() {
+        let (dir, _) = self_signed();
+        let yaml = format!(
+            "{}    reload_interval: 0\n    handshake_timeout: 2s\n",
+            tls_yaml(&dir, "")
+        );
+        let tls = parse_tls(Some(&R2eConfig::from_yaml_str(&yaml).unwrap()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(tls.reload_interval, None);
+        assert_eq!(tls.handshake_timeout, Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn serves_https_on_the_single_listener() {
+        let (dir, der) = self_signed();
+        let addr = format!("127.0.0.1:{}", free_port());
+        serve_and_ping(tls_yaml(&dir, ""), addr, der).await;
+    }
+
+    #[cfg(all(
+        unix,
+        not(any(target_os = "solaris", target_os = "illumos", target_os = "cygwin"))
+    ))]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn serves_https_on_sharded_workers() {
+        let (dir, der) = self_signed();
+        let port = free_port();
+        let yaml = tls_yaml(&dir, &format!("  workers: 2\n  port: {port}\n"));
+        serve_and_ping(yaml, format!("127.0.0.1:{port}"), der).await;
+    }
+}
@@ -0,0 +1,200 @@
+//! Whitelisted sort and filter parameters for Diesel queries.
+
+use diesel::backend::Backend;
+use diesel::dsl::sql;
+use diesel::expression::SqlLiteral;
+use diesel::expression::{
+    is_aggregate, AppearsOnTable, Expression, SelectableExpression, ValidGrouping,
+};
+use diesel::query_builder::{AstPass, QueryFragment, QueryId};
+use diesel::serialize::ToSql;
+use diesel::sql_types::{BigInt, Bool, Double, HasSqlType, Text, Untyped};
+use diesel::QueryResult;
+use r2e_core::pagination::{Filter, FilterOp, Sort};
+use r2e_core::serde_json::Value;
+
+use crate::keyset::Bound;
+
+/// Boolean expression for the conditions of a [`Filter`], built by
+/// [`field_filter`]. Usable in `.filter()` on any table.
+#[derive(Debug, Clone)]
+pub struct FieldFilter {
+    conditions: Vec<Condition>,
+}
+
+#[derive(Debug, Clone)]
+struct Condition {
+    column: &'static str,
+    op: FilterOp,
+    cast: Option<&'static str>,
+    values: Vec<Bound>,
+}
+
+/// Build the filter expression for the requested conditions, `1 = 1` when
+/// the request has none:
+///
+/// `(status IN (?, ?) AND created_at < CAST(? AS timestamptz))`
+///
+/// ```ignore
+/// let mut query = users::table
+///     .into_boxed()
+///     .filter(field_filter(&filter));
+/// if let Some(order) = sort_order(&sort) {
+///     query = query.order(order);
+/// }
+/// ```
+pub fn field_filter<T>(filter: &Filter<T>) -> FieldFilter {
+    let conditions = filter
+        .conditions()
+        .iter()
+        .map(|condition| Condition {
+            column: condition.column,
+            op: condition.op,
+            cast: condition.cast,
+            values: match &condition.value {
+                Value::Array(values) => values.iter().map(Bound::from).collect(),
+                value => vec![Bound::from(value)],
+            },
+        })
+        .collect();
+    FieldFilter { conditions }
+}
+
+/// The `ORDER BY` expression for the requested sort, `None` when the
+/// request has no sort.
+pub fn sort_order<T>(sort: &Sort<T>) -> Option<SqlLiteral<Untyped>> {
+    sort.order_by().map(|order_by| sql::<Untyped>(&order_by))
+}
+
+impl Expression for FieldFilter {
+    type SqlType = Bool;
+}
+
+impl<QS> AppearsOnTable<QS> for FieldFilter {}
+
+impl<QS> SelectableExpression<QS> for FieldFilter {}
+
+impl<GB> ValidGrouping<GB> for FieldFilter {
+    type IsAggregate = is_aggregate::Never;
+}
+
+impl QueryId for FieldFilter {
+    type QueryId = ();
+    const HAS_STATIC_QUERY_ID: bool = false;
+}
+
+impl<DB> QueryFragment<DB> for FieldFilter
+where
+    DB: Backend + HasSqlType<BigInt> + HasSqlType<Double> + HasSqlType<Bool> + HasSqlType<Text>,
+    i64: ToSql<BigInt, DB>,
+    f64: ToSql<Double, DB>,
+    bool: ToSql<Bool, DB>,
+    String: ToSql<Text, DB>,
+{
+    fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, DB>) -> QueryResult<()> {
+        if self.conditions.is_empty() {
+            out.push_sql("1 = 1");
+            return Ok(());
+        }
+        out.push_sql("(");
+        for (index, condition) in self.conditions.iter().enumerate() {
+            if index > 0 {
+                out.push_sql(" AND ");
+            }
+            out.push_sql(condition.column);
+            out.push_sql(" ");
+            out.push_sql(condition.op.as_sql());
+            out.push_sql(" ");
+            let list = condition.op == FilterOp::In;
+            if list {
+                out.push_sql("(");
+            }
+            for (index, value) in condition.values.iter().enumerate() {
+                if index > 0 {
+                    out.push_sql(", ");
+                }
+                value.walk_ast(out.reborrow(), condition.cast)?;
+            }
+            if list {
+                out.push_sql(")");
+            }
+        }
+        out.push_sql(")");
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use diesel::sqlite::Sqlite;
+    use diesel::{sql_query, Connection, QueryDsl, RunQueryDsl, SqliteConnection};
+    use r2e_core::pagination::{FieldKind, FilterField, QueryFields, SortField};
+
+    diesel::table! {
+        items (id) {
+            id -> BigInt,
+            grp -> Text,
+        }
+    }
+
+    struct Item;
+
+    impl QueryFields for Item {
+        const SORT: &'static [SortField] = &[SortField {
+            name: "id",
+            column: "id",
+        }];
+        const FILTER: &'static [FilterField] = &[
+            FilterField {
+                name: "grp",
+                column: "grp",
+                kind: FieldKind::String,
+                ops: &[FilterOp::Eq, FilterOp::In],
+                cast: None,
+            },
+            FilterField {
+                name: "id",
+                column: "id",
+                kind: FieldKind::Integer,
+                ops: &[FilterOp::Gt],
+                cast: Some("INTEGER"),
+            },
+        ];
+    }
+
+    fn ids(connection: &mut SqliteConnection, query: &[(&str, &str)], sort: &str) -> Vec<i64> {
+        let filter = Filter::<Item>::parse(query.iter().copied(), "").unwrap();
+        let sort = Sort::<Item>::parse([sort]).unwrap();
+        let mut items = items::table
+            .select(items::id)
+            .into_boxed::<Sqlite>()
+            .filter(field_filter(&filter));
+        if let Some(order) = sort_order(&sort) {
+            items = items.order(order);
+        }
+        items.load(connection).unwrap()
+    }
+
+    #[test]
+    fn filters_and_sorts_on_whitelisted_columns() {
+        let mut connection = SqliteConnection::establish(":memory:").unwrap();
+        sql_query("CREATE TABLE items(id INTEGER PRIMARY KEY, grp TEXT NOT NULL)")
+            .execute(&mut connection)
+            .unwrap();
+        sql_query("INSERT INTO items(id, grp) VALUES (1, 'b'), (2, 'a'), (3, 'b'), (4, 'c')")
+            .execute(&mut connection)
+            .unwrap();
+
+        assert_eq!(ids(&mut connection, &[], "-id"), [4, 3, 2, 1]);
+        assert_eq!(
+            ids(
+                &mut connection,
+                &[("filter[grp][in]", "a,b"), ("filter[id][gt]", "1")],
+                "id"
+            ),
+            [2, 3]
+        );
+        assert_eq!(ids(&mut connection, &[("filter[grp]", "b")], "-id"), [3, 1]);
+    }
+}
@@ -0,0 +1,248 @@
+//! Keyset pagination filters for Diesel queries.
+
+use diesel::backend::Backend;
+use diesel::expression::{
+    is_aggregate, AppearsOnTable, Expression, SelectableExpression, ValidGrouping,
+};
+use diesel::query_builder::{AstPass, QueryFragment, QueryId};
+use diesel::serialize::ToSql;
+use diesel::sql_types::{BigInt, Bool, Double, HasSqlType, Text};
+use diesel::QueryResult;
+use r2e_core::pagination::cursor::{CursorPosition, KeysetSort};
+use r2e_core::serde_json::Value;
+use r2e_core::HttpError;
+
+/// Boolean expression selecting the rows past a cursor, built by
+/// [`keyset_filter`]. Usable in `.filter()` on any table.
+#[derive(Debug, Clone)]
+pub struct KeysetFilter {
+    branches: Vec<Vec<Comparison>>,
+}
+
+#[derive(Debug, Clone)]
+struct Comparison {
+    column: String,
+    operator: &'static str,
+    cast: Option<String>,
+    value: Bound,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Bound {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+/// Build the keyset filter selecting the rows after (or before) `position`
+/// in `sort` order: `((a > ?) OR (a = ? AND b > ?))`.
+///
+/// The cursor values are bound, cast where the column asks for it. Fails
+/// with `400 Bad Request` when the cursor does not match the sort.
+///
+/// ```ignore
+/// let mut query = users::table.into_boxed();
+/// if let Some(position) = &position {
+///     query = query.filter(keyset_filter(&sort, position)?);
+/// }
+/// let rows = query
+///     .order(sql::<Untyped>(&sort.order_by(position.as_ref())))
+///     .limit(i64::from(pageable.fetch_limit()))
+///     .load::<User>(connection)?;
+/// ```
+pub fn keyset_filter(
+    sort: &KeysetSort,
+    position: &CursorPosition,
+) -> Result<KeysetFilter, HttpError> {
+    let branches = sort
+        .conditions(position)?
+        .into_iter()
+        .map(|terms| {
+            terms
+                .into_iter()
+                .map(|term| Comparison {
+                    column: term.column.name.clone(),
+                    operator: term.operator,
+                    cast: term.column.cast.clone(),
+                    value: Bound::from(term.value),
+                })
+                .collect()
+        })
+        .collect();
+    Ok(KeysetFilter { branches })
+}
+
+impl Expression for KeysetFilter {
+    type SqlType = Bool;
+}
+
+impl<QS> AppearsOnTable<QS> for KeysetFilter {}
+
+impl<QS> SelectableExpression<QS> for KeysetFilter {}
+
+impl<GB> ValidGrouping<GB> for KeysetFilter {
+    type IsAggregate = is_aggregate::Never;
+}
+
+impl QueryId for KeysetFilter {
+    type QueryId = ();
+    const HAS_STATIC_QUERY_ID: bool = false;
+}
+
+impl<DB> QueryFragment<DB> for KeysetFilter
+where
+    DB: Backend + HasSqlType<BigInt> + HasSqlType<Double> + HasSqlType<Bool> + HasSqlType<Text>,
+    i64: ToSql<BigInt, DB>,
+    f64: ToSql<Double, DB>,
+    bool: ToSql<Bool, DB>,
+    String: ToSql<Text, DB>,
+{
+    fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, DB>) -> QueryResult<()> {
+        out.push_sql("(");
+        for (index, terms) in self.branches.iter().enumerate() {
+            if index > 0 {
+                out.push_sql(" OR ");
+            }
+            out.push_sql("(");
+            for (index, term) in terms.iter().enumerate() {
+                if index > 0 {
+                    out.push_sql(" AND ");
+                }
+                out.push_sql(&term.column);
+                out.push_sql(" ");
+                out.push_sql(term.operator);
+                out.push_sql(" ");
+                term.value.walk_ast(out.reborrow(), term.cast.as_deref())?;
+            }
+            out.push_sql(")");
+        }
+        out.push_sql(")");
+        Ok(())
+    }
+}
+
+impl From<&Value> for Bound {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Bool(value) => Bound::Bool(*value),
+            Value::Number(number) => match number.as_i64() {
+                Some(value) => Bound::Int(value),
+                None => Bound::Float(number.as_f64().unwrap_or_default()),
+            },
+            value => Bound::Text(value.as_str().unwrap_or_default().to_owned()),
+        }
+    }
+}
+
+impl Bound {
+    /// Push the bind parameter, wrapped in `CAST(? AS cast)` when given.
+    pub(crate) fn walk_ast<'b, DB>(
+        &'b self,
+        mut out: AstPass<'_, 'b, DB>,
+        cast: Option<&'b str>,
+    ) -> QueryResult<()>
+    where
+        DB: Backend + HasSqlType<BigInt> + HasSqlType<Double> + HasSqlType<Bool> + HasSqlType<Text>,
+        i64: ToSql<BigInt, DB>,
+        f64: ToSql<Double, DB>,
+        bool: ToSql<Bool, DB>,
+        String: ToSql<Text, DB>,
+    {
+        if cast.is_some() {
+            out.push_sql("CAST(");
+        }
+        match self {
+            Bound::Bool(value) => out.push_bind_param::<Bool, _>(value)?,
+            Bound::Int(value) => out.push_bind_param::<BigInt, _>(value)?,
+            Bound::Float(value) => out.push_bind_param::<Double, _>(value)?,
+            Bound::Text(value) => out.push_bind_param::<Text, _>(value)?,
+        }
+        if let Some(cast) = cast {
+            out.push_sql(" AS ");
+            out.push_sql(cast);
+            out.push_sql(")");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use diesel::dsl::sql;
+    use diesel::sql_types::Untyped;
+    use diesel::sqlite::Sqlite;
+    use diesel::{sql_query, Connection, QueryDsl, RunQueryDsl, SqliteConnection};
+    use r2e_core::pagination::{CursorCodec, CursorPage, CursorPageable};
+
+    diesel::table! {
+        items (id) {
+            id -> BigInt,
+            grp -> Text,
+        }
+    }
+
+    fn connection() -> SqliteConnection {
+        let mut connection = SqliteConnection::establish(":memory:").unwrap();
+        sql_query("CREATE TABLE items(id INTEGER PRIMARY KEY, grp TEXT NOT NULL)")
+            .execute(&mut connection)
+            .unwrap();
+        sql_query(
+            "INSERT INTO items(id, grp) VALUES (1, 'b'), (2, 'a'), (3, 'b'), (4, 'a'), (5, 'c')",
+        )
+        .execute(&mut connection)
+        .unwrap();
+        connection
+    }
+
+    fn page(
+        connection: &mut SqliteConnection,
+        sort: &KeysetSort,
+        codec: &CursorCodec,
+        pageable: &CursorPageable,
+    ) -> CursorPage<(String, i64)> {
+        let position = pageable.position(codec).unwrap();
+        let mut query = items::table
+            .select((items::grp, items::id))
+            .into_boxed::<Sqlite>();
+        if let Some(position) = &position {
+            query = query.filter(keyset_filter(sort, position).unwrap());
+        }
+        let rows = query
+            .order(sql::<Untyped>(&sort.order_by(position.as_ref())))
+            .limit(i64::from(pageable.fetch_limit()))
+            .load::<(String, i64)>(connection)
+            .unwrap();
+        CursorPage::new(rows, pageable, position.as_ref(), codec, |row| row.clone())
+    }
+
+    fn from_cursor(cursor: &Option<String>) -> CursorPageable {
+        let mut pageable = CursorPageable::new(2);
+        pageable.cursor = cursor.clone();
+        pageable
+    }
+
+    #[test]
+    fn walks_a_multi_column_keyset_both_ways() {
+        let mut connection = connection();
+        let codec = CursorCodec::new("secret");
+        let sort = KeysetSort::new().asc("grp").desc("id");
+        let ids = |page: &CursorPage<(String, i64)>| {
+            page.content.iter().map(|(_, id)| *id).collect::<Vec<_>>()
+        };
+
+        let first = page(&mut connection, &sort, &codec, &CursorPageable::new(2));
+        assert_eq!(ids(&first), [4, 2]);
+        let second = page(&mut connection, &sort, &codec, &from_cursor(&first.next));
+        assert_eq!(ids(&second), [3, 1]);
+        let third = page(&mut connection, &sort, &codec, &from_cursor(&second.next));
+        assert_eq!(ids(&third), [5]);
+
+        let back = page(&mut connection, &sort, &codec, &from_cursor(&third.prev));
+        assert_eq!(ids(&back), [3, 1]);
+        let start = page(&mut connection, &sort, &codec, &from_cursor(&back.prev));
+        assert_eq!(ids(&start), [4, 2]);
+        assert!(start.prev.is_none());
+    }
+}
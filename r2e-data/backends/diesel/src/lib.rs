@@ -3,6 +3,13 @@
 //! The crate supports SQLite, PostgreSQL, and MySQL through the matching Cargo
 //! features. Register a Diesel r2d2 pool as a bean and use [`Tx`] (or the more
 //! explicit [`DieselTx`]) as a `#[managed]` route parameter.
+//!
+//! Outside routes, `#[transactional]` binds a transaction begun from a pool
+//! field to the current task; [`current_tx`] reaches it from the method and
+//! from any nested call, and [`DieselTx::run`] executes on it.
+//!
+//! With the `migrations` feature, `DieselMigrations` runs the app's
+//! `embed_migrations!()` at boot.
 
 use diesel::{
     connection::TransactionManager,
@@ -14,12 +21,24 @@ use r2e_core::{
 };
 use std::ops::{Deref, DerefMut};
 
+mod fields;
+mod keyset;
+#[cfg(feature = "migrations")]
+mod migrations;
+mod transactional;
+
+pub use fields::{field_filter, sort_order, FieldFilter};
+pub use keyset::{keyset_filter, KeysetFilter};
+#[cfg(feature = "migrations")]
+pub use migrations::{DieselMigrations, MigrationsConfig};
+pub use transactional::{current_tx, Diesel};
+
 /// Request-scoped Diesel transaction backed by an r2d2 pooled connection.
 pub struct DieselTx<Conn>
 where
     Conn: Connection + R2D2Connection + 'static,
 {
-    connection: Option<PooledConnection<ConnectionManager<Conn>>>,
+    pub(crate) connection: Option<PooledConnection<ConnectionManager<Conn>>>,
 }
 
 /// Short name for applications depending directly on this backend crate.
@@ -151,7 +170,7 @@ where
 }
 
 pub mod prelude {
-    pub use crate::DieselTx;
+    pub use crate::{current_tx, DieselTx};
 }
 
 #[cfg(all(test, feature = "sqlite"))]
@@ -228,4 +247,44 @@ mod tests {
             .unwrap();
         assert_eq!(count.count, 1);
     }
+
+    #[tokio::test]
+    async fn transactional_commits_ok_and_rolls_back_err() {
+        use r2e_core::transaction::{transactional, TxError, TxOptions};
+
+        async fn insert(name: &'static str) -> Result<(), HttpError> {
+            current_tx::<SqliteConnection>()
+                .await?
+                .run(move |connection| {
+                    sql_query(format!("INSERT INTO items(name) VALUES ('{name}')"))
+                        .execute(connection)
+                })
+                .await?;
+            Ok(())
+        }
+
+        let pool = pool_with_table();
+        let committed: Result<(), HttpError> = transactional(&pool, TxOptions::new(), async {
+            insert("outer").await?;
+            transactional(&pool, TxOptions::new(), insert("joined")).await
+        })
+        .await;
+        assert!(committed.is_ok());
+        let rolled_back: Result<(), HttpError> = transactional(&pool, TxOptions::new(), async {
+            insert("doomed").await?;
+            Err(TxError::RollbackOnly.into())
+        })
+        .await;
+        assert!(rolled_back.is_err());
+
+        #[derive(diesel::QueryableByName)]
+        struct Count {
+            #[diesel(sql_type = diesel::sql_types::BigInt)]
+            count: i64,
+        }
+        let count = sql_query("SELECT COUNT(*) AS count FROM items")
+            .get_result::<Count>(&mut pool.get().unwrap())
+            .unwrap();
+        assert_eq!(count.count, 2);
+    }
 }
@@ -0,0 +1,443 @@
+//! Diesel embedded migrations applied at boot (`migrations` feature).
+//!
+//! ```ignore
+//! pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!();
+//!
+//! AppBuilder::new()
+//!     .load_config()
+//!     .provide(pool) // Pool<ConnectionManager<PgConnection>>
+//!     .plugin(DieselMigrations::<PgConnection, _>::new(MIGRATIONS))
+//! ```
+//!
+//! The section and the `r2e db` command mode are the same as for the SQLx
+//! `DataSource`: `datasource.migrations.migrate-at-start` (default `true`)
+//! and `datasource.migrations.validate-only` (default `false`), usually set
+//! per profile.
+
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+use diesel::backend::Backend;
+use diesel::migration::{Migration, MigrationSource};
+use diesel::r2d2::{ConnectionManager, Pool, R2D2Connection};
+use diesel::Connection;
+use diesel_migrations::MigrationHarness;
+use r2e_core::prelude::ConfigProperties;
+use r2e_core::{PluginInstallContext, PreStatePlugin};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Config key naming the migration command to run instead of booting
+/// (`migrate`, `revert` or `status`). Set by `r2e db` via `R2E_MIGRATE_COMMAND`.
+const COMMAND_KEY: &str = "migrate.command";
+/// Config key naming the datasource the command targets.
+const TARGET_KEY: &str = "migrate.datasource";
+/// Diesel pools are not named; the plugin answers for the default datasource.
+const NAME: &str = "default";
+const SECTION: &str = "datasource.migrations";
+
+/// `datasource.migrations` section read by [`DieselMigrations`].
+#[derive(ConfigProperties, Clone, Debug)]
+pub struct MigrationsConfig {
+    /// Run pending migrations at boot.
+    #[config(key = "migrate-at-start", default = true)]
+    pub migrate_at_start: bool,
+    /// Run nothing; fail the boot if a migration is pending or an applied one
+    /// is missing from the app. Wins over `migrate-at-start`.
+    #[config(key = "validate-only", default = false)]
+    pub validate_only: bool,
+}
+
+impl Default for MigrationsConfig {
+    fn default() -> Self {
+        Self {
+            migrate_at_start: true,
+            validate_only: false,
+        }
+    }
+}
+
+/// Pre-state plugin running a Diesel migration source — usually the
+/// `embed_migrations!()` constant — against the provided r2d2 pool of `Conn`,
+/// during `build_state()` and before any `#[post_construct]` hook.
+pub struct DieselMigrations<Conn, S> {
+    source: Option<S>,
+    _conn: PhantomData<fn() -> Conn>,
+}
+
+impl<Conn, S> DieselMigrations<Conn, S> {
+    pub fn new(source: S) -> Self {
+        Self {
+            source: Some(source),
+            _conn: PhantomData,
+        }
+    }
+}
+
+impl<Conn, S> PreStatePlugin for DieselMigrations<Conn, S>
+where
+    Conn: Connection + R2D2Connection + MigrationHarness<Conn::Backend> + Send + 'static,
+    S: MigrationSource<Conn::Backend> + Send + Sync + 'static,
+{
+    type Provided = ();
+    type Deps = (Pool<ConnectionManager<Conn>>,);
+    type Config = ();
+
+    fn install(&mut self, ctx: &mut PluginInstallContext<'_>) {
+        let source = Arc::new(
+            self.source
+                .take()
+                .expect("DieselMigrations installed twice"),
+        );
+        let settings = match ctx.config() {
+            Some(config) => MigrationsConfig::from_config(config, Some(SECTION))
+                .unwrap_or_else(|error| panic!("diesel migrations: {error}")),
+            None => MigrationsConfig::default(),
+        };
+        let command = ctx.config_get::<String>(COMMAND_KEY).map(|value| {
+            Command::parse(&value).unwrap_or_else(|| {
+                panic!("`{COMMAND_KEY}` must be `migrate`, `revert` or `status`, got `{value}`")
+            })
+        });
+        let targeted = ctx
+            .config_get::<String>(TARGET_KEY)
+            .as_deref()
+            .unwrap_or(NAME)
+            == NAME;
+
+        let report: Arc<Mutex<Option<Vec<Row>>>> = Arc::default();
+        let reported = report.clone();
+        let mode = if settings.validate_only {
+            "validate"
+        } else if settings.migrate_at_start {
+            "migrate"
+        } else {
+            "off"
+        };
+        ctx.add_dev_status(SECTION, move || {
+            report_json(mode, &reported.lock().unwrap())
+        });
+
+        if command.is_some() {
+            ctx.on_serve(move |_| {
+                eprintln!(
+                    "no datasource with migrations matches `{TARGET_KEY}` — \
+                     check `r2e db --datasource`"
+                );
+                std::process::exit(2);
+            });
+        }
+
+        ctx.on_resolved(move |beans| {
+            let pool: Pool<ConnectionManager<Conn>> = beans.get();
+            Box::pin(async move {
+                if let Some(command) = command {
+                    if !targeted {
+                        return Ok(());
+                    }
+                    let code = match blocking(pool, move |conn| run_command(command, conn, &source))
+                        .await
+                    {
+                        Ok(()) => 0,
+                        Err(error) => {
+                            eprintln!("datasource `{NAME}`: {error}");
+                            1
+                        }
+                    };
+                    std::process::exit(code);
+                }
+
+                if !settings.validate_only && !settings.migrate_at_start {
+                    return Ok(());
+                }
+                let rows = blocking(pool, move |conn| {
+                    if !settings.validate_only {
+                        conn.run_pending_migrations(SharedSource(source.clone()))?;
+                    }
+                    status(conn, &*source)
+                })
+                .await?;
+                if settings.validate_only {
+                    validate(&rows)?;
+                } else {
+                    tracing::info!(
+                        datasource = NAME,
+                        applied = rows.len(),
+                        "database migrations up to date"
+                    );
+                }
+                *report.lock().unwrap() = Some(rows);
+                Ok(())
+            })
+        });
+    }
+}
+
+/// Diesel's harness takes the source by value; this hands it the shared one.
+struct SharedSource<S>(Arc<S>);
+
+impl<S, DB> MigrationSource<DB> for SharedSource<S>
+where
+    S: MigrationSource<DB>,
+    DB: Backend,
+{
+    fn migrations(&self) -> diesel::migration::Result<Vec<Box<dyn Migration<DB>>>> {
+        self.0.migrations()
+    }
+}
+
+/// Run `operation` on a pooled connection on Tokio's blocking pool.
+async fn blocking<Conn, T, F>(
+    pool: Pool<ConnectionManager<Conn>>,
+    operation: F,
+) -> Result<T, BoxError>
+where
+    Conn: Connection + R2D2Connection + 'static,
+    T: Send + 'static,
+    F: FnOnce(&mut Conn) -> Result<T, BoxError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(move || {
+        let mut connection = pool.get()?;
+        operation(&mut connection)
+    })
+    .await?
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Applied,
+    Pending,
+    Missing,
+}
+
+impl State {
+    fn label(self) -> &'static str {
+        match self {
+            State::Applied => "applied",
+            State::Pending => "pending",
+            State::Missing => "missing",
+        }
+    }
+}
+
+/// A migration version (`20240101000000`), its full name
+/// (`2024-01-01-000000_create_users`, empty when missing) and its state.
+#[derive(Debug, Clone)]
+struct Row {
+    version: String,
+    name: String,
+    state: State,
+}
+
+impl Row {
+    fn name_or_version(&self) -> &str {
+        if self.name.is_empty() {
+            &self.version
+        } else {
+            &self.name
+        }
+    }
+}
+
+fn status<Conn, S>(conn: &mut Conn, source: &S) -> Result<Vec<Row>, BoxError>
+where
+    Conn: MigrationHarness<Conn::Backend> + Connection,
+    S: MigrationSource<Conn::Backend>,
+{
+    let applied: Vec<String> = conn
+        .applied_migrations()?
+        .into_iter()
+        .map(|version| version.to_string())
+        .collect();
+    let known = source.migrations()?;
+    let mut rows: Vec<Row> = known
+        .iter()
+        .map(|migration| {
+            let version = migration.name().version().to_string();
+            let state = if applied.contains(&version) {
+                State::Applied
+            } else {
+                State::Pending
+            };
+            Row {
+                name: migration.name().to_string(),
+                version,
+                state,
+            }
+        })
+        .collect();
+    rows.extend(
+        applied
+            .into_iter()
+            .filter(|version| !rows.iter().any(|row| &row.version == version))
+            .map(|version| Row {
+                version,
+                name: String::new(),
+                state: State::Missing,
+            })
+            .collect::<Vec<_>>(),
+    );
+    rows.sort_by(|a, b| a.version.cmp(&b.version));
+    Ok(rows)
+}
+
+fn validate(rows: &[Row]) -> Result<(), BoxError> {
+    let behind: Vec<String> = rows
+        .iter()
+        .filter(|row| row.state != State::Applied)
+        .map(|row| format!("{} ({})", row.version, row.state.label()))
+        .collect();
+    if behind.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "datasource `{NAME}`: schema is not up to date: {} — run `r2e db migrate`",
+            behind.join(", ")
+        )
+        .into())
+    }
+}
+
+/// The migration commands `r2e db` delegates to the app.
+#[derive(Debug, Clone, Copy)]
+enum Command {
+    Migrate,
+    Revert,
+    Status,
+}
+
+impl Command {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "migrate" => Some(Command::Migrate),
+            "revert" => Some(Command::Revert),
+            "status" => Some(Command::Status),
+            _ => None,
+        }
+    }
+}
+
+fn run_command<Conn, S>(command: Command, conn: &mut Conn, source: &Arc<S>) -> Result<(), BoxError>
+where
+    Conn: MigrationHarness<Conn::Backend> + Connection,
+    S: MigrationSource<Conn::Backend>,
+{
+    match command {
+        Command::Migrate => {
+            let applied = conn
+                .run_pending_migrations(SharedSource(source.clone()))?
+                .len();
+            println!("datasource `{NAME}`: applied {applied} migration(s)");
+        }
+        Command::Revert => {
+            if conn.applied_migrations()?.is_empty() {
+                println!("datasource `{NAME}`: nothing to revert");
+            } else {
+                let version = conn.revert_last_migration(SharedSource(source.clone()))?;
+                println!("datasource `{NAME}`: reverted {version}");
+            }
+        }
+        Command::Status => {
+            let rows = status(conn, &**source)?;
+            println!("datasource `{NAME}`:");
+            if rows.is_empty() {
+                println!("  no migrations");
+            }
+            for row in rows {
+                println!("  {:<9} {}", row.state.label(), row.name_or_version());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn report_json(mode: &str, rows: &Option<Vec<Row>>) -> r2e_core::serde_json::Value {
+    let migrations: Vec<_> = rows
+        .iter()
+        .flatten()
+        .map(|row| {
+            r2e_core::serde_json::json!({
+                "version": row.version,
+                "name": row.name,
+                "state": row.state.label(),
+            })
+        })
+        .collect();
+    r2e_core::serde_json::json!({ "mode": mode, "migrations": migrations })
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use diesel::{sql_query, RunQueryDsl, SqliteConnection};
+    use diesel_migrations::FileBasedMigrations;
+    use r2e_core::config::R2eConfig;
+    use r2e_core::AppBuilder;
+
+    fn fixture(
+        name: &str,
+    ) -> (
+        std::path::PathBuf,
+        Pool<ConnectionManager<SqliteConnection>>,
+    ) {
+        let dir = std::env::temp_dir().join(format!(
+            "r2e-diesel-migrations-{}-{name}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        let migration = dir.join("migrations/2024-01-01-000000_create_users");
+        std::fs::create_dir_all(&migration).unwrap();
+        std::fs::write(
+            migration.join("up.sql"),
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL);",
+        )
+        .unwrap();
+        std::fs::write(migration.join("down.sql"), "DROP TABLE users;").unwrap();
+        let manager = ConnectionManager::<SqliteConnection>::new(
+            dir.join("app.db").to_string_lossy().into_owned(),
+        );
+        let pool = Pool::builder().max_size(1).build(manager).unwrap();
+        (dir, pool)
+    }
+
+    async fn boot(
+        dir: &std::path::Path,
+        pool: Pool<ConnectionManager<SqliteConnection>>,
+        yaml: &str,
+    ) -> Result<(), String> {
+        let source = FileBasedMigrations::from_path(dir.join("migrations")).unwrap();
+        AppBuilder::new()
+            .override_config(R2eConfig::from_yaml_str(yaml).unwrap())
+            .load_config::<()>()
+            .provide(pool)
+            .plugin(DieselMigrations::<SqliteConnection, _>::new(source))
+            .try_build_state()
+            .await
+            .map(|_| ())
+            .map_err(|error| error.to_string())
+    }
+
+    #[tokio::test]
+    async fn runs_pending_migrations_at_boot() {
+        let (dir, pool) = fixture("boot");
+        boot(&dir, pool.clone(), "app: {}\n").await.unwrap();
+
+        sql_query("INSERT INTO users (name) VALUES ('ada')")
+            .execute(&mut pool.get().unwrap())
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn validate_only_fails_the_boot_when_behind() {
+        let (dir, pool) = fixture("validate");
+        let error = boot(
+            &dir,
+            pool,
+            "datasource:\n  migrations:\n    validate-only: true\n",
+        )
+        .await
+        .unwrap_err();
+
+        assert!(error.contains("20240101000000 (pending)"), "{error}");
+    }
+}
@@ -0,0 +1,132 @@
+//! `#[transactional]` support: the r2d2 pool as a task-bound transaction
+//! manager.
+//!
+//! `isolation` and `read_only` become a `SET TRANSACTION` statement on
+//! PostgreSQL (first statement of the transaction) and MySQL (issued just
+//! before it). SQLite transactions are always serializable and the options
+//! are ignored there.
+
+use std::any::TypeId;
+
+use diesel::{
+    connection::{SimpleConnection, TransactionManager as DieselTransactionManager},
+    r2d2::{ConnectionManager, Pool, R2D2Connection},
+    Connection,
+};
+use r2e_core::transaction::{self, TransactionManager, TxError, TxGuard, TxOptions};
+
+use crate::DieselTx;
+
+/// Marker selecting this crate's [`TransactionManager`] impl; inferred by
+/// `#[transactional]`.
+pub struct Diesel;
+
+impl<Conn> TransactionManager<Diesel> for Pool<ConnectionManager<Conn>>
+where
+    Conn: Connection + R2D2Connection + Send + 'static,
+{
+    type Tx = DieselTx<Conn>;
+
+    async fn begin(&self, options: TxOptions) -> Result<Self::Tx, TxError> {
+        let pool = self.clone();
+        let (before, after) = set_transaction::<Conn>(&options);
+        let connection = tokio::task::spawn_blocking(move || {
+            let mut connection = pool.get().map_err(TxError::backend)?;
+            if let Some(statement) = before {
+                connection
+                    .batch_execute(&statement)
+                    .map_err(TxError::backend)?;
+            }
+            <Conn::TransactionManager as DieselTransactionManager<Conn>>::begin_transaction(
+                &mut connection,
+            )
+            .map_err(TxError::backend)?;
+            if let Some(statement) = after {
+                connection
+                    .batch_execute(&statement)
+                    .map_err(TxError::backend)?;
+            }
+            Ok::<_, TxError>(connection)
+        })
+        .await
+        .map_err(|error| TxError::backend(format!("Diesel task failed: {error}")))??;
+        Ok(DieselTx {
+            connection: Some(connection),
+        })
+    }
+
+    async fn commit(&self, tx: Self::Tx) -> Result<(), TxError> {
+        finish(tx, true).await
+    }
+
+    async fn rollback(&self, tx: Self::Tx) -> Result<(), TxError> {
+        finish(tx, false).await
+    }
+}
+
+async fn finish<Conn>(mut tx: DieselTx<Conn>, commit: bool) -> Result<(), TxError>
+where
+    Conn: Connection + R2D2Connection + Send + 'static,
+{
+    let Some(mut connection) = tx.connection.take() else {
+        return Ok(());
+    };
+    tokio::task::spawn_blocking(move || {
+        if commit {
+            <Conn::TransactionManager as DieselTransactionManager<Conn>>::commit_transaction(
+                &mut connection,
+            )
+        } else {
+            <Conn::TransactionManager as DieselTransactionManager<Conn>>::rollback_transaction(
+                &mut connection,
+            )
+        }
+    })
+    .await
+    .map_err(|error| TxError::backend(format!("Diesel task failed: {error}")))?
+    .map_err(TxError::backend)
+}
+
+/// `SET TRANSACTION` statement to run `(before, after)` beginning the
+/// transaction, depending on the backend.
+fn set_transaction<Conn>(options: &TxOptions) -> (Option<String>, Option<String>)
+where
+    Conn: Connection + 'static,
+{
+    let mut characteristics = Vec::new();
+    if let Some(level) = options.isolation {
+        characteristics.push(format!("ISOLATION LEVEL {}", level.as_sql()));
+    }
+    if options.read_only {
+        characteristics.push("READ ONLY".to_string());
+    }
+    if characteristics.is_empty() {
+        return (None, None);
+    }
+    let statement = format!("SET TRANSACTION {}", characteristics.join(", "));
+    let backend = TypeId::of::<Conn::Backend>();
+    #[cfg(feature = "postgres")]
+    if backend == TypeId::of::<diesel::pg::Pg>() {
+        return (None, Some(statement));
+    }
+    #[cfg(feature = "mysql")]
+    if backend == TypeId::of::<diesel::mysql::Mysql>() {
+        return (Some(statement), None);
+    }
+    let _ = (backend, statement);
+    (None, None)
+}
+
+/// The transaction bound to the current task by `#[transactional]`.
+///
+/// Drop the guard before calling another method that uses the transaction.
+pub async fn current_tx<Conn>() -> Result<TxGuard<DieselTx<Conn>>, TxError>
+where
+    Conn: Connection + R2D2Connection + Send + 'static,
+{
+    transaction::current::<DieselTx<Conn>>()
+        .await
+        .ok_or(TxError::NoTransaction(
+            std::any::type_name::<DieselTx<Conn>>(),
+        ))
+}
@@ -0,0 +1,141 @@
+//! Whitelisted sort and filter parameters for [`QueryBuilder`].
+
+use r2e_core::pagination::{Filter, FilterOp, Sort};
+use r2e_core::serde_json::Value;
+use sqlx::{Database, Encode, QueryBuilder, Type};
+
+use crate::keyset::push_value;
+
+/// Push the conditions of `filter` as one parenthesized predicate, or
+/// `1 = 1` when the request has no filter:
+///
+/// `(status IN ($1, $2) AND created_at < CAST($3 AS timestamptz))`
+///
+/// Columns come from the `#[filterable]` declarations; values are bound.
+///
+/// ```ignore
+/// let mut query = QueryBuilder::<Postgres>::new("SELECT * FROM users WHERE ");
+/// push_filter(&mut query, &filter);
+/// push_sort(&mut query, &sort);
+/// ```
+pub fn push_filter<DB, T>(builder: &mut QueryBuilder<DB>, filter: &Filter<T>)
+where
+    DB: Database,
+    i64: Encode<'static, DB> + Type<DB>,
+    f64: Encode<'static, DB> + Type<DB>,
+    bool: Encode<'static, DB> + Type<DB>,
+    String: Encode<'static, DB> + Type<DB>,
+{
+    if filter.is_empty() {
+        builder.push("1 = 1");
+        return;
+    }
+    builder.push("(");
+    for (index, condition) in filter.conditions().iter().enumerate() {
+        if index > 0 {
+            builder.push(" AND ");
+        }
+        builder
+            .push(condition.column)
+            .push(" ")
+            .push(condition.op.as_sql())
+            .push(" ");
+        let values = match (&condition.op, &condition.value) {
+            (FilterOp::In, Value::Array(values)) => values.as_slice(),
+            (_, value) => std::slice::from_ref(value),
+        };
+        if condition.op == FilterOp::In {
+            builder.push("(");
+        }
+        for (index, value) in values.iter().enumerate() {
+            if index > 0 {
+                builder.push(", ");
+            }
+            if condition.cast.is_some() {
+                builder.push("CAST(");
+            }
+            push_value(builder, value);
+            if let Some(cast) = condition.cast {
+                builder.push(" AS ").push(cast).push(")");
+            }
+        }
+        if condition.op == FilterOp::In {
+            builder.push(")");
+        }
+    }
+    builder.push(")");
+}
+
+/// Push ` ORDER BY <columns>` for the requested sort; nothing when the
+/// request has no sort.
+pub fn push_sort<DB: Database, T>(builder: &mut QueryBuilder<DB>, sort: &Sort<T>) {
+    if let Some(order_by) = sort.order_by() {
+        builder.push(" ORDER BY ").push(order_by);
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use r2e_core::pagination::{FieldKind, FilterField, QueryFields, SortField};
+    use sqlx::{Sqlite, SqlitePool};
+
+    struct Item;
+
+    impl QueryFields for Item {
+        const SORT: &'static [SortField] = &[SortField {
+            name: "id",
+            column: "id",
+        }];
+        const FILTER: &'static [FilterField] = &[
+            FilterField {
+                name: "grp",
+                column: "grp",
+                kind: FieldKind::String,
+                ops: &[FilterOp::Eq, FilterOp::In],
+                cast: None,
+            },
+            FilterField {
+                name: "id",
+                column: "id",
+                kind: FieldKind::Integer,
+                ops: &[FilterOp::Gt],
+                cast: Some("INTEGER"),
+            },
+        ];
+    }
+
+    async fn ids(pool: &SqlitePool, query: &[(&str, &str)], sort: &str) -> Vec<i64> {
+        let filter = Filter::<Item>::parse(query.iter().copied(), "").unwrap();
+        let sort = Sort::<Item>::parse([sort]).unwrap();
+        let mut builder = QueryBuilder::<Sqlite>::new("SELECT id FROM items WHERE ");
+        push_filter(&mut builder, &filter);
+        push_sort(&mut builder, &sort);
+        builder.build_query_scalar().fetch_all(pool).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn filters_and_sorts_on_whitelisted_columns() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE items(id INTEGER PRIMARY KEY, grp TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO items(id, grp) VALUES (1, 'b'), (2, 'a'), (3, 'b'), (4, 'c')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(ids(&pool, &[], "-id").await, [4, 3, 2, 1]);
+        assert_eq!(
+            ids(
+                &pool,
+                &[("filter[grp][in]", "a,b"), ("filter[id][gt]", "1")],
+                "id"
+            )
+            .await,
+            [2, 3]
+        );
+        assert_eq!(ids(&pool, &[("filter[grp]", "b")], "-id").await, [3, 1]);
+    }
+}
@@ -0,0 +1,275 @@
+//! [`IdempotencyStore`] in an SQL table, shared by every instance.
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use r2e_core::idempotency::{
+    IdempotencyRecord, IdempotencyStore, StoreError, StoreFuture, StoredResponse,
+};
+use sqlx::{Database, Pool};
+
+/// Keeps `#[idempotent]` records in the `r2e_idempotency` table. The key is
+/// claimed with an `INSERT` that does nothing on conflict, so exactly one
+/// instance runs the handler for a key.
+///
+/// Create the table with [`create_table`](Self::create_table) at boot, or
+/// with a migration (PostgreSQL):
+///
+/// ```sql
+/// CREATE TABLE r2e_idempotency (
+///     idempotency_key TEXT PRIMARY KEY,
+///     fingerprint     TEXT NOT NULL,
+///     response        TEXT,            -- JSON, NULL while in flight
+///     expires_at      BIGINT NOT NULL  -- unix milliseconds
+/// );
+/// ```
+///
+/// ```ignore
+/// let store = SqlxIdempotencyStore::new(pool.clone());
+/// store.create_table().await?;
+/// AppBuilder::new().provide(pool).provide(store.shared())
+/// ```
+///
+/// Expired rows are replaced when their key is used again; delete the rest
+/// with `DELETE FROM r2e_idempotency WHERE expires_at < <now in ms>` from a
+/// scheduled job.
+pub struct SqlxIdempotencyStore<DB: Database> {
+    pool: Pool<DB>,
+    _db: PhantomData<DB>,
+}
+
+impl<DB: Database> Clone for SqlxIdempotencyStore<DB> {
+    fn clone(&self) -> Self {
+        Self {
+            pool: self.pool.clone(),
+            _db: PhantomData,
+        }
+    }
+}
+
+impl<DB: Database> SqlxIdempotencyStore<DB> {
+    pub fn new(pool: Pool<DB>) -> Self {
+        Self {
+            pool,
+            _db: PhantomData,
+        }
+    }
+
+    /// Ready-to-provide store bean: `Arc<dyn IdempotencyStore>`.
+    pub fn shared(self) -> Arc<dyn IdempotencyStore>
+    where
+        Self: IdempotencyStore,
+    {
+        Arc::new(self)
+    }
+}
+
+fn unix_millis(at: SystemTime) -> i64 {
+    at.duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as i64)
+}
+
+fn expiry(ttl: Duration) -> i64 {
+    unix_millis(SystemTime::now() + ttl)
+}
+
+fn record(fingerprint: String, response: Option<String>) -> Result<IdempotencyRecord, StoreError> {
+    Ok(match response {
+        None => IdempotencyRecord::InFlight { fingerprint },
+        Some(json) => IdempotencyRecord::Completed {
+            fingerprint,
+            response: r2e_core::serde_json::from_str(&json).map_err(StoreError::new)?,
+        },
+    })
+}
+
+macro_rules! impl_idempotency_store {
+    (
+        $feature:literal,
+        $db:ty,
+        create = $create:literal,
+        expire = $expire:literal,
+        insert = $insert:literal,
+        select = $select:literal,
+        complete = $complete:literal,
+        release = $release:literal $(,)?
+    ) => {
+        #[cfg(feature = $feature)]
+        impl SqlxIdempotencyStore<$db> {
+            /// Create the `r2e_idempotency` table if it does not exist.
+            pub async fn create_table(&self) -> Result<(), sqlx::Error> {
+                sqlx::query($create).execute(&self.pool).await?;
+                Ok(())
+            }
+        }
+
+        #[cfg(feature = $feature)]
+        impl IdempotencyStore for SqlxIdempotencyStore<$db> {
+            fn try_begin<'a>(
+                &'a self,
+                key: &'a str,
+                fingerprint: &'a str,
+                ttl: Duration,
+            ) -> StoreFuture<'a, Option<IdempotencyRecord>> {
+                Box::pin(async move {
+                    // A release between the INSERT and the SELECT frees the
+                    // key again: claim it on the next pass.
+                    for _ in 0..2 {
+                        sqlx::query($expire)
+                            .bind(key)
+                            .bind(unix_millis(SystemTime::now()))
+                            .execute(&self.pool)
+                            .await
+                            .map_err(StoreError::new)?;
+                        let inserted = sqlx::query($insert)
+                            .bind(key)
+                            .bind(fingerprint)
+                            .bind(expiry(ttl))
+                            .execute(&self.pool)
+                            .await
+                            .map_err(StoreError::new)?;
+                        if inserted.rows_affected() == 1 {
+                            return Ok(None);
+                        }
+                        let existing: Option<(String, Option<String>)> = sqlx::query_as($select)
+                            .bind(key)
+                            .fetch_optional(&self.pool)
+                            .await
+                            .map_err(StoreError::new)?;
+                        if let Some((fingerprint, response)) = existing {
+                            return record(fingerprint, response).map(Some);
+                        }
+                    }
+                    Ok(Some(IdempotencyRecord::InFlight {
+                        fingerprint: fingerprint.to_string(),
+                    }))
+                })
+            }
+
+            fn complete<'a>(
+                &'a self,
+                key: &'a str,
+                response: StoredResponse,
+                ttl: Duration,
+            ) -> StoreFuture<'a, ()> {
+                Box::pin(async move {
+                    let json =
+                        r2e_core::serde_json::to_string(&response).map_err(StoreError::new)?;
+                    sqlx::query($complete)
+                        .bind(json)
+                        .bind(expiry(ttl))
+                        .bind(key)
+                        .execute(&self.pool)
+                        .await
+                        .map_err(StoreError::new)?;
+                    Ok(())
+                })
+            }
+
+            fn release<'a>(&'a self, key: &'a str) -> StoreFuture<'a, ()> {
+                Box::pin(async move {
+                    sqlx::query($release)
+                        .bind(key)
+                        .execute(&self.pool)
+                        .await
+                        .map_err(StoreError::new)?;
+                    Ok(())
+                })
+            }
+        }
+    };
+}
+
+impl_idempotency_store!(
+    "postgres",
+    sqlx::Postgres,
+    create = "CREATE TABLE IF NOT EXISTS r2e_idempotency (idempotency_key TEXT PRIMARY KEY, \
+              fingerprint TEXT NOT NULL, response TEXT, expires_at BIGINT NOT NULL)",
+    expire = "DELETE FROM r2e_idempotency WHERE idempotency_key = $1 AND expires_at <= $2",
+    insert = "INSERT INTO r2e_idempotency (idempotency_key, fingerprint, expires_at) \
+              VALUES ($1, $2, $3) ON CONFLICT (idempotency_key) DO NOTHING",
+    select = "SELECT fingerprint, response FROM r2e_idempotency WHERE idempotency_key = $1",
+    complete = "UPDATE r2e_idempotency SET response = $1, expires_at = $2 \
+                WHERE idempotency_key = $3",
+    release = "DELETE FROM r2e_idempotency WHERE idempotency_key = $1",
+);
+impl_idempotency_store!(
+    "mysql",
+    sqlx::MySql,
+    create = "CREATE TABLE IF NOT EXISTS r2e_idempotency (idempotency_key VARCHAR(512) \
+              PRIMARY KEY, fingerprint VARCHAR(64) NOT NULL, response LONGTEXT, \
+              expires_at BIGINT NOT NULL)",
+    expire = "DELETE FROM r2e_idempotency WHERE idempotency_key = ? AND expires_at <= ?",
+    insert = "INSERT IGNORE INTO r2e_idempotency (idempotency_key, fingerprint, expires_at) \
+              VALUES (?, ?, ?)",
+    select = "SELECT fingerprint, response FROM r2e_idempotency WHERE idempotency_key = ?",
+    complete = "UPDATE r2e_idempotency SET response = ?, expires_at = ? \
+                WHERE idempotency_key = ?",
+    release = "DELETE FROM r2e_idempotency WHERE idempotency_key = ?",
+);
+impl_idempotency_store!(
+    "sqlite",
+    sqlx::Sqlite,
+    create = "CREATE TABLE IF NOT EXISTS r2e_idempotency (idempotency_key TEXT PRIMARY KEY, \
+              fingerprint TEXT NOT NULL, response TEXT, expires_at INTEGER NOT NULL)",
+    expire = "DELETE FROM r2e_idempotency WHERE idempotency_key = $1 AND expires_at <= $2",
+    insert = "INSERT INTO r2e_idempotency (idempotency_key, fingerprint, expires_at) \
+              VALUES ($1, $2, $3) ON CONFLICT (idempotency_key) DO NOTHING",
+    select = "SELECT fingerprint, response FROM r2e_idempotency WHERE idempotency_key = $1",
+    complete = "UPDATE r2e_idempotency SET response = $1, expires_at = $2 \
+                WHERE idempotency_key = $3",
+    release = "DELETE FROM r2e_idempotency WHERE idempotency_key = $1",
+);
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use sqlx::Sqlite;
+
+    #[tokio::test]
+    async fn claims_each_key_once_and_replays_the_response() {
+        let pool = Pool::<Sqlite>::connect("sqlite::memory:").await.unwrap();
+        let store = SqlxIdempotencyStore::new(pool);
+        store.create_table().await.unwrap();
+        let ttl = Duration::from_secs(60);
+
+        assert_eq!(store.try_begin("3:ada:k", "f", ttl).await.unwrap(), None);
+        assert_eq!(
+            store.try_begin("3:ada:k", "f", ttl).await.unwrap(),
+            Some(IdempotencyRecord::InFlight {
+                fingerprint: "f".into()
+            })
+        );
+
+        let response = StoredResponse {
+            status: 201,
+            headers: vec![("content-type".into(), "application/json".into())],
+            body: b"{\"id\":1}".to_vec(),
+        };
+        store
+            .complete("3:ada:k", response.clone(), ttl)
+            .await
+            .unwrap();
+        assert_eq!(
+            store.try_begin("3:ada:k", "g", ttl).await.unwrap(),
+            Some(IdempotencyRecord::Completed {
+                fingerprint: "f".into(),
+                response
+            })
+        );
+
+        store.release("3:ada:k").await.unwrap();
+        assert_eq!(store.try_begin("3:ada:k", "g", ttl).await.unwrap(), None);
+
+        // An expired claim is taken over.
+        assert_eq!(
+            store
+                .try_begin("3:bob:k", "f", Duration::ZERO)
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(store.try_begin("3:bob:k", "f", ttl).await.unwrap(), None);
+    }
+}
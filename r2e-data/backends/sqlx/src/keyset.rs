@@ -0,0 +1,162 @@
+//! Keyset pagination `WHERE` clauses for [`QueryBuilder`].
+
+use r2e_core::pagination::cursor::{CursorPosition, KeysetSort};
+use r2e_core::serde_json::Value;
+use r2e_core::HttpError;
+use sqlx::{Database, Encode, QueryBuilder, Type};
+
+/// Push the keyset condition selecting the rows after (or before) `position`
+/// in `sort` order, as one parenthesized predicate:
+///
+/// `((a > $1) OR (a = $2 AND b > $3))`
+///
+/// The cursor values are bound, cast where the column asks for it. Fails
+/// with `400 Bad Request` when the cursor does not match the sort.
+///
+/// ```ignore
+/// let mut query = QueryBuilder::<Postgres>::new("SELECT id, name, created_at FROM users");
+/// if let Some(position) = &position {
+///     query.push(" WHERE ");
+///     push_keyset(&mut query, &sort, position)?;
+/// }
+/// query
+///     .push(" ORDER BY ")
+///     .push(sort.order_by(position.as_ref()))
+///     .push(" LIMIT ")
+///     .push_bind(i64::from(pageable.fetch_limit()));
+/// ```
+pub fn push_keyset<DB>(
+    builder: &mut QueryBuilder<DB>,
+    sort: &KeysetSort,
+    position: &CursorPosition,
+) -> Result<(), HttpError>
+where
+    DB: Database,
+    i64: Encode<'static, DB> + Type<DB>,
+    f64: Encode<'static, DB> + Type<DB>,
+    bool: Encode<'static, DB> + Type<DB>,
+    String: Encode<'static, DB> + Type<DB>,
+{
+    let conditions = sort.conditions(position)?;
+    builder.push("(");
+    for (index, terms) in conditions.iter().enumerate() {
+        if index > 0 {
+            builder.push(" OR ");
+        }
+        builder.push("(");
+        for (index, term) in terms.iter().enumerate() {
+            if index > 0 {
+                builder.push(" AND ");
+            }
+            builder
+                .push(&term.column.name)
+                .push(" ")
+                .push(term.operator)
+                .push(" ");
+            if term.column.cast.is_some() {
+                builder.push("CAST(");
+            }
+            push_value(builder, term.value);
+            if let Some(cast) = &term.column.cast {
+                builder.push(" AS ").push(cast).push(")");
+            }
+        }
+        builder.push(")");
+    }
+    builder.push(")");
+    Ok(())
+}
+
+/// Bind a cursor or filter value with the matching SQL type.
+pub(crate) fn push_value<DB>(builder: &mut QueryBuilder<DB>, value: &Value)
+where
+    DB: Database,
+    i64: Encode<'static, DB> + Type<DB>,
+    f64: Encode<'static, DB> + Type<DB>,
+    bool: Encode<'static, DB> + Type<DB>,
+    String: Encode<'static, DB> + Type<DB>,
+{
+    match value {
+        Value::Bool(value) => builder.push_bind(*value),
+        Value::Number(number) => match number.as_i64() {
+            Some(value) => builder.push_bind(value),
+            None => builder.push_bind(number.as_f64().unwrap_or_default()),
+        },
+        value => builder.push_bind(value.as_str().unwrap_or_default().to_owned()),
+    };
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use r2e_core::pagination::{CursorCodec, CursorPage, CursorPageable};
+    use sqlx::{Sqlite, SqlitePool};
+
+    async fn pool() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE items(id INTEGER PRIMARY KEY, grp TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for (id, group) in [(1, "b"), (2, "a"), (3, "b"), (4, "a"), (5, "c")] {
+            sqlx::query("INSERT INTO items(id, grp) VALUES (?, ?)")
+                .bind(id)
+                .bind(group)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        pool
+    }
+
+    async fn page(
+        pool: &SqlitePool,
+        sort: &KeysetSort,
+        codec: &CursorCodec,
+        pageable: &CursorPageable,
+    ) -> CursorPage<(String, i64)> {
+        let position = pageable.position(codec).unwrap();
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT grp, id FROM items");
+        if let Some(position) = &position {
+            query.push(" WHERE ");
+            push_keyset(&mut query, sort, position).unwrap();
+        }
+        query
+            .push(" ORDER BY ")
+            .push(sort.order_by(position.as_ref()))
+            .push(" LIMIT ")
+            .push_bind(i64::from(pageable.fetch_limit()));
+        let rows: Vec<(String, i64)> = query.build_query_as().fetch_all(pool).await.unwrap();
+        CursorPage::new(rows, pageable, position.as_ref(), codec, |row| row.clone())
+    }
+
+    fn from_cursor(cursor: &Option<String>) -> CursorPageable {
+        let mut pageable = CursorPageable::new(2);
+        pageable.cursor = cursor.clone();
+        pageable
+    }
+
+    #[tokio::test]
+    async fn walks_a_multi_column_keyset_both_ways() {
+        let pool = pool().await;
+        let codec = CursorCodec::new("secret");
+        let sort = KeysetSort::new().asc("grp").desc("id");
+        let ids = |page: &CursorPage<(String, i64)>| {
+            page.content.iter().map(|(_, id)| *id).collect::<Vec<_>>()
+        };
+
+        let first = page(&pool, &sort, &codec, &CursorPageable::new(2)).await;
+        assert_eq!(ids(&first), [4, 2]);
+        let second = page(&pool, &sort, &codec, &from_cursor(&first.next)).await;
+        assert_eq!(ids(&second), [3, 1]);
+        let third = page(&pool, &sort, &codec, &from_cursor(&second.next)).await;
+        assert_eq!(ids(&third), [5]);
+        assert!(third.next.is_none());
+
+        let back = page(&pool, &sort, &codec, &from_cursor(&third.prev)).await;
+        assert_eq!(ids(&back), [3, 1]);
+        let start = page(&pool, &sort, &codec, &from_cursor(&back.prev)).await;
+        assert_eq!(ids(&start), [4, 2]);
+        assert!(start.prev.is_none());
+    }
+}
@@ -1,5 +1,9 @@
 //! Managed SQLx transactions for R2E.
 //!
+//! The pool comes either from `.provide(pool)` or from the [`DataSource`]
+//! plugin, which builds it from the `datasource.*` config section and can
+//! apply the app's migrations at boot.
+//!
 //! Register an SQLx pool as a bean, then request a [`Tx`] from an HTTP route:
 //!
 //! ```ignore
@@ -21,11 +25,60 @@
 //!
 //! Responses below status 400 commit. Client/server error responses roll back.
 //! Cancellation and panic use SQLx's drop rollback as a safety fallback.
+//!
+//! A read-only route can take a [`ReadTx`] instead. When the `DataSource` has
+//! `datasource.replicas`, it is served by a read replica through the
+//! [`ReadReplicas`] router, with the primary as fallback.
+//!
+//! Outside routes, `#[transactional]` binds a transaction begun from a
+//! `Pool<DB>` field to the current task; [`current_tx`] reaches it from the
+//! method and from any nested call:
+//!
+//! ```ignore
+//! #[transactional]
+//! async fn archive(&self, id: i64) -> Result<(), HttpError> {
+//!     let mut tx = current_tx::<sqlx::Postgres>().await?;
+//!     sqlx::query("UPDATE users SET archived = true WHERE id = $1")
+//!         .bind(id)
+//!         .execute(tx.connection())
+//!         .await
+//!         .map_err(|error| HttpError::internal(error.to_string()))?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! [`SqlxIdempotencyStore`] keeps the responses of `#[idempotent]` routes in
+//! a table shared by every instance, and [`SqlxSessionStore`] the sessions of
+//! the `Sessions` plugin.
 
+mod datasource;
+mod fields;
+mod idempotency;
+mod keyset;
+#[cfg(feature = "prometheus")]
+mod metrics;
+mod migrations;
+mod replicas;
+mod session;
+mod transactional;
 mod tx;
 
-pub use tx::{SqlxTx, Tx};
+pub use datasource::{
+    DataSource, DataSourceConfig, DataSourceDatabase, DataSourceHealth, DataSourceName,
+    NamedDataSource, NamedPool,
+};
+pub use fields::{push_filter, push_sort};
+pub use idempotency::SqlxIdempotencyStore;
+pub use keyset::push_keyset;
+pub use migrations::{migration_status, MigrationInfo, MigrationState, MigrationsConfig};
+pub use replicas::{on_primary, primary_reads, ReadReplicas, ReplicasConfig};
+pub use session::SqlxSessionStore;
+pub use transactional::{current_tx, Sqlx};
+pub use tx::{ReadTx, SqlxTx, Tx};
 
 pub mod prelude {
-    pub use crate::{SqlxTx, Tx};
+    pub use crate::{
+        current_tx, on_primary, DataSource, DataSourceName, NamedPool, ReadReplicas, ReadTx,
+        SqlxTx, Tx,
+    };
 }
@@ -0,0 +1,136 @@
+//! Pool gauges on the shared `r2e-prometheus` registry (`prometheus` feature).
+
+use r2e_core::PluginInstallContext;
+use r2e_prometheus::prometheus::core::{Collector, Desc};
+use r2e_prometheus::prometheus::proto::MetricFamily;
+use r2e_prometheus::prometheus::{IntGauge, IntGaugeVec, Opts};
+use sqlx::{Database, Pool};
+
+/// Reads the pool statistics on every scrape:
+///
+/// - `datasource_connections{datasource, state="active"|"idle"}`
+/// - `datasource_max_connections{datasource}`
+pub(crate) struct PoolCollector<DB: Database> {
+    pool: Pool<DB>,
+    connections: IntGaugeVec,
+    max_connections: IntGauge,
+}
+
+impl<DB: Database> PoolCollector<DB> {
+    pub(crate) fn new(name: &str, pool: Pool<DB>) -> Self {
+        let connections = IntGaugeVec::new(
+            Opts::new("datasource_connections", "Open pool connections, by state")
+                .const_label("datasource", name),
+            &["state"],
+        )
+        .expect("valid datasource_connections metric");
+        let max_connections = IntGauge::with_opts(
+            Opts::new(
+                "datasource_max_connections",
+                "Maximum number of pool connections",
+            )
+            .const_label("datasource", name),
+        )
+        .expect("valid datasource_max_connections metric");
+        Self {
+            pool,
+            connections,
+            max_connections,
+        }
+    }
+}
+
+impl<DB: Database> Collector for PoolCollector<DB> {
+    fn desc(&self) -> Vec<&Desc> {
+        let mut descs = self.connections.desc();
+        descs.extend(self.max_connections.desc());
+        descs
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        let size = i64::from(self.pool.size());
+        let idle = self.pool.num_idle() as i64;
+        self.connections
+            .with_label_values(&["active"])
+            .set(size - idle);
+        self.connections.with_label_values(&["idle"]).set(idle);
+        self.max_connections
+            .set(i64::from(self.pool.options().get_max_connections()));
+
+        let mut families = self.connections.collect();
+        families.extend(self.max_connections.collect());
+        families
+    }
+}
+
+/// Register the pool's collector when the server starts and remove it on
+/// shutdown, so a hot-reloaded app does not collide with its predecessor.
+///
+/// Waiting for the serve phase leaves the Prometheus plugin's `configure`
+/// step — which initializes the shared registry — ahead of us whatever the
+/// plugin install order.
+pub(crate) fn register<DB: Database>(
+    ctx: &mut PluginInstallContext<'_>,
+    name: String,
+    pool: &Pool<DB>,
+) {
+    let serving = pool.clone();
+    let serving_name = name.clone();
+    ctx.on_serve(move |_| {
+        let collector = PoolCollector::new(&serving_name, serving);
+        if let Err(error) = r2e_prometheus::registry().register(Box::new(collector)) {
+            tracing::warn!(datasource = %serving_name, error = %error, "failed to register pool metrics");
+        }
+    });
+    let stopping = pool.clone();
+    ctx.on_shutdown(move || {
+        let _ =
+            r2e_prometheus::registry().unregister(Box::new(PoolCollector::new(&name, stopping)));
+    });
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use r2e_prometheus::prometheus::Registry;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn exports_pool_gauges_per_datasource() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(3)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let held = pool.acquire().await.unwrap();
+        let registry = Registry::new();
+        registry
+            .register(Box::new(PoolCollector::new("primary", pool.clone())))
+            .unwrap();
+        registry
+            .register(Box::new(PoolCollector::new("replica", pool.clone())))
+            .unwrap();
+
+        let families = registry.gather();
+        let connections = families
+            .iter()
+            .find(|family| family.name() == "datasource_connections")
+            .unwrap();
+        let active = connections
+            .get_metric()
+            .iter()
+            .find(|metric| {
+                metric.get_label().iter().any(|l| l.value() == "primary")
+                    && metric.get_label().iter().any(|l| l.value() == "active")
+            })
+            .unwrap();
+        assert_eq!(active.get_gauge().value(), 1.0);
+        let max = families
+            .iter()
+            .find(|family| family.name() == "datasource_max_connections")
+            .unwrap();
+        assert_eq!(max.get_metric().len(), 2);
+        assert_eq!(max.get_metric()[0].get_gauge().value(), 3.0);
+        drop(held);
+    }
+}
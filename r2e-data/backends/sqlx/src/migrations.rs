@@ -0,0 +1,522 @@
+//! Schema migrations applied by the [`DataSource`](crate::DataSource) plugin.
+//!
+//! Hand the plugin the app's embedded migrations and it applies the pending
+//! ones while `build_state()` runs — after the beans are constructed, before
+//! any `#[post_construct]` hook:
+//!
+//! ```ignore
+//! .plugin(DataSource::<Postgres>::new().migrations(sqlx::migrate!()))
+//! ```
+//!
+//! ```yaml
+//! datasource:
+//!   url: ${DATABASE_URL}
+//!   migrations:
+//!     migrate-at-start: true   # apply pending migrations at boot
+//!     validate-only: false     # only check; fail the boot if behind
+//! ```
+//!
+//! Per-profile behaviour goes in the profile file, e.g. `validate-only: true`
+//! in `application-prod.yaml`.
+//!
+//! `r2e db migrate|revert|status` runs the app itself with the
+//! `migrate.command` key set (through `R2E_MIGRATE_COMMAND`), so the command
+//! sees the same config, profile, secrets and embedded migrations as the
+//! server. The plugin then runs that command instead of booting and exits.
+
+use std::sync::{Arc, Mutex};
+
+use r2e_core::prelude::ConfigProperties;
+use r2e_core::PluginInstallContext;
+use sqlx::migrate::{Migrate, MigrateError, Migrator};
+use sqlx::{Database, Pool};
+
+/// Config key naming the migration command to run instead of booting
+/// (`migrate`, `revert` or `status`). Set by `r2e db` via `R2E_MIGRATE_COMMAND`.
+const COMMAND_KEY: &str = "migrate.command";
+/// Config key naming the datasource the command targets (`default` when unset).
+/// Set by `r2e db --datasource` via `R2E_MIGRATE_DATASOURCE`.
+const TARGET_KEY: &str = "migrate.datasource";
+
+/// Config section of a datasource's migrations: `datasource.migrations`, or
+/// `datasource.<name>.migrations` for a named datasource. Every key is
+/// optional.
+#[derive(ConfigProperties, Clone, Debug)]
+pub struct MigrationsConfig {
+    /// Apply pending migrations at boot.
+    #[config(key = "migrate-at-start", default = true)]
+    pub migrate_at_start: bool,
+    /// Apply nothing; fail the boot if a migration is pending, was modified
+    /// after being applied, or is missing from the app. Wins over
+    /// `migrate-at-start`.
+    #[config(key = "validate-only", default = false)]
+    pub validate_only: bool,
+}
+
+impl Default for MigrationsConfig {
+    fn default() -> Self {
+        Self {
+            migrate_at_start: true,
+            validate_only: false,
+        }
+    }
+}
+
+impl MigrationsConfig {
+    fn mode(&self) -> &'static str {
+        if self.validate_only {
+            "validate"
+        } else if self.migrate_at_start {
+            "migrate"
+        } else {
+            "off"
+        }
+    }
+}
+
+/// Where a migration stands against the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationState {
+    Applied,
+    Pending,
+    /// Applied, but its SQL changed since.
+    Modified,
+    /// Applied, but no longer part of the app's migrations.
+    Missing,
+}
+
+impl MigrationState {
+    fn label(self) -> &'static str {
+        match self {
+            MigrationState::Applied => "applied",
+            MigrationState::Pending => "pending",
+            MigrationState::Modified => "modified",
+            MigrationState::Missing => "missing",
+        }
+    }
+}
+
+/// One row of [`migration_status`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationInfo {
+    pub version: i64,
+    /// Empty for a [`Missing`](MigrationState::Missing) migration.
+    pub description: String,
+    pub state: MigrationState,
+}
+
+/// Compare `migrator` with what the database recorded, ordered by version.
+///
+/// Creates the migrations table if it does not exist yet; fails with
+/// [`MigrateError::Dirty`] if a migration was left partially applied.
+pub async fn migration_status<DB>(
+    migrator: &Migrator,
+    pool: &Pool<DB>,
+) -> Result<Vec<MigrationInfo>, MigrateError>
+where
+    DB: Database,
+    DB::Connection: Migrate,
+{
+    // `table_name` has no getter; the field is public for `migrate!()`.
+    let table = &migrator.table_name;
+    let mut connection = pool.acquire().await?;
+    connection.ensure_migrations_table(table).await?;
+    if let Some(version) = connection.dirty_version(table).await? {
+        return Err(MigrateError::Dirty(version));
+    }
+    let applied = connection.list_applied_migrations(table).await?;
+
+    let mut rows: Vec<MigrationInfo> = migrator
+        .iter()
+        .filter(|migration| migration.migration_type.is_up_migration())
+        .map(|migration| {
+            let state = match applied.iter().find(|a| a.version == migration.version) {
+                Some(a) if a.checksum == migration.checksum => MigrationState::Applied,
+                Some(_) => MigrationState::Modified,
+                None => MigrationState::Pending,
+            };
+            MigrationInfo {
+                version: migration.version,
+                description: migration.description.to_string(),
+                state,
+            }
+        })
+        .collect();
+    rows.extend(
+        applied
+            .iter()
+            .filter(|a| !migrator.version_exists(a.version))
+            .map(|a| MigrationInfo {
+                version: a.version,
+                description: String::new(),
+                state: MigrationState::Missing,
+            }),
+    );
+    rows.sort_by_key(|row| row.version);
+    Ok(rows)
+}
+
+/// Fail unless every migration is applied unchanged.
+fn validate(
+    name: &str,
+    rows: &[MigrationInfo],
+    ignore_missing: bool,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let behind: Vec<String> = rows
+        .iter()
+        .filter(|row| match row.state {
+            MigrationState::Applied => false,
+            MigrationState::Missing => !ignore_missing,
+            _ => true,
+        })
+        .map(|row| format!("{} ({})", row.version, row.state.label()))
+        .collect();
+    if behind.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "datasource `{name}`: schema is not up to date: {} — run `r2e db migrate`",
+            behind.join(", ")
+        )
+        .into())
+    }
+}
+
+/// The migration commands `r2e db` delegates to the app.
+#[derive(Debug, Clone, Copy)]
+enum Command {
+    Migrate,
+    Revert,
+    Status,
+}
+
+impl Command {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "migrate" => Some(Command::Migrate),
+            "revert" => Some(Command::Revert),
+            "status" => Some(Command::Status),
+            _ => None,
+        }
+    }
+}
+
+async fn run_command<DB>(
+    command: Command,
+    name: &str,
+    migrator: &Migrator,
+    pool: &Pool<DB>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+where
+    DB: Database,
+    DB::Connection: Migrate,
+{
+    match command {
+        Command::Migrate => {
+            let pending = migration_status(migrator, pool)
+                .await?
+                .into_iter()
+                .filter(|row| row.state == MigrationState::Pending)
+                .count();
+            migrator.run(pool).await?;
+            println!("datasource `{name}`: applied {pending} migration(s)");
+        }
+        Command::Revert => {
+            let applied: Vec<MigrationInfo> = migration_status(migrator, pool)
+                .await?
+                .into_iter()
+                .filter(|row| row.state == MigrationState::Applied)
+                .collect();
+            match applied.split_last() {
+                None => println!("datasource `{name}`: nothing to revert"),
+                Some((last, rest)) => {
+                    let reversible = migrator.iter().any(|migration| {
+                        migration.version == last.version
+                            && migration.migration_type.is_down_migration()
+                    });
+                    if !reversible {
+                        return Err(format!(
+                            "migration {} has no `.down.sql` and cannot be reverted",
+                            last.version
+                        )
+                        .into());
+                    }
+                    let target = rest.last().map_or(-1, |row| row.version);
+                    migrator.undo(pool, target).await?;
+                    println!(
+                        "datasource `{name}`: reverted {} {}",
+                        last.version, last.description
+                    );
+                }
+            }
+        }
+        Command::Status => {
+            let rows = migration_status(migrator, pool).await?;
+            println!("datasource `{name}`:");
+            if rows.is_empty() {
+                println!("  no migrations");
+            }
+            for row in rows {
+                println!(
+                    "  {:<9} {:<15} {}",
+                    row.state.label(),
+                    row.version,
+                    row.description
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn report_json(mode: &str, rows: &Option<Vec<MigrationInfo>>) -> r2e_core::serde_json::Value {
+    let migrations: Vec<_> = rows
+        .iter()
+        .flatten()
+        .map(|row| {
+            r2e_core::serde_json::json!({
+                "version": row.version,
+                "description": row.description,
+                "state": row.state.label(),
+            })
+        })
+        .collect();
+    r2e_core::serde_json::json!({ "mode": mode, "migrations": migrations })
+}
+
+/// Wire `migrator` into the app for the datasource `name`: the boot-time run
+/// or validation, the `r2e db` command mode, and the dev-status section.
+/// `read_pool` fetches the pool from the resolved graph, so a pinned test
+/// override is the one migrated.
+pub(crate) fn install<DB>(
+    ctx: &mut PluginInstallContext<'_>,
+    name: &'static str,
+    prefix: &str,
+    migrator: Migrator,
+    read_pool: fn(&r2e_core::BeanContext) -> Pool<DB>,
+) where
+    DB: Database,
+    DB::Connection: Migrate,
+{
+    let section = format!("{prefix}.migrations");
+    let settings = match ctx.config() {
+        Some(config) => MigrationsConfig::from_config(config, Some(&section))
+            .unwrap_or_else(|error| panic!("datasource `{name}`: {error}")),
+        None => MigrationsConfig::default(),
+    };
+    let command = ctx.config_get::<String>(COMMAND_KEY).map(|value| {
+        Command::parse(&value).unwrap_or_else(|| {
+            panic!("`{COMMAND_KEY}` must be `migrate`, `revert` or `status`, got `{value}`")
+        })
+    });
+    let targeted = ctx
+        .config_get::<String>(TARGET_KEY)
+        .as_deref()
+        .unwrap_or("default")
+        == name;
+
+    let report: Arc<Mutex<Option<Vec<MigrationInfo>>>> = Arc::default();
+    let reported = report.clone();
+    let mode = settings.mode();
+    ctx.add_dev_status(section, move || {
+        report_json(mode, &reported.lock().unwrap())
+    });
+
+    if command.is_some() {
+        // Reached only when no datasource took the command: the app would
+        // otherwise start serving under `r2e db`.
+        ctx.on_serve(move |_| {
+            eprintln!(
+                "no datasource with migrations matches `{TARGET_KEY}` — \
+                 check `r2e db --datasource`"
+            );
+            std::process::exit(2);
+        });
+    }
+
+    let migrator = Arc::new(migrator);
+    ctx.on_resolved(move |beans| {
+        let pool = read_pool(beans);
+        Box::pin(async move {
+            if let Some(command) = command {
+                if !targeted {
+                    return Ok(());
+                }
+                let code = match run_command(command, name, &migrator, &pool).await {
+                    Ok(()) => 0,
+                    Err(error) => {
+                        eprintln!("datasource `{name}`: {error}");
+                        1
+                    }
+                };
+                pool.close().await;
+                std::process::exit(code);
+            }
+
+            if settings.validate_only {
+                let rows = migration_status(&migrator, &pool).await?;
+                validate(name, &rows, migrator.ignore_missing)?;
+                *report.lock().unwrap() = Some(rows);
+            } else if settings.migrate_at_start {
+                migrator
+                    .run(&pool)
+                    .await
+                    .map_err(|error| format!("datasource `{name}`: migration failed: {error}"))?;
+                let rows = migration_status(&migrator, &pool).await?;
+                tracing::info!(
+                    datasource = name,
+                    applied = rows.len(),
+                    "database migrations up to date"
+                );
+                *report.lock().unwrap() = Some(rows);
+            }
+            Ok(())
+        })
+    });
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::DataSource;
+    use r2e_core::config::R2eConfig;
+    use r2e_core::http::body::{to_bytes, Body};
+    use r2e_core::http::Request;
+    use r2e_core::plugins::DevReload;
+    use r2e_core::{AppBuilder, BeanLookup};
+    use sqlx::migrate::{Migration, MigrationType};
+    use sqlx::{SqlSafeStr, Sqlite};
+    use tower::ServiceExt;
+
+    fn migrator() -> Migrator {
+        Migrator::with_migrations(vec![
+            Migration::new(
+                1,
+                "create users".into(),
+                MigrationType::Simple,
+                "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)".into_sql_str(),
+                false,
+            ),
+            Migration::new(
+                2,
+                "add email".into(),
+                MigrationType::Simple,
+                "ALTER TABLE users ADD COLUMN email TEXT".into_sql_str(),
+                false,
+            ),
+        ])
+    }
+
+    fn shared_memory(name: &str) -> String {
+        format!("sqlite:file:r2e-migrations-{name}?mode=memory&cache=shared")
+    }
+
+    async fn boot(config: &str) -> Result<Pool<Sqlite>, String> {
+        let config = R2eConfig::from_yaml_str(config).unwrap();
+        let app = AppBuilder::new()
+            .override_config(config)
+            .load_config::<()>()
+            .plugin(DataSource::<Sqlite>::new().migrations(migrator()))
+            .try_build_state()
+            .await
+            .map_err(|error| error.to_string())?;
+        Ok(app.state().bean::<Pool<Sqlite>>().unwrap())
+    }
+
+    #[tokio::test]
+    async fn applies_pending_migrations_at_boot() {
+        let url = shared_memory("boot");
+        let pool = boot(&format!(
+            "datasource:\n  url: \"{url}\"\n  min_connections: 1\n"
+        ))
+        .await
+        .unwrap();
+
+        sqlx::query("INSERT INTO users (name, email) VALUES ('ada', 'ada@example.com')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let rows = migration_status(&migrator(), &pool).await.unwrap();
+        assert!(rows.iter().all(|row| row.state == MigrationState::Applied));
+    }
+
+    #[tokio::test]
+    async fn validate_only_fails_the_boot_when_behind() {
+        let url = shared_memory("validate");
+        let error = boot(&format!(
+            "datasource:\n  url: \"{url}\"\n  migrations:\n    validate-only: true\n"
+        ))
+        .await
+        .unwrap_err();
+
+        assert!(error.contains("1 (pending), 2 (pending)"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn migrate_at_start_false_leaves_the_schema_alone() {
+        let url = shared_memory("off");
+        let pool = boot(&format!(
+            "datasource:\n  url: \"{url}\"\n  min_connections: 1\n  migrations:\n    migrate-at-start: false\n"
+        ))
+        .await
+        .unwrap();
+
+        assert!(sqlx::query("SELECT 1 FROM users")
+            .execute(&pool)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn status_reports_modified_and_missing_migrations() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        migrator().run(&pool).await.unwrap();
+
+        let changed = Migrator::with_migrations(vec![Migration::new(
+            1,
+            "create users".into(),
+            MigrationType::Simple,
+            "CREATE TABLE users (id INTEGER PRIMARY KEY)".into_sql_str(),
+            false,
+        )]);
+        let rows = migration_status(&changed, &pool).await.unwrap();
+
+        assert_eq!(rows[0].state, MigrationState::Modified);
+        assert_eq!(rows[1].state, MigrationState::Missing);
+        assert!(validate("default", &rows, false).is_err());
+    }
+
+    #[tokio::test]
+    async fn dev_status_lists_applied_migrations() {
+        let url = shared_memory("dev-status");
+        let config = R2eConfig::from_yaml_str(&format!("datasource:\n  url: \"{url}\"\n")).unwrap();
+        let router = AppBuilder::new()
+            .override_config(config)
+            .load_config::<()>()
+            .plugin(DataSource::<Sqlite>::new().migrations(migrator()))
+            .build_state()
+            .await
+            .with(DevReload)
+            .build();
+
+        let response = router
+            .oneshot(
+                Request::get("/__r2e_dev/status")
+                    .header("accept", "application/json")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let section = &json["sections"]["datasource.migrations"];
+        assert_eq!(section["mode"], "migrate");
+        assert_eq!(section["migrations"][1]["version"], 2);
+        assert_eq!(section["migrations"][1]["state"], "applied");
+    }
+}
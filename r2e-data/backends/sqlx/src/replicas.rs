@@ -0,0 +1,628 @@
+//! Read/write splitting: the [`ReadReplicas`] router.
+//!
+//! The [`DataSource`](crate::DataSource) plugin builds one pool per entry of
+//! `datasource.replicas` and provides a [`ReadReplicas<DB>`] next to the
+//! primary `Pool<DB>`:
+//!
+//! ```yaml
+//! datasource:
+//!   url: postgres://app@primary/app
+//!   replica-max-lag: 5s
+//!   replicas:
+//!     replica-a:
+//!       url: postgres://app@replica-a/app
+//!       acquire_timeout: 2s
+//!     replica-b:
+//!       url: postgres://app@replica-b/app
+//! ```
+//!
+//! Read-only transactions — [`ReadTx`](crate::ReadTx) route parameters and
+//! `#[transactional(read_only, pool = "replicas")]` methods — begin on the
+//! replicas in turn. A replica whose `BEGIN` fails, or whose replication lag
+//! exceeds `replica-max-lag`, is skipped until a later check finds it healthy
+//! again; with no replica left, reads go to the primary. Write transactions
+//! always use the primary.
+//!
+//! A flow that must read its own writes runs inside [`on_primary`], or
+//! behind the [`primary_reads`] route middleware.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use r2e_core::health::{HealthIndicator, HealthStatus};
+use r2e_core::http::middleware::Next;
+use r2e_core::http::{Request, Response};
+use r2e_core::prelude::ConfigProperties;
+use r2e_core::transaction::{TransactionManager, TxError, TxOptions};
+use r2e_core::PluginInstallContext;
+use sqlx::{Connection, Database, Pool};
+
+use crate::datasource::{connect_pool, DataSourceConfig, DataSourceDatabase};
+use crate::transactional::{self, Sqlx};
+use crate::SqlxTx;
+
+tokio::task_local! {
+    static PRIMARY: ();
+}
+
+/// Read replicas of the default datasource and how they are checked.
+///
+/// Read from the `datasource` section: each `datasource.replicas.<name>` is a
+/// [`DataSourceConfig`], and the `replica-*` keys set the routing.
+#[derive(ConfigProperties, Clone, Debug)]
+pub struct ReplicasConfig {
+    /// Replica pools, by name.
+    #[config(section)]
+    pub replicas: BTreeMap<String, DataSourceConfig>,
+    /// Replicas lagging further behind the primary are skipped. `None`
+    /// routes to any reachable replica.
+    #[config(key = "replica-max-lag")]
+    pub max_lag: Option<Duration>,
+    /// How often the replicas are pinged and their lag measured.
+    #[config(key = "replica-check-interval", default = Duration::from_secs(5))]
+    pub check_interval: Duration,
+}
+
+impl ReplicasConfig {
+    /// No replicas: every read goes to the primary.
+    pub fn new() -> Self {
+        Self {
+            replicas: BTreeMap::new(),
+            max_lag: None,
+            check_interval: Duration::from_secs(5),
+        }
+    }
+
+    /// Add the replica `name`.
+    pub fn replica(mut self, name: impl Into<String>, config: DataSourceConfig) -> Self {
+        self.replicas.insert(name.into(), config);
+        self
+    }
+
+    pub fn max_lag(mut self, max_lag: Duration) -> Self {
+        self.max_lag = Some(max_lag);
+        self
+    }
+
+    pub fn check_interval(mut self, interval: Duration) -> Self {
+        self.check_interval = interval;
+        self
+    }
+}
+
+impl Default for ReplicasConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Routes read-only transactions to read replicas, falling back to the
+/// primary.
+///
+/// Provided by the [`DataSource`](crate::DataSource) plugin. Replicas are
+/// used round robin; one that fails to begin a transaction, or fails a
+/// check, is skipped until a check finds it healthy again. As a
+/// `#[transactional]` pool field it sends `read_only` methods to a replica
+/// and the others to the primary.
+pub struct ReadReplicas<DB: Database> {
+    inner: Arc<Inner<DB>>,
+}
+
+struct Inner<DB: Database> {
+    primary: Pool<DB>,
+    replicas: Vec<Replica<DB>>,
+    next: AtomicUsize,
+}
+
+struct Replica<DB: Database> {
+    name: String,
+    pool: Pool<DB>,
+    available: AtomicBool,
+}
+
+impl<DB: Database> Replica<DB> {
+    fn mark_down(&self, reason: &str) {
+        if self.available.swap(false, Ordering::Relaxed) {
+            tracing::warn!(replica = %self.name, reason, "read replica unavailable, routing reads elsewhere");
+        }
+    }
+
+    fn mark_up(&self) {
+        if !self.available.swap(true, Ordering::Relaxed) {
+            tracing::info!(replica = %self.name, "read replica available again");
+        }
+    }
+}
+
+impl<DB: Database> Clone for ReadReplicas<DB> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<DB: Database> ReadReplicas<DB> {
+    /// A router over `replicas`, all initially available.
+    pub fn new<I, N>(primary: Pool<DB>, replicas: I) -> Self
+    where
+        I: IntoIterator<Item = (N, Pool<DB>)>,
+        N: Into<String>,
+    {
+        let replicas = replicas
+            .into_iter()
+            .map(|(name, pool)| Replica {
+                name: name.into(),
+                pool,
+                available: AtomicBool::new(true),
+            })
+            .collect();
+        Self {
+            inner: Arc::new(Inner {
+                primary,
+                replicas,
+                next: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    pub fn primary(&self) -> &Pool<DB> {
+        &self.inner.primary
+    }
+
+    /// Names of the replicas reads are currently routed to.
+    pub fn available(&self) -> Vec<&str> {
+        self.inner
+            .replicas
+            .iter()
+            .filter(|replica| replica.available.load(Ordering::Relaxed))
+            .map(|replica| replica.name.as_str())
+            .collect()
+    }
+
+    /// Begin a read-only transaction on the next available replica, or on
+    /// the primary when none is left or inside [`on_primary`].
+    pub async fn begin_read(&self) -> Result<SqlxTx<'static, DB>, sqlx::Error> {
+        self.begin_read_with(&TxOptions::new().read_only(true))
+            .await
+    }
+
+    async fn begin_read_with(
+        &self,
+        options: &TxOptions,
+    ) -> Result<SqlxTx<'static, DB>, sqlx::Error> {
+        let replicas = &self.inner.replicas;
+        if !primary_forced() && !replicas.is_empty() {
+            let start = self.inner.next.fetch_add(1, Ordering::Relaxed);
+            for offset in 0..replicas.len() {
+                let replica = &replicas[(start + offset) % replicas.len()];
+                if !replica.available.load(Ordering::Relaxed) {
+                    continue;
+                }
+                match transactional::begin(&replica.pool, options).await {
+                    Ok(tx) => return Ok(tx),
+                    Err(error) => replica.mark_down(&error.to_string()),
+                }
+            }
+        }
+        transactional::begin(&self.inner.primary, options).await
+    }
+}
+
+impl<DB: DataSourceDatabase> ReadReplicas<DB> {
+    /// Check every replica and update which ones reads are routed to.
+    ///
+    /// The [`DataSource`](crate::DataSource) plugin runs this every
+    /// `replica-check-interval` while the server runs.
+    pub async fn refresh(&self, max_lag: Option<Duration>) {
+        for index in 0..self.inner.replicas.len() {
+            let _ = self.check(index, max_lag).await;
+        }
+    }
+
+    /// Ping replica `index` and compare its lag with `max_lag`.
+    async fn check(&self, index: usize, max_lag: Option<Duration>) -> Result<(), String> {
+        let replica = &self.inner.replicas[index];
+        let result = probe(&replica.pool, max_lag).await;
+        match &result {
+            Ok(()) => replica.mark_up(),
+            Err(reason) => replica.mark_down(reason),
+        }
+        result
+    }
+}
+
+async fn probe<DB: DataSourceDatabase>(
+    pool: &Pool<DB>,
+    max_lag: Option<Duration>,
+) -> Result<(), String> {
+    let mut connection = pool.acquire().await.map_err(|error| error.to_string())?;
+    connection.ping().await.map_err(|error| error.to_string())?;
+    let Some(max_lag) = max_lag else {
+        return Ok(());
+    };
+    match DB::replica_lag(&mut connection).await {
+        Ok(Some(lag)) if lag > max_lag => Err(format!(
+            "replication lag {lag:?} exceeds replica-max-lag {max_lag:?}"
+        )),
+        Ok(_) => Ok(()),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+impl<DB: Database> TransactionManager<Sqlx> for ReadReplicas<DB> {
+    type Tx = SqlxTx<'static, DB>;
+
+    async fn begin(&self, options: TxOptions) -> Result<Self::Tx, TxError> {
+        if options.read_only {
+            self.begin_read_with(&options).await
+        } else {
+            transactional::begin(&self.inner.primary, &options).await
+        }
+        .map_err(TxError::backend)
+    }
+
+    async fn commit(&self, tx: Self::Tx) -> Result<(), TxError> {
+        transactional::commit(tx).await
+    }
+
+    async fn rollback(&self, tx: Self::Tx) -> Result<(), TxError> {
+        transactional::rollback(tx).await
+    }
+}
+
+/// Run `body` with every read on the primary, for flows that must see their
+/// own writes.
+///
+/// Like `#[transactional]`, the override is bound to the current task.
+pub async fn on_primary<F: Future>(body: F) -> F::Output {
+    PRIMARY.scope((), body).await
+}
+
+/// Route middleware serving the route's reads from the primary:
+/// `#[middleware(primary_reads)]`.
+pub async fn primary_reads(request: Request, next: Next) -> Response {
+    on_primary(next.run(request)).await
+}
+
+fn primary_forced() -> bool {
+    PRIMARY.try_with(|_| ()).is_ok()
+}
+
+/// Liveness-only check of one replica: a down or lagging replica takes no
+/// reads, but the app stays ready on the primary.
+struct ReplicaHealth<DB: Database> {
+    name: String,
+    replicas: ReadReplicas<DB>,
+    index: usize,
+    max_lag: Option<Duration>,
+}
+
+impl<DB: DataSourceDatabase> HealthIndicator for ReplicaHealth<DB> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn check(&self) -> HealthStatus {
+        match self.replicas.check(self.index, self.max_lag).await {
+            Ok(()) => HealthStatus::Up,
+            Err(reason) => HealthStatus::Down(reason),
+        }
+    }
+
+    fn affects_readiness(&self) -> bool {
+        false
+    }
+}
+
+/// Build the replica pools and their health checks, and refresh the routing
+/// every `check_interval` while the server runs.
+pub(crate) fn install<DB: DataSourceDatabase>(
+    ctx: &mut PluginInstallContext<'_>,
+    name: &str,
+    prefix: &str,
+    primary: &Pool<DB>,
+    config: ReplicasConfig,
+) -> ReadReplicas<DB> {
+    let pools: Vec<(String, Pool<DB>)> = config
+        .replicas
+        .iter()
+        .map(|(replica, replica_config)| {
+            let pool = connect_pool(
+                ctx,
+                &format!("{name}.replicas.{replica}"),
+                &format!("{prefix}.replicas.{replica}"),
+                replica_config,
+            );
+            (replica.clone(), pool)
+        })
+        .collect();
+    let replicas = ReadReplicas::new(primary.clone(), pools);
+    if replicas.inner.replicas.is_empty() {
+        return replicas;
+    }
+
+    for (index, replica) in replicas.inner.replicas.iter().enumerate() {
+        ctx.add_health_check(ReplicaHealth {
+            name: format!("{prefix}.replicas.{}", replica.name),
+            replicas: replicas.clone(),
+            index,
+            max_lag: config.max_lag,
+        });
+    }
+
+    let status = replicas.clone();
+    ctx.add_dev_status(format!("{prefix}.replicas"), move || {
+        let available = status.available();
+        status
+            .inner
+            .replicas
+            .iter()
+            .map(|replica| {
+                (
+                    replica.name.clone(),
+                    r2e_core::serde_json::json!({ "available": available.contains(&replica.name.as_str()) }),
+                )
+            })
+            .collect::<r2e_core::serde_json::Map<_, _>>()
+            .into()
+    });
+
+    let refreshing = replicas.clone();
+    let ReplicasConfig {
+        max_lag,
+        check_interval,
+        ..
+    } = config;
+    ctx.on_serve(move |serve_ctx| {
+        let shutdown = serve_ctx.shutdown_token();
+        serve_ctx.track(r2e_core::rt::spawn(async move {
+            let mut ticks = r2e_core::rt::interval(check_interval);
+            loop {
+                tokio::select! {
+                    _ = shutdown.cancelled() => break,
+                    _ = async {
+                        ticks.tick().await;
+                        refreshing.refresh(max_lag).await;
+                    } => {}
+                }
+            }
+        }));
+    });
+    replicas
+}
+
+#[cfg(feature = "postgres")]
+pub(crate) async fn postgres_lag(
+    connection: &mut sqlx::PgConnection,
+) -> Result<Option<Duration>, sqlx::Error> {
+    // NULL on a primary, and on a replica that has not replayed anything yet.
+    // A replica that replayed all the WAL it received is caught up: the replay
+    // timestamp only moves with new writes, so it would make an idle primary's
+    // replicas look late.
+    let seconds: Option<f64> = sqlx::query_scalar(
+        "SELECT CASE \
+         WHEN NOT pg_is_in_recovery() THEN NULL \
+         WHEN pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() THEN 0::float8 \
+         ELSE EXTRACT(EPOCH FROM now() - pg_last_xact_replay_timestamp())::float8 END",
+    )
+    .fetch_one(connection)
+    .await?;
+    Ok(seconds.map(|seconds| Duration::from_secs_f64(seconds.max(0.0))))
+}
+
+#[cfg(feature = "mysql")]
+pub(crate) async fn mysql_lag(
+    connection: &mut sqlx::MySqlConnection,
+) -> Result<Option<Duration>, sqlx::Error> {
+    use sqlx::Row;
+
+    // No row on a server that is not a replica.
+    let Some(row) = sqlx::query("SHOW REPLICA STATUS")
+        .fetch_optional(connection)
+        .await?
+    else {
+        return Ok(None);
+    };
+    let seconds: Option<i64> = row.try_get_unchecked("Seconds_Behind_Source")?;
+    Ok(seconds.map(|seconds| Duration::from_secs(seconds.max(0) as u64)))
+}
+
+#[cfg(feature = "sqlite")]
+pub(crate) async fn unknown_lag<C: Send>(
+    _connection: &mut C,
+) -> Result<Option<Duration>, sqlx::Error> {
+    Ok(None)
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    use r2e_core::config::R2eConfig;
+    use r2e_core::http::body::Body;
+    use r2e_core::http::StatusCode;
+    use r2e_core::plugins::Health;
+    use r2e_core::transaction::transactional;
+    use r2e_core::{AppBuilder, BeanLookup, ManagedContext, ManagedGuard};
+    use sqlx::{Sqlite, SqlitePool};
+    use tower::ServiceExt;
+
+    use crate::{current_tx, DataSource, ReadTx};
+
+    /// A database file whose `source` table names it.
+    async fn database(test: &str, name: &str) -> String {
+        let path: PathBuf = std::env::temp_dir().join(format!(
+            "r2e-replicas-{}-{test}-{name}.db",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let url = format!("sqlite://{}?mode=rwc", path.display());
+        let pool = SqlitePool::connect(&url).await.unwrap();
+        sqlx::query("CREATE TABLE source(name TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO source(name) VALUES (?)")
+            .bind(name)
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool.close().await;
+        url
+    }
+
+    fn unreachable() -> DataSourceConfig {
+        DataSourceConfig {
+            acquire_timeout: Some(Duration::from_millis(200)),
+            ..DataSourceConfig::new("sqlite:///r2e-missing-dir/replica.db")
+        }
+    }
+
+    async fn source(connection: &mut sqlx::SqliteConnection) -> String {
+        sqlx::query_scalar("SELECT name FROM source")
+            .fetch_one(connection)
+            .await
+            .unwrap()
+    }
+
+    async fn read<S: BeanLookup + Send + Sync>(state: &S) -> String {
+        let mut tx = ManagedGuard::<ReadTx<'static, Sqlite>, _>::acquire(ManagedContext::new(
+            state, "Test", "read",
+        ))
+        .await
+        .unwrap();
+        source(tx.resource_mut().connection()).await
+    }
+
+    #[tokio::test]
+    async fn reads_rotate_over_configured_replicas() {
+        let yaml = format!(
+            "datasource:\n  url: \"{}\"\n  replica-max-lag: 5s\n  replicas:\n    \
+             a:\n      url: \"{}\"\n    b:\n      url: \"{}\"\n",
+            database("rotate", "primary").await,
+            database("rotate", "a").await,
+            database("rotate", "b").await,
+        );
+        let app = AppBuilder::new()
+            .override_config(R2eConfig::from_yaml_str(&yaml).unwrap())
+            .load_config::<()>()
+            .plugin(DataSource::<Sqlite>::new())
+            .build_state()
+            .await;
+
+        let mut seen = vec![read(app.state()).await, read(app.state()).await];
+        seen.sort();
+        assert_eq!(seen, ["a", "b"]);
+
+        let primary = app.state().bean::<Pool<Sqlite>>().unwrap();
+        let mut connection = primary.acquire().await.unwrap();
+        assert_eq!(source(&mut connection).await, "primary");
+    }
+
+    #[tokio::test]
+    async fn failed_replica_is_skipped_then_primary_takes_over() {
+        let config = DataSourceConfig::new(database("failover", "primary").await);
+        let replicas = ReplicasConfig::new()
+            .replica("a", unreachable())
+            .replica("b", DataSourceConfig::new(database("failover", "b").await));
+        let app = AppBuilder::new()
+            .plugin(
+                DataSource::<Sqlite>::new()
+                    .config(config)
+                    .replicas(replicas),
+            )
+            .build_state()
+            .await;
+        let router = app.state().bean::<ReadReplicas<Sqlite>>().unwrap();
+
+        assert_eq!(read(app.state()).await, "b");
+        assert_eq!(read(app.state()).await, "b");
+        assert_eq!(router.available(), ["b"]);
+
+        let only_broken = ReadReplicas::new(
+            router.primary().clone(),
+            [(
+                "a",
+                SqlitePool::connect_lazy("sqlite:///r2e-missing-dir/a.db").unwrap(),
+            )],
+        );
+        let mut tx = only_broken.begin_read().await.unwrap();
+        assert_eq!(source(tx.connection()).await, "primary");
+        assert!(only_broken.available().is_empty());
+    }
+
+    #[tokio::test]
+    async fn on_primary_overrides_replica_reads() {
+        let config = DataSourceConfig::new(database("override", "primary").await);
+        let replicas = ReplicasConfig::new()
+            .replica("a", DataSourceConfig::new(database("override", "a").await));
+        let app = AppBuilder::new()
+            .plugin(
+                DataSource::<Sqlite>::new()
+                    .config(config)
+                    .replicas(replicas),
+            )
+            .build_state()
+            .await;
+
+        assert_eq!(read(app.state()).await, "a");
+        assert_eq!(on_primary(read(app.state())).await, "primary");
+        assert_eq!(read(app.state()).await, "a");
+    }
+
+    #[tokio::test]
+    async fn transactional_read_only_uses_a_replica() {
+        let primary = SqlitePool::connect(&database("transactional", "primary").await)
+            .await
+            .unwrap();
+        let replica = SqlitePool::connect(&database("transactional", "a").await)
+            .await
+            .unwrap();
+        let router = ReadReplicas::new(primary, [("a", replica)]);
+
+        let current_source = || async {
+            let mut tx = current_tx::<Sqlite>().await?;
+            Ok::<_, TxError>(source(tx.connection()).await)
+        };
+        let read = transactional(&router, TxOptions::new().read_only(true), current_source()).await;
+        let write = transactional(&router, TxOptions::new(), current_source()).await;
+        assert_eq!(read.as_deref(), Ok("a"));
+        assert_eq!(write.as_deref(), Ok("primary"));
+    }
+
+    #[tokio::test]
+    async fn lagging_or_down_replica_fails_health_but_not_readiness() {
+        let config = DataSourceConfig::new(database("health", "primary").await);
+        let replicas = ReplicasConfig::new().replica("a", unreachable());
+        let router = AppBuilder::new()
+            .plugin(
+                DataSource::<Sqlite>::new()
+                    .config(config)
+                    .replicas(replicas),
+            )
+            .build_state()
+            .await
+            .with(Health::builder().build())
+            .build();
+
+        let get = |uri: &'static str| {
+            router
+                .clone()
+                .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        };
+        assert_eq!(get("/health/ready").await.unwrap().status(), StatusCode::OK);
+        let health = get("/health").await.unwrap();
+        assert_eq!(health.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = r2e_core::http::body::to_bytes(health.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["checks"][1]["name"], "datasource.replicas.a");
+        assert_eq!(json["checks"][1]["status"], "DOWN");
+    }
+}
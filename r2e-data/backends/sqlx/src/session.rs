@@ -0,0 +1,200 @@
+//! [`SessionStore`] in an SQL table, shared by every instance.
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use r2e_core::session::{SessionRecord, SessionStore, StoreError, StoreFuture};
+use sqlx::{Database, Pool};
+
+/// Keeps sessions in the `r2e_sessions` table, the record as JSON.
+///
+/// Create the table with [`create_table`](Self::create_table) at boot, or
+/// with a migration (PostgreSQL):
+///
+/// ```sql
+/// CREATE TABLE r2e_sessions (
+///     session_id TEXT PRIMARY KEY,
+///     data       TEXT NOT NULL,   -- JSON
+///     expires_at BIGINT NOT NULL  -- unix milliseconds
+/// );
+/// ```
+///
+/// ```ignore
+/// let store = SqlxSessionStore::new(pool.clone());
+/// store.create_table().await?;
+/// AppBuilder::new().provide(pool).provide(store.shared())
+/// ```
+///
+/// Expired rows are never loaded; delete them with
+/// `DELETE FROM r2e_sessions WHERE expires_at < <now in ms>` from a
+/// scheduled job.
+pub struct SqlxSessionStore<DB: Database> {
+    pool: Pool<DB>,
+    _db: PhantomData<DB>,
+}
+
+impl<DB: Database> Clone for SqlxSessionStore<DB> {
+    fn clone(&self) -> Self {
+        Self {
+            pool: self.pool.clone(),
+            _db: PhantomData,
+        }
+    }
+}
+
+impl<DB: Database> SqlxSessionStore<DB> {
+    pub fn new(pool: Pool<DB>) -> Self {
+        Self {
+            pool,
+            _db: PhantomData,
+        }
+    }
+
+    /// Ready-to-provide store bean: `Arc<dyn SessionStore>`.
+    pub fn shared(self) -> Arc<dyn SessionStore>
+    where
+        Self: SessionStore,
+    {
+        Arc::new(self)
+    }
+}
+
+fn unix_millis(at: SystemTime) -> i64 {
+    at.duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as i64)
+}
+
+macro_rules! impl_session_store {
+    (
+        $feature:literal,
+        $db:ty,
+        create = $create:literal,
+        select = $select:literal,
+        upsert = $upsert:literal,
+        delete = $delete:literal $(,)?
+    ) => {
+        #[cfg(feature = $feature)]
+        impl SqlxSessionStore<$db> {
+            /// Create the `r2e_sessions` table if it does not exist.
+            pub async fn create_table(&self) -> Result<(), sqlx::Error> {
+                sqlx::query($create).execute(&self.pool).await?;
+                Ok(())
+            }
+        }
+
+        #[cfg(feature = $feature)]
+        impl SessionStore for SqlxSessionStore<$db> {
+            fn load<'a>(&'a self, id: &'a str) -> StoreFuture<'a, Option<SessionRecord>> {
+                Box::pin(async move {
+                    let data: Option<(String,)> = sqlx::query_as($select)
+                        .bind(id)
+                        .bind(unix_millis(SystemTime::now()))
+                        .fetch_optional(&self.pool)
+                        .await
+                        .map_err(StoreError::new)?;
+                    data.map(|(json,)| SessionRecord::from_bytes(json.as_bytes()))
+                        .transpose()
+                })
+            }
+
+            fn save<'a>(
+                &'a self,
+                id: &'a str,
+                record: &'a SessionRecord,
+                ttl: Duration,
+            ) -> StoreFuture<'a, ()> {
+                Box::pin(async move {
+                    let json = r2e_core::serde_json::to_string(record).map_err(StoreError::new)?;
+                    sqlx::query($upsert)
+                        .bind(id)
+                        .bind(json)
+                        .bind(unix_millis(SystemTime::now() + ttl))
+                        .execute(&self.pool)
+                        .await
+                        .map_err(StoreError::new)?;
+                    Ok(())
+                })
+            }
+
+            fn delete<'a>(&'a self, id: &'a str) -> StoreFuture<'a, ()> {
+                Box::pin(async move {
+                    sqlx::query($delete)
+                        .bind(id)
+                        .execute(&self.pool)
+                        .await
+                        .map_err(StoreError::new)?;
+                    Ok(())
+                })
+            }
+        }
+    };
+}
+
+impl_session_store!(
+    "postgres",
+    sqlx::Postgres,
+    create = "CREATE TABLE IF NOT EXISTS r2e_sessions (session_id TEXT PRIMARY KEY, \
+              data TEXT NOT NULL, expires_at BIGINT NOT NULL)",
+    select = "SELECT data FROM r2e_sessions WHERE session_id = $1 AND expires_at > $2",
+    upsert = "INSERT INTO r2e_sessions (session_id, data, expires_at) VALUES ($1, $2, $3) \
+              ON CONFLICT (session_id) DO UPDATE SET data = EXCLUDED.data, \
+              expires_at = EXCLUDED.expires_at",
+    delete = "DELETE FROM r2e_sessions WHERE session_id = $1",
+);
+impl_session_store!(
+    "mysql",
+    sqlx::MySql,
+    create = "CREATE TABLE IF NOT EXISTS r2e_sessions (session_id VARCHAR(64) PRIMARY KEY, \
+              data LONGTEXT NOT NULL, expires_at BIGINT NOT NULL)",
+    select = "SELECT data FROM r2e_sessions WHERE session_id = ? AND expires_at > ?",
+    upsert = "INSERT INTO r2e_sessions (session_id, data, expires_at) VALUES (?, ?, ?) \
+              ON DUPLICATE KEY UPDATE data = VALUES(data), expires_at = VALUES(expires_at)",
+    delete = "DELETE FROM r2e_sessions WHERE session_id = ?",
+);
+impl_session_store!(
+    "sqlite",
+    sqlx::Sqlite,
+    create = "CREATE TABLE IF NOT EXISTS r2e_sessions (session_id TEXT PRIMARY KEY, \
+              data TEXT NOT NULL, expires_at INTEGER NOT NULL)",
+    select = "SELECT data FROM r2e_sessions WHERE session_id = $1 AND expires_at > $2",
+    upsert = "INSERT INTO r2e_sessions (session_id, data, expires_at) VALUES ($1, $2, $3) \
+              ON CONFLICT (session_id) DO UPDATE SET data = excluded.data, \
+              expires_at = excluded.expires_at",
+    delete = "DELETE FROM r2e_sessions WHERE session_id = $1",
+);
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use sqlx::Sqlite;
+
+    #[tokio::test]
+    async fn saves_replaces_and_deletes_sessions() {
+        let pool = Pool::<Sqlite>::connect("sqlite::memory:").await.unwrap();
+        let store = SqlxSessionStore::new(pool);
+        store.create_table().await.unwrap();
+        let ttl = Duration::from_secs(60);
+
+        assert_eq!(store.load("abc").await.unwrap(), None);
+
+        let mut record = SessionRecord::new(1_000);
+        store.save("abc", &record, ttl).await.unwrap();
+        record
+            .data
+            .insert("cart".into(), r2e_core::serde_json::json!(["apple"]));
+        record.accessed_at = 2_000;
+        store.save("abc", &record, ttl).await.unwrap();
+        assert_eq!(store.load("abc").await.unwrap(), Some(record));
+
+        store.delete("abc").await.unwrap();
+        assert_eq!(store.load("abc").await.unwrap(), None);
+
+        // Expired rows are not loaded.
+        store
+            .save("old", &SessionRecord::new(1_000), Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(store.load("old").await.unwrap(), None);
+    }
+}
@@ -0,0 +1,240 @@
+//! `#[transactional]` support: [`Pool<DB>`] as a task-bound transaction
+//! manager.
+//!
+//! `isolation` and `read_only` are sent in the `BEGIN` statement on
+//! PostgreSQL and MySQL. SQLite transactions are always serializable and the
+//! options are ignored there.
+
+use r2e_core::transaction::{self, TransactionManager, TxError, TxGuard, TxOptions};
+use sqlx::{AssertSqlSafe, Database, Pool};
+
+use crate::SqlxTx;
+
+/// Marker selecting this crate's [`TransactionManager`] impl; inferred by
+/// `#[transactional]`.
+pub struct Sqlx;
+
+impl<DB: Database> TransactionManager<Sqlx> for Pool<DB> {
+    type Tx = SqlxTx<'static, DB>;
+
+    async fn begin(&self, options: TxOptions) -> Result<Self::Tx, TxError> {
+        begin(self, &options).await.map_err(TxError::backend)
+    }
+
+    async fn commit(&self, tx: Self::Tx) -> Result<(), TxError> {
+        commit(tx).await
+    }
+
+    async fn rollback(&self, tx: Self::Tx) -> Result<(), TxError> {
+        rollback(tx).await
+    }
+}
+
+/// Begin a transaction on `pool`, sending the options in `BEGIN` where the
+/// database supports them.
+pub(crate) async fn begin<DB: Database>(
+    pool: &Pool<DB>,
+    options: &TxOptions,
+) -> Result<SqlxTx<'static, DB>, sqlx::Error> {
+    let transaction = match begin_statement(DB::NAME, options) {
+        Some(statement) => pool.begin_with(AssertSqlSafe(statement)).await,
+        None => pool.begin().await,
+    }?;
+    Ok(SqlxTx {
+        inner: Some(transaction),
+    })
+}
+
+pub(crate) async fn commit<DB: Database>(mut tx: SqlxTx<'static, DB>) -> Result<(), TxError> {
+    match tx.inner.take() {
+        Some(transaction) => transaction.commit().await.map_err(TxError::backend),
+        None => Ok(()),
+    }
+}
+
+pub(crate) async fn rollback<DB: Database>(mut tx: SqlxTx<'static, DB>) -> Result<(), TxError> {
+    match tx.inner.take() {
+        Some(transaction) => transaction.rollback().await.map_err(TxError::backend),
+        None => Ok(()),
+    }
+}
+
+/// Custom `BEGIN` for the options, or `None` for the driver's default.
+fn begin_statement(database: &str, options: &TxOptions) -> Option<String> {
+    if options.isolation.is_none() && !options.read_only {
+        return None;
+    }
+    let isolation = options
+        .isolation
+        .map(|level| format!(" ISOLATION LEVEL {}", level.as_sql()));
+    let access = if options.read_only { " READ ONLY" } else { "" };
+    match database {
+        "PostgreSQL" => Some(format!("BEGIN{}{access}", isolation.unwrap_or_default())),
+        // MySQL only takes the access mode on `START TRANSACTION`; the
+        // isolation level is set for the next transaction just before it.
+        "MySQL" => Some(match isolation {
+            Some(isolation) => format!("SET TRANSACTION{isolation}; START TRANSACTION{access}"),
+            None => format!("START TRANSACTION{access}"),
+        }),
+        _ => None,
+    }
+}
+
+/// The transaction bound to the current task by `#[transactional]`.
+///
+/// Drop the guard before calling another method that uses the transaction.
+pub async fn current_tx<DB: Database>() -> Result<TxGuard<SqlxTx<'static, DB>>, TxError> {
+    transaction::current::<SqlxTx<'static, DB>>()
+        .await
+        .ok_or(TxError::NoTransaction(std::any::type_name::<
+            SqlxTx<'static, DB>,
+        >()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use r2e_core::transaction::Isolation;
+
+    #[test]
+    fn begin_statement_per_database() {
+        let options = TxOptions::new()
+            .isolation(Isolation::Serializable)
+            .read_only(true);
+        assert_eq!(
+            begin_statement("PostgreSQL", &options).as_deref(),
+            Some("BEGIN ISOLATION LEVEL SERIALIZABLE READ ONLY")
+        );
+        assert_eq!(
+            begin_statement("MySQL", &options).as_deref(),
+            Some("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE; START TRANSACTION READ ONLY")
+        );
+        assert_eq!(begin_statement("SQLite", &options), None);
+        assert_eq!(begin_statement("PostgreSQL", &TxOptions::new()), None);
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod sqlite_tests {
+    use super::*;
+    use r2e_core::transaction::{transactional, Propagation};
+    use sqlx::{sqlite::SqliteConnectOptions, sqlite::SqlitePoolOptions, Row, Sqlite, SqlitePool};
+
+    // A file database: `requires_new` needs a second connection to the same data.
+    async fn pool_with_table(name: &str) -> SqlitePool {
+        let path = std::env::temp_dir().join(format!("r2e-tx-{}-{name}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(2)
+            .connect_with(
+                SqliteConnectOptions::new()
+                    .filename(path)
+                    .create_if_missing(true),
+            )
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE items(id INTEGER PRIMARY KEY, name TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool
+    }
+
+    async fn insert(name: &str) -> Result<(), TxError> {
+        let mut tx = current_tx::<Sqlite>().await?;
+        sqlx::query("INSERT INTO items(name) VALUES (?)")
+            .bind(name)
+            .execute(tx.connection())
+            .await
+            .map_err(TxError::backend)?;
+        Ok(())
+    }
+
+    async fn count(pool: &SqlitePool) -> i64 {
+        sqlx::query("SELECT COUNT(*) AS count FROM items")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+            .get("count")
+    }
+
+    #[tokio::test]
+    async fn nested_call_joins_and_commits_on_ok() {
+        let pool = pool_with_table("join").await;
+        let result: Result<(), TxError> = transactional(&pool, TxOptions::new(), async {
+            insert("outer").await?;
+            transactional(&pool, TxOptions::new(), insert("inner")).await
+        })
+        .await;
+        assert_eq!(result, Ok(()));
+        assert_eq!(count(&pool).await, 2);
+    }
+
+    #[tokio::test]
+    async fn rolls_back_on_err() {
+        let pool = pool_with_table("err").await;
+        let result: Result<(), TxError> = transactional(&pool, TxOptions::new(), async {
+            insert("doomed").await?;
+            Err(TxError::backend("business failure"))
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(count(&pool).await, 0);
+    }
+
+    #[tokio::test]
+    async fn failed_participant_marks_rollback_only() {
+        let pool = pool_with_table("rollback_only").await;
+        let result: Result<(), TxError> = transactional(&pool, TxOptions::new(), async {
+            insert("outer").await?;
+            let inner: Result<(), TxError> = transactional(&pool, TxOptions::new(), async {
+                Err(TxError::backend("inner failure"))
+            })
+            .await;
+            assert!(inner.is_err());
+            Ok(())
+        })
+        .await;
+        assert_eq!(result, Err(TxError::RollbackOnly));
+        assert_eq!(count(&pool).await, 0);
+    }
+
+    #[tokio::test]
+    async fn requires_new_commits_independently() {
+        let pool = pool_with_table("requires_new").await;
+        let requires_new = TxOptions::new().propagation(Propagation::RequiresNew);
+        let result: Result<(), TxError> = transactional(&pool, TxOptions::new(), async {
+            transactional(&pool, requires_new, insert("audit")).await?;
+            insert("outer").await?;
+            Err(TxError::backend("outer failure"))
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(count(&pool).await, 1);
+    }
+
+    #[tokio::test]
+    async fn mandatory_requires_an_active_transaction() {
+        let pool = pool_with_table("mandatory").await;
+        let mandatory = TxOptions::new().propagation(Propagation::Mandatory);
+        let result = transactional(&pool, mandatory, insert("never")).await;
+        assert!(matches!(result, Err(TxError::NoTransaction(_))));
+        assert!(current_tx::<Sqlite>().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn panic_rolls_back() {
+        let pool = pool_with_table("panic").await;
+        let task_pool = pool.clone();
+        let joined = tokio::spawn(async move {
+            transactional::<_, _, _, ()>(&task_pool, TxOptions::new(), async {
+                insert("doomed").await.unwrap();
+                panic!("handler panicked");
+            })
+            .await
+        })
+        .await;
+        assert!(joined.unwrap_err().is_panic());
+        assert_eq!(count(&pool).await, 0);
+    }
+}
@@ -1,16 +1,19 @@
+use r2e_core::transaction::TxOptions;
 use r2e_core::{
     BeanLookup, HttpError, ManagedContext, ManagedErr, ManagedOutcome, ManagedResource,
 };
 use sqlx::{Database, Pool, Transaction};
 use std::ops::{Deref, DerefMut};
 
+use crate::ReadReplicas;
+
 /// Request-scoped SQLx transaction managed by R2E.
 ///
 /// A `Pool<DB>` must be registered as a bean. Successful HTTP responses
 /// (status below 400) commit; error responses roll back explicitly. Dropping
 /// an unfinished transaction provides SQLx's rollback fallback.
 pub struct SqlxTx<'a, DB: Database> {
-    inner: Option<Transaction<'a, DB>>,
+    pub(crate) inner: Option<Transaction<'a, DB>>,
 }
 
 /// Backward-compatible short name used in handler signatures.
@@ -90,6 +93,66 @@ where
     }
 }
 
+/// Request-scoped read-only SQLx transaction, served from a read replica.
+///
+/// With a [`ReadReplicas<DB>`] bean — provided by the
+/// [`DataSource`](crate::DataSource) plugin — the transaction begins on the
+/// next available replica, or on the primary when none is left or inside
+/// [`on_primary`](crate::on_primary). Without one it begins on the
+/// `Pool<DB>` bean. PostgreSQL and MySQL run it `READ ONLY`. Derefs to
+/// [`SqlxTx`], and finishes like it.
+pub struct ReadTx<'a, DB: Database> {
+    tx: SqlxTx<'a, DB>,
+}
+
+impl<'a, DB: Database> Deref for ReadTx<'a, DB> {
+    type Target = SqlxTx<'a, DB>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tx
+    }
+}
+
+impl<'a, DB: Database> DerefMut for ReadTx<'a, DB> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.tx
+    }
+}
+
+impl<S, DB> ManagedResource<S> for ReadTx<'static, DB>
+where
+    DB: Database,
+    S: BeanLookup + Send + Sync,
+{
+    type Error = ManagedErr<HttpError>;
+
+    async fn acquire(context: ManagedContext<'_, S>) -> Result<Self, Self::Error> {
+        let begun = if let Some(replicas) = context.state.bean::<ReadReplicas<DB>>() {
+            replicas.begin_read().await
+        } else if let Some(pool) = context.state.bean::<Pool<DB>>() {
+            crate::transactional::begin(&pool, &TxOptions::new().read_only(true)).await
+        } else {
+            return Err(ManagedErr(HttpError::internal(format!(
+                "database pool bean `{}` not found for {}::{}; install the DataSource plugin \
+                 or call .provide(pool) before build_state()",
+                std::any::type_name::<Pool<DB>>(),
+                context.controller,
+                context.handler,
+            ))));
+        };
+        let tx = begun.map_err(|error| ManagedErr(HttpError::internal(error.to_string())))?;
+        Ok(Self { tx })
+    }
+
+    async fn finalize(&mut self, outcome: &ManagedOutcome) -> Result<(), Self::Error> {
+        <SqlxTx<'static, DB> as ManagedResource<S>>::finalize(&mut self.tx, outcome).await
+    }
+
+    fn abort(&mut self) {
+        <SqlxTx<'static, DB> as ManagedResource<S>>::abort(&mut self.tx);
+    }
+}
+
 #[cfg(all(test, feature = "sqlite"))]
 mod tests {
     use super::*;
@@ -2,8 +2,10 @@ use std::sync::Arc;
 
 use iggy::prelude::*;
 
-use r2e_events::backend::{instance_id, reply_topic, BackendState, PendingRequests, TopicRegistry};
-use r2e_events::{DlqPublisher, EventBusError};
+use r2e_events::backend::{
+    instance_id, reply_topic, BackendState, DelayQueue, PendingRequests, TopicRegistry,
+};
+use r2e_events::{DelayStore, DlqPublisher, EventBusError, FileDelayStore};
 
 use crate::bus::IggyEventBus;
 use crate::config::{IggyConfig, Transport};
@@ -24,6 +26,7 @@ use crate::inner::IggyInner;
 pub struct IggyEventBusBuilder {
     config: IggyConfig,
     topic_registry: TopicRegistry,
+    delay_store: Option<Arc<dyn DelayStore>>,
 }
 
 impl IggyEventBusBuilder {
@@ -31,6 +34,7 @@ impl IggyEventBusBuilder {
         Self {
             config,
             topic_registry: TopicRegistry::default(),
+            delay_store: None,
         }
     }
 
@@ -45,6 +49,17 @@ impl IggyEventBusBuilder {
         self.topic::<E>(E::topic())
     }
 
+    /// Persist delayed emits (`emit_after` / `emit_at`) in `store` so they
+    /// survive a restart. Defaults to a
+    /// [per-instance `FileDelayStore`](FileDelayStore::per_instance) under
+    /// [`IggyConfig::delay_store_dir`]; pass an
+    /// [`InMemoryDelayStore`](r2e_events::InMemoryDelayStore) to opt out of
+    /// persistence.
+    pub fn delay_store(mut self, store: impl DelayStore) -> Self {
+        self.delay_store = Some(Arc::new(store));
+        self
+    }
+
     /// Connect to the Iggy server and return a ready-to-use [`IggyEventBus`].
     pub async fn connect(self) -> Result<IggyEventBus, EventBusError> {
         let client = build_client(&self.config).map_err(map_iggy_error)?;
@@ -80,6 +95,10 @@ impl IggyEventBusBuilder {
         let stream_id = Identifier::named(&self.config.stream_name).map_err(map_iggy_error)?;
 
         let client = Arc::new(client);
+        let delay_store: Arc<dyn DelayStore> = match self.delay_store {
+            Some(store) => store,
+            None => Arc::new(FileDelayStore::per_instance(self.config.delay_store_dir())),
+        };
         let inner = Arc::new_cyclic(|weak: &std::sync::Weak<IggyInner>| {
             let weak = weak.clone();
             let dlq: DlqPublisher = Arc::new(move |topic, payload, metadata| {
@@ -94,10 +113,11 @@ impl IggyEventBusBuilder {
             IggyInner {
                 config: self.config,
                 client,
-                state: Arc::new(BackendState::with_dlq_publisher(
-                    self.topic_registry,
-                    Some(dlq),
-                )),
+                state: Arc::new(
+                    BackendState::with_dlq_publisher(self.topic_registry, Some(dlq.clone()))
+                        .with_messaging_system("iggy"),
+                ),
+                delay_queue: DelayQueue::new(delay_store, dlq),
                 instance_id: instance,
                 reply_topic: reply_topic_name,
                 stream_id,
@@ -108,6 +128,9 @@ impl IggyEventBusBuilder {
             }
         });
 
+        // Re-arm delayed emits a previous process left in the store.
+        inner.delay_queue.recover().await?;
+
         Ok(IggyEventBus { inner })
     }
 }
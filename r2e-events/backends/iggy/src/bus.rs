@@ -6,6 +6,7 @@ use std::collections::BTreeMap;
 use std::future::Future;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::SystemTime;
 
 use iggy::prelude::*;
 use serde::de::DeserializeOwned;
@@ -18,9 +19,10 @@ use r2e_events::backend::{
     Handler, ReplyHeaders, WatermarkTracker, COMPLETION_CHANNEL_CAPACITY, COMPLETION_DRAIN_TIMEOUT,
     HEADER_PARTITION_KEY, HEADER_TIMESTAMP,
 };
+use r2e_events::propagation;
 use r2e_events::{
-    EmitReceipt, EventBus, EventBusError, EventEnvelope, EventMetadata, HandlerResult,
-    RequestOptions, ResponderHandle, SubscriptionHandle,
+    DelayMetrics, EmitReceipt, EventBus, EventBusError, EventEnvelope, EventMetadata,
+    HandlerResult, RequestOptions, ResponderHandle, ScheduledEmit, SubscriptionHandle,
 };
 
 use crate::builder::IggyEventBusBuilder;
@@ -52,6 +54,11 @@ impl IggyEventBus {
         IggyEventBusBuilder::new(config)
     }
 
+    /// Counters for delayed emits (`emit_after` / `emit_at`).
+    pub fn delay_metrics(&self) -> &DelayMetrics {
+        self.inner.delay_queue.metrics()
+    }
+
     /// Resolve the topic name for an event type.
     fn resolve_topic<E: 'static>(&self) -> Arc<str> {
         self.inner.state.resolve_topic::<E>()
@@ -312,6 +319,20 @@ impl EventBus for IggyEventBus {
         }
     }
 
+    fn configure_ordering<E: 'static>(
+        &self,
+        handler_id: r2e_events::SubscriptionId,
+        ordered_by: r2e_events::OrderedBy,
+    ) -> impl Future<Output = ()> + Send {
+        let inner = self.inner.clone();
+        async move {
+            inner
+                .state
+                .configure_ordering(handler_id.0, ordered_by, Some(TypeId::of::<E>()))
+                .await;
+        }
+    }
+
     fn subscribe<E, F, Fut>(
         &self,
         handler: F,
@@ -424,7 +445,7 @@ impl EventBus for IggyEventBus {
             let payload = serde_json::to_vec(&event)
                 .map_err(|e| EventBusError::Serialization(e.to_string()))?;
             let topic_name = bus.resolve_topic::<E>();
-            let metadata = EventMetadata::new();
+            let metadata = propagation::inject(EventMetadata::new());
             bus.publish(&topic_name, payload, &metadata).await
         }
     }
@@ -440,6 +461,7 @@ impl EventBus for IggyEventBus {
         let bus = self.clone();
         async move {
             bus.inner.state.check_shutdown()?;
+            let metadata = propagation::inject(metadata);
 
             let payload = serde_json::to_vec(&event)
                 .map_err(|e| EventBusError::Serialization(e.to_string()))?;
@@ -462,7 +484,7 @@ impl EventBus for IggyEventBus {
             let payload = serde_json::to_vec(&event)
                 .map_err(|e| EventBusError::Serialization(e.to_string()))?;
             let topic_name = bus.resolve_topic::<E>();
-            let metadata = EventMetadata::new();
+            let metadata = propagation::inject(EventMetadata::new());
 
             let headers = Self::build_headers(&metadata)?;
             bus.ensure_topic(&topic_name).await?;
@@ -507,6 +529,7 @@ impl EventBus for IggyEventBus {
         let bus = self.clone();
         async move {
             bus.inner.state.check_shutdown()?;
+            let metadata = propagation::inject(metadata);
 
             let payload = serde_json::to_vec(&event)
                 .map_err(|e| EventBusError::Serialization(e.to_string()))?;
@@ -544,6 +567,36 @@ impl EventBus for IggyEventBus {
         }
     }
 
+    /// Schedule a delayed publication through the persistent delay queue.
+    ///
+    /// Iggy has no per-message delivery delay, so the serialized event is
+    /// written to the builder's [`DelayStore`](r2e_events::DelayStore) and
+    /// published when due; entries left behind by a previous process are
+    /// re-armed on `connect()`.
+    fn emit_at_with<E>(
+        &self,
+        event: E,
+        at: SystemTime,
+        metadata: EventMetadata,
+    ) -> impl Future<Output = Result<ScheduledEmit, EventBusError>> + Send
+    where
+        E: Serialize + Send + Sync + 'static,
+    {
+        let bus = self.clone();
+        async move {
+            bus.inner.state.check_shutdown()?;
+            let metadata = propagation::inject(metadata);
+
+            let payload = serde_json::to_vec(&event)
+                .map_err(|e| EventBusError::Serialization(e.to_string()))?;
+            let topic_name = bus.resolve_topic::<E>();
+            bus.inner
+                .delay_queue
+                .schedule(&topic_name, payload, metadata, at)
+                .await
+        }
+    }
+
     fn request_with<Req, Resp>(
         &self,
         req: Req,
@@ -579,7 +632,7 @@ impl EventBus for IggyEventBus {
             // request metadata's own correlation_id (a user string) is left
             // untouched; the u128 request-reply id travels via the reply headers
             // in their own dedicated header slot.
-            let metadata = options.metadata.unwrap_or_default();
+            let metadata = propagation::inject(options.metadata.unwrap_or_default());
             let pairs = encode_metadata(&metadata).chain(encode_reply_headers(
                 request_id,
                 Some(&reply_to),
@@ -695,6 +748,9 @@ impl EventBus for IggyEventBus {
             // Cancel all pollers
             inner.state.cancel_all_pollers();
 
+            // Stop delayed-emit timers; pending entries stay in the delay store.
+            inner.delay_queue.shutdown();
+
             // Cancel the request-reply pollers and fail any pending requests:
             // waking the shutdown notifier makes requesters awaiting a reply
             // return `Shutdown` instead of waiting out their timeout.
@@ -747,6 +803,10 @@ async fn run_poller_inner(
     topic_name: &str,
     cancel: &CancellationToken,
 ) {
+    // A new session may receive messages another consumer nacked; lift the
+    // ordering blocks of the previous one (see `r2e_events::ordering`).
+    inner.state.reset_ordering();
+
     let consumer_result = inner.client.consumer_group(
         &inner.config.consumer_group,
         &inner.config.stream_name,
@@ -1,5 +1,8 @@
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+use r2e_events::DEFAULT_DELAY_DIR;
+
 /// Transport protocol for connecting to the Iggy server.
 #[derive(Clone, Debug, Default)]
 pub enum Transport {
@@ -40,6 +43,9 @@ pub struct IggyConfig {
     /// means horizontal scale-out adds zero consumer parallelism. Set this to at
     /// least the expected number of consumer instances.
     pub default_partitions: u32,
+    /// Parent directory of the per-instance file delay store used when the bus
+    /// builder is given no `delay_store`. Default: `delayed-events/iggy-{consumer_group}`.
+    pub delay_dir: Option<PathBuf>,
     /// Whether to automatically reconnect when the consumer disconnects (default: true).
     pub reconnect: bool,
     /// Maximum backoff between reconnection attempts (default: 60s).
@@ -59,6 +65,7 @@ impl Default for IggyConfig {
             poll_batch_size: 1000,
             auto_create: true,
             default_partitions: 3,
+            delay_dir: None,
             reconnect: true,
             reconnect_max_backoff: Duration::from_secs(60),
         }
@@ -70,6 +77,15 @@ impl IggyConfig {
     pub fn builder() -> IggyConfigBuilder {
         IggyConfigBuilder::default()
     }
+
+    /// Parent directory of the default delay store: `delay_dir`, or a
+    /// subdirectory of [`DEFAULT_DELAY_DIR`] named after this bus. Each running
+    /// instance keeps its entries in its own slot below it.
+    pub fn delay_store_dir(&self) -> PathBuf {
+        self.delay_dir.clone().unwrap_or_else(|| {
+            Path::new(DEFAULT_DELAY_DIR).join(format!("iggy-{}", self.consumer_group))
+        })
+    }
 }
 
 /// Builder for [`IggyConfig`].
@@ -139,6 +155,11 @@ impl IggyConfigBuilder {
         self
     }
 
+    pub fn delay_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.config.delay_dir = Some(dir.into());
+        self
+    }
+
     pub fn build(self) -> IggyConfig {
         self.config
     }
@@ -4,7 +4,7 @@ use std::sync::{Arc, Mutex, RwLock};
 use iggy::prelude::{Identifier, IggyClient};
 use tokio_util::sync::CancellationToken;
 
-use r2e_events::backend::{BackendState, PendingRequests};
+use r2e_events::backend::{BackendState, DelayQueue, PendingRequests};
 
 use crate::config::IggyConfig;
 
@@ -13,6 +13,8 @@ pub(crate) struct IggyInner {
     pub config: IggyConfig,
     pub client: Arc<IggyClient>,
     pub state: Arc<BackendState>,
+    /// Persistent queue behind `emit_after` / `emit_at`.
+    pub delay_queue: DelayQueue,
     /// Per-bus-instance nonce (minted in the builder). Distinguishes two bus
     /// instances sharing a config within one process so their reply topics and
     /// standalone reply-consumer names never collide.
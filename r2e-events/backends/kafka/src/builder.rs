@@ -5,8 +5,10 @@ use rdkafka::admin::{AdminClient, AdminOptions, NewTopic, TopicReplication};
 use rdkafka::client::DefaultClientContext;
 use rdkafka::producer::FutureProducer;
 
-use r2e_events::backend::{instance_id, reply_topic, BackendState, PendingRequests, TopicRegistry};
-use r2e_events::{DlqPublisher, EventBusError};
+use r2e_events::backend::{
+    instance_id, reply_topic, BackendState, DelayQueue, PendingRequests, TopicRegistry,
+};
+use r2e_events::{DelayStore, DlqPublisher, EventBusError, FileDelayStore};
 
 use crate::bus::KafkaEventBus;
 use crate::config::KafkaConfig;
@@ -31,6 +33,7 @@ pub(crate) const REPLY_TOPIC_RETENTION_MS: &str = "300000";
 pub struct KafkaEventBusBuilder {
     config: KafkaConfig,
     topic_registry: TopicRegistry,
+    delay_store: Option<Arc<dyn DelayStore>>,
 }
 
 impl KafkaEventBusBuilder {
@@ -38,6 +41,7 @@ impl KafkaEventBusBuilder {
         Self {
             config,
             topic_registry: TopicRegistry::default(),
+            delay_store: None,
         }
     }
 
@@ -52,6 +56,17 @@ impl KafkaEventBusBuilder {
         self.topic::<E>(E::topic())
     }
 
+    /// Persist delayed emits (`emit_after` / `emit_at`) in `store` so they
+    /// survive a restart. Defaults to a
+    /// [per-instance `FileDelayStore`](FileDelayStore::per_instance) under
+    /// [`KafkaConfig::delay_store_dir`]; pass an
+    /// [`InMemoryDelayStore`](r2e_events::InMemoryDelayStore) to opt out of
+    /// persistence.
+    pub fn delay_store(mut self, store: impl DelayStore) -> Self {
+        self.delay_store = Some(Arc::new(store));
+        self
+    }
+
     /// Connect to the Kafka cluster and return a ready-to-use [`KafkaEventBus`].
     pub async fn connect(self) -> Result<KafkaEventBus, EventBusError> {
         let producer: FutureProducer = self
@@ -66,6 +81,10 @@ impl KafkaEventBusBuilder {
         let instance_id = instance_id();
         let reply_topic = reply_topic(&self.config.group_id, instance_id);
 
+        let delay_store: Arc<dyn DelayStore> = match self.delay_store {
+            Some(store) => store,
+            None => Arc::new(FileDelayStore::per_instance(self.config.delay_store_dir())),
+        };
         let inner = Arc::new_cyclic(|weak: &std::sync::Weak<KafkaInner>| {
             let weak = weak.clone();
             let dlq: DlqPublisher = Arc::new(move |topic, payload, metadata| {
@@ -80,10 +99,11 @@ impl KafkaEventBusBuilder {
             KafkaInner {
                 config: self.config,
                 producer,
-                state: Arc::new(BackendState::with_dlq_publisher(
-                    self.topic_registry,
-                    Some(dlq),
-                )),
+                state: Arc::new(
+                    BackendState::with_dlq_publisher(self.topic_registry, Some(dlq.clone()))
+                        .with_messaging_system("kafka"),
+                ),
+                delay_queue: DelayQueue::new(delay_store, dlq),
                 pending: Arc::new(PendingRequests::new()),
                 reply_consumer: tokio::sync::OnceCell::new(),
                 responder_cancels: std::sync::Mutex::new(std::collections::HashMap::new()),
@@ -93,6 +113,9 @@ impl KafkaEventBusBuilder {
             }
         });
 
+        // Re-arm delayed emits a previous process left in the store.
+        inner.delay_queue.recover().await?;
+
         Ok(KafkaEventBus { inner })
     }
 }
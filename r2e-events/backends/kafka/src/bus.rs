@@ -6,7 +6,7 @@ use std::collections::HashMap;
 use std::future::Future;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 use rdkafka::consumer::{
     BaseConsumer, CommitMode, Consumer, ConsumerContext, Rebalance, StreamConsumer,
@@ -24,9 +24,10 @@ use r2e_events::backend::{
     request_topic, spawn_completion_forwarder, DispatchOutcome, Handler, HeaderPair,
     WatermarkTracker, COMPLETION_CHANNEL_CAPACITY, COMPLETION_DRAIN_TIMEOUT,
 };
+use r2e_events::propagation;
 use r2e_events::{
-    EmitReceipt, EventBus, EventBusError, EventEnvelope, EventMetadata, HandlerResult,
-    RequestOptions, ResponderHandle, SubscriptionHandle,
+    DelayMetrics, EmitReceipt, EventBus, EventBusError, EventEnvelope, EventMetadata,
+    HandlerResult, RequestOptions, ResponderHandle, ScheduledEmit, SubscriptionHandle,
 };
 
 use crate::builder::{ensure_topic_exists, KafkaEventBusBuilder, REPLY_TOPIC_RETENTION_MS};
@@ -115,6 +116,9 @@ impl KafkaProgress {
 /// tracker's `stored` guard suppressing re-commits of redelivered offsets.
 pub(crate) struct R2eConsumerContext {
     pub(crate) tracker: SharedTracker,
+    /// Event consumers only: revoked partitions also lift the ordering blocks
+    /// of `ordered_by` handlers — the nacked event may go to another member.
+    pub(crate) ordering: Option<Arc<r2e_events::backend::BackendState>>,
 }
 
 impl ClientContext for R2eConsumerContext {}
@@ -129,6 +133,9 @@ impl ConsumerContext for R2eConsumerContext {
             for elem in tpl.elements() {
                 progress.revoke(elem.partition());
             }
+            if let Some(state) = &self.ordering {
+                state.reset_ordering();
+            }
         }
     }
 
@@ -166,6 +173,11 @@ impl KafkaEventBus {
         KafkaEventBusBuilder::new(config)
     }
 
+    /// Counters for delayed emits (`emit_after` / `emit_at`).
+    pub fn delay_metrics(&self) -> &DelayMetrics {
+        self.inner.delay_queue.metrics()
+    }
+
     /// Resolve the topic name for an event type.
     fn resolve_topic<E: 'static>(&self) -> Arc<str> {
         self.inner.state.resolve_topic::<E>()
@@ -379,6 +391,20 @@ impl EventBus for KafkaEventBus {
         }
     }
 
+    fn configure_ordering<E: 'static>(
+        &self,
+        handler_id: r2e_events::SubscriptionId,
+        ordered_by: r2e_events::OrderedBy,
+    ) -> impl Future<Output = ()> + Send {
+        let inner = self.inner.clone();
+        async move {
+            inner
+                .state
+                .configure_ordering(handler_id.0, ordered_by, Some(TypeId::of::<E>()))
+                .await;
+        }
+    }
+
     fn subscribe<E, F, Fut>(
         &self,
         handler: F,
@@ -491,7 +517,7 @@ impl EventBus for KafkaEventBus {
             let payload = serde_json::to_vec(&event)
                 .map_err(|e| EventBusError::Serialization(e.to_string()))?;
             let topic_name = bus.resolve_topic::<E>();
-            let metadata = EventMetadata::new();
+            let metadata = propagation::inject(EventMetadata::new());
             bus.publish(&topic_name, payload, &metadata).await
         }
     }
@@ -507,6 +533,7 @@ impl EventBus for KafkaEventBus {
         let bus = self.clone();
         async move {
             bus.inner.state.check_shutdown()?;
+            let metadata = propagation::inject(metadata);
 
             let payload = serde_json::to_vec(&event)
                 .map_err(|e| EventBusError::Serialization(e.to_string()))?;
@@ -529,7 +556,7 @@ impl EventBus for KafkaEventBus {
             let payload = serde_json::to_vec(&event)
                 .map_err(|e| EventBusError::Serialization(e.to_string()))?;
             let topic_name = bus.resolve_topic::<E>();
-            let metadata = EventMetadata::new();
+            let metadata = propagation::inject(EventMetadata::new());
 
             bus.ensure_topic(&topic_name).await?;
             let pairs = encode_metadata(&metadata);
@@ -554,6 +581,7 @@ impl EventBus for KafkaEventBus {
         let bus = self.clone();
         async move {
             bus.inner.state.check_shutdown()?;
+            let metadata = propagation::inject(metadata);
 
             let payload = serde_json::to_vec(&event)
                 .map_err(|e| EventBusError::Serialization(e.to_string()))?;
@@ -571,6 +599,36 @@ impl EventBus for KafkaEventBus {
         }
     }
 
+    /// Schedule a delayed publication through the persistent delay queue.
+    ///
+    /// Kafka has no per-message delivery delay, so the serialized event is
+    /// written to the builder's [`DelayStore`](r2e_events::DelayStore) and
+    /// published when due; entries left behind by a previous process are
+    /// re-armed on `connect()`.
+    fn emit_at_with<E>(
+        &self,
+        event: E,
+        at: SystemTime,
+        metadata: EventMetadata,
+    ) -> impl Future<Output = Result<ScheduledEmit, EventBusError>> + Send
+    where
+        E: Serialize + Send + Sync + 'static,
+    {
+        let bus = self.clone();
+        async move {
+            bus.inner.state.check_shutdown()?;
+            let metadata = propagation::inject(metadata);
+
+            let payload = serde_json::to_vec(&event)
+                .map_err(|e| EventBusError::Serialization(e.to_string()))?;
+            let topic_name = bus.resolve_topic::<E>();
+            bus.inner
+                .delay_queue
+                .schedule(&topic_name, payload, metadata, at)
+                .await
+        }
+    }
+
     fn request_with<Req, Resp>(
         &self,
         req: Req,
@@ -597,7 +655,7 @@ impl EventBus for KafkaEventBus {
 
             let (correlation_id, guard, rx) = bus.inner.pending.register();
 
-            let metadata = options.metadata.unwrap_or_default();
+            let metadata = propagation::inject(options.metadata.unwrap_or_default());
             bus.publish_request(
                 &request_topic_name,
                 payload,
@@ -712,6 +770,9 @@ impl EventBus for KafkaEventBus {
 
             inner.state.cancel_all_pollers();
 
+            // Stop delayed-emit timers; pending entries stay in the delay store.
+            inner.delay_queue.shutdown();
+
             // Stop the request-reply consumers and fail in-flight requesters so
             // they return `Shutdown` instead of blocking to their timeout.
             if let Some(cancel) = inner.reply_consumer.get() {
@@ -775,6 +836,10 @@ async fn run_consumer_inner(
     topic_name: &str,
     cancel: &CancellationToken,
 ) {
+    // A new session may receive messages another consumer nacked; lift the
+    // ordering blocks of the previous one (see `r2e_events::ordering`).
+    inner.state.reset_ordering();
+
     // At-least-once delivery: offsets are stored only after local handlers
     // complete. Handlers run concurrently (pipelined), so completions arrive
     // out of order; the tracker advances each partition's commit watermark over
@@ -785,6 +850,7 @@ async fn run_consumer_inner(
 
     let context = R2eConsumerContext {
         tracker: tracker.clone(),
+        ordering: Some(inner.state.clone()),
     };
     let consumer: StreamConsumer<R2eConsumerContext> = match inner
         .config
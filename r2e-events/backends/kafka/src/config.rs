@@ -1,4 +1,7 @@
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use r2e_events::DEFAULT_DELAY_DIR;
 
 /// Security protocol for connecting to Kafka.
 #[derive(Clone, Debug, Default)]
@@ -120,6 +123,9 @@ pub struct KafkaConfig {
     pub enable_idempotence: bool,
     /// Extra librdkafka configuration overrides.
     pub overrides: HashMap<String, String>,
+    /// Parent directory of the per-instance file delay store used when the bus
+    /// builder is given no `delay_store`. Default: `delayed-events/kafka-{group_id}`.
+    pub delay_dir: Option<PathBuf>,
     /// Whether to automatically reconnect when the consumer disconnects (default: true).
     pub reconnect: bool,
     /// Maximum backoff between reconnection attempts (default: 60s).
@@ -149,6 +155,7 @@ impl Default for KafkaConfig {
             message_timeout_ms: None,
             enable_idempotence: false,
             overrides: HashMap::new(),
+            delay_dir: None,
             reconnect: true,
             reconnect_max_backoff: std::time::Duration::from_secs(60),
         }
@@ -161,6 +168,15 @@ impl KafkaConfig {
         KafkaConfigBuilder::default()
     }
 
+    /// Parent directory of the default delay store: `delay_dir`, or a
+    /// subdirectory of [`DEFAULT_DELAY_DIR`] named after this bus. Each running
+    /// instance keeps its entries in its own slot below it.
+    pub fn delay_store_dir(&self) -> PathBuf {
+        self.delay_dir.clone().unwrap_or_else(|| {
+            Path::new(DEFAULT_DELAY_DIR).join(format!("kafka-{}", self.group_id))
+        })
+    }
+
     /// Build an rdkafka `ClientConfig` for the producer.
     pub(crate) fn to_producer_client_config(&self) -> rdkafka::ClientConfig {
         let mut config = rdkafka::ClientConfig::new();
@@ -400,6 +416,11 @@ impl KafkaConfigBuilder {
         self
     }
 
+    pub fn delay_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.config.delay_dir = Some(dir.into());
+        self
+    }
+
     pub fn build(self) -> KafkaConfig {
         self.config
     }
@@ -6,7 +6,7 @@ use rdkafka::producer::FutureProducer;
 use tokio::sync::OnceCell;
 use tokio_util::sync::CancellationToken;
 
-use r2e_events::backend::{BackendState, PendingRequests};
+use r2e_events::backend::{BackendState, DelayQueue, PendingRequests};
 
 use crate::config::KafkaConfig;
 
@@ -15,6 +15,8 @@ pub(crate) struct KafkaInner {
     pub config: KafkaConfig,
     pub producer: FutureProducer,
     pub state: Arc<BackendState>,
+    /// Persistent queue behind `emit_after` / `emit_at`.
+    pub delay_queue: DelayQueue,
     /// Correlation map for in-flight `request` calls awaiting a reply
     /// (ReplyingKafkaTemplate pattern).
     pub pending: Arc<PendingRequests>,
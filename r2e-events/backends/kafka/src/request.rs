@@ -165,6 +165,7 @@ async fn run_responder_consumer_inner(
 
     let context = R2eConsumerContext {
         tracker: tracker.clone(),
+        ordering: None,
     };
     let consumer: StreamConsumer<R2eConsumerContext> = match cfg.create_with_context(context) {
         Ok(c) => c,
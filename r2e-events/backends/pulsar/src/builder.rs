@@ -4,8 +4,8 @@ use std::sync::Arc;
 use pulsar::{Authentication, Pulsar, TokioExecutor};
 use tokio::sync::Mutex;
 
-use r2e_events::backend::{instance_id, BackendState, PendingRequests, TopicRegistry};
-use r2e_events::{DlqPublisher, EventBusError};
+use r2e_events::backend::{instance_id, BackendState, DelayQueue, PendingRequests, TopicRegistry};
+use r2e_events::{DelayStore, DlqPublisher, EventBusError, FileDelayStore};
 
 use crate::bus::PulsarEventBus;
 use crate::config::PulsarConfig;
@@ -26,6 +26,7 @@ use crate::inner::PulsarInner;
 pub struct PulsarEventBusBuilder {
     config: PulsarConfig,
     topic_registry: TopicRegistry,
+    delay_store: Option<Arc<dyn DelayStore>>,
 }
 
 impl PulsarEventBusBuilder {
@@ -33,6 +34,7 @@ impl PulsarEventBusBuilder {
         Self {
             config,
             topic_registry: TopicRegistry::default(),
+            delay_store: None,
         }
     }
 
@@ -47,6 +49,18 @@ impl PulsarEventBusBuilder {
         self.topic::<E>(E::topic())
     }
 
+    /// Persist delayed emits (`emit_after` / `emit_at`) in `store` so they
+    /// survive a restart. Only used when the subscription type does not
+    /// honour `deliver_at_time`. Defaults to a
+    /// [per-instance `FileDelayStore`](FileDelayStore::per_instance) under
+    /// [`PulsarConfig::delay_store_dir`]; pass an
+    /// [`InMemoryDelayStore`](r2e_events::InMemoryDelayStore) to opt out of
+    /// persistence.
+    pub fn delay_store(mut self, store: impl DelayStore) -> Self {
+        self.delay_store = Some(Arc::new(store));
+        self
+    }
+
     /// Connect to the Pulsar cluster and return a ready-to-use [`PulsarEventBus`].
     pub async fn connect(self) -> Result<PulsarEventBus, EventBusError> {
         let mut builder = Pulsar::builder(&self.config.service_url, TokioExecutor);
@@ -68,6 +82,11 @@ impl PulsarEventBusBuilder {
         // derived from it (and cached) on first request-reply use.
         let instance = instance_id();
 
+        let delay_store: Arc<dyn DelayStore> = match self.delay_store {
+            Some(store) => store,
+            None => Arc::new(FileDelayStore::per_instance(self.config.delay_store_dir())),
+        };
+
         let inner = Arc::new_cyclic(|weak: &std::sync::Weak<PulsarInner>| {
             let weak = weak.clone();
             let dlq: DlqPublisher = Arc::new(move |topic, payload, metadata| {
@@ -83,10 +102,11 @@ impl PulsarEventBusBuilder {
                 config: self.config,
                 pulsar,
                 producers: Mutex::new(HashMap::new()),
-                state: Arc::new(BackendState::with_dlq_publisher(
-                    self.topic_registry,
-                    Some(dlq),
-                )),
+                state: Arc::new(
+                    BackendState::with_dlq_publisher(self.topic_registry, Some(dlq.clone()))
+                        .with_messaging_system("pulsar"),
+                ),
+                delay_queue: DelayQueue::new(delay_store, dlq),
                 full_topics: std::sync::RwLock::new(HashMap::new()),
                 instance_id: instance,
                 reply_topic_full: std::sync::OnceLock::new(),
@@ -96,6 +116,9 @@ impl PulsarEventBusBuilder {
             }
         });
 
+        // Re-arm delayed emits a previous process left in the store.
+        inner.delay_queue.recover().await?;
+
         Ok(PulsarEventBus { inner })
     }
 }
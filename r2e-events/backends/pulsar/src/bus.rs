@@ -6,6 +6,7 @@ use std::collections::HashMap;
 use std::future::Future;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::SystemTime;
 
 use pulsar::consumer::{Consumer, ConsumerOptions, InitialPosition};
 use pulsar::message::proto::command_subscribe::SubType;
@@ -22,13 +23,15 @@ use r2e_events::backend::{
     DispatchOutcome, Handler, COMPLETION_CHANNEL_CAPACITY, COMPLETION_DRAIN_TIMEOUT,
     HEADER_PARTITION_KEY,
 };
+use r2e_events::delay::epoch_millis;
+use r2e_events::propagation;
 use r2e_events::{
-    EmitReceipt, EventBus, EventBusError, EventEnvelope, EventMetadata, HandlerResult,
-    RequestOptions, ResponderHandle, SubscriptionHandle,
+    DelayMetrics, EmitReceipt, EventBus, EventBusError, EventEnvelope, EventMetadata,
+    HandlerResult, RequestOptions, ResponderHandle, ScheduledEmit, SubscriptionHandle,
 };
 
 use crate::builder::PulsarEventBusBuilder;
-use crate::config::PulsarConfig;
+use crate::config::{PulsarConfig, SubscriptionType};
 use crate::error::map_pulsar_error;
 use crate::inner::PulsarInner;
 
@@ -50,6 +53,8 @@ const REPLY_SUBSCRIPTION: &str = "r2e-reply";
 /// - `emit` is fan-out publish/subscribe; use `request`/`respond` for
 ///   point-to-point request-reply.
 /// - One event type per topic (the deserializer is registered on first `subscribe`).
+/// - Pulsar honours `deliver_at` only on `Shared` / `KeyShared` subscriptions;
+///   with `Exclusive` / `Failover` delayed emits use the persistent delay queue.
 #[derive(Clone)]
 pub struct PulsarEventBus {
     pub(crate) inner: Arc<PulsarInner>,
@@ -135,12 +140,37 @@ impl PulsarEventBus {
             .collect()
     }
 
+    /// Counters for delayed emits (`emit_after` / `emit_at`).
+    pub fn delay_metrics(&self) -> &DelayMetrics {
+        self.inner.delay_queue.metrics()
+    }
+
+    /// Whether the configured subscription type honours `deliver_at_time`.
+    fn native_delay_supported(&self) -> bool {
+        matches!(
+            self.inner.config.subscription_type,
+            SubscriptionType::Shared | SubscriptionType::KeyShared
+        )
+    }
+
     /// Publish a serialized event to Pulsar.
     pub(crate) async fn publish(
         &self,
         topic_name: &str,
         payload: Vec<u8>,
         metadata: &EventMetadata,
+    ) -> Result<(), EventBusError> {
+        self.publish_at(topic_name, payload, metadata, None).await
+    }
+
+    /// Publish a serialized event, optionally held by the broker until
+    /// `deliver_at_time` (epoch milliseconds).
+    async fn publish_at(
+        &self,
+        topic_name: &str,
+        payload: Vec<u8>,
+        metadata: &EventMetadata,
+        deliver_at_time: Option<i64>,
     ) -> Result<(), EventBusError> {
         let full_topic = self.full_topic(topic_name);
 
@@ -159,7 +189,7 @@ impl PulsarEventBus {
             replicate_to: Vec::new(),
             event_time: None,
             schema_version: None,
-            deliver_at_time: None,
+            deliver_at_time,
         };
 
         // Lock only this topic's producer for the send; other topics proceed in
@@ -329,6 +359,20 @@ impl EventBus for PulsarEventBus {
         }
     }
 
+    fn configure_ordering<E: 'static>(
+        &self,
+        handler_id: r2e_events::SubscriptionId,
+        ordered_by: r2e_events::OrderedBy,
+    ) -> impl Future<Output = ()> + Send {
+        let inner = self.inner.clone();
+        async move {
+            inner
+                .state
+                .configure_ordering(handler_id.0, ordered_by, Some(TypeId::of::<E>()))
+                .await;
+        }
+    }
+
     fn subscribe<E, F, Fut>(
         &self,
         handler: F,
@@ -435,7 +479,7 @@ impl EventBus for PulsarEventBus {
             let payload = serde_json::to_vec(&event)
                 .map_err(|e| EventBusError::Serialization(e.to_string()))?;
             let topic_name = bus.resolve_topic::<E>();
-            let metadata = EventMetadata::new();
+            let metadata = propagation::inject(EventMetadata::new());
             bus.publish(&topic_name, payload, &metadata).await
         }
     }
@@ -451,6 +495,7 @@ impl EventBus for PulsarEventBus {
         let bus = self.clone();
         async move {
             bus.inner.state.check_shutdown()?;
+            let metadata = propagation::inject(metadata);
 
             let payload = serde_json::to_vec(&event)
                 .map_err(|e| EventBusError::Serialization(e.to_string()))?;
@@ -473,7 +518,7 @@ impl EventBus for PulsarEventBus {
             let payload = serde_json::to_vec(&event)
                 .map_err(|e| EventBusError::Serialization(e.to_string()))?;
             let topic_name = bus.resolve_topic::<E>();
-            let metadata = EventMetadata::new();
+            let metadata = propagation::inject(EventMetadata::new());
             bus.publish_nowait(&topic_name, payload, &metadata).await
         }
     }
@@ -489,6 +534,7 @@ impl EventBus for PulsarEventBus {
         let bus = self.clone();
         async move {
             bus.inner.state.check_shutdown()?;
+            let metadata = propagation::inject(metadata);
 
             let payload = serde_json::to_vec(&event)
                 .map_err(|e| EventBusError::Serialization(e.to_string()))?;
@@ -497,6 +543,53 @@ impl EventBus for PulsarEventBus {
         }
     }
 
+    /// Schedule a delayed publication.
+    ///
+    /// On `Shared` / `KeyShared` subscriptions the message is published now
+    /// with `deliver_at_time` and the broker holds it; the returned handle is
+    /// not cancellable. Otherwise the event goes through the persistent delay
+    /// queue and can be cancelled.
+    fn emit_at_with<E>(
+        &self,
+        event: E,
+        at: SystemTime,
+        metadata: EventMetadata,
+    ) -> impl Future<Output = Result<ScheduledEmit, EventBusError>> + Send
+    where
+        E: Serialize + Send + Sync + 'static,
+    {
+        let bus = self.clone();
+        async move {
+            bus.inner.state.check_shutdown()?;
+            let metadata = propagation::inject(metadata);
+
+            let payload = serde_json::to_vec(&event)
+                .map_err(|e| EventBusError::Serialization(e.to_string()))?;
+            let topic_name = bus.resolve_topic::<E>();
+
+            if !bus.native_delay_supported() {
+                return bus
+                    .inner
+                    .delay_queue
+                    .schedule(&topic_name, payload, metadata, at)
+                    .await;
+            }
+
+            let id = metadata.event_id;
+            bus.publish_at(
+                &topic_name,
+                payload,
+                &metadata,
+                Some(epoch_millis(at) as i64),
+            )
+            .await?;
+            let metrics = bus.delay_metrics();
+            metrics.record_scheduled();
+            metrics.record_delivered();
+            Ok(ScheduledEmit::broker_native(id, at))
+        }
+    }
+
     fn request_with<Req, Resp>(
         &self,
         req: Req,
@@ -521,7 +614,7 @@ impl EventBus for PulsarEventBus {
             let full_request_topic = bus.full_topic(&request_topic);
             let reply_to = bus.reply_topic_full();
 
-            let metadata = options.metadata.unwrap_or_default();
+            let metadata = propagation::inject(options.metadata.unwrap_or_default());
             let partition_key = metadata.partition_key.clone();
 
             // Register the pending request and tag the message with its
@@ -652,6 +745,9 @@ impl EventBus for PulsarEventBus {
             // Cancel all subscribe pollers.
             inner.state.cancel_all_pollers();
 
+            // Stop delayed-emit timers; pending entries stay in the delay store.
+            inner.delay_queue.shutdown();
+
             // Stop the reply consumer and every responder (request-topic)
             // consumer. In-flight requests still awaiting a reply then resolve
             // via their per-request timeout — no new replies can arrive once the
@@ -710,6 +806,10 @@ async fn run_poller_inner(
     config: &PulsarConfig,
     cancel: &CancellationToken,
 ) {
+    // A new session may receive messages another consumer nacked; lift the
+    // ordering blocks of the previous one (see `r2e_events::ordering`).
+    inner.state.reset_ordering();
+
     let consumer_result: Result<Consumer<Vec<u8>, TokioExecutor>, PulsarError> = inner
         .pulsar
         .consumer()
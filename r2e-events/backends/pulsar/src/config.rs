@@ -1,4 +1,7 @@
+use std::path::{Path, PathBuf};
+
 use pulsar::message::proto::command_subscribe::SubType;
+use r2e_events::DEFAULT_DELAY_DIR;
 
 /// Subscription type for Pulsar consumers.
 ///
@@ -50,6 +53,9 @@ pub struct PulsarConfig {
     /// Default number of partitions for topics (0 = non-partitioned). Reserved
     /// for future admin API integration with partitioned topic creation.
     pub default_partitions: u32,
+    /// Parent directory of the per-instance file delay store used when the bus
+    /// builder is given no `delay_store`. Default: `delayed-events/pulsar-{subscription}`.
+    pub delay_dir: Option<PathBuf>,
     /// Whether to automatically reconnect when the consumer disconnects (default: true).
     pub reconnect: bool,
     /// Maximum backoff between reconnection attempts (default: 60s).
@@ -67,6 +73,7 @@ impl Default for PulsarConfig {
             tls_hostname_verification: false,
             auto_create: true,
             default_partitions: 0,
+            delay_dir: None,
             reconnect: true,
             reconnect_max_backoff: std::time::Duration::from_secs(60),
         }
@@ -79,6 +86,15 @@ impl PulsarConfig {
         PulsarConfigBuilder::default()
     }
 
+    /// Parent directory of the default delay store: `delay_dir`, or a
+    /// subdirectory of [`DEFAULT_DELAY_DIR`] named after this bus. Each running
+    /// instance keeps its entries in its own slot below it.
+    pub fn delay_store_dir(&self) -> PathBuf {
+        self.delay_dir.clone().unwrap_or_else(|| {
+            Path::new(DEFAULT_DELAY_DIR).join(format!("pulsar-{}", self.subscription))
+        })
+    }
+
     /// Build the full topic name from a short topic name.
     pub fn full_topic_name(&self, topic: &str) -> String {
         format!("{}{}", self.topic_prefix, topic)
@@ -142,6 +158,11 @@ impl PulsarConfigBuilder {
         self
     }
 
+    pub fn delay_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.config.delay_dir = Some(dir.into());
+        self
+    }
+
     pub fn build(self) -> PulsarConfig {
         self.config
     }
@@ -6,7 +6,7 @@ use pulsar::{producer::Producer, Pulsar, TokioExecutor};
 use tokio::sync::Mutex;
 use tokio_util::sync::CancellationToken;
 
-use r2e_events::backend::{BackendState, PendingRequests};
+use r2e_events::backend::{BackendState, DelayQueue, PendingRequests};
 
 use crate::config::PulsarConfig;
 
@@ -20,6 +20,9 @@ pub(crate) struct PulsarInner {
     /// (and a first-emit broker connect) from blocking one another.
     pub producers: Mutex<HashMap<String, Arc<Mutex<Producer<TokioExecutor>>>>>,
     pub state: Arc<BackendState>,
+    /// Persistent queue behind `emit_after` / `emit_at` when the subscription
+    /// type does not honour `deliver_at_time`.
+    pub delay_queue: DelayQueue,
     /// Cached fully-qualified topic names (`topic_prefix + short_name`),
     /// populated on first use per short topic name. Avoids `format!` on every
     /// publish.
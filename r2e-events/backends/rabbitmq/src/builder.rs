@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
-use r2e_events::backend::{BackendState, TopicRegistry};
-use r2e_events::{DlqPublisher, EventBusError};
+use r2e_events::backend::{BackendState, DelayQueue, TopicRegistry};
+use r2e_events::{DelayStore, DlqPublisher, EventBusError, FileDelayStore, InMemoryDelayStore};
 
 use crate::bus::RabbitMqEventBus;
 use crate::config::RabbitMqConfig;
@@ -21,6 +21,7 @@ use crate::inner::RabbitMqInner;
 pub struct RabbitMqEventBusBuilder {
     config: RabbitMqConfig,
     topic_registry: TopicRegistry,
+    delay_store: Option<Arc<dyn DelayStore>>,
 }
 
 impl RabbitMqEventBusBuilder {
@@ -28,6 +29,7 @@ impl RabbitMqEventBusBuilder {
         Self {
             config,
             topic_registry: TopicRegistry::default(),
+            delay_store: None,
         }
     }
 
@@ -42,6 +44,18 @@ impl RabbitMqEventBusBuilder {
         self.topic::<E>(E::topic())
     }
 
+    /// Persist delayed emits (`emit_after` / `emit_at`) in `store` so they
+    /// survive a restart. Only used when no
+    /// [`delayed_exchange`](RabbitMqConfig::delayed_exchange) is configured.
+    /// Defaults to a
+    /// [per-instance `FileDelayStore`](FileDelayStore::per_instance) under
+    /// [`RabbitMqConfig::delay_store_dir`]; pass an [`InMemoryDelayStore`] to
+    /// opt out of persistence.
+    pub fn delay_store(mut self, store: impl DelayStore) -> Self {
+        self.delay_store = Some(Arc::new(store));
+        self
+    }
+
     /// Connect to the RabbitMQ broker and return a ready-to-use [`RabbitMqEventBus`].
     pub async fn connect(self) -> Result<RabbitMqEventBus, EventBusError> {
         // Open the connection. It is retained on the inner so channels can be
@@ -51,8 +65,30 @@ impl RabbitMqEventBusBuilder {
 
         tracing::info!(uri = %self.config.uri, "connected to RabbitMQ");
 
+        // With a delayed-message exchange the broker holds delayed emits and
+        // the delay queue stays empty: no need for a directory.
+        let delay_store: Arc<dyn DelayStore> = match self.delay_store {
+            Some(store) => store,
+            None if self.config.delayed_exchange.is_some() => Arc::new(InMemoryDelayStore::new()),
+            None => Arc::new(FileDelayStore::per_instance(self.config.delay_store_dir())),
+        };
+
         let inner = Arc::new_cyclic(|weak: &std::sync::Weak<RabbitMqInner>| {
             let weak = weak.clone();
+            // Due delayed entries are plain publishes: unlike DLQ parking they
+            // must not declare a queue for the current group.
+            let delayed: DlqPublisher = {
+                let weak = weak.clone();
+                Arc::new(move |topic, payload, metadata| {
+                    let weak = weak.clone();
+                    Box::pin(async move {
+                        let inner = weak.upgrade().ok_or(EventBusError::Shutdown)?;
+                        RabbitMqEventBus { inner }
+                            .publish(&topic, payload, &metadata)
+                            .await
+                    })
+                })
+            };
             let dlq: DlqPublisher = Arc::new(move |topic, payload, metadata| {
                 let weak = weak.clone();
                 Box::pin(async move {
@@ -71,10 +107,11 @@ impl RabbitMqEventBusBuilder {
             RabbitMqInner::new(
                 self.config,
                 connection,
-                Arc::new(BackendState::with_dlq_publisher(
-                    self.topic_registry,
-                    Some(dlq),
-                )),
+                Arc::new(
+                    BackendState::with_dlq_publisher(self.topic_registry, Some(dlq))
+                        .with_messaging_system("rabbitmq"),
+                ),
+                DelayQueue::new(delay_store, delayed),
             )
         });
 
@@ -83,6 +120,9 @@ impl RabbitMqEventBusBuilder {
         // first `emit`.
         inner.publisher_channel().await?;
 
+        // Re-arm delayed emits a previous process left in the store.
+        inner.delay_queue.recover().await?;
+
         Ok(RabbitMqEventBus { inner })
     }
 }
@@ -5,6 +5,7 @@ use std::any::TypeId;
 use std::future::Future;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 use futures_util::StreamExt;
 use lapin::options::{BasicAckOptions, BasicConsumeOptions, BasicNackOptions, BasicPublishOptions};
@@ -18,9 +19,11 @@ use r2e_events::backend::{
     await_reply, decode_metadata, encode_metadata, reconnect_loop, request_topic, DispatchOutcome,
     Handler, HEADER_REPLY_ERROR,
 };
+use r2e_events::delay::delay_until;
+use r2e_events::propagation;
 use r2e_events::{
-    EmitReceipt, EventBus, EventBusError, EventEnvelope, EventMetadata, HandlerResult,
-    RequestOptions, ResponderHandle, SubscriptionHandle,
+    DelayMetrics, EmitReceipt, EventBus, EventBusError, EventEnvelope, EventMetadata,
+    HandlerResult, RequestOptions, ResponderHandle, ScheduledEmit, SubscriptionHandle,
 };
 
 use crate::builder::RabbitMqEventBusBuilder;
@@ -28,6 +31,13 @@ use crate::config::RabbitMqConfig;
 use crate::error::{map_lapin_error, require_publisher_ack};
 use crate::inner::{RabbitMqInner, DIRECT_REPLY_TO};
 
+/// Header read by the delayed-message exchange plugin (delay in milliseconds).
+const X_DELAY: &str = "x-delay";
+
+/// Longest delay the delayed-message plugin accepts (`2^32 - 1` ms, ~49 days).
+/// Longer delays go through the persistent delay queue.
+const MAX_NATIVE_DELAY: Duration = Duration::from_millis(u32::MAX as u64);
+
 /// RabbitMQ-backed event bus using AMQP 0-9-1.
 ///
 /// Publishes events as JSON messages to a topic exchange and consumes them
@@ -50,6 +60,9 @@ use crate::inner::{RabbitMqInner, DIRECT_REPLY_TO};
 ///   point-to-point request-reply.
 /// - RabbitMQ has no native partitioning. `partition_key` is stored as an AMQP
 ///   header but does not affect routing.
+/// - Broker-native delayed emits need the `rabbitmq_delayed_message_exchange`
+///   plugin and [`RabbitMqConfig::delayed_exchange`]; without them delayed
+///   emits use the persistent delay queue.
 #[derive(Clone)]
 pub struct RabbitMqEventBus {
     pub(crate) inner: Arc<RabbitMqInner>,
@@ -61,6 +74,11 @@ impl RabbitMqEventBus {
         RabbitMqEventBusBuilder::new(config)
     }
 
+    /// Counters for delayed emits (`emit_after` / `emit_at`).
+    pub fn delay_metrics(&self) -> &DelayMetrics {
+        self.inner.delay_queue.metrics()
+    }
+
     /// Resolve the topic name for an event type.
     fn resolve_topic<E: 'static>(&self) -> Arc<str> {
         self.inner.state.resolve_topic::<E>()
@@ -135,6 +153,41 @@ impl RabbitMqEventBus {
         require_publisher_ack(confirmation)
     }
 
+    /// Publish to the delayed-message exchange with an `x-delay` header. The
+    /// broker holds the message and routes it through the topic exchange once
+    /// the delay has elapsed. Awaits the publisher confirm.
+    async fn publish_delayed(
+        &self,
+        delayed_exchange: &str,
+        topic_name: &str,
+        payload: Vec<u8>,
+        metadata: &EventMetadata,
+        delay: Duration,
+    ) -> Result<(), EventBusError> {
+        let props = self.build_properties(metadata);
+        let mut headers = props.headers().clone().unwrap_or_default();
+        headers.insert(
+            ShortString::from(X_DELAY),
+            AMQPValue::LongLongInt(delay.as_millis() as i64),
+        );
+        let props = props.with_headers(headers);
+        let channel = self.inner.publisher_channel().await?;
+
+        let confirmation = channel
+            .basic_publish(
+                delayed_exchange.into(),
+                topic_name.into(),
+                BasicPublishOptions::default(),
+                &payload,
+                props,
+            )
+            .await
+            .map_err(map_lapin_error)?
+            .await
+            .map_err(map_lapin_error)?;
+        require_publisher_ack(confirmation)
+    }
+
     /// Publish without awaiting the publisher confirm. Returns an
     /// [`EmitReceipt`] wrapping the confirm future.
     async fn publish_nowait(
@@ -194,6 +247,20 @@ impl EventBus for RabbitMqEventBus {
         }
     }
 
+    fn configure_ordering<E: 'static>(
+        &self,
+        handler_id: r2e_events::SubscriptionId,
+        ordered_by: r2e_events::OrderedBy,
+    ) -> impl Future<Output = ()> + Send {
+        let inner = self.inner.clone();
+        async move {
+            inner
+                .state
+                .configure_ordering(handler_id.0, ordered_by, Some(TypeId::of::<E>()))
+                .await;
+        }
+    }
+
     fn subscribe<E, F, Fut>(
         &self,
         handler: F,
@@ -324,7 +391,7 @@ impl EventBus for RabbitMqEventBus {
             let payload = serde_json::to_vec(&event)
                 .map_err(|e| EventBusError::Serialization(e.to_string()))?;
             let topic_name = bus.resolve_topic::<E>();
-            let metadata = EventMetadata::new();
+            let metadata = propagation::inject(EventMetadata::new());
             bus.publish(&topic_name, payload, &metadata).await
         }
     }
@@ -340,6 +407,7 @@ impl EventBus for RabbitMqEventBus {
         let bus = self.clone();
         async move {
             bus.inner.state.check_shutdown()?;
+            let metadata = propagation::inject(metadata);
 
             let payload = serde_json::to_vec(&event)
                 .map_err(|e| EventBusError::Serialization(e.to_string()))?;
@@ -362,7 +430,7 @@ impl EventBus for RabbitMqEventBus {
             let payload = serde_json::to_vec(&event)
                 .map_err(|e| EventBusError::Serialization(e.to_string()))?;
             let topic_name = bus.resolve_topic::<E>();
-            let metadata = EventMetadata::new();
+            let metadata = propagation::inject(EventMetadata::new());
             bus.publish_nowait(&topic_name, payload, &metadata).await
         }
     }
@@ -378,6 +446,7 @@ impl EventBus for RabbitMqEventBus {
         let bus = self.clone();
         async move {
             bus.inner.state.check_shutdown()?;
+            let metadata = propagation::inject(metadata);
 
             let payload = serde_json::to_vec(&event)
                 .map_err(|e| EventBusError::Serialization(e.to_string()))?;
@@ -386,6 +455,51 @@ impl EventBus for RabbitMqEventBus {
         }
     }
 
+    /// Schedule a delayed publication.
+    ///
+    /// With [`RabbitMqConfig::delayed_exchange`] set (and a delay the plugin
+    /// accepts) the message is published now with an `x-delay` header and the
+    /// broker holds it; the returned handle is not cancellable. Otherwise the
+    /// event goes through the persistent delay queue and can be cancelled.
+    fn emit_at_with<E>(
+        &self,
+        event: E,
+        at: SystemTime,
+        metadata: EventMetadata,
+    ) -> impl Future<Output = Result<ScheduledEmit, EventBusError>> + Send
+    where
+        E: Serialize + Send + Sync + 'static,
+    {
+        let bus = self.clone();
+        async move {
+            bus.inner.state.check_shutdown()?;
+            let metadata = propagation::inject(metadata);
+
+            let payload = serde_json::to_vec(&event)
+                .map_err(|e| EventBusError::Serialization(e.to_string()))?;
+            let topic_name = bus.resolve_topic::<E>();
+            let delay = delay_until(at);
+
+            match bus.inner.config.delayed_exchange.as_deref() {
+                Some(delayed) if delay <= MAX_NATIVE_DELAY => {
+                    let id = metadata.event_id;
+                    bus.publish_delayed(delayed, &topic_name, payload, &metadata, delay)
+                        .await?;
+                    let metrics = bus.delay_metrics();
+                    metrics.record_scheduled();
+                    metrics.record_delivered();
+                    Ok(ScheduledEmit::broker_native(id, at))
+                }
+                _ => {
+                    bus.inner
+                        .delay_queue
+                        .schedule(&topic_name, payload, metadata, at)
+                        .await
+                }
+            }
+        }
+    }
+
     /// Send a point-to-point request via classic AMQP RPC (Direct Reply-To).
     ///
     /// The request is published to the topic exchange with routing key
@@ -416,7 +530,7 @@ impl EventBus for RabbitMqEventBus {
             let payload = serde_json::to_vec(&req)
                 .map_err(|e| EventBusError::Serialization(e.to_string()))?;
             let request_topic = request_topic(&bus.resolve_topic::<Req>());
-            let metadata = options.metadata.unwrap_or_default();
+            let metadata = propagation::inject(options.metadata.unwrap_or_default());
 
             // Register the pending entry BEFORE publishing so a fast reply can
             // never race ahead of the correlation map. The guard evicts the entry
@@ -560,6 +674,9 @@ impl EventBus for RabbitMqEventBus {
             // consumers, both registered via `register_poller_cancel`).
             inner.state.cancel_all_pollers();
 
+            // Stop delayed-emit timers; pending entries stay in the delay store.
+            inner.delay_queue.shutdown();
+
             // Fail in-flight requesters promptly rather than making them wait
             // out their timeouts; each drops its pending entry on return.
             inner.request_cancel.cancel();
@@ -633,6 +750,10 @@ async fn run_consumer_inner(
     cancel: &CancellationToken,
     initial: Option<(Channel, String)>,
 ) {
+    // A new session may receive messages another consumer nacked; lift the
+    // ordering blocks of the previous one (see `r2e_events::ordering`).
+    inner.state.reset_ordering();
+
     // The channel is kept alive for the whole function; dropping it on return
     // closes only this consumer's channel.
     let (channel, queue_name) = match initial {
@@ -1,3 +1,7 @@
+use std::path::{Path, PathBuf};
+
+use r2e_events::DEFAULT_DELAY_DIR;
+
 /// Configuration for connecting to a RabbitMQ server via AMQP 0-9-1.
 #[derive(Clone, Debug)]
 pub struct RabbitMqConfig {
@@ -20,10 +24,17 @@ pub struct RabbitMqConfig {
     pub message_ttl_ms: Option<u32>,
     /// Optional dead-letter exchange name for rejected/expired messages.
     pub dead_letter_exchange: Option<String>,
+    /// Optional `x-delayed-message` exchange used for broker-native delayed
+    /// emits. Requires the `rabbitmq_delayed_message_exchange` plugin; when
+    /// `None`, `emit_after` / `emit_at` go through the persistent delay queue.
+    pub delayed_exchange: Option<String>,
     /// AMQP heartbeat interval in seconds. `0` disables heartbeats.
     pub heartbeat: u16,
     /// Optional connection name shown in the RabbitMQ management UI.
     pub connection_name: Option<String>,
+    /// Parent directory of the per-instance file delay store used when the bus
+    /// builder is given no `delay_store`. Default: `delayed-events/rabbitmq-{consumer_group}`.
+    pub delay_dir: Option<PathBuf>,
     /// Whether to automatically reconnect when the consumer disconnects (default: true).
     pub reconnect: bool,
     /// Maximum backoff between reconnection attempts (default: 60s).
@@ -42,8 +53,10 @@ impl Default for RabbitMqConfig {
             auto_create: true,
             message_ttl_ms: None,
             dead_letter_exchange: None,
+            delayed_exchange: None,
             heartbeat: 60,
             connection_name: None,
+            delay_dir: None,
             reconnect: true,
             reconnect_max_backoff: std::time::Duration::from_secs(60),
         }
@@ -55,6 +68,15 @@ impl RabbitMqConfig {
     pub fn builder() -> RabbitMqConfigBuilder {
         RabbitMqConfigBuilder::default()
     }
+
+    /// Parent directory of the default delay store: `delay_dir`, or a
+    /// subdirectory of [`DEFAULT_DELAY_DIR`] named after this bus. Each running
+    /// instance keeps its entries in its own slot below it.
+    pub fn delay_store_dir(&self) -> PathBuf {
+        self.delay_dir.clone().unwrap_or_else(|| {
+            Path::new(DEFAULT_DELAY_DIR).join(format!("rabbitmq-{}", self.consumer_group))
+        })
+    }
 }
 
 /// Builder for [`RabbitMqConfig`].
@@ -109,6 +131,11 @@ impl RabbitMqConfigBuilder {
         self
     }
 
+    pub fn delayed_exchange(mut self, exchange: impl Into<String>) -> Self {
+        self.config.delayed_exchange = Some(exchange.into());
+        self
+    }
+
     pub fn heartbeat(mut self, seconds: u16) -> Self {
         self.config.heartbeat = seconds;
         self
@@ -129,6 +156,11 @@ impl RabbitMqConfigBuilder {
         self
     }
 
+    pub fn delay_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.config.delay_dir = Some(dir.into());
+        self
+    }
+
     pub fn build(self) -> RabbitMqConfig {
         self.config
     }
@@ -3,15 +3,15 @@ use std::sync::{Arc, RwLock as StdRwLock};
 
 use futures_util::StreamExt;
 use lapin::options::{
-    BasicConsumeOptions, BasicQosOptions, ExchangeDeclareOptions, QueueBindOptions,
-    QueueDeclareOptions,
+    BasicConsumeOptions, BasicQosOptions, ExchangeBindOptions, ExchangeDeclareOptions,
+    QueueBindOptions, QueueDeclareOptions,
 };
 use lapin::types::{AMQPValue, FieldTable, LongString, ShortString};
 use lapin::{Channel, Connection, ConnectionProperties, ExchangeKind};
 use tokio::sync::Mutex;
 use tokio_util::sync::CancellationToken;
 
-use r2e_events::backend::{BackendState, PendingRequests, HEADER_REPLY_ERROR};
+use r2e_events::backend::{BackendState, DelayQueue, PendingRequests, HEADER_REPLY_ERROR};
 use r2e_events::EventBusError;
 
 use crate::config::RabbitMqConfig;
@@ -58,6 +58,9 @@ pub(crate) struct RabbitMqInner {
     /// [`EventBusError::Shutdown`] instead of blocking until their timeout.
     pub(crate) request_cancel: CancellationToken,
     pub state: Arc<BackendState>,
+    /// Persistent queue behind `emit_after` / `emit_at` when no delayed
+    /// exchange is configured.
+    pub(crate) delay_queue: DelayQueue,
 }
 
 struct RequesterChannel {
@@ -70,6 +73,7 @@ impl RabbitMqInner {
         config: RabbitMqConfig,
         connection: Connection,
         state: Arc<BackendState>,
+        delay_queue: DelayQueue,
     ) -> Self {
         Self {
             config,
@@ -82,6 +86,7 @@ impl RabbitMqInner {
             pending: Arc::new(PendingRequests::new()),
             request_cancel: CancellationToken::new(),
             state,
+            delay_queue,
         }
     }
 
@@ -140,10 +145,51 @@ impl RabbitMqInner {
                 )
                 .await
                 .map_err(map_lapin_error)?;
+
+            if let Some(ref delayed) = self.config.delayed_exchange {
+                self.declare_delayed_exchange(channel, delayed).await?;
+            }
         }
         Ok(())
     }
 
+    /// Declare the `x-delayed-message` exchange and bind it to the topic
+    /// exchange, so a delayed message re-enters normal topic routing once its
+    /// `x-delay` has elapsed.
+    async fn declare_delayed_exchange(
+        &self,
+        channel: &Channel,
+        delayed: &str,
+    ) -> Result<(), EventBusError> {
+        let mut args = FieldTable::default();
+        args.insert(
+            ShortString::from("x-delayed-type"),
+            AMQPValue::LongString(LongString::from("topic".as_bytes())),
+        );
+        channel
+            .exchange_declare(
+                delayed.into(),
+                ExchangeKind::Custom("x-delayed-message".into()),
+                ExchangeDeclareOptions {
+                    durable: self.config.durable,
+                    ..ExchangeDeclareOptions::default()
+                },
+                args,
+            )
+            .await
+            .map_err(map_lapin_error)?;
+        channel
+            .exchange_bind(
+                self.config.exchange.as_str().into(),
+                delayed.into(),
+                "#".into(),
+                ExchangeBindOptions::default(),
+                FieldTable::default(),
+            )
+            .await
+            .map_err(map_lapin_error)
+    }
+
     /// Create a dedicated consumer channel: reconnect if needed, apply QoS
     /// (prefetch) and declare the exchange.
     pub(crate) async fn new_consumer_channel(&self) -> Result<Channel, EventBusError> {
@@ -0,0 +1,185 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use tokio_util::sync::CancellationToken;
+
+use crate::delay::{
+    delay_until, epoch_millis, from_epoch_millis, DelayMetrics, DelayStore, DelayedEntry,
+    InMemoryDelayStore, ScheduledEmit, CANCELLED, FIRING, PENDING,
+};
+use crate::{DlqPublisher, EventBusError, EventMetadata};
+
+/// Initial backoff between publish attempts of a due entry.
+const RETRY_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+/// Upper bound on the backoff between publish attempts of a due entry.
+const RETRY_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Persistent delay queue shared by the distributed backends.
+///
+/// [`schedule`](Self::schedule) writes the serialized event to the
+/// [`DelayStore`] before arming an in-process timer; when the timer fires the
+/// entry is published through `publisher` (retrying with backoff until the
+/// broker accepts it) and only then removed from the store. On connect,
+/// [`recover`](Self::recover) re-arms whatever the previous process left
+/// behind — entries already due are published immediately.
+///
+/// [`shutdown`](Self::shutdown) stops the timers but keeps the entries, so they
+/// fire on the next start.
+pub struct DelayQueue {
+    store: Arc<dyn DelayStore>,
+    publisher: DlqPublisher,
+    metrics: DelayMetrics,
+    cancel: CancellationToken,
+}
+
+impl DelayQueue {
+    /// Create a queue that persists to `store` and publishes due entries via
+    /// `publisher` (same shape as the DLQ publisher: topic, payload, metadata).
+    pub fn new(store: Arc<dyn DelayStore>, publisher: DlqPublisher) -> Self {
+        Self {
+            store,
+            publisher,
+            metrics: DelayMetrics::new(),
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    /// Create a queue backed by an [`InMemoryDelayStore`].
+    pub fn in_memory(publisher: DlqPublisher) -> Self {
+        Self::new(Arc::new(InMemoryDelayStore::new()), publisher)
+    }
+
+    /// Delivery counters for this queue.
+    pub fn metrics(&self) -> &DelayMetrics {
+        &self.metrics
+    }
+
+    /// Persist and arm a delayed publication of `payload` to `topic`.
+    ///
+    /// Returns once the entry is in the store; a store error is returned to
+    /// the caller and nothing is scheduled.
+    pub async fn schedule(
+        &self,
+        topic: &str,
+        payload: Vec<u8>,
+        metadata: EventMetadata,
+        deliver_at: SystemTime,
+    ) -> Result<ScheduledEmit, EventBusError> {
+        if self.cancel.is_cancelled() {
+            return Err(EventBusError::Shutdown);
+        }
+        let entry = DelayedEntry {
+            id: metadata.event_id,
+            topic: topic.to_string(),
+            payload,
+            metadata,
+            deliver_at: epoch_millis(deliver_at),
+        };
+        self.store.save(&entry).await?;
+        Ok(self.arm(entry))
+    }
+
+    /// Re-arm every entry left in the store. Returns the number of entries
+    /// recovered.
+    pub async fn recover(&self) -> Result<usize, EventBusError> {
+        let entries = self.store.load_all().await?;
+        let count = entries.len();
+        for entry in entries {
+            self.arm(entry);
+        }
+        if count > 0 {
+            tracing::info!(count, "recovered delayed events from the delay store");
+        }
+        Ok(count)
+    }
+
+    /// Stop every timer. Pending entries stay in the store.
+    pub fn shutdown(&self) {
+        self.cancel.cancel();
+    }
+
+    fn arm(&self, entry: DelayedEntry) -> ScheduledEmit {
+        let id = entry.id;
+        let deliver_at = from_epoch_millis(entry.deliver_at);
+        let token = self.cancel.child_token();
+        let state = Arc::new(AtomicU8::new(PENDING));
+        self.metrics.record_scheduled();
+
+        {
+            let token = token.clone();
+            let state = state.clone();
+            let store = self.store.clone();
+            let publisher = self.publisher.clone();
+            let metrics = self.metrics.clone();
+            r2e_core::rt::spawn(async move {
+                tokio::select! {
+                    _ = token.cancelled() => return,
+                    _ = r2e_core::rt::sleep(delay_until(deliver_at)) => {}
+                }
+                if state
+                    .compare_exchange(PENDING, FIRING, Ordering::AcqRel, Ordering::Acquire)
+                    .is_err()
+                {
+                    return;
+                }
+
+                let mut backoff = RETRY_INITIAL_BACKOFF;
+                loop {
+                    let attempt = publisher(
+                        entry.topic.clone(),
+                        entry.payload.clone(),
+                        entry.metadata.clone(),
+                    );
+                    match attempt.await {
+                        Ok(()) => break,
+                        Err(e) => {
+                            tracing::warn!(
+                                event_id = id,
+                                topic = %entry.topic,
+                                error = %e,
+                                "delayed publish failed, retrying in {backoff:?}"
+                            );
+                        }
+                    }
+                    // Shutdown while retrying: the entry stays in the store and
+                    // is recovered on the next start.
+                    tokio::select! {
+                        _ = token.cancelled() => return,
+                        _ = r2e_core::rt::sleep(backoff) => {}
+                    }
+                    backoff = (backoff * 2).min(RETRY_MAX_BACKOFF);
+                }
+
+                metrics.record_delivered();
+                // A failed removal means the entry is published again after a
+                // restart — acceptable under at-least-once delivery.
+                if let Err(e) = store.remove(id).await {
+                    tracing::warn!(event_id = id, error = %e, "failed to remove delivered entry from the delay store");
+                }
+            });
+        }
+
+        let store = self.store.clone();
+        let metrics = self.metrics.clone();
+        ScheduledEmit::new(id, deliver_at, move || {
+            let cancelled = state
+                .compare_exchange(PENDING, CANCELLED, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok();
+            let token = token.clone();
+            let store = store.clone();
+            let metrics = metrics.clone();
+            async move {
+                if !cancelled {
+                    return false;
+                }
+                token.cancel();
+                metrics.record_cancelled();
+                if let Err(e) = store.remove(id).await {
+                    tracing::warn!(event_id = id, error = %e, "failed to remove cancelled entry from the delay store");
+                }
+                true
+            }
+        })
+    }
+}
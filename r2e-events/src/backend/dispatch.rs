@@ -3,6 +3,7 @@ use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 
+use crate::ordering::KeyedSequencer;
 use crate::{EventFilter, EventMetadata, HandlerResult, RetryPolicy};
 
 /// Type-erased async handler function.
@@ -29,6 +30,9 @@ pub struct HandlerEntry {
     /// Optional retry policy — when set, failed handlers are retried
     /// according to this policy before being sent to the DLQ.
     pub retry_policy: Option<RetryPolicy>,
+    /// Optional per-key sequencer — when set, events sharing an ordering key
+    /// are handled one at a time (see [`crate::ordering`]).
+    pub ordering: Option<Arc<KeyedSequencer>>,
 }
 
 /// All handlers and the deserializer for a single event type / topic.
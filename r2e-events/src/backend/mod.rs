@@ -3,8 +3,10 @@
 //! This module provides building blocks that all distributed backends
 //! (Iggy, Kafka, Pulsar, RabbitMQ) share: topic registries, type-erased
 //! dispatch types, metadata header encoding, request-reply plumbing
-//! (correlation map + responder registry), and common inner state.
+//! (correlation map + responder registry), the persistent delay queue, and
+//! common inner state.
 
+mod delay_queue;
 mod dispatch;
 mod metadata_codec;
 mod pending;
@@ -13,6 +15,7 @@ mod state;
 mod topic;
 mod watermark;
 
+pub use delay_queue::DelayQueue;
 pub use dispatch::{DeserializerFn, Handler, HandlerEntry, TopicHandlers};
 pub use metadata_codec::{
     decode_metadata, decode_reply_headers, encode_metadata, encode_reply_headers, HeaderPair,
@@ -27,8 +30,8 @@ pub use state::{
     DEFAULT_BACKEND_CONCURRENCY,
 };
 pub use topic::{
-    instance_id, reply_topic, request_topic, responder_group, sanitize_topic_name, TopicRegistry,
-    REQUEST_TOPIC_SUFFIX,
+    event_topic_name, instance_id, reply_topic, request_topic, responder_group,
+    sanitize_topic_name, TopicRegistry, REQUEST_TOPIC_SUFFIX,
 };
 pub use watermark::WatermarkTracker;
 
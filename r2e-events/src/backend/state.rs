@@ -6,11 +6,14 @@ use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 
 use serde::{de::DeserializeOwned, Serialize};
-use tokio::sync::{Notify, RwLock, Semaphore};
+use tokio::sync::{Notify, OwnedSemaphorePermit, RwLock, Semaphore};
 use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 
 use super::dispatch::{DeserializerFn, Handler, HandlerEntry, TopicHandlers};
-use super::topic::TopicRegistry;
+use super::topic::{request_topic, TopicRegistry};
+use crate::ordering::{KeyedSequencer, OrderedBy};
+use crate::propagation;
 use crate::{
     DlqPublisher, EventBusError, EventEnvelope, EventMetadata, HandlerResult, SubscriptionHandle,
     SubscriptionId,
@@ -123,6 +126,11 @@ pub struct BackendState {
     pub dlq_publisher: Option<DlqPublisher>,
     /// Semaphore limiting concurrent handler execution (backpressure).
     pub handler_semaphore: Arc<Semaphore>,
+    /// `messaging.system` reported on consumer spans (`"kafka"`, `"rabbitmq"`, …).
+    pub messaging_system: &'static str,
+    /// Bumped by [`reset_ordering`](BackendState::reset_ordering); ordering
+    /// keys blocked in an earlier epoch are released.
+    pub ordering_epoch: AtomicU64,
 }
 
 /// Default capacity for a poller's completion channel — bounds how many
@@ -203,9 +211,17 @@ impl BackendState {
             responders: RwLock::new(HashMap::new()),
             dlq_publisher,
             handler_semaphore: Arc::new(Semaphore::new(max_concurrency)),
+            messaging_system: "unknown",
+            ordering_epoch: AtomicU64::new(0),
         }
     }
 
+    /// Set the `messaging.system` reported on consumer spans.
+    pub fn with_messaging_system(mut self, system: &'static str) -> Self {
+        self.messaging_system = system;
+        self
+    }
+
     /// Check if the bus is shut down, returning `Err(Shutdown)` if so.
     pub fn check_shutdown(&self) -> Result<(), EventBusError> {
         if self.shutdown.load(Ordering::Acquire) {
@@ -231,6 +247,15 @@ impl BackendState {
         }
     }
 
+    /// Take one of the handler concurrency slots.
+    async fn acquire_handler_permit(&self) -> OwnedSemaphorePermit {
+        self.handler_semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("semaphore closed")
+    }
+
     /// Register a cancellation token for a background poller.
     ///
     /// Returns the token — pass it to the poller task. Call `.cancel()` to stop
@@ -268,6 +293,16 @@ impl BackendState {
             .resolve(type_id, type_name)
     }
 
+    /// Topic registered for `type_id`, or the placeholder used in consumer
+    /// spans when none is (every subscribed type has one).
+    fn topic_name(&self, type_id: TypeId) -> Arc<str> {
+        self.topic_registry
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(type_id)
+            .unwrap_or_else(|| Arc::from("unknown"))
+    }
+
     /// Check if a topic has already been ensured.
     ///
     /// Returns `true` if the topic was previously marked as ensured.
@@ -358,6 +393,7 @@ impl BackendState {
             handler,
             filter,
             retry_policy,
+            ordering: None,
         });
         (id, is_first)
     }
@@ -474,10 +510,15 @@ impl BackendState {
             let map = self.responders.read().await;
             map.get(&type_id).cloned()
         };
-        match responder {
-            Some(r) => Some(r(payload, metadata).await),
-            None => None,
-        }
+        let r = responder?;
+        let destination = request_topic(&self.topic_name(type_id));
+        // `payload` is only borrowed while the responder deserializes it, so
+        // the responder is called up front; its future runs in the context.
+        let fut = r(payload, metadata.clone());
+        Some(
+            propagation::in_consumer_context(self.messaging_system, &destination, &metadata, fut)
+                .await,
+        )
     }
 
     /// Build the reply payload and optional error for a request of `type_id`,
@@ -543,6 +584,8 @@ impl BackendState {
     ///
     /// Backpressure: a semaphore permit is acquired **before** spawning each handler
     /// task, so the poller naturally slows down when handlers are saturated.
+    /// Ordered handlers instead wait for room in their key's queue, and take
+    /// the permit once their turn is ready (see [`crate::ordering`]).
     ///
     /// Panic-safety: each task holds an [`InFlightGuard`] that decrements the
     /// in-flight counter on drop, even on panic; a panicked handler resolves
@@ -577,13 +620,14 @@ impl BackendState {
                             .retry_policy
                             .as_ref()
                             .and_then(|p| p.dead_letter_topic.clone()),
+                        entry.ordering.clone(),
                     )
                 })
                 .collect();
 
             // Pre-allocate DLQ data only if any handler has a DLQ configured.
             let has_dlq =
-                self.dlq_publisher.is_some() && handlers.iter().any(|(_, _, dlq)| dlq.is_some());
+                self.dlq_publisher.is_some() && handlers.iter().any(|(_, _, dlq, _)| dlq.is_some());
             let dlq_data: Option<(Arc<Vec<u8>>, EventMetadata)> = if has_dlq {
                 Some((Arc::new(payload.to_vec()), metadata.clone()))
             } else {
@@ -600,7 +644,7 @@ impl BackendState {
                 tracing::error!("failed to deserialize event: {err}");
                 let mut dlq_topics: Vec<String> = handler_data
                     .iter()
-                    .filter_map(|(_, _, dlq)| dlq.clone())
+                    .filter_map(|(_, _, dlq, _)| dlq.clone())
                     .collect();
                 dlq_topics.sort();
                 dlq_topics.dedup();
@@ -624,7 +668,7 @@ impl BackendState {
                 // message is intentional. With a DLQ, acknowledge only after
                 // every broker publication succeeded.
                 return DispatchCompletion::resolved(
-                    if parked || handler_data.iter().all(|(_, _, dlq)| dlq.is_none()) {
+                    if parked || handler_data.iter().all(|(_, _, dlq, _)| dlq.is_none()) {
                         DispatchOutcome::Ack
                     } else {
                         DispatchOutcome::Nack
@@ -633,20 +677,30 @@ impl BackendState {
             }
         };
 
+        let topic = self.topic_name(type_id);
         let mut receivers = Vec::with_capacity(handler_data.len());
-        for (h, retry_policy, _dlq_topic) in handler_data {
+        for (h, retry_policy, _dlq_topic, ordering) in handler_data {
             let e = event.clone();
             let m = metadata.clone();
             let state = self.clone();
+            let topic = topic.clone();
             let dlq_data = dlq_data.clone();
 
-            // Backpressure: acquire permit BEFORE spawning to bound task count.
-            let permit = self
-                .handler_semaphore
-                .clone()
-                .acquire_owned()
-                .await
-                .expect("semaphore closed");
+            // Pollers dispatch in partition order, so taking the key's turn
+            // here (before spawning) sequences the handler by offset.
+            let mut turn = ordering.as_ref().and_then(|seq| seq.enter(&m));
+
+            // Backpressure BEFORE spawning, to bound task count: a handler
+            // slot, or — for an ordered handler — room in its key's queue.
+            // The latter takes its slot once the turn is ready, so events
+            // waiting behind a slow key do not starve the other keys.
+            let permit = match &mut turn {
+                Some(turn) => {
+                    turn.reserve().await;
+                    None
+                }
+                None => Some(self.acquire_handler_permit().await),
+            };
 
             let guard = self.acquire_in_flight();
 
@@ -657,11 +711,37 @@ impl BackendState {
             // startup), so plain `spawn` keeps handler tasks there.
             r2e_core::rt::spawn(async move {
                 let _guard = guard;
-                let result = if let Some(ref policy) = retry_policy {
-                    Self::invoke_with_retry(&h, &e, &m, policy).await
-                } else {
-                    h(e, m).await
+                if let Some(turn) = &mut turn {
+                    turn.ready().await;
+                    // An earlier event of this key failed and awaits
+                    // redelivery: running this one now would overtake it.
+                    // Nack so it is redelivered after it (the watermark is
+                    // already pinned below this offset).
+                    let epoch = state.ordering_epoch.load(Ordering::Acquire);
+                    if !turn.admit(m.event_id, epoch) {
+                        tracing::debug!(
+                            key = turn.key(),
+                            "ordering key blocked by a failed event; deferring to redelivery"
+                        );
+                        let _ = tx.send(false);
+                        return;
+                    }
+                }
+                let permit = match permit {
+                    Some(permit) => permit,
+                    None => state.acquire_handler_permit().await,
                 };
+                let span = propagation::consumer_span(state.messaging_system, &topic, &m);
+                let result = propagation::request_context(&m)
+                    .scope(async {
+                        if let Some(ref policy) = retry_policy {
+                            Self::invoke_with_retry(&h, &e, &m, policy).await
+                        } else {
+                            h(e, m.clone()).await
+                        }
+                    })
+                    .instrument(span)
+                    .await;
                 let acked = match result {
                     HandlerResult::Ack => true,
                     HandlerResult::Nack(ref reason) => {
@@ -692,6 +772,10 @@ impl BackendState {
                         captured
                     }
                 };
+                if let Some(turn) = &mut turn {
+                    turn.finish(acked);
+                }
+                drop(turn);
                 drop(permit);
                 // Receiver may be gone (untracked dispatch) — ignore send errors.
                 let _ = tx.send(acked);
@@ -743,6 +827,51 @@ impl BackendState {
         }
     }
 
+    /// Sequence a handler's events per ordering key (see [`crate::ordering`]).
+    ///
+    /// Called by generated code after `subscribe()` for
+    /// `#[consumer(ordered_by = "...")]`. If `type_id_hint` is provided, only
+    /// that type's handlers are searched.
+    pub async fn configure_ordering(
+        &self,
+        handler_id: u64,
+        ordered_by: OrderedBy,
+        type_id_hint: Option<TypeId>,
+    ) {
+        let sequencer = Arc::new(KeyedSequencer::new(ordered_by));
+        let mut map = self.handlers.write().await;
+        let entry = match type_id_hint.and_then(|type_id| map.get_mut(&type_id)) {
+            Some(topic_handlers) => topic_handlers
+                .entries
+                .iter_mut()
+                .find(|e| e.id == handler_id),
+            None => None,
+        };
+        if let Some(entry) = entry {
+            entry.ordering = Some(sequencer);
+            return;
+        }
+        // Fallback: scan all types
+        if let Some(entry) = map
+            .values_mut()
+            .flat_map(|th| th.entries.iter_mut())
+            .find(|e| e.id == handler_id)
+        {
+            entry.ordering = Some(sequencer);
+        }
+    }
+
+    /// Release every ordering key blocked by a failed event (see
+    /// [`crate::ordering`]).
+    ///
+    /// Backends call this when a consumer session starts and when partitions
+    /// are revoked: the failed event may now be redelivered to another
+    /// consumer, so waiting for it here could block its key forever. Lock-free,
+    /// so it is safe from broker callbacks.
+    pub fn reset_ordering(&self) {
+        self.ordering_epoch.fetch_add(1, Ordering::AcqRel);
+    }
+
     /// Invoke a handler with retry logic.
     pub async fn invoke_with_retry(
         handler: &Handler,
@@ -756,12 +885,7 @@ impl BackendState {
         }
 
         for attempt in 0..policy.max_retries {
-            let delay = if policy.exponential_backoff {
-                policy.retry_delay * 2u32.saturating_pow(attempt)
-            } else {
-                policy.retry_delay
-            };
-            r2e_core::rt::sleep(delay).await;
+            r2e_core::rt::sleep(policy.backoff(attempt)).await;
 
             tracing::debug!(
                 attempt = attempt + 1,
@@ -47,6 +47,20 @@ pub fn sanitize_topic_name(name: &str) -> String {
         .replace(' ', "")
 }
 
+/// The topic an event type is known by when no explicit topic is configured
+/// for it: its overridden [`Event::topic()`](crate::Event::topic), else the
+/// sanitized type name the bus falls back to.
+///
+/// `event_topic` is `None` for types that do not implement `Event`. Consumer
+/// metadata and AsyncAPI `publishes::<E>()` both resolve topics through this
+/// function, so the two sides of a channel always agree.
+pub fn event_topic_name(type_name: &str, event_topic: Option<&str>) -> String {
+    match event_topic {
+        Some(topic) if topic != type_name => topic.to_string(),
+        _ => sanitize_topic_name(type_name),
+    }
+}
+
 /// Suffix appended to an event topic to form its request-reply request topic.
 pub const REQUEST_TOPIC_SUFFIX: &str = ".requests";
 
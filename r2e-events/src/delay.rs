@@ -0,0 +1,607 @@
+//! Delayed and scheduled event delivery.
+//!
+//! [`EventBus::emit_after`](crate::EventBus::emit_after) and
+//! [`EventBus::emit_at`](crate::EventBus::emit_at) hand an event to the bus now
+//! and have it published later. Each call returns a [`ScheduledEmit`] handle
+//! that can cancel the delivery while it is still pending.
+//!
+//! Three delivery strategies exist, chosen per backend:
+//!
+//! - **In-process timer** — `LocalEventBus`. Cheap, cancellable, but lost on
+//!   restart.
+//! - **Persistent delay queue** — Kafka, Iggy, and the RabbitMQ / Pulsar
+//!   fallbacks. The serialized event is written to a [`DelayStore`] before the
+//!   call returns, armed as a timer, and removed from the store only after the
+//!   broker confirmed the publish. Entries still in the store are re-armed by
+//!   [`DelayQueue::recover`](crate::backend::DelayQueue::recover) when the bus
+//!   connects, so a restart never loses a scheduled event (at-least-once: a
+//!   crash between publish and removal re-publishes it).
+//! - **Broker-native** — RabbitMQ with a delayed-message exchange, Pulsar
+//!   `deliver_at`. The broker holds the message; the returned handle is not
+//!   cancellable ([`ScheduledEmit::is_cancellable`] is `false`).
+
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+
+use crate::{EventBusError, EventMetadata};
+
+// ── ScheduledEmit ──────────────────────────────────────────────────────
+
+type CancelFn = Arc<dyn Fn() -> Pin<Box<dyn Future<Output = bool> + Send>> + Send + Sync>;
+
+/// Handle returned by [`EventBus::emit_after`](crate::EventBus::emit_after) /
+/// [`EventBus::emit_at`](crate::EventBus::emit_at).
+///
+/// Dropping the handle does **not** cancel the delivery.
+#[derive(Clone)]
+pub struct ScheduledEmit {
+    id: u128,
+    deliver_at: SystemTime,
+    cancel: Option<CancelFn>,
+}
+
+impl ScheduledEmit {
+    /// Create a cancellable handle. `cancel` resolves to `true` when it
+    /// prevented the delivery, `false` when the event was already published
+    /// (or cancelled).
+    pub fn new<F, Fut>(id: u128, deliver_at: SystemTime, cancel: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = bool> + Send + 'static,
+    {
+        Self {
+            id,
+            deliver_at,
+            cancel: Some(Arc::new(move || Box::pin(cancel()))),
+        }
+    }
+
+    /// Create a handle for a delivery the broker has taken over
+    /// (broker-native delay). Such deliveries cannot be cancelled.
+    pub fn broker_native(id: u128, deliver_at: SystemTime) -> Self {
+        Self {
+            id,
+            deliver_at,
+            cancel: None,
+        }
+    }
+
+    /// The `event_id` of the scheduled event.
+    pub fn id(&self) -> u128 {
+        self.id
+    }
+
+    /// When the event is due for publication.
+    pub fn deliver_at(&self) -> SystemTime {
+        self.deliver_at
+    }
+
+    /// Whether [`cancel`](Self::cancel) can still prevent the delivery.
+    /// `false` for broker-native delays.
+    pub fn is_cancellable(&self) -> bool {
+        self.cancel.is_some()
+    }
+
+    /// Cancel the delivery. Returns `true` if the event will not be published,
+    /// `false` if it was already published, already cancelled, or the delay is
+    /// broker-native.
+    pub async fn cancel(&self) -> bool {
+        match &self.cancel {
+            Some(cancel) => cancel().await,
+            None => false,
+        }
+    }
+}
+
+impl fmt::Debug for ScheduledEmit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScheduledEmit")
+            .field("id", &self.id)
+            .field("deliver_at", &self.deliver_at)
+            .field("cancellable", &self.is_cancellable())
+            .finish()
+    }
+}
+
+// ── DelayMetrics ───────────────────────────────────────────────────────
+
+#[derive(Default)]
+struct DelayCounters {
+    scheduled: AtomicU64,
+    delivered: AtomicU64,
+    cancelled: AtomicU64,
+    failed: AtomicU64,
+}
+
+/// Counters for delayed deliveries of one bus. Cheap to clone (shared).
+///
+/// For broker-native delays an event counts as delivered as soon as the broker
+/// accepted it.
+#[derive(Clone, Default)]
+pub struct DelayMetrics {
+    counters: Arc<DelayCounters>,
+}
+
+impl DelayMetrics {
+    /// Create a fresh set of zeroed counters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Total number of events scheduled (including recovered entries).
+    pub fn scheduled(&self) -> u64 {
+        self.counters.scheduled.load(Ordering::Relaxed)
+    }
+
+    /// Number of delayed events published.
+    pub fn delivered(&self) -> u64 {
+        self.counters.delivered.load(Ordering::Relaxed)
+    }
+
+    /// Number of delayed events cancelled before publication.
+    pub fn cancelled(&self) -> u64 {
+        self.counters.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Number of delayed events whose publication failed and was abandoned.
+    pub fn failed(&self) -> u64 {
+        self.counters.failed.load(Ordering::Relaxed)
+    }
+
+    /// Number of events currently waiting for their delivery time.
+    pub fn pending(&self) -> u64 {
+        self.scheduled()
+            .saturating_sub(self.delivered() + self.cancelled() + self.failed())
+    }
+
+    pub fn record_scheduled(&self) {
+        self.counters.scheduled.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_delivered(&self) {
+        self.counters.delivered.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_cancelled(&self) {
+        self.counters.cancelled.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_failed(&self) {
+        self.counters.failed.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl fmt::Debug for DelayMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DelayMetrics")
+            .field("scheduled", &self.scheduled())
+            .field("delivered", &self.delivered())
+            .field("cancelled", &self.cancelled())
+            .field("failed", &self.failed())
+            .finish()
+    }
+}
+
+// ── Time helpers ───────────────────────────────────────────────────────
+
+/// Time remaining until `at`, or zero when `at` is already past.
+pub fn delay_until(at: SystemTime) -> Duration {
+    at.duration_since(SystemTime::now()).unwrap_or_default()
+}
+
+/// `at` as epoch milliseconds (zero for times before the epoch).
+pub fn epoch_millis(at: SystemTime) -> u64 {
+    at.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Epoch milliseconds back to a `SystemTime`.
+pub fn from_epoch_millis(ms: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(ms)
+}
+
+// ── In-process timer ───────────────────────────────────────────────────
+
+pub(crate) const PENDING: u8 = 0;
+pub(crate) const FIRING: u8 = 1;
+pub(crate) const CANCELLED: u8 = 2;
+
+/// Arm an in-process timer that awaits `fire` at `deliver_at`.
+///
+/// The timer is a child of `parent`: cancelling the parent (bus shutdown)
+/// drops every pending delivery, which then counts as cancelled. Nothing is
+/// persisted.
+pub(crate) fn spawn_in_process<Fut>(
+    id: u128,
+    deliver_at: SystemTime,
+    parent: &CancellationToken,
+    metrics: DelayMetrics,
+    fire: Fut,
+) -> ScheduledEmit
+where
+    Fut: Future<Output = Result<(), EventBusError>> + Send + 'static,
+{
+    let token = parent.child_token();
+    let state = Arc::new(AtomicU8::new(PENDING));
+    metrics.record_scheduled();
+
+    {
+        let token = token.clone();
+        let state = state.clone();
+        let metrics = metrics.clone();
+        r2e_core::rt::spawn(async move {
+            tokio::select! {
+                _ = token.cancelled() => {
+                    // Bus shutdown (an explicit `cancel` already flipped the state).
+                    if state.compare_exchange(PENDING, CANCELLED, Ordering::AcqRel, Ordering::Acquire).is_ok() {
+                        metrics.record_cancelled();
+                    }
+                    return;
+                }
+                _ = r2e_core::rt::sleep(delay_until(deliver_at)) => {}
+            }
+            if state
+                .compare_exchange(PENDING, FIRING, Ordering::AcqRel, Ordering::Acquire)
+                .is_err()
+            {
+                return;
+            }
+            match fire.await {
+                Ok(()) => metrics.record_delivered(),
+                Err(e) => {
+                    metrics.record_failed();
+                    tracing::warn!(event_id = id, error = %e, "delayed emit failed");
+                }
+            }
+        });
+    }
+
+    ScheduledEmit::new(id, deliver_at, move || {
+        let cancelled = state
+            .compare_exchange(PENDING, CANCELLED, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok();
+        if cancelled {
+            token.cancel();
+            metrics.record_cancelled();
+        }
+        std::future::ready(cancelled)
+    })
+}
+
+// ── DelayStore ─────────────────────────────────────────────────────────
+
+/// A serialized event waiting in a [`DelayStore`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DelayedEntry {
+    /// The event's `event_id` (also the store key).
+    pub id: u128,
+    /// Resolved topic the event is published to.
+    pub topic: String,
+    /// Serialized event payload.
+    pub payload: Vec<u8>,
+    /// Metadata published with the event.
+    pub metadata: EventMetadata,
+    /// Delivery time in epoch milliseconds.
+    pub deliver_at: u64,
+}
+
+/// Boxed future returned by [`DelayStore`] methods.
+pub type StoreFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, EventBusError>> + Send + 'a>>;
+
+/// Durable storage behind the persistent delay queue.
+///
+/// Implement for a database table or a key-value store to share scheduled
+/// events across instances; [`FileDelayStore`] (the default, one slot per
+/// instance under [`DEFAULT_DELAY_DIR`]) covers single-node deployments and
+/// [`InMemoryDelayStore`] keeps nothing across restarts.
+pub trait DelayStore: Send + Sync + 'static {
+    /// Persist an entry. Called before the scheduling call returns.
+    fn save<'a>(&'a self, entry: &'a DelayedEntry) -> StoreFuture<'a, ()>;
+
+    /// Remove an entry after publication or cancellation. Removing an unknown
+    /// id is not an error.
+    fn remove(&self, id: u128) -> StoreFuture<'_, ()>;
+
+    /// Load every stored entry (on connect, to re-arm them).
+    fn load_all(&self) -> StoreFuture<'_, Vec<DelayedEntry>>;
+}
+
+/// Non-durable [`DelayStore`], opted into with the backend builder's
+/// `.delay_store(InMemoryDelayStore::new())`. Scheduled events survive a
+/// broker reconnect but not a process restart.
+#[derive(Default)]
+pub struct InMemoryDelayStore {
+    entries: Mutex<HashMap<u128, DelayedEntry>>,
+}
+
+impl InMemoryDelayStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl DelayStore for InMemoryDelayStore {
+    fn save<'a>(&'a self, entry: &'a DelayedEntry) -> StoreFuture<'a, ()> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(entry.id, entry.clone());
+        Box::pin(std::future::ready(Ok(())))
+    }
+
+    fn remove(&self, id: u128) -> StoreFuture<'_, ()> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&id);
+        Box::pin(std::future::ready(Ok(())))
+    }
+
+    fn load_all(&self) -> StoreFuture<'_, Vec<DelayedEntry>> {
+        let entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect();
+        Box::pin(std::future::ready(Ok(entries)))
+    }
+}
+
+/// Parent directory of the [`FileDelayStore`] a distributed backend uses when
+/// its builder is given no `delay_store`, relative to the working directory.
+/// Each bus gets its own subdirectory (`{backend}-{group}`), unless its
+/// config sets `delay_dir`, and each running instance its own slot in it
+/// (see [`FileDelayStore::per_instance`]).
+pub const DEFAULT_DELAY_DIR: &str = "delayed-events";
+
+/// [`DelayStore`] that keeps one JSON file per entry in a directory — the
+/// default store of the distributed backends.
+///
+/// Writes go to a temporary file that is renamed into place, so a crash never
+/// leaves a half-written entry. Unreadable files are skipped (and logged) on
+/// [`load_all`](DelayStore::load_all).
+pub struct FileDelayStore {
+    location: Location,
+}
+
+#[derive(Clone)]
+enum Location {
+    Fixed(PathBuf),
+    PerInstance(Arc<InstanceDir>),
+}
+
+impl FileDelayStore {
+    /// Use `dir` for entries, creating it if needed.
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self, EventBusError> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).map_err(|e| {
+            EventBusError::Other(format!("cannot create delay store {}: {e}", dir.display()))
+        })?;
+        Ok(Self {
+            location: Location::Fixed(dir),
+        })
+    }
+
+    /// Keep entries in a slot directory under `parent` that belongs to this
+    /// store alone, for replicas sharing a host or volume.
+    ///
+    /// Nothing is written until the first entry is saved, so a bus that never
+    /// schedules anything needs no writable directory. The store then claims
+    /// the first free slot (`parent/0`, `parent/1`, ...) by locking its
+    /// `.lock` file for as long as the store lives. When `parent` exists,
+    /// [`load_all`](DelayStore::load_all) claims a slot too and first moves
+    /// in the entries of every slot whose owner is gone. Relies on advisory
+    /// file locks, so `parent` must not be on a network file system that
+    /// ignores them.
+    pub fn per_instance(parent: impl Into<PathBuf>) -> Self {
+        Self {
+            location: Location::PerInstance(Arc::new(InstanceDir {
+                parent: parent.into(),
+                slot: Mutex::new(None),
+            })),
+        }
+    }
+}
+
+impl Location {
+    /// The entry directory, claiming a slot for a per-instance store.
+    fn dir(&self) -> Result<PathBuf, EventBusError> {
+        match self {
+            Location::Fixed(dir) => Ok(dir.clone()),
+            Location::PerInstance(instance) => instance.claim(),
+        }
+    }
+
+    /// The entry directory if entries may already be in it.
+    fn existing_dir(&self) -> Option<PathBuf> {
+        match self {
+            Location::Fixed(dir) => Some(dir.clone()),
+            Location::PerInstance(instance) => instance.claimed(),
+        }
+    }
+}
+
+/// Lazily claimed slot of a [`FileDelayStore::per_instance`] store.
+struct InstanceDir {
+    parent: PathBuf,
+    /// The claimed slot directory and its locked `.lock` file.
+    slot: Mutex<Option<(PathBuf, std::fs::File)>>,
+}
+
+impl InstanceDir {
+    fn claimed(&self) -> Option<PathBuf> {
+        let slot = self.slot.lock().unwrap_or_else(|e| e.into_inner());
+        slot.as_ref().map(|(dir, _)| dir.clone())
+    }
+
+    /// Claim the first free slot, unless this store already holds one.
+    fn claim(&self) -> Result<PathBuf, EventBusError> {
+        let mut slot = self.slot.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((dir, _)) = slot.as_ref() {
+            return Ok(dir.clone());
+        }
+        std::fs::create_dir_all(&self.parent).map_err(|e| {
+            EventBusError::Other(format!(
+                "cannot create delay store {}: {e}",
+                self.parent.display()
+            ))
+        })?;
+        let mut n = 0;
+        let lock = loop {
+            match try_lock_slot(&self.parent, n)? {
+                Some(lock) => break lock,
+                None => n += 1,
+            }
+        };
+        let dir = self.parent.join(n.to_string());
+        std::fs::create_dir_all(&dir).map_err(io_error)?;
+        *slot = Some((dir.clone(), lock));
+        Ok(dir)
+    }
+
+    /// Claim a slot if a previous process may have left entries under
+    /// `parent`, and move the entries of every slot whose owner is gone into
+    /// it. `None` when there is nothing to recover.
+    fn recover(&self) -> Result<Option<PathBuf>, EventBusError> {
+        if self.claimed().is_none() && !self.parent.is_dir() {
+            return Ok(None);
+        }
+        let dir = self.claim()?;
+        for item in std::fs::read_dir(&self.parent).map_err(io_error)? {
+            let path = item.map_err(io_error)?.path();
+            let Some(n) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.parse::<usize>().ok())
+            else {
+                continue;
+            };
+            if path == dir || !path.is_dir() {
+                continue;
+            }
+            // Held until the entries are moved, so two instances never adopt
+            // the same slot.
+            let Some(_lock) = try_lock_slot(&self.parent, n)? else {
+                continue;
+            };
+            for entry in std::fs::read_dir(&path).map_err(io_error)? {
+                let from = entry.map_err(io_error)?.path();
+                if from.extension().is_some_and(|ext| ext == "json") {
+                    if let Some(name) = from.file_name() {
+                        std::fs::rename(&from, dir.join(name)).map_err(io_error)?;
+                    }
+                }
+            }
+        }
+        Ok(Some(dir))
+    }
+}
+
+/// Lock slot `n` of `parent`, or `None` if another store holds it.
+fn try_lock_slot(parent: &Path, n: usize) -> Result<Option<std::fs::File>, EventBusError> {
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(parent.join(format!("{n}.lock")))
+        .map_err(io_error)?;
+    match file.try_lock() {
+        Ok(()) => Ok(Some(file)),
+        Err(std::fs::TryLockError::WouldBlock) => Ok(None),
+        Err(std::fs::TryLockError::Error(e)) => Err(io_error(e)),
+    }
+}
+
+fn entry_path(dir: &Path, id: u128) -> PathBuf {
+    dir.join(format!("{id:032x}.json"))
+}
+
+fn io_error(e: std::io::Error) -> EventBusError {
+    EventBusError::Other(format!("delay store I/O error: {e}"))
+}
+
+async fn blocking<T, F>(f: F) -> Result<T, EventBusError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, EventBusError> + Send + 'static,
+{
+    r2e_core::rt::spawn_blocking(f)
+        .await
+        .map_err(|e| EventBusError::Other(format!("delay store task failed: {e}")))?
+}
+
+impl DelayStore for FileDelayStore {
+    fn save<'a>(&'a self, entry: &'a DelayedEntry) -> StoreFuture<'a, ()> {
+        let location = self.location.clone();
+        let id = entry.id;
+        let bytes = serde_json::to_vec(entry);
+        Box::pin(async move {
+            let bytes = bytes.map_err(|e| EventBusError::Serialization(e.to_string()))?;
+            blocking(move || {
+                let path = entry_path(&location.dir()?, id);
+                let tmp = path.with_extension("json.tmp");
+                std::fs::write(&tmp, bytes).map_err(io_error)?;
+                std::fs::rename(&tmp, &path).map_err(io_error)
+            })
+            .await
+        })
+    }
+
+    fn remove(&self, id: u128) -> StoreFuture<'_, ()> {
+        let location = self.location.clone();
+        Box::pin(blocking(move || {
+            // A per-instance store without a slot has saved nothing.
+            let Some(dir) = location.existing_dir() else {
+                return Ok(());
+            };
+            match std::fs::remove_file(entry_path(&dir, id)) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(io_error(e)),
+                _ => Ok(()),
+            }
+        }))
+    }
+
+    fn load_all(&self) -> StoreFuture<'_, Vec<DelayedEntry>> {
+        let location = self.location.clone();
+        Box::pin(blocking(move || {
+            let dir = match &location {
+                Location::Fixed(dir) => dir.clone(),
+                Location::PerInstance(instance) => match instance.recover()? {
+                    Some(dir) => dir,
+                    None => return Ok(Vec::new()),
+                },
+            };
+            let mut entries = Vec::new();
+            for item in std::fs::read_dir(&dir).map_err(io_error)? {
+                let path = item.map_err(io_error)?.path();
+                if path.extension().is_none_or(|ext| ext != "json") {
+                    continue;
+                }
+                let parsed = std::fs::read(&path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|bytes| {
+                        serde_json::from_slice::<DelayedEntry>(&bytes).map_err(|e| e.to_string())
+                    });
+                match parsed {
+                    Ok(entry) => entries.push(entry),
+                    Err(e) => {
+                        tracing::warn!(path = %path.display(), error = %e, "skipping unreadable delayed entry")
+                    }
+                }
+            }
+            Ok(entries)
+        }))
+    }
+}
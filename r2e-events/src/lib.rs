@@ -29,6 +29,25 @@
 //! the matching handlers' configured dead-letter topics (when any) and then
 //! acked — never redelivered forever; a panicking handler counts as a
 //! `Nack`. There is a single delivery path per consumer group.
+//!
+//! # Delayed delivery
+//!
+//! **`emit_after` / `emit_at`** publish an event later and return a
+//! [`ScheduledEmit`] handle that can cancel it while pending. See [`delay`]
+//! for how each backend holds the event until it is due.
+//!
+//! # Ordered consumers
+//!
+//! Handlers run concurrently, so events of one aggregate can complete out of
+//! order. [`EventBus::configure_ordering`] (`#[consumer(ordered_by = ...)]`)
+//! runs a handler's events sharing a key strictly in sequence — see
+//! [`ordering`].
+//!
+//! # Context propagation
+//!
+//! Emitting stamps the current trace context, request id and authenticated
+//! subject into [`EventMetadata::headers`]; consumers run inside a child span
+//! with that context restored. See [`propagation`].
 
 use std::collections::HashMap;
 use std::fmt;
@@ -41,10 +60,20 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 pub mod backend;
+pub mod delay;
 mod local;
+pub mod observer;
+pub mod ordering;
+pub mod propagation;
 pub mod sse_bridge;
 
+pub use delay::{
+    DelayMetrics, DelayStore, DelayedEntry, FileDelayStore, InMemoryDelayStore, ScheduledEmit,
+    DEFAULT_DELAY_DIR,
+};
 pub use local::{LocalEventBus, DEFAULT_MAX_CONCURRENCY};
+pub use observer::{EventObserver, ObservedDeadLetter, ObservedEmit, ObservedHandling};
+pub use ordering::OrderedBy;
 pub use sse_bridge::SseBridgeExt;
 
 // ── EventBusError ──────────────────────────────────────────────────────
@@ -355,7 +384,7 @@ pub struct EventEnvelope<E> {
 // ── HandlerResult ──────────────────────────────────────────────────────
 
 /// Result returned by event handlers for ack/nack semantics.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum HandlerResult {
     /// Handler processed the event successfully.
     Ack,
@@ -378,6 +407,17 @@ impl<E: fmt::Display> From<Result<(), E>> for HandlerResult {
     }
 }
 
+/// `#[transactional]` consumers: `Ack` commits, `Nack` rolls back.
+impl r2e_core::transaction::TransactionOutcome for HandlerResult {
+    fn should_commit(&self) -> bool {
+        matches!(self, HandlerResult::Ack)
+    }
+
+    fn from_tx_error(error: r2e_core::transaction::TxError) -> Self {
+        HandlerResult::Nack(error.to_string())
+    }
+}
+
 // ── EventFilter ───────────────────────────────────────────────────────
 
 /// A predicate that decides whether a handler should process a given event.
@@ -426,6 +466,16 @@ impl RetryPolicy {
         self.dead_letter_topic = Some(topic.into());
         self
     }
+
+    /// Delay before retry number `retry` (0-based): `retry_delay`, doubled per
+    /// retry when `exponential_backoff` is set.
+    pub fn backoff(&self, retry: u32) -> std::time::Duration {
+        if self.exponential_backoff {
+            self.retry_delay * 2u32.saturating_pow(retry)
+        } else {
+            self.retry_delay
+        }
+    }
 }
 
 // ── DlqPublisher ──────────────────────────────────────────────────────
@@ -488,6 +538,20 @@ pub trait EventBus: Clone + Send + Sync + 'static {
         async {}
     }
 
+    /// Process a handler's events sharing an ordering key one at a time, in
+    /// dispatch order (events with different keys still run concurrently).
+    ///
+    /// Called by generated code for `#[consumer(ordered_by = "...")]`; see
+    /// [`ordering`] for how a `Nack` holds back the rest of its key. The
+    /// default is a no-op (handlers stay fully concurrent).
+    fn configure_ordering<E: 'static>(
+        &self,
+        _handler_id: SubscriptionId,
+        _ordered_by: OrderedBy,
+    ) -> impl Future<Output = ()> + Send {
+        async {}
+    }
+
     /// Subscribe to events of type `E`.
     ///
     /// The handler receives an [`EventEnvelope<E>`] and returns a [`HandlerResult`].
@@ -589,6 +653,56 @@ pub trait EventBus: Clone + Send + Sync + 'static {
         }
     }
 
+    /// Publish an event once `delay` has elapsed.
+    ///
+    /// Returns a [`ScheduledEmit`] handle that can cancel the delivery while it
+    /// is pending. Metadata is generated now; the handle's id is its
+    /// `event_id`. See [`emit_at_with`](EventBus::emit_at_with).
+    fn emit_after<E>(
+        &self,
+        event: E,
+        delay: Duration,
+    ) -> impl Future<Output = Result<ScheduledEmit, EventBusError>> + Send
+    where
+        E: Serialize + Send + Sync + 'static,
+    {
+        self.emit_at_with(event, SystemTime::now() + delay, EventMetadata::new())
+    }
+
+    /// Publish an event at `at` (immediately when `at` is in the past).
+    ///
+    /// See [`emit_at_with`](EventBus::emit_at_with).
+    fn emit_at<E>(
+        &self,
+        event: E,
+        at: SystemTime,
+    ) -> impl Future<Output = Result<ScheduledEmit, EventBusError>> + Send
+    where
+        E: Serialize + Send + Sync + 'static,
+    {
+        self.emit_at_with(event, at, EventMetadata::new())
+    }
+
+    /// Publish an event with explicit metadata at `at`.
+    ///
+    /// Errors returned here are pre-flight failures (serialization, shutdown,
+    /// delay-store write). A publication that fails once the event is due is
+    /// logged and counted in the bus's [`DelayMetrics`].
+    ///
+    /// There is no default implementation: a bus owns the cancellation token
+    /// and [`DelayMetrics`] its delayed emits belong to, so that shutdown
+    /// cancels them and `delay_metrics()` counts them. `LocalEventBus` arms
+    /// in-process timers; distributed backends use a broker-native delay or a
+    /// persistent [`backend::DelayQueue`].
+    fn emit_at_with<E>(
+        &self,
+        event: E,
+        at: SystemTime,
+        metadata: EventMetadata,
+    ) -> impl Future<Output = Result<ScheduledEmit, EventBusError>> + Send
+    where
+        E: Serialize + Send + Sync + 'static;
+
     /// Send a point-to-point request and await the responder's reply.
     ///
     /// Exactly one responder (registered via [`respond`]) handles the request
@@ -678,8 +792,8 @@ pub mod prelude {
     pub use crate::backend::DeserializerFn;
     pub use crate::sse_bridge::SseBridgeExt;
     pub use crate::{
-        EmitReceipt, Event, EventBus, EventBusError, EventEnvelope, EventFilter, EventMetadata,
-        HandlerResult, LocalEventBus, RequestOptions, ResponderHandle, RetryPolicy,
-        SubscriptionHandle, SubscriptionId, DEFAULT_REQUEST_TIMEOUT,
+        DelayMetrics, EmitReceipt, Event, EventBus, EventBusError, EventEnvelope, EventFilter,
+        EventMetadata, HandlerResult, LocalEventBus, RequestOptions, ResponderHandle, RetryPolicy,
+        ScheduledEmit, SubscriptionHandle, SubscriptionId, DEFAULT_REQUEST_TIMEOUT,
     };
 }
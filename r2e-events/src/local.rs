@@ -5,12 +5,20 @@ use std::pin::Pin;
 use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 
-use arc_swap::ArcSwap;
-use tokio::sync::{Notify, RwLock, Semaphore};
+use std::time::SystemTime;
 
+use arc_swap::{ArcSwap, ArcSwapOption};
+use tokio::sync::{Notify, OwnedSemaphorePermit, RwLock, Semaphore};
+use tokio_util::sync::CancellationToken;
+
+use crate::delay::{self, DelayMetrics};
+use crate::observer::{EventObserver, ObservedDeadLetter, ObservedEmit, ObservedHandling};
+use crate::ordering::{KeyedSequencer, OrderedBy};
+use crate::propagation;
 use crate::{
     EmitReceipt, EventBus, EventBusError, EventEnvelope, EventMetadata, HandlerResult,
-    RequestOptions, ResponderHandle, SubscriptionHandle, SubscriptionId,
+    RequestOptions, ResponderHandle, RetryPolicy, ScheduledEmit, SubscriptionHandle,
+    SubscriptionId,
 };
 
 use crate::EventFilter;
@@ -41,8 +49,13 @@ struct HandlerEntry {
     id: u64,
     handler: Handler,
     filter: Option<EventFilter>,
+    retry: Option<RetryPolicy>,
+    ordering: Option<Arc<KeyedSequencer>>,
 }
 
+/// Shared observer slot (boxed: `ArcSwapOption` needs a sized pointee).
+type ObserverSlot = Arc<ArcSwapOption<Box<dyn EventObserver>>>;
+
 /// Default maximum concurrent handlers.
 pub const DEFAULT_MAX_CONCURRENCY: usize = 1024;
 
@@ -53,10 +66,22 @@ pub const DEFAULT_MAX_CONCURRENCY: usize = 1024;
 ///
 /// Backpressure is enforced via a semaphore that limits the number of
 /// concurrently executing handlers. When the limit is reached, `emit()`
-/// will block until a slot becomes available.
+/// will block until a slot becomes available. A handler waiting out a retry
+/// backoff does not hold a slot.
 ///
 /// `LocalEventBus` is `Clone` and can be shared across threads.
 ///
+/// Delayed emits (`emit_after` / `emit_at`) use in-process timers: they can
+/// be cancelled, are dropped on [`shutdown`](EventBus::shutdown), and do not
+/// survive a restart.
+///
+/// Filters and retry policies attached by `#[consumer(filter = ..., retry = N)]`
+/// apply as on the distributed backends. There is no dead-letter destination
+/// in-process: an event that exhausts its retries is logged and reported to
+/// the [`EventObserver`], if any. Handlers configured with
+/// `#[consumer(ordered_by = ...)]` process events sharing a key one at a time;
+/// a `Nack` releases the next event of the key once retries are exhausted.
+///
 /// **Performance note:** The `Serialize`/`DeserializeOwned` bounds required by
 /// the [`EventBus`] trait are compile-time only. `LocalEventBus` never
 /// serializes events — dispatch uses `Arc<dyn Any>` downcasting internally.
@@ -70,6 +95,11 @@ pub struct LocalEventBus {
     shutdown: Arc<AtomicBool>,
     in_flight: Arc<AtomicUsize>,
     in_flight_zero: Arc<Notify>,
+    /// Parent of every pending delayed-emit timer; cancelled on shutdown.
+    delay_cancel: CancellationToken,
+    delay_metrics: DelayMetrics,
+    /// Shared by every clone, so an observer set on one sees them all.
+    observer: ObserverSlot,
 }
 
 /// Drop-based guard that decrements in_flight and notifies when it reaches zero.
@@ -106,6 +136,9 @@ impl LocalEventBus {
             shutdown: Arc::new(AtomicBool::new(false)),
             in_flight: Arc::new(AtomicUsize::new(0)),
             in_flight_zero: Arc::new(Notify::new()),
+            delay_cancel: CancellationToken::new(),
+            delay_metrics: DelayMetrics::new(),
+            observer: Arc::new(ArcSwapOption::empty()),
         }
     }
 
@@ -122,6 +155,9 @@ impl LocalEventBus {
             shutdown: Arc::new(AtomicBool::new(false)),
             in_flight: Arc::new(AtomicUsize::new(0)),
             in_flight_zero: Arc::new(Notify::new()),
+            delay_cancel: CancellationToken::new(),
+            delay_metrics: DelayMetrics::new(),
+            observer: Arc::new(ArcSwapOption::empty()),
         }
     }
 
@@ -132,6 +168,39 @@ impl LocalEventBus {
             .map(|s| s.available_permits() + self.active_handlers())
     }
 
+    /// Counters for delayed emits (`emit_after` / `emit_at`).
+    pub fn delay_metrics(&self) -> &DelayMetrics {
+        &self.delay_metrics
+    }
+
+    /// Install an [`EventObserver`] (builder form of
+    /// [`set_observer`](Self::set_observer)).
+    pub fn with_observer(self, observer: impl EventObserver) -> Self {
+        self.set_observer(observer);
+        self
+    }
+
+    /// Install an [`EventObserver`], replacing any previous one.
+    ///
+    /// The observer is shared by every clone of this bus — including the ones
+    /// already handed out as beans — and sees emits from now on.
+    pub fn set_observer(&self, observer: impl EventObserver) {
+        self.observer
+            .store(Some(Arc::new(Box::new(observer) as Box<dyn EventObserver>)));
+    }
+
+    /// Remove the installed [`EventObserver`], if any.
+    pub fn clear_observer(&self) {
+        self.observer.store(None);
+    }
+
+    /// JSON rendering of `event` for the observer, or `None` when no observer
+    /// is installed (the common case pays nothing).
+    fn observed_payload<E: serde::Serialize>(&self, event: &E) -> Option<serde_json::Value> {
+        self.observer.load().as_ref()?;
+        Some(serde_json::to_value(event).unwrap_or(serde_json::Value::Null))
+    }
+
     /// Returns the number of currently active (executing) handlers.
     fn active_handlers(&self) -> usize {
         self.in_flight.load(Ordering::Acquire)
@@ -166,48 +235,74 @@ impl LocalEventBus {
     ///
     /// `make_metadata` is a thunk so that a zero-subscriber emit allocates
     /// nothing: [`EventMetadata::new`] runs only once we know at least one
-    /// handler is registered for `type_id`. The metadata is `Arc`-shared across
-    /// the fan-out, so each handler costs a pointer bump rather than a deep
-    /// clone (and all handlers of one emit observe the same `event_id`).
+    /// handler is registered for `type_id` (or an observer wants the emit).
+    /// The metadata is `Arc`-shared across the fan-out, so each handler costs
+    /// a pointer bump rather than a deep clone (and all handlers of one emit
+    /// observe the same `event_id`).
     async fn dispatch(
         &self,
         type_id: TypeId,
+        destination: &'static str,
         event: Arc<dyn Any + Send + Sync>,
+        payload: Option<serde_json::Value>,
         make_metadata: impl FnOnce() -> EventMetadata,
     ) -> Result<(), EventBusError> {
         if self.shutdown.load(Ordering::Acquire) {
             return Err(EventBusError::Shutdown);
         }
 
+        let observer = self.observer.load_full();
+
         // Load a lock-free snapshot of the handler map. Build the metadata (via
         // the thunk) only after confirming there is at least one handler for
         // this type, so a zero-subscriber emit is a true no-op that allocates
         // nothing. The metadata `Arc` is shared across the fan-out below.
-        let (metadata, handler_snapshot): (Arc<EventMetadata>, Vec<Handler>) = {
+        let (metadata, handler_snapshot): (Arc<EventMetadata>, Vec<HandlerEntry>) = {
             let map = self.handlers.load();
-            match map.get(&type_id) {
-                Some(entries) if !entries.is_empty() => {
-                    let metadata = Arc::new(make_metadata());
-                    let handlers = entries
-                        .iter()
-                        .filter(|entry| !entry.filter.as_ref().is_some_and(|f| !f(&metadata)))
-                        .map(|entry| entry.handler.clone())
-                        .collect();
-                    (metadata, handlers)
-                }
-                _ => return Ok(()),
+            let entries = map.get(&type_id).filter(|entries| !entries.is_empty());
+            if entries.is_none() && observer.is_none() {
+                return Ok(());
             }
+            let metadata = Arc::new(propagation::inject(make_metadata()));
+            let handlers = entries
+                .into_iter()
+                .flatten()
+                .filter(|entry| entry.filter.as_ref().is_none_or(|f| f(&metadata)))
+                .cloned()
+                .collect();
+            (metadata, handlers)
         };
 
-        for h in handler_snapshot {
+        if let Some(observer) = &observer {
+            observer.on_emit(&ObservedEmit {
+                type_id,
+                type_name: destination,
+                event: event.clone(),
+                payload: payload.unwrap_or_default(),
+                metadata: metadata.clone(),
+            });
+        }
+
+        for entry in handler_snapshot {
             let e = event.clone();
             let m = metadata.clone();
+            let observer = observer.clone();
             let in_flight = self.in_flight.clone();
             let in_flight_zero = self.in_flight_zero.clone();
 
-            let permit = match &self.semaphore {
-                Some(sem) => Some(sem.clone().acquire_owned().await.expect("semaphore closed")),
-                None => None,
+            let semaphore = self.semaphore.clone();
+
+            // Take the key's turn synchronously, in emit order. An ordered
+            // handler waits for room in its key's queue rather than for a
+            // handler slot: the slot is taken once the turn is ready, so a
+            // slow key does not hold slots other keys could use.
+            let mut turn = entry.ordering.as_ref().and_then(|seq| seq.enter(&m));
+            let permit = match &mut turn {
+                Some(turn) => {
+                    turn.reserve().await;
+                    None
+                }
+                None => acquire_permit(&semaphore).await,
             };
 
             // Increment AFTER acquiring the permit and immediately before
@@ -219,8 +314,24 @@ impl LocalEventBus {
                     in_flight,
                     in_flight_zero,
                 };
-                let result = h(e, m).await;
-                drop(permit);
+                let permit = match &mut turn {
+                    Some(turn) => {
+                        turn.ready().await;
+                        acquire_permit(&semaphore).await
+                    }
+                    None => permit,
+                };
+                // Invoke the handler inside the context so its synchronous
+                // prologue observes it too.
+                let meta = m.clone();
+                let result = propagation::in_consumer_context(
+                    propagation::LOCAL_MESSAGING_SYSTEM,
+                    destination,
+                    &meta,
+                    invoke_handler(entry, e, m, destination, observer, semaphore, permit),
+                )
+                .await;
+                drop(turn);
                 if let HandlerResult::Nack(ref reason) = result {
                     tracing::warn!("event handler returned Nack: {reason}");
                 }
@@ -231,7 +342,121 @@ impl LocalEventBus {
     }
 }
 
+/// Take a handler slot from the concurrency limit, if any.
+async fn acquire_permit(semaphore: &Option<Arc<Semaphore>>) -> Option<OwnedSemaphorePermit> {
+    match semaphore {
+        Some(sem) => Some(sem.clone().acquire_owned().await.expect("semaphore closed")),
+        None => None,
+    }
+}
+
+/// Run one handler for one event, retrying `Nack`s under the entry's
+/// [`RetryPolicy`] and reporting every attempt to the observer.
+///
+/// `permit` is the handler's concurrency slot. It is released during each
+/// retry backoff and taken again before the next attempt, so a backing-off
+/// handler does not hold back other events.
+async fn invoke_handler(
+    entry: HandlerEntry,
+    event: Arc<dyn Any + Send + Sync>,
+    metadata: Arc<EventMetadata>,
+    type_name: &'static str,
+    observer: Option<Arc<Box<dyn EventObserver>>>,
+    semaphore: Option<Arc<Semaphore>>,
+    mut permit: Option<OwnedSemaphorePermit>,
+) -> HandlerResult {
+    let mut attempt = 1;
+    loop {
+        let result = (entry.handler)(event.clone(), metadata.clone()).await;
+        if let Some(observer) = &observer {
+            observer.on_handled(&ObservedHandling {
+                subscription: SubscriptionId(entry.id),
+                type_name,
+                event_id: metadata.event_id,
+                attempt,
+                result: result.clone(),
+            });
+        }
+        let HandlerResult::Nack(reason) = &result else {
+            return result;
+        };
+        let Some(policy) = &entry.retry else {
+            return result;
+        };
+        if attempt > policy.max_retries {
+            if let Some(topic) = &policy.dead_letter_topic {
+                tracing::error!(
+                    event_type = type_name,
+                    topic = %topic,
+                    attempts = attempt,
+                    "event handler exhausted its retries; no dead-letter destination in-process"
+                );
+                if let Some(observer) = &observer {
+                    observer.on_dead_letter(&ObservedDeadLetter {
+                        subscription: SubscriptionId(entry.id),
+                        type_name,
+                        topic: topic.clone(),
+                        metadata: metadata.clone(),
+                        reason: reason.clone(),
+                    });
+                }
+            }
+            return result;
+        }
+        drop(permit.take());
+        r2e_core::rt::sleep(policy.backoff(attempt - 1)).await;
+        permit = acquire_permit(&semaphore).await;
+        tracing::debug!(
+            attempt = attempt + 1,
+            max = policy.max_retries,
+            "retrying event handler"
+        );
+        attempt += 1;
+    }
+}
+
 impl EventBus for LocalEventBus {
+    fn configure_handler<E: 'static>(
+        &self,
+        handler_id: SubscriptionId,
+        filter: Option<EventFilter>,
+        retry_policy: Option<RetryPolicy>,
+    ) -> impl Future<Output = ()> + Send {
+        let type_id = TypeId::of::<E>();
+        self.handlers.rcu(|map| {
+            let mut new_map = HashMap::clone(map);
+            if let Some(entry) = new_map
+                .get_mut(&type_id)
+                .and_then(|entries| entries.iter_mut().find(|e| e.id == handler_id.0))
+            {
+                entry.filter = filter.clone();
+                entry.retry = retry_policy.clone();
+            }
+            new_map
+        });
+        async {}
+    }
+
+    fn configure_ordering<E: 'static>(
+        &self,
+        handler_id: SubscriptionId,
+        ordered_by: OrderedBy,
+    ) -> impl Future<Output = ()> + Send {
+        let type_id = TypeId::of::<E>();
+        let sequencer = Arc::new(KeyedSequencer::new(ordered_by));
+        self.handlers.rcu(|map| {
+            let mut new_map = HashMap::clone(map);
+            if let Some(entry) = new_map
+                .get_mut(&type_id)
+                .and_then(|entries| entries.iter_mut().find(|e| e.id == handler_id.0))
+            {
+                entry.ordering = Some(sequencer.clone());
+            }
+            new_map
+        });
+        async {}
+    }
+
     fn subscribe<E, F, Fut>(
         &self,
         handler: F,
@@ -265,6 +490,8 @@ impl EventBus for LocalEventBus {
                     id,
                     handler: h.clone(),
                     filter: None,
+                    retry: None,
+                    ordering: None,
                 });
                 new_map
             });
@@ -287,9 +514,16 @@ impl EventBus for LocalEventBus {
         E: serde::Serialize + Send + Sync + 'static,
     {
         let type_id = TypeId::of::<E>();
+        let payload = self.observed_payload(&event);
         let event = Arc::new(event) as Arc<dyn Any + Send + Sync>;
         // Lazy: `EventMetadata::new()` only runs if `dispatch` finds a handler.
-        self.dispatch(type_id, event, EventMetadata::new)
+        self.dispatch(
+            type_id,
+            std::any::type_name::<E>(),
+            event,
+            payload,
+            EventMetadata::new,
+        )
     }
 
     fn emit_with<E>(
@@ -301,10 +535,17 @@ impl EventBus for LocalEventBus {
         E: serde::Serialize + Send + Sync + 'static,
     {
         let type_id = TypeId::of::<E>();
+        let payload = self.observed_payload(&event);
         let event = Arc::new(event) as Arc<dyn Any + Send + Sync>;
         // Caller supplied the metadata; hand it over as a ready thunk. (If there
         // are no subscribers it is simply dropped, as before.)
-        self.dispatch(type_id, event, move || metadata)
+        self.dispatch(
+            type_id,
+            std::any::type_name::<E>(),
+            event,
+            payload,
+            move || metadata,
+        )
     }
 
     fn emit_nowait<E>(
@@ -336,6 +577,30 @@ impl EventBus for LocalEventBus {
         }
     }
 
+    fn emit_at_with<E>(
+        &self,
+        event: E,
+        at: SystemTime,
+        metadata: EventMetadata,
+    ) -> impl Future<Output = Result<ScheduledEmit, EventBusError>> + Send
+    where
+        E: serde::Serialize + Send + Sync + 'static,
+    {
+        let bus = self.clone();
+        async move {
+            if bus.shutdown.load(Ordering::Acquire) {
+                return Err(EventBusError::Shutdown);
+            }
+            // Capture the caller's context now; the timer task has none.
+            let metadata = propagation::inject(metadata);
+            let id = metadata.event_id;
+            let parent = bus.delay_cancel.clone();
+            let metrics = bus.delay_metrics.clone();
+            let fire = async move { bus.emit_with(event, metadata).await };
+            Ok(delay::spawn_in_process(id, at, &parent, metrics, fire))
+        }
+    }
+
     fn request_with<Req, Resp>(
         &self,
         req: Req,
@@ -361,7 +626,7 @@ impl EventBus for LocalEventBus {
             }
             .ok_or(EventBusError::NoResponder)?;
 
-            let metadata = Arc::new(options.metadata.unwrap_or_default());
+            let metadata = Arc::new(propagation::inject(options.metadata.unwrap_or_default()));
             let req_any = Arc::new(req) as Arc<dyn Any + Send + Sync>;
 
             // Invoke on the control plane with in-flight tracking, mirroring the
@@ -372,7 +637,14 @@ impl EventBus for LocalEventBus {
                     in_flight,
                     in_flight_zero,
                 };
-                responder(req_any, metadata).await
+                let meta = metadata.clone();
+                propagation::in_consumer_context(
+                    propagation::LOCAL_MESSAGING_SYSTEM,
+                    std::any::type_name::<Req>(),
+                    &meta,
+                    async move { responder(req_any, metadata).await },
+                )
+                .await
             });
 
             match r2e_core::rt::timeout(options.timeout, handle).await {
@@ -460,8 +732,10 @@ impl EventBus for LocalEventBus {
         let in_flight = self.in_flight.clone();
         let in_flight_zero = self.in_flight_zero.clone();
         let handlers = self.handlers.clone();
+        let delay_cancel = self.delay_cancel.clone();
         async move {
             shutdown.store(true, Ordering::Release);
+            delay_cancel.cancel();
 
             if in_flight.load(Ordering::Acquire) > 0 {
                 let wait = async {
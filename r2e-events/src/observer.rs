@@ -0,0 +1,99 @@
+//! Taps on a [`LocalEventBus`](crate::LocalEventBus)'s traffic.
+//!
+//! An [`EventObserver`] installed with
+//! [`LocalEventBus::with_observer`](crate::LocalEventBus::with_observer) sees
+//! every emit (with its metadata and a JSON rendering of the payload), every
+//! handler attempt and its [`HandlerResult`], and every event that exhausts
+//! its [`RetryPolicy`](crate::RetryPolicy). The test harness in `r2e-test`
+//! records through this hook; it is equally usable for debugging taps.
+//!
+//! Observers run synchronously on the emitting / handling task, so keep them
+//! cheap. The payload is serialized only when an observer is installed.
+
+use std::any::{Any, TypeId};
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{EventMetadata, HandlerResult, SubscriptionId};
+
+/// Receives notifications about a bus's traffic. Every method defaults to a
+/// no-op.
+pub trait EventObserver: Send + Sync + 'static {
+    /// An event was emitted — called once per emit, whether or not any
+    /// handler is subscribed.
+    fn on_emit(&self, _emit: &ObservedEmit) {}
+
+    /// A handler attempt finished. Retried handlers report every attempt.
+    fn on_handled(&self, _handled: &ObservedHandling) {}
+
+    /// A handler exhausted its retry policy and the policy names a
+    /// dead-letter topic.
+    fn on_dead_letter(&self, _dead_letter: &ObservedDeadLetter) {}
+}
+
+/// One emitted event, as seen by an [`EventObserver`].
+#[derive(Clone)]
+pub struct ObservedEmit {
+    /// `TypeId` of the event type.
+    pub type_id: TypeId,
+    /// `std::any::type_name` of the event type.
+    pub type_name: &'static str,
+    /// The event value itself — see [`downcast`](Self::downcast).
+    pub event: Arc<dyn Any + Send + Sync>,
+    /// JSON rendering of the event (`Null` if it failed to serialize).
+    pub payload: serde_json::Value,
+    /// Metadata handed to the handlers (same `event_id`).
+    pub metadata: Arc<EventMetadata>,
+}
+
+impl ObservedEmit {
+    /// Whether the event is of type `E`.
+    pub fn is<E: 'static>(&self) -> bool {
+        self.type_id == TypeId::of::<E>()
+    }
+
+    /// The event as an `E`, or `None` if it is of another type.
+    pub fn downcast<E: Send + Sync + 'static>(&self) -> Option<Arc<E>> {
+        self.event.clone().downcast::<E>().ok()
+    }
+}
+
+impl fmt::Debug for ObservedEmit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObservedEmit")
+            .field("type_name", &self.type_name)
+            .field("payload", &self.payload)
+            .field("metadata", &self.metadata)
+            .finish()
+    }
+}
+
+/// One handler attempt and its outcome.
+#[derive(Debug, Clone)]
+pub struct ObservedHandling {
+    /// The subscription that handled the event.
+    pub subscription: SubscriptionId,
+    /// `std::any::type_name` of the event type.
+    pub type_name: &'static str,
+    /// `event_id` of the emit being handled.
+    pub event_id: u128,
+    /// 1-based attempt number (greater than 1 only under a retry policy).
+    pub attempt: u32,
+    /// What the handler returned.
+    pub result: HandlerResult,
+}
+
+/// An event whose handler failed every attempt allowed by its retry policy.
+#[derive(Debug, Clone)]
+pub struct ObservedDeadLetter {
+    /// The subscription that gave up.
+    pub subscription: SubscriptionId,
+    /// `std::any::type_name` of the event type.
+    pub type_name: &'static str,
+    /// The policy's dead-letter topic.
+    pub topic: String,
+    /// Metadata of the failed event.
+    pub metadata: Arc<EventMetadata>,
+    /// The last `Nack` reason.
+    pub reason: String,
+}
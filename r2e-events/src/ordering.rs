@@ -0,0 +1,288 @@
+//! Per-key ordered dispatch for `#[consumer(ordered_by = "...")]`.
+//!
+//! Handlers normally run concurrently (up to the bus's concurrency limit), so
+//! two events for the same aggregate can be applied out of order even when
+//! the broker partition delivers them in order. A handler configured with an
+//! [`OrderedBy`] key gets a [`KeyedSequencer`]: events sharing a key run
+//! strictly one after another, in dispatch order, while different keys — and
+//! events without a key — still run in parallel.
+//!
+//! # Nack and redelivery (distributed backends)
+//!
+//! A `Nack` that is not captured to a dead-letter topic leaves the message
+//! uncommitted ([`WatermarkTracker`](crate::backend::WatermarkTracker) pins
+//! the partition at it) so the broker redelivers it. Running the *next*
+//! event of the same key in the meantime would apply it before its
+//! predecessor, so the key is **blocked**: later events of that key are
+//! nacked without running the handler (they sit above the pinned watermark
+//! and are redelivered too) until the failed event itself comes back — it is
+//! recognised by its `event_id` — and acks. A panicking handler blocks its
+//! key the same way. Blocking is per handler and in memory: a restart starts
+//! clean, and a backend lifts every block when a new consumer session or
+//! partition assignment begins ([`BackendState::reset_ordering`]), since the
+//! failed event may then be redelivered to another consumer.
+//!
+//! [`BackendState::reset_ordering`]: crate::backend::BackendState::reset_ordering
+//!
+//! `LocalEventBus` has no redelivery, so there a `Nack` (after retries)
+//! simply releases the next event of the key.
+//!
+//! # Concurrency
+//!
+//! An event waiting for its key's turn does not hold one of the bus's
+//! handler slots: the slot is taken once the turn is ready, so a slow key
+//! cannot starve the others. The number of events queued per key is instead
+//! bounded by [`KeyedSequencer::with_max_waiting`] (default
+//! [`DEFAULT_MAX_WAITING_PER_KEY`]); once a key is full, dispatch waits for
+//! its queue to drain.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use tokio::sync::{oneshot, OwnedSemaphorePermit, Semaphore};
+
+use crate::EventMetadata;
+
+/// Which part of an event's metadata forms its ordering key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderedBy {
+    /// [`EventMetadata::partition_key`] — the key the broker partitions by.
+    PartitionKey,
+    /// The value of a metadata header.
+    Header(String),
+}
+
+impl OrderedBy {
+    /// Parse the `#[consumer(ordered_by = "...")]` syntax: `"partition_key"`
+    /// or `"header:<name>"`.
+    pub fn parse(spec: &str) -> Option<Self> {
+        match spec.split_once(':') {
+            None if spec == "partition_key" => Some(Self::PartitionKey),
+            Some(("header", name)) if !name.is_empty() => Some(Self::Header(name.to_string())),
+            _ => None,
+        }
+    }
+
+    /// The ordering key of an event, or `None` when it has none (such events
+    /// are not sequenced).
+    pub fn key(&self, metadata: &EventMetadata) -> Option<String> {
+        match self {
+            Self::PartitionKey => metadata.partition_key.clone(),
+            Self::Header(name) => metadata.headers.get(name).cloned(),
+        }
+    }
+}
+
+impl fmt::Display for OrderedBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PartitionKey => f.write_str("partition_key"),
+            Self::Header(name) => write!(f, "header:{name}"),
+        }
+    }
+}
+
+/// Default number of events that may be queued for one key.
+pub const DEFAULT_MAX_WAITING_PER_KEY: usize = 1024;
+
+/// The last turn handed out for a key.
+struct Tail {
+    seq: u64,
+    /// The signal its successor waits on.
+    signal: oneshot::Receiver<()>,
+    /// Bounds the turns queued for the key; shared by all of them.
+    queue: Arc<Semaphore>,
+}
+
+#[derive(Default)]
+struct Lanes {
+    /// Per key: the last turn handed out. Removed when that turn ends with no
+    /// successor.
+    tails: HashMap<String, Tail>,
+    /// Keys blocked by a failed event: that event's `event_id` and the
+    /// ordering epoch it was blocked in (blocks from an earlier epoch are
+    /// stale).
+    blocked: HashMap<String, (u128, u64)>,
+    next_seq: u64,
+}
+
+/// Serializes one handler's events per ordering key.
+///
+/// [`enter`](Self::enter) must be called in dispatch order (synchronously,
+/// before spawning the handler task); the returned [`Turn`] is then awaited
+/// inside the task.
+pub struct KeyedSequencer {
+    ordered_by: OrderedBy,
+    max_waiting: usize,
+    lanes: Mutex<Lanes>,
+}
+
+impl KeyedSequencer {
+    pub fn new(ordered_by: OrderedBy) -> Self {
+        Self {
+            ordered_by,
+            max_waiting: DEFAULT_MAX_WAITING_PER_KEY,
+            lanes: Mutex::new(Lanes::default()),
+        }
+    }
+
+    /// Bound the events queued for one key, the running one included
+    /// (see [`Turn::reserve`]).
+    pub fn with_max_waiting(mut self, max_waiting: usize) -> Self {
+        self.max_waiting = max_waiting.max(1);
+        self
+    }
+
+    /// The key definition this sequencer orders by.
+    pub fn ordered_by(&self) -> &OrderedBy {
+        &self.ordered_by
+    }
+
+    fn lanes(&self) -> MutexGuard<'_, Lanes> {
+        self.lanes.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Queue an event behind the previous one with the same key. Returns
+    /// `None` for an event without a key (it runs unordered).
+    pub fn enter(self: &Arc<Self>, metadata: &EventMetadata) -> Option<Turn> {
+        let key = self.ordered_by.key(metadata)?;
+        let (done, signal) = oneshot::channel();
+        let mut lanes = self.lanes();
+        let seq = lanes.next_seq;
+        lanes.next_seq += 1;
+        let queue = match lanes.tails.get(&key) {
+            Some(tail) => tail.queue.clone(),
+            None => Arc::new(Semaphore::new(self.max_waiting)),
+        };
+        let previous = lanes.tails.insert(
+            key.clone(),
+            Tail {
+                seq,
+                signal,
+                queue: queue.clone(),
+            },
+        );
+        Some(Turn {
+            sequencer: self.clone(),
+            key,
+            seq,
+            previous: previous.map(|tail| tail.signal),
+            _done: done,
+            queue,
+            reserved: None,
+            pending: None,
+        })
+    }
+
+    /// Whether `key` is blocked by a failed event.
+    pub fn is_blocked(&self, key: &str) -> bool {
+        self.lanes().blocked.contains_key(key)
+    }
+}
+
+/// An event's place in its key's queue. Dropping it lets the next event of
+/// the key run.
+pub struct Turn {
+    sequencer: Arc<KeyedSequencer>,
+    key: String,
+    seq: u64,
+    previous: Option<oneshot::Receiver<()>>,
+    /// Dropped with the turn, which wakes the successor.
+    _done: oneshot::Sender<()>,
+    queue: Arc<Semaphore>,
+    /// This turn's place in `queue`, held until the turn is dropped.
+    reserved: Option<OwnedSemaphorePermit>,
+    /// `(event_id, epoch)` admitted by [`admit`](Self::admit) and not yet
+    /// [`finish`](Self::finish)ed.
+    pending: Option<(u128, u64)>,
+}
+
+impl Turn {
+    /// The ordering key.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Wait for room in the key's queue. Called by the dispatcher before it
+    /// spawns the handler task, so a key that falls behind applies
+    /// backpressure instead of accumulating waiting tasks.
+    pub async fn reserve(&mut self) {
+        if self.reserved.is_none() {
+            let permit = self
+                .queue
+                .clone()
+                .acquire_owned()
+                .await
+                .expect("semaphore closed");
+            self.reserved = Some(permit);
+        }
+    }
+
+    /// Wait until every earlier event of the key has finished.
+    pub async fn ready(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            // `Err` (sender dropped) is the only outcome: the predecessor is done.
+            let _ = previous.await;
+        }
+    }
+
+    /// Check the key is not blocked by another failed event (distributed
+    /// backends only). `epoch` is the backend's current ordering epoch;
+    /// blocks recorded in an earlier one are dropped. On `true` the handler
+    /// may run and its outcome must be reported with
+    /// [`finish`](Self::finish); a turn dropped before that — a panicking
+    /// handler — blocks the key.
+    pub fn admit(&mut self, event_id: u128, epoch: u64) -> bool {
+        let mut lanes = self.sequencer.lanes();
+        match lanes.blocked.get(&self.key) {
+            Some(&(_, blocked_in)) if blocked_in < epoch => {
+                lanes.blocked.remove(&self.key);
+            }
+            Some(&(id, _)) if id != event_id => return false,
+            _ => {}
+        }
+        self.pending = Some((event_id, epoch));
+        true
+    }
+
+    /// Report the outcome of an admitted event: `processed` (acked, or
+    /// captured to a dead-letter topic) unblocks the key if this event was
+    /// blocking it; otherwise the event blocks the key until its redelivery
+    /// is processed.
+    pub fn finish(&mut self, processed: bool) {
+        if let Some(admitted) = self.pending.take() {
+            self.settle(admitted, processed);
+        }
+    }
+
+    fn settle(&self, (event_id, epoch): (u128, u64), processed: bool) {
+        let mut lanes = self.sequencer.lanes();
+        if processed {
+            if lanes.blocked.get(&self.key).map(|(id, _)| *id) == Some(event_id) {
+                lanes.blocked.remove(&self.key);
+            }
+        } else {
+            lanes
+                .blocked
+                .entry(self.key.clone())
+                .or_insert((event_id, epoch));
+        }
+    }
+}
+
+impl Drop for Turn {
+    fn drop(&mut self) {
+        if let Some(admitted) = self.pending.take() {
+            self.settle(admitted, false);
+        }
+        let mut lanes = self.sequencer.lanes();
+        if lanes
+            .tails
+            .get(&self.key)
+            .is_some_and(|tail| tail.seq == self.seq)
+        {
+            lanes.tails.remove(&self.key);
+        }
+    }
+}
@@ -0,0 +1,194 @@
+//! Trace-context and identity propagation through [`EventMetadata`] headers.
+//!
+//! Every bus calls [`inject`] on the emit path and wraps each handler
+//! invocation with [`in_consumer_context`], so an event carries its producer's
+//! context across the hop — in-process or through a broker:
+//!
+//! - **Trace context.** When a [`TracePropagator`] is installed (the
+//!   `r2e-observability` plugin installs one for W3C `traceparent` /
+//!   `tracestate`), the current span context is written into the headers and
+//!   the consumer span is parented to it, so the consumer continues the
+//!   emitter's trace instead of starting a new one.
+//! - **Request id and subject.** The ambient [`RequestContext`] (request id and
+//!   authenticated `sub`, see `r2e_core::request_context`) is copied into the
+//!   [`REQUEST_ID_HEADER`] / [`SUBJECT_HEADER`] headers and restored around the
+//!   consumer, so events the consumer emits in turn keep carrying them. The
+//!   request id is copied by default; the subject is personal data that ends
+//!   up in broker storage and logs, so it is only copied once enabled with
+//!   [`set_context_propagation`].
+//!
+//! Consumer spans follow the OpenTelemetry messaging conventions
+//! (`messaging.system`, `messaging.destination.name`,
+//! `messaging.operation.type = "process"`, `messaging.message.id`, …).
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+
+use r2e_core::RequestContext;
+use tracing::Instrument;
+
+use crate::EventMetadata;
+
+/// W3C trace-context header carrying the parent span.
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+/// W3C trace-context vendor state.
+pub const TRACESTATE_HEADER: &str = "tracestate";
+/// Request id of the HTTP request (or upstream event) that emitted the event.
+pub const REQUEST_ID_HEADER: &str = "request-id";
+/// Authenticated subject (`sub`) of the caller that emitted the event.
+pub const SUBJECT_HEADER: &str = "subject";
+
+/// `messaging.system` reported by [`LocalEventBus`](crate::LocalEventBus) consumer spans.
+pub const LOCAL_MESSAGING_SYSTEM: &str = "local";
+
+/// Bridge between the bus and a tracing backend's context propagation.
+///
+/// r2e-events only depends on `tracing`; reading the current trace context and
+/// re-parenting a span needs the backend (OpenTelemetry) API, so it is plugged
+/// in through this trait with [`set_trace_propagator`].
+pub trait TracePropagator: Send + Sync + 'static {
+    /// Write the current span's trace context into `headers`.
+    ///
+    /// Leaves `headers` untouched when there is no active trace.
+    fn inject(&self, headers: &mut HashMap<String, String>);
+
+    /// Make the trace context carried by `headers` (if any) the parent of `span`.
+    fn extract(&self, headers: &HashMap<String, String>, span: &tracing::Span);
+}
+
+static PROPAGATOR: RwLock<Option<Arc<dyn TracePropagator>>> = RwLock::new(None);
+
+/// Install the process-wide trace propagator, replacing any previous one.
+pub fn set_trace_propagator(propagator: impl TracePropagator) {
+    *PROPAGATOR.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(propagator));
+}
+
+/// Remove the process-wide trace propagator.
+pub fn clear_trace_propagator() {
+    *PROPAGATOR.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+fn trace_propagator() -> Option<Arc<dyn TracePropagator>> {
+    PROPAGATOR.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Which parts of the ambient [`RequestContext`] [`inject`] copies into the
+/// event headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextPropagation {
+    /// Copy the request id into [`REQUEST_ID_HEADER`]. Default: on.
+    pub request_id: bool,
+    /// Copy the authenticated `sub` into [`SUBJECT_HEADER`]. Default: off.
+    pub subject: bool,
+}
+
+impl Default for ContextPropagation {
+    fn default() -> Self {
+        Self {
+            request_id: true,
+            subject: false,
+        }
+    }
+}
+
+static PROPAGATE_REQUEST_ID: AtomicBool = AtomicBool::new(true);
+static PROPAGATE_SUBJECT: AtomicBool = AtomicBool::new(false);
+
+/// Choose, process-wide, which parts of the request context emits carry.
+///
+/// ```ignore
+/// propagation::set_context_propagation(ContextPropagation {
+///     subject: true,
+///     ..Default::default()
+/// });
+/// ```
+pub fn set_context_propagation(settings: ContextPropagation) {
+    PROPAGATE_REQUEST_ID.store(settings.request_id, Ordering::Relaxed);
+    PROPAGATE_SUBJECT.store(settings.subject, Ordering::Relaxed);
+}
+
+/// The current [`ContextPropagation`] settings.
+pub fn context_propagation() -> ContextPropagation {
+    ContextPropagation {
+        request_id: PROPAGATE_REQUEST_ID.load(Ordering::Relaxed),
+        subject: PROPAGATE_SUBJECT.load(Ordering::Relaxed),
+    }
+}
+
+/// Stamp the caller's context onto outgoing metadata.
+///
+/// The trace context always reflects the current span (an event re-emitted
+/// with the metadata it was received with continues from the re-emitting
+/// consumer). Request id and subject are copied as enabled by
+/// [`set_context_propagation`], and only when absent, so explicitly supplied
+/// values win.
+pub fn inject(mut metadata: EventMetadata) -> EventMetadata {
+    if let Some(propagator) = trace_propagator() {
+        propagator.inject(&mut metadata.headers);
+    }
+    if let Some(ctx) = RequestContext::current() {
+        let settings = context_propagation();
+        if let Some(id) = ctx.request_id().filter(|_| settings.request_id) {
+            metadata
+                .headers
+                .entry(REQUEST_ID_HEADER.to_string())
+                .or_insert_with(|| id.to_string());
+        }
+        if let Some(sub) = ctx.subject().filter(|_| settings.subject) {
+            metadata
+                .headers
+                .entry(SUBJECT_HEADER.to_string())
+                .or_insert_with(|| sub.to_string());
+        }
+    }
+    metadata
+}
+
+/// The [`RequestContext`] carried by `metadata` (empty when it has none).
+pub fn request_context(metadata: &EventMetadata) -> RequestContext {
+    let mut ctx = RequestContext::new();
+    if let Some(id) = metadata.headers.get(REQUEST_ID_HEADER) {
+        ctx = ctx.with_request_id(id.clone());
+    }
+    if let Some(sub) = metadata.headers.get(SUBJECT_HEADER) {
+        ctx = ctx.with_subject(sub.clone());
+    }
+    ctx
+}
+
+/// Open the consumer span for one handler invocation, parented to the trace
+/// context carried by `metadata`.
+pub fn consumer_span(system: &str, destination: &str, metadata: &EventMetadata) -> tracing::Span {
+    let span = tracing::info_span!(
+        "event.process",
+        otel.name = %format_args!("process {destination}"),
+        otel.kind = "consumer",
+        messaging.system = system,
+        messaging.destination.name = destination,
+        messaging.operation.type = "process",
+        messaging.message.id = %metadata.event_id,
+        messaging.message.conversation_id = metadata.correlation_id.as_deref(),
+        enduser.id = metadata.headers.get(SUBJECT_HEADER).map(String::as_str),
+        request_id = metadata.headers.get(REQUEST_ID_HEADER).map(String::as_str),
+    );
+    if !span.is_disabled() {
+        if let Some(propagator) = trace_propagator() {
+            propagator.extract(&metadata.headers, &span);
+        }
+    }
+    span
+}
+
+/// Run a handler future inside its consumer span, with the emitter's
+/// [`RequestContext`] restored.
+pub fn in_consumer_context<F: Future>(
+    system: &str,
+    destination: &str,
+    metadata: &EventMetadata,
+    fut: F,
+) -> impl Future<Output = F::Output> {
+    let span = consumer_span(system, destination, metadata);
+    request_context(metadata).scope(fut).instrument(span)
+}
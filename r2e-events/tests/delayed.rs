@@ -0,0 +1,368 @@
+//! Tests for delayed delivery — `emit_after` / `emit_at` on `LocalEventBus`
+//! and the persistent `backend::DelayQueue`.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use r2e_events::backend::DelayQueue;
+use r2e_events::{
+    DelayStore, DelayedEntry, DlqPublisher, EventBus, EventBusError, EventEnvelope, EventMetadata,
+    FileDelayStore, HandlerResult, InMemoryDelayStore, LocalEventBus,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Reminder {
+    value: usize,
+}
+
+async fn counting_bus() -> (LocalEventBus, Arc<AtomicUsize>) {
+    let bus = LocalEventBus::new();
+    let counter = Arc::new(AtomicUsize::new(0));
+    let c = counter.clone();
+    bus.subscribe(move |envelope: EventEnvelope<Reminder>| {
+        let c = c.clone();
+        async move {
+            c.fetch_add(envelope.event.value, Ordering::SeqCst);
+            HandlerResult::Ack
+        }
+    })
+    .await
+    .unwrap();
+    (bus, counter)
+}
+
+#[r2e_core::test]
+async fn emit_after_delivers_once_the_delay_elapsed() {
+    let (bus, counter) = counting_bus().await;
+
+    bus.emit_after(Reminder { value: 7 }, Duration::from_millis(100))
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    bus.wait_idle().await;
+    assert_eq!(counter.load(Ordering::SeqCst), 0);
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    bus.wait_idle().await;
+    assert_eq!(counter.load(Ordering::SeqCst), 7);
+    assert_eq!(bus.delay_metrics().delivered(), 1);
+    assert_eq!(bus.delay_metrics().pending(), 0);
+}
+
+#[r2e_core::test]
+async fn emit_at_in_the_past_delivers_immediately() {
+    let (bus, counter) = counting_bus().await;
+
+    let past = SystemTime::now() - Duration::from_secs(60);
+    bus.emit_at(Reminder { value: 3 }, past).await.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    bus.wait_idle().await;
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
+
+#[r2e_core::test]
+async fn cancelled_emit_is_never_delivered() {
+    let (bus, counter) = counting_bus().await;
+
+    let handle = bus
+        .emit_after(Reminder { value: 1 }, Duration::from_millis(50))
+        .await
+        .unwrap();
+    assert!(handle.is_cancellable());
+    assert!(handle.cancel().await);
+    // A second cancel reports that there was nothing left to cancel.
+    assert!(!handle.cancel().await);
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    bus.wait_idle().await;
+    assert_eq!(counter.load(Ordering::SeqCst), 0);
+    assert_eq!(bus.delay_metrics().cancelled(), 1);
+}
+
+#[r2e_core::test]
+async fn cancel_after_delivery_returns_false() {
+    let (bus, counter) = counting_bus().await;
+
+    let handle = bus
+        .emit_after(Reminder { value: 1 }, Duration::from_millis(10))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    bus.wait_idle().await;
+
+    assert_eq!(counter.load(Ordering::SeqCst), 1);
+    assert!(!handle.cancel().await);
+}
+
+#[r2e_core::test]
+async fn handle_id_is_the_event_id() {
+    let bus = LocalEventBus::new();
+    let seen = Arc::new(Mutex::new(None));
+    let s = seen.clone();
+    bus.subscribe(move |envelope: EventEnvelope<Reminder>| {
+        let s = s.clone();
+        async move {
+            *s.lock().unwrap() = Some(envelope.metadata.event_id);
+            HandlerResult::Ack
+        }
+    })
+    .await
+    .unwrap();
+
+    let metadata = EventMetadata::new().with_correlation_id("wf-1");
+    let handle = bus
+        .emit_at_with(Reminder { value: 1 }, SystemTime::now(), metadata)
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    bus.wait_idle().await;
+    assert_eq!(*seen.lock().unwrap(), Some(handle.id()));
+}
+
+#[r2e_core::test]
+async fn shutdown_drops_pending_delayed_emits() {
+    let (bus, counter) = counting_bus().await;
+
+    bus.emit_after(Reminder { value: 1 }, Duration::from_millis(50))
+        .await
+        .unwrap();
+    bus.shutdown(Duration::from_secs(1)).await.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(counter.load(Ordering::SeqCst), 0);
+    assert_eq!(bus.delay_metrics().cancelled(), 1);
+    assert!(matches!(
+        bus.emit_after(Reminder { value: 1 }, Duration::ZERO).await,
+        Err(EventBusError::Shutdown)
+    ));
+}
+
+// ── DelayQueue ─────────────────────────────────────────────────────────
+
+type Published = Arc<Mutex<Vec<(String, Vec<u8>)>>>;
+
+fn recording_publisher(published: Published) -> DlqPublisher {
+    Arc::new(move |topic, payload, _metadata| {
+        let published = published.clone();
+        Box::pin(async move {
+            published.lock().unwrap().push((topic, payload));
+            Ok(())
+        })
+    })
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn delay_queue_publishes_and_removes_the_entry() {
+    let store = Arc::new(InMemoryDelayStore::new());
+    let published: Published = Arc::default();
+    let queue = DelayQueue::new(store.clone(), recording_publisher(published.clone()));
+
+    queue
+        .schedule(
+            "reminders",
+            b"{}".to_vec(),
+            EventMetadata::new(),
+            SystemTime::now() + Duration::from_millis(50),
+        )
+        .await
+        .unwrap();
+    assert_eq!(store.load_all().await.unwrap().len(), 1);
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(
+        published.lock().unwrap().as_slice(),
+        [("reminders".to_string(), b"{}".to_vec())]
+    );
+    assert!(store.load_all().await.unwrap().is_empty());
+    assert_eq!(queue.metrics().delivered(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn delay_queue_cancel_removes_the_entry() {
+    let store = Arc::new(InMemoryDelayStore::new());
+    let published: Published = Arc::default();
+    let queue = DelayQueue::new(store.clone(), recording_publisher(published.clone()));
+
+    let handle = queue
+        .schedule(
+            "reminders",
+            Vec::new(),
+            EventMetadata::new(),
+            SystemTime::now() + Duration::from_millis(50),
+        )
+        .await
+        .unwrap();
+    assert!(handle.cancel().await);
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(published.lock().unwrap().is_empty());
+    assert!(store.load_all().await.unwrap().is_empty());
+    assert_eq!(queue.metrics().cancelled(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn delay_queue_retries_until_the_publish_succeeds() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let a = attempts.clone();
+    let flaky: DlqPublisher = Arc::new(move |_topic, _payload, _metadata| {
+        let a = a.clone();
+        Box::pin(async move {
+            if a.fetch_add(1, Ordering::SeqCst) == 0 {
+                Err(EventBusError::Connection("broker down".into()))
+            } else {
+                Ok(())
+            }
+        })
+    });
+    let queue = DelayQueue::in_memory(flaky);
+
+    queue
+        .schedule(
+            "reminders",
+            Vec::new(),
+            EventMetadata::new(),
+            SystemTime::now(),
+        )
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    assert_eq!(queue.metrics().delivered(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn shutdown_keeps_entries_for_the_next_process() {
+    let dir = tempfile::tempdir().unwrap();
+    let published: Published = Arc::default();
+
+    let first = DelayQueue::new(
+        Arc::new(FileDelayStore::new(dir.path()).unwrap()),
+        recording_publisher(published.clone()),
+    );
+    first
+        .schedule(
+            "reminders",
+            b"42".to_vec(),
+            EventMetadata::new(),
+            SystemTime::now() + Duration::from_millis(100),
+        )
+        .await
+        .unwrap();
+    first.shutdown();
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert!(published.lock().unwrap().is_empty());
+
+    // A fresh queue over the same directory re-arms the overdue entry.
+    let second = DelayQueue::new(
+        Arc::new(FileDelayStore::new(dir.path()).unwrap()),
+        recording_publisher(published.clone()),
+    );
+    assert_eq!(second.recover().await.unwrap(), 1);
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(
+        published.lock().unwrap().as_slice(),
+        [("reminders".to_string(), b"42".to_vec())]
+    );
+    assert!(FileDelayStore::new(dir.path())
+        .unwrap()
+        .load_all()
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn file_store_round_trips_entries_and_skips_garbage() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = FileDelayStore::new(dir.path()).unwrap();
+    let metadata = EventMetadata::new().with_header("tenant", "acme");
+    let entry = DelayedEntry {
+        id: metadata.event_id,
+        topic: "reminders".into(),
+        payload: b"{\"value\":1}".to_vec(),
+        metadata,
+        deliver_at: 1_700_000_000_000,
+    };
+    store.save(&entry).await.unwrap();
+    std::fs::write(dir.path().join("broken.json"), b"not json").unwrap();
+
+    let loaded = store.load_all().await.unwrap();
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].id, entry.id);
+    assert_eq!(loaded[0].payload, entry.payload);
+    assert_eq!(
+        loaded[0].metadata.headers.get("tenant").map(String::as_str),
+        Some("acme")
+    );
+
+    store.remove(entry.id).await.unwrap();
+    // Removing twice is not an error.
+    store.remove(entry.id).await.unwrap();
+    assert!(store.load_all().await.unwrap().is_empty());
+}
+
+fn entry(value: u8) -> DelayedEntry {
+    let metadata = EventMetadata::new();
+    DelayedEntry {
+        id: metadata.event_id,
+        topic: "reminders".into(),
+        payload: vec![value],
+        metadata,
+        deliver_at: 1_700_000_000_000,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn per_instance_store_writes_nothing_until_an_entry_is_saved() {
+    let dir = tempfile::tempdir().unwrap();
+    let parent = dir.path().join("delayed");
+    let store = FileDelayStore::per_instance(&parent);
+
+    assert!(store.load_all().await.unwrap().is_empty());
+    store.remove(1).await.unwrap();
+    assert!(!parent.exists());
+
+    store.save(&entry(1)).await.unwrap();
+    assert!(parent.join("0").is_dir());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn per_instance_stores_sharing_a_directory_keep_their_own_entries() {
+    let dir = tempfile::tempdir().unwrap();
+    let first = FileDelayStore::per_instance(dir.path());
+    let second = FileDelayStore::per_instance(dir.path());
+
+    first.save(&entry(1)).await.unwrap();
+    second.save(&entry(2)).await.unwrap();
+
+    let payloads = |entries: Vec<DelayedEntry>| -> Vec<Vec<u8>> {
+        entries.into_iter().map(|e| e.payload).collect()
+    };
+    assert_eq!(payloads(first.load_all().await.unwrap()), [vec![1]]);
+    assert_eq!(payloads(second.load_all().await.unwrap()), [vec![2]]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn per_instance_store_adopts_entries_of_a_gone_instance() {
+    let dir = tempfile::tempdir().unwrap();
+    let running = FileDelayStore::per_instance(dir.path());
+    let gone = FileDelayStore::per_instance(dir.path());
+    running.save(&entry(1)).await.unwrap();
+    gone.save(&entry(2)).await.unwrap();
+    drop(gone);
+
+    let restarted = FileDelayStore::per_instance(dir.path());
+    let recovered = restarted.load_all().await.unwrap();
+    assert_eq!(recovered.len(), 1);
+    assert_eq!(recovered[0].payload, [2]);
+    // The running instance's entry stays its own.
+    assert_eq!(running.load_all().await.unwrap().len(), 1);
+}
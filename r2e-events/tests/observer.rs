@@ -0,0 +1,235 @@
+//! `EventObserver` taps and the `LocalEventBus` filter / retry policies
+//! attached through `configure_handler`.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use r2e_events::{
+    EventBus, EventEnvelope, EventFilter, EventMetadata, EventObserver, HandlerResult,
+    LocalEventBus, ObservedDeadLetter, ObservedEmit, ObservedHandling, RetryPolicy,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Ping {
+    n: u32,
+}
+
+#[derive(Default)]
+struct Log {
+    emits: Mutex<Vec<ObservedEmit>>,
+    handled: Mutex<Vec<ObservedHandling>>,
+    dead_letters: Mutex<Vec<ObservedDeadLetter>>,
+}
+
+#[derive(Clone, Default)]
+struct Recorder(Arc<Log>);
+
+impl EventObserver for Recorder {
+    fn on_emit(&self, emit: &ObservedEmit) {
+        self.0.emits.lock().unwrap().push(emit.clone());
+    }
+
+    fn on_handled(&self, handled: &ObservedHandling) {
+        self.0.handled.lock().unwrap().push(handled.clone());
+    }
+
+    fn on_dead_letter(&self, dead_letter: &ObservedDeadLetter) {
+        self.0
+            .dead_letters
+            .lock()
+            .unwrap()
+            .push(dead_letter.clone());
+    }
+}
+
+/// Subscribe a handler that `Nack`s its first `failures` calls.
+async fn flaky(
+    bus: &LocalEventBus,
+    failures: usize,
+) -> (r2e_events::SubscriptionId, Arc<AtomicUsize>) {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let c = calls.clone();
+    let handle = bus
+        .subscribe(move |_: EventEnvelope<Ping>| {
+            let c = c.clone();
+            async move {
+                if c.fetch_add(1, Ordering::SeqCst) < failures {
+                    HandlerResult::Nack("boom".into())
+                } else {
+                    HandlerResult::Ack
+                }
+            }
+        })
+        .await
+        .unwrap();
+    (handle.id(), calls)
+}
+
+// ── Observer ────────────────────────────────────────────────────────────────
+
+#[r2e_core::test]
+async fn observer_sees_emits_without_subscribers() {
+    let recorder = Recorder::default();
+    let bus = LocalEventBus::new().with_observer(recorder.clone());
+
+    bus.emit_with(Ping { n: 7 }, EventMetadata::new().with_header("k", "v"))
+        .await
+        .unwrap();
+
+    let emits = recorder.0.emits.lock().unwrap();
+    assert_eq!(emits.len(), 1);
+    assert!(emits[0].is::<Ping>());
+    assert_eq!(emits[0].downcast::<Ping>().unwrap().n, 7);
+    assert_eq!(emits[0].payload, serde_json::json!({ "n": 7 }));
+    assert_eq!(emits[0].metadata.headers["k"], "v");
+}
+
+#[r2e_core::test]
+async fn observer_is_shared_by_clones() {
+    let bus = LocalEventBus::new();
+    let clone = bus.clone();
+    let recorder = Recorder::default();
+    bus.set_observer(recorder.clone());
+
+    clone.emit(Ping { n: 1 }).await.unwrap();
+    assert_eq!(recorder.0.emits.lock().unwrap().len(), 1);
+
+    bus.clear_observer();
+    clone.emit(Ping { n: 2 }).await.unwrap();
+    assert_eq!(recorder.0.emits.lock().unwrap().len(), 1);
+}
+
+#[r2e_core::test]
+async fn observer_reports_handler_results() {
+    let recorder = Recorder::default();
+    let bus = LocalEventBus::new().with_observer(recorder.clone());
+    let (id, _) = flaky(&bus, 1).await;
+
+    let metadata = EventMetadata::new();
+    let event_id = metadata.event_id;
+    bus.emit_with(Ping { n: 1 }, metadata).await.unwrap();
+    bus.wait_idle().await;
+
+    let handled = recorder.0.handled.lock().unwrap();
+    assert_eq!(handled.len(), 1);
+    assert_eq!(handled[0].subscription, id);
+    assert_eq!(handled[0].event_id, event_id);
+    assert_eq!(handled[0].attempt, 1);
+    assert_eq!(handled[0].result, HandlerResult::Nack("boom".into()));
+}
+
+// ── configure_handler ───────────────────────────────────────────────────────
+
+#[r2e_core::test]
+async fn filter_skips_events() {
+    let bus = LocalEventBus::new();
+    let (id, calls) = flaky(&bus, 0).await;
+    let filter: EventFilter = Arc::new(|meta: &EventMetadata| meta.headers.contains_key("tenant"));
+    bus.configure_handler::<Ping>(id, Some(filter), None).await;
+
+    bus.emit(Ping { n: 1 }).await.unwrap();
+    bus.emit_with(
+        Ping { n: 2 },
+        EventMetadata::new().with_header("tenant", "a"),
+    )
+    .await
+    .unwrap();
+    bus.wait_idle().await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[r2e_core::test(flavor = "current_thread", start_paused = true)]
+async fn retry_policy_retries_with_backoff() {
+    let recorder = Recorder::default();
+    let bus = LocalEventBus::new().with_observer(recorder.clone());
+    let (id, calls) = flaky(&bus, 2).await;
+    bus.configure_handler::<Ping>(id, None, Some(RetryPolicy::new(3)))
+        .await;
+
+    let start = tokio::time::Instant::now();
+    bus.emit(Ping { n: 1 }).await.unwrap();
+    bus.wait_idle().await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    // 1s + 2s of exponential backoff, on the paused clock.
+    assert_eq!(start.elapsed(), Duration::from_secs(3));
+    let attempts: Vec<_> = recorder
+        .0
+        .handled
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|h| (h.attempt, h.result.clone()))
+        .collect();
+    assert_eq!(
+        attempts,
+        vec![
+            (1, HandlerResult::Nack("boom".into())),
+            (2, HandlerResult::Nack("boom".into())),
+            (3, HandlerResult::Ack),
+        ]
+    );
+    assert!(recorder.0.dead_letters.lock().unwrap().is_empty());
+}
+
+#[r2e_core::test(flavor = "current_thread", start_paused = true)]
+async fn exhausted_retries_report_dead_letter() {
+    let recorder = Recorder::default();
+    let bus = LocalEventBus::new().with_observer(recorder.clone());
+    let (id, calls) = flaky(&bus, usize::MAX).await;
+    bus.configure_handler::<Ping>(id, None, Some(RetryPolicy::new(2).with_dlq("pings.dlq")))
+        .await;
+
+    bus.emit(Ping { n: 1 }).await.unwrap();
+    bus.wait_idle().await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    let dead_letters = recorder.0.dead_letters.lock().unwrap();
+    assert_eq!(dead_letters.len(), 1);
+    assert_eq!(dead_letters[0].subscription, id);
+    assert_eq!(dead_letters[0].topic, "pings.dlq");
+    assert_eq!(dead_letters[0].reason, "boom");
+}
+
+#[r2e_core::test(flavor = "current_thread", start_paused = true)]
+async fn retry_backoff_releases_the_concurrency_slot() {
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Pong;
+
+    let bus = LocalEventBus::with_concurrency(1);
+    let (id, calls) = flaky(&bus, 1).await;
+    bus.configure_handler::<Ping>(
+        id,
+        None,
+        Some(RetryPolicy {
+            retry_delay: Duration::from_secs(60),
+            ..RetryPolicy::new(1)
+        }),
+    )
+    .await;
+    let handled_at = Arc::new(Mutex::new(None));
+    let h = handled_at.clone();
+    bus.subscribe(move |_: EventEnvelope<Pong>| {
+        let h = h.clone();
+        async move {
+            *h.lock().unwrap() = Some(tokio::time::Instant::now());
+            HandlerResult::Ack
+        }
+    })
+    .await
+    .unwrap();
+
+    let start = tokio::time::Instant::now();
+    bus.emit(Ping { n: 1 }).await.unwrap();
+    // The only slot is free while `Ping` backs off, so `Pong` runs right away.
+    bus.emit(Pong).await.unwrap();
+    bus.wait_idle().await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(start.elapsed(), Duration::from_secs(60));
+    let handled_at = handled_at.lock().unwrap().expect("Pong handled");
+    assert_eq!(handled_at - start, Duration::ZERO);
+}
@@ -0,0 +1,429 @@
+//! Per-key ordered dispatch (`EventBus::configure_ordering`) on
+//! `LocalEventBus` and on the shared backend dispatch path.
+
+use std::any::TypeId;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use r2e_events::backend::{BackendState, DeserializerFn, DispatchOutcome, Handler, TopicRegistry};
+use r2e_events::ordering::KeyedSequencer;
+use r2e_events::{
+    EventBus, EventEnvelope, EventMetadata, HandlerResult, LocalEventBus, OrderedBy, SubscriptionId,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Update {
+    seq: u64,
+    /// Simulated work, in milliseconds.
+    work_ms: u64,
+}
+
+/// Subscribe a handler that sleeps `work_ms` and logs `(key, seq)` on
+/// completion.
+async fn slow_handler(bus: &LocalEventBus) -> (SubscriptionId, Arc<Mutex<Vec<(String, u64)>>>) {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let l = log.clone();
+    let handle = bus
+        .subscribe(move |env: EventEnvelope<Update>| {
+            let l = l.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(env.event.work_ms)).await;
+                let key = env.metadata.partition_key.clone().unwrap_or_default();
+                l.lock().unwrap().push((key, env.event.seq));
+                HandlerResult::Ack
+            }
+        })
+        .await
+        .unwrap();
+    (handle.id(), log)
+}
+
+async fn emit(bus: &LocalEventBus, key: &str, seq: u64, work_ms: u64) {
+    bus.emit_with(
+        Update { seq, work_ms },
+        EventMetadata::new().with_partition_key(key),
+    )
+    .await
+    .unwrap();
+}
+
+// ── OrderedBy ───────────────────────────────────────────────────────────────
+
+#[test]
+fn ordered_by_parses_attribute_syntax() {
+    assert_eq!(
+        OrderedBy::parse("partition_key"),
+        Some(OrderedBy::PartitionKey)
+    );
+    assert_eq!(
+        OrderedBy::parse("header:tenant"),
+        Some(OrderedBy::Header("tenant".into()))
+    );
+    assert_eq!(OrderedBy::parse("header:"), None);
+    assert_eq!(OrderedBy::parse("tenant"), None);
+
+    let meta = EventMetadata::new().with_header("tenant", "acme");
+    assert_eq!(
+        OrderedBy::Header("tenant".into()).key(&meta).as_deref(),
+        Some("acme")
+    );
+    assert_eq!(OrderedBy::PartitionKey.key(&meta), None);
+}
+
+// ── LocalEventBus ───────────────────────────────────────────────────────────
+
+#[r2e_core::test(flavor = "current_thread", start_paused = true)]
+async fn same_key_runs_in_emit_order() {
+    let bus = LocalEventBus::new();
+    let (id, log) = slow_handler(&bus).await;
+    bus.configure_ordering::<Update>(id, OrderedBy::PartitionKey)
+        .await;
+
+    // Later events finish their work sooner — unordered, they would overtake.
+    for seq in 0..4 {
+        emit(&bus, "order-1", seq, 400 - seq * 100).await;
+    }
+    let start = tokio::time::Instant::now();
+    bus.wait_idle().await;
+
+    let seqs: Vec<_> = log.lock().unwrap().iter().map(|(_, s)| *s).collect();
+    assert_eq!(seqs, vec![0, 1, 2, 3]);
+    assert_eq!(start.elapsed(), Duration::from_millis(1000));
+}
+
+#[r2e_core::test(flavor = "current_thread", start_paused = true)]
+async fn different_keys_run_in_parallel() {
+    let bus = LocalEventBus::new();
+    let (id, log) = slow_handler(&bus).await;
+    bus.configure_ordering::<Update>(id, OrderedBy::PartitionKey)
+        .await;
+
+    emit(&bus, "a", 0, 300).await;
+    emit(&bus, "a", 1, 100).await;
+    emit(&bus, "b", 0, 300).await;
+    emit(&bus, "b", 1, 100).await;
+    let start = tokio::time::Instant::now();
+    bus.wait_idle().await;
+
+    // Each key takes 400ms; the two keys overlap.
+    assert_eq!(start.elapsed(), Duration::from_millis(400));
+    let log = log.lock().unwrap();
+    for key in ["a", "b"] {
+        let seqs: Vec<_> = log
+            .iter()
+            .filter(|(k, _)| k == key)
+            .map(|(_, s)| *s)
+            .collect();
+        assert_eq!(seqs, vec![0, 1], "key {key}");
+    }
+}
+
+#[r2e_core::test(flavor = "current_thread", start_paused = true)]
+async fn slow_key_does_not_starve_other_keys() {
+    let bus = LocalEventBus::with_concurrency(2);
+    let (id, log) = slow_handler(&bus).await;
+    bus.configure_ordering::<Update>(id, OrderedBy::PartitionKey)
+        .await;
+
+    let start = tokio::time::Instant::now();
+    for seq in 0..4 {
+        emit(&bus, "a", seq, 1_000).await;
+    }
+    // The queued `a` events hold no handler slot, so `b` is not held back.
+    emit(&bus, "b", 0, 10).await;
+    assert_eq!(start.elapsed(), Duration::ZERO);
+    bus.wait_idle().await;
+
+    assert_eq!(log.lock().unwrap()[0], ("b".to_string(), 0));
+    assert_eq!(start.elapsed(), Duration::from_millis(4_000));
+}
+
+#[r2e_core::test(flavor = "current_thread", start_paused = true)]
+async fn events_without_key_are_not_sequenced() {
+    let bus = LocalEventBus::new();
+    let (id, log) = slow_handler(&bus).await;
+    bus.configure_ordering::<Update>(id, OrderedBy::PartitionKey)
+        .await;
+
+    bus.emit(Update {
+        seq: 0,
+        work_ms: 200,
+    })
+    .await
+    .unwrap();
+    bus.emit(Update {
+        seq: 1,
+        work_ms: 100,
+    })
+    .await
+    .unwrap();
+    bus.wait_idle().await;
+
+    let seqs: Vec<_> = log.lock().unwrap().iter().map(|(_, s)| *s).collect();
+    assert_eq!(seqs, vec![1, 0]);
+}
+
+#[r2e_core::test(flavor = "current_thread", start_paused = true)]
+async fn nack_releases_the_next_local_event() {
+    let bus = LocalEventBus::new();
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let c = calls.clone();
+    let handle = bus
+        .subscribe(move |env: EventEnvelope<Update>| {
+            let c = c.clone();
+            async move {
+                c.lock().unwrap().push(env.event.seq);
+                if env.event.seq == 0 {
+                    HandlerResult::Nack("boom".into())
+                } else {
+                    HandlerResult::Ack
+                }
+            }
+        })
+        .await
+        .unwrap();
+    bus.configure_ordering::<Update>(handle.id(), OrderedBy::PartitionKey)
+        .await;
+
+    emit(&bus, "a", 0, 0).await;
+    emit(&bus, "a", 1, 0).await;
+    bus.wait_idle().await;
+
+    assert_eq!(*calls.lock().unwrap(), vec![0, 1]);
+}
+
+// ── Backend dispatch ────────────────────────────────────────────────────────
+
+struct Tick;
+
+fn tick_deserializer() -> DeserializerFn {
+    Arc::new(|_bytes: &[u8]| Ok(Arc::new(Tick) as Arc<dyn std::any::Any + Send + Sync>))
+}
+
+/// Handler that nacks while `failing` is set and counts its invocations.
+fn switchable_handler(failing: Arc<Mutex<bool>>, calls: Arc<AtomicUsize>) -> Handler {
+    Arc::new(move |_event, _meta| {
+        calls.fetch_add(1, Ordering::SeqCst);
+        let fail = *failing.lock().unwrap();
+        Box::pin(async move {
+            if fail {
+                HandlerResult::Nack("unavailable".into())
+            } else {
+                HandlerResult::Ack
+            }
+        })
+    })
+}
+
+async fn dispatch(state: &Arc<BackendState>, metadata: &EventMetadata) -> DispatchOutcome {
+    state
+        .dispatch_from_poller_tracked(TypeId::of::<Tick>(), b"{}", metadata.clone())
+        .await
+        .outcome()
+        .await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn backend_nack_blocks_key_until_redelivery_acks() {
+    let state = Arc::new(BackendState::new(TopicRegistry::default()));
+    let failing = Arc::new(Mutex::new(true));
+    let calls = Arc::new(AtomicUsize::new(0));
+    let (id, _) = state
+        .register_handler_with_deserializer::<Tick>(
+            switchable_handler(failing.clone(), calls.clone()),
+            tick_deserializer(),
+        )
+        .await;
+    state
+        .configure_ordering(id, OrderedBy::PartitionKey, Some(TypeId::of::<Tick>()))
+        .await;
+
+    let first = EventMetadata::new().with_partition_key("acct-1");
+    let second = EventMetadata::new().with_partition_key("acct-1");
+    let other = EventMetadata::new().with_partition_key("acct-2");
+
+    assert_eq!(dispatch(&state, &first).await, DispatchOutcome::Nack);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    // The handler has recovered, but `second` must not overtake `first`:
+    // nacked without running, so the broker redelivers it after `first`.
+    *failing.lock().unwrap() = false;
+    assert_eq!(dispatch(&state, &second).await, DispatchOutcome::Nack);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    // Other keys are unaffected.
+    assert_eq!(dispatch(&state, &other).await, DispatchOutcome::Ack);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    // Redelivery (same event_id) of `first` unblocks the key.
+    assert_eq!(dispatch(&state, &first).await, DispatchOutcome::Ack);
+    assert_eq!(dispatch(&state, &second).await, DispatchOutcome::Ack);
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn backend_reset_releases_blocked_keys() {
+    let state = Arc::new(BackendState::new(TopicRegistry::default()));
+    let failing = Arc::new(Mutex::new(true));
+    let calls = Arc::new(AtomicUsize::new(0));
+    let (id, _) = state
+        .register_handler_with_deserializer::<Tick>(
+            switchable_handler(failing.clone(), calls.clone()),
+            tick_deserializer(),
+        )
+        .await;
+    state
+        .configure_ordering(id, OrderedBy::PartitionKey, Some(TypeId::of::<Tick>()))
+        .await;
+
+    let first = EventMetadata::new().with_partition_key("acct-1");
+    assert_eq!(dispatch(&state, &first).await, DispatchOutcome::Nack);
+
+    // A new session (or partition revoke): `first` may be redelivered to
+    // another consumer, so this one stops waiting for it.
+    *failing.lock().unwrap() = false;
+    state.reset_ordering();
+    let second = EventMetadata::new().with_partition_key("acct-1");
+    assert_eq!(dispatch(&state, &second).await, DispatchOutcome::Ack);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn backend_panic_blocks_key() {
+    let state = Arc::new(BackendState::new(TopicRegistry::default()));
+    let handler: Handler = Arc::new(|_event, _meta| Box::pin(async { panic!("handler bug") }));
+    let (id, _) = state
+        .register_handler_with_deserializer::<Tick>(handler, tick_deserializer())
+        .await;
+    state
+        .configure_ordering(id, OrderedBy::PartitionKey, None)
+        .await;
+
+    let meta = EventMetadata::new().with_partition_key("acct-1");
+    assert_eq!(dispatch(&state, &meta).await, DispatchOutcome::Nack);
+    assert!(
+        state.handlers.read().await[&TypeId::of::<Tick>()].entries[0]
+            .ordering
+            .as_ref()
+            .unwrap()
+            .is_blocked("acct-1")
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn backend_same_key_handlers_do_not_overlap() {
+    let state = Arc::new(BackendState::new(TopicRegistry::default()));
+    let active = Arc::new(AtomicUsize::new(0));
+    let max_active = Arc::new(AtomicUsize::new(0));
+    let (a, m) = (active.clone(), max_active.clone());
+    let handler: Handler = Arc::new(move |_event, _meta| {
+        let (a, m) = (a.clone(), m.clone());
+        Box::pin(async move {
+            let now = a.fetch_add(1, Ordering::SeqCst) + 1;
+            m.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            a.fetch_sub(1, Ordering::SeqCst);
+            HandlerResult::Ack
+        })
+    });
+    let (id, _) = state
+        .register_handler_with_deserializer::<Tick>(handler, tick_deserializer())
+        .await;
+    state
+        .configure_ordering(id, OrderedBy::PartitionKey, Some(TypeId::of::<Tick>()))
+        .await;
+
+    let mut completions = Vec::new();
+    for _ in 0..10 {
+        completions.push(
+            state
+                .dispatch_from_poller_tracked(
+                    TypeId::of::<Tick>(),
+                    b"{}",
+                    EventMetadata::new().with_partition_key("acct-1"),
+                )
+                .await,
+        );
+    }
+    for completion in completions {
+        assert_eq!(completion.outcome().await, DispatchOutcome::Ack);
+    }
+    assert_eq!(max_active.load(Ordering::SeqCst), 1);
+}
+
+#[r2e_core::test(flavor = "current_thread", start_paused = true)]
+async fn backend_slow_key_does_not_starve_other_keys() {
+    let state = Arc::new(BackendState::with_options(
+        TopicRegistry::default(),
+        None,
+        2,
+    ));
+    let handler: Handler = Arc::new(|_event, meta| {
+        Box::pin(async move {
+            if meta.partition_key.as_deref() == Some("slow") {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+            HandlerResult::Ack
+        })
+    });
+    let (id, _) = state
+        .register_handler_with_deserializer::<Tick>(handler, tick_deserializer())
+        .await;
+    state
+        .configure_ordering(id, OrderedBy::PartitionKey, Some(TypeId::of::<Tick>()))
+        .await;
+
+    let start = tokio::time::Instant::now();
+    let mut slow = Vec::new();
+    for _ in 0..3 {
+        slow.push(
+            state
+                .dispatch_from_poller_tracked(
+                    TypeId::of::<Tick>(),
+                    b"{}",
+                    EventMetadata::new().with_partition_key("slow"),
+                )
+                .await,
+        );
+    }
+    let fast = EventMetadata::new().with_partition_key("fast");
+    assert_eq!(dispatch(&state, &fast).await, DispatchOutcome::Ack);
+    assert_eq!(start.elapsed(), Duration::ZERO);
+
+    for completion in slow {
+        assert_eq!(completion.outcome().await, DispatchOutcome::Ack);
+    }
+    assert_eq!(start.elapsed(), Duration::from_secs(3));
+}
+
+#[r2e_core::test(flavor = "current_thread", start_paused = true)]
+async fn sequencer_bounds_events_waiting_per_key() {
+    let sequencer = Arc::new(KeyedSequencer::new(OrderedBy::PartitionKey).with_max_waiting(1));
+    let meta = EventMetadata::new().with_partition_key("a");
+    let mut first = sequencer.enter(&meta).unwrap();
+    let mut second = sequencer.enter(&meta).unwrap();
+    let mut other = sequencer
+        .enter(&EventMetadata::new().with_partition_key("b"))
+        .unwrap();
+
+    first.reserve().await;
+    other.reserve().await;
+    assert!(
+        tokio::time::timeout(Duration::from_secs(1), second.reserve())
+            .await
+            .is_err()
+    );
+    drop(first);
+    second.reserve().await;
+}
+
+#[test]
+fn sequencer_skips_events_without_key() {
+    let sequencer = Arc::new(KeyedSequencer::new(OrderedBy::Header("tenant".into())));
+    assert!(sequencer.enter(&EventMetadata::new()).is_none());
+    assert!(sequencer
+        .enter(&EventMetadata::new().with_header("tenant", "a"))
+        .is_some());
+}
@@ -0,0 +1,236 @@
+//! Tests for trace-context and identity propagation through `EventMetadata`.
+
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use r2e_core::RequestContext;
+use r2e_events::backend::{BackendState, DeserializerFn, Handler, TopicRegistry};
+use r2e_events::propagation::{
+    self, ContextPropagation, TracePropagator, REQUEST_ID_HEADER, SUBJECT_HEADER,
+};
+use r2e_events::{EventBus, EventEnvelope, EventMetadata, HandlerResult, LocalEventBus};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct OrderPlaced;
+
+#[derive(Serialize, Deserialize)]
+struct InvoiceRequested;
+
+/// What a handler observed: the event headers and the ambient context.
+#[derive(Default)]
+struct Seen {
+    headers: HashMap<String, String>,
+    request_id: Option<String>,
+    subject: Option<String>,
+}
+
+/// The ambient context of a request. Every test of this binary propagates
+/// the subject; the defaults are covered in `propagation_defaults.rs`.
+fn request_context() -> RequestContext {
+    propagation::set_context_propagation(ContextPropagation {
+        request_id: true,
+        subject: true,
+    });
+    RequestContext::new()
+        .with_request_id("req-1")
+        .with_subject("alice")
+}
+
+async fn recording_bus() -> (LocalEventBus, Arc<Mutex<Vec<Seen>>>) {
+    let bus = LocalEventBus::new();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let s = seen.clone();
+    bus.subscribe(move |envelope: EventEnvelope<OrderPlaced>| {
+        let s = s.clone();
+        async move {
+            let ctx = RequestContext::current().unwrap_or_default();
+            s.lock().unwrap().push(Seen {
+                headers: envelope.metadata.headers.clone(),
+                request_id: ctx.request_id().map(str::to_string),
+                subject: ctx.subject().map(str::to_string),
+            });
+            HandlerResult::Ack
+        }
+    })
+    .await
+    .unwrap();
+    (bus, seen)
+}
+
+#[r2e_core::test]
+async fn emit_stamps_and_consumer_restores_request_context() {
+    let (bus, seen) = recording_bus().await;
+
+    request_context()
+        .scope(bus.emit(OrderPlaced))
+        .await
+        .unwrap();
+    bus.wait_idle().await;
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 1);
+    assert_eq!(seen[0].headers[REQUEST_ID_HEADER], "req-1");
+    assert_eq!(seen[0].headers[SUBJECT_HEADER], "alice");
+    assert_eq!(seen[0].request_id.as_deref(), Some("req-1"));
+    assert_eq!(seen[0].subject.as_deref(), Some("alice"));
+}
+
+#[r2e_core::test]
+async fn emit_outside_a_request_adds_no_identity_headers() {
+    let (bus, seen) = recording_bus().await;
+
+    bus.emit(OrderPlaced).await.unwrap();
+    bus.wait_idle().await;
+
+    let seen = seen.lock().unwrap();
+    assert!(!seen[0].headers.contains_key(REQUEST_ID_HEADER));
+    assert!(!seen[0].headers.contains_key(SUBJECT_HEADER));
+    assert_eq!(seen[0].request_id, None);
+}
+
+#[r2e_core::test]
+async fn explicit_metadata_headers_win() {
+    let (bus, seen) = recording_bus().await;
+
+    let metadata = EventMetadata::new().with_header(REQUEST_ID_HEADER, "explicit");
+    request_context()
+        .scope(bus.emit_with(OrderPlaced, metadata))
+        .await
+        .unwrap();
+    bus.wait_idle().await;
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen[0].headers[REQUEST_ID_HEADER], "explicit");
+    assert_eq!(seen[0].headers[SUBJECT_HEADER], "alice");
+}
+
+#[r2e_core::test]
+async fn consumer_emits_keep_the_originating_context() {
+    let bus = LocalEventBus::new();
+    let seen = Arc::new(Mutex::new(None));
+
+    let forward = bus.clone();
+    bus.subscribe(move |_: EventEnvelope<OrderPlaced>| {
+        let forward = forward.clone();
+        async move {
+            forward.emit(InvoiceRequested).await.unwrap();
+            HandlerResult::Ack
+        }
+    })
+    .await
+    .unwrap();
+    let s = seen.clone();
+    bus.subscribe(move |envelope: EventEnvelope<InvoiceRequested>| {
+        let s = s.clone();
+        async move {
+            *s.lock().unwrap() = Some(envelope.metadata.headers.clone());
+            HandlerResult::Ack
+        }
+    })
+    .await
+    .unwrap();
+
+    request_context()
+        .scope(bus.emit(OrderPlaced))
+        .await
+        .unwrap();
+    bus.wait_idle().await;
+    bus.wait_idle().await;
+
+    let headers = seen.lock().unwrap().take().expect("second hop delivered");
+    assert_eq!(headers[REQUEST_ID_HEADER], "req-1");
+    assert_eq!(headers[SUBJECT_HEADER], "alice");
+}
+
+#[r2e_core::test]
+async fn delayed_emit_captures_context_when_scheduled() {
+    let (bus, seen) = recording_bus().await;
+
+    request_context()
+        .scope(bus.emit_after(OrderPlaced, Duration::from_millis(20)))
+        .await
+        .unwrap();
+    r2e_core::rt::sleep(Duration::from_millis(200)).await;
+    bus.wait_idle().await;
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 1);
+    assert_eq!(seen[0].request_id.as_deref(), Some("req-1"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn backend_dispatch_restores_request_context() {
+    let state = Arc::new(BackendState::new(TopicRegistry::default()).with_messaging_system("test"));
+    let seen = Arc::new(Mutex::new(None));
+    let s = seen.clone();
+    let handler: Handler = Arc::new(move |_event, _meta| {
+        let s = s.clone();
+        Box::pin(async move {
+            *s.lock().unwrap() = RequestContext::current();
+            HandlerResult::Ack
+        })
+    });
+    let deserializer: DeserializerFn =
+        Arc::new(|_bytes: &[u8]| Ok(Arc::new(OrderPlaced) as Arc<dyn std::any::Any + Send + Sync>));
+    state
+        .register_handler_with_deserializer::<OrderPlaced>(handler, deserializer)
+        .await;
+
+    let metadata = request_context()
+        .scope(async { propagation::inject(EventMetadata::new()) })
+        .await;
+    let completion = state
+        .dispatch_from_poller_tracked(TypeId::of::<OrderPlaced>(), b"null", metadata)
+        .await;
+    completion.outcome().await;
+
+    let ctx = seen.lock().unwrap().take().expect("context restored");
+    assert_eq!(ctx.request_id(), Some("req-1"));
+    assert_eq!(ctx.subject(), Some("alice"));
+}
+
+/// Fake propagator: injects a fixed `traceparent`, records what it extracts.
+struct FakePropagator {
+    extracted: Arc<Mutex<Vec<String>>>,
+}
+
+impl TracePropagator for FakePropagator {
+    fn inject(&self, headers: &mut HashMap<String, String>) {
+        headers.insert(
+            propagation::TRACEPARENT_HEADER.to_string(),
+            "00-trace-span-01".to_string(),
+        );
+    }
+
+    fn extract(&self, headers: &HashMap<String, String>, _span: &tracing::Span) {
+        if let Some(parent) = headers.get(propagation::TRACEPARENT_HEADER) {
+            self.extracted.lock().unwrap().push(parent.clone());
+        }
+    }
+}
+
+// Current-thread runtime: the handler task runs on this thread, under the
+// thread-local subscriber that enables the consumer span.
+#[tokio::test]
+async fn trace_propagator_injects_on_emit_and_extracts_on_consume() {
+    let _subscriber = tracing::subscriber::set_default(tracing_subscriber::registry());
+    let extracted = Arc::new(Mutex::new(Vec::new()));
+    propagation::set_trace_propagator(FakePropagator {
+        extracted: extracted.clone(),
+    });
+
+    let (bus, seen) = recording_bus().await;
+    bus.emit(OrderPlaced).await.unwrap();
+    bus.wait_idle().await;
+    propagation::clear_trace_propagator();
+
+    let headers = &seen.lock().unwrap()[0].headers;
+    assert_eq!(headers[propagation::TRACEPARENT_HEADER], "00-trace-span-01");
+    assert!(extracted
+        .lock()
+        .unwrap()
+        .contains(&"00-trace-span-01".to_string()));
+}
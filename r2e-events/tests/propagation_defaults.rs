@@ -0,0 +1,33 @@
+//! Default and opt-out `ContextPropagation` settings. Kept apart from
+//! `propagation.rs`: the settings are process-wide.
+
+use r2e_core::RequestContext;
+use r2e_events::propagation::{self, ContextPropagation, REQUEST_ID_HEADER, SUBJECT_HEADER};
+use r2e_events::EventMetadata;
+
+async fn injected() -> EventMetadata {
+    RequestContext::new()
+        .with_request_id("req-1")
+        .with_subject("alice")
+        .scope(async { propagation::inject(EventMetadata::new()) })
+        .await
+}
+
+#[r2e_core::test]
+async fn request_id_is_propagated_and_subject_is_not_by_default() {
+    assert_eq!(
+        propagation::context_propagation(),
+        ContextPropagation::default()
+    );
+    let metadata = injected().await;
+    assert_eq!(metadata.headers[REQUEST_ID_HEADER], "req-1");
+    assert!(!metadata.headers.contains_key(SUBJECT_HEADER));
+
+    propagation::set_context_propagation(ContextPropagation {
+        request_id: false,
+        subject: false,
+    });
+    let metadata = injected().await;
+    assert!(!metadata.headers.contains_key(REQUEST_ID_HEADER));
+    assert!(!metadata.headers.contains_key(SUBJECT_HEADER));
+}
@@ -14,6 +14,8 @@ pub mod multipart;
 pub mod quic;
 pub mod response;
 pub mod routing;
+#[cfg(feature = "tls")]
+pub mod tls;
 #[cfg(feature = "ws")]
 pub mod ws;
 
@@ -1 +1,2 @@
+pub use axum::handler::Handler;
 pub use axum::routing::{any, delete, get, patch, post, put, MethodRouter, Route};
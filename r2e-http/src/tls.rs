@@ -0,0 +1,513 @@
+// FACADE EXCEPTION: r2e-http sits below r2e-core in the dependency graph
+// (r2e-core depends on r2e-http), so this file cannot use r2e_core::rt.
+// TLS handshakes are spawned on a tokio `JoinSet` directly (see `quic.rs`).
+
+//! Native TLS (HTTPS over TCP) for the axum server.
+//!
+//! - [`TlsConfig`] — a shared, reloadable `rustls` server configuration built
+//!   from PEM files ([`TlsSettings`]). [`TlsConfig::reload_if_changed`]
+//!   swaps in new certificates when the files change; connections already
+//!   established keep their session, new handshakes use the new certificate.
+//! - [`TlsListener`] — an axum [`Listener`] that accepts TCP connections and
+//!   completes TLS handshakes off the accept path.
+//! - [`TlsMakeService`] — the make-service to pass to [`serve`](crate::serve):
+//!   every request carries `ConnectInfo<SocketAddr>` and, when the client
+//!   presented one, its [`PeerCertificates`].
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime};
+
+use axum::serve::{IncomingStream, Listener};
+use rustls::pki_types::CertificateDer;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinSet;
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+
+pub use rustls;
+pub use tokio_rustls;
+
+// ── Error ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+pub enum TlsError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    Tls(String),
+}
+
+impl std::fmt::Display for TlsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "TLS: cannot read {}: {source}", path.display()),
+            Self::Tls(e) => write!(f, "TLS: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for TlsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Tls(_) => None,
+        }
+    }
+}
+
+// ── Configuration ──────────────────────────────────────────────────────────
+
+/// Client-certificate policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClientAuth {
+    /// No client certificate is requested.
+    #[default]
+    None,
+    /// A client certificate is requested and verified when presented;
+    /// clients without one are still accepted.
+    Optional,
+    /// Handshakes without a valid client certificate are rejected.
+    Required,
+}
+
+/// Files and options a [`TlsConfig`] is built from.
+#[derive(Debug, Clone)]
+pub struct TlsSettings {
+    /// PEM certificate chain, leaf first.
+    pub cert: PathBuf,
+    /// PEM private key (PKCS#8, PKCS#1 or SEC1).
+    pub key: PathBuf,
+    /// PEM bundle of CAs trusted for client certificates.
+    pub client_ca: Option<PathBuf>,
+    /// Client-certificate policy. Ignored without `client_ca`.
+    pub client_auth: ClientAuth,
+    /// ALPN protocols, in preference order. Defaults to `h2`, `http/1.1`.
+    pub alpn: Vec<Vec<u8>>,
+}
+
+impl TlsSettings {
+    pub fn new(cert: impl Into<PathBuf>, key: impl Into<PathBuf>) -> Self {
+        Self {
+            cert: cert.into(),
+            key: key.into(),
+            client_ca: None,
+            client_auth: ClientAuth::None,
+            alpn: vec![b"h2".to_vec(), b"http/1.1".to_vec()],
+        }
+    }
+
+    /// Verify client certificates against the CAs in `ca`.
+    pub fn with_client_ca(mut self, ca: impl Into<PathBuf>, client_auth: ClientAuth) -> Self {
+        self.client_ca = Some(ca.into());
+        self.client_auth = client_auth;
+        self
+    }
+
+    pub fn with_alpn<I, P>(mut self, protocols: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<Vec<u8>>,
+    {
+        self.alpn = protocols.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Files whose changes trigger a reload.
+    fn files(&self) -> Vec<&Path> {
+        let mut files = vec![self.cert.as_path(), self.key.as_path()];
+        files.extend(self.client_ca.as_deref());
+        files
+    }
+}
+
+/// Build a [`rustls::ServerConfig`] from PEM-encoded certificate chain, private
+/// key and, for mTLS, client CA bundle.
+pub fn build_server_config(
+    cert_pem: &[u8],
+    key_pem: &[u8],
+    client_ca_pem: Option<&[u8]>,
+    client_auth: ClientAuth,
+    alpn_protocols: Vec<Vec<u8>>,
+) -> Result<rustls::ServerConfig, TlsError> {
+    let certs = parse_certs(cert_pem, "certificate")?;
+    let key = rustls_pemfile::private_key(&mut &*key_pem)
+        .map_err(|e| TlsError::Tls(format!("invalid private key: {e}")))?
+        .ok_or_else(|| TlsError::Tls("no private key found in PEM".into()))?;
+
+    let provider = rustls::crypto::CryptoProvider::get_default()
+        .cloned()
+        .unwrap_or_else(|| Arc::new(rustls::crypto::ring::default_provider()));
+    let builder = rustls::ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| TlsError::Tls(e.to_string()))?;
+
+    let builder = match (client_ca_pem, client_auth) {
+        (Some(ca_pem), ClientAuth::Optional | ClientAuth::Required) => {
+            let mut roots = rustls::RootCertStore::empty();
+            for ca in parse_certs(ca_pem, "client CA")? {
+                roots
+                    .add(ca)
+                    .map_err(|e| TlsError::Tls(format!("invalid client CA: {e}")))?;
+            }
+            let verifier = rustls::server::WebPkiClientVerifier::builder_with_provider(
+                Arc::new(roots),
+                provider,
+            );
+            let verifier = if client_auth == ClientAuth::Optional {
+                verifier.allow_unauthenticated()
+            } else {
+                verifier
+            };
+            let verifier = verifier.build().map_err(|e| TlsError::Tls(e.to_string()))?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        (None, ClientAuth::Optional | ClientAuth::Required) => {
+            return Err(TlsError::Tls(
+                "client certificate verification requires a client CA bundle".into(),
+            ))
+        }
+        (_, ClientAuth::None) => builder.with_no_client_auth(),
+    };
+
+    let mut config = builder
+        .with_single_cert(certs, key)
+        .map_err(|e| TlsError::Tls(e.to_string()))?;
+    config.alpn_protocols = alpn_protocols;
+    Ok(config)
+}
+
+fn parse_certs(pem: &[u8], what: &str) -> Result<Vec<CertificateDer<'static>>, TlsError> {
+    let certs: Vec<_> = rustls_pemfile::certs(&mut &*pem)
+        .collect::<Result<_, _>>()
+        .map_err(|e| TlsError::Tls(format!("invalid {what}: {e}")))?;
+    if certs.is_empty() {
+        return Err(TlsError::Tls(format!("no {what} found in PEM")));
+    }
+    Ok(certs)
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, TlsError> {
+    std::fs::read(path).map_err(|source| TlsError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Modification time and length — a cheap change fingerprint.
+type Stamp = Option<(SystemTime, u64)>;
+
+fn stamp(path: &Path) -> Stamp {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+/// Shared, reloadable TLS server configuration.
+///
+/// Cheap to clone: every clone (e.g. one per sharded worker listener) sees
+/// reloads.
+#[derive(Clone)]
+pub struct TlsConfig {
+    inner: Arc<TlsConfigInner>,
+}
+
+struct TlsConfigInner {
+    settings: TlsSettings,
+    current: RwLock<Arc<rustls::ServerConfig>>,
+    stamps: Mutex<Vec<Stamp>>,
+}
+
+impl TlsConfig {
+    /// Read the PEM files and build the initial configuration.
+    pub fn load(settings: TlsSettings) -> Result<Self, TlsError> {
+        let stamps = settings.files().into_iter().map(stamp).collect();
+        let config = Self::build(&settings)?;
+        Ok(Self {
+            inner: Arc::new(TlsConfigInner {
+                settings,
+                current: RwLock::new(Arc::new(config)),
+                stamps: Mutex::new(stamps),
+            }),
+        })
+    }
+
+    fn build(settings: &TlsSettings) -> Result<rustls::ServerConfig, TlsError> {
+        let ca = settings.client_ca.as_deref().map(read).transpose()?;
+        build_server_config(
+            &read(&settings.cert)?,
+            &read(&settings.key)?,
+            ca.as_deref(),
+            settings.client_auth,
+            settings.alpn.clone(),
+        )
+    }
+
+    pub fn settings(&self) -> &TlsSettings {
+        &self.inner.settings
+    }
+
+    /// The configuration used for new handshakes.
+    pub fn server_config(&self) -> Arc<rustls::ServerConfig> {
+        self.inner
+            .current
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Re-read the PEM files and swap the configuration in. On error the
+    /// current configuration stays active.
+    pub fn reload(&self) -> Result<(), TlsError> {
+        let config = Self::build(&self.inner.settings)?;
+        *self
+            .inner
+            .current
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = Arc::new(config);
+        Ok(())
+    }
+
+    /// Reload when any of the files changed (modification time or size)
+    /// since the last successful load. Returns whether a reload happened.
+    ///
+    /// A failed reload (e.g. the key was not rewritten yet) keeps the old
+    /// configuration and is retried on the next call.
+    pub fn reload_if_changed(&self) -> Result<bool, TlsError> {
+        let now: Vec<Stamp> = self.inner.settings.files().into_iter().map(stamp).collect();
+        let mut stamps = self.inner.stamps.lock().unwrap_or_else(|e| e.into_inner());
+        if *stamps == now {
+            return Ok(false);
+        }
+        self.reload()?;
+        *stamps = now;
+        Ok(true)
+    }
+
+    /// Poll the files every `interval` and reload on change until `shutdown`
+    /// resolves. Reload failures are logged.
+    pub async fn watch(self, interval: Duration, shutdown: impl std::future::Future<Output = ()>) {
+        let mut shutdown = std::pin::pin!(shutdown);
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => return,
+                _ = tokio::time::sleep(interval) => {}
+            }
+            match self.reload_if_changed() {
+                Ok(true) => tracing::info!(
+                    cert = %self.inner.settings.cert.display(),
+                    "TLS certificates reloaded"
+                ),
+                Ok(false) => {}
+                Err(e) => {
+                    tracing::warn!(error = %e, "TLS reload failed; keeping the current certificates")
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for TlsConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TlsConfig")
+            .field("settings", &self.inner.settings)
+            .finish_non_exhaustive()
+    }
+}
+
+// ── Listener ───────────────────────────────────────────────────────────────
+
+/// Default bound on a TLS handshake; slower clients are dropped.
+pub const DEFAULT_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An axum [`Listener`] serving TLS over a [`TcpListener`].
+///
+/// Handshakes run concurrently in the background, so a slow or malicious
+/// client cannot stall the accept loop. Failed and timed-out handshakes are
+/// logged at debug level and dropped.
+pub struct TlsListener {
+    tcp: TcpListener,
+    config: TlsConfig,
+    handshakes: JoinSet<Option<(TlsStream<TcpStream>, SocketAddr)>>,
+    handshake_timeout: Duration,
+    tcp_nodelay: bool,
+}
+
+impl TlsListener {
+    pub fn new(tcp: TcpListener, config: TlsConfig) -> Self {
+        Self {
+            tcp,
+            config,
+            handshakes: JoinSet::new(),
+            handshake_timeout: DEFAULT_HANDSHAKE_TIMEOUT,
+            tcp_nodelay: false,
+        }
+    }
+
+    pub fn with_handshake_timeout(mut self, timeout: Duration) -> Self {
+        self.handshake_timeout = timeout;
+        self
+    }
+
+    /// Set `TCP_NODELAY` on accepted connections.
+    pub fn with_tcp_nodelay(mut self, nodelay: bool) -> Self {
+        self.tcp_nodelay = nodelay;
+        self
+    }
+
+    fn start_handshake(&mut self, stream: TcpStream, addr: SocketAddr) {
+        if self.tcp_nodelay {
+            if let Err(e) = stream.set_nodelay(true) {
+                tracing::warn!(error = %e, "failed to set TCP_NODELAY on accepted connection");
+            }
+        }
+        let acceptor = TlsAcceptor::from(self.config.server_config());
+        let timeout = self.handshake_timeout;
+        self.handshakes.spawn(async move {
+            match tokio::time::timeout(timeout, acceptor.accept(stream)).await {
+                Ok(Ok(tls)) => Some((tls, addr)),
+                Ok(Err(e)) => {
+                    tracing::debug!(%addr, error = %e, "TLS handshake failed");
+                    None
+                }
+                Err(_) => {
+                    tracing::debug!(%addr, "TLS handshake timed out");
+                    None
+                }
+            }
+        });
+    }
+}
+
+impl Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        loop {
+            tokio::select! {
+                accepted = self.tcp.accept() => match accepted {
+                    Ok((stream, addr)) => self.start_handshake(stream, addr),
+                    Err(e) => handle_accept_error(e).await,
+                },
+                Some(done) = self.handshakes.join_next(), if !self.handshakes.is_empty() => {
+                    if let Ok(Some(connection)) = done {
+                        return connection;
+                    }
+                }
+            }
+        }
+    }
+
+    fn local_addr(&self) -> std::io::Result<Self::Addr> {
+        self.tcp.local_addr()
+    }
+}
+
+/// Same policy as axum's TCP listener: per-connection errors are ignored,
+/// anything else (e.g. fd exhaustion) is logged and backed off.
+async fn handle_accept_error(e: std::io::Error) {
+    use std::io::ErrorKind::{ConnectionAborted, ConnectionRefused, ConnectionReset};
+    if matches!(
+        e.kind(),
+        ConnectionRefused | ConnectionAborted | ConnectionReset
+    ) {
+        return;
+    }
+    tracing::error!(error = %e, "accept error");
+    tokio::time::sleep(Duration::from_secs(1)).await;
+}
+
+// ── Per-connection service ─────────────────────────────────────────────────
+
+/// Certificate chain presented by the client (leaf first), inserted as a
+/// request extension on mTLS connections.
+#[derive(Debug, Clone)]
+pub struct PeerCertificates(Arc<[CertificateDer<'static>]>);
+
+impl PeerCertificates {
+    pub fn new(chain: Vec<CertificateDer<'static>>) -> Self {
+        Self(chain.into())
+    }
+
+    /// The client's own certificate.
+    pub fn end_entity(&self) -> Option<&CertificateDer<'static>> {
+        self.0.first()
+    }
+
+    pub fn chain(&self) -> &[CertificateDer<'static>] {
+        &self.0
+    }
+}
+
+/// Make-service for [`serve`](crate::serve) over a [`TlsListener`].
+///
+/// The TLS counterpart of `Router::into_make_service_with_connect_info`:
+/// requests carry `ConnectInfo<SocketAddr>` plus [`PeerCertificates`] when
+/// the client authenticated with a certificate.
+#[derive(Clone)]
+pub struct TlsMakeService {
+    router: crate::Router,
+}
+
+impl TlsMakeService {
+    pub fn new(router: crate::Router) -> Self {
+        Self { router }
+    }
+}
+
+impl<'a> tower::Service<IncomingStream<'a, TlsListener>> for TlsMakeService {
+    type Response = TlsConnectionService;
+    type Error = Infallible;
+    type Future = std::future::Ready<Result<Self::Response, Infallible>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, stream: IncomingStream<'a, TlsListener>) -> Self::Future {
+        let (_, connection) = stream.io().get_ref();
+        let peer = connection
+            .peer_certificates()
+            .filter(|chain| !chain.is_empty())
+            .map(|chain| {
+                PeerCertificates::new(chain.iter().map(|c| c.clone().into_owned()).collect())
+            });
+        std::future::ready(Ok(TlsConnectionService {
+            router: self.router.clone(),
+            remote_addr: *stream.remote_addr(),
+            peer,
+        }))
+    }
+}
+
+/// Serves the requests of one TLS connection. Created by [`TlsMakeService`].
+#[derive(Clone)]
+pub struct TlsConnectionService {
+    router: crate::Router,
+    remote_addr: SocketAddr,
+    peer: Option<PeerCertificates>,
+}
+
+impl tower::Service<crate::Request> for TlsConnectionService {
+    type Response = crate::Response;
+    type Error = Infallible;
+    type Future = <crate::Router as tower::Service<crate::Request>>::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        tower::Service::<crate::Request>::poll_ready(&mut self.router, cx)
+    }
+
+    fn call(&mut self, mut request: crate::Request) -> Self::Future {
+        request
+            .extensions_mut()
+            .insert(crate::ConnectInfo(self.remote_addr));
+        if let Some(peer) = &self.peer {
+            request.extensions_mut().insert(peer.clone());
+        }
+        self.router.call(request)
+    }
+}
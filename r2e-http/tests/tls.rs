@@ -0,0 +1,271 @@
+#![cfg(feature = "tls")]
+
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering::SeqCst};
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+use r2e_http::tls::tokio_rustls::client::TlsStream;
+use r2e_http::tls::tokio_rustls::TlsConnector;
+use r2e_http::tls::{
+    rustls, ClientAuth, PeerCertificates, TlsConfig, TlsListener, TlsMakeService, TlsSettings,
+};
+use r2e_http::{ConnectInfo, Extension, Router};
+use rcgen::{BasicConstraints, CertificateParams, CertifiedIssuer, DnType, IsCa, KeyPair};
+use tempfile::TempDir;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+struct Pki {
+    dir: TempDir,
+    ca: CertifiedIssuer<'static, KeyPair>,
+}
+
+impl Pki {
+    fn new() -> Self {
+        let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params
+            .distinguished_name
+            .push(DnType::CommonName, "Test CA");
+        let ca = CertifiedIssuer::self_signed(params, KeyPair::generate().unwrap()).unwrap();
+        let pki = Self {
+            dir: TempDir::new().unwrap(),
+            ca,
+        };
+        std::fs::write(pki.path("ca.pem"), pki.ca.pem()).unwrap();
+        pki
+    }
+
+    fn path(&self, name: &str) -> std::path::PathBuf {
+        self.dir.path().join(name)
+    }
+
+    /// Issue a certificate signed by the CA; returns `(cert_pem, key_pem)`.
+    fn issue(&self, sans: &[&str], cn: &str) -> (String, String) {
+        let mut params =
+            CertificateParams::new(sans.iter().map(|s| s.to_string()).collect::<Vec<_>>()).unwrap();
+        params.distinguished_name.push(DnType::CommonName, cn);
+        let key = KeyPair::generate().unwrap();
+        let cert = params.signed_by(&key, &self.ca).unwrap();
+        (cert.pem(), key.serialize_pem())
+    }
+
+    fn write_server_cert(&self, cn: &str) {
+        let (cert, key) = self.issue(&["localhost"], cn);
+        write_touched(&self.path("server.pem"), &cert);
+        write_touched(&self.path("server.key"), &key);
+    }
+
+    fn settings(&self) -> TlsSettings {
+        TlsSettings::new(self.path("server.pem"), self.path("server.key"))
+    }
+}
+
+/// Write `contents` with a fresh, strictly increasing mtime so reload
+/// detection does not depend on the filesystem timestamp granularity.
+fn write_touched(path: &Path, contents: &str) {
+    static GENERATION: AtomicU64 = AtomicU64::new(1);
+    std::fs::write(path, contents).unwrap();
+    let mtime = UNIX_EPOCH + Duration::from_secs(1_700_000_000 + GENERATION.fetch_add(1, SeqCst));
+    std::fs::File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(mtime)
+        .unwrap();
+}
+
+async fn serve(config: TlsConfig) -> SocketAddr {
+    let router = Router::new().route(
+        "/whoami",
+        r2e_http::routing::get(
+            |ConnectInfo(addr): ConnectInfo<SocketAddr>,
+             peer: Option<Extension<PeerCertificates>>| async move {
+                assert!(addr.ip().is_loopback());
+                match peer {
+                    Some(Extension(peer)) => format!("client certs: {}", peer.chain().len()),
+                    None => "anonymous".to_string(),
+                }
+            },
+        ),
+    );
+    let tcp = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = tcp.local_addr().unwrap();
+    let listener = TlsListener::new(tcp, config).with_tcp_nodelay(true);
+    tokio::spawn(async move {
+        r2e_http::serve(listener, TlsMakeService::new(router))
+            .await
+            .unwrap();
+    });
+    addr
+}
+
+async fn connect(
+    pki: &Pki,
+    addr: SocketAddr,
+    client_cert: Option<(String, String)>,
+) -> std::io::Result<TlsStream<TcpStream>> {
+    let mut roots = rustls::RootCertStore::empty();
+    roots.add(pki.ca.der().clone()).unwrap();
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = rustls::ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_root_certificates(roots);
+    let mut config = match client_cert {
+        Some((cert, key)) => {
+            let certs = rustls_pemfile::certs(&mut cert.as_bytes())
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            let key = rustls_pemfile::private_key(&mut key.as_bytes())
+                .unwrap()
+                .unwrap();
+            builder.with_client_auth_cert(certs, key).unwrap()
+        }
+        None => builder.with_no_client_auth(),
+    };
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    let tcp = TcpStream::connect(addr).await?;
+    TlsConnector::from(Arc::new(config))
+        .connect("localhost".try_into().unwrap(), tcp)
+        .await
+}
+
+async fn get(stream: &mut TlsStream<TcpStream>, keep_alive: bool) -> std::io::Result<String> {
+    let connection = if keep_alive { "keep-alive" } else { "close" };
+    stream
+        .write_all(
+            format!("GET /whoami HTTP/1.1\r\nhost: localhost\r\nconnection: {connection}\r\n\r\n")
+                .as_bytes(),
+        )
+        .await?;
+    let mut response = Vec::new();
+    if keep_alive {
+        // Read until the (small, single-chunk) body arrives.
+        let mut buf = [0u8; 4096];
+        while !response.ends_with(b"anonymous") {
+            let n = stream.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            response.extend_from_slice(&buf[..n]);
+        }
+    } else {
+        stream.read_to_end(&mut response).await?;
+    }
+    Ok(String::from_utf8_lossy(&response).into_owned())
+}
+
+fn served_cert(stream: &TlsStream<TcpStream>) -> Vec<u8> {
+    stream.get_ref().1.peer_certificates().unwrap()[0].to_vec()
+}
+
+#[tokio::test]
+async fn serves_https_with_connect_info() {
+    let pki = Pki::new();
+    pki.write_server_cert("server");
+    let addr = serve(TlsConfig::load(pki.settings()).unwrap()).await;
+
+    let mut stream = connect(&pki, addr, None).await.unwrap();
+    assert_eq!(stream.get_ref().1.alpn_protocol(), Some(&b"http/1.1"[..]));
+    let response = get(&mut stream, false).await.unwrap();
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+    assert!(response.ends_with("anonymous"), "{response}");
+}
+
+#[tokio::test]
+async fn required_client_auth_rejects_anonymous_clients() {
+    let pki = Pki::new();
+    pki.write_server_cert("server");
+    let settings = pki
+        .settings()
+        .with_client_ca(pki.path("ca.pem"), ClientAuth::Required);
+    let addr = serve(TlsConfig::load(settings).unwrap()).await;
+
+    let client = pki.issue(&[], "billing-service");
+    let mut stream = connect(&pki, addr, Some(client)).await.unwrap();
+    let response = get(&mut stream, false).await.unwrap();
+    assert!(response.ends_with("client certs: 1"), "{response}");
+
+    // TLS 1.3 reports the rejected client certificate on the first read.
+    let rejected = match connect(&pki, addr, None).await {
+        Ok(mut stream) => get(&mut stream, false).await.map(|r| r.is_empty()),
+        Err(_) => Ok(true),
+    };
+    assert!(rejected.unwrap_or(true), "anonymous client was served");
+}
+
+#[tokio::test]
+async fn optional_client_auth_accepts_both() {
+    let pki = Pki::new();
+    pki.write_server_cert("server");
+    let settings = pki
+        .settings()
+        .with_client_ca(pki.path("ca.pem"), ClientAuth::Optional);
+    let addr = serve(TlsConfig::load(settings).unwrap()).await;
+
+    let mut anonymous = connect(&pki, addr, None).await.unwrap();
+    assert!(get(&mut anonymous, false)
+        .await
+        .unwrap()
+        .ends_with("anonymous"));
+
+    let mut authenticated = connect(&pki, addr, Some(pki.issue(&[], "svc")))
+        .await
+        .unwrap();
+    assert!(get(&mut authenticated, false)
+        .await
+        .unwrap()
+        .ends_with("client certs: 1"));
+}
+
+#[tokio::test]
+async fn reload_swaps_certificates_without_dropping_connections() {
+    let pki = Pki::new();
+    pki.write_server_cert("first");
+    let config = TlsConfig::load(pki.settings()).unwrap();
+    let addr = serve(config.clone()).await;
+
+    let mut existing = connect(&pki, addr, None).await.unwrap();
+    assert!(get(&mut existing, true)
+        .await
+        .unwrap()
+        .ends_with("anonymous"));
+    let first = served_cert(&existing);
+
+    assert!(!config.reload_if_changed().unwrap());
+    pki.write_server_cert("second");
+    assert!(config.reload_if_changed().unwrap());
+
+    // New handshakes get the new certificate...
+    let fresh = connect(&pki, addr, None).await.unwrap();
+    assert_ne!(served_cert(&fresh), first);
+    // ...while the established connection keeps working.
+    assert!(get(&mut existing, false)
+        .await
+        .unwrap()
+        .ends_with("anonymous"));
+}
+
+#[tokio::test]
+async fn failed_reload_keeps_the_current_config() {
+    let pki = Pki::new();
+    pki.write_server_cert("server");
+    let config = TlsConfig::load(pki.settings()).unwrap();
+    let before = config.server_config();
+
+    write_touched(&pki.path("server.key"), "not a key");
+    assert!(config.reload_if_changed().is_err());
+    assert!(Arc::ptr_eq(&before, &config.server_config()));
+}
+
+#[test]
+fn client_auth_without_ca_is_an_error() {
+    let pki = Pki::new();
+    pki.write_server_cert("server");
+    let mut settings = pki.settings();
+    settings.client_auth = ClientAuth::Required;
+    assert!(TlsConfig::load(settings).is_err());
+}
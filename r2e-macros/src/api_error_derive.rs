@@ -1,9 +1,7 @@
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote};
-use syn::{
-    parse_macro_input, Attribute, Data, DeriveInput, Fields, Ident, Lit, Meta, Type, Variant,
-};
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Fields, Ident, Lit, Type, Variant};
 
 use crate::crate_path::r2e_core_path;
 
@@ -42,6 +40,10 @@ enum ErrorAttr {
     Standard {
         status: StatusExpr,
         message: Option<String>,
+        /// `type = "..."` — the RFC 9457 problem type URI.
+        problem_type: Option<String>,
+        /// `code = "..."` — the message key translating the message.
+        code: Option<String>,
     },
     Transparent,
 }
@@ -93,6 +95,7 @@ fn generate(input: &DeriveInput) -> syn::Result<TokenStream2> {
     let into_response_impl = gen_into_response(&def, &krate);
     let error_impl = gen_error(&def);
     let from_impls = gen_from_impls(&def);
+    let error_responses = gen_error_responses(&def, &krate);
 
     let (impl_generics, ty_generics, where_clause) = def.generics.split_for_impl();
 
@@ -115,6 +118,12 @@ fn generate(input: &DeriveInput) -> syn::Result<TokenStream2> {
             }
         }
 
+        impl #impl_generics #krate::error::ErrorResponses for #name #ty_generics #where_clause {
+            fn error_responses() -> Vec<#krate::meta::ErrorResponseInfo> {
+                #error_responses
+            }
+        }
+
         #from_impls
     })
 }
@@ -179,49 +188,65 @@ fn parse_error_attr(attrs: &[Attribute], variant_ident: &Ident) -> syn::Result<E
             )
         })?;
 
-    let nested = attr.parse_args_with(
-        syn::punctuated::Punctuated::<Meta, syn::token::Comma>::parse_terminated,
-    )?;
-
-    // Check for #[error(transparent)]
-    for meta in &nested {
-        if let Meta::Path(p) = meta {
-            if p.is_ident("transparent") {
-                return Ok(ErrorAttr::Transparent);
-            }
-        }
-    }
-
+    let mut transparent = false;
     let mut status: Option<StatusExpr> = None;
     let mut message: Option<String> = None;
-
-    for meta in &nested {
-        match meta {
-            Meta::NameValue(nv) if nv.path.is_ident("status") => {
-                status = Some(parse_status_expr(&nv.value)?);
-            }
-            Meta::NameValue(nv) if nv.path.is_ident("message") => {
-                if let syn::Expr::Lit(syn::ExprLit {
-                    lit: Lit::Str(s), ..
-                }) = &nv.value
-                {
-                    message = Some(s.value());
-                } else {
-                    return Err(syn::Error::new_spanned(
-                        &nv.value,
-                        "message must be a string literal",
-                    ));
-                }
-            }
-            _ => {}
+    let mut problem_type: Option<String> = None;
+    let mut code: Option<String> = None;
+
+    // `parse_nested_meta` (unlike `Meta`) accepts the `type` keyword as a key.
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("transparent") {
+            transparent = true;
+        } else if meta.path.is_ident("status") {
+            status = Some(parse_status_expr(&meta.value()?.parse()?)?);
+        } else if meta.path.is_ident("message") {
+            message = Some(parse_string_value(
+                meta.value()?.parse()?,
+                "message must be a string literal",
+            )?);
+        } else if meta.path.is_ident("type") {
+            problem_type = Some(parse_string_value(
+                meta.value()?.parse()?,
+                "type must be a string literal (a problem type URI)",
+            )?);
+        } else if meta.path.is_ident("code") {
+            code = Some(parse_string_value(
+                meta.value()?.parse()?,
+                "code must be a string literal (a message key)",
+            )?);
+        } else if meta.input.peek(syn::Token![=]) {
+            // Unknown keys are ignored.
+            meta.value()?.parse::<syn::Expr>()?;
+        } else if meta.input.peek(syn::token::Paren) {
+            meta.input.parse::<proc_macro2::Group>()?;
         }
+        Ok(())
+    })?;
+
+    if transparent {
+        return Ok(ErrorAttr::Transparent);
     }
 
     let status = status.ok_or_else(|| {
         syn::Error::new_spanned(attr, "#[error(...)] requires status = STATUS_CODE")
     })?;
 
-    Ok(ErrorAttr::Standard { status, message })
+    Ok(ErrorAttr::Standard {
+        status,
+        message,
+        problem_type,
+        code,
+    })
+}
+
+fn parse_string_value(expr: syn::Expr, error: &str) -> syn::Result<String> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: Lit::Str(s), ..
+        }) => Ok(s.value()),
+        other => Err(syn::Error::new_spanned(other, error)),
+    }
 }
 
 fn parse_status_expr(expr: &syn::Expr) -> syn::Result<StatusExpr> {
@@ -410,13 +435,178 @@ fn gen_into_response(def: &ApiErrorDef, krate: &TokenStream2) -> TokenStream2 {
         .map(|v| gen_response_arm(name, v, krate))
         .collect();
 
+    // `#[error(type = "...")]` variants set the type of the attached problem.
+    let type_arms: Vec<TokenStream2> = def
+        .variants
+        .iter()
+        .filter_map(|v| match &v.error_attr {
+            ErrorAttr::Standard {
+                problem_type: Some(ty),
+                ..
+            } => {
+                let vname = &v.ident;
+                Some(quote! { #name::#vname { .. } => Some(#ty), })
+            }
+            _ => None,
+        })
+        .collect();
+
+    // `#[error(code = "...")]` variants attach their message key, with the
+    // fields their message interpolates as arguments.
+    let key_arms: Vec<TokenStream2> = def
+        .variants
+        .iter()
+        .filter_map(|v| match &v.error_attr {
+            ErrorAttr::Standard {
+                code: Some(code),
+                message,
+                ..
+            } => Some(gen_message_key_arm(
+                name,
+                v,
+                code,
+                message.as_deref(),
+                krate,
+            )),
+            _ => None,
+        })
+        .collect();
+
+    if type_arms.is_empty() && key_arms.is_empty() {
+        return quote! {
+            match self {
+                #(#arms)*
+            }
+        };
+    }
+
+    let (type_prelude, type_apply) = if type_arms.is_empty() {
+        (quote! {}, quote! {})
+    } else {
+        (
+            quote! {
+                let __problem_type: Option<&'static str> = match &self {
+                    #(#type_arms)*
+                    _ => None,
+                };
+            },
+            quote! {
+                let __response = match __problem_type {
+                    Some(__type) => #krate::problem::with_problem_type(__response, __type),
+                    None => __response,
+                };
+            },
+        )
+    };
+    let (key_prelude, key_apply) = if key_arms.is_empty() {
+        (quote! {}, quote! {})
+    } else {
+        (
+            quote! {
+                let __message_key: Option<#krate::i18n::MessageKey> = match &self {
+                    #(#key_arms)*
+                    _ => None,
+                };
+            },
+            quote! {
+                let __response = match __message_key {
+                    Some(__key) => __key.attach(__response),
+                    None => __response,
+                };
+            },
+        )
+    };
+
     quote! {
-        match self {
+        #type_prelude
+        #key_prelude
+        let __response = match self {
             #(#arms)*
+        };
+        #type_apply
+        #key_apply
+        __response
+    }
+}
+
+/// `Variant { .. } => Some(MessageKey::new(code).arg(..)),` matching `&self`,
+/// with an argument per field the message interpolates.
+fn gen_message_key_arm(
+    enum_name: &Ident,
+    variant: &ApiErrorVariant,
+    code: &str,
+    message: Option<&str>,
+    krate: &TokenStream2,
+) -> TokenStream2 {
+    let vname = &variant.ident;
+    let placeholders = message.map(message_placeholders).unwrap_or_default();
+    let (pattern, args): (TokenStream2, Vec<TokenStream2>) = match &variant.fields {
+        VariantFields::Unit => (quote!(#enum_name::#vname), Vec::new()),
+        VariantFields::Tuple(fields) => {
+            let bindings: Vec<TokenStream2> = (0..fields.len())
+                .map(|i| {
+                    let id = format_ident!("_{}", i);
+                    quote!(#id)
+                })
+                .collect();
+            let args = placeholders
+                .iter()
+                .filter_map(|(name, spec)| {
+                    let index: usize = name.parse().ok()?;
+                    (index < fields.len()).then(|| {
+                        let fmt = format!("{{_{index}{spec}}}");
+                        quote!(.arg(#name, format!(#fmt)))
+                    })
+                })
+                .collect();
+            (quote!(#enum_name::#vname(#(#bindings),*)), args)
         }
+        VariantFields::Named(fields) => {
+            let field_names: Vec<&Ident> = fields.iter().map(|f| &f.name).collect();
+            let args = placeholders
+                .iter()
+                .filter(|(name, _)| field_names.iter().any(|f| *f == name))
+                .map(|(name, spec)| {
+                    let fmt = format!("{{{name}{spec}}}");
+                    quote!(.arg(#name, format!(#fmt)))
+                })
+                .collect();
+            (quote!(#enum_name::#vname { #(#field_names),* }), args)
+        }
+    };
+    quote! {
+        #[allow(unused_variables)]
+        #pattern => Some(#krate::i18n::MessageKey::new(#code) #(#args)*),
     }
 }
 
+/// The `{name}` / `{name:spec}` placeholders of a message, without
+/// duplicates: `(name, ":spec")`.
+fn message_placeholders(message: &str) -> Vec<(String, String)> {
+    let mut placeholders: Vec<(String, String)> = Vec::new();
+    let mut rest = message;
+    while let Some(start) = rest.find('{') {
+        let tail = &rest[start..];
+        if let Some(after) = tail.strip_prefix("{{") {
+            rest = after;
+            continue;
+        }
+        let Some(end) = tail.find('}') else {
+            break;
+        };
+        let inner = &tail[1..end];
+        let (name, spec) = match inner.find(':') {
+            Some(colon) => (&inner[..colon], &inner[colon..]),
+            None => (inner, ""),
+        };
+        if !name.is_empty() && !placeholders.iter().any(|(n, _)| n == name) {
+            placeholders.push((name.to_string(), spec.to_string()));
+        }
+        rest = &tail[end + 1..];
+    }
+    placeholders
+}
+
 fn gen_response_arm(
     enum_name: &Ident,
     variant: &ApiErrorVariant,
@@ -429,7 +619,9 @@ fn gen_response_arm(
                 #pattern => #krate::http::response::IntoResponse::into_response(#inner_expr),
             }
         }
-        ErrorAttr::Standard { status, message } => {
+        ErrorAttr::Standard {
+            status, message, ..
+        } => {
             let status_tokens = status_to_tokens(status, krate);
             gen_response_arm_with_pattern(
                 enum_name,
@@ -451,6 +643,14 @@ fn gen_response_arm_with_pattern(
 ) -> TokenStream2 {
     let vname = &variant.ident;
 
+    if message.is_none() && is_validation_variant(variant) {
+        return quote! {
+            #enum_name::#vname(ref _0) => {
+                #krate::validation::validation_error_response(#status_tokens, _0)
+            }
+        };
+    }
+
     match message {
         Some(msg) => {
             let (pattern, fmt_str) = interpolated_message_pattern(enum_name, variant, msg);
@@ -543,6 +743,59 @@ fn gen_response_arm_with_pattern(
     }
 }
 
+// ── Codegen: ErrorResponses ──────────────────────────────────────────────
+
+/// One `ErrorResponseInfo` per standard variant; transparent variants add
+/// the inner type's responses when it implements `ErrorResponses`.
+fn gen_error_responses(def: &ApiErrorDef, krate: &TokenStream2) -> TokenStream2 {
+    let pushes: Vec<TokenStream2> = def
+        .variants
+        .iter()
+        .map(|v| match &v.error_attr {
+            ErrorAttr::Transparent => {
+                let ty = match &v.fields {
+                    VariantFields::Tuple(fields) => &fields[0].ty,
+                    VariantFields::Named(fields) => &fields[0].ty,
+                    VariantFields::Unit => unreachable!("transparent variants have one field"),
+                };
+                quote! {
+                    {
+                        let __probe = #krate::error::__ErrorResponsesProbe::<#ty>(::core::marker::PhantomData);
+                        use #krate::error::__NoErrorResponses as _;
+                        __v.extend((&__probe).error_responses());
+                    }
+                }
+            }
+            ErrorAttr::Standard {
+                status,
+                message,
+                problem_type,
+                ..
+            } => {
+                let status = status_to_tokens(status, krate);
+                let problem_type = problem_type
+                    .as_ref()
+                    .map(|ty| quote! { .with_problem_type(#ty) });
+                let description = message.clone().unwrap_or_else(|| humanize_ident(&v.ident));
+                let ctor = if message.is_none() && is_validation_variant(v) {
+                    quote! { validation }
+                } else {
+                    quote! { new }
+                };
+                quote! {
+                    __v.push(#krate::meta::ErrorResponseInfo::#ctor((#status).as_u16(), #description)#problem_type);
+                }
+            }
+        })
+        .collect();
+
+    quote! {
+        let mut __v = Vec::new();
+        #(#pushes)*
+        __v
+    }
+}
+
 // ── Codegen: std::error::Error ───────────────────────────────────────────
 
 fn gen_error(def: &ApiErrorDef) -> TokenStream2 {
@@ -690,6 +943,23 @@ fn humanize_ident(ident: &Ident) -> String {
     result
 }
 
+/// A variant carrying only a `ValidationErrorResponse` renders it as the
+/// standard validation body (with the variant's status) instead of a
+/// humanized message.
+fn is_validation_variant(variant: &ApiErrorVariant) -> bool {
+    match &variant.fields {
+        VariantFields::Tuple(fields) if fields.len() == 1 => {
+            if let Type::Path(tp) = &fields[0].ty {
+                if let Some(seg) = tp.path.segments.last() {
+                    return seg.ident == "ValidationErrorResponse";
+                }
+            }
+            false
+        }
+        _ => false,
+    }
+}
+
 fn is_string_type(ty: &Type) -> bool {
     if let Type::Path(tp) = ty {
         if let Some(seg) = tp.path.segments.last() {
@@ -546,6 +546,7 @@ fn generate(item_impl: &ItemImpl, bean_args: &BeanArgs) -> syn::Result<Generated
             filter: cm.config.filter.clone(),
             retry: cm.config.retry,
             dlq: cm.config.dlq.clone(),
+            ordered_by: cm.config.ordered_by.clone(),
         })
         .collect();
     // A bean implements `EventSubscriber` for its own type, so custom
@@ -822,12 +823,13 @@ fn scan_consumer_methods(
                         || config.deserializer.is_some()
                         || config.filter.is_some()
                         || config.retry.is_some()
-                        || config.dlq.is_some())
+                        || config.dlq.is_some()
+                        || config.ordered_by.is_some())
                 {
                     return Err(syn::Error::new_spanned(
                         &method.sig,
                         "a request-reply #[consumer] (non-`()` return) is a responder and does not \
-                         support `topic`/`deserializer`/`filter`/`retry`/`dlq` — those are fan-out options",
+                         support `topic`/`deserializer`/`filter`/`retry`/`dlq`/`ordered_by` — those are fan-out options",
                     ));
                 }
 
@@ -34,6 +34,8 @@ pub fn generate_controller_impl(def: &RoutesImplDef) -> TokenStream {
     let route_metadata_items = generate_route_metadata(def, name, &meta_mod);
     let sse_metadata_items = generate_sse_route_metadata(def, name, &meta_mod);
     let ws_metadata_items = generate_ws_route_metadata(def, name, &meta_mod);
+    let consumer_metadata_items = generate_consumer_metadata(def, name);
+    let emitter_metadata_items = generate_emitter_metadata(def, name);
     // Off-request (transverse) wiring: ScheduledSource / EventSubscriber /
     // BeanDecoFill / PostConstruct impls (module scope) + the Controller method
     // overrides that delegate to them.
@@ -67,18 +69,76 @@ pub fn generate_controller_impl(def: &RoutesImplDef) -> TokenStream {
         quote! {}
     };
 
+    // #[since] / #[until] / #[deprecated_in] are checked against the
+    // `versions` of the #[controller] side, like the fallback prefix above.
+    let version_asserts: Vec<TokenStream> = def
+        .route_methods
+        .iter()
+        .map(|rm| (&rm.fn_item, rm.decorators.versions))
+        .chain(
+            def.sse_methods
+                .iter()
+                .map(|sm| (&sm.fn_item, sm.decorators.versions)),
+        )
+        .chain(
+            def.ws_methods
+                .iter()
+                .map(|wm| (&wm.fn_item, wm.decorators.versions)),
+        )
+        .filter(|(_, versions)| !versions.is_empty())
+        .map(|(fn_item, versions)| {
+            let fn_name = fn_item.sig.ident.to_string();
+            let unversioned = format!(
+                "`{fn_name}` uses #[since], #[until] or #[deprecated_in], but its controller \
+                 has no `versions = \"...\"` in #[controller]"
+            );
+            let bounds: Vec<TokenStream> = [
+                ("since", versions.since),
+                ("until", versions.until),
+                ("deprecated_in", versions.deprecated_in),
+            ]
+            .into_iter()
+            .filter_map(|(attr, version)| {
+                let version = version?;
+                let message = format!(
+                    "`{fn_name}`: #[{attr}({version})] is outside the controller's `versions`"
+                );
+                Some(quote! { assert!(#version >= __low && #version <= __high, #message); })
+            })
+            .collect();
+            quote::quote_spanned! {fn_item.sig.ident.span()=>
+                const _: () = match #meta_mod::VERSIONS {
+                    None => panic!(#unversioned),
+                    Some((__low, __high)) => { #(#bounds)* }
+                };
+            }
+        })
+        .collect();
+
     // Only emit extend() calls for non-empty metadata lists to avoid
     // type inference issues with empty vec![].
     let register_meta_stmts = {
         let mut stmts = Vec::new();
         if !route_metadata_items.is_empty() {
-            stmts.push(quote! { __registry.extend(vec![#(#route_metadata_items),*]); });
+            // Route limits in the metadata reflect `r2e.routes.*` overrides.
+            stmts.push(quote! { let __r2e_config = __registry.config().cloned(); });
+            stmts.push(quote! { __registry.extend(vec![#(#route_metadata_items),*].into_iter().flatten()); });
         }
         if !sse_metadata_items.is_empty() {
-            stmts.push(quote! { __registry.extend(vec![#(#sse_metadata_items),*]); });
+            stmts.push(
+                quote! { __registry.extend(vec![#(#sse_metadata_items),*].into_iter().flatten()); },
+            );
         }
         if !ws_metadata_items.is_empty() {
-            stmts.push(quote! { __registry.extend(vec![#(#ws_metadata_items),*]); });
+            stmts.push(
+                quote! { __registry.extend(vec![#(#ws_metadata_items),*].into_iter().flatten()); },
+            );
+        }
+        if !consumer_metadata_items.is_empty() {
+            stmts.push(quote! { __registry.extend(vec![#(#consumer_metadata_items),*]); });
+        }
+        if !emitter_metadata_items.is_empty() {
+            stmts.push(quote! { __registry.extend(vec![#(#emitter_metadata_items),*]); });
         }
         stmts
     };
@@ -102,15 +162,15 @@ pub fn generate_controller_impl(def: &RoutesImplDef) -> TokenStream {
     let application_router_body = quote! {
         |__ctrl: ::std::sync::Arc<#name>, __ctx: &#krate::beans::BeanContext| {
             #ctrl_router_setup
-            let mut __inner = #krate::http::Router::new()
+            let mut __inner = #krate::versioning::VersionedRoutes::new(
+                #meta_mod::PATH_PREFIX,
+                #meta_mod::VERSIONS,
+            )
                 #(#route_registrations)*
                 #(#sse_route_registrations)*
                 #(#ws_route_registrations)*;
             #(#pre_auth_registrations)*
-            match #meta_mod::PATH_PREFIX {
-                Some("/") | None => __inner,
-                Some(__prefix) => #krate::http::Router::new().nest(__prefix, __inner),
-            }
+            __inner.into_router()
         }
     };
 
@@ -188,6 +248,7 @@ pub fn generate_controller_impl(def: &RoutesImplDef) -> TokenStream {
         #transverse_items
 
         #fallback_prefix_assert
+        #(#version_asserts)*
 
         // State-independent carrier of the full dep list (core ++ decorator
         // deps) — lets `register_module` check decorator deps in the NoState
@@ -266,8 +327,39 @@ fn generate_route_metadata(
             let handler_param_types = extract_handler_param_types(rm);
             let (body_type_token, body_schema_token, body_content_type_token) =
                 extract_body_info(rm);
-            let (response_type_token, response_schema_token) = extract_response_info(rm);
-            let response_unmapped_token = response_unmapped_token(rm);
+            // A #[template] route answers with HTML, not its context type.
+            let (response_type_token, response_schema_token, response_unmapped_token, response_content_type) =
+                if rm.decorators.template.is_some() {
+                    (quote! { None }, quote! { None }, quote! { None }, quote! { Some("text/html".to_string()) })
+                } else {
+                    let (response_type, response_schema) = extract_response_info(rm);
+                    (response_type, response_schema, response_unmapped_token(rm), quote! { None })
+                };
+            let error_responses_token = error_responses_token(rm, &krate);
+            let route_key = format!("{}.{}", name, rm.fn_item.sig.ident);
+            let limits = route_limits_tokens(rm.decorators.limits.or(def.controller_limits));
+            let priority =
+                priority_extension_tokens(rm.decorators.priority.or(def.controller_priority));
+            let media_types = rm.decorators.media_types.or(&def.controller_media_types);
+            let produces = &media_types.produces;
+            let consumes = &media_types.consumes;
+            let (etag_errors, etag_params) = if rm.decorators.etag || def.controller_etag {
+                (
+                    quote! { __e.extend(#krate::conditional::error_responses(#method)); },
+                    quote! { __p.extend(#krate::conditional::params(#method)); },
+                )
+            } else {
+                (quote! {}, quote! {})
+            };
+            let (idempotency_errors, idempotency_params) =
+                if rm.decorators.idempotent_ttl_ms.is_some() {
+                    (
+                        quote! { __e.extend(#krate::idempotency::error_responses()); },
+                        quote! { __p.extend(#krate::idempotency::params()); },
+                    )
+                } else {
+                    (quote! {}, quote! {})
+                };
 
             // Extract doc comments for summary + description
             let (doc_summary, doc_description) =
@@ -301,20 +393,43 @@ fn generate_route_metadata(
 
             // Autoref specialization: for each handler param type, probe for ParamsMetadata.
             // Types implementing ParamsMetadata return their param infos; others return empty vec.
+            // A `Query<T>` without ParamsMetadata falls back to T's JSON schema properties.
             let probe_blocks: Vec<TokenStream> = handler_param_types
                 .iter()
-                .map(|ty| {
+                .map(|(ty, is_query)| {
+                    let schema_fallback = if *is_query {
+                        let json_schema = crate::params_derive::json_schema_probe(ty, &krate);
+                        quote! {
+                            if __infos.is_empty() {
+                                if let Some(__schema) = #json_schema {
+                                    __infos = #krate::params::query_param_infos(&__schema);
+                                }
+                            }
+                        }
+                    } else {
+                        quote! {}
+                    };
                     quote! {
                         {
                             let __probe = #krate::params::__ParamMetaProbe::<#ty>(::core::marker::PhantomData);
                             use #krate::params::__NoParamsMeta as _;
-                            __p.extend((&__probe).param_infos());
+                            #[allow(unused_mut)]
+                            let mut __infos = (&__probe).param_infos();
+                            #schema_fallback
+                            __p.extend(__infos);
                         }
                     }
                 })
                 .collect();
+            let versions = route_versions_tokens(rm.decorators.versions);
 
             quote! {
+                #krate::versioning::__expand(#meta_mod::VERSIONS, #versions, {
+                let __limits = #krate::route_limits::__resolve(
+                    __r2e_config.as_ref(),
+                    #route_key,
+                    #limits,
+                );
                 #krate::meta::RouteInfo {
                     path: match #meta_mod::PATH_PREFIX {
                         Some(__prefix) => format!("{}{}", __prefix, #route_path_str),
@@ -328,13 +443,29 @@ fn generate_route_metadata(
                     request_body_schema: #body_schema_token,
                     request_body_content_type: #body_content_type_token,
                     request_body_required: #body_required,
+                    consumes: vec![#(#consumes.to_string()),*],
                     response_type: #response_type_token,
                     response_schema: #response_schema_token,
+                    response_content_type: #response_content_type,
+                    produces: vec![#(#produces.to_string()),*],
                     response_status: #status_code,
                     response_unmapped: #response_unmapped_token,
+                    error_responses: {
+                        let mut __e: Vec<#krate::meta::ErrorResponseInfo> = #error_responses_token;
+                        __e.extend(__limits.error_responses());
+                        __e.extend(#krate::negotiation::error_responses(
+                            &[#(#produces),*],
+                            &[#(#consumes),*],
+                        ));
+                        #etag_errors
+                        #idempotency_errors
+                        __e
+                    },
                     params: {
                         let mut __p: Vec<#krate::meta::ParamInfo> = vec![#(#path_params),*];
                         #(#probe_blocks)*
+                        #etag_params
+                        #idempotency_params
                         // Deduplicate params by (name, location) — possible when
                         // a Params struct includes #[path] fields alongside Path<T>.
                         {
@@ -347,12 +478,151 @@ fn generate_route_metadata(
                     tag: Some(#tag.to_string()),
                     deprecated: #deprecated,
                     has_auth: #has_auth,
+                    extensions: {
+                        #[allow(unused_mut)]
+                        let mut __x = __limits.openapi_extensions();
+                        #priority
+                        __x
+                    },
                 }
+                })
             }
         })
         .collect()
 }
 
+/// Statement inserting a route's effective `#[priority]` into the `__x`
+/// extensions map, or nothing when no priority is set.
+fn priority_extension_tokens(priority: Option<crate::types::RoutePriorityAttr>) -> TokenStream {
+    let krate = r2e_core_path();
+    match priority {
+        Some(priority) => {
+            let class = priority.as_str();
+            quote! {
+                __x.insert(#krate::meta::PRIORITY_EXTENSION.to_string(), #class.into());
+            }
+        }
+        None => quote! {},
+    }
+}
+
+/// Wrap a route's method router in the `#[produces]` / `#[consumes]`
+/// negotiation layer — innermost, so guards and middleware run first. A
+/// no-op without either attribute.
+fn with_negotiation(
+    route_key: &str,
+    media_types: &crate::types::RouteMediaTypesAttr,
+    router: TokenStream,
+) -> TokenStream {
+    if media_types.is_empty() {
+        return router;
+    }
+    let krate = r2e_core_path();
+    let produces = &media_types.produces;
+    let consumes = &media_types.consumes;
+    quote! {
+        #krate::negotiation::__apply(
+            __ctx,
+            #route_key,
+            &[#(#produces),*],
+            &[#(#consumes),*],
+            #router,
+        )
+    }
+}
+
+/// Wrap a route's method router in the `#[template]` layer, innermost:
+/// the page is rendered inside the handler, so every outer layer sees the
+/// HTML. A no-op without the attribute.
+fn with_template(
+    route_key: &str,
+    template: Option<&syn::LitStr>,
+    router: TokenStream,
+) -> TokenStream {
+    let Some(template) = template else {
+        return router;
+    };
+    let krate = r2e_core_path();
+    quote! { #krate::templating::__apply(__ctx, #route_key, #template, #router) }
+}
+
+/// Wrap a route's method router in the `#[etag]` conditional-request
+/// layer, outside the negotiation layer so the tag covers the negotiated
+/// representation. A no-op without the attribute.
+fn with_etag(etag: bool, router: TokenStream) -> TokenStream {
+    if !etag {
+        return router;
+    }
+    let krate = r2e_core_path();
+    quote! { #krate::conditional::__apply(#router) }
+}
+
+/// Wrap a route's method router in the `#[idempotent]` layer, outside the
+/// conditional-request layer so the stored response is the one the client
+/// received. A no-op without the attribute.
+fn with_idempotency(idempotent: bool, router: TokenStream) -> TokenStream {
+    if !idempotent {
+        return router;
+    }
+    let krate = r2e_core_path();
+    quote! { #krate::idempotency::__apply(#router) }
+}
+
+/// A `RouteVersions` literal for a method's `#[since]` / `#[until]` /
+/// `#[deprecated_in]` values.
+fn route_versions_tokens(versions: crate::types::RouteVersionsAttr) -> TokenStream {
+    let krate = r2e_core_path();
+    let opt = |value: Option<u32>| match value {
+        Some(v) => quote! { Some(#v) },
+        None => quote! { None },
+    };
+    let opt_secs = |value: Option<u64>| match value {
+        Some(v) => quote! { Some(#v) },
+        None => quote! { None },
+    };
+    let since = opt(versions.since);
+    let until = opt(versions.until);
+    let deprecated_in = opt(versions.deprecated_in);
+    let deprecation_date = opt_secs(versions.deprecation_date);
+    let sunset = opt_secs(versions.sunset);
+    quote! {
+        #krate::versioning::RouteVersions {
+            since: #since,
+            until: #until,
+            deprecated_in: #deprecated_in,
+            deprecation_date: #deprecation_date,
+            sunset: #sunset,
+        }
+    }
+}
+
+/// A `RouteLimits` literal for a route's effective `#[timeout]` /
+/// `#[body_limit]` / `#[max_concurrent]` values.
+fn route_limits_tokens(limits: crate::types::RouteLimitsAttr) -> TokenStream {
+    let krate = r2e_core_path();
+    let timeout = match limits.timeout_ms {
+        Some(ms) => quote! { Some(::std::time::Duration::from_millis(#ms)) },
+        None => quote! { None },
+    };
+    let body_limit = match limits.body_limit {
+        Some(bytes) => {
+            quote! { Some(#krate::route_limits::BodyLimit::Max(#bytes as usize)) }
+        }
+        None => quote! { None },
+    };
+    let max_concurrent = match limits.max_concurrent {
+        Some(max) => quote! { Some(#max as usize) },
+        None => quote! { None },
+    };
+    quote! {
+        #krate::route_limits::RouteLimits {
+            timeout: #timeout,
+            body_limit: #body_limit,
+            max_concurrent: #max_concurrent,
+        }
+    }
+}
+
 /// The `RouteInfo.has_auth` expression for a route.
 ///
 /// Normal routes: roles, an identity param, guard fns, or the struct-level
@@ -389,12 +659,35 @@ fn extract_path_params(rm: &crate::types::RouteMethod, krate: &TokenStream) -> V
                         if let Some(elem) = ts.elems.first() {
                             let param_name = quote!(#elem).to_string();
                             let param_type = infer_path_param_openapi_type(&pt.ty);
+                            let inner = unwrap_extractor_inner(&pt.ty);
+                            // Tuple paths name no single parameter type
+                            let schema = if matches!(inner, syn::Type::Path(_)) {
+                                let json_schema =
+                                    crate::params_derive::json_schema_probe(&inner, krate);
+                                let fallback =
+                                    crate::params_derive::static_schema_tokens(&inner, krate);
+                                quote! {
+                                    Some(#krate::params::__param_schema(
+                                        #json_schema,
+                                        #fallback,
+                                        #krate::serde_json::Value::Null,
+                                        None,
+                                    ))
+                                }
+                            } else {
+                                quote! { None }
+                            };
                             return Some(quote! {
                                 #krate::meta::ParamInfo {
                                     name: #param_name.to_string(),
                                     location: #krate::meta::ParamLocation::Path,
                                     param_type: #param_type.to_string(),
                                     required: true,
+                                    schema: #schema,
+                                    description: None,
+                                    example: None,
+                                    style: None,
+                                    explode: None,
                                 }
                             });
                         }
@@ -408,18 +701,22 @@ fn extract_path_params(rm: &crate::types::RouteMethod, krate: &TokenStream) -> V
         .collect()
 }
 
-/// Extract the types to probe for `ParamsMetadata` from handler parameters.
+/// Extract the types to probe for `ParamsMetadata` from handler parameters,
+/// flagged when they come from a `Query<T>` extractor.
 ///
 /// For wrapper types like `Query<T>`, `Path<T>`, we unwrap to probe the inner
 /// type `T` instead, since `T` is where `ParamsMetadata` would be implemented.
-fn extract_handler_param_types(rm: &crate::types::RouteMethod) -> Vec<syn::Type> {
+fn extract_handler_param_types(rm: &crate::types::RouteMethod) -> Vec<(syn::Type, bool)> {
     rm.fn_item
         .sig
         .inputs
         .iter()
         .filter_map(|arg| {
             if let syn::FnArg::Typed(pt) = arg {
-                Some(unwrap_extractor_inner(&pt.ty))
+                Some((
+                    unwrap_extractor_inner(&pt.ty),
+                    type_last_segment_is(&pt.ty, "Query"),
+                ))
             } else {
                 None // skip &self
             }
@@ -515,7 +812,7 @@ fn detect_body_required(rm: &crate::types::RouteMethod) -> bool {
     true
 }
 
-/// Check if a type is `Option<Json<T>>`.
+/// Check if a type is `Option<Json<T>>` (or `Option<Body<T>>`).
 fn is_option_wrapping_json(ty: &syn::Type) -> bool {
     if let syn::Type::Path(type_path) = ty {
         if let Some(segment) = type_path.path.segments.last() {
@@ -531,11 +828,14 @@ fn is_option_wrapping_json(ty: &syn::Type) -> bool {
     false
 }
 
-/// Check if a type contains Json (is `Json<T>` or a destructured pattern).
+/// Check if a type is a typed body extractor: `Json<T>` or the negotiated
+/// `Body<T>`.
 fn has_json_type(ty: &syn::Type) -> bool {
     if let syn::Type::Path(type_path) = ty {
         if let Some(segment) = type_path.path.segments.last() {
-            return segment.ident == "Json";
+            return segment.ident == "Json"
+                || (segment.ident == "Body"
+                    && matches!(segment.arguments, syn::PathArguments::AngleBracketed(_)));
         }
     }
     false
@@ -558,11 +858,12 @@ fn unwrap_result_type(ty: &syn::Type) -> &syn::Type {
     ty
 }
 
-/// Extract the inner type from `Json<T>` → `T`.
+/// Extract the inner type from `Json<T>` or `Negotiated<T>` → `T`.
 fn unwrap_json_type(ty: &syn::Type) -> Option<&syn::Type> {
     if let syn::Type::Path(type_path) = ty {
         if let Some(segment) = type_path.path.segments.last() {
-            if segment.ident == "Json" {
+            if segment.ident == "Json" || segment.ident == "Negotiated" || segment.ident == "Tagged"
+            {
                 if let syn::PathArguments::AngleBracketed(ref args) = segment.arguments {
                     if let Some(syn::GenericArgument::Type(inner)) = args.args.first() {
                         return Some(inner);
@@ -619,17 +920,31 @@ fn type_to_schema_name(ty: &syn::Type) -> String {
 /// the inherent method wins and returns `Some(#some_body)` (with `T` bound to
 /// `ty`); otherwise the trait fallback returns `None`. Lets optional schema
 /// discovery work without requiring the bound on every type.
-fn autoref_schema_probe(ty: &syn::Type, bound: TokenStream, some_body: TokenStream) -> TokenStream {
+pub(crate) fn autoref_schema_probe(
+    ty: &syn::Type,
+    bound: TokenStream,
+    some_body: TokenStream,
+) -> TokenStream {
     let krate = r2e_core_path();
+    autoref_probe(ty, bound, quote! { #krate::serde_json::Value }, some_body)
+}
+
+/// [`autoref_schema_probe`] for an arbitrary `Option<#output>` result.
+fn autoref_probe(
+    ty: &syn::Type,
+    bound: TokenStream,
+    output: TokenStream,
+    some_body: TokenStream,
+) -> TokenStream {
     quote! {
         {
             struct __SchemaProbe<T>(::core::marker::PhantomData<T>);
             trait __NoSchema {
-                fn __schema(&self) -> Option<#krate::serde_json::Value> { None }
+                fn __schema(&self) -> Option<#output> { None }
             }
             impl<T> __NoSchema for &__SchemaProbe<T> {}
             impl<T: #bound> __SchemaProbe<T> {
-                fn __schema(&self) -> Option<#krate::serde_json::Value> {
+                fn __schema(&self) -> Option<#output> {
                     Some(#some_body)
                 }
             }
@@ -692,7 +1007,7 @@ fn resolve_response_type(rm: &crate::types::RouteMethod) -> Option<syn::Type> {
         }
     }
 
-    // Try to unwrap Json<T>
+    // Try to unwrap Json<T> / Negotiated<T>
     unwrap_json_type(unwrapped).cloned()
 }
 
@@ -776,9 +1091,72 @@ fn response_unmapped_name(rm: &crate::types::RouteMethod) -> Option<String> {
     Some(readable_type(unwrapped))
 }
 
+/// The `RouteInfo.error_responses` expression for a route.
+///
+/// Probes the `E` of a `Result<T, E>` return type for `ErrorResponses`
+/// (implemented by `#[derive(ApiError)]`), and adds `429` when a guard is a
+/// `RateLimit`.
+fn error_responses_token(rm: &crate::types::RouteMethod, krate: &TokenStream) -> TokenStream {
+    let probe = result_error_type(rm).map(|ty| {
+        quote! {
+            {
+                let __probe = #krate::error::__ErrorResponsesProbe::<#ty>(::core::marker::PhantomData);
+                use #krate::error::__NoErrorResponses as _;
+                __e.extend((&__probe).error_responses());
+            }
+        }
+    });
+    let rate_limited = rm
+        .decorators
+        .guard_fns
+        .iter()
+        .chain(rm.decorators.pre_auth_guard_fns.iter())
+        .any(|expr| {
+            crate::codegen::decorators::spec_type_of(expr)
+                .ok()
+                .and_then(|(path, _)| path.segments.last().map(|s| s.ident == "RateLimit"))
+                .unwrap_or(false)
+        });
+    let rate_limit = rate_limited.then(|| {
+        quote! {
+            __e.push(#krate::meta::ErrorResponseInfo::new(429, "Too many requests"));
+        }
+    });
+    quote! {
+        {
+            let mut __e: Vec<#krate::meta::ErrorResponseInfo> = Vec::new();
+            #probe
+            #rate_limit
+            __e
+        }
+    }
+}
+
+/// The `E` of a `Result<T, E>` return type, if spelled out.
+fn result_error_type(rm: &crate::types::RouteMethod) -> Option<&syn::Type> {
+    let syn::ReturnType::Type(_, ty) = &rm.fn_item.sig.output else {
+        return None;
+    };
+    let syn::Type::Path(type_path) = ty.as_ref() else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Result" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.iter().nth(1)? {
+        syn::GenericArgument::Type(error) => Some(error),
+        _ => None,
+    }
+}
+
 /// A handler parameter recognized as the request body extractor.
 enum BodyExtractor {
-    /// `Json<T>` — `application/json` with a schemars-generated schema.
+    /// `Json<T>` or `Body<T>` — `application/json` (or the `#[consumes]`
+    /// types) with a schemars-generated schema.
     Json { name: String, ty: syn::Type },
     /// `TypedMultipart<T>` — `multipart/form-data` with a `MultipartSchema`-probed schema.
     TypedMultipart { name: String, ty: syn::Type },
@@ -856,14 +1234,14 @@ fn extract_body_type_info(ty: &syn::Type) -> Option<BodyExtractor> {
         if let Some(segment) = type_path.path.segments.last() {
             let ident = segment.ident.to_string();
             match ident.as_str() {
-                "Json" | "TypedMultipart" => {
+                "Json" | "Body" | "TypedMultipart" => {
                     if let syn::PathArguments::AngleBracketed(ref args) = segment.arguments {
                         if let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first() {
                             if let syn::Type::Path(inner_path) = inner_ty {
                                 if let Some(inner_seg) = inner_path.path.segments.last() {
                                     let name = inner_seg.ident.to_string();
                                     let ty = inner_ty.clone();
-                                    return Some(if ident == "Json" {
+                                    return Some(if ident == "Json" || ident == "Body" {
                                         BodyExtractor::Json { name, ty }
                                     } else {
                                         BodyExtractor::TypedMultipart { name, ty }
@@ -1014,6 +1392,7 @@ fn generate_transverse(def: &RoutesImplDef, name: &syn::Ident) -> (TokenStream,
                 filter: cm.filter.clone(),
                 retry: cm.retry,
                 dlq: cm.dlq.clone(),
+                ordered_by: cm.ordered_by.clone(),
             })
             .collect();
         // Custom `deserializer` assoc fns live on the concrete core.
@@ -1136,6 +1515,130 @@ fn generate_transverse(def: &RoutesImplDef, name: &syn::Ident) -> (TokenStream,
     (quote! { #(#module_items)* }, quote! { #(#controller_fns)* })
 }
 
+/// Emit a `ConsumerInfo` literal per `#[consumer]` method, for AsyncAPI
+/// generation.
+///
+/// The topic mirrors what the bus subscribes to: the explicit `topic = "..."`
+/// wins; otherwise an `Event::topic()` override (probed, since consumers do not
+/// require `Event`); otherwise the sanitized type name the bus falls back to.
+fn generate_consumer_metadata(def: &RoutesImplDef, name: &syn::Ident) -> Vec<TokenStream> {
+    if def.consumer_methods.is_empty() {
+        return Vec::new();
+    }
+    let krate = r2e_core_path();
+    let owner = name.to_string();
+
+    def.consumer_methods
+        .iter()
+        .map(|cm| {
+            let event_type = &cm.event_type;
+            let op_id = format!("{}_{}", name, cm.fn_item.sig.ident);
+            let event_type_name = type_to_schema_name(event_type);
+            let event_schema = response_schema_token(event_type);
+
+            let (doc_summary, doc_description) =
+                crate::extract::route::extract_doc_comments(&cm.fn_item.attrs);
+            let summary_token = option_string_token(doc_summary.as_deref());
+            let description_token = option_string_token(doc_description.as_deref());
+
+            let topic_token = match &cm.topic {
+                Some(topic) => quote! { #topic.to_string() },
+                None => event_topic_token(event_type),
+            };
+
+            let (reply_type_token, reply_schema_token) = match &cm.kind {
+                crate::types::ConsumerKind::Responder { resp_type, .. } => {
+                    let reply_name = type_to_schema_name(resp_type);
+                    let reply_schema = response_schema_token(resp_type);
+                    (quote! { Some(#reply_name.to_string()) }, reply_schema)
+                }
+                crate::types::ConsumerKind::Subscriber => (quote! { None }, quote! { None }),
+            };
+            let max_retries = match cm.retry {
+                Some(n) => quote! { Some(#n) },
+                None => quote! { None },
+            };
+            let dlq = option_string_token(cm.dlq.as_deref());
+
+            quote! {
+                #krate::meta::ConsumerInfo {
+                    owner: #owner.to_string(),
+                    operation_id: #op_id.to_string(),
+                    summary: #summary_token,
+                    description: #description_token,
+                    topic: #topic_token,
+                    event_type: #event_type_name.to_string(),
+                    event_schema: #event_schema,
+                    reply_type: #reply_type_token,
+                    reply_schema: #reply_schema_token,
+                    max_retries: #max_retries,
+                    dead_letter_topic: #dlq,
+                }
+            }
+        })
+        .collect()
+}
+
+/// Emit an `EmitterInfo` literal per event type of every `#[emits(...)]`
+/// method, resolving the topic exactly like [`generate_consumer_metadata`].
+fn generate_emitter_metadata(def: &RoutesImplDef, name: &syn::Ident) -> Vec<TokenStream> {
+    let krate = r2e_core_path();
+    let owner = name.to_string();
+
+    def.emitter_methods
+        .iter()
+        .flat_map(|em| {
+            let op_id = format!("{}_{}", name, em.fn_ident);
+            let summary_token = option_string_token(em.summary.as_deref());
+            let krate = &krate;
+            let owner = &owner;
+            em.event_types.iter().map(move |event_type| {
+                let event_type_name = type_to_schema_name(event_type);
+                let event_schema = response_schema_token(event_type);
+                let topic_token = event_topic_token(event_type);
+                quote! {
+                    #krate::meta::EmitterInfo {
+                        owner: #owner.to_string(),
+                        operation_id: #op_id.to_string(),
+                        summary: #summary_token,
+                        topic: #topic_token,
+                        event_type: #event_type_name.to_string(),
+                        event_schema: #event_schema,
+                    }
+                }
+            })
+        })
+        .collect()
+}
+
+/// The topic the bus uses for `event_type` when none is configured: an
+/// `Event::topic()` override (probed, since the type need not implement
+/// `Event`), else the sanitized type name — see
+/// `r2e_events::backend::event_topic_name`.
+fn event_topic_token(event_type: &syn::Type) -> TokenStream {
+    let events_krate = crate::crate_path::r2e_events_path();
+    let event_topic = autoref_probe(
+        event_type,
+        quote! { #events_krate::Event },
+        quote! { &'static str },
+        quote! { <T as #events_krate::Event>::topic() },
+    );
+    quote! {
+        #events_krate::backend::event_topic_name(
+            ::std::any::type_name::<#event_type>(),
+            #event_topic,
+        )
+    }
+}
+
+/// `Some("...".to_string())` / `None` token for an optional string literal.
+fn option_string_token(value: Option<&str>) -> TokenStream {
+    match value {
+        Some(v) => quote! { Some(#v.to_string()) },
+        None => quote! { None },
+    }
+}
+
 fn generate_sse_route_metadata(
     def: &RoutesImplDef,
     name: &syn::Ident,
@@ -1154,7 +1657,10 @@ fn generate_sse_route_metadata(
                 !sm.decorators.guard_fns.is_empty(),
                 sm.identity_param.is_some(),
                 sm.decorators.anonymous,
+                sm.decorators.priority.or(def.controller_priority),
+                sm.decorators.versions,
                 "SSE stream",
+                Some("text/event-stream"),
             )
         })
         .collect()
@@ -1178,7 +1684,10 @@ fn generate_ws_route_metadata(
                 !wm.decorators.guard_fns.is_empty(),
                 wm.identity_param.is_some(),
                 wm.decorators.anonymous,
+                wm.decorators.priority.or(def.controller_priority),
+                wm.decorators.versions,
                 "WebSocket endpoint",
+                None,
             )
         })
         .collect()
@@ -1187,7 +1696,7 @@ fn generate_ws_route_metadata(
 /// Emit a `RouteInfo` literal for SSE / WS routes.
 ///
 /// Both emit a `GET` with empty body/params/response and a 200 status; they
-/// differ only in summary text. Keeping this in one place makes adding a new
+/// differ only in summary text and the SSE `text/event-stream` media type. Keeping this in one place makes adding a new
 /// streaming route kind (or a new `RouteInfo` field) a single-edit affair.
 #[allow(clippy::too_many_arguments)]
 fn emit_streaming_route_info(
@@ -1200,9 +1709,18 @@ fn emit_streaming_route_info(
     has_guards: bool,
     has_identity_param: bool,
     anonymous: bool,
+    priority: Option<crate::types::RoutePriorityAttr>,
+    versions: crate::types::RouteVersionsAttr,
     summary: &str,
+    response_content_type: Option<&str>,
 ) -> TokenStream {
     let krate = r2e_core_path();
+    let priority = priority_extension_tokens(priority);
+    let versions = route_versions_tokens(versions);
+    let response_content_type = match response_content_type {
+        Some(content_type) => quote! { Some(#content_type.to_string()) },
+        None => quote! { None },
+    };
     let tag = controller_name.to_string();
     let op_id = format!("{}_{}", controller_name, fn_ident);
     let roles_tokens: Vec<_> = roles
@@ -1220,7 +1738,7 @@ fn emit_streaming_route_info(
     );
 
     quote! {
-        #krate::meta::RouteInfo {
+        #krate::versioning::__expand(#meta_mod::VERSIONS, #versions, #krate::meta::RouteInfo {
             path: match #meta_mod::PATH_PREFIX {
                 Some(__prefix) => format!("{}{}", __prefix, #path),
                 None => #path.to_string(),
@@ -1233,16 +1751,26 @@ fn emit_streaming_route_info(
             request_body_schema: None,
             request_body_content_type: None,
             request_body_required: true,
+            consumes: Vec::new(),
             response_type: None,
             response_schema: None,
+            response_content_type: #response_content_type,
+            produces: Vec::new(),
             response_status: 200,
             response_unmapped: None,
+            error_responses: Vec::new(),
             params: vec![],
             roles: vec![#(#roles_tokens),*],
             tag: Some(#tag.to_string()),
             deprecated: false,
             has_auth: #has_auth,
-        }
+            extensions: {
+                #[allow(unused_mut)]
+                let mut __x = ::std::collections::BTreeMap::new();
+                #priority
+                __x
+            },
+        })
     }
 }
 
@@ -1282,12 +1810,36 @@ fn generate_route_registrations(def: &RoutesImplDef) -> Vec<TokenStream> {
                     .fallback(#closure)
                 }
             } else {
+                let route_key = format!("{}.{}", def.controller_name, rm.fn_item.sig.ident);
+                let limits = route_limits_tokens(rm.decorators.limits.or(def.controller_limits));
+                let handler = with_idempotency(
+                    rm.decorators.idempotent_ttl_ms.is_some(),
+                    with_etag(
+                        rm.decorators.etag || def.controller_etag,
+                        with_negotiation(
+                            &route_key,
+                            &rm.decorators.media_types.or(&def.controller_media_types),
+                            with_template(
+                                &route_key,
+                                rm.decorators.template.as_ref(),
+                                quote! { #krate::http::routing::#method_fn(#closure) },
+                            ),
+                        ),
+                    ),
+                );
+                let versions = route_versions_tokens(rm.decorators.versions);
                 quote! {
                     .route(
                         #path,
-                        #krate::http::routing::#method_fn(#closure)
-                            #(#middleware_layers)*
-                            #(#direct_layers)*
+                        #versions,
+                        #krate::route_limits::__apply(
+                            __ctx,
+                            #route_key,
+                            #limits,
+                            #handler
+                                #(#middleware_layers)*
+                                #(#direct_layers)*,
+                        )
                     )
                 }
             }
@@ -1315,9 +1867,11 @@ fn generate_sse_route_registrations(def: &RoutesImplDef) -> Vec<TokenStream> {
                 .iter()
                 .map(|expr| quote! { .layer(#expr) })
                 .collect();
+            let versions = route_versions_tokens(sm.decorators.versions);
             quote! {
                 .route(
                     #path,
+                    #versions,
                     #krate::http::routing::get(#closure)
                         #(#middleware_layers)*
                         #(#direct_layers)*
@@ -1347,9 +1901,11 @@ fn generate_ws_route_registrations(def: &RoutesImplDef) -> Vec<TokenStream> {
                 .iter()
                 .map(|expr| quote! { .layer(#expr) })
                 .collect();
+            let versions = route_versions_tokens(wm.decorators.versions);
             quote! {
                 .route(
                     #path,
+                    #versions,
                     #krate::http::routing::get(#closure)
                         #(#middleware_layers)*
                         #(#direct_layers)*
@@ -1361,8 +1917,8 @@ fn generate_ws_route_registrations(def: &RoutesImplDef) -> Vec<TokenStream> {
 
 /// Generate `__inner = __inner.route(...);` statements wrapping
 /// pre-auth-guarded routes with the captured-core closure + pre-auth middleware.
-/// Paths are bare here because the
-/// surrounding `match PATH_PREFIX` re-nests the router afterwards.
+/// Paths are bare here because
+/// `VersionedRoutes` prefixes them with the controller path (and `/v{n}`).
 ///
 /// Pre-auth guards are prebuilt (once, from the bean context) into the
 /// method's `__R2ePreDeco_*` set; the middleware closure captures one `Arc`
@@ -1387,6 +1943,9 @@ fn generate_pre_auth_registrations(
             &rm.decorators,
             quote! { #method_fn },
             super::handlers::generate_route_closure(def, rm),
+            Some(rm.decorators.limits.or(def.controller_limits)),
+            rm.decorators.media_types.or(&def.controller_media_types),
+            rm.decorators.etag || def.controller_etag,
         ));
     }
     // SSE/WS endpoints run their pre-auth guards through the same middleware.
@@ -1402,6 +1961,9 @@ fn generate_pre_auth_registrations(
             &sm.decorators,
             quote! { get },
             super::handlers::generate_sse_closure(def, sm),
+            None,
+            Default::default(),
+            false,
         ));
     }
     for wm in &def.ws_methods {
@@ -1416,11 +1978,15 @@ fn generate_pre_auth_registrations(
             &wm.decorators,
             quote! { get },
             super::handlers::generate_ws_closure(def, wm),
+            None,
+            Default::default(),
+            false,
         ));
     }
     registrations
 }
 
+#[allow(clippy::too_many_arguments)]
 fn pre_auth_registration(
     def: &RoutesImplDef,
     name: &syn::Ident,
@@ -1429,8 +1995,36 @@ fn pre_auth_registration(
     decorators: &crate::types::MethodDecorators,
     method_fn: TokenStream,
     closure: TokenStream,
+    limits: Option<crate::types::RouteLimitsAttr>,
+    media_types: crate::types::RouteMediaTypesAttr,
+    etag: bool,
 ) -> TokenStream {
     let krate = r2e_core_path();
+    let versions = route_versions_tokens(decorators.versions);
+    let handler = with_idempotency(
+        decorators.idempotent_ttl_ms.is_some(),
+        with_etag(
+            etag,
+            with_negotiation(
+                &format!("{}.{}", name, fn_ident),
+                &media_types,
+                with_template(
+                    &format!("{}.{}", name, fn_ident),
+                    decorators.template.as_ref(),
+                    quote! { #krate::http::routing::#method_fn(#closure) },
+                ),
+            ),
+        ),
+    );
+    // HTTP routes get the route-limit layer outermost; SSE/WS pass `None`.
+    let with_limits = |router: TokenStream| match limits {
+        Some(limits) => {
+            let route_key = format!("{}.{}", name, fn_ident);
+            let limits = route_limits_tokens(limits);
+            quote! { #krate::route_limits::__apply(__ctx, #route_key, #limits, #router) }
+        }
+        None => router,
+    };
 
     // Mirror the post-auth degrade: when a pre-guard spec type is not
     // inferable, `generate_predeco_items` emitted the compile_error and no
@@ -1447,13 +2041,13 @@ fn pre_auth_registration(
             .iter()
             .map(|expr| quote! { .layer(#expr) })
             .collect();
+        let router = with_limits(quote! {
+            #handler
+                #(#middleware_layers)*
+                #(#direct_layers)*
+        });
         return quote! {
-            __inner = __inner.route(
-                #path,
-                #krate::http::routing::#method_fn(#closure)
-                    #(#middleware_layers)*
-                    #(#direct_layers)*
-            );
+            __inner = __inner.route(#path, #versions, #router);
         };
     }
 
@@ -1487,6 +2081,13 @@ fn pre_auth_registration(
         .map(|expr| quote! { .layer(#expr) })
         .collect();
 
+    let router = with_limits(quote! {
+        #handler
+            #(#middleware_layers)*
+            #(#direct_layers)*
+            .layer(#krate::http::middleware::from_fn(__pre_auth_mw))
+    });
+
     quote! {
         {
             let __pre_deco_capture = ::std::sync::Arc::new(#predeco_ctor(__ctx));
@@ -1505,13 +2106,7 @@ fn pre_auth_registration(
                     __next.run(__req).await
                 }
             };
-            __inner = __inner.route(
-                #path,
-                #krate::http::routing::#method_fn(#closure)
-                    #(#middleware_layers)*
-                    #(#direct_layers)*
-                    .layer(#krate::http::middleware::from_fn(__pre_auth_mw))
-            );
+            __inner = __inner.route(#path, #versions, #router);
         }
     }
 }
@@ -196,7 +196,7 @@ fn generate_validation_calls(
         .map(|(i, pt)| {
             let arg_name = format_ident!("__arg_{}", i);
             let validate_target = if is_wrapper_type(&pt.ty) {
-                // For Json<T>, Query<T>, Path<T>, Form<T> → validate the inner .0
+                // For Json<T>, Body<T>, Query<T>, Path<T>, Form<T> → validate the inner .0
                 quote! { &#arg_name.0 }
             } else {
                 // For Params and other custom types → validate directly
@@ -215,12 +215,14 @@ fn generate_validation_calls(
         .collect()
 }
 
-/// Check if a type is a known Axum wrapper (Json, Query, Path, Form).
+/// Check if a type is a known extractor wrapper (Json, Body, Query, Path, Form).
 fn is_wrapper_type(ty: &syn::Type) -> bool {
     if let syn::Type::Path(type_path) = ty {
         if let Some(segment) = type_path.path.segments.last() {
             let ident = segment.ident.to_string();
-            return matches!(ident.as_str(), "Json" | "Query" | "Path" | "Form");
+            return matches!(ident.as_str(), "Json" | "Query" | "Path" | "Form")
+                || (ident == "Body"
+                    && matches!(segment.arguments, syn::PathArguments::AngleBracketed(_)));
         }
     }
     false
@@ -633,6 +635,23 @@ fn generate_single_handler(def: &RoutesImplDef, rm: &RouteMethod) -> TokenStream
         quote! { __ctrl.#fn_name(#(#call_args),*) }
     };
 
+    // #[template]: the returned context (or the `Ok` of a Result) is
+    // rendered when it becomes a response.
+    let call_expr = match &rm.decorators.template {
+        Some(_) => {
+            let returns_result = matches!(
+                return_type,
+                syn::ReturnType::Type(_, ty) if crate::type_utils::is_result_like(ty)
+            );
+            if returns_result {
+                quote! { (#call_expr).map(#krate::templating::Render) }
+            } else {
+                quote! { #krate::templating::Render(#call_expr) }
+            }
+        }
+        None => call_expr,
+    };
+
     let has_managed = !rm.managed_params.is_empty();
 
     let invocation_name = &ctx.invocation_name;
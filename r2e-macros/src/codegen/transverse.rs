@@ -189,6 +189,8 @@ pub(crate) struct ConsumerMethodDef {
     pub filter: Option<String>,
     pub retry: Option<u32>,
     pub dlq: Option<String>,
+    /// `"partition_key"` or `"header:<name>"`.
+    pub ordered_by: Option<String>,
 }
 
 /// The per-method subscribe/respond blocks (one per consumer method),
@@ -314,6 +316,22 @@ pub(crate) fn event_subscribe_blocks(
                 quote! {}
             };
 
+            let configure_ordering = cm.ordered_by.as_deref().map(|spec| {
+                let ordered_by = match spec.strip_prefix("header:") {
+                    Some(name) => quote! { #events_krate::OrderedBy::Header(#name.to_string()) },
+                    None => quote! { #events_krate::OrderedBy::PartitionKey },
+                };
+                quote! {
+                    if let Ok(ref __h) = __handle {
+                        #events_krate::EventBus::configure_ordering::<#event_type>(
+                            &__bus_ref,
+                            __h.id(),
+                            #ordered_by,
+                        ).await;
+                    }
+                }
+            });
+
             quote! {
                 {
                     let __bus = #instance.#bus_field.clone();
@@ -323,6 +341,7 @@ pub(crate) fn event_subscribe_blocks(
                     #register_topic
                     let __handle = #subscribe_call;
                     #configure_handler
+                    #configure_ordering
                     if let Err(__e) = __handle {
                         eprintln!("[r2e] Failed to subscribe consumer: {__e}");
                     }
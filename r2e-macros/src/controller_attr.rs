@@ -11,7 +11,7 @@ pub fn expand(args: TokenStream, input: TokenStream) -> TokenStream {
     let args = TokenStream2::from(args);
 
     let parsed = controller_parsing::parse_controller_args(args, item.ident.span())
-        .and_then(|prefix| controller_parsing::parse(prefix, &item));
+        .and_then(|args| controller_parsing::parse(args, &item));
 
     match parsed {
         Ok(def) => {
@@ -66,7 +66,7 @@ fn add_deco_slot_field(mut item: syn::ItemStruct) -> syn::ItemStruct {
     item
 }
 
-/// Generate `mod __r2e_meta_<Name>` with PATH_PREFIX, IdentityType,
+/// Generate `mod __r2e_meta_<Name>` with PATH_PREFIX, VERSIONS, IdentityType,
 /// guard_identity, bind_request, and config validation.
 fn generate_meta_module(def: &ControllerStructDef) -> TokenStream {
     let krate = r2e_core_path();
@@ -77,6 +77,10 @@ fn generate_meta_module(def: &ControllerStructDef) -> TokenStream {
         Some(p) => quote! { Some(#p) },
         None => quote! { None },
     };
+    let versions = match def.versions {
+        Some((low, high)) => quote! { Some((#low, #high)) },
+        None => quote! { None },
+    };
 
     let facade_name = format_ident!("__R2eRequest_{}", name);
     let data_name = format_ident!("__R2eRequestData_{}", name);
@@ -206,6 +210,7 @@ fn generate_meta_module(def: &ControllerStructDef) -> TokenStream {
         mod #mod_name {
             use super::*;
             pub const PATH_PREFIX: Option<&str> = #path_prefix;
+            pub const VERSIONS: Option<(u32, u32)> = #versions;
             pub const HAS_STRUCT_IDENTITY: bool = #has_struct_identity;
             pub const STRUCT_IDENTITY_IS_REQUIRED: bool = #struct_identity_is_required;
             #identity_type
@@ -7,6 +7,8 @@ use crate::types::*;
 pub struct ControllerStructDef {
     pub name: syn::Ident,
     pub prefix: Option<String>,
+    /// `versions = "1..=2"`: the inclusive range of API versions served.
+    pub versions: Option<(u32, u32)>,
     pub injected_fields: Vec<InjectedField>,
     pub identity_fields: Vec<IdentityField>,
     pub request_fields: Vec<RequestField>,
@@ -67,18 +69,32 @@ fn inject_qualifier_is(attr: &syn::Attribute, want: &str) -> bool {
     }
 }
 
-/// Parse the `#[controller(path = "...")]` attribute arguments.
+/// Arguments of `#[controller(path = "...", versions = "...")]`.
+#[derive(Default)]
+pub struct ControllerArgs {
+    pub prefix: Option<String>,
+    pub versions: Option<(u32, u32)>,
+}
+
+/// Parse the `#[controller(path = "...", versions = "...")]` attribute
+/// arguments.
 pub fn parse_controller_args(
     args: proc_macro2::TokenStream,
     span: proc_macro2::Span,
-) -> syn::Result<Option<String>> {
-    let mut prefix: Option<String> = None;
+) -> syn::Result<ControllerArgs> {
+    let mut parsed = ControllerArgs::default();
 
     let parser = syn::meta::parser(|meta| {
         if meta.path.is_ident("path") {
             let value = meta.value()?;
             let lit: syn::LitStr = value.parse()?;
-            prefix = Some(lit.value());
+            parsed.prefix = Some(lit.value());
+            Ok(())
+        } else if meta.path.is_ident("versions") {
+            let lit: syn::LitStr = meta.value()?.parse()?;
+            parsed.versions = Some(
+                parse_version_range(&lit.value()).map_err(|e| syn::Error::new(lit.span(), e))?,
+            );
             Ok(())
         } else if meta.path.is_ident("state") {
             Err(meta.error(
@@ -87,20 +103,47 @@ pub fn parse_controller_args(
                  registered on the AppBuilder before build_state()",
             ))
         } else {
-            Err(meta.error("unknown attribute in #[controller(...)]: expected `path`"))
+            Err(meta
+                .error("unknown attribute in #[controller(...)]: expected `path` or `versions`"))
         }
     });
     parser.parse2(args)?;
     let _ = span;
 
-    Ok(prefix)
+    Ok(parsed)
+}
+
+/// Parse `"1..=3"`, `"1..4"` or `"2"` into an inclusive version range.
+fn parse_version_range(value: &str) -> Result<(u32, u32), String> {
+    let number = |s: &str| {
+        s.trim()
+            .parse::<u32>()
+            .map_err(|_| format!("invalid versions '{value}': expected e.g. \"1..=3\""))
+    };
+    let (low, high) = if let Some((low, high)) = value.split_once("..=") {
+        (number(low)?, number(high)?)
+    } else if let Some((low, high)) = value.split_once("..") {
+        let high = number(high)?;
+        if high == 0 {
+            return Err(format!("versions '{value}' is empty"));
+        }
+        (number(low)?, high - 1)
+    } else {
+        let version = number(value)?;
+        (version, version)
+    };
+    if low > high {
+        return Err(format!("versions '{value}' is empty"));
+    }
+    Ok((low, high))
 }
 
 /// Parse a `#[controller]` struct into a [`ControllerStructDef`].
 ///
-/// `prefix` comes from the attribute arguments; field scopes are read from
-/// the struct's named fields.
-pub fn parse(prefix: Option<String>, item: &syn::ItemStruct) -> syn::Result<ControllerStructDef> {
+/// `args` come from the attribute arguments; field scopes are read from the
+/// struct's named fields.
+pub fn parse(args: ControllerArgs, item: &syn::ItemStruct) -> syn::Result<ControllerStructDef> {
+    let ControllerArgs { prefix, versions } = args;
     let name = item.ident.clone();
 
     let fields: Vec<&syn::Field> = match &item.fields {
@@ -209,6 +252,7 @@ pub fn parse(prefix: Option<String>, item: &syn::ItemStruct) -> syn::Result<Cont
     Ok(ControllerStructDef {
         name,
         prefix,
+        versions,
         injected_fields,
         identity_fields,
         request_fields,
@@ -114,11 +114,19 @@ pub fn r2e_scheduler_path() -> TokenStream {
 /// Resolution order:
 /// 1. Direct `schemars` dependency → `::schemars`
 /// 2. Direct `r2e-openapi` dependency → `::r2e_openapi::schemars`
+/// 3. Direct `r2e-asyncapi` dependency → `::r2e_asyncapi::schemars`
 ///
 /// Returns `None` if no path is found (i.e. user hasn't opted into OpenAPI).
 pub fn r2e_schemars_path() -> Option<TokenStream> {
     static CACHE: OnceLock<Option<String>> = OnceLock::new();
-    resolve_cached_optional(&CACHE, &[("schemars", ""), ("r2e-openapi", "schemars")])
+    resolve_cached_optional(
+        &CACHE,
+        &[
+            ("schemars", ""),
+            ("r2e-openapi", "schemars"),
+            ("r2e-asyncapi", "schemars"),
+        ],
+    )
 }
 
 /// Returns the token stream for accessing `r2e_grpc` types.
@@ -1,12 +1,12 @@
 //! Consumer-related attribute extraction.
 
 use crate::type_utils::{is_unit_type, result_ok_type};
-use crate::types::ConsumerKind;
+use crate::types::{ConsumerKind, EmitterMethod};
 
 /// Classify a `#[consumer]` method by its return type.
 ///
-/// - `-> ()` (or an elided return) and `-> Result<(), E>` are fan-out
-///   [`ConsumerKind::Subscriber`]s.
+/// - `-> ()` (or an elided return), `-> Result<(), E>` and
+///   `-> HandlerResult` are fan-out [`ConsumerKind::Subscriber`]s.
 /// - any other return type is a request-reply [`ConsumerKind::Responder`]:
 ///   a bare `-> Resp` (infallible) or `-> Result<Resp, E>` (fallible, `Err`
 ///   mapped to the responder error string).
@@ -15,7 +15,7 @@ pub fn classify_consumer_return(output: &syn::ReturnType) -> ConsumerKind {
         syn::ReturnType::Default => return ConsumerKind::Subscriber,
         syn::ReturnType::Type(_, t) => &**t,
     };
-    if is_unit_type(ty) {
+    if is_unit_type(ty) || is_handler_result(ty) {
         return ConsumerKind::Subscriber;
     }
     if let Some(ok) = result_ok_type(ty) {
@@ -33,6 +33,13 @@ pub fn classify_consumer_return(output: &syn::ReturnType) -> ConsumerKind {
     }
 }
 
+/// `HandlerResult` (any path ending in it): an explicit ack/nack, not a
+/// reply type.
+fn is_handler_result(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(p) if p.qself.is_none()
+        && p.path.segments.last().is_some_and(|s| s.ident == "HandlerResult" && s.arguments.is_none()))
+}
+
 /// Parsed configuration from `#[consumer(...)]`.
 pub struct ConsumerConfig {
     pub bus_field: String,
@@ -41,6 +48,8 @@ pub struct ConsumerConfig {
     pub filter: Option<String>,
     pub retry: Option<u32>,
     pub dlq: Option<String>,
+    /// `"partition_key"` or `"header:<name>"` (validated at parse time).
+    pub ordered_by: Option<String>,
 }
 
 pub fn strip_consumer_attrs(attrs: Vec<syn::Attribute>) -> Vec<syn::Attribute> {
@@ -59,6 +68,7 @@ pub fn extract_consumer(attrs: &[syn::Attribute]) -> syn::Result<Option<Consumer
             let mut filter = None;
             let mut retry = None;
             let mut dlq = None;
+            let mut ordered_by = None;
             attr.parse_nested_meta(|meta| {
                 if meta.path.is_ident("bus") {
                     let value = meta.value()?;
@@ -90,9 +100,24 @@ pub fn extract_consumer(attrs: &[syn::Attribute]) -> syn::Result<Option<Consumer
                     let lit: syn::LitStr = value.parse()?;
                     dlq = Some(lit.value());
                     Ok(())
+                } else if meta.path.is_ident("ordered_by") {
+                    let value = meta.value()?;
+                    let lit: syn::LitStr = value.parse()?;
+                    let spec = lit.value();
+                    let valid = spec == "partition_key"
+                        || spec.strip_prefix("header:").is_some_and(|name| !name.is_empty());
+                    if !valid {
+                        return Err(syn::Error::new_spanned(
+                            lit,
+                            "`ordered_by` must be `\"partition_key\"` or `\"header:<name>\"`:\n\
+                             \n  #[consumer(bus = \"event_bus\", ordered_by = \"partition_key\")]",
+                        ));
+                    }
+                    ordered_by = Some(spec);
+                    Ok(())
                 } else {
                     Err(meta.error(
-                        "unknown key in #[consumer(...)]: expected `bus`, `topic`, `deserializer`, `filter`, `retry`, or `dlq`\n\
+                        "unknown key in #[consumer(...)]: expected `bus`, `topic`, `deserializer`, `filter`, `retry`, `dlq`, or `ordered_by`\n\
                          \n  usage: #[consumer(bus = \"event_bus\", topic = \"my-topic\")]"
                     ))
                 }
@@ -111,6 +136,7 @@ pub fn extract_consumer(attrs: &[syn::Attribute]) -> syn::Result<Option<Consumer
                 filter,
                 retry,
                 dlq,
+                ordered_by,
             }));
         }
     }
@@ -136,3 +162,36 @@ pub fn extract_event_type_from_arc(ty: &syn::Type) -> syn::Result<syn::Type> {
          Events are shared across subscribers via Arc, so the parameter type must be Arc<YourEventType>.",
     ))
 }
+
+/// Take the `#[emits(EventA, EventB)]` attributes off `method`.
+///
+/// Any controller method (route, consumer, scheduled task or plain helper)
+/// may declare the events it emits; the attribute is documentation only.
+pub fn take_emits(method: &mut syn::ImplItemFn) -> syn::Result<Option<EmitterMethod>> {
+    let (emits, rest): (Vec<_>, Vec<_>) = std::mem::take(&mut method.attrs)
+        .into_iter()
+        .partition(|a| a.path().is_ident("emits"));
+    method.attrs = rest;
+    if emits.is_empty() {
+        return Ok(None);
+    }
+    let mut event_types = Vec::new();
+    for attr in &emits {
+        let types = attr.parse_args_with(
+            syn::punctuated::Punctuated::<syn::Type, syn::Token![,]>::parse_terminated,
+        )?;
+        if types.is_empty() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "#[emits] expects at least one event type: #[emits(OrderPlaced)]",
+            ));
+        }
+        event_types.extend(types);
+    }
+    let (summary, _) = crate::extract::route::extract_doc_comments(&method.attrs);
+    Ok(Some(EmitterMethod {
+        fn_ident: method.sig.ident.clone(),
+        summary,
+        event_types,
+    }))
+}
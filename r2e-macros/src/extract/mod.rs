@@ -12,6 +12,7 @@ pub mod managed;
 pub mod plugins;
 pub mod route;
 pub mod scheduled;
+pub mod size;
 
 // Re-export all public items for backward compatibility
 pub use async_exec::*;
@@ -6,10 +6,12 @@
 //! requires implementing `RoutePlugin` and appending it here.
 
 use crate::extract::route::{
-    all_roles_guard_expr, extract_all_roles, extract_guard_fns, extract_intercept_fns,
-    extract_layer_exprs, extract_middleware_fns, extract_pre_guard_fns, extract_returns,
-    extract_roles, extract_status, is_fallback_attr, is_route_attr, is_sse_attr, is_ws_attr,
-    roles_guard_expr,
+    all_roles_guard_expr, extract_all_roles, extract_etag, extract_guard_fns, extract_idempotent,
+    extract_intercept_fns, extract_layer_exprs, extract_limits, extract_media_types,
+    extract_middleware_fns, extract_pre_guard_fns, extract_priority, extract_returns,
+    extract_roles, extract_status, extract_template, extract_versions, idempotency_guard_expr,
+    is_fallback_attr, is_route_attr, is_sse_attr, is_ws_attr, roles_guard_expr,
+    template_guard_expr,
 };
 use crate::types::MethodDecorators;
 
@@ -193,11 +195,125 @@ impl RoutePlugin for ReturnsPlugin {
     }
 }
 
+struct LimitsPlugin;
+impl RoutePlugin for LimitsPlugin {
+    fn attr_names(&self) -> &'static [&'static str] {
+        &["timeout", "body_limit", "max_concurrent"]
+    }
+    fn parse(
+        &self,
+        attrs: &[syn::Attribute],
+        decorators: &mut MethodDecorators,
+    ) -> syn::Result<()> {
+        decorators.limits = extract_limits(attrs)?;
+        Ok(())
+    }
+}
+
+struct PriorityPlugin;
+impl RoutePlugin for PriorityPlugin {
+    fn attr_names(&self) -> &'static [&'static str] {
+        &["priority"]
+    }
+    fn parse(
+        &self,
+        attrs: &[syn::Attribute],
+        decorators: &mut MethodDecorators,
+    ) -> syn::Result<()> {
+        decorators.priority = extract_priority(attrs)?;
+        Ok(())
+    }
+}
+
+struct MediaTypesPlugin;
+impl RoutePlugin for MediaTypesPlugin {
+    fn attr_names(&self) -> &'static [&'static str] {
+        &["produces", "consumes"]
+    }
+    fn parse(
+        &self,
+        attrs: &[syn::Attribute],
+        decorators: &mut MethodDecorators,
+    ) -> syn::Result<()> {
+        decorators.media_types = extract_media_types(attrs)?;
+        Ok(())
+    }
+}
+
+struct EtagPlugin;
+impl RoutePlugin for EtagPlugin {
+    fn attr_names(&self) -> &'static [&'static str] {
+        &["etag"]
+    }
+    fn parse(
+        &self,
+        attrs: &[syn::Attribute],
+        decorators: &mut MethodDecorators,
+    ) -> syn::Result<()> {
+        decorators.etag = extract_etag(attrs)?;
+        Ok(())
+    }
+}
+
+struct TemplatePlugin;
+impl RoutePlugin for TemplatePlugin {
+    fn attr_names(&self) -> &'static [&'static str] {
+        &["template"]
+    }
+    fn parse(
+        &self,
+        attrs: &[syn::Attribute],
+        decorators: &mut MethodDecorators,
+    ) -> syn::Result<()> {
+        decorators.template = extract_template(attrs)?;
+        if decorators.template.is_some() {
+            decorators.guard_fns.push(template_guard_expr());
+        }
+        Ok(())
+    }
+}
+
+struct IdempotentPlugin;
+impl RoutePlugin for IdempotentPlugin {
+    fn attr_names(&self) -> &'static [&'static str] {
+        &["idempotent"]
+    }
+    fn parse(
+        &self,
+        attrs: &[syn::Attribute],
+        decorators: &mut MethodDecorators,
+    ) -> syn::Result<()> {
+        decorators.idempotent_ttl_ms = extract_idempotent(attrs)?;
+        // Appended after every other guard so a rejected request never
+        // claims the key.
+        if let Some(ttl_ms) = decorators.idempotent_ttl_ms {
+            decorators.guard_fns.push(idempotency_guard_expr(ttl_ms));
+        }
+        Ok(())
+    }
+}
+
+struct VersionsPlugin;
+impl RoutePlugin for VersionsPlugin {
+    fn attr_names(&self) -> &'static [&'static str] {
+        &["since", "until", "deprecated_in"]
+    }
+    fn parse(
+        &self,
+        attrs: &[syn::Attribute],
+        decorators: &mut MethodDecorators,
+    ) -> syn::Result<()> {
+        decorators.versions = extract_versions(attrs)?;
+        Ok(())
+    }
+}
+
 // ── Registry ─────────────────────────────────────────────────────────────
 
 /// Ordered registry of all decorator plugins for HTTP/SSE/WS routes.
 /// **Ordering matters**: `RolesPlugin` runs before `GuardPlugin` so the
-/// generated `RolesGuard` is inserted at the front of `guard_fns`.
+/// generated `RolesGuard` is inserted at the front of `guard_fns`, and
+/// `IdempotentPlugin` runs last so its guard is at the back.
 static HTTP_PLUGINS: &[&dyn RoutePlugin] = &[
     &AnonymousPlugin,
     &RolesPlugin,
@@ -209,6 +325,13 @@ static HTTP_PLUGINS: &[&dyn RoutePlugin] = &[
     &LayerPlugin,
     &StatusPlugin,
     &ReturnsPlugin,
+    &LimitsPlugin,
+    &PriorityPlugin,
+    &MediaTypesPlugin,
+    &EtagPlugin,
+    &TemplatePlugin,
+    &IdempotentPlugin,
+    &VersionsPlugin,
 ];
 
 /// Decorator plugins allowed for gRPC routes.
@@ -222,6 +345,18 @@ const GRPC_DISALLOWED_ATTRS: &[&str] = &[
     "pre_guard",
     "middleware",
     "layer",
+    "timeout",
+    "body_limit",
+    "max_concurrent",
+    "priority",
+    "produces",
+    "consumes",
+    "etag",
+    "idempotent",
+    "template",
+    "since",
+    "until",
+    "deprecated_in",
     // Lifecycle / transverse markers are not wired for gRPC services. Left
     // unrejected they either silently never run (sync shapes drop into
     // `other_methods`) or die with a confusing E0407 "not a member of trait"
@@ -2,8 +2,11 @@
 
 use quote::quote;
 
+use super::duration::parse_duration_ms;
+use super::size::parse_size_bytes;
 use crate::crate_path::r2e_security_path;
 use crate::route::{HttpMethod, RoutePath};
+use crate::types::{RouteLimitsAttr, RouteMediaTypesAttr, RoutePriorityAttr, RouteVersionsAttr};
 
 pub fn is_route_attr(attr: &syn::Attribute) -> bool {
     attr.path().is_ident("get")
@@ -286,6 +289,400 @@ pub fn extract_status(attrs: &[syn::Attribute]) -> syn::Result<Option<u16>> {
     Ok(None)
 }
 
+/// Extract `#[timeout("5s")]`, `#[body_limit("50MB")]` and
+/// `#[max_concurrent(10)]`. `timeout` also takes integer seconds and
+/// `body_limit` an integer byte count.
+pub fn extract_limits(attrs: &[syn::Attribute]) -> syn::Result<RouteLimitsAttr> {
+    let mut limits = RouteLimitsAttr::default();
+    for attr in attrs {
+        if attr.path().is_ident("timeout") {
+            reject_duplicate(attr, limits.timeout_ms.is_some())?;
+            let lit: syn::Lit = attr.parse_args()?;
+            limits.timeout_ms = Some(match &lit {
+                syn::Lit::Int(int) => positive(int)? * 1_000,
+                syn::Lit::Str(s) => parse_duration_ms(&s.value()).map_err(|e| {
+                    syn::Error::new(s.span(), format!("invalid timeout '{}': {}", s.value(), e))
+                })?,
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        lit,
+                        "expected a duration like #[timeout(\"5s\")] or integer seconds",
+                    ))
+                }
+            });
+        } else if attr.path().is_ident("body_limit") {
+            reject_duplicate(attr, limits.body_limit.is_some())?;
+            let lit: syn::Lit = attr.parse_args()?;
+            limits.body_limit = Some(match &lit {
+                syn::Lit::Int(int) => positive(int)?,
+                syn::Lit::Str(s) => parse_size_bytes(&s.value()).map_err(|e| {
+                    syn::Error::new(
+                        s.span(),
+                        format!("invalid body limit '{}': {}", s.value(), e),
+                    )
+                })?,
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        lit,
+                        "expected a size like #[body_limit(\"50MB\")] or an integer byte count",
+                    ))
+                }
+            });
+        } else if attr.path().is_ident("max_concurrent") {
+            reject_duplicate(attr, limits.max_concurrent.is_some())?;
+            let int: syn::LitInt = attr.parse_args()?;
+            let max = int.base10_parse::<u32>()?;
+            if max == 0 {
+                return Err(syn::Error::new(
+                    int.span(),
+                    "#[max_concurrent] must be greater than zero",
+                ));
+            }
+            limits.max_concurrent = Some(max);
+        }
+    }
+    Ok(limits)
+}
+
+/// Extract `#[priority(critical)]`, `#[priority(normal)]` or
+/// `#[priority(background)]`.
+pub fn extract_priority(attrs: &[syn::Attribute]) -> syn::Result<Option<RoutePriorityAttr>> {
+    let mut priority = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("priority")) {
+        if priority.is_some() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "duplicate attribute: #[priority] can only be set once",
+            ));
+        }
+        let class: syn::Ident = attr.parse_args()?;
+        priority = Some(match class.to_string().as_str() {
+            "critical" => RoutePriorityAttr::Critical,
+            "normal" => RoutePriorityAttr::Normal,
+            "background" => RoutePriorityAttr::Background,
+            _ => {
+                return Err(syn::Error::new(
+                    class.span(),
+                    "unknown priority — use #[priority(critical)], #[priority(normal)], \
+                     or #[priority(background)]",
+                ))
+            }
+        });
+    }
+    Ok(priority)
+}
+
+/// Extract `#[etag]` (no arguments).
+pub fn extract_etag(attrs: &[syn::Attribute]) -> syn::Result<bool> {
+    let mut etag = false;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("etag")) {
+        if etag {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "duplicate attribute: #[etag] can only be set once",
+            ));
+        }
+        if !matches!(attr.meta, syn::Meta::Path(_)) {
+            return Err(syn::Error::new_spanned(attr, "#[etag] takes no arguments"));
+        }
+        etag = true;
+    }
+    Ok(etag)
+}
+
+/// Default `ttl` of `#[idempotent]`: 24 hours.
+const DEFAULT_IDEMPOTENCY_TTL_MS: u64 = 24 * 3_600_000;
+
+/// Extract `#[idempotent]` or `#[idempotent(ttl = "24h")]`: the TTL of the
+/// stored responses in milliseconds.
+pub fn extract_idempotent(attrs: &[syn::Attribute]) -> syn::Result<Option<u64>> {
+    let mut ttl_ms = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("idempotent")) {
+        if ttl_ms.is_some() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "duplicate attribute: #[idempotent] can only be set once",
+            ));
+        }
+        let mut ttl = DEFAULT_IDEMPOTENCY_TTL_MS;
+        if !matches!(attr.meta, syn::Meta::Path(_)) {
+            attr.parse_nested_meta(|meta| {
+                if !meta.path.is_ident("ttl") {
+                    return Err(meta.error("unknown #[idempotent] argument — expected `ttl`"));
+                }
+                let lit: syn::LitStr = meta.value()?.parse()?;
+                ttl = parse_duration_ms(&lit.value()).map_err(|e| {
+                    syn::Error::new(lit.span(), format!("invalid ttl '{}': {}", lit.value(), e))
+                })?;
+                if ttl == 0 {
+                    return Err(syn::Error::new(lit.span(), "ttl must be greater than zero"));
+                }
+                Ok(())
+            })?;
+        }
+        ttl_ms = Some(ttl);
+    }
+    Ok(ttl_ms)
+}
+
+/// The `#[guard]` expression claiming the `Idempotency-Key` of an
+/// `#[idempotent]` route.
+pub fn idempotency_guard_expr(ttl_ms: u64) -> syn::Expr {
+    let krate = crate::crate_path::r2e_core_path();
+    syn::parse_quote! {
+        #krate::idempotency::Idempotent::ttl(::std::time::Duration::from_millis(#ttl_ms))
+    }
+}
+
+/// Extract `#[template("users/list.html")]`: the name of the template
+/// rendering the handler's return value.
+pub fn extract_template(attrs: &[syn::Attribute]) -> syn::Result<Option<syn::LitStr>> {
+    let mut template: Option<syn::LitStr> = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("template")) {
+        if template.is_some() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "duplicate attribute: #[template] can only be set once",
+            ));
+        }
+        let name: syn::LitStr = attr.parse_args().map_err(|_| {
+            syn::Error::new_spanned(attr, "expected #[template(\"path/to/template.html\")]")
+        })?;
+        if name.value().trim().is_empty() {
+            return Err(syn::Error::new(
+                name.span(),
+                "the template name cannot be empty",
+            ));
+        }
+        template = Some(name);
+    }
+    Ok(template)
+}
+
+/// The `#[guard]` expression recording the globals of a `#[template]`
+/// route.
+pub fn template_guard_expr() -> syn::Expr {
+    let krate = crate::crate_path::r2e_core_path();
+    syn::parse_quote! { #krate::templating::Templated }
+}
+
+/// Extract `#[since(N)]`, `#[until(N)]` and
+/// `#[deprecated_in(N, date = "YYYY-MM-DD", sunset = "YYYY-MM-DD")]`.
+pub fn extract_versions(attrs: &[syn::Attribute]) -> syn::Result<RouteVersionsAttr> {
+    let mut versions = RouteVersionsAttr::default();
+    let mut until_attr = None;
+    for attr in attrs {
+        if attr.path().is_ident("until") {
+            until_attr = Some(attr);
+        }
+        let (slot, name) = if attr.path().is_ident("since") {
+            (&mut versions.since, "since")
+        } else if attr.path().is_ident("until") {
+            (&mut versions.until, "until")
+        } else if attr.path().is_ident("deprecated_in") {
+            (&mut versions.deprecated_in, "deprecated_in")
+        } else {
+            continue;
+        };
+        if slot.is_some() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                format!("duplicate attribute: #[{name}] can only be set once"),
+            ));
+        }
+        let usage = format!("expected #[{name}(N)] with an API version number");
+        let syn::Meta::List(list) = &attr.meta else {
+            return Err(syn::Error::new_spanned(attr, usage));
+        };
+        if name != "deprecated_in" {
+            let lit: syn::LitInt = list.parse_args()?;
+            *slot = Some(lit.base10_parse()?);
+            continue;
+        }
+        let args = list.parse_args_with(
+            syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated,
+        )?;
+        let mut args = args.into_iter();
+        match args.next() {
+            Some(syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(lit),
+                ..
+            })) => *slot = Some(lit.base10_parse()?),
+            _ => return Err(syn::Error::new_spanned(attr, usage)),
+        }
+        for arg in args {
+            let syn::Expr::Assign(assign) = &arg else {
+                return Err(syn::Error::new_spanned(
+                    arg,
+                    "expected `date = \"YYYY-MM-DD\"` or `sunset = \"YYYY-MM-DD\"`",
+                ));
+            };
+            let key = match &*assign.left {
+                syn::Expr::Path(path) if path.path.is_ident("date") => "date",
+                syn::Expr::Path(path) if path.path.is_ident("sunset") => "sunset",
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        other,
+                        "unknown #[deprecated_in] argument — expected `date` or `sunset`",
+                    ))
+                }
+            };
+            let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(lit),
+                ..
+            }) = &*assign.right
+            else {
+                return Err(syn::Error::new_spanned(
+                    &assign.right,
+                    format!("`{key}` expects a \"YYYY-MM-DD\" string"),
+                ));
+            };
+            let secs = parse_date_secs(&lit.value()).map_err(|e| {
+                syn::Error::new(lit.span(), format!("invalid {key} '{}': {e}", lit.value()))
+            })?;
+            match key {
+                "date" => versions.deprecation_date = Some(secs),
+                _ => versions.sunset = Some(secs),
+            }
+        }
+    }
+    if let (Some(since), Some(until)) = (versions.since, versions.until) {
+        if since > until {
+            return Err(syn::Error::new_spanned(
+                until_attr,
+                format!(
+                    "#[since({since})] is after #[until({until})]: the method serves no version"
+                ),
+            ));
+        }
+    }
+    if let (Some(deprecated_in), Some(until)) = (versions.deprecated_in, versions.until) {
+        if deprecated_in > until {
+            return Err(syn::Error::new_spanned(
+                until_attr,
+                format!("#[deprecated_in({deprecated_in})] is after #[until({until})]"),
+            ));
+        }
+    }
+    Ok(versions)
+}
+
+/// Seconds since the Unix epoch at midnight UTC of a `YYYY-MM-DD` date.
+fn parse_date_secs(value: &str) -> Result<u64, String> {
+    let parts: Vec<&str> = value.split('-').collect();
+    let [year, month, day] = parts.as_slice() else {
+        return Err("expected YYYY-MM-DD".to_string());
+    };
+    let number = |s: &str| {
+        s.parse::<i64>()
+            .map_err(|_| "expected YYYY-MM-DD".to_string())
+    };
+    let (year, month, day) = (number(year)?, number(month)?, number(day)?);
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return Err("month must be 01 to 12".to_string()),
+    };
+    if year < 1970 || !(1..=days_in_month).contains(&day) {
+        return Err("not a date after 1970-01-01".to_string());
+    }
+    // Days from civil (Howard Hinnant's algorithm).
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+    Ok(days as u64 * 86_400)
+}
+
+/// Extract `#[produces("application/json", "text/csv")]` and
+/// `#[consumes(...)]`: one or more `type/subtype` media types each.
+pub fn extract_media_types(attrs: &[syn::Attribute]) -> syn::Result<RouteMediaTypesAttr> {
+    let mut media_types = RouteMediaTypesAttr::default();
+    for attr in attrs {
+        let (list, name) = if attr.path().is_ident("produces") {
+            (&mut media_types.produces, "produces")
+        } else if attr.path().is_ident("consumes") {
+            (&mut media_types.consumes, "consumes")
+        } else {
+            continue;
+        };
+        if !list.is_empty() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                format!("duplicate attribute: #[{name}] can only be set once"),
+            ));
+        }
+        let lits = attr.parse_args_with(
+            syn::punctuated::Punctuated::<syn::LitStr, syn::Token![,]>::parse_terminated,
+        )?;
+        if lits.is_empty() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                format!("#[{name}] expects at least one media type, e.g. #[{name}(\"application/json\")]"),
+            ));
+        }
+        for lit in lits {
+            let media_type = lit.value().trim().to_ascii_lowercase();
+            if !is_media_type(&media_type) {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    format!(
+                        "invalid media type '{}' — expected `type/subtype` without wildcards \
+                         or parameters, e.g. \"text/csv\"",
+                        lit.value()
+                    ),
+                ));
+            }
+            if list.contains(&media_type) {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    format!("media type '{media_type}' is listed twice"),
+                ));
+            }
+            list.push(media_type);
+        }
+    }
+    Ok(media_types)
+}
+
+/// `type/subtype` made of RFC 7230 token characters.
+fn is_media_type(s: &str) -> bool {
+    let token = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "!#$%&'+-.^_`|~".contains(c))
+    };
+    matches!(s.split_once('/'), Some((kind, subtype)) if token(kind) && token(subtype))
+}
+
+fn reject_duplicate(attr: &syn::Attribute, seen: bool) -> syn::Result<()> {
+    if seen {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "duplicate attribute: a route limit can only be set once",
+        ));
+    }
+    Ok(())
+}
+
+fn positive(int: &syn::LitInt) -> syn::Result<u64> {
+    let value = int.base10_parse::<u64>()?;
+    if value == 0 {
+        return Err(syn::Error::new(
+            int.span(),
+            "value must be greater than zero",
+        ));
+    }
+    Ok(value)
+}
+
 /// Extract `#[returns(T)]` — explicit response type for custom wrappers.
 pub fn extract_returns(attrs: &[syn::Attribute]) -> syn::Result<Option<syn::Type>> {
     for attr in attrs {
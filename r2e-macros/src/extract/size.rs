@@ -0,0 +1,41 @@
+//! Pure byte-size parser (`"50MB"`, `"512KiB"`, `"1024"` → bytes).
+//!
+//! Self-contained like `duration.rs`, so the integration tests under `tests/`
+//! can pull it in via `#[path = "../src/extract/size.rs"]`.
+
+/// Parse a size string like "50MB", "512KB", "1GiB" or "1024" into bytes.
+///
+/// Suffixes are case-insensitive and binary (`KB` = `KiB` = 1024): `B`,
+/// `KB`/`KiB`, `MB`/`MiB`, `GB`/`GiB`. A bare number is a byte count.
+pub fn parse_size_bytes(input: &str) -> Result<u64, String> {
+    let s = input.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, suffix) = s.split_at(split);
+    if number.is_empty() {
+        return Err(format!("expected a size like \"50MB\", got '{}'", input));
+    }
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid number: '{}'", number))?;
+
+    let multiplier: u64 = match suffix.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "kb" | "kib" | "k" => 1 << 10,
+        "mb" | "mib" | "m" => 1 << 20,
+        "gb" | "gib" | "g" => 1 << 30,
+        _ => {
+            return Err(format!(
+                "unknown size suffix '{}' — use B, KB, MB, or GB",
+                suffix.trim()
+            ))
+        }
+    };
+
+    let bytes = number
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("size too large: '{}' overflows", s))?;
+    if bytes == 0 {
+        return Err("size must be greater than zero".to_string());
+    }
+    Ok(bytes)
+}
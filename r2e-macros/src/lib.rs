@@ -24,9 +24,11 @@ pub(crate) mod main_attr;
 pub(crate) mod module_attr;
 pub(crate) mod params_derive;
 pub(crate) mod producer_attr;
+pub(crate) mod query_fields_derive;
 pub(crate) mod route;
 pub(crate) mod routes_attr;
 pub(crate) mod routes_parsing;
+pub(crate) mod transactional_attr;
 pub(crate) mod type_list_gen;
 pub(crate) mod type_utils;
 pub(crate) mod types;
@@ -597,6 +599,55 @@ pub fn scheduled(_args: TokenStream, input: TokenStream) -> TokenStream {
     input
 }
 
+/// Run a method inside a **transaction bound to the current task**.
+///
+/// Works on any `async fn(&self, ...)` — `#[bean]` service methods,
+/// `#[consumer]` handlers, `#[scheduled]` jobs and routes alike. The
+/// transaction is begun from a pool field (default name: `pool`; override
+/// with `pool = "name"`) whose type implements
+/// [`TransactionManager`](r2e_core::transaction::TransactionManager) — an
+/// SQLx `Pool<DB>` or a Diesel r2d2 pool. Code inside reaches it through the
+/// backend's accessor (`r2e_data_sqlx::current_tx`, `r2e_data_diesel::current_tx`),
+/// including code in nested service calls on the same task.
+///
+/// The return value decides the outcome: `Ok` / `()` / `HandlerResult::Ack`
+/// commit; `Err` / `HandlerResult::Nack` or a panic roll back. A `Result`
+/// error type must implement `From<TxError>` so a failed begin or commit can
+/// be returned.
+///
+/// Options:
+/// - `propagation = required | requires_new | mandatory` (default
+///   `required`: join the current transaction, or begin one)
+/// - `isolation = read_uncommitted | read_committed | repeatable_read | serializable`
+/// - `read_only`
+///
+/// ```ignore
+/// #[bean]
+/// impl OrderService {
+///     pub fn new(pool: SqlitePool, stock: StockService) -> Self { Self { pool, stock } }
+///
+///     #[transactional]
+///     pub async fn place(&self, order: Order) -> Result<(), HttpError> {
+///         {
+///             let mut tx = current_tx::<Sqlite>().await?;
+///             sqlx::query("INSERT INTO orders(item) VALUES (?)")
+///                 .bind(&order.item)
+///                 .execute(tx.connection())
+///                 .await
+///                 .map_err(|e| HttpError::internal(e.to_string()))?;
+///         }
+///         // Joins the same transaction.
+///         self.stock.reserve(&order.item).await
+///     }
+/// }
+/// ```
+///
+/// See [`r2e_core::transaction`] for the full semantics.
+#[proc_macro_attribute]
+pub fn transactional(args: TokenStream, input: TokenStream) -> TokenStream {
+    transactional_attr::expand(args, input)
+}
+
 /// Mark a method on a `#[bean]` impl or a `#[routes]` controller as an
 /// **async executor job**.
 ///
@@ -1277,11 +1328,11 @@ pub fn grpc_routes(args: TokenStream, input: TokenStream) -> TokenStream {
 // Params derive
 // ---------------------------------------------------------------------------
 
-/// Derive macro for aggregating path, query, and header parameters into a
-/// single struct.
+/// Derive macro for aggregating path, query, header, and cookie parameters
+/// into a single struct.
 ///
-/// Fields are annotated with `#[path]`, `#[query]`, or `#[header("Name")]`
-/// to indicate their extraction source. The generated `FromRequestParts`
+/// Fields are annotated with `#[path]`, `#[query]`, `#[header("Name")]`, or
+/// `#[cookie]` to indicate their extraction source. The generated `FromRequestParts`
 /// implementation extracts and parses each field automatically.
 ///
 /// # Attributes
@@ -1293,10 +1344,20 @@ pub fn grpc_routes(args: TokenStream, input: TokenStream) -> TokenStream {
 /// | `#[query]` | Query string | field name |
 /// | `#[query(name = "q")]` | Query string | custom name |
 /// | `#[header("X-Custom")]` | HTTP headers | explicit name |
+/// | `#[cookie]` | `Cookie` header | field name |
+/// | `#[cookie(name = "sid")]` | `Cookie` header | custom name |
 ///
 /// `Option<T>` fields are optional (absent = `None`).
 /// Non-Option fields are required (absent = 400 Bad Request).
 /// Conversion uses `FromStr` for non-String types.
+/// `Vec<T>` query fields collect repeated keys (`?tag=a&tag=b`), or a
+/// comma-separated value with `#[query(explode = false)]` (`?tag=a,b`).
+///
+/// `#[param(default)]` / `#[param(default = expr)]` fill absent fields and
+/// `#[param(example = expr)]` documents an example. The generated OpenAPI
+/// parameters carry the field's doc comment as description, its JSON schema
+/// (via `schemars` when available), and `minLength`/`maxLength`,
+/// `minimum`/`maximum` and `pattern` from its `#[garde(...)]` rules.
 ///
 /// # Example
 ///
@@ -1306,6 +1367,7 @@ pub fn grpc_routes(args: TokenStream, input: TokenStream) -> TokenStream {
 ///     #[path]
 ///     id: u64,
 ///
+///     /// Page number, starting at 1.
 ///     #[query]
 ///     #[garde(range(min = 1))]
 ///     page: Option<u32>,
@@ -1320,15 +1382,57 @@ pub fn grpc_routes(args: TokenStream, input: TokenStream) -> TokenStream {
 ///     // params.id, params.page, params.tenant_id extracted and validated
 /// }
 /// ```
-#[proc_macro_derive(Params, attributes(path, query, header, param, params))]
+#[proc_macro_derive(Params, attributes(path, query, header, cookie, param, params))]
 pub fn derive_params(input: TokenStream) -> TokenStream {
     params_derive::expand(input)
 }
 
+/// Derive macro declaring which fields of a DTO clients may sort and filter
+/// on, for the `Sort<T>` and `Filter<T>` extractors.
+///
+/// Query names follow the field's serde name (`rename`, `rename_all`); the
+/// SQL column defaults to the field name.
+///
+/// # Attributes
+///
+/// | Attribute | Meaning |
+/// |---|---|
+/// | `#[sortable]` | `?sort=field` / `?sort=-field` allowed |
+/// | `#[filterable]` | `?filter[field]=value` (equality) allowed |
+/// | `#[filterable(ops = "eq,lt,in")]` | allowed operators; the first is used by `filter[field]=value` |
+/// | `name = "..."` | query name override |
+/// | `column = "..."` | SQL column override, e.g. `"u.created_at"` |
+/// | `cast = "..."` | (`filterable`) SQL type the bound value is cast to |
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(Serialize, QueryFields)]
+/// #[serde(rename_all = "camelCase")]
+/// struct User {
+///     #[sortable]
+///     #[filterable(ops = "eq,in")]
+///     status: String,
+///     #[sortable]
+///     #[filterable(ops = "lt,gte", cast = "timestamptz")]
+///     created_at: String,
+/// }
+///
+/// #[get("/")]
+/// async fn list(&self, sort: Sort<User>, filter: Filter<User>) -> Json<Vec<User>> {
+///     // GET /users?sort=-createdAt&filter[status][in]=active,blocked
+/// }
+/// ```
+#[proc_macro_derive(QueryFields, attributes(sortable, filterable))]
+pub fn derive_query_fields(input: TokenStream) -> TokenStream {
+    query_fields_derive::expand(input)
+}
+
 /// Derive macro for ergonomic HTTP error types.
 ///
-/// Generates `impl Display`, `impl IntoResponse`, `impl Error`, and
-/// `impl From<T>` (for `#[from]` fields) from a simple enum declaration.
+/// Generates `impl Display`, `impl IntoResponse`, `impl Error`,
+/// `impl ErrorResponses` (per-variant OpenAPI responses), and `impl From<T>`
+/// (for `#[from]` fields) from a simple enum declaration.
 ///
 /// # Variant attributes
 ///
@@ -8,11 +8,12 @@ enum ParamSource {
     Path { name: String },
     Query { name: String },
     Header { name: String },
+    Cookie { name: String },
 }
 
 enum DefaultValue {
-    Trait,      // #[param(default)] → Default::default()
-    Expr(Expr), // #[param(default = 42)] → 42
+    Trait,           // #[param(default)] → Default::default()
+    Expr(Box<Expr>), // #[param(default = 42)] → 42
 }
 
 struct ParamField {
@@ -20,7 +21,22 @@ struct ParamField {
     ty: Type,
     source: ParamSource,
     is_optional: bool,
+    /// `Vec<T>` query fields collect every value of the key.
+    is_vec: bool,
+    /// `#[query(explode = false)]`: values come comma-separated in one key.
+    explode: bool,
     default_value: Option<DefaultValue>,
+    example: Option<Box<Expr>>,
+    description: Option<String>,
+    /// OpenAPI keywords derived from `#[garde(...)]` rules.
+    constraints: Vec<(&'static str, TokenStream)>,
+}
+
+/// Parsed field-level `#[param(...)]` options.
+#[derive(Default)]
+struct ParamOptions {
+    default_value: Option<DefaultValue>,
+    example: Option<Box<Expr>>,
 }
 
 enum NestedMode {
@@ -77,18 +93,21 @@ fn expand_inner(input: DeriveInput) -> syn::Result<TokenStream> {
         let ident = field.ident.clone().unwrap();
         let ty = field.ty.clone();
         let is_optional = is_option_type(&ty);
+        let is_vec = unwrap_vec_type(&ty).is_some();
 
         let mut source = None;
-        let mut default_value = None;
+        let mut explode = true;
+        let mut options = ParamOptions::default();
+        let mut constraints = Vec::new();
         let mut nested_mode = None;
 
         for attr in &field.attrs {
             if attr.path().is_ident("path") {
-                let custom_name = parse_name_attr(attr)?;
+                let custom_name = parse_name_attr(attr, None)?;
                 let name = custom_name.unwrap_or_else(|| ident.to_string());
                 source = Some(ParamSource::Path { name });
             } else if attr.path().is_ident("query") {
-                let custom_name = parse_name_attr(attr)?;
+                let custom_name = parse_name_attr(attr, Some(&mut explode))?;
                 let name = custom_name.unwrap_or_else(|| ident.to_string());
                 source = Some(ParamSource::Query { name });
             } else if attr.path().is_ident("header") {
@@ -96,18 +115,31 @@ fn expand_inner(input: DeriveInput) -> syn::Result<TokenStream> {
                 source = Some(ParamSource::Header {
                     name: header_name.value(),
                 });
+            } else if attr.path().is_ident("cookie") {
+                let custom_name = parse_name_attr(attr, None)?;
+                let name = custom_name.unwrap_or_else(|| ident.to_string());
+                source = Some(ParamSource::Cookie { name });
             } else if attr.path().is_ident("param") {
-                default_value = Some(parse_param_default(attr)?);
+                parse_param_options(attr, &mut options)?;
             } else if attr.path().is_ident("params") {
                 nested_mode = Some(parse_nested_mode(attr, &ident)?);
+            } else if attr.path().is_ident("garde") {
+                parse_garde_constraints(attr, is_vec, &mut constraints);
             }
         }
 
-        // Error if #[params] is combined with #[path], #[query], or #[header]
+        // Error if #[params] is combined with a parameter source
         if nested_mode.is_some() && source.is_some() {
             return Err(syn::Error::new_spanned(
                 &ident,
-                "#[params] cannot be combined with #[path], #[query], or #[header]",
+                "#[params] cannot be combined with #[path], #[query], #[header], or #[cookie]",
+            ));
+        }
+
+        if is_vec && source.is_some() && !matches!(source, Some(ParamSource::Query { .. })) {
+            return Err(syn::Error::new_spanned(
+                &ty,
+                "Vec fields are only supported for #[query] parameters",
             ));
         }
 
@@ -115,11 +147,16 @@ fn expand_inner(input: DeriveInput) -> syn::Result<TokenStream> {
             parsed_fields.push(ParsedField::Nested(NestedParamsField { ident, ty, mode }));
         } else if let Some(source) = source {
             parsed_fields.push(ParsedField::Param(ParamField {
+                description: field_doc(&field.attrs),
                 ident,
                 ty,
                 source,
                 is_optional,
-                default_value,
+                is_vec,
+                explode,
+                default_value: options.default_value,
+                example: options.example,
+                constraints,
             }));
         }
         // Fields without any recognized attribute are ignored
@@ -260,6 +297,7 @@ fn generate_field_construction(field: &ParamField, krate: &TokenStream) -> Token
         ParamSource::Path { name } => name.as_str(),
         ParamSource::Query { name } => name.as_str(),
         ParamSource::Header { name } => name.as_str(),
+        ParamSource::Cookie { name } => name.as_str(),
     };
 
     let missing_fallback = |error_msg: &str| -> TokenStream {
@@ -313,6 +351,39 @@ fn generate_field_construction(field: &ParamField, krate: &TokenStream) -> Token
                 }
             }
         }
+        ParamSource::Query { .. } if field.is_vec => {
+            // Every occurrence of the key, each optionally comma-separated
+            let split = if field.explode {
+                quote! { ::core::iter::once(v.as_str()) }
+            } else {
+                quote! { v.split(',') }
+            };
+            let when_empty = match &field.default_value {
+                Some(DefaultValue::Expr(expr)) => quote! {
+                    if __values.is_empty() {
+                        __values = (#expr).into();
+                    }
+                },
+                _ => quote! {},
+            };
+            quote! {
+                let #ident = {
+                    let __key = #krate::params::prefixed_key(__prefix, #name_str);
+                    let mut __values = Vec::new();
+                    for (_, v) in __query_pairs.iter().filter(|(k, _)| k.as_str() == __key.as_ref()) {
+                        for __item in #split.filter(|s| !s.is_empty()) {
+                            __values.push(__item.parse().map_err(|_| #krate::http::response::IntoResponse::into_response(
+                                #krate::params::ParamError {
+                                    message: format!("Invalid query parameter '{}': parse error", __key),
+                                }
+                            ))?);
+                        }
+                    }
+                    #when_empty
+                    __values
+                };
+            }
+        }
         ParamSource::Query { .. } => {
             // Query params are prefix-aware
             if field.is_optional {
@@ -426,6 +497,34 @@ fn generate_field_construction(field: &ParamField, krate: &TokenStream) -> Token
                 }
             }
         }
+        ParamSource::Cookie { .. } => {
+            // Cookie params are never prefixed
+            if field.is_optional {
+                let inner_ty = unwrap_option_type(&field.ty).unwrap();
+                quote! {
+                    let #ident: Option<#inner_ty> = match #krate::params::cookie_value(&parts.headers, #name_str) {
+                        Some(v) => Some(v.parse().map_err(|_| #krate::http::response::IntoResponse::into_response(
+                            #krate::params::ParamError {
+                                message: format!("Invalid cookie '{}': parse error", #name_str),
+                            }
+                        ))?),
+                        None => None,
+                    };
+                }
+            } else {
+                let fallback = missing_fallback(&format!("Missing cookie '{}'", name_str));
+                quote! {
+                    let #ident = match #krate::params::cookie_value(&parts.headers, #name_str) {
+                        Some(v) => v.parse().map_err(|_| #krate::http::response::IntoResponse::into_response(
+                            #krate::params::ParamError {
+                                message: format!("Invalid cookie '{}': parse error", #name_str),
+                            }
+                        ))?,
+                        None => #fallback,
+                    };
+                }
+            }
+        }
     }
 }
 
@@ -521,31 +620,43 @@ fn parse_nested_mode(attr: &syn::Attribute, field_ident: &Ident) -> syn::Result<
     }
 }
 
-fn parse_param_default(attr: &syn::Attribute) -> syn::Result<DefaultValue> {
-    let mut result = None;
+/// Parse `#[param(default)]`, `#[param(default = <expr>)]` and
+/// `#[param(example = <expr>)]`.
+fn parse_param_options(attr: &syn::Attribute, options: &mut ParamOptions) -> syn::Result<()> {
+    let mut any = false;
     attr.parse_nested_meta(|meta| {
+        any = true;
         if meta.path.is_ident("default") {
             if meta.input.peek(syn::Token![=]) {
                 let value = meta.value()?;
                 let expr: Expr = value.parse()?;
-                result = Some(DefaultValue::Expr(expr));
+                options.default_value = Some(DefaultValue::Expr(Box::new(expr)));
             } else {
-                result = Some(DefaultValue::Trait);
+                options.default_value = Some(DefaultValue::Trait);
             }
             Ok(())
+        } else if meta.path.is_ident("example") {
+            options.example = Some(meta.value()?.parse()?);
+            Ok(())
         } else {
-            Err(meta.error("expected `default` or `default = <expr>`"))
+            Err(meta.error("expected `default`, `default = <expr>`, or `example = <expr>`"))
         }
     })?;
-    result.ok_or_else(|| {
-        syn::Error::new_spanned(
+    if !any {
+        return Err(syn::Error::new_spanned(
             attr,
-            "expected #[param(default)] or #[param(default = <expr>)]",
-        )
-    })
+            "expected #[param(default)], #[param(default = <expr>)], or #[param(example = <expr>)]",
+        ));
+    }
+    Ok(())
 }
 
-fn parse_name_attr(attr: &syn::Attribute) -> syn::Result<Option<String>> {
+/// Parse `#[path(name = "...")]`-style attributes. `explode` is `Some` only
+/// for `#[query]`, which also accepts `explode = <bool>`.
+fn parse_name_attr(
+    attr: &syn::Attribute,
+    mut explode: Option<&mut bool>,
+) -> syn::Result<Option<String>> {
     match attr.meta {
         syn::Meta::Path(_) => Ok(None),
         syn::Meta::List(_) => {
@@ -556,6 +667,12 @@ fn parse_name_attr(attr: &syn::Attribute) -> syn::Result<Option<String>> {
                     let lit: LitStr = value.parse()?;
                     name = Some(lit.value());
                     Ok(())
+                } else if let (true, Some(explode)) =
+                    (meta.path.is_ident("explode"), explode.as_deref_mut())
+                {
+                    let lit: syn::LitBool = meta.value()?.parse()?;
+                    *explode = lit.value;
+                    Ok(())
                 } else {
                     Err(meta.error("expected `name = \"...\"`"))
                 }
@@ -566,11 +683,96 @@ fn parse_name_attr(attr: &syn::Attribute) -> syn::Result<Option<String>> {
     }
 }
 
-use crate::type_utils::{is_option_type, unwrap_option_type};
+/// Collect the OpenAPI keywords implied by a field's `#[garde(...)]` rules:
+/// `length` → `minLength`/`maxLength` (`minItems`/`maxItems` for `Vec`),
+/// `range` → `minimum`/`maximum`, `pattern`, `email` and `url` formats.
+/// Other rules are skipped; garde itself reports malformed attributes.
+fn parse_garde_constraints(
+    attr: &syn::Attribute,
+    is_vec: bool,
+    constraints: &mut Vec<(&'static str, TokenStream)>,
+) {
+    let (min_len, max_len) = if is_vec {
+        ("minItems", "maxItems")
+    } else {
+        ("minLength", "maxLength")
+    };
+    let _ = attr.parse_nested_meta(|meta| {
+        let bounds = if meta.path.is_ident("length") {
+            Some((min_len, max_len))
+        } else if meta.path.is_ident("range") {
+            Some(("minimum", "maximum"))
+        } else {
+            None
+        };
+        if let Some((min_key, max_key)) = bounds {
+            return meta.parse_nested_meta(|bound| {
+                if bound.input.peek(syn::Token![=]) {
+                    let expr: Expr = bound.value()?.parse()?;
+                    let value = quote! { (#expr) };
+                    if bound.path.is_ident("min") || bound.path.is_ident("equal") {
+                        constraints.push((min_key, value.clone()));
+                    }
+                    if bound.path.is_ident("max") || bound.path.is_ident("equal") {
+                        constraints.push((max_key, value));
+                    }
+                }
+                Ok(())
+            });
+        }
+        if meta.path.is_ident("pattern") {
+            let content;
+            syn::parenthesized!(content in meta.input);
+            if let Ok(lit) = content.parse::<LitStr>() {
+                constraints.push(("pattern", quote! { #lit }));
+            }
+            let _: TokenStream = content.parse()?;
+            return Ok(());
+        }
+        if meta.path.is_ident("email") {
+            constraints.push(("format", quote! { "email" }));
+        } else if meta.path.is_ident("url") {
+            constraints.push(("format", quote! { "uri" }));
+        }
+        // Skip the rule's arguments, whatever their shape.
+        if meta.input.peek(syn::Token![=]) {
+            let _: Expr = meta.value()?.parse()?;
+        } else if meta.input.peek(syn::token::Paren) {
+            let _: proc_macro2::TokenTree = meta.input.parse()?;
+        }
+        Ok(())
+    });
+}
+
+/// A field's doc comment, lines joined with spaces.
+fn field_doc(attrs: &[syn::Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(s),
+                        ..
+                    }),
+                ..
+            }) => Some(s.value().trim().to_string()),
+            _ => None,
+        })
+        .filter(|line| !line.is_empty())
+        .collect();
+    (!lines.is_empty()).then(|| lines.join(" "))
+}
+
+use crate::type_utils::{is_option_type, unwrap_option_type, unwrap_vec_type};
 
 /// Map a Rust type to an OpenAPI type string.
-fn rust_type_to_openapi_str(ty: &Type) -> &'static str {
+pub(crate) fn rust_type_to_openapi_str(ty: &Type) -> &'static str {
     let inner = unwrap_option_type(ty).unwrap_or(ty);
+    if unwrap_vec_type(inner).is_some() {
+        return "array";
+    }
     if let Type::Path(type_path) = inner {
         if let Some(segment) = type_path.path.segments.last() {
             return match segment.ident.to_string().as_str() {
@@ -587,6 +789,57 @@ fn rust_type_to_openapi_str(ty: &Type) -> &'static str {
     "string"
 }
 
+/// Static JSON schema for types the routes and params macros can classify
+/// without `JsonSchema`: primitives, UUIDs, dates and `Vec`s of those.
+pub(crate) fn static_schema_tokens(ty: &Type, krate: &TokenStream) -> TokenStream {
+    let inner = unwrap_option_type(ty).unwrap_or(ty);
+    if let Some(item) = unwrap_vec_type(inner) {
+        let items = static_schema_tokens(item, krate);
+        return quote! { #krate::serde_json::json!({ "type": "array", "items": #items }) };
+    }
+    let ident = match inner {
+        Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string())
+            .unwrap_or_default(),
+        _ => String::new(),
+    };
+    let (openapi_type, format) = match ident.as_str() {
+        "u8" | "u16" | "u32" | "i8" | "i16" | "i32" => ("integer", Some("int32")),
+        "u64" | "usize" | "i64" | "isize" => ("integer", Some("int64")),
+        "f32" => ("number", Some("float")),
+        "f64" => ("number", Some("double")),
+        "bool" => ("boolean", None),
+        "Uuid" => ("string", Some("uuid")),
+        "NaiveDate" | "Date" => ("string", Some("date")),
+        "DateTime" | "NaiveDateTime" | "OffsetDateTime" | "PrimitiveDateTime" => {
+            ("string", Some("date-time"))
+        }
+        _ => ("string", None),
+    };
+    match format {
+        Some(format) => {
+            quote! { #krate::serde_json::json!({ "type": #openapi_type, "format": #format }) }
+        }
+        None => quote! { #krate::serde_json::json!({ "type": #openapi_type }) },
+    }
+}
+
+/// `Option<Value>` expression holding the schemars schema of `ty`, or `None`
+/// when schemars is unavailable or `ty` does not implement `JsonSchema`.
+pub(crate) fn json_schema_probe(ty: &Type, krate: &TokenStream) -> TokenStream {
+    match crate::crate_path::r2e_schemars_path() {
+        Some(schemars) => crate::codegen::controller_impl::autoref_schema_probe(
+            ty,
+            quote! { #schemars::JsonSchema },
+            quote! { #krate::serde_json::to_value(#schemars::schema_for!(T)).unwrap() },
+        ),
+        None => quote! { None },
+    }
+}
+
 /// Generate `ParamInfo` literal tokens for each parsed field.
 fn generate_param_infos(
     fields: &[&ParamField],
@@ -605,9 +858,54 @@ fn generate_param_infos(
                 ParamSource::Header { name } => {
                     (name.clone(), quote! { #krate::meta::ParamLocation::Header })
                 }
+                ParamSource::Cookie { name } => {
+                    (name.clone(), quote! { #krate::meta::ParamLocation::Cookie })
+                }
             };
             let param_type = rust_type_to_openapi_str(&f.ty);
-            let required = !f.is_optional && f.default_value.is_none();
+            let required = !f.is_optional && !f.is_vec && f.default_value.is_none();
+
+            let ty = &f.ty;
+            let inner_ty = unwrap_option_type(ty).unwrap_or(ty);
+            let json_schema = json_schema_probe(inner_ty, krate);
+            let fallback = static_schema_tokens(inner_ty, krate);
+            let constraint_entries = f.constraints.iter().map(|(key, value)| {
+                quote! { #key: #value }
+            });
+            let default = match (&f.default_value, f.is_optional) {
+                (Some(default), false) => {
+                    // The expression is documented as written, before `.into()`
+                    let binding = match default {
+                        DefaultValue::Trait => quote! { let __default: #ty = Default::default(); },
+                        DefaultValue::Expr(expr) => quote! { let __default = #expr; },
+                    };
+                    quote! {
+                        {
+                            #binding
+                            use #krate::params::__NoValue as _;
+                            (&#krate::params::__ValueProbe(&__default)).value()
+                        }
+                    }
+                }
+                _ => quote! { None },
+            };
+            let example = match &f.example {
+                Some(expr) => quote! { #krate::serde_json::to_value(&(#expr)).ok() },
+                None => quote! { None },
+            };
+            let description = match &f.description {
+                Some(doc) => quote! { Some(#doc.to_string()) },
+                None => quote! { None },
+            };
+            let (style, explode) = if f.is_vec {
+                let explode = f.explode;
+                (
+                    quote! { Some("form".to_string()) },
+                    quote! { Some(#explode) },
+                )
+            } else {
+                (quote! { None }, quote! { None })
+            };
 
             quote! {
                 #krate::meta::ParamInfo {
@@ -615,6 +913,16 @@ fn generate_param_infos(
                     location: #location,
                     param_type: #param_type.to_string(),
                     required: #required,
+                    schema: Some(#krate::params::__param_schema(
+                        #json_schema,
+                        #fallback,
+                        #krate::serde_json::json!({ #(#constraint_entries),* }),
+                        #default,
+                    )),
+                    description: #description,
+                    example: #example,
+                    style: #style,
+                    explode: #explode,
                 }
             }
         })
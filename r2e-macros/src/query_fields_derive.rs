@@ -0,0 +1,263 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Data, DeriveInput, Expr, Fields, Lit, LitStr, Meta, Token};
+
+use crate::crate_path::r2e_core_path;
+use crate::params_derive::rust_type_to_openapi_str;
+
+const OPS: &[(&str, &str)] = &[
+    ("eq", "Eq"),
+    ("ne", "Ne"),
+    ("lt", "Lt"),
+    ("lte", "Lte"),
+    ("gt", "Gt"),
+    ("gte", "Gte"),
+    ("in", "In"),
+];
+
+struct SortableField {
+    name: String,
+    column: String,
+}
+
+struct FilterableField {
+    name: String,
+    column: String,
+    kind: &'static str,
+    ops: Vec<syn::Ident>,
+    cast: Option<String>,
+}
+
+/// Options shared by `#[sortable(...)]` and `#[filterable(...)]`.
+#[derive(Default)]
+struct FieldOptions {
+    name: Option<String>,
+    column: Option<String>,
+    ops: Option<LitStr>,
+    cast: Option<String>,
+}
+
+pub fn expand(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    match expand_inner(input) {
+        Ok(ts) => ts.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand_inner(input: DeriveInput) -> syn::Result<TokenStream> {
+    let krate = r2e_core_path();
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(f) => &f.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "QueryFields can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "QueryFields can only be derived for structs",
+            ))
+        }
+    };
+
+    let rename_all = serde_string(&input.attrs, "rename_all");
+
+    let mut sortable = Vec::new();
+    let mut filterable = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        let ident_str = ident.to_string();
+        let ident_str = ident_str.trim_start_matches("r#");
+        let default_name = serde_string(&field.attrs, "rename").unwrap_or_else(|| {
+            rename_all
+                .as_deref()
+                .map(|rule| rename(ident_str, rule))
+                .unwrap_or_else(|| ident_str.to_string())
+        });
+
+        for attr in &field.attrs {
+            if attr.path().is_ident("sortable") {
+                let options = parse_options(attr)?;
+                if options.ops.is_some() || options.cast.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "#[sortable] accepts `name` and `column`",
+                    ));
+                }
+                sortable.push(SortableField {
+                    name: options.name.unwrap_or_else(|| default_name.clone()),
+                    column: options.column.unwrap_or_else(|| ident_str.to_string()),
+                });
+            } else if attr.path().is_ident("filterable") {
+                let options = parse_options(attr)?;
+                let ops = match &options.ops {
+                    Some(lit) => parse_ops(lit)?,
+                    None => vec![syn::Ident::new("Eq", proc_macro2::Span::call_site())],
+                };
+                let kind = match rust_type_to_openapi_str(&field.ty) {
+                    "integer" => "Integer",
+                    "number" => "Number",
+                    "boolean" => "Boolean",
+                    _ => "String",
+                };
+                filterable.push(FilterableField {
+                    name: options.name.unwrap_or_else(|| default_name.clone()),
+                    column: options.column.unwrap_or_else(|| ident_str.to_string()),
+                    kind,
+                    ops,
+                    cast: options.cast,
+                });
+            }
+        }
+    }
+
+    let sort_items = sortable.iter().map(|f| {
+        let (name, column) = (&f.name, &f.column);
+        quote! {
+            #krate::pagination::SortField { name: #name, column: #column }
+        }
+    });
+    let filter_items = filterable.iter().map(|f| {
+        let (name, column) = (&f.name, &f.column);
+        let kind = syn::Ident::new(f.kind, proc_macro2::Span::call_site());
+        let ops = &f.ops;
+        let cast = match &f.cast {
+            Some(cast) => quote! { Some(#cast) },
+            None => quote! { None },
+        };
+        quote! {
+            #krate::pagination::FilterField {
+                name: #name,
+                column: #column,
+                kind: #krate::pagination::FieldKind::#kind,
+                ops: &[#(#krate::pagination::FilterOp::#ops),*],
+                cast: #cast,
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics #krate::pagination::QueryFields for #name #ty_generics #where_clause {
+            const SORT: &'static [#krate::pagination::SortField] = &[#(#sort_items),*];
+            const FILTER: &'static [#krate::pagination::FilterField] = &[#(#filter_items),*];
+        }
+    })
+}
+
+/// Parse `#[sortable]` / `#[filterable(name = "..", column = "..", ops = "..", cast = "..")]`.
+fn parse_options(attr: &Attribute) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+    if matches!(attr.meta, Meta::Path(_)) {
+        return Ok(options);
+    }
+    attr.parse_nested_meta(|meta| {
+        let value: LitStr = meta.value()?.parse()?;
+        if meta.path.is_ident("name") {
+            options.name = Some(value.value());
+        } else if meta.path.is_ident("column") {
+            check_sql_name(&value)?;
+            options.column = Some(value.value());
+        } else if meta.path.is_ident("ops") {
+            options.ops = Some(value);
+        } else if meta.path.is_ident("cast") {
+            check_sql_name(&value)?;
+            options.cast = Some(value.value());
+        } else {
+            return Err(meta.error("expected `name`, `column`, `ops` or `cast`"));
+        }
+        Ok(())
+    })?;
+    Ok(options)
+}
+
+/// Columns and casts are pasted into SQL, so keep them to identifiers.
+fn check_sql_name(lit: &LitStr) -> syn::Result<()> {
+    let value = lit.value();
+    let valid = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | ' '));
+    if valid {
+        Ok(())
+    } else {
+        Err(syn::Error::new_spanned(
+            lit,
+            "expected a column or type name (letters, digits, `_`, `.`, spaces)",
+        ))
+    }
+}
+
+fn parse_ops(lit: &LitStr) -> syn::Result<Vec<syn::Ident>> {
+    let mut ops = Vec::new();
+    for op in lit.value().split(',').map(str::trim) {
+        match OPS.iter().find(|(name, _)| *name == op) {
+            Some((_, variant)) => ops.push(syn::Ident::new(variant, lit.span())),
+            None => {
+                return Err(syn::Error::new_spanned(
+                    lit,
+                    format!(
+                        "unknown filter operator `{op}` (expected eq, ne, lt, lte, gt, gte, in)"
+                    ),
+                ))
+            }
+        }
+    }
+    Ok(ops)
+}
+
+/// Read a string-valued `#[serde(key = "...")]`, so the query names match
+/// the JSON field names.
+fn serde_string(attrs: &[Attribute], key: &str) -> Option<String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .find_map(|meta| match meta {
+            Meta::NameValue(nv) if nv.path.is_ident(key) => match nv.value {
+                Expr::Lit(syn::ExprLit {
+                    lit: Lit::Str(s), ..
+                }) => Some(s.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+}
+
+/// Apply a serde `rename_all` rule to a snake_case field name.
+fn rename(field: &str, rule: &str) -> String {
+    let words: Vec<&str> = field.split('_').filter(|w| !w.is_empty()).collect();
+    let capitalize = |word: &str| {
+        let mut chars = word.chars();
+        match chars.next() {
+            Some(c) => c.to_uppercase().collect::<String>() + chars.as_str(),
+            None => String::new(),
+        }
+    };
+    match rule {
+        "lowercase" => field.to_lowercase(),
+        "UPPERCASE" => field.to_uppercase(),
+        "PascalCase" => words.iter().map(|w| capitalize(w)).collect(),
+        "camelCase" => words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| if i == 0 { w.to_string() } else { capitalize(w) })
+            .collect(),
+        "SCREAMING_SNAKE_CASE" => field.to_uppercase(),
+        "kebab-case" => field.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => field.replace('_', "-").to_uppercase(),
+        _ => field.to_string(),
+    }
+}
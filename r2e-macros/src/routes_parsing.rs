@@ -6,10 +6,23 @@ use crate::types::*;
 pub struct RoutesImplDef {
     pub controller_name: syn::Ident,
     pub controller_intercepts: Vec<syn::Expr>,
+    /// Impl-level `#[timeout]` / `#[body_limit]` / `#[max_concurrent]`:
+    /// defaults for every HTTP route that does not set its own.
+    pub controller_limits: RouteLimitsAttr,
+    /// Impl-level `#[priority]`: the default for every route that does not
+    /// set its own.
+    pub controller_priority: Option<RoutePriorityAttr>,
+    /// Impl-level `#[produces]` / `#[consumes]`: defaults for every HTTP
+    /// route that does not set its own.
+    pub controller_media_types: RouteMediaTypesAttr,
+    /// Impl-level `#[etag]`: conditional requests on every HTTP route.
+    pub controller_etag: bool,
     pub route_methods: Vec<RouteMethod>,
     pub sse_methods: Vec<SseMethod>,
     pub ws_methods: Vec<WsMethod>,
     pub consumer_methods: Vec<ConsumerMethod>,
+    /// `#[emits(...)]` declarations, from any kind of method.
+    pub emitter_methods: Vec<EmitterMethod>,
     pub scheduled_methods: Vec<ScheduledMethod>,
     pub async_exec_methods: Vec<AsyncExecMethod>,
     /// `#[post_construct]` lifecycle methods (bodies stay on the core impl,
@@ -357,6 +370,10 @@ pub fn parse(item: syn::ItemImpl) -> syn::Result<RoutesImplDef> {
 
     // Extract controller-level intercepts from impl attrs
     let controller_intercepts = extract_intercept_fns(&item.attrs)?;
+    let controller_limits = extract_limits(&item.attrs)?;
+    let controller_priority = extract_priority(&item.attrs)?;
+    let controller_media_types = extract_media_types(&item.attrs)?;
+    let controller_etag = extract_etag(&item.attrs)?;
 
     // Scan `#[post_construct]` methods up front (the shared bean-side scan
     // validates `&self` / no extra params). Their bodies still flow to the core
@@ -371,6 +388,7 @@ pub fn parse(item: syn::ItemImpl) -> syn::Result<RoutesImplDef> {
     let mut sse_methods = Vec::new();
     let mut ws_methods = Vec::new();
     let mut consumer_methods = Vec::new();
+    let mut emitter_methods = Vec::new();
     let mut scheduled_methods = Vec::new();
     let mut async_exec_methods = Vec::new();
     let mut request_helper_methods = Vec::new();
@@ -379,6 +397,7 @@ pub fn parse(item: syn::ItemImpl) -> syn::Result<RoutesImplDef> {
     for impl_item in item.items {
         match impl_item {
             syn::ImplItem::Fn(mut method) => {
+                emitter_methods.extend(take_emits(&mut method)?);
                 let all_attrs = std::mem::take(&mut method.attrs);
 
                 // `#[request_helper]` — a plain helper deliberately moved onto
@@ -485,9 +504,10 @@ pub fn parse(item: syn::ItemImpl) -> syn::Result<RoutesImplDef> {
 
                     // A responder (non-`()` return) is point-to-point: exactly
                     // one handler replies. The fan-out subscriber options
-                    // (`topic`/`deserializer`/`filter`/`retry`/`dlq`) have no
-                    // meaning there — `respond` takes only the handler — so
-                    // reject them rather than silently ignore.
+                    // (`topic`/`deserializer`/`filter`/`retry`/`dlq`/
+                    // `ordered_by`) have no meaning there — `respond` takes
+                    // only the handler — so reject them rather than silently
+                    // ignore.
                     if matches!(kind, ConsumerKind::Responder { .. }) {
                         let bad = [
                             ("topic", config.topic.is_some()),
@@ -495,6 +515,7 @@ pub fn parse(item: syn::ItemImpl) -> syn::Result<RoutesImplDef> {
                             ("filter", config.filter.is_some()),
                             ("retry", config.retry.is_some()),
                             ("dlq", config.dlq.is_some()),
+                            ("ordered_by", config.ordered_by.is_some()),
                         ]
                         .into_iter()
                         .find_map(|(name, present)| present.then_some(name));
@@ -506,7 +527,7 @@ pub fn parse(item: syn::ItemImpl) -> syn::Result<RoutesImplDef> {
                                      responder #[consumer] (a method with a non-`()` return type).\n\
                                      \nA responder is point-to-point (registered via `EventBus::respond`): \
                                      exactly one handler replies to each `request`, so `topic`, `deserializer`, \
-                                     `filter`, `retry`, and `dlq` do not apply.\n\
+                                     `filter`, `retry`, `dlq`, and `ordered_by` do not apply.\n\
                                      \n  - For request-reply, keep only `bus`: #[consumer(bus = \"event_bus\")] \
                                      async fn handle(&self, event: Arc<Req>) -> Resp\n\
                                      \n  - For fan-out with {bad}, return `()` (or `Result<(), E>`) instead."
@@ -531,6 +552,7 @@ pub fn parse(item: syn::ItemImpl) -> syn::Result<RoutesImplDef> {
                         filter: config.filter,
                         retry: config.retry,
                         dlq: config.dlq,
+                        ordered_by: config.ordered_by,
                         event_type,
                         kind,
                         intercept_fns,
@@ -558,6 +580,11 @@ pub fn parse(item: syn::ItemImpl) -> syn::Result<RoutesImplDef> {
                     });
                 } else if let Some((sse_path, keep_alive)) = extract_sse_attr(&all_attrs)? {
                     let decorators = parse_decorators(&all_attrs)?;
+                    reject_streaming_limits(&decorators, &method, "#[sse]")?;
+                    reject_streaming_media_types(&decorators, &method, "#[sse]")?;
+                    reject_streaming_etag(&decorators, &method, "#[sse]")?;
+                    reject_streaming_idempotent(&decorators, &method, "#[sse]")?;
+                    reject_streaming_template(&decorators, &method, "#[sse]")?;
 
                     method.attrs = strip_known_attrs(all_attrs);
                     let identity_param = extract_identity_param(&mut method)?;
@@ -577,6 +604,11 @@ pub fn parse(item: syn::ItemImpl) -> syn::Result<RoutesImplDef> {
                     });
                 } else if let Some(ws_path) = extract_ws_attr(&all_attrs)? {
                     let decorators = parse_decorators(&all_attrs)?;
+                    reject_streaming_limits(&decorators, &method, "#[ws]")?;
+                    reject_streaming_media_types(&decorators, &method, "#[ws]")?;
+                    reject_streaming_etag(&decorators, &method, "#[ws]")?;
+                    reject_streaming_idempotent(&decorators, &method, "#[ws]")?;
+                    reject_streaming_template(&decorators, &method, "#[ws]")?;
 
                     method.attrs = strip_known_attrs(all_attrs);
                     let identity_param = extract_identity_param(&mut method)?;
@@ -603,8 +635,9 @@ pub fn parse(item: syn::ItemImpl) -> syn::Result<RoutesImplDef> {
                     // #[middleware]/#[layer]/#[pre_guard] attach to a
                     // `.route(path, method_router)` registration; a fallback is
                     // registered via `Router::fallback(handler)`, which takes
-                    // no layers. #[guard] and #[intercept] run inside the
-                    // generated handler and work as usual.
+                    // no layers (route limits are layers too). #[guard] and
+                    // #[intercept] run inside the generated handler and work
+                    // as usual.
                     if route_kind.is_fallback {
                         if !decorators.pre_auth_guard_fns.is_empty()
                             || !decorators.middleware_fns.is_empty()
@@ -617,6 +650,80 @@ pub fn parse(item: syn::ItemImpl) -> syn::Result<RoutesImplDef> {
                                  the handler) or do the work in the handler body",
                             ));
                         }
+                        if !decorators.limits.is_empty() {
+                            return Err(syn::Error::new(
+                                method.sig.ident.span(),
+                                "#[timeout], #[body_limit], and #[max_concurrent] are not supported \
+                                 on #[fallback] routes",
+                            ));
+                        }
+                        if !decorators.media_types.is_empty() {
+                            return Err(syn::Error::new(
+                                method.sig.ident.span(),
+                                "#[produces] and #[consumes] are not supported on #[fallback] \
+                                 routes",
+                            ));
+                        }
+                        if decorators.priority.is_some() {
+                            return Err(syn::Error::new(
+                                method.sig.ident.span(),
+                                "#[priority] is not supported on #[fallback] routes — an \
+                                 unmatched request has no route to take its priority from",
+                            ));
+                        }
+                        if decorators.etag {
+                            return Err(syn::Error::new(
+                                method.sig.ident.span(),
+                                "#[etag] is not supported on #[fallback] routes",
+                            ));
+                        }
+                        if decorators.idempotent_ttl_ms.is_some() {
+                            return Err(syn::Error::new(
+                                method.sig.ident.span(),
+                                "#[idempotent] is not supported on #[fallback] routes",
+                            ));
+                        }
+                        if !decorators.versions.is_empty() {
+                            return Err(syn::Error::new(
+                                method.sig.ident.span(),
+                                "#[since], #[until] and #[deprecated_in] are not supported on \
+                                 #[fallback] routes — the fallback is shared by every version",
+                            ));
+                        }
+                        if decorators.template.is_some() {
+                            return Err(syn::Error::new(
+                                method.sig.ident.span(),
+                                "#[template] is not supported on #[fallback] routes",
+                            ));
+                        }
+                    }
+                    if decorators.template.is_some()
+                        && !decorators
+                            .media_types
+                            .or(&controller_media_types)
+                            .produces
+                            .is_empty()
+                    {
+                        return Err(syn::Error::new(
+                            method.sig.ident.span(),
+                            "#[template] renders HTML and cannot be combined with #[produces]",
+                        ));
+                    }
+                    if decorators.etag && route_kind.method == crate::route::HttpMethod::Post {
+                        return Err(syn::Error::new(
+                            method.sig.ident.span(),
+                            "#[etag] applies to GET, PUT, PATCH and DELETE routes — a POST \
+                             creates a resource that has no version yet",
+                        ));
+                    }
+                    if decorators.idempotent_ttl_ms.is_some()
+                        && route_kind.method == crate::route::HttpMethod::Get
+                    {
+                        return Err(syn::Error::new(
+                            method.sig.ident.span(),
+                            "#[idempotent] applies to POST, PUT, PATCH and DELETE routes — a GET \
+                             is already safe to retry",
+                        ));
                     }
 
                     method.attrs = strip_known_attrs(all_attrs);
@@ -688,10 +795,15 @@ pub fn parse(item: syn::ItemImpl) -> syn::Result<RoutesImplDef> {
     Ok(RoutesImplDef {
         controller_name,
         controller_intercepts,
+        controller_limits,
+        controller_priority,
+        controller_media_types,
+        controller_etag,
         route_methods,
         sse_methods,
         ws_methods,
         consumer_methods,
+        emitter_methods,
         scheduled_methods,
         async_exec_methods,
         post_construct_methods,
@@ -700,3 +812,83 @@ pub fn parse(item: syn::ItemImpl) -> syn::Result<RoutesImplDef> {
         other_methods,
     })
 }
+
+/// `#[timeout]` / `#[body_limit]` / `#[max_concurrent]` bound a single
+/// request/response exchange; a stream or an upgraded socket outlives it.
+fn reject_streaming_limits(
+    decorators: &MethodDecorators,
+    method: &syn::ImplItemFn,
+    kind: &str,
+) -> syn::Result<()> {
+    if decorators.limits.is_empty() {
+        return Ok(());
+    }
+    Err(syn::Error::new(
+        method.sig.ident.span(),
+        format!(
+            "#[timeout], #[body_limit], and #[max_concurrent] are not supported on {kind} \
+             methods — the stream outlives the request they bound"
+        ),
+    ))
+}
+
+/// An SSE stream is always `text/event-stream` and a WebSocket upgrade has
+/// no body to negotiate.
+fn reject_streaming_media_types(
+    decorators: &MethodDecorators,
+    method: &syn::ImplItemFn,
+    kind: &str,
+) -> syn::Result<()> {
+    if decorators.media_types.is_empty() {
+        return Ok(());
+    }
+    Err(syn::Error::new(
+        method.sig.ident.span(),
+        format!("#[produces] and #[consumes] are not supported on {kind} methods"),
+    ))
+}
+
+/// A stream has no single representation to tag.
+fn reject_streaming_etag(
+    decorators: &MethodDecorators,
+    method: &syn::ImplItemFn,
+    kind: &str,
+) -> syn::Result<()> {
+    if !decorators.etag {
+        return Ok(());
+    }
+    Err(syn::Error::new(
+        method.sig.ident.span(),
+        format!("#[etag] is not supported on {kind} methods"),
+    ))
+}
+
+/// A stream cannot be stored and replayed.
+fn reject_streaming_idempotent(
+    decorators: &MethodDecorators,
+    method: &syn::ImplItemFn,
+    kind: &str,
+) -> syn::Result<()> {
+    if decorators.idempotent_ttl_ms.is_none() {
+        return Ok(());
+    }
+    Err(syn::Error::new(
+        method.sig.ident.span(),
+        format!("#[idempotent] is not supported on {kind} methods"),
+    ))
+}
+
+/// A stream has no single context to render.
+fn reject_streaming_template(
+    decorators: &MethodDecorators,
+    method: &syn::ImplItemFn,
+    kind: &str,
+) -> syn::Result<()> {
+    if decorators.template.is_none() {
+        return Ok(());
+    }
+    Err(syn::Error::new(
+        method.sig.ident.span(),
+        format!("#[template] is not supported on {kind} methods"),
+    ))
+}
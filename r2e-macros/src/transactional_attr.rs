@@ -0,0 +1,149 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, FnArg, ItemFn, ReturnType};
+
+use crate::crate_path::r2e_core_path;
+
+/// Parsed `#[transactional(...)]` arguments.
+struct TransactionalArgs {
+    /// Field holding the pool / transaction manager (default `pool`).
+    pool: syn::Ident,
+    /// `Propagation` variant name.
+    propagation: Option<syn::Ident>,
+    /// `Isolation` variant name.
+    isolation: Option<syn::Ident>,
+    read_only: bool,
+}
+
+impl TransactionalArgs {
+    fn parse(args: TokenStream) -> syn::Result<Self> {
+        let mut pool = None;
+        let mut propagation = None;
+        let mut isolation = None;
+        let mut read_only = false;
+        if !args.is_empty() {
+            let parser = syn::meta::parser(|meta| {
+                if meta.path.is_ident("pool") {
+                    let lit: syn::LitStr = meta.value()?.parse()?;
+                    pool = Some(syn::Ident::new(&lit.value(), lit.span()));
+                } else if meta.path.is_ident("propagation") {
+                    let value: syn::Ident = meta.value()?.parse()?;
+                    let variant =
+                        match value.to_string().as_str() {
+                            "required" => "Required",
+                            "requires_new" => "RequiresNew",
+                            "mandatory" => "Mandatory",
+                            _ => return Err(syn::Error::new_spanned(
+                                &value,
+                                "`propagation` must be `required`, `requires_new` or `mandatory`",
+                            )),
+                        };
+                    propagation = Some(syn::Ident::new(variant, value.span()));
+                } else if meta.path.is_ident("isolation") {
+                    let value: syn::Ident = meta.value()?.parse()?;
+                    let variant = match value.to_string().as_str() {
+                        "read_uncommitted" => "ReadUncommitted",
+                        "read_committed" => "ReadCommitted",
+                        "repeatable_read" => "RepeatableRead",
+                        "serializable" => "Serializable",
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                &value,
+                                "`isolation` must be `read_uncommitted`, `read_committed`, \
+                                 `repeatable_read` or `serializable`",
+                            ))
+                        }
+                    };
+                    isolation = Some(syn::Ident::new(variant, value.span()));
+                } else if meta.path.is_ident("read_only") {
+                    read_only = if meta.input.peek(syn::Token![=]) {
+                        meta.value()?.parse::<syn::LitBool>()?.value
+                    } else {
+                        true
+                    };
+                } else {
+                    return Err(meta.error(
+                        "unknown key in #[transactional(...)]: expected `propagation`, \
+                         `isolation`, `read_only` or `pool`\n\n\
+                         example: #[transactional(propagation = requires_new, isolation = serializable)]",
+                    ));
+                }
+                Ok(())
+            });
+            syn::parse::Parser::parse(parser, args)?;
+        }
+        Ok(Self {
+            pool: pool.unwrap_or_else(|| syn::Ident::new("pool", proc_macro2::Span::call_site())),
+            propagation,
+            isolation,
+            read_only,
+        })
+    }
+}
+
+pub fn expand(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = match TransactionalArgs::parse(args) {
+        Ok(a) => a,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let item_fn = parse_macro_input!(input as ItemFn);
+    match generate(&item_fn, &args) {
+        Ok(output) => output.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn generate(item_fn: &ItemFn, args: &TransactionalArgs) -> syn::Result<TokenStream2> {
+    let sig = &item_fn.sig;
+    if sig.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(
+            sig.fn_token,
+            "#[transactional] requires an `async fn` — the body runs inside the transaction scope",
+        ));
+    }
+    match sig.inputs.first() {
+        Some(FnArg::Receiver(receiver))
+            if receiver.reference.is_some() && receiver.mutability.is_none() => {}
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &sig.ident,
+                "#[transactional] methods must take `&self` as the first argument. \
+                 The bean/controller also needs a pool field \
+                 (default name: `pool`; override with `#[transactional(pool = \"name\")]`)",
+            ))
+        }
+    }
+
+    let krate = r2e_core_path();
+    let output_ty = match &sig.output {
+        ReturnType::Default => quote! { () },
+        ReturnType::Type(_, ty) => quote! { #ty },
+    };
+    let pool = &args.pool;
+    let mut options = quote! { #krate::transaction::TxOptions::new() };
+    if let Some(propagation) = &args.propagation {
+        options = quote! { #options.propagation(#krate::transaction::Propagation::#propagation) };
+    }
+    if let Some(isolation) = &args.isolation {
+        options = quote! { #options.isolation(#krate::transaction::Isolation::#isolation) };
+    }
+    if args.read_only {
+        options = quote! { #options.read_only(true) };
+    }
+
+    let attrs = &item_fn.attrs;
+    let vis = &item_fn.vis;
+    let block = &item_fn.block;
+    Ok(quote! {
+        #(#attrs)*
+        #vis #sig {
+            #krate::transaction::transactional::<_, _, _, #output_ty>(
+                &self.#pool,
+                #options,
+                async move #block,
+            )
+            .await
+        }
+    })
+}
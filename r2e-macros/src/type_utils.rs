@@ -50,6 +50,24 @@ pub fn result_ok_type(ty: &Type) -> Option<&Type> {
     })
 }
 
+/// If `ty` is `Vec<X>`, return `Some(X)`. Otherwise, return `None`.
+pub fn unwrap_vec_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let last = type_path.path.segments.last()?;
+    if last.ident != "Vec" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &last.arguments else {
+        return None;
+    };
+    match args.args.first() {
+        Some(syn::GenericArgument::Type(inner)) if args.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
+
 /// If `ty` is `Option<X>` (or `std::option::Option<X>`), return `Some(X)`.
 /// Otherwise, return `None`.
 pub fn unwrap_option_type(ty: &Type) -> Option<&Type> {
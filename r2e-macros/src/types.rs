@@ -45,6 +45,14 @@ pub struct ConfigSectionField {
     pub prefix: String,
 }
 
+/// A controller method annotated `#[emits(EventA, EventB)]`: the event types
+/// it publishes, documented as `EmitterInfo` metadata.
+pub struct EmitterMethod {
+    pub fn_ident: syn::Ident,
+    pub summary: Option<String>,
+    pub event_types: Vec<syn::Type>,
+}
+
 pub struct ConsumerMethod {
     pub bus_field: String,
     pub topic: Option<String>,
@@ -52,6 +60,7 @@ pub struct ConsumerMethod {
     pub filter: Option<String>,
     pub retry: Option<u32>,
     pub dlq: Option<String>,
+    pub ordered_by: Option<String>,
     pub event_type: syn::Type,
     /// Whether the method is a plain fan-out subscriber (`-> ()` /
     /// `-> Result<(), E>`) or a request-reply responder (non-`()` return).
@@ -144,6 +153,112 @@ pub struct MethodDecorators {
     /// identity: no extraction runs and the method is emitted on the core
     /// (reading the identity field is a compile error).
     pub anonymous: bool,
+    /// `#[timeout]`, `#[body_limit]`, `#[max_concurrent]`.
+    pub limits: RouteLimitsAttr,
+    /// `#[priority(critical|normal|background)]`.
+    pub priority: Option<RoutePriorityAttr>,
+    /// `#[produces(...)]`, `#[consumes(...)]`.
+    pub media_types: RouteMediaTypesAttr,
+    /// `#[etag]`.
+    pub etag: bool,
+    /// `#[idempotent(ttl = "...")]`: stored-response TTL in milliseconds.
+    pub idempotent_ttl_ms: Option<u64>,
+    /// `#[since(N)]`, `#[until(N)]`, `#[deprecated_in(N, ...)]`.
+    pub versions: RouteVersionsAttr,
+    /// `#[template("name")]`: the template rendering the returned context.
+    pub template: Option<syn::LitStr>,
+}
+
+/// API version bounds of a method in a `#[controller(versions = "...")]`.
+#[derive(Default, Clone, Copy)]
+pub struct RouteVersionsAttr {
+    pub since: Option<u32>,
+    pub until: Option<u32>,
+    pub deprecated_in: Option<u32>,
+    /// `date = "YYYY-MM-DD"`, in seconds since the Unix epoch.
+    pub deprecation_date: Option<u64>,
+    /// `sunset = "YYYY-MM-DD"`, in seconds since the Unix epoch.
+    pub sunset: Option<u64>,
+}
+
+impl RouteVersionsAttr {
+    pub fn is_empty(&self) -> bool {
+        self.since.is_none() && self.until.is_none() && self.deprecated_in.is_none()
+    }
+}
+
+/// Media types from `#[produces("application/json", "text/csv")]` and
+/// `#[consumes(...)]`, on a method or on the `#[routes]` impl
+/// (controller-wide defaults). Lowercase, in declaration order.
+#[derive(Default, Clone)]
+pub struct RouteMediaTypesAttr {
+    pub produces: Vec<String>,
+    pub consumes: Vec<String>,
+}
+
+impl RouteMediaTypesAttr {
+    pub fn is_empty(&self) -> bool {
+        self.produces.is_empty() && self.consumes.is_empty()
+    }
+
+    /// Fill the lists this set leaves unspecified from `defaults`.
+    pub fn or(&self, defaults: &RouteMediaTypesAttr) -> RouteMediaTypesAttr {
+        let pick = |own: &Vec<String>, default: &Vec<String>| {
+            if own.is_empty() {
+                default.clone()
+            } else {
+                own.clone()
+            }
+        };
+        RouteMediaTypesAttr {
+            produces: pick(&self.produces, &defaults.produces),
+            consumes: pick(&self.consumes, &defaults.consumes),
+        }
+    }
+}
+
+/// Load-shedding class from `#[priority(...)]`, on a method or on the
+/// `#[routes]` impl (controller-wide default).
+#[derive(Clone, Copy)]
+pub enum RoutePriorityAttr {
+    Critical,
+    Normal,
+    Background,
+}
+
+impl RoutePriorityAttr {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RoutePriorityAttr::Critical => "critical",
+            RoutePriorityAttr::Normal => "normal",
+            RoutePriorityAttr::Background => "background",
+        }
+    }
+}
+
+/// Per-route request bounds from `#[timeout("5s")]`, `#[body_limit("50MB")]`
+/// and `#[max_concurrent(10)]`, on a method or on the `#[routes]` impl
+/// (controller-wide defaults).
+#[derive(Default, Clone, Copy)]
+pub struct RouteLimitsAttr {
+    pub timeout_ms: Option<u64>,
+    pub body_limit: Option<u64>,
+    pub max_concurrent: Option<u32>,
+}
+
+impl RouteLimitsAttr {
+    pub fn is_empty(&self) -> bool {
+        self.timeout_ms.is_none() && self.body_limit.is_none() && self.max_concurrent.is_none()
+    }
+
+    /// Fill the fields this set leaves unspecified from `defaults`.
+    pub fn or(self, defaults: RouteLimitsAttr) -> RouteLimitsAttr {
+        RouteLimitsAttr {
+            timeout_ms: self.timeout_ms.or(defaults.timeout_ms),
+            body_limit: self.body_limit.or(defaults.body_limit),
+            max_concurrent: self.max_concurrent.or(defaults.max_concurrent),
+        }
+    }
 }
 
 pub struct IdentityParam {
@@ -0,0 +1,46 @@
+//! Integration tests for the byte-size parser used by `#[body_limit]`.
+//!
+//! Pulled in via `#[path]` for the same reason as the duration parser.
+
+#[path = "../src/extract/size.rs"]
+mod size;
+
+use size::parse_size_bytes;
+
+#[test]
+fn parse_bare_bytes() {
+    assert_eq!(parse_size_bytes("1024").unwrap(), 1024);
+    assert_eq!(parse_size_bytes("512B").unwrap(), 512);
+}
+
+#[test]
+fn parse_binary_units() {
+    assert_eq!(parse_size_bytes("1KB").unwrap(), 1024);
+    assert_eq!(parse_size_bytes("50MB").unwrap(), 50 * 1024 * 1024);
+    assert_eq!(parse_size_bytes("2GiB").unwrap(), 2 * 1024 * 1024 * 1024);
+}
+
+#[test]
+fn parse_is_case_insensitive() {
+    assert_eq!(parse_size_bytes("10mb").unwrap(), 10 * 1024 * 1024);
+    assert_eq!(parse_size_bytes(" 4 KiB ").unwrap(), 4096);
+}
+
+#[test]
+fn reject_unknown_suffix() {
+    assert!(parse_size_bytes("5TB")
+        .unwrap_err()
+        .contains("unknown size suffix"));
+}
+
+#[test]
+fn reject_missing_number() {
+    assert!(parse_size_bytes("MB").is_err());
+    assert!(parse_size_bytes("").is_err());
+}
+
+#[test]
+fn reject_zero_and_overflow() {
+    assert!(parse_size_bytes("0MB").is_err());
+    assert!(parse_size_bytes("99999999999999999GB").is_err());
+}
@@ -0,0 +1,39 @@
+//! Trace-context propagation for the R2E event bus.
+//!
+//! [`OtelEventPropagator`] plugs the global OpenTelemetry text-map propagator
+//! into `r2e_events::propagation`: emits carry the current span's
+//! `traceparent` in their metadata headers and consumer spans continue that
+//! trace. The [`Observability`](crate::Observability) plugin installs it
+//! automatically when the `events` feature is enabled.
+
+use std::collections::HashMap;
+
+use opentelemetry::trace::TraceContextExt;
+use r2e_events::propagation::TracePropagator;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// [`TracePropagator`] backed by the global OpenTelemetry propagator.
+pub struct OtelEventPropagator;
+
+impl TracePropagator for OtelEventPropagator {
+    fn inject(&self, headers: &mut HashMap<String, String>) {
+        let cx = tracing::Span::current().context();
+        if !cx.span().span_context().is_valid() {
+            return;
+        }
+        opentelemetry::global::get_text_map_propagator(|propagator| {
+            propagator.inject_context(&cx, headers)
+        });
+    }
+
+    fn extract(&self, headers: &HashMap<String, String>, span: &tracing::Span) {
+        let parent = opentelemetry::global::get_text_map_propagator(|propagator| {
+            propagator.extract(headers)
+        });
+        // Without a carried context, leave the span as a root rather than
+        // parenting it to an empty context.
+        if parent.span().span_context().is_valid() {
+            let _ = span.set_parent(parent);
+        }
+    }
+}
@@ -22,6 +22,8 @@
 //! ```
 
 pub mod config;
+#[cfg(feature = "events")]
+pub mod events;
 pub mod middleware;
 pub mod propagation;
 pub mod tracing_setup;
@@ -43,7 +45,8 @@ use r2e_core::Plugin;
 /// # What it does
 ///
 /// 1. Initialises a `tracing-subscriber` stack (fmt layer + OTel layer).
-/// 2. Installs a W3C `traceparent` propagator for cross-service context.
+/// 2. Installs a W3C `traceparent` propagator for cross-service context (and,
+///    with the `events` feature, for event-bus emits and consumers).
 /// 3. Adds a tower-http `TraceLayer` (same as the `Tracing` plugin).
 /// 4. Adds an `OtelTraceLayer` that creates OTel spans for each HTTP request.
 /// 5. Registers an `on_stop` hook that flushes pending traces on shutdown.
@@ -102,8 +105,10 @@ impl Plugin for Observability {
         self,
         app: r2e_core::AppBuilder<T>,
     ) -> r2e_core::AppBuilder<T> {
-        // 1. Install global propagator
+        // 1. Install global propagator (HTTP and, with `events`, the event bus)
         propagation::install_propagator(&self.config);
+        #[cfg(feature = "events")]
+        r2e_events::propagation::set_trace_propagator(events::OtelEventPropagator);
 
         // 2. Initialize tracing + OTel (if enabled)
         let guard = if self.config.tracing_enabled {
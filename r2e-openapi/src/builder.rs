@@ -1,6 +1,6 @@
-use r2e_core::meta::{ParamLocation, RouteInfo};
+use r2e_core::meta::{ErrorResponseInfo, ParamInfo, ParamLocation, RouteInfo};
 use serde_json::{json, Map, Value};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use crate::schema::SchemaRegistry;
 
@@ -55,6 +55,20 @@ fn insert_schema(
     }
 }
 
+/// The inline schema of a parameter: its JSON schema without `$schema` or
+/// `$defs` (promoted to components), or `{"type": param_type}`.
+fn param_schema(param: &ParamInfo) -> Value {
+    let Some(mut schema) = param.schema.clone() else {
+        return json!({ "type": param.param_type });
+    };
+    if let Some(obj) = schema.as_object_mut() {
+        obj.remove("$schema");
+        obj.remove("$defs");
+    }
+    sanitize_schema(&mut schema);
+    schema
+}
+
 /// A gap between a route and its generated OpenAPI schema, surfaced as a
 /// once-at-boot warning so silently-undocumented bodies become visible.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -171,6 +185,9 @@ pub struct OpenApiConfig {
     pub version: String,
     pub description: Option<String>,
     pub docs_ui: bool,
+    /// Document error responses as RFC 9457 Problem Details, to match the
+    /// `ProblemDetails` plugin.
+    pub problem_details: bool,
     pub(crate) schema_registry: SchemaRegistry,
     pub(crate) schema_overrides: HashMap<String, Value>,
 }
@@ -182,6 +199,7 @@ impl OpenApiConfig {
             version: version.to_string(),
             description: None,
             docs_ui: false,
+            problem_details: false,
             schema_registry: SchemaRegistry::new(),
             schema_overrides: HashMap::new(),
         }
@@ -197,6 +215,14 @@ impl OpenApiConfig {
         self
     }
 
+    /// Document error responses as `application/problem+json` with the
+    /// `ProblemDetails` / `ValidationProblemDetails` schemas. Enable this when
+    /// the app installs the `ProblemDetails` plugin.
+    pub fn with_problem_details(mut self, enabled: bool) -> Self {
+        self.problem_details = enabled;
+        self
+    }
+
     /// Add a schema for a type implementing `schemars::JsonSchema`.
     ///
     /// The schema will appear in `components/schemas` even if the type is not
@@ -229,6 +255,78 @@ impl OpenApiConfig {
     }
 }
 
+/// The response object for one status: descriptions joined with ` / `,
+/// and a `oneOf` when the variants use different body schemas.
+fn error_response_object(errors: &[&ErrorResponseInfo], problem_details: bool) -> Value {
+    let mut descriptions: Vec<&str> = Vec::new();
+    let mut schemas: Vec<Value> = Vec::new();
+    for error in errors {
+        if !descriptions.contains(&error.description.as_str()) {
+            descriptions.push(&error.description);
+        }
+        let schema = error_schema(
+            &error.schema,
+            error.problem_type.as_deref(),
+            problem_details,
+        );
+        if !schemas.contains(&schema) {
+            schemas.push(schema);
+        }
+    }
+    let schema = if schemas.len() == 1 {
+        schemas.remove(0)
+    } else {
+        json!({ "oneOf": schemas })
+    };
+    let media_type = if problem_details {
+        r2e_core::problem::PROBLEM_JSON
+    } else {
+        "application/json"
+    };
+    json!({
+        "description": descriptions.join(" / "),
+        "content": {
+            media_type: { "schema": schema }
+        }
+    })
+}
+
+/// `$ref` to an error body schema. With Problem Details, the matching
+/// `application/problem+json` schema, pinned to the problem type if known.
+/// An OpenAPI `content` map: `schema` under each of `media_types`, or under
+/// `fallback` alone when the route declares none.
+fn media_type_map(media_types: &[String], fallback: &str, schema: &Value) -> Value {
+    let mut content = Map::new();
+    if media_types.is_empty() {
+        content.insert(fallback.to_string(), json!({ "schema": schema }));
+    }
+    for media_type in media_types {
+        content.insert(media_type.clone(), json!({ "schema": schema }));
+    }
+    Value::Object(content)
+}
+
+fn error_schema(name: &str, problem_type: Option<&str>, problem_details: bool) -> Value {
+    let schema_ref = |name: &str| json!({ "$ref": format!("#/components/schemas/{name}") });
+    if !problem_details {
+        return schema_ref(name);
+    }
+    let name = if name == "ValidationErrorResponse" {
+        "ValidationProblemDetails"
+    } else {
+        "ProblemDetails"
+    };
+    match problem_type {
+        Some(problem_type) => json!({
+            "allOf": [
+                schema_ref(name),
+                { "type": "object", "properties": { "type": { "const": problem_type } } }
+            ]
+        }),
+        None => schema_ref(name),
+    }
+}
+
 /// Build an OpenAPI 3.1.0 JSON spec from config and route metadata.
 pub fn build_spec(config: &OpenApiConfig, routes: &[RouteInfo]) -> Value {
     // Surface schema gaps once, at boot (build_spec runs during plugin install),
@@ -243,7 +341,29 @@ pub fn build_spec(config: &OpenApiConfig, routes: &[RouteInfo]) -> Value {
             "{message}"
         );
     }
+    spec_document(config, routes)
+}
+
+/// The API versions of the versioned controllers in `routes`, ascending.
+pub fn api_versions(routes: &[RouteInfo]) -> Vec<u32> {
+    let mut versions: Vec<u32> = routes.iter().filter_map(RouteInfo::api_version).collect();
+    versions.sort_unstable();
+    versions.dedup();
+    versions
+}
+
+/// Build the OpenAPI spec of one API version: its `/v{n}` routes and every
+/// unversioned route.
+pub fn build_version_spec(config: &OpenApiConfig, routes: &[RouteInfo], version: u32) -> Value {
+    let routes: Vec<RouteInfo> = routes
+        .iter()
+        .filter(|route| route.api_version().is_none_or(|v| v == version))
+        .cloned()
+        .collect();
+    spec_document(config, &routes)
+}
 
+fn spec_document(config: &OpenApiConfig, routes: &[RouteInfo]) -> Value {
     let mut paths: Map<String, Value> = Map::new();
 
     for route in routes {
@@ -270,13 +390,27 @@ pub fn build_spec(config: &OpenApiConfig, routes: &[RouteInfo]) -> Value {
                     ParamLocation::Path => "path",
                     ParamLocation::Query => "query",
                     ParamLocation::Header => "header",
+                    ParamLocation::Cookie => "cookie",
                 };
-                json!({
+                let mut param = json!({
                     "name": p.name,
                     "in": location,
                     "required": p.required,
-                    "schema": { "type": p.param_type }
-                })
+                    "schema": param_schema(p),
+                });
+                if let Some(ref description) = p.description {
+                    param["description"] = json!(description);
+                }
+                if let Some(ref example) = p.example {
+                    param["example"] = example.clone();
+                }
+                if let Some(ref style) = p.style {
+                    param["style"] = json!(style);
+                }
+                if let Some(explode) = p.explode {
+                    param["explode"] = json!(explode);
+                }
+                param
             })
             .collect();
 
@@ -295,9 +429,9 @@ pub fn build_spec(config: &OpenApiConfig, routes: &[RouteInfo]) -> Value {
         }
 
         // Request body. The media type defaults to application/json; multipart
-        // routes carry an explicit request_body_content_type. A content type
-        // without a named body type (raw Multipart) is modeled as a free-form
-        // object.
+        // routes carry an explicit request_body_content_type and `#[consumes]`
+        // routes list theirs. A content type without a named body type (raw
+        // Multipart) is modeled as a free-form object.
         let body_schema = match (&route.request_body_type, &route.request_body_content_type) {
             (Some(body_type), _) => {
                 Some(json!({ "$ref": format!("#/components/schemas/{body_type}") }))
@@ -314,7 +448,7 @@ pub fn build_spec(config: &OpenApiConfig, routes: &[RouteInfo]) -> Value {
                 "requestBody".into(),
                 json!({
                     "required": route.request_body_required,
-                    "content": { content_type: { "schema": schema } }
+                    "content": media_type_map(&route.consumes, content_type, &schema)
                 }),
             );
         }
@@ -332,74 +466,89 @@ pub fn build_spec(config: &OpenApiConfig, routes: &[RouteInfo]) -> Value {
             // 204 No Content — no response body
             responses.insert(status_key, json!({ "description": status_desc }));
         } else if let Some(ref resp_type) = route.response_type {
+            let media_type = route
+                .response_content_type
+                .as_deref()
+                .unwrap_or("application/json");
+            let schema = json!({ "$ref": format!("#/components/schemas/{resp_type}") });
             responses.insert(
                 status_key,
                 json!({
                     "description": status_desc,
-                    "content": {
-                        "application/json": {
-                            "schema": { "$ref": format!("#/components/schemas/{resp_type}") }
-                        }
-                    }
+                    "content": media_type_map(&route.produces, media_type, &schema)
+                }),
+            );
+        } else if !route.produces.is_empty() {
+            // `#[produces]` on a hand-built response: media types, no schema
+            responses.insert(
+                status_key,
+                json!({
+                    "description": status_desc,
+                    "content": media_type_map(&route.produces, "", &json!({}))
+                }),
+            );
+        } else if let Some(ref media_type) = route.response_content_type {
+            // A typed media type without a schema (e.g. an SSE stream)
+            responses.insert(
+                status_key,
+                json!({
+                    "description": status_desc,
+                    "content": { media_type.as_str(): { "schema": { "type": "string" } } }
                 }),
             );
         } else {
             responses.insert(status_key, json!({ "description": status_desc }));
         }
 
+        let generic_error =
+            |error: ErrorResponseInfo| error_response_object(&[&error], config.problem_details);
+
         // Conditional 401/403 only when route has auth
         if route.has_auth {
             responses.insert(
                 "401".into(),
-                json!({
-                    "description": "Unauthorized",
-                    "content": {
-                        "application/json": {
-                            "schema": { "$ref": "#/components/schemas/ErrorResponse" }
-                        }
-                    }
-                }),
+                generic_error(ErrorResponseInfo::new(401, "Unauthorized")),
             );
             responses.insert(
                 "403".into(),
-                json!({
-                    "description": "Forbidden",
-                    "content": {
-                        "application/json": {
-                            "schema": { "$ref": "#/components/schemas/ErrorResponse" }
-                        }
-                    }
-                }),
+                generic_error(ErrorResponseInfo::new(403, "Forbidden")),
             );
         }
 
         // Default 500 response
         responses.insert(
             "500".into(),
-            json!({
-                "description": "Internal server error",
-                "content": {
-                    "application/json": {
-                        "schema": { "$ref": "#/components/schemas/ErrorResponse" }
-                    }
-                }
-            }),
+            generic_error(ErrorResponseInfo::new(500, "Internal server error")),
         );
 
         // If route has a request body, it may return 400
         if route.request_body_type.is_some() || route.request_body_content_type.is_some() {
             responses.entry("400".to_string()).or_insert_with(|| {
-                json!({
-                    "description": "Bad request / Validation error",
-                    "content": {
-                        "application/json": {
-                            "schema": { "$ref": "#/components/schemas/ValidationErrorResponse" }
-                        }
-                    }
-                })
+                generic_error(ErrorResponseInfo::validation(
+                    400,
+                    "Bad request / Validation error",
+                ))
             });
         }
 
+        // Declared error responses (ApiError variants, rate limits) replace the
+        // generic entries above; variants sharing a status are merged.
+        let mut declared: BTreeMap<u16, Vec<&ErrorResponseInfo>> = BTreeMap::new();
+        for error in &route.error_responses {
+            if error.status != route.response_status {
+                declared.entry(error.status).or_default().push(error);
+            }
+        }
+        for (status, errors) in declared {
+            let response = if status == 304 {
+                // `#[etag]`: Not Modified has no body.
+                json!({ "description": errors[0].description })
+            } else {
+                error_response_object(&errors, config.problem_details)
+            };
+            responses.insert(status.to_string(), response);
+        }
+
         operation.insert("responses".into(), Value::Object(responses));
 
         // Security
@@ -407,6 +556,11 @@ pub fn build_spec(config: &OpenApiConfig, routes: &[RouteInfo]) -> Value {
             operation.insert("security".into(), json!([{ "bearerAuth": route.roles }]));
         }
 
+        // Vendor extensions (e.g. `x-r2e-timeout-ms` from route limits)
+        for (key, value) in &route.extensions {
+            operation.insert(key.clone(), value.clone());
+        }
+
         let path_entry = paths.entry(axum_path).or_insert_with(|| json!({}));
 
         if let Some(obj) = path_entry.as_object_mut() {
@@ -455,6 +609,15 @@ pub fn build_spec(config: &OpenApiConfig, routes: &[RouteInfo]) -> Value {
                 );
             }
         }
+
+        // Parameter schemas are inlined; only their `$defs` become components.
+        for param in &route.params {
+            if let Some(Value::Object(defs)) = param.schema.as_ref().and_then(|s| s.get("$defs")) {
+                for (def_name, def_schema) in defs {
+                    extra_definitions.push((def_name.clone(), def_schema.clone()));
+                }
+            }
+        }
     }
 
     // Merge extra schemas from registry (route schemas take precedence).
@@ -517,6 +680,51 @@ pub fn build_spec(config: &OpenApiConfig, routes: &[RouteInfo]) -> Value {
                 "required": ["error", "details"]
             })
         });
+    if config.problem_details {
+        schemas
+            .entry("ProblemDetails".to_string())
+            .or_insert_with(|| {
+                json!({
+                    "type": "object",
+                    "description": "RFC 9457 Problem Details",
+                    "properties": {
+                        "type": {
+                            "type": "string",
+                            "format": "uri-reference",
+                            "description": "Problem type URI (`about:blank` when unspecified)"
+                        },
+                        "title": { "type": "string" },
+                        "status": { "type": "integer" },
+                        "detail": { "type": "string" },
+                        "instance": {
+                            "type": "string",
+                            "description": "Request id of the failed request"
+                        }
+                    },
+                    "required": ["type", "title", "status"],
+                    "additionalProperties": true
+                })
+            });
+        schemas
+            .entry("ValidationProblemDetails".to_string())
+            .or_insert_with(|| {
+                json!({
+                    "allOf": [
+                        { "$ref": "#/components/schemas/ProblemDetails" },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "errors": {
+                                    "type": "array",
+                                    "items": { "$ref": "#/components/schemas/FieldError" }
+                                }
+                            },
+                            "required": ["errors"]
+                        }
+                    ]
+                })
+            });
+    }
     schemas.entry("FieldError".to_string()).or_insert_with(|| {
         json!({
             "type": "object",
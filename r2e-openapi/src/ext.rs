@@ -1,7 +1,11 @@
-use crate::{openapi_routes, OpenApiConfig};
+use crate::{build_spec, openapi_routes, OpenApiConfig};
 use r2e_core::meta::RouteInfo;
 use r2e_core::Plugin;
 
+/// When set, the plugin writes the spec to this path and exits the process
+/// instead of serving. Used by `r2e generate client`.
+pub const EXPORT_ENV: &str = "R2E_OPENAPI_EXPORT";
+
 /// Plugin that adds OpenAPI spec generation and optional documentation UI.
 ///
 /// # Example
@@ -34,6 +38,29 @@ impl Plugin for OpenApiPlugin {
         app: r2e_core::AppBuilder<T>,
     ) -> r2e_core::AppBuilder<T> {
         let config = self.config;
-        app.with_meta_consumer::<RouteInfo, _>(move |routes| openapi_routes::<T>(config, routes))
+        app.with_meta_consumer::<RouteInfo, _>(move |routes| {
+            if let Ok(path) = std::env::var(EXPORT_ENV) {
+                export_and_exit(&config, routes, &path);
+            }
+            openapi_routes::<T>(config, routes)
+        })
+    }
+}
+
+/// Write the spec to `path` and exit: the route registry is complete once
+/// meta consumers run, so no listener is ever bound.
+fn export_and_exit(config: &OpenApiConfig, routes: &[RouteInfo], path: &str) -> ! {
+    let spec = build_spec(config, routes);
+    let json = serde_json::to_string_pretty(&spec)
+        .expect("OpenAPI spec is a serde_json::Value and serializes infallibly");
+    match std::fs::write(path, json) {
+        Ok(()) => {
+            tracing::info!(path, "OpenAPI spec exported");
+            std::process::exit(0)
+        }
+        Err(err) => {
+            tracing::error!(path, error = %err, "failed to export the OpenAPI spec");
+            std::process::exit(1)
+        }
     }
 }
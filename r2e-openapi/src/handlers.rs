@@ -4,7 +4,7 @@ use r2e_core::http::Router;
 use r2e_core::meta::RouteInfo;
 use std::sync::Arc;
 
-use crate::builder::{build_spec, OpenApiConfig};
+use crate::builder::{api_versions, build_spec, build_version_spec, OpenApiConfig};
 
 const WTI_CSS: &str = include_str!("../assets/wti-element.css");
 const WTI_JS: &str = include_str!("../assets/wti-element.iife.js");
@@ -15,6 +15,9 @@ struct OpenApiState {
 
 /// Build an `axum::Router` that serves `/openapi.json` and optionally `/docs`.
 ///
+/// With versioned controllers, `/openapi/v{n}.json` also serves the spec of
+/// each API version.
+///
 /// The returned router can be passed to `AppBuilder::register_routes()`.
 pub fn openapi_routes<T: Clone + Send + Sync + 'static>(
     config: OpenApiConfig,
@@ -36,6 +39,19 @@ pub fn openapi_routes<T: Clone + Send + Sync + 'static>(
         }),
     );
 
+    for version in api_versions(routes) {
+        let spec = build_version_spec(&config, routes, version);
+        let json = serde_json::to_string_pretty(&spec)
+            .expect("OpenAPI spec is a serde_json::Value and serializes infallibly");
+        router = router.route(
+            &format!("/openapi/v{version}.json"),
+            get(move || {
+                let json = json.clone();
+                async move { ([("content-type", "application/json")], json).into_response() }
+            }),
+        );
+    }
+
     if docs_ui {
         let state_for_ui = state.clone();
         router = router
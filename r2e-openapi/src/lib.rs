@@ -56,8 +56,11 @@ mod ext;
 mod handlers;
 pub mod schema;
 
-pub use builder::{build_spec, spec_warnings, OpenApiConfig, SchemaGap, SpecWarning};
-pub use ext::OpenApiPlugin;
+pub use builder::{
+    api_versions, build_spec, build_version_spec, spec_warnings, OpenApiConfig, SchemaGap,
+    SpecWarning,
+};
+pub use ext::{OpenApiPlugin, EXPORT_ENV};
 pub use handlers::openapi_routes;
 pub use schema::{SchemaProvider, SchemaRegistry};
 pub use schemars;
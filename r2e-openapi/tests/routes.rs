@@ -20,15 +20,20 @@ fn simple_route(method: &str, path: &str, op: &str) -> RouteInfo {
         request_body_schema: None,
         request_body_content_type: None,
         request_body_required: true,
+        consumes: Vec::new(),
         response_type: None,
         response_schema: None,
+        response_content_type: None,
+        produces: Vec::new(),
         response_status: 200,
         response_unmapped: None,
+        error_responses: Vec::new(),
         params: vec![],
         roles: vec![],
         tag: None,
         deprecated: false,
         has_auth: false,
+        extensions: Default::default(),
     }
 }
 
@@ -176,3 +181,36 @@ async fn openapi_json_always_served_regardless_of_docs_ui() {
     let spec: Value = serde_json::from_str(&body).unwrap();
     assert_eq!(spec["openapi"], "3.1.0");
 }
+
+#[r2e_core::test]
+async fn each_api_version_has_its_own_spec() {
+    let versioned = |version: u32, path: &str| {
+        let mut route = simple_route("GET", path, &format!("get_user_v{version}"));
+        route.extensions.insert(
+            r2e_core::meta::API_VERSION_EXTENSION.to_string(),
+            version.into(),
+        );
+        route
+    };
+    let routes = vec![
+        versioned(1, "/v1/users/{id}"),
+        versioned(2, "/v2/users/{id}"),
+        simple_route("GET", "/health", "health"),
+    ];
+    let router = openapi_routes::<()>(config_without_ui(), &routes);
+
+    let (status, body, _) = get_response(router.clone(), "/openapi/v2.json").await;
+    assert_eq!(status, http::StatusCode::OK);
+    let spec: Value = serde_json::from_str(&body).unwrap();
+    let paths = spec["paths"].as_object().unwrap();
+    assert!(paths.contains_key("/v2/users/{id}"));
+    assert!(paths.contains_key("/health"));
+    assert!(!paths.contains_key("/v1/users/{id}"));
+
+    let (_, body, _) = get_response(router.clone(), "/openapi.json").await;
+    let spec: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(spec["paths"].as_object().unwrap().len(), 3);
+
+    let (status, _, _) = get_response(router, "/openapi/v3.json").await;
+    assert_eq!(status, http::StatusCode::NOT_FOUND);
+}
@@ -1,4 +1,4 @@
-use r2e_core::meta::{ParamInfo, ParamLocation, RouteInfo};
+use r2e_core::meta::{ErrorResponseInfo, ParamInfo, ParamLocation, RouteInfo};
 use r2e_openapi::{build_spec, OpenApiConfig};
 use serde_json::{json, Value};
 
@@ -19,15 +19,20 @@ fn route(method: &str, path: &str, operation_id: &str) -> RouteInfo {
         request_body_schema: None,
         request_body_content_type: None,
         request_body_required: true,
+        consumes: Vec::new(),
         response_type: None,
         response_schema: None,
+        response_content_type: None,
+        produces: Vec::new(),
         response_status: 200,
         response_unmapped: None,
+        error_responses: Vec::new(),
         params: vec![],
         roles: vec![],
         tag: None,
         deprecated: false,
         has_auth: false,
+        extensions: Default::default(),
     }
 }
 
@@ -88,6 +93,11 @@ fn route_with_path_param() {
             location: ParamLocation::Path,
             param_type: "integer".to_string(),
             required: true,
+            schema: None,
+            description: None,
+            example: None,
+            style: None,
+            explode: None,
         }],
         ..route("GET", "/users/{id}", "get_user")
     }];
@@ -111,6 +121,11 @@ fn route_with_query_param() {
             location: ParamLocation::Query,
             param_type: "integer".to_string(),
             required: false,
+            schema: None,
+            description: None,
+            example: None,
+            style: None,
+            explode: None,
         }],
         ..route("GET", "/users", "list_users")
     }];
@@ -124,6 +139,178 @@ fn route_with_query_param() {
     assert_eq!(params[0]["required"], false);
 }
 
+#[test]
+fn param_schema_overrides_type() {
+    let routes = vec![RouteInfo {
+        params: vec![ParamInfo {
+            name: "sort".to_string(),
+            location: ParamLocation::Query,
+            param_type: "array".to_string(),
+            required: false,
+            schema: Some(json!({
+                "type": "array",
+                "items": { "type": "string", "enum": ["name", "-name"] },
+            })),
+            description: None,
+            example: None,
+            style: None,
+            explode: None,
+        }],
+        ..route("GET", "/users", "list_users")
+    }];
+    let spec = build_spec(&default_config(), &routes);
+
+    let schema = &spec["paths"]["/users"]["get"]["parameters"][0]["schema"];
+    assert_eq!(schema["items"]["enum"], json!(["name", "-name"]));
+}
+
+#[test]
+fn param_metadata_is_rendered() {
+    let routes = vec![RouteInfo {
+        params: vec![
+            ParamInfo {
+                name: "tag".to_string(),
+                location: ParamLocation::Query,
+                param_type: "array".to_string(),
+                required: false,
+                schema: Some(json!({
+                    "$schema": "https://json-schema.org/draft/2020-12/schema",
+                    "type": "array",
+                    "items": { "$ref": "#/$defs/Tag" },
+                    "$defs": { "Tag": { "type": "string", "enum": ["a", "b"] } },
+                })),
+                description: Some("Tags to match".to_string()),
+                example: Some(json!(["a"])),
+                style: Some("form".to_string()),
+                explode: Some(false),
+            },
+            ParamInfo {
+                name: "sid".to_string(),
+                location: ParamLocation::Cookie,
+                param_type: "string".to_string(),
+                required: true,
+                schema: None,
+                description: None,
+                example: None,
+                style: None,
+                explode: None,
+            },
+        ],
+        ..route("GET", "/items", "list_items")
+    }];
+    let spec = build_spec(&default_config(), &routes);
+
+    let params = &spec["paths"]["/items"]["get"]["parameters"];
+    assert_eq!(
+        params[0],
+        json!({
+            "name": "tag",
+            "in": "query",
+            "required": false,
+            "description": "Tags to match",
+            "example": ["a"],
+            "style": "form",
+            "explode": false,
+            "schema": {
+                "type": "array",
+                "items": { "$ref": "#/components/schemas/Tag" },
+            },
+        })
+    );
+    assert_eq!(params[1]["in"], "cookie");
+    assert!(params[1].get("description").is_none());
+    assert_eq!(
+        spec["components"]["schemas"]["Tag"]["enum"],
+        json!(["a", "b"])
+    );
+}
+
+#[derive(schemars::JsonSchema, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[allow(dead_code)]
+enum Status {
+    Active,
+    Banned,
+}
+
+impl std::str::FromStr for Status {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "active" => Ok(Status::Active),
+            "banned" => Ok(Status::Banned),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(r2e_core::prelude::Params, garde::Validate)]
+#[allow(dead_code)]
+struct UserSearch {
+    /// Name prefix.
+    #[query]
+    #[garde(length(min = 2, max = 32), pattern(r"^[a-z]+$"))]
+    name: String,
+    #[query]
+    #[garde(range(min = 1, max = 100))]
+    limit: Option<u32>,
+    #[query]
+    #[garde(skip)]
+    status: Option<Status>,
+}
+
+#[test]
+fn params_derive_documents_schemas_and_constraints() {
+    use r2e_core::params::ParamsMetadata;
+
+    let infos = UserSearch::param_infos();
+    assert_eq!(infos[0].description.as_deref(), Some("Name prefix."));
+    assert_eq!(
+        infos[0].schema,
+        Some(json!({
+            "type": "string",
+            "minLength": 2,
+            "maxLength": 32,
+            "pattern": "^[a-z]+$",
+        }))
+    );
+
+    let limit = infos[1].schema.as_ref().unwrap();
+    assert_eq!(
+        (&limit["minimum"], &limit["maximum"]),
+        (&json!(1), &json!(100))
+    );
+    assert_eq!(limit["format"], "uint32");
+
+    let status = infos[2].schema.as_ref().unwrap();
+    assert_eq!(status["enum"], json!(["active", "banned"]));
+    assert!(status.get("title").is_none());
+}
+
+#[derive(schemars::JsonSchema)]
+#[allow(dead_code)]
+struct PageQuery {
+    /// Page number.
+    page: u32,
+    per_page: Option<u32>,
+}
+
+#[test]
+fn query_schema_properties_become_parameters() {
+    let schema = serde_json::to_value(schemars::schema_for!(PageQuery)).unwrap();
+    let infos = r2e_core::params::query_param_infos(&schema);
+
+    assert_eq!(infos.len(), 2);
+    assert_eq!(infos[0].name, "page");
+    assert!(infos[0].required);
+    assert_eq!(infos[0].description.as_deref(), Some("Page number."));
+    assert_eq!(infos[0].param_type, "integer");
+    assert_eq!(infos[1].name, "per_page");
+    assert!(!infos[1].required);
+    assert_eq!(infos[1].param_type, "integer");
+}
+
 #[test]
 fn route_with_request_body() {
     let routes = vec![RouteInfo {
@@ -417,6 +604,11 @@ fn generated_spec_is_valid_openapi_structure() {
                 location: ParamLocation::Path,
                 param_type: "integer".to_string(),
                 required: true,
+                schema: None,
+                description: None,
+                example: None,
+                style: None,
+                explode: None,
             }],
             ..route("GET", "/users/{id}", "get_user")
         },
@@ -515,6 +707,11 @@ fn header_param_location() {
             location: ParamLocation::Header,
             param_type: "string".to_string(),
             required: true,
+            schema: None,
+            description: None,
+            example: None,
+            style: None,
+            explode: None,
         }],
         ..route("GET", "/data", "get_data")
     }];
@@ -553,6 +750,7 @@ fn response_schema_in_spec() {
     let routes = vec![RouteInfo {
         response_type: Some("User".to_string()),
         response_schema: Some(schema.clone()),
+        response_content_type: None,
         ..route("GET", "/users/{id}", "get_user")
     }];
     let spec = build_spec(&default_config(), &routes);
@@ -583,6 +781,92 @@ fn response_204_has_no_content_block() {
     assert!(resp.get("content").is_none());
 }
 
+#[test]
+fn response_content_type_is_documented() {
+    let routes = vec![RouteInfo {
+        response_content_type: Some("text/event-stream".to_string()),
+        ..route("GET", "/events", "events")
+    }];
+    let spec = build_spec(&default_config(), &routes);
+
+    let content = &spec["paths"]["/events"]["get"]["responses"]["200"]["content"];
+    assert_eq!(content["text/event-stream"]["schema"]["type"], "string");
+    assert!(content.get("application/json").is_none());
+}
+
+#[test]
+fn produces_and_consumes_list_every_media_type() {
+    let routes = vec![RouteInfo {
+        request_body_type: Some("Report".to_string()),
+        response_type: Some("Report".to_string()),
+        response_schema: Some(json!({"type": "object"})),
+        consumes: vec![
+            "application/json".to_string(),
+            "application/x-www-form-urlencoded".to_string(),
+        ],
+        produces: vec!["application/json".to_string(), "text/csv".to_string()],
+        ..route("POST", "/reports", "create_report")
+    }];
+    let spec = build_spec(&default_config(), &routes);
+
+    let op = &spec["paths"]["/reports"]["post"];
+    let request = &op["requestBody"]["content"];
+    assert_eq!(
+        request["application/json"]["schema"]["$ref"],
+        "#/components/schemas/Report"
+    );
+    assert_eq!(
+        request["application/x-www-form-urlencoded"]["schema"]["$ref"],
+        "#/components/schemas/Report"
+    );
+
+    let response = &op["responses"]["200"]["content"];
+    assert_eq!(
+        response["application/json"]["schema"]["$ref"],
+        "#/components/schemas/Report"
+    );
+    assert_eq!(
+        response["text/csv"]["schema"]["$ref"],
+        "#/components/schemas/Report"
+    );
+}
+
+#[test]
+fn etag_routes_document_conditional_responses() {
+    let routes = vec![
+        RouteInfo {
+            error_responses: r2e_core::conditional::error_responses("GET"),
+            params: r2e_core::conditional::params("GET"),
+            ..route("GET", "/docs/{id}", "get_doc")
+        },
+        RouteInfo {
+            error_responses: r2e_core::conditional::error_responses("PUT"),
+            params: r2e_core::conditional::params("PUT"),
+            ..route("PUT", "/docs/{id}", "update_doc")
+        },
+    ];
+    let spec = build_spec(&default_config(), &routes);
+
+    let get = &spec["paths"]["/docs/{id}"]["get"];
+    assert_eq!(
+        get["responses"]["304"],
+        json!({ "description": "Not modified" })
+    );
+    assert_eq!(get["parameters"][0]["name"], "If-None-Match");
+    assert_eq!(get["parameters"][0]["in"], "header");
+
+    let put = &spec["paths"]["/docs/{id}"]["put"];
+    assert_eq!(
+        put["responses"]["412"]["description"],
+        "Precondition failed"
+    );
+    assert_eq!(
+        put["responses"]["428"]["description"],
+        "Precondition required"
+    );
+    assert_eq!(put["parameters"][0]["name"], "If-Match");
+}
+
 #[test]
 fn post_defaults_to_201() {
     let routes = vec![RouteInfo {
@@ -590,6 +874,7 @@ fn post_defaults_to_201() {
         response_unmapped: None,
         response_type: Some("User".to_string()),
         response_schema: Some(json!({"type": "object"})),
+        response_content_type: None,
         ..route("POST", "/users", "create_user")
     }];
     let spec = build_spec(&default_config(), &routes);
@@ -642,6 +927,99 @@ fn auth_route_has_401_403() {
     assert_eq!(responses["403"]["description"], "Forbidden");
 }
 
+#[test]
+fn declared_error_responses_are_documented() {
+    let routes = vec![RouteInfo {
+        has_auth: true,
+        error_responses: vec![
+            ErrorResponseInfo::new(404, "User not found"),
+            ErrorResponseInfo::new(409, "Email taken"),
+            ErrorResponseInfo::new(409, "Username taken"),
+            ErrorResponseInfo::validation(422, "Invalid user"),
+            ErrorResponseInfo::new(422, "Unprocessable"),
+            ErrorResponseInfo::new(429, "Too many requests"),
+        ],
+        ..route("POST", "/users", "create_user")
+    }];
+    let spec = build_spec(&default_config(), &routes);
+
+    let responses = &spec["paths"]["/users"]["post"]["responses"];
+    assert_eq!(responses["404"]["description"], "User not found");
+    assert_eq!(
+        responses["404"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/ErrorResponse"
+    );
+    assert_eq!(
+        responses["409"]["description"],
+        "Email taken / Username taken"
+    );
+    assert_eq!(
+        responses["422"]["content"]["application/json"]["schema"]["oneOf"],
+        json!([
+            { "$ref": "#/components/schemas/ValidationErrorResponse" },
+            { "$ref": "#/components/schemas/ErrorResponse" }
+        ])
+    );
+    assert_eq!(responses["429"]["description"], "Too many requests");
+    assert_eq!(responses["401"]["description"], "Unauthorized");
+    assert!(responses["500"].is_object());
+}
+
+#[test]
+fn problem_details_mode_documents_problem_json() {
+    let routes = vec![RouteInfo {
+        request_body_type: Some("CreateUser".to_string()),
+        error_responses: vec![
+            ErrorResponseInfo::new(404, "User not found")
+                .with_problem_type("https://errors.example.com/user-not-found"),
+            ErrorResponseInfo::validation(422, "Invalid user"),
+        ],
+        ..route("POST", "/users", "create_user")
+    }];
+    let config = default_config().with_problem_details(true);
+    let spec = build_spec(&config, &routes);
+
+    let responses = &spec["paths"]["/users"]["post"]["responses"];
+    assert_eq!(
+        responses["404"]["content"]["application/problem+json"]["schema"],
+        json!({
+            "allOf": [
+                { "$ref": "#/components/schemas/ProblemDetails" },
+                {
+                    "type": "object",
+                    "properties": {
+                        "type": { "const": "https://errors.example.com/user-not-found" }
+                    }
+                }
+            ]
+        })
+    );
+    assert_eq!(
+        responses["422"]["content"]["application/problem+json"]["schema"]["$ref"],
+        "#/components/schemas/ValidationProblemDetails"
+    );
+    assert_eq!(
+        responses["400"]["content"]["application/problem+json"]["schema"]["$ref"],
+        "#/components/schemas/ValidationProblemDetails"
+    );
+    assert!(responses["500"]["content"]["application/json"].is_null());
+
+    let schemas = &spec["components"]["schemas"];
+    assert_eq!(
+        schemas["ProblemDetails"]["required"],
+        json!(["type", "title", "status"])
+    );
+    assert!(schemas["ValidationProblemDetails"].is_object());
+}
+
+#[test]
+fn problem_details_schemas_absent_by_default() {
+    let spec = build_spec(&default_config(), &[route("GET", "/users", "list_users")]);
+    assert!(spec["components"]["schemas"]
+        .get("ProblemDetails")
+        .is_none());
+}
+
 #[test]
 fn deprecated_flag_in_spec() {
     let routes = vec![RouteInfo {
@@ -661,6 +1039,29 @@ fn non_deprecated_has_no_deprecated_key() {
     assert!(spec["paths"]["/users"]["get"].get("deprecated").is_none());
 }
 
+#[test]
+fn route_limits_render_as_extensions_and_error_responses() {
+    let limits = r2e_core::route_limits::RouteLimits {
+        timeout: Some(std::time::Duration::from_secs(5)),
+        body_limit: Some(r2e_core::route_limits::BodyLimit::Max(50 << 20)),
+        max_concurrent: Some(10),
+    };
+    let routes = vec![RouteInfo {
+        extensions: limits.openapi_extensions(),
+        error_responses: limits.error_responses(),
+        ..route("POST", "/uploads", "upload")
+    }];
+    let spec = build_spec(&default_config(), &routes);
+
+    let op = &spec["paths"]["/uploads"]["post"];
+    assert_eq!(op["x-r2e-timeout-ms"], json!(5000));
+    assert_eq!(op["x-r2e-body-limit"], json!(52_428_800));
+    assert_eq!(op["x-r2e-max-concurrent"], json!(10));
+    for status in ["408", "413", "503"] {
+        assert!(op["responses"][status].is_object(), "missing {status}");
+    }
+}
+
 #[test]
 fn optional_request_body() {
     let routes = vec![RouteInfo {
@@ -707,6 +1108,7 @@ fn response_schema_ref_rewrite() {
     let routes = vec![RouteInfo {
         response_type: Some("User".to_string()),
         response_schema: Some(schema),
+        response_content_type: None,
         ..route("GET", "/users/{id}", "get_user")
     }];
     let spec = build_spec(&default_config(), &routes);
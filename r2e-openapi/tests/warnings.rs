@@ -15,15 +15,20 @@ fn base(method: &str, path: &str) -> RouteInfo {
         request_body_schema: None,
         request_body_content_type: None,
         request_body_required: true,
+        consumes: Vec::new(),
         response_type: None,
         response_schema: None,
+        response_content_type: None,
+        produces: Vec::new(),
         response_status: 200,
         response_unmapped: None,
+        error_responses: Vec::new(),
         params: vec![],
         roles: vec![],
         tag: None,
         deprecated: false,
         has_auth: false,
+        extensions: Default::default(),
     }
 }
 
@@ -63,6 +68,7 @@ fn no_warning_when_response_body_is_mapped() {
     let routes = vec![RouteInfo {
         response_type: Some("User".to_string()),
         response_schema: Some(json!({ "type": "object" })),
+        response_content_type: None,
         ..base("GET", "/users")
     }];
     assert!(spec_warnings(&routes).is_empty());
@@ -101,6 +107,7 @@ fn warns_on_schemaless_response_type() {
     let routes = vec![RouteInfo {
         response_type: Some("User".to_string()),
         response_schema: None,
+        response_content_type: None,
         ..base("GET", "/users")
     }];
 
@@ -0,0 +1,33 @@
+//! Readiness indicator: DOWN while the limiter is saturated.
+
+use r2e_core::health::{HealthIndicator, HealthStatus};
+
+use crate::limiter::AdaptiveLimiter;
+
+pub(crate) struct OverloadHealth {
+    limiter: AdaptiveLimiter,
+}
+
+impl OverloadHealth {
+    pub(crate) fn new(limiter: AdaptiveLimiter) -> Self {
+        Self { limiter }
+    }
+}
+
+impl HealthIndicator for OverloadHealth {
+    fn name(&self) -> &str {
+        "overload"
+    }
+
+    async fn check(&self) -> HealthStatus {
+        if self.limiter.is_saturated() {
+            HealthStatus::Down(format!(
+                "shedding load: {} requests in flight, limit {}",
+                self.limiter.in_flight(),
+                self.limiter.limit()
+            ))
+        } else {
+            HealthStatus::Up
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! The shedding middleware: admits each request through the limiter before
+//! its handler runs, or answers `503` with `Retry-After`.
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+use r2e_core::http::extract::MatchedPath;
+use r2e_core::http::header::{HeaderValue, RETRY_AFTER};
+use r2e_core::http::middleware::Next;
+use r2e_core::http::{IntoResponse, Request, Response, StatusCode};
+use r2e_core::meta::{RouteInfo, PRIORITY_EXTENSION};
+use r2e_core::HttpError;
+
+use crate::limiter::{AdaptiveLimiter, Priority};
+
+/// `#[priority]` by `(METHOD, path template)`, read from [`RouteInfo`].
+pub(crate) type RoutePriorities = HashMap<(String, String), Priority>;
+
+/// Collect the routes that declare a priority.
+pub(crate) fn route_priorities(routes: &[RouteInfo]) -> RoutePriorities {
+    let mut priorities = RoutePriorities::new();
+    for route in routes {
+        let Some(value) = route.extensions.get(PRIORITY_EXTENSION) else {
+            continue;
+        };
+        match value.as_str().map(str::parse::<Priority>) {
+            Some(Ok(priority)) => {
+                priorities.insert((route.method.clone(), route.path.clone()), priority);
+            }
+            _ => tracing::warn!(
+                route = %route.operation_id,
+                value = %value,
+                "ignoring invalid route priority"
+            ),
+        }
+    }
+    priorities
+}
+
+#[derive(Clone)]
+pub(crate) struct Shedder {
+    limiter: AdaptiveLimiter,
+    /// Filled at `build()`, after the controllers registered their metadata.
+    priorities: Arc<OnceLock<RoutePriorities>>,
+    exclude_paths: Arc<[String]>,
+}
+
+impl Shedder {
+    pub(crate) fn new(
+        limiter: AdaptiveLimiter,
+        priorities: Arc<OnceLock<RoutePriorities>>,
+        exclude_paths: Vec<String>,
+    ) -> Self {
+        Self {
+            limiter,
+            priorities,
+            exclude_paths: exclude_paths.into(),
+        }
+    }
+
+    pub(crate) async fn handle(self, req: Request, next: Next) -> Response {
+        let path = req.uri().path();
+        if self
+            .exclude_paths
+            .iter()
+            .any(|prefix| is_under(path, prefix))
+        {
+            return next.run(req).await;
+        }
+
+        let priority = self.priority(&req);
+        let Some(permit) = self.limiter.try_acquire(priority) else {
+            return rejection(&self.limiter);
+        };
+        let response = next.run(req).await;
+        permit.record(matches!(
+            response.status(),
+            StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+        ));
+        response
+    }
+
+    fn priority(&self, req: &Request) -> Priority {
+        let (Some(priorities), Some(matched)) =
+            (self.priorities.get(), req.extensions().get::<MatchedPath>())
+        else {
+            return Priority::Normal;
+        };
+        let path = matched.as_str().to_string();
+        priorities
+            .get(&(req.method().as_str().to_string(), path.clone()))
+            .or_else(|| priorities.get(&("ANY".to_string(), path)))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Whether `path` is `prefix` or below it: `/health` covers `/health` and
+/// `/health/ready`, but not `/healthcare`.
+fn is_under(path: &str, prefix: &str) -> bool {
+    match path.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/') || prefix.ends_with('/'),
+        None => false,
+    }
+}
+
+fn rejection(limiter: &AdaptiveLimiter) -> Response {
+    let seconds = limiter.retry_after().as_secs_f64().ceil().max(1.0) as u64;
+    let mut response = HttpError::from_status(StatusCode::SERVICE_UNAVAILABLE, "Server overloaded")
+        .into_response();
+    response
+        .headers_mut()
+        .insert(RETRY_AFTER, HeaderValue::from(seconds));
+    response
+}
@@ -0,0 +1,330 @@
+//! Adaptive overload protection for R2E.
+//!
+//! The [`Overload`] plugin keeps an adaptive limit on concurrent requests —
+//! tuned from observed latency by AIMD or Gradient — and sheds the excess
+//! with `503 Service Unavailable` plus `Retry-After` before any handler runs.
+//! Routes opt into a shedding class with `#[priority]`:
+//!
+//! ```rust,ignore
+//! #[routes]
+//! impl OrderController {
+//!     #[post("/checkout")]
+//!     #[priority(critical)]   // shed last
+//!     async fn checkout(&self, body: Json<Order>) -> Json<Receipt> { ... }
+//!
+//!     #[get("/recommendations")]
+//!     #[priority(background)] // shed first
+//!     async fn recommendations(&self) -> Json<Vec<Product>> { ... }
+//! }
+//!
+//! AppBuilder::new()
+//!     .plugin(Overload::new())
+//!     .build_state()
+//!     .await
+//!     .with(Health::builder().build())
+//!     .register_controller::<OrderController>()
+//!     .serve("0.0.0.0:3000")
+//!     .await;
+//! ```
+//!
+//! Routes without `#[priority]` are `normal`. While the limiter is saturated
+//! the plugin's `overload` health check is DOWN, taking the instance out of
+//! `/health/ready`. With the `prometheus` feature the limit, in-flight count
+//! and per-priority rejections are exported as `overload_*` metrics.
+
+mod health;
+mod layer;
+mod limiter;
+#[cfg(feature = "prometheus")]
+mod metrics;
+
+pub use limiter::{AdaptiveLimiter, Algorithm, LimiterSettings, Permit, Priority};
+
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use r2e_core::http::middleware::from_fn;
+use r2e_core::meta::RouteInfo;
+use r2e_core::prelude::ConfigProperties;
+use r2e_core::{DeferredContext, PluginInstallContext, PreStatePlugin};
+
+/// Typed configuration for the [`Overload`] plugin, read from the
+/// `overload.*` YAML section.
+///
+/// ```yaml
+/// overload:
+///   algorithm: gradient          # or aimd
+///   initial_limit: 100
+///   min_limit: 10
+///   max_limit: 1000
+///   tolerance: 2.0               # gradient
+///   smoothing: 0.2               # gradient
+///   backoff_ratio: 0.9           # aimd
+///   latency_threshold: 1s        # aimd
+///   background_share: 0.5
+///   critical_reserve: 0.2
+///   retry_after: 1s
+///   exclude_paths: ["/health", "/metrics"]
+/// ```
+///
+/// Precedence for each knob: **programmatic builder setting > this file config >
+/// default**. `overload.enabled: false` turns shedding off.
+#[derive(ConfigProperties, Clone, Debug, Default)]
+pub struct OverloadConfig {
+    /// Limit algorithm: `gradient` (default) or `aimd`.
+    pub algorithm: Option<String>,
+    /// Limit before the first sample.
+    pub initial_limit: Option<usize>,
+    /// Floor of the adaptive limit.
+    pub min_limit: Option<usize>,
+    /// Ceiling of the adaptive limit.
+    pub max_limit: Option<usize>,
+    /// Gradient: latency growth over the baseline tolerated before shrinking.
+    pub tolerance: Option<f64>,
+    /// Gradient: weight of each new limit estimate.
+    pub smoothing: Option<f64>,
+    /// AIMD: factor applied to the limit on a slow or overloaded response.
+    pub backoff_ratio: Option<f64>,
+    /// AIMD: responses slower than this count as overload.
+    pub latency_threshold: Option<Duration>,
+    /// Fraction of the limit background routes may occupy.
+    pub background_share: Option<f64>,
+    /// Extra fraction of the limit reserved for critical routes.
+    pub critical_reserve: Option<f64>,
+    /// `Retry-After` sent with shed requests.
+    pub retry_after: Option<Duration>,
+    /// Request paths never counted or shed, each with everything below it.
+    pub exclude_paths: Option<Vec<String>>,
+}
+
+/// Overload protection plugin.
+///
+/// Installs as a [`PreStatePlugin`] providing the [`AdaptiveLimiter`] bean.
+/// After state resolution it adds the shedding layer, the `overload` health
+/// check (picked up by `Health::builder()`), a dev status section and — with
+/// the `prometheus` feature — the limiter metrics.
+///
+/// ```rust,ignore
+/// // Everything from `overload.*` config (or the defaults)
+/// .plugin(Overload::new())
+///
+/// // Programmatic settings win over config
+/// .plugin(Overload::builder()
+///     .algorithm(Algorithm::aimd())
+///     .max_limit(200)
+///     .retry_after(Duration::from_secs(5))
+///     .build())
+/// ```
+#[derive(Default)]
+pub struct Overload {
+    algorithm: Option<Algorithm>,
+    initial_limit: Option<usize>,
+    min_limit: Option<usize>,
+    max_limit: Option<usize>,
+    background_share: Option<f64>,
+    critical_reserve: Option<f64>,
+    retry_after: Option<Duration>,
+    exclude_paths: Option<Vec<String>>,
+}
+
+impl Overload {
+    /// Overload protection tuned by `overload.*` config and the defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a builder for programmatic settings.
+    pub fn builder() -> OverloadBuilder {
+        OverloadBuilder::default()
+    }
+
+    /// Merge builder settings, the file section and the defaults.
+    fn resolve(
+        &self,
+        file: Option<OverloadConfig>,
+    ) -> Result<(LimiterSettings, Vec<String>), String> {
+        let file = file.unwrap_or_default();
+        let defaults = LimiterSettings::default();
+
+        let algorithm = match self.algorithm {
+            Some(algorithm) => algorithm,
+            None => match file.algorithm.as_deref().unwrap_or("gradient") {
+                "gradient" => Algorithm::Gradient {
+                    tolerance: file.tolerance.unwrap_or(2.0),
+                    smoothing: file.smoothing.unwrap_or(0.2),
+                },
+                "aimd" => Algorithm::Aimd {
+                    backoff_ratio: file.backoff_ratio.unwrap_or(0.9),
+                    latency_threshold: file.latency_threshold.unwrap_or(Duration::from_secs(1)),
+                },
+                other => {
+                    return Err(format!(
+                        "algorithm: unknown algorithm '{other}' — expected gradient or aimd"
+                    ))
+                }
+            },
+        };
+
+        let min_limit = self
+            .min_limit
+            .or(file.min_limit)
+            .unwrap_or(defaults.min_limit);
+        let max_limit = self
+            .max_limit
+            .or(file.max_limit)
+            .unwrap_or(defaults.max_limit);
+        let settings = LimiterSettings {
+            algorithm,
+            initial_limit: self
+                .initial_limit
+                .or(file.initial_limit)
+                .unwrap_or_else(|| defaults.initial_limit.min(max_limit).max(min_limit)),
+            min_limit,
+            max_limit,
+            background_share: self
+                .background_share
+                .or(file.background_share)
+                .unwrap_or(defaults.background_share),
+            critical_reserve: self
+                .critical_reserve
+                .or(file.critical_reserve)
+                .unwrap_or(defaults.critical_reserve),
+            retry_after: self
+                .retry_after
+                .or(file.retry_after)
+                .unwrap_or(defaults.retry_after),
+        };
+        settings.validate()?;
+
+        let exclude_paths = self
+            .exclude_paths
+            .clone()
+            .or(file.exclude_paths)
+            .unwrap_or_else(|| vec!["/health".to_string(), "/metrics".to_string()]);
+        Ok((settings, exclude_paths))
+    }
+}
+
+impl PreStatePlugin for Overload {
+    type Provided = (AdaptiveLimiter,);
+    type Deps = ();
+    type Config = OverloadConfig;
+    const CONFIG_PREFIX: Option<&'static str> = Some("overload");
+
+    fn install(&mut self, _ctx: &mut PluginInstallContext<'_>) -> Self::Provided {
+        // The effective settings depend on file config, which is only
+        // guaranteed loaded in `configure`; the bean is reconfigured there.
+        (AdaptiveLimiter::new(LimiterSettings::default()),)
+    }
+
+    fn configure(
+        self,
+        (limiter,): &Self::Provided,
+        (): (),
+        config: Option<OverloadConfig>,
+        ctx: &mut DeferredContext<'_>,
+    ) {
+        let (settings, exclude_paths) = self
+            .resolve(config)
+            .unwrap_or_else(|error| panic!("invalid `overload` configuration: {error}"));
+        limiter.reconfigure(settings);
+
+        let priorities = Arc::new(OnceLock::new());
+        let table = priorities.clone();
+        ctx.observe_meta::<RouteInfo, _>(move |routes| {
+            let _ = table.set(layer::route_priorities(routes));
+        });
+
+        ctx.add_health_check(health::OverloadHealth::new(limiter.clone()));
+
+        let status = limiter.clone();
+        ctx.add_dev_status("overload", move || {
+            r2e_core::serde_json::json!({
+                "algorithm": status.settings().algorithm.name(),
+                "limit": status.limit(),
+                "in_flight": status.in_flight(),
+                "saturated": status.is_saturated(),
+                "rejected": Priority::ALL
+                    .iter()
+                    .map(|p| (p.as_str().to_string(), status.rejected(*p).into()))
+                    .collect::<r2e_core::serde_json::Map<_, _>>(),
+            })
+        });
+
+        #[cfg(feature = "prometheus")]
+        metrics::register(ctx, limiter);
+
+        let shedder = layer::Shedder::new(limiter.clone(), priorities, exclude_paths);
+        ctx.add_layer(Box::new(move |router| {
+            router.layer(from_fn(move |req, next| shedder.clone().handle(req, next)))
+        }));
+    }
+}
+
+/// Builder for programmatic [`Overload`] settings.
+#[derive(Default)]
+pub struct OverloadBuilder {
+    plugin: Overload,
+}
+
+impl OverloadBuilder {
+    /// Set the limit algorithm (default [`Algorithm::gradient`]).
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.plugin.algorithm = Some(algorithm);
+        self
+    }
+
+    /// Set the limit before the first sample (default `100`).
+    pub fn initial_limit(mut self, limit: usize) -> Self {
+        self.plugin.initial_limit = Some(limit);
+        self
+    }
+
+    /// Set the floor of the adaptive limit (default `10`).
+    pub fn min_limit(mut self, limit: usize) -> Self {
+        self.plugin.min_limit = Some(limit);
+        self
+    }
+
+    /// Set the ceiling of the adaptive limit (default `1000`).
+    pub fn max_limit(mut self, limit: usize) -> Self {
+        self.plugin.max_limit = Some(limit);
+        self
+    }
+
+    /// Set the fraction of the limit background routes may occupy
+    /// (default `0.5`).
+    pub fn background_share(mut self, share: f64) -> Self {
+        self.plugin.background_share = Some(share);
+        self
+    }
+
+    /// Set the extra fraction of the limit reserved for critical routes
+    /// (default `0.2`).
+    pub fn critical_reserve(mut self, reserve: f64) -> Self {
+        self.plugin.critical_reserve = Some(reserve);
+        self
+    }
+
+    /// Set the `Retry-After` sent with shed requests (default 1 s).
+    pub fn retry_after(mut self, retry_after: Duration) -> Self {
+        self.plugin.retry_after = Some(retry_after);
+        self
+    }
+
+    /// Never count or shed requests to this path or below it (`/health`
+    /// covers `/health/ready`, not `/healthcare`). Replaces the default
+    /// exclusions (`/health`, `/metrics`).
+    pub fn exclude_path(mut self, path: &str) -> Self {
+        self.plugin
+            .exclude_paths
+            .get_or_insert_with(Vec::new)
+            .push(path.to_string());
+        self
+    }
+
+    /// Build the plugin.
+    pub fn build(self) -> Overload {
+        self.plugin
+    }
+}
@@ -0,0 +1,434 @@
+//! The adaptive concurrency limit and its priority-aware admission.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Number of samples the Gradient baseline latency averages over.
+const BASELINE_WINDOW: f64 = 100.0;
+
+/// Load-shedding class of a route, set with `#[priority(...)]`.
+///
+/// `Background` traffic is shed first (once the in-flight count reaches
+/// [`LimiterSettings::background_share`] of the limit), `Normal` traffic at
+/// the limit, and `Critical` traffic only past the extra
+/// [`LimiterSettings::critical_reserve`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Priority {
+    Critical,
+    #[default]
+    Normal,
+    Background,
+}
+
+impl Priority {
+    /// Every priority, most important first.
+    pub const ALL: [Priority; 3] = [Priority::Critical, Priority::Normal, Priority::Background];
+
+    /// The attribute spelling: `"critical"`, `"normal"` or `"background"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Priority::Critical => "critical",
+            Priority::Normal => "normal",
+            Priority::Background => "background",
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Priority::Critical => 0,
+            Priority::Normal => 1,
+            Priority::Background => 2,
+        }
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Priority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "critical" => Ok(Priority::Critical),
+            "normal" => Ok(Priority::Normal),
+            "background" => Ok(Priority::Background),
+            other => Err(format!(
+                "unknown priority '{other}' — expected critical, normal or background"
+            )),
+        }
+    }
+}
+
+/// How the limit reacts to the latency of completed requests.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Algorithm {
+    /// Additive increase, multiplicative decrease: `+1` per fast response
+    /// while at least half the limit is in use, `× backoff_ratio` per response
+    /// slower than `latency_threshold` or answered with `503`/`504`.
+    Aimd {
+        backoff_ratio: f64,
+        latency_threshold: Duration,
+    },
+    /// Compares each latency against a long-running baseline: the limit
+    /// shrinks as latency climbs past `tolerance × baseline` and otherwise
+    /// grows by `√limit`, each step blended in by `smoothing`.
+    Gradient { tolerance: f64, smoothing: f64 },
+}
+
+impl Algorithm {
+    /// AIMD with a `0.9` backoff and a 1 s latency threshold.
+    pub fn aimd() -> Self {
+        Algorithm::Aimd {
+            backoff_ratio: 0.9,
+            latency_threshold: Duration::from_secs(1),
+        }
+    }
+
+    /// Gradient with a tolerance of `2.0` and `0.2` smoothing.
+    pub fn gradient() -> Self {
+        Algorithm::Gradient {
+            tolerance: 2.0,
+            smoothing: 0.2,
+        }
+    }
+
+    /// `"aimd"` or `"gradient"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Aimd { .. } => "aimd",
+            Algorithm::Gradient { .. } => "gradient",
+        }
+    }
+}
+
+/// Tuning of an [`AdaptiveLimiter`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LimiterSettings {
+    pub algorithm: Algorithm,
+    /// Limit before the first sample (default `100`).
+    pub initial_limit: usize,
+    /// Floor of the adaptive limit (default `10`).
+    pub min_limit: usize,
+    /// Ceiling of the adaptive limit (default `1000`).
+    pub max_limit: usize,
+    /// Fraction of the limit `Background` requests may occupy (default `0.5`).
+    pub background_share: f64,
+    /// Extra fraction of the limit reserved for `Critical` requests
+    /// (default `0.2`).
+    pub critical_reserve: f64,
+    /// Advertised in the `Retry-After` header of shed requests, and how long
+    /// the limiter reports itself saturated after shedding (default 1 s).
+    pub retry_after: Duration,
+}
+
+impl Default for LimiterSettings {
+    fn default() -> Self {
+        Self {
+            algorithm: Algorithm::gradient(),
+            initial_limit: 100,
+            min_limit: 10,
+            max_limit: 1000,
+            background_share: 0.5,
+            critical_reserve: 0.2,
+            retry_after: Duration::from_secs(1),
+        }
+    }
+}
+
+impl LimiterSettings {
+    /// Check the bounds and ratios, naming the offending setting.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.min_limit == 0 {
+            return Err("min_limit must be greater than zero".into());
+        }
+        if self.min_limit > self.max_limit {
+            return Err(format!(
+                "min_limit ({}) exceeds max_limit ({})",
+                self.min_limit, self.max_limit
+            ));
+        }
+        if !(self.min_limit..=self.max_limit).contains(&self.initial_limit) {
+            return Err(format!(
+                "initial_limit ({}) must lie between min_limit ({}) and max_limit ({})",
+                self.initial_limit, self.min_limit, self.max_limit
+            ));
+        }
+        if !(self.background_share > 0.0 && self.background_share <= 1.0) {
+            return Err("background_share must be in (0, 1]".into());
+        }
+        if !(0.0..).contains(&self.critical_reserve) {
+            return Err("critical_reserve must not be negative".into());
+        }
+        match self.algorithm {
+            Algorithm::Aimd { backoff_ratio, .. } => {
+                if !(backoff_ratio > 0.0 && backoff_ratio < 1.0) {
+                    return Err("backoff_ratio must be in (0, 1)".into());
+                }
+            }
+            Algorithm::Gradient {
+                tolerance,
+                smoothing,
+            } => {
+                if !(1.0..).contains(&tolerance) {
+                    return Err("tolerance must be at least 1.0".into());
+                }
+                if !(smoothing > 0.0 && smoothing <= 1.0) {
+                    return Err("smoothing must be in (0, 1]".into());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An adaptive in-flight request limit with priority-aware admission.
+///
+/// Clone is cheap (shared `Arc`). The [`Overload`](crate::Overload) plugin
+/// provides it as a bean, so services can read the current limit and
+/// rejection counts.
+#[derive(Clone)]
+pub struct AdaptiveLimiter {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    state: Mutex<State>,
+    rejected: [AtomicU64; 3],
+    epoch: Instant,
+    /// Milliseconds since `epoch` (plus one; `0` = never) of the last shed
+    /// `Normal` or `Critical` request.
+    last_shed: AtomicU64,
+}
+
+struct State {
+    settings: LimiterSettings,
+    limit: f64,
+    in_flight: usize,
+    /// Gradient's long-running average latency, in seconds.
+    baseline: Option<f64>,
+}
+
+impl State {
+    fn new(settings: LimiterSettings) -> Self {
+        Self {
+            limit: settings.initial_limit as f64,
+            settings,
+            in_flight: 0,
+            baseline: None,
+        }
+    }
+
+    fn limit(&self) -> usize {
+        (self.limit.round() as usize).max(1)
+    }
+
+    fn capacity(&self, priority: Priority) -> usize {
+        let limit = self.limit();
+        match priority {
+            Priority::Critical => {
+                limit + (limit as f64 * self.settings.critical_reserve).ceil() as usize
+            }
+            Priority::Normal => limit,
+            Priority::Background => {
+                ((limit as f64 * self.settings.background_share).floor() as usize).max(1)
+            }
+        }
+    }
+
+    fn update(&mut self, latency: Duration, overloaded: bool, in_flight: usize) {
+        let limit = self.limit;
+        // Only grow a limit that is actually being used.
+        let app_limited = (in_flight as f64) * 2.0 < limit;
+        let next = match self.settings.algorithm {
+            Algorithm::Aimd {
+                backoff_ratio,
+                latency_threshold,
+            } => {
+                if overloaded || latency > latency_threshold {
+                    limit * backoff_ratio
+                } else if app_limited {
+                    limit
+                } else {
+                    limit + 1.0
+                }
+            }
+            Algorithm::Gradient {
+                tolerance,
+                smoothing,
+            } => {
+                let rtt = latency.as_secs_f64().max(1e-6);
+                let mut baseline = match self.baseline {
+                    Some(baseline) => baseline + (rtt - baseline) / BASELINE_WINDOW,
+                    None => rtt,
+                };
+                // Let the baseline recover after a sustained slow period.
+                if baseline / rtt > 2.0 {
+                    baseline *= 0.95;
+                }
+                self.baseline = Some(baseline);
+
+                let gradient = if overloaded {
+                    0.5
+                } else {
+                    (tolerance * baseline / rtt).clamp(0.5, 1.0)
+                };
+                let target = limit * gradient + limit.sqrt();
+                if target > limit && app_limited {
+                    limit
+                } else {
+                    limit * (1.0 - smoothing) + target * smoothing
+                }
+            }
+        };
+        self.limit = next.clamp(
+            self.settings.min_limit as f64,
+            self.settings.max_limit as f64,
+        );
+    }
+}
+
+impl AdaptiveLimiter {
+    /// Create a limiter. Panics if `settings` fail
+    /// [`validate`](LimiterSettings::validate).
+    pub fn new(settings: LimiterSettings) -> Self {
+        if let Err(error) = settings.validate() {
+            panic!("invalid overload limiter settings: {error}");
+        }
+        Self {
+            inner: Arc::new(Inner {
+                state: Mutex::new(State::new(settings)),
+                rejected: Default::default(),
+                epoch: Instant::now(),
+                last_shed: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Swap in new settings, restarting from their initial limit. Requests
+    /// already in flight keep their slots.
+    pub(crate) fn reconfigure(&self, settings: LimiterSettings) {
+        let mut state = self.state();
+        let in_flight = state.in_flight;
+        *state = State::new(settings);
+        state.in_flight = in_flight;
+    }
+
+    /// Admit a request of the given priority, or `None` when its share of
+    /// the limit is used up.
+    pub fn try_acquire(&self, priority: Priority) -> Option<Permit> {
+        let mut state = self.state();
+        if state.in_flight >= state.capacity(priority) {
+            drop(state);
+            self.inner.rejected[priority.index()].fetch_add(1, Ordering::Relaxed);
+            if priority != Priority::Background {
+                let now = self.inner.epoch.elapsed().as_millis() as u64 + 1;
+                self.inner.last_shed.store(now, Ordering::Relaxed);
+            }
+            return None;
+        }
+        state.in_flight += 1;
+        Some(Permit {
+            limiter: self.clone(),
+            started: Instant::now(),
+            in_flight: state.in_flight,
+            released: false,
+        })
+    }
+
+    /// The current concurrency limit.
+    pub fn limit(&self) -> usize {
+        self.state().limit()
+    }
+
+    /// Requests currently holding a slot.
+    pub fn in_flight(&self) -> usize {
+        self.state().in_flight
+    }
+
+    /// Requests of `priority` shed since startup.
+    pub fn rejected(&self, priority: Priority) -> u64 {
+        self.inner.rejected[priority.index()].load(Ordering::Relaxed)
+    }
+
+    /// `true` while the limit is fully used, or for
+    /// [`retry_after`](LimiterSettings::retry_after) after a `Normal` or
+    /// `Critical` request was shed. Background-only shedding does not count.
+    pub fn is_saturated(&self) -> bool {
+        let (at_limit, retry_after) = {
+            let state = self.state();
+            (state.in_flight >= state.limit(), state.settings.retry_after)
+        };
+        if at_limit {
+            return true;
+        }
+        match self.inner.last_shed.load(Ordering::Relaxed) {
+            0 => false,
+            shed => {
+                let since = self.inner.epoch.elapsed().as_millis() as u64 + 1 - shed;
+                since < retry_after.as_millis() as u64
+            }
+        }
+    }
+
+    /// The `Retry-After` advertised to shed requests.
+    pub fn retry_after(&self) -> Duration {
+        self.state().settings.retry_after
+    }
+
+    /// The active settings.
+    pub fn settings(&self) -> LimiterSettings {
+        self.state().settings.clone()
+    }
+
+    fn release(&self, sample: Option<(Duration, bool, usize)>) {
+        let mut state = self.state();
+        state.in_flight = state.in_flight.saturating_sub(1);
+        if let Some((latency, overloaded, in_flight)) = sample {
+            state.update(latency, overloaded, in_flight);
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.inner
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// A slot granted by [`AdaptiveLimiter::try_acquire`].
+///
+/// [`record`](Self::record) frees it and feeds the request latency to the
+/// limit; dropping it unrecorded (a cancelled request) only frees it.
+pub struct Permit {
+    limiter: AdaptiveLimiter,
+    started: Instant,
+    in_flight: usize,
+    released: bool,
+}
+
+impl Permit {
+    /// Free the slot and sample the latency since admission. `overloaded`
+    /// marks a response that signals overload regardless of latency (the
+    /// shedding layer passes `true` for `503` and `504`).
+    pub fn record(mut self, overloaded: bool) {
+        self.released = true;
+        self.limiter
+            .release(Some((self.started.elapsed(), overloaded, self.in_flight)));
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if !self.released {
+            self.limiter.release(None);
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! Limiter metrics on the shared `r2e-prometheus` registry (`prometheus`
+//! feature).
+
+use std::sync::Mutex;
+
+use r2e_core::DeferredContext;
+use r2e_prometheus::prometheus::core::{Collector, Desc};
+use r2e_prometheus::prometheus::proto::MetricFamily;
+use r2e_prometheus::prometheus::{IntCounterVec, IntGauge, Opts};
+
+use crate::limiter::{AdaptiveLimiter, Priority};
+
+/// Reads the limiter on every scrape:
+///
+/// - `overload_concurrency_limit`
+/// - `overload_requests_in_flight`
+/// - `overload_rejected_requests_total{priority}`
+pub(crate) struct LimiterCollector {
+    limiter: AdaptiveLimiter,
+    limit: IntGauge,
+    in_flight: IntGauge,
+    rejected: IntCounterVec,
+    /// Serializes scrapes while catching the counters up with the limiter.
+    sync: Mutex<()>,
+}
+
+impl LimiterCollector {
+    pub(crate) fn new(limiter: AdaptiveLimiter) -> Self {
+        let limit = IntGauge::new(
+            "overload_concurrency_limit",
+            "Current adaptive concurrency limit",
+        )
+        .expect("valid overload_concurrency_limit metric");
+        let in_flight = IntGauge::new(
+            "overload_requests_in_flight",
+            "Requests holding a concurrency slot",
+        )
+        .expect("valid overload_requests_in_flight metric");
+        let rejected = IntCounterVec::new(
+            Opts::new(
+                "overload_rejected_requests_total",
+                "Requests shed with 503, by route priority",
+            ),
+            &["priority"],
+        )
+        .expect("valid overload_rejected_requests_total metric");
+        Self {
+            limiter,
+            limit,
+            in_flight,
+            rejected,
+            sync: Mutex::new(()),
+        }
+    }
+}
+
+impl Collector for LimiterCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        let mut descs = self.limit.desc();
+        descs.extend(self.in_flight.desc());
+        descs.extend(self.rejected.desc());
+        descs
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        let _guard = self.sync.lock().unwrap_or_else(|p| p.into_inner());
+        self.limit.set(self.limiter.limit() as i64);
+        self.in_flight.set(self.limiter.in_flight() as i64);
+        for priority in Priority::ALL {
+            let counter = self.rejected.with_label_values(&[priority.as_str()]);
+            let total = self.limiter.rejected(priority);
+            counter.inc_by(total.saturating_sub(counter.get()));
+        }
+
+        let mut families = self.limit.collect();
+        families.extend(self.in_flight.collect());
+        families.extend(self.rejected.collect());
+        families
+    }
+}
+
+/// Register the collector when the server starts and remove it on shutdown,
+/// so a hot-reloaded app does not collide with its predecessor.
+///
+/// Waiting for the serve phase leaves the Prometheus plugin's `configure`
+/// step — which initializes the shared registry — ahead of us whatever the
+/// plugin install order.
+pub(crate) fn register(ctx: &mut DeferredContext<'_>, limiter: &AdaptiveLimiter) {
+    let serving = limiter.clone();
+    ctx.on_serve(move |_| {
+        if let Err(error) =
+            r2e_prometheus::registry().register(Box::new(LimiterCollector::new(serving)))
+        {
+            tracing::warn!(error = %error, "failed to register overload metrics");
+        }
+    });
+    let stopping = limiter.clone();
+    ctx.on_shutdown(move || {
+        let _ = r2e_prometheus::registry().unregister(Box::new(LimiterCollector::new(stopping)));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::limiter::{Algorithm, LimiterSettings};
+    use r2e_prometheus::prometheus::Registry;
+
+    #[test]
+    fn exports_limit_in_flight_and_rejections() {
+        let limiter = AdaptiveLimiter::new(LimiterSettings {
+            algorithm: Algorithm::aimd(),
+            initial_limit: 2,
+            min_limit: 2,
+            max_limit: 2,
+            ..LimiterSettings::default()
+        });
+        let _held = limiter.try_acquire(Priority::Background).unwrap();
+        assert!(limiter.try_acquire(Priority::Background).is_none());
+        assert!(limiter.try_acquire(Priority::Background).is_none());
+
+        let registry = Registry::new();
+        registry
+            .register(Box::new(LimiterCollector::new(limiter.clone())))
+            .unwrap();
+        // Scraping twice must not double-count the rejections.
+        registry.gather();
+        let families = registry.gather();
+        let family = |name: &str| {
+            families
+                .iter()
+                .find(|family| family.name() == name)
+                .unwrap()
+                .get_metric()
+                .to_vec()
+        };
+
+        assert_eq!(
+            family("overload_concurrency_limit")[0].get_gauge().value(),
+            2.0
+        );
+        assert_eq!(
+            family("overload_requests_in_flight")[0].get_gauge().value(),
+            1.0
+        );
+        let background = family("overload_rejected_requests_total")
+            .into_iter()
+            .find(|metric| metric.get_label()[0].value() == "background")
+            .unwrap();
+        assert_eq!(background.get_counter().value(), 2.0);
+    }
+}
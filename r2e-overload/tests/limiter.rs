@@ -0,0 +1,194 @@
+//! Admission by priority and the AIMD / Gradient limit updates.
+
+use r2e_overload::{AdaptiveLimiter, Algorithm, LimiterSettings, Priority};
+use std::time::Duration;
+
+fn fixed(limit: usize) -> LimiterSettings {
+    LimiterSettings {
+        algorithm: Algorithm::aimd(),
+        initial_limit: limit,
+        min_limit: limit,
+        max_limit: limit,
+        ..LimiterSettings::default()
+    }
+}
+
+fn adaptive(algorithm: Algorithm) -> AdaptiveLimiter {
+    AdaptiveLimiter::new(LimiterSettings {
+        algorithm,
+        initial_limit: 10,
+        min_limit: 1,
+        max_limit: 100,
+        ..LimiterSettings::default()
+    })
+}
+
+#[test]
+fn priorities_get_different_shares_of_the_limit() {
+    let limiter = AdaptiveLimiter::new(fixed(10));
+
+    let background: Vec<_> = (0..5)
+        .map(|_| limiter.try_acquire(Priority::Background).unwrap())
+        .collect();
+    assert!(limiter.try_acquire(Priority::Background).is_none());
+
+    let normal: Vec<_> = (0..5)
+        .map(|_| limiter.try_acquire(Priority::Normal).unwrap())
+        .collect();
+    assert!(limiter.try_acquire(Priority::Normal).is_none());
+
+    let critical: Vec<_> = (0..2)
+        .map(|_| limiter.try_acquire(Priority::Critical).unwrap())
+        .collect();
+    assert!(limiter.try_acquire(Priority::Critical).is_none());
+
+    assert_eq!(limiter.in_flight(), 12);
+    assert_eq!(limiter.rejected(Priority::Background), 1);
+    assert_eq!(limiter.rejected(Priority::Normal), 1);
+    assert_eq!(limiter.rejected(Priority::Critical), 1);
+    drop((background, normal, critical));
+    assert_eq!(limiter.in_flight(), 0);
+}
+
+#[test]
+fn dropped_permit_frees_its_slot_without_a_sample() {
+    let limiter = adaptive(Algorithm::aimd());
+    let permit = limiter.try_acquire(Priority::Normal).unwrap();
+    assert_eq!(limiter.in_flight(), 1);
+    drop(permit);
+    assert_eq!(limiter.in_flight(), 0);
+    assert_eq!(limiter.limit(), 10);
+}
+
+#[test]
+fn aimd_backs_off_on_overload_and_grows_when_used() {
+    let limiter = adaptive(Algorithm::aimd());
+    limiter.try_acquire(Priority::Normal).unwrap().record(true);
+    assert_eq!(limiter.limit(), 9);
+
+    // A lone fast request does not use enough of the limit to grow it.
+    limiter.try_acquire(Priority::Normal).unwrap().record(false);
+    assert_eq!(limiter.limit(), 9);
+
+    let permits: Vec<_> = (0..5)
+        .map(|_| limiter.try_acquire(Priority::Normal).unwrap())
+        .collect();
+    for permit in permits {
+        permit.record(false);
+    }
+    assert!(limiter.limit() > 9);
+}
+
+#[test]
+fn aimd_treats_slow_responses_as_overload() {
+    let limiter = adaptive(Algorithm::Aimd {
+        backoff_ratio: 0.5,
+        latency_threshold: Duration::from_millis(5),
+    });
+    let permit = limiter.try_acquire(Priority::Normal).unwrap();
+    std::thread::sleep(Duration::from_millis(20));
+    permit.record(false);
+    assert_eq!(limiter.limit(), 5);
+}
+
+#[test]
+fn gradient_shrinks_when_latency_climbs() {
+    let limiter = adaptive(Algorithm::Gradient {
+        tolerance: 1.0,
+        smoothing: 1.0,
+    });
+    // Establish a fast baseline.
+    limiter.try_acquire(Priority::Normal).unwrap().record(false);
+    let before = limiter.limit();
+
+    let permit = limiter.try_acquire(Priority::Normal).unwrap();
+    std::thread::sleep(Duration::from_millis(20));
+    permit.record(false);
+    assert!(limiter.limit() < before);
+}
+
+#[test]
+fn gradient_halves_on_overload_signal() {
+    let limiter = adaptive(Algorithm::Gradient {
+        tolerance: 2.0,
+        smoothing: 1.0,
+    });
+    limiter.try_acquire(Priority::Normal).unwrap().record(true);
+    // 10 × 0.5 + √10
+    assert_eq!(limiter.limit(), 8);
+}
+
+#[test]
+fn limit_stays_within_bounds() {
+    let limiter = AdaptiveLimiter::new(LimiterSettings {
+        algorithm: Algorithm::aimd(),
+        initial_limit: 2,
+        min_limit: 2,
+        max_limit: 4,
+        ..LimiterSettings::default()
+    });
+    for _ in 0..5 {
+        limiter.try_acquire(Priority::Normal).unwrap().record(true);
+    }
+    assert_eq!(limiter.limit(), 2);
+}
+
+#[test]
+fn shedding_normal_traffic_marks_the_limiter_saturated() {
+    let limiter = AdaptiveLimiter::new(LimiterSettings {
+        retry_after: Duration::from_secs(60),
+        ..fixed(2)
+    });
+    let _background = limiter.try_acquire(Priority::Background).unwrap();
+    assert!(limiter.try_acquire(Priority::Background).is_none());
+    assert!(!limiter.is_saturated());
+
+    let _normal = limiter.try_acquire(Priority::Normal).unwrap();
+    assert!(limiter.is_saturated());
+    drop(_normal);
+    assert!(!limiter.is_saturated());
+
+    let _second = limiter.try_acquire(Priority::Normal).unwrap();
+    assert!(limiter.try_acquire(Priority::Normal).is_none());
+    drop(_second);
+    // Still within `retry_after` of the shed request.
+    assert!(limiter.is_saturated());
+}
+
+#[test]
+fn validate_names_the_bad_setting() {
+    let err = LimiterSettings {
+        initial_limit: 5,
+        ..fixed(10)
+    }
+    .validate()
+    .unwrap_err();
+    assert!(err.contains("initial_limit"), "{err}");
+
+    let err = LimiterSettings {
+        background_share: 0.0,
+        ..LimiterSettings::default()
+    }
+    .validate()
+    .unwrap_err();
+    assert!(err.contains("background_share"), "{err}");
+
+    let err = LimiterSettings {
+        algorithm: Algorithm::Gradient {
+            tolerance: 0.5,
+            smoothing: 0.2,
+        },
+        ..LimiterSettings::default()
+    }
+    .validate()
+    .unwrap_err();
+    assert!(err.contains("tolerance"), "{err}");
+}
+
+#[test]
+fn priority_round_trips_through_its_name() {
+    for priority in Priority::ALL {
+        assert_eq!(priority.as_str().parse::<Priority>().unwrap(), priority);
+    }
+    assert!("urgent".parse::<Priority>().is_err());
+}
@@ -0,0 +1,234 @@
+//! The `Overload` plugin end to end: `#[priority]` routes, `503` +
+//! `Retry-After`, the readiness check and `overload.*` config.
+
+use http_body_util::BodyExt;
+use r2e_core::config::{ConfigValue, R2eConfig};
+use r2e_core::http::{Body, Request, Router, StatusCode};
+use r2e_core::plugins::Health;
+use r2e_core::prelude::*;
+use r2e_core::type_list::BeanAccess;
+use r2e_core::AppBuilder;
+use r2e_overload::{AdaptiveLimiter, Algorithm, LimiterSettings, Overload, Priority};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tower::ServiceExt;
+
+/// Holds `/work/slow` requests until the test adds permits.
+static GATE: Semaphore = Semaphore::const_new(0);
+
+#[controller(path = "/work")]
+pub struct WorkController {}
+
+#[routes]
+#[priority(background)]
+impl WorkController {
+    #[get("/slow")]
+    #[priority(normal)]
+    async fn slow(&self) -> &'static str {
+        GATE.acquire().await.unwrap().forget();
+        "done"
+    }
+
+    #[get("/normal")]
+    #[priority(normal)]
+    async fn normal(&self) -> &'static str {
+        "done"
+    }
+
+    #[get("/normalize")]
+    #[priority(normal)]
+    async fn normalize(&self) -> &'static str {
+        "done"
+    }
+
+    #[get("/report")]
+    async fn report(&self) -> &'static str {
+        "done"
+    }
+
+    #[get("/checkout")]
+    #[priority(critical)]
+    async fn checkout(&self) -> &'static str {
+        "done"
+    }
+}
+
+async fn app(plugin: Overload, config: R2eConfig) -> (Router, AdaptiveLimiter) {
+    let app = AppBuilder::new()
+        .override_config(config)
+        .load_config::<()>()
+        .plugin(plugin)
+        .build_state()
+        .await;
+    let limiter = app.state().get::<AdaptiveLimiter>();
+    let router = app
+        .with(Health::builder().build())
+        .register_controller::<WorkController>()
+        .build();
+    (router, limiter)
+}
+
+async fn get(router: &Router, path: &str) -> (StatusCode, Option<String>, String) {
+    let req = Request::builder().uri(path).body(Body::empty()).unwrap();
+    let resp = router.clone().oneshot(req).await.unwrap();
+    let status = resp.status();
+    let retry_after = resp
+        .headers()
+        .get("retry-after")
+        .map(|v| v.to_str().unwrap().to_string());
+    let body = resp.into_body().collect().await.unwrap().to_bytes();
+    (
+        status,
+        retry_after,
+        String::from_utf8_lossy(&body).to_string(),
+    )
+}
+
+#[r2e_core::test]
+async fn sheds_by_priority_and_reports_not_ready_while_saturated() {
+    let plugin = Overload::builder()
+        .algorithm(Algorithm::aimd())
+        .initial_limit(2)
+        .min_limit(2)
+        .max_limit(2)
+        .critical_reserve(0.5)
+        .retry_after(Duration::from_secs(3))
+        .build();
+    let (router, limiter) = app(plugin, R2eConfig::empty()).await;
+
+    let (status, _, _) = get(&router, "/health/ready").await;
+    assert_eq!(status, StatusCode::OK);
+
+    let held: Vec<_> = (0..2)
+        .map(|_| tokio::spawn(get_owned(router.clone(), "/work/slow")))
+        .collect();
+    while limiter.in_flight() < 2 {
+        tokio::task::yield_now().await;
+    }
+
+    let (status, retry_after, body) = get(&router, "/work/normal").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(retry_after.as_deref(), Some("3"));
+    assert_eq!(body, r#"{"error":"Server overloaded"}"#);
+
+    let (status, _, _) = get(&router, "/work/report").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+    let (status, _, _) = get(&router, "/work/checkout").await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Health paths are excluded from shedding, and report the saturation.
+    let (status, _, body) = get(&router, "/health/ready").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert!(body.contains("shedding load"), "{body}");
+
+    assert_eq!(limiter.rejected(Priority::Normal), 1);
+    assert_eq!(limiter.rejected(Priority::Background), 1);
+    assert_eq!(limiter.rejected(Priority::Critical), 0);
+
+    GATE.add_permits(2);
+    for request in held {
+        assert_eq!(request.await.unwrap(), StatusCode::OK);
+    }
+    assert_eq!(limiter.in_flight(), 0);
+}
+
+async fn get_owned(router: Router, path: &'static str) -> StatusCode {
+    get(&router, path).await.0
+}
+
+#[r2e_core::test]
+async fn excluded_paths_match_whole_segments() {
+    let plugin = Overload::builder()
+        .algorithm(Algorithm::aimd())
+        .initial_limit(1)
+        .min_limit(1)
+        .max_limit(1)
+        .exclude_path("/work/normal")
+        .build();
+    let (router, limiter) = app(plugin, R2eConfig::empty()).await;
+
+    let held = tokio::spawn(get_owned(router.clone(), "/work/slow"));
+    while limiter.in_flight() < 1 {
+        tokio::task::yield_now().await;
+    }
+
+    let (status, _, _) = get(&router, "/work/normal").await;
+    assert_eq!(status, StatusCode::OK);
+    // Shares the prefix but not the path segment.
+    let (status, _, _) = get(&router, "/work/normalize").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+    GATE.add_permits(1);
+    assert_eq!(held.await.unwrap(), StatusCode::OK);
+}
+
+#[r2e_core::test]
+async fn impl_level_priority_is_the_route_default() {
+    let plugin = Overload::builder()
+        .algorithm(Algorithm::aimd())
+        .initial_limit(1)
+        .min_limit(1)
+        .max_limit(1)
+        .build();
+    let (router, limiter) = app(plugin, R2eConfig::empty()).await;
+
+    // Background routes may use `max(1, ⌊0.5 × 1⌋)` slots, so each request
+    // on its own still passes.
+    let (status, _, _) = get(&router, "/work/report").await;
+    assert_eq!(status, StatusCode::OK);
+    let permit = limiter.try_acquire(Priority::Normal).unwrap();
+    let (status, _, _) = get(&router, "/work/report").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    let (status, _, _) = get(&router, "/work/checkout").await;
+    assert_eq!(status, StatusCode::OK);
+    drop(permit);
+}
+
+#[r2e_core::test]
+async fn file_config_tunes_the_limiter() {
+    let mut config = R2eConfig::empty();
+    config.set("overload.algorithm", ConfigValue::String("aimd".into()));
+    config.set("overload.initial_limit", ConfigValue::Integer(4));
+    config.set("overload.min_limit", ConfigValue::Integer(2));
+    config.set("overload.max_limit", ConfigValue::Integer(8));
+    config.set("overload.retry_after", ConfigValue::String("5s".into()));
+    let (_router, limiter) = app(Overload::new(), config).await;
+
+    let settings = limiter.settings();
+    assert_eq!(settings.algorithm, Algorithm::aimd());
+    assert_eq!(limiter.limit(), 4);
+    assert_eq!((settings.min_limit, settings.max_limit), (2, 8));
+    assert_eq!(settings.retry_after, Duration::from_secs(5));
+}
+
+#[r2e_core::test]
+async fn builder_settings_win_over_file_config() {
+    let mut config = R2eConfig::empty();
+    config.set("overload.max_limit", ConfigValue::Integer(8));
+    let plugin = Overload::builder().max_limit(50).build();
+    let (_router, limiter) = app(plugin, config).await;
+    assert_eq!(limiter.settings().max_limit, 50);
+}
+
+#[r2e_core::test]
+#[should_panic(expected = "invalid `overload` configuration: algorithm")]
+async fn unknown_algorithm_fails_startup() {
+    let mut config = R2eConfig::empty();
+    config.set("overload.algorithm", ConfigValue::String("vegas".into()));
+    let _ = app(Overload::new(), config).await;
+}
+
+#[r2e_core::test]
+async fn disabled_plugin_does_not_shed() {
+    let mut config = R2eConfig::empty();
+    config.set("overload.enabled", ConfigValue::Bool(false));
+    let (router, limiter) = app(Overload::new(), config).await;
+    assert_eq!(limiter.settings(), LimiterSettings::default());
+
+    let _permits: Vec<_> = (0..limiter.limit())
+        .map(|_| limiter.try_acquire(Priority::Normal).unwrap())
+        .collect();
+    let (status, _, _) = get(&router, "/work/normal").await;
+    assert_eq!(status, StatusCode::OK);
+}
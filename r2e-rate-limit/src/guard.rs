@@ -1,6 +1,5 @@
 use r2e_core::beans::BeanContext;
 use r2e_core::guards::{Guard, GuardContext, Identity, PreAuthGuard, PreAuthGuardContext};
-use r2e_core::http::response::IntoResponse;
 use r2e_core::type_list::{TCons, TNil};
 use r2e_core::DecoratorSpec;
 
@@ -151,11 +150,10 @@ impl<I: Identity> Guard<I> for RateLimitGuard {
         let result = if self.registry.try_acquire(&key, self.max, self.window_secs) {
             Ok(())
         } else {
-            Err((
+            Err(r2e_core::error::error_response(
                 r2e_core::http::StatusCode::TOO_MANY_REQUESTS,
-                r2e_core::http::Json(serde_json::json!({ "error": "Rate limit exceeded" })),
-            )
-                .into_response())
+                "Rate limit exceeded",
+            ))
         };
         std::future::ready(result)
     }
@@ -201,11 +199,10 @@ impl PreAuthGuard for PreAuthRateLimitGuard {
         let result = if self.registry.try_acquire(&key, self.max, self.window_secs) {
             Ok(())
         } else {
-            Err((
+            Err(r2e_core::error::error_response(
                 r2e_core::http::StatusCode::TOO_MANY_REQUESTS,
-                r2e_core::http::Json(serde_json::json!({ "error": "Rate limit exceeded" })),
-            )
-                .into_response())
+                "Rate limit exceeded",
+            ))
         };
         std::future::ready(result)
     }
@@ -72,8 +72,7 @@ impl SecurityError {
 
 impl IntoResponse for SecurityError {
     fn into_response(self) -> Response {
-        let body = serde_json::json!({ "error": self.public_message() });
-        (self.status(), r2e_core::http::Json(body)).into_response()
+        r2e_core::error::error_response(self.status(), self.public_message())
     }
 }
 
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use r2e_core::extract::{FromRequestPartsVia, OptionalFromRequestPartsVia, ViaBean};
 use r2e_core::http::header::{Parts, AUTHORIZATION};
 use r2e_core::type_list::HasBean;
+use r2e_core::RequestContext;
 use tracing::{debug, warn};
 
 use crate::error::SecurityError;
@@ -106,7 +107,9 @@ pub async fn extract_jwt_claims<S, I>(
 where
     S: HasBean<Arc<JwtClaimsValidator>, I> + Send + Sync,
 {
-    extract_jwt_claims_as::<S, I, serde_json::Value>(parts, state).await
+    let claims = extract_jwt_claims_as::<S, I, serde_json::Value>(parts, state).await?;
+    record_claims(&claims);
+    Ok(claims)
 }
 
 /// Extract, validate and deserialize JWT claims into an application claim set.
@@ -172,15 +175,42 @@ where
     let token = extract_bearer_token_from_parts(parts)?;
     let validator: Arc<JwtValidator<B>> = state.get_bean();
 
-    let identity = validator.validate(token).await.map_err(|e| {
+    let claims = validator.validate_claims(token).await.map_err(|e| {
         warn!(uri = %parts.uri, error = %e, "JWT validation failed");
         r2e_core::HttpError::from(e)
     })?;
+    record_claims(&claims);
+    let identity = validator
+        .identity_builder()
+        .build(claims)
+        .await
+        .map_err(|e| {
+            warn!(uri = %parts.uri, error = %e, "JWT validation failed");
+            r2e_core::HttpError::from(e)
+        })?;
 
     debug!(uri = %parts.uri, "Authenticated request");
     Ok(identity)
 }
 
+/// Record the `sub` claim on the ambient [`RequestContext`] and the claims
+/// for the locale of the request, for identities built from raw claims.
+fn record_claims(claims: &serde_json::Value) {
+    if let Some(sub) = claims.get("sub").and_then(serde_json::Value::as_str) {
+        RequestContext::record_subject(sub);
+    }
+    r2e_core::i18n::record_claims(claims);
+}
+
+/// Record the user's subject on the ambient [`RequestContext`], so events
+/// emitted while serving the request carry it, and their claims for the
+/// locale of the request.
+fn recorded(user: AuthenticatedUser) -> AuthenticatedUser {
+    RequestContext::record_subject(&user.sub);
+    r2e_core::i18n::record_claims(&user.claims);
+    user
+}
+
 /// Request-extraction implementation for `AuthenticatedUser`.
 ///
 /// This extracts the JWT from the `Authorization: Bearer <token>` header,
@@ -202,7 +232,7 @@ where
 
     async fn from_request_parts_via(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
         let claims = extract_jwt_claims(parts, state).await?;
-        Ok(AuthenticatedUser::from_claims(claims))
+        Ok(recorded(AuthenticatedUser::from_claims(claims)))
     }
 }
 
@@ -245,6 +275,6 @@ where
         }
 
         let claims = extract_jwt_claims(parts, state).await?;
-        Ok(Some(AuthenticatedUser::from_claims(claims)))
+        Ok(Some(recorded(AuthenticatedUser::from_claims(claims))))
     }
 }
@@ -287,6 +287,12 @@ impl<B: IdentityBuilder> JwtValidator<B> {
         self.claims_validator.config()
     }
 
+    /// Returns the identity builder used to turn validated claims into
+    /// `B::Identity`.
+    pub fn identity_builder(&self) -> &B {
+        &self.identity_builder
+    }
+
     /// Validate a JWT token and return the identity on success.
     pub async fn validate(&self, token: &str) -> Result<B::Identity, SecurityError> {
         let claims = self.claims_validator.validate(token).await?;
@@ -6,6 +6,8 @@ pub mod identity;
 pub mod jwks;
 pub mod jwt;
 pub mod keycloak;
+#[cfg(feature = "mtls")]
+pub mod mtls;
 pub mod openid;
 
 // Re-export primary public types for convenience.
@@ -19,6 +21,8 @@ pub use identity::{
 };
 pub use jwks::JwksCache;
 pub use jwt::{JwtClaimSet, JwtClaimsValidator, JwtValidator};
+#[cfg(feature = "mtls")]
+pub use mtls::ClientCertIdentity;
 
 // Re-export the base RoleExtractor trait at crate root for convenience.
 pub use openid::RoleExtractor;
@@ -40,6 +44,8 @@ pub mod __macro_support {
 
 pub mod prelude {
     //! Re-exports of the most commonly used security types.
+    #[cfg(feature = "mtls")]
+    pub use crate::ClientCertIdentity;
     pub use crate::{
         AllRolesGuard, AuthenticatedUser, JwtValidator, RoleBasedIdentity, RolesGuard,
         SecurityConfig,
@@ -0,0 +1,173 @@
+//! Client-certificate (mTLS) identity.
+//!
+//! When the server terminates TLS with `server.tls.client_ca` set, the
+//! verified client certificate chain travels with every request as
+//! [`PeerCertificates`]. [`ClientCertIdentity`] parses the leaf certificate
+//! into an [`Identity`]: the subject common name is the `sub`, and the
+//! subject's organizational units (`OU=`) are the roles, so `#[roles]` and
+//! [`RolesGuard`](crate::RolesGuard) work unchanged.
+//!
+//! ```ignore
+//! #[controller(path = "/internal")]
+//! pub struct InternalController {
+//!     #[inject(identity)]
+//!     caller: ClientCertIdentity,
+//! }
+//!
+//! #[routes]
+//! impl InternalController {
+//!     #[get("/jobs")]
+//!     #[roles("billing")]
+//!     async fn jobs(&self) -> String {
+//!         format!("hello {}", self.caller.common_name.as_deref().unwrap_or("?"))
+//!     }
+//! }
+//! ```
+//!
+//! The certificate was already verified against the CA bundle during the
+//! handshake; this extractor only reads it.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use r2e_core::http::extract::{FromRequestParts, OptionalFromRequestParts};
+use r2e_core::http::header::Parts;
+use r2e_core::http::tls::rustls::pki_types::CertificateDer;
+use r2e_core::http::tls::PeerCertificates;
+use r2e_core::{HttpError, Identity};
+use x509_parser::certificate::X509Certificate;
+use x509_parser::extensions::GeneralName;
+use x509_parser::prelude::FromDer;
+
+/// Identity of a client authenticated by its TLS certificate.
+#[derive(Clone, Debug)]
+pub struct ClientCertIdentity {
+    /// Subject common name, falling back to the full subject DN.
+    pub sub: String,
+    /// Subject distinguished name, RFC 4514 style (`CN=svc, OU=billing`).
+    pub subject: String,
+    /// Issuer distinguished name.
+    pub issuer: String,
+    /// First subject `CN=` attribute.
+    pub common_name: Option<String>,
+    /// Subject `OU=` attributes; exposed as roles.
+    pub organizational_units: Vec<String>,
+    /// `DNS` subject alternative names.
+    pub dns_names: Vec<String>,
+    /// `URI` subject alternative names (e.g. SPIFFE IDs).
+    pub uris: Vec<String>,
+    /// `email` subject alternative names and subject `emailAddress=`
+    /// attributes.
+    pub emails: Vec<String>,
+    /// Serial number, colon-separated hex.
+    pub serial: String,
+    /// End of the certificate validity period.
+    pub not_after: SystemTime,
+    /// The DER-encoded leaf certificate.
+    pub der: CertificateDer<'static>,
+}
+
+impl ClientCertIdentity {
+    /// Parse the identity from a DER-encoded certificate.
+    pub fn from_der(der: &CertificateDer<'_>) -> Result<Self, String> {
+        let (_, cert) = X509Certificate::from_der(der.as_ref())
+            .map_err(|e| format!("invalid client certificate: {e}"))?;
+        let subject = cert.subject();
+        let common_name = first_attr(subject.iter_common_name());
+        let organizational_units = all_attrs(subject.iter_organizational_unit());
+        let mut emails = all_attrs(subject.iter_email());
+        let mut dns_names = Vec::new();
+        let mut uris = Vec::new();
+        if let Ok(Some(san)) = cert.subject_alternative_name() {
+            for name in &san.value.general_names {
+                match name {
+                    GeneralName::DNSName(dns) => dns_names.push(dns.to_string()),
+                    GeneralName::URI(uri) => uris.push(uri.to_string()),
+                    GeneralName::RFC822Name(email) => emails.push(email.to_string()),
+                    _ => {}
+                }
+            }
+        }
+        let not_after = cert.validity().not_after.timestamp();
+        Ok(Self {
+            sub: common_name.clone().unwrap_or_else(|| subject.to_string()),
+            subject: subject.to_string(),
+            issuer: cert.issuer().to_string(),
+            common_name,
+            organizational_units,
+            dns_names,
+            uris,
+            emails,
+            serial: cert.raw_serial_as_string(),
+            not_after: UNIX_EPOCH + Duration::from_secs(not_after.max(0) as u64),
+            der: der.clone().into_owned(),
+        })
+    }
+
+    /// Parse the identity from the leaf of a verified peer chain.
+    pub fn from_peer(peer: &PeerCertificates) -> Result<Self, String> {
+        let leaf = peer
+            .end_entity()
+            .ok_or_else(|| "empty client certificate chain".to_string())?;
+        Self::from_der(leaf)
+    }
+}
+
+fn first_attr<'a, 'b: 'a>(
+    mut attrs: impl Iterator<Item = &'a x509_parser::x509::AttributeTypeAndValue<'b>>,
+) -> Option<String> {
+    attrs.find_map(|attr| attr.as_str().ok().map(str::to_string))
+}
+
+fn all_attrs<'a, 'b: 'a>(
+    attrs: impl Iterator<Item = &'a x509_parser::x509::AttributeTypeAndValue<'b>>,
+) -> Vec<String> {
+    attrs
+        .filter_map(|attr| attr.as_str().ok().map(str::to_string))
+        .collect()
+}
+
+impl Identity for ClientCertIdentity {
+    fn sub(&self) -> &str {
+        &self.sub
+    }
+    fn email(&self) -> Option<&str> {
+        self.emails.first().map(String::as_str)
+    }
+}
+
+impl crate::guards::RoleBasedIdentity for ClientCertIdentity {
+    fn roles(&self) -> &[String] {
+        &self.organizational_units
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ClientCertIdentity {
+    type Rejection = HttpError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let peer = parts
+            .extensions
+            .get::<PeerCertificates>()
+            .ok_or_else(|| HttpError::unauthorized("Client certificate required"))?;
+        Self::from_peer(peer).map_err(|e| {
+            tracing::debug!(error = %e, "unparseable client certificate");
+            HttpError::unauthorized("Invalid client certificate")
+        })
+    }
+}
+
+impl<S: Send + Sync> OptionalFromRequestParts<S> for ClientCertIdentity {
+    type Rejection = HttpError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        if parts.extensions.get::<PeerCertificates>().is_none() {
+            return Ok(None);
+        }
+        <Self as FromRequestParts<S>>::from_request_parts(parts, state)
+            .await
+            .map(Some)
+    }
+}
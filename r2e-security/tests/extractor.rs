@@ -80,3 +80,71 @@ fn authenticated_user_extraction_route_is_unambiguous() {
     assert_unambiguous_extractor::<S, AuthenticatedUser, _>();
     assert_unambiguous_extractor::<S, Option<AuthenticatedUser>, _>();
 }
+
+/// Custom identities extracted through `extract_jwt_identity` record their
+/// `sub` on the ambient `RequestContext`, like `AuthenticatedUser` does.
+#[tokio::test]
+async fn extract_jwt_identity_records_subject() {
+    use jsonwebtoken::{encode, Algorithm, DecodingKey, EncodingKey, Header};
+    use r2e_core::http::header::HttpRequest;
+    use r2e_core::type_list::{HCons, HNil};
+    use r2e_core::RequestContext;
+    use r2e_security::config::SecurityConfig;
+    use r2e_security::extractor::extract_jwt_identity;
+    use r2e_security::identity::IdentityBuilder;
+    use r2e_security::jwt::JwtValidator;
+    use std::sync::Arc;
+
+    #[derive(Clone)]
+    struct Tenant(String);
+
+    struct TenantBuilder;
+
+    impl IdentityBuilder for TenantBuilder {
+        type Identity = Tenant;
+
+        async fn build(&self, claims: serde_json::Value) -> Result<Tenant, SecurityError> {
+            Ok(Tenant(
+                claims["tenant"].as_str().unwrap_or_default().to_owned(),
+            ))
+        }
+    }
+
+    const SECRET: &[u8] = b"r2e-test-secret-do-not-use-in-production";
+    let config = SecurityConfig::new("unused", "test-issuer", "test-audience")
+        .with_allowed_algorithm(Algorithm::HS256);
+    let validator =
+        JwtValidator::from_static_key(DecodingKey::from_secret(SECRET), config, TenantBuilder);
+    let state = HCons {
+        head: Arc::new(validator),
+        tail: HNil,
+    };
+
+    let token = encode(
+        &Header::new(Algorithm::HS256),
+        &serde_json::json!({
+            "sub": "alice",
+            "tenant": "acme",
+            "iss": "test-issuer",
+            "aud": "test-audience",
+            "exp": u32::MAX,
+        }),
+        &EncodingKey::from_secret(SECRET),
+    )
+    .unwrap();
+    let (parts, _) = HttpRequest::builder()
+        .uri("/test")
+        .header("Authorization", format!("Bearer {token}"))
+        .body(())
+        .unwrap()
+        .into_parts();
+
+    let (tenant, ctx) = RequestContext::new()
+        .scope(async {
+            let tenant = extract_jwt_identity(&parts, &state).await.unwrap();
+            (tenant, RequestContext::current().unwrap())
+        })
+        .await;
+    assert_eq!(tenant.0, "acme");
+    assert_eq!(ctx.subject(), Some("alice"));
+}
@@ -0,0 +1,112 @@
+#![cfg(feature = "mtls")]
+
+use r2e_core::guards::{Guard, GuardContext, Identity, PathParams};
+use r2e_core::http::extract::{FromRequestParts, OptionalFromRequestParts};
+use r2e_core::http::tls::PeerCertificates;
+use r2e_core::http::{HeaderMap, Request, StatusCode, Uri};
+use r2e_core::HttpError;
+use r2e_security::guards::{RoleBasedIdentity, RolesGuard};
+use r2e_security::ClientCertIdentity;
+use rcgen::{CertificateParams, DnType, KeyPair, SanType};
+
+fn client_cert() -> rcgen::Certificate {
+    let mut params = CertificateParams::new(vec!["billing.internal".to_string()]).unwrap();
+    params
+        .distinguished_name
+        .push(DnType::CommonName, "billing-service");
+    params
+        .distinguished_name
+        .push(DnType::OrganizationalUnitName, "billing");
+    params.subject_alt_names.extend([
+        SanType::URI("spiffe://example.org/billing".try_into().unwrap()),
+        SanType::Rfc822Name("billing@example.org".try_into().unwrap()),
+    ]);
+    params.self_signed(&KeyPair::generate().unwrap()).unwrap()
+}
+
+fn parts(peer: Option<PeerCertificates>) -> r2e_core::http::header::Parts {
+    let mut request = Request::new(());
+    if let Some(peer) = peer {
+        request.extensions_mut().insert(peer);
+    }
+    request.into_parts().0
+}
+
+#[test]
+fn parses_subject_and_alternative_names() {
+    let cert = client_cert();
+    let identity = ClientCertIdentity::from_der(cert.der()).unwrap();
+
+    assert_eq!(identity.sub(), "billing-service");
+    assert_eq!(identity.common_name.as_deref(), Some("billing-service"));
+    assert!(
+        identity.subject.contains("CN=billing-service"),
+        "{}",
+        identity.subject
+    );
+    assert_eq!(identity.roles(), ["billing".to_string()]);
+    assert_eq!(identity.dns_names, ["billing.internal"]);
+    assert_eq!(identity.uris, ["spiffe://example.org/billing"]);
+    assert_eq!(identity.email(), Some("billing@example.org"));
+    assert!(!identity.serial.is_empty());
+    assert_eq!(identity.der, *cert.der());
+}
+
+#[test]
+fn invalid_der_is_an_error() {
+    let der = vec![0u8; 8].into();
+    assert!(ClientCertIdentity::from_der(&der).is_err());
+}
+
+#[tokio::test]
+async fn extracts_from_peer_certificates() {
+    let cert = client_cert();
+    let mut parts = parts(Some(PeerCertificates::new(vec![cert.der().clone()])));
+
+    let identity =
+        <ClientCertIdentity as FromRequestParts<()>>::from_request_parts(&mut parts, &())
+            .await
+            .unwrap();
+    assert_eq!(identity.sub(), "billing-service");
+}
+
+#[tokio::test]
+async fn missing_certificate_is_unauthorized() {
+    let mut parts = parts(None);
+
+    let err = <ClientCertIdentity as FromRequestParts<()>>::from_request_parts(&mut parts, &())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, HttpError::Unauthorized(_)));
+    assert_eq!(err.status(), StatusCode::UNAUTHORIZED);
+
+    let optional =
+        <ClientCertIdentity as OptionalFromRequestParts<()>>::from_request_parts(&mut parts, &())
+            .await
+            .unwrap();
+    assert!(optional.is_none());
+}
+
+#[tokio::test]
+async fn organizational_units_drive_roles_guard() {
+    let identity = ClientCertIdentity::from_der(client_cert().der()).unwrap();
+    let uri: Uri = "/jobs".parse().unwrap();
+    let headers = HeaderMap::new();
+    let ctx = GuardContext {
+        method_name: "jobs",
+        controller_name: "InternalController",
+        headers: &headers,
+        uri: &uri,
+        path_params: PathParams::EMPTY,
+        identity: Some(&identity),
+    };
+
+    let billing = RolesGuard {
+        required_roles: &["billing"],
+    };
+    assert!(billing.check(&ctx).await.is_ok());
+    let admin = RolesGuard {
+        required_roles: &["admin"],
+    };
+    assert!(admin.check(&ctx).await.is_err());
+}
@@ -0,0 +1,173 @@
+//! Server-side HTML templates for R2E, on [MiniJinja](minijinja).
+//!
+//! [`Templates`] is the [`TemplateEngine`] behind `#[template]` routes. It
+//! reads the templates from a [`rust_embed`] folder, like `r2e-static`'s
+//! `EmbeddedFrontend`: release builds embed them in the binary, and debug
+//! builds — `r2e dev`, tests — read them from disk.
+//!
+//! # Quick start
+//!
+//! ```ignore
+//! #[derive(rust_embed::Embed)]
+//! #[folder = "templates"]
+//! struct Views;
+//!
+//! AppBuilder::new()
+//!     .provide(Templates::new::<Views>().shared())
+//!     // ...
+//!
+//! #[routes]
+//! impl AdminController {
+//!     #[get("/users")]
+//!     #[template("users/list.html")]
+//!     async fn list(&self) -> UserList {
+//!         UserList { users: self.repo.all().await }
+//!     }
+//! }
+//! ```
+//!
+//! ```jinja
+//! {% extends "layout.html" %}
+//! {% block body %}
+//!   <p>Signed in as {{ identity.sub }}</p>
+//!   <form method="post">
+//!     <input type="hidden" name="csrf_token" value="{{ csrf_token }}">
+//!   </form>
+//!   {% for user in users %}<li>{{ user.name }}</li>{% endfor %}
+//! {% endblock %}
+//! ```
+//!
+//! Templates ending in `.html`, `.htm` and `.xml` are HTML-escaped.
+//!
+//! # Hot reload
+//!
+//! With [`reload`](TemplatesBuilder::reload) on — the default in debug
+//! builds — a template whose file changed on disk is recompiled on its next
+//! render, so `r2e dev` picks up template edits without a rebuild.
+//!
+//! # Builder API
+//!
+//! ```ignore
+//! Templates::builder::<Views>()
+//!     .reload(false)
+//!     .configure(|env| {
+//!         env.add_filter("money", |cents: i64| format!("{:.2} €", cents as f64 / 100.0));
+//!         env.add_global("app_name", "Back office");
+//!     })
+//!     .build()
+//!     .shared()
+//! ```
+
+use std::sync::Arc;
+
+use minijinja::value::Serde;
+use minijinja::{Environment, ErrorKind, TemplateSource};
+use r2e_core::serde_json::Value;
+use r2e_core::templating::{TemplateEngine, TemplateError};
+
+pub use minijinja;
+pub use rust_embed;
+
+/// A [`TemplateEngine`] rendering MiniJinja templates from a
+/// [`rust_embed::Embed`] folder. See the crate docs.
+pub struct Templates {
+    env: Environment<'static>,
+}
+
+impl Templates {
+    /// The templates of `E`, reloaded on change in debug builds.
+    pub fn new<E: rust_embed::Embed + Send + Sync + 'static>() -> Self {
+        Self::builder::<E>().build()
+    }
+
+    /// Start building an engine with custom configuration.
+    pub fn builder<E: rust_embed::Embed + Send + Sync + 'static>() -> TemplatesBuilder {
+        TemplatesBuilder {
+            env: Environment::new(),
+            loader: load::<E>,
+            reload: cfg!(debug_assertions),
+        }
+    }
+
+    /// Ready-to-provide engine bean: `Arc<dyn TemplateEngine>`.
+    pub fn shared(self) -> Arc<dyn TemplateEngine> {
+        Arc::new(self)
+    }
+}
+
+impl TemplateEngine for Templates {
+    fn render(&self, name: &str, context: &Value) -> Result<String, TemplateError> {
+        let template = self.env.get_template(name).map_err(|e| describe(name, e))?;
+        template
+            .render(Serde(context))
+            .map_err(|e| describe(name, e))
+    }
+
+    fn check(&self, name: &str) -> Result<(), TemplateError> {
+        self.env
+            .get_template(name)
+            .map(|_| ())
+            .map_err(|e| describe(name, e))
+    }
+}
+
+/// Builder for [`Templates`].
+pub struct TemplatesBuilder {
+    env: Environment<'static>,
+    loader: fn(&str, bool) -> Option<TemplateSource>,
+    reload: bool,
+}
+
+impl TemplatesBuilder {
+    /// Recompile a template when its file changes (default: on in debug
+    /// builds, off in release builds, where the files are embedded).
+    pub fn reload(mut self, enabled: bool) -> Self {
+        self.reload = enabled;
+        self
+    }
+
+    /// Customize the MiniJinja environment: filters, functions, globals,
+    /// syntax or escaping.
+    pub fn configure(mut self, configure: impl FnOnce(&mut Environment<'static>)) -> Self {
+        configure(&mut self.env);
+        self
+    }
+
+    /// Build the engine.
+    pub fn build(self) -> Templates {
+        let TemplatesBuilder {
+            mut env,
+            loader,
+            reload,
+        } = self;
+        env.set_loader(move |name| Ok(loader(name, reload)));
+        env.set_auto_reload(reload);
+        Templates { env }
+    }
+}
+
+/// Load `name` from `E`; with `reload`, the source is stale once the file's
+/// hash changes.
+fn load<E: rust_embed::Embed + Send + Sync + 'static>(
+    name: &str,
+    reload: bool,
+) -> Option<TemplateSource> {
+    let file = E::get(name)?;
+    let source = String::from_utf8_lossy(&file.data).into_owned();
+    let source = TemplateSource::new(source);
+    if !reload {
+        return Some(source);
+    }
+    let name = name.to_string();
+    let hash = file.metadata.sha256_hash();
+    Some(source.with_uptodate_check(move || {
+        E::get(&name).is_some_and(|file| file.metadata.sha256_hash() == hash)
+    }))
+}
+
+fn describe(name: &str, error: minijinja::Error) -> TemplateError {
+    match error.kind() {
+        ErrorKind::TemplateNotFound => TemplateError::new(format!("template `{name}` not found")),
+        _ => TemplateError::new(format!("{error:#}")),
+    }
+}
@@ -0,0 +1,131 @@
+use r2e_core::http::body::to_bytes;
+use r2e_core::http::{Body, Request, StatusCode};
+use r2e_core::prelude::*;
+use r2e_core::AppBuilder;
+use r2e_templates::{rust_embed, Templates};
+use serde::Serialize;
+use serde_json::json;
+use tower::ServiceExt;
+
+#[derive(rust_embed::Embed)]
+#[folder = "tests/templates"]
+struct Views;
+
+fn engine() -> Templates {
+    Templates::new::<Views>()
+}
+
+#[test]
+fn renders_inherited_templates_with_html_escaping() {
+    let html = engine()
+        .render(
+            "users/list.html",
+            &json!({ "users": ["ada", "<script>"], "identity": null, "request_id": "r-1" }),
+        )
+        .unwrap();
+    assert_eq!(
+        html,
+        "<title>Users</title>\n<main><ul><li>ada</li><li>&lt;script&gt;</li></ul><i>r-1</i></main>"
+    );
+}
+
+#[test]
+fn leaves_non_html_templates_unescaped() {
+    let text = engine()
+        .render("plain.txt", &json!({ "greeting": "Hi", "name": "<ada>" }))
+        .unwrap();
+    assert_eq!(text, "Hi, <ada>!");
+}
+
+#[test]
+fn check_reports_missing_and_invalid_templates() {
+    let engine = engine();
+    assert!(engine.check("users/list.html").is_ok());
+    assert_eq!(
+        engine.check("users/show.html").unwrap_err().to_string(),
+        "template `users/show.html` not found"
+    );
+    let error = engine
+        .check("broken/unclosed.html")
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("syntax error"), "{error}");
+}
+
+#[test]
+fn configure_adds_filters_and_globals() {
+    let engine = Templates::builder::<Views>()
+        .configure(|env| {
+            env.add_filter("shout", |value: String| value.to_uppercase());
+            env.add_global("greeting", "Hello");
+        })
+        .build();
+    engine.check("plain.txt").unwrap();
+    let text = engine
+        .render("plain.txt", &json!({ "name": "ada" }))
+        .unwrap();
+    assert_eq!(text, "Hello, ada!");
+}
+
+#[test]
+fn reload_picks_up_changed_files() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/templates/reload.html");
+    std::fs::write(path, "v1").unwrap();
+
+    let reloading = Templates::builder::<Views>().reload(true).build();
+    let fixed = Templates::builder::<Views>().reload(false).build();
+    assert_eq!(reloading.render("reload.html", &json!({})).unwrap(), "v1");
+    assert_eq!(fixed.render("reload.html", &json!({})).unwrap(), "v1");
+
+    std::fs::write(path, "v2").unwrap();
+    let reloaded = reloading.render("reload.html", &json!({}));
+    let cached = fixed.render("reload.html", &json!({}));
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(reloaded.unwrap(), "v2");
+    assert_eq!(cached.unwrap(), "v1");
+}
+
+#[derive(Serialize)]
+pub struct UserList {
+    users: Vec<&'static str>,
+}
+
+#[controller(path = "/users")]
+pub struct UserController;
+
+#[routes]
+impl UserController {
+    #[get("/")]
+    #[template("users/list.html")]
+    async fn list(&self) -> UserList {
+        UserList {
+            users: vec!["ada", "bob"],
+        }
+    }
+}
+
+#[r2e_core::test]
+async fn serves_template_routes() {
+    let app = AppBuilder::new()
+        .provide(engine().shared())
+        .build_state()
+        .await
+        .with(RequestIdPlugin)
+        .register_controller::<UserController>()
+        .build();
+
+    let req = Request::builder()
+        .uri("/users")
+        .header("x-request-id", "req-9")
+        .body(Body::empty())
+        .unwrap();
+    let resp = app.oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "text/html; charset=utf-8");
+    let body = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&body),
+        "<title>Users</title>\n<main><ul><li>ada</li><li>bob</li></ul><i>req-9</i></main>"
+    );
+}
@@ -288,6 +288,8 @@ pub struct TestApp {
     pub(crate) bean_context: Option<std::sync::Arc<r2e_core::beans::BeanContext>>,
     pub(crate) config: Option<r2e_core::config::R2eConfig>,
     pub(crate) jwt: Option<crate::TestJwt>,
+    #[cfg(feature = "events")]
+    pub(crate) events: std::sync::OnceLock<crate::EventRecorder>,
 }
 
 impl TestApp {
@@ -298,6 +300,8 @@ impl TestApp {
             bean_context: None,
             config: None,
             jwt: None,
+            #[cfg(feature = "events")]
+            events: std::sync::OnceLock::new(),
         }
     }
 
@@ -316,6 +320,8 @@ impl TestApp {
             bean_context: Some(bean_context),
             config,
             jwt: None,
+            #[cfg(feature = "events")]
+            events: std::sync::OnceLock::new(),
         }
     }
 
@@ -352,6 +358,21 @@ impl TestApp {
             })
     }
 
+    /// Recorder for the app's `LocalEventBus` bean, attached on first call;
+    /// later calls return the same recorder. Events emitted before the first
+    /// call are not captured — build the bus from an
+    /// [`EventRecorder`](crate::EventRecorder) to record startup emits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the app has no bean graph or no `LocalEventBus` bean.
+    #[cfg(feature = "events")]
+    pub fn events(&self) -> crate::EventRecorder {
+        self.events
+            .get_or_init(|| crate::EventRecorder::attach(&self.bean::<r2e_events::LocalEventBus>()))
+            .clone()
+    }
+
     /// The [`TestJwt`] wired by [`boot`](Self::boot) (or
     /// [`with_jwt`](Self::with_jwt)).
     ///
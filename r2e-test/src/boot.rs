@@ -79,6 +79,8 @@ impl TestApp {
             bean_context: Some(bean_context),
             config,
             jwt,
+            #[cfg(feature = "events")]
+            events: std::sync::OnceLock::new(),
         }
     }
 }
@@ -0,0 +1,313 @@
+//! Event-bus test harness: capture, await and assert emitted events.
+//!
+//! [`EventRecorder`] installs an [`EventObserver`] on a [`LocalEventBus`] and
+//! records every emit (with its metadata), every handler attempt and its
+//! [`HandlerResult`], and every event that exhausted its retry policy. Tests
+//! then wait for events deterministically instead of sleeping and polling.
+//!
+//! Retry backoff runs on Tokio's clock, so under
+//! `#[r2e::test(flavor = "current_thread", start_paused = true)]` a handler
+//! retried with multi-second delays completes instantly: the paused runtime
+//! auto-advances to the next timer whenever it is otherwise idle. The `events`
+//! feature enables Tokio's `test-util` for this.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use r2e_events::{
+    EventBus, EventMetadata, EventObserver, HandlerResult, LocalEventBus, ObservedDeadLetter,
+    ObservedEmit, ObservedHandling,
+};
+use serde::Serialize;
+use tokio::sync::Notify;
+
+#[derive(Default)]
+struct Records {
+    emitted: Vec<ObservedEmit>,
+    handled: Vec<ObservedHandling>,
+    dead_letters: Vec<ObservedDeadLetter>,
+}
+
+#[derive(Default)]
+struct Shared {
+    records: Mutex<Records>,
+    /// Woken on every emit, for [`EventRecorder::await_event`].
+    emitted: Notify,
+}
+
+impl Shared {
+    fn records(&self) -> MutexGuard<'_, Records> {
+        self.records.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// The observer half, installed on the bus.
+struct Tap(Arc<Shared>);
+
+impl EventObserver for Tap {
+    fn on_emit(&self, emit: &ObservedEmit) {
+        self.0.records().emitted.push(emit.clone());
+        self.0.emitted.notify_waiters();
+    }
+
+    fn on_handled(&self, handled: &ObservedHandling) {
+        self.0.records().handled.push(handled.clone());
+    }
+
+    fn on_dead_letter(&self, dead_letter: &ObservedDeadLetter) {
+        self.0.records().dead_letters.push(dead_letter.clone());
+    }
+}
+
+/// Records the traffic of a [`LocalEventBus`] for assertions.
+///
+/// Obtain one from [`TestApp::events`](crate::TestApp::events) (records the
+/// app's `LocalEventBus` bean from that point on), or build the bus from one
+/// so emits during startup are captured too:
+///
+/// ```ignore
+/// let events = EventRecorder::new();
+/// let app = TestApp::from_builder(
+///     AppBuilder::new()
+///         .provide(events.bus())
+///         .build_state()
+///         .await
+///         .register_controller::<OrderConsumers>(),
+/// );
+///
+/// app.post("/orders").json(&order).send().await.assert_created();
+/// let placed = events
+///     .await_event::<OrderPlaced>(|e| e.total > 0, Duration::from_secs(1))
+///     .await;
+/// ```
+///
+/// Cloning is cheap; clones share the recording.
+#[derive(Clone)]
+pub struct EventRecorder {
+    bus: LocalEventBus,
+    shared: Arc<Shared>,
+}
+
+impl EventRecorder {
+    /// A fresh [`LocalEventBus`] with a recorder attached — provide
+    /// [`bus`](Self::bus) to the app.
+    pub fn new() -> Self {
+        Self::attach(&LocalEventBus::new())
+    }
+
+    /// Start recording `bus` (and every clone of it), replacing any observer
+    /// already installed on it.
+    pub fn attach(bus: &LocalEventBus) -> Self {
+        let shared = Arc::new(Shared::default());
+        bus.set_observer(Tap(shared.clone()));
+        Self {
+            bus: bus.clone(),
+            shared,
+        }
+    }
+
+    /// The recorded bus.
+    pub fn bus(&self) -> LocalEventBus {
+        self.bus.clone()
+    }
+
+    // ── Inspection ──────────────────────────────────────────────────────
+
+    /// Every recorded emit, in emission order.
+    pub fn emitted(&self) -> Vec<ObservedEmit> {
+        self.shared.records().emitted.clone()
+    }
+
+    /// Every recorded event of type `E`, in emission order.
+    pub fn events<E: Send + Sync + 'static>(&self) -> Vec<Arc<E>> {
+        self.shared
+            .records()
+            .emitted
+            .iter()
+            .filter_map(ObservedEmit::downcast::<E>)
+            .collect()
+    }
+
+    /// Number of recorded events of type `E`.
+    pub fn count<E: 'static>(&self) -> usize {
+        self.shared
+            .records()
+            .emitted
+            .iter()
+            .filter(|emit| emit.is::<E>())
+            .count()
+    }
+
+    /// Every handler attempt, in completion order.
+    pub fn handled(&self) -> Vec<ObservedHandling> {
+        self.shared.records().handled.clone()
+    }
+
+    /// Every event whose handler exhausted a retry policy with a dead-letter
+    /// topic.
+    pub fn dead_letters(&self) -> Vec<ObservedDeadLetter> {
+        self.shared.records().dead_letters.clone()
+    }
+
+    /// Forget everything recorded so far.
+    pub fn clear(&self) {
+        *self.shared.records() = Records::default();
+    }
+
+    /// Wait until every in-flight handler has completed (see
+    /// [`LocalEventBus::wait_idle`]).
+    pub async fn wait_idle(&self) {
+        self.bus.wait_idle().await;
+    }
+
+    // ── Waiting ─────────────────────────────────────────────────────────
+
+    /// Wait for an event of type `E` matching `predicate` — already recorded
+    /// or emitted within `timeout` — and return it.
+    ///
+    /// # Panics
+    ///
+    /// Panics after `timeout`, listing what was emitted instead.
+    pub async fn await_event<E: Send + Sync + 'static>(
+        &self,
+        predicate: impl Fn(&E) -> bool,
+        timeout: Duration,
+    ) -> Arc<E> {
+        let wait = async {
+            loop {
+                // Register for the wake-up BEFORE scanning, so an emit between
+                // the scan and the await is not missed.
+                let notified = self.shared.emitted.notified();
+                tokio::pin!(notified);
+                notified.as_mut().enable();
+                if let Some(event) = self.find(&predicate) {
+                    return event;
+                }
+                notified.await;
+            }
+        };
+        match tokio::time::timeout(timeout, wait).await {
+            Ok(event) => event,
+            Err(_) => panic!(
+                "no matching `{}` emitted within {timeout:?}\n{}",
+                std::any::type_name::<E>(),
+                self.describe()
+            ),
+        }
+    }
+
+    fn find<E: Send + Sync + 'static>(&self, predicate: &impl Fn(&E) -> bool) -> Option<Arc<E>> {
+        self.shared
+            .records()
+            .emitted
+            .iter()
+            .filter_map(ObservedEmit::downcast::<E>)
+            .find(|event| predicate(event))
+    }
+
+    // ── Assertions ──────────────────────────────────────────────────────
+
+    /// Assert that at least one `E` was emitted.
+    pub fn assert_emitted<E: 'static>(&self) -> &Self {
+        if self.count::<E>() == 0 {
+            panic!(
+                "expected a `{}` to be emitted\n{}",
+                std::any::type_name::<E>(),
+                self.describe()
+            );
+        }
+        self
+    }
+
+    /// Assert that at least one emitted `E` matches `predicate`.
+    pub fn assert_emitted_matching<E: Send + Sync + 'static>(
+        &self,
+        predicate: impl Fn(&E) -> bool,
+    ) -> &Self {
+        if self.find(&predicate).is_none() {
+            panic!(
+                "expected a matching `{}` to be emitted\n{}",
+                std::any::type_name::<E>(),
+                self.describe()
+            );
+        }
+        self
+    }
+
+    /// Assert that exactly `times` `E`s were emitted.
+    pub fn assert_emitted_times<E: 'static>(&self, times: usize) -> &Self {
+        let count = self.count::<E>();
+        if count != times {
+            panic!(
+                "expected `{}` to be emitted {times} time(s), got {count}\n{}",
+                std::any::type_name::<E>(),
+                self.describe()
+            );
+        }
+        self
+    }
+
+    /// Assert that no `E` was emitted.
+    pub fn assert_not_emitted<E: 'static>(&self) -> &Self {
+        self.assert_emitted_times::<E>(0)
+    }
+
+    // ── Injection ───────────────────────────────────────────────────────
+
+    /// Emit `event` on the bus, wait for its handlers (including retries),
+    /// and return each handler's final [`HandlerResult`], in subscription
+    /// order. Handlers skipped by a filter contribute nothing.
+    ///
+    /// The injected event is recorded like any other emit.
+    pub async fn deliver<E: Serialize + Send + Sync + 'static>(
+        &self,
+        event: E,
+    ) -> Vec<HandlerResult> {
+        self.deliver_with(event, EventMetadata::new()).await
+    }
+
+    /// [`deliver`](Self::deliver) with explicit metadata (headers, partition
+    /// key, ...).
+    pub async fn deliver_with<E: Serialize + Send + Sync + 'static>(
+        &self,
+        event: E,
+        metadata: EventMetadata,
+    ) -> Vec<HandlerResult> {
+        let event_id = metadata.event_id;
+        self.bus
+            .emit_with(event, metadata)
+            .await
+            .unwrap_or_else(|e| panic!("failed to deliver `{}`: {e}", std::any::type_name::<E>()));
+        self.bus.wait_idle().await;
+
+        // Last attempt per subscription.
+        let mut results = BTreeMap::new();
+        for handled in &self.shared.records().handled {
+            if handled.event_id == event_id {
+                results.insert(handled.subscription.0, handled.result.clone());
+            }
+        }
+        results.into_values().collect()
+    }
+
+    /// Diagnostic listing of the recorded emits, for panic messages.
+    fn describe(&self) -> String {
+        let records = self.shared.records();
+        if records.emitted.is_empty() {
+            return "no events were emitted".to_string();
+        }
+        let mut out = format!("{} event(s) emitted:", records.emitted.len());
+        for emit in &records.emitted {
+            let _ = write!(out, "\n  - {} {}", emit.type_name, emit.payload);
+        }
+        out
+    }
+}
+
+impl Default for EventRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
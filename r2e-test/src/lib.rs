@@ -1,6 +1,8 @@
 #[macro_use]
 mod app;
 mod boot;
+#[cfg(feature = "events")]
+mod events;
 mod jwt;
 mod multipart;
 pub mod ordering;
@@ -14,6 +16,8 @@ pub use app::{
     json_contains, resolve_path, tokenize_path, PathToken, SameSite, SetCookie, TestApp,
     TestRequest, TestResponse,
 };
+#[cfg(feature = "events")]
+pub use events::EventRecorder;
 pub use jwt::{TestJwt, TokenBuilder};
 pub use server::TestServer;
 pub use session::{SessionRequest, TestSession};
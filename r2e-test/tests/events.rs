@@ -0,0 +1,170 @@
+//! `EventRecorder`: capture, await and assert emitted events, inject events
+//! into handlers and read back their `HandlerResult`s.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use r2e_core::AppBuilder;
+use r2e_events::{
+    EventBus, EventEnvelope, EventMetadata, HandlerResult, LocalEventBus, RetryPolicy,
+};
+use r2e_test::{EventRecorder, TestApp};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OrderPlaced {
+    id: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OrderShipped {
+    id: u64,
+}
+
+/// Re-emit every `OrderPlaced` as an `OrderShipped`, `Nack`ing odd ids.
+async fn shipper(bus: &LocalEventBus) -> r2e_events::SubscriptionId {
+    let out = bus.clone();
+    bus.subscribe(move |envelope: EventEnvelope<OrderPlaced>| {
+        let out = out.clone();
+        async move {
+            if envelope.event.id % 2 == 1 {
+                return HandlerResult::Nack(format!("order {} is odd", envelope.event.id));
+            }
+            out.emit(OrderShipped {
+                id: envelope.event.id,
+            })
+            .await
+            .into()
+        }
+    })
+    .await
+    .unwrap()
+    .id()
+}
+
+#[r2e_core::test]
+async fn records_emits_with_metadata() {
+    let events = EventRecorder::new();
+    let bus = events.bus();
+
+    bus.emit_with(
+        OrderPlaced { id: 1 },
+        EventMetadata::new().with_correlation_id("c-1"),
+    )
+    .await
+    .unwrap();
+    bus.emit(OrderPlaced { id: 2 }).await.unwrap();
+
+    events
+        .assert_emitted::<OrderPlaced>()
+        .assert_emitted_times::<OrderPlaced>(2)
+        .assert_emitted_matching::<OrderPlaced>(|e| e.id == 2)
+        .assert_not_emitted::<OrderShipped>();
+    let ids: Vec<u64> = events
+        .events::<OrderPlaced>()
+        .iter()
+        .map(|e| e.id)
+        .collect();
+    assert_eq!(ids, vec![1, 2]);
+    assert_eq!(
+        events.emitted()[0].metadata.correlation_id.as_deref(),
+        Some("c-1")
+    );
+
+    events.clear();
+    events.assert_not_emitted::<OrderPlaced>();
+}
+
+#[r2e_core::test]
+async fn await_event_waits_for_handler_emits() {
+    let events = EventRecorder::new();
+    shipper(&events.bus()).await;
+
+    events.bus().emit(OrderPlaced { id: 4 }).await.unwrap();
+    let shipped = events
+        .await_event::<OrderShipped>(|e| e.id == 4, Duration::from_secs(5))
+        .await;
+    assert_eq!(shipped.id, 4);
+}
+
+#[r2e_core::test]
+#[should_panic(expected = "no matching `events::OrderShipped` emitted within")]
+async fn await_event_panics_with_what_was_emitted() {
+    let events = EventRecorder::new();
+    events.bus().emit(OrderPlaced { id: 1 }).await.unwrap();
+    events
+        .await_event::<OrderShipped>(|_| true, Duration::from_millis(20))
+        .await;
+}
+
+#[r2e_core::test]
+#[should_panic(expected = "events::OrderPlaced {\"id\":3}")]
+async fn assertion_failures_list_payloads() {
+    let events = EventRecorder::new();
+    events.bus().emit(OrderPlaced { id: 3 }).await.unwrap();
+    events.assert_emitted::<OrderShipped>();
+}
+
+#[r2e_core::test]
+async fn deliver_returns_handler_results() {
+    let events = EventRecorder::new();
+    shipper(&events.bus()).await;
+
+    assert_eq!(
+        events.deliver(OrderPlaced { id: 2 }).await,
+        vec![HandlerResult::Ack]
+    );
+    events.assert_emitted_matching::<OrderShipped>(|e| e.id == 2);
+
+    assert_eq!(
+        events.deliver(OrderPlaced { id: 3 }).await,
+        vec![HandlerResult::Nack("order 3 is odd".into())]
+    );
+}
+
+#[r2e_core::test(flavor = "current_thread", start_paused = true)]
+async fn retries_run_on_the_paused_clock() {
+    let events = EventRecorder::new();
+    let bus = events.bus();
+    let id = shipper(&bus).await;
+    bus.configure_handler::<OrderPlaced>(
+        id,
+        None,
+        Some(RetryPolicy {
+            retry_delay: Duration::from_secs(60),
+            ..RetryPolicy::new(2).with_dlq("orders.dlq")
+        }),
+    )
+    .await;
+
+    let results = events.deliver(OrderPlaced { id: 5 }).await;
+
+    assert_eq!(results, vec![HandlerResult::Nack("order 5 is odd".into())]);
+    assert_eq!(events.handled().len(), 3);
+    let dead_letters = events.dead_letters();
+    assert_eq!(dead_letters.len(), 1);
+    assert_eq!(dead_letters[0].topic, "orders.dlq");
+}
+
+#[r2e_core::test]
+async fn test_app_records_its_bus_bean() {
+    let app = TestApp::from_builder(
+        AppBuilder::new()
+            .provide(LocalEventBus::new())
+            .build_state()
+            .await,
+    );
+    let events = app.events();
+
+    app.bean::<LocalEventBus>()
+        .emit(OrderPlaced { id: 9 })
+        .await
+        .unwrap();
+
+    // Same recorder on every call.
+    app.events().assert_emitted_times::<OrderPlaced>(1);
+    assert_eq!(events.events::<OrderPlaced>()[0].id, 9);
+    let _: Arc<OrderPlaced> = events
+        .await_event(|e: &OrderPlaced| e.id == 9, Duration::from_secs(1))
+        .await;
+}
@@ -0,0 +1,103 @@
+//! [`IdempotencyStore`] on top of the application's
+//! [`CacheStore`](r2e_cache::CacheStore).
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use r2e_cache::CacheStore;
+use r2e_core::idempotency::{
+    IdempotencyRecord, IdempotencyStore, StoreError, StoreFuture, StoredResponse,
+};
+
+/// Keeps `#[idempotent]` records in a [`CacheStore`] under
+/// `idempotency:<key>`, JSON-encoded.
+///
+/// `CacheStore` has no compare-and-set, so two requests with the same key
+/// that arrive at the same instant can both run the handler. Use the
+/// in-memory store on a single instance, or the SQL store of
+/// `r2e-data-sqlx` when the handler must run exactly once.
+///
+/// ```ignore
+/// let cache = r2e_cache::InMemoryStore::shared();
+/// AppBuilder::new()
+///     .provide(cache.clone())
+///     .provide(CacheIdempotencyStore::shared(cache))
+/// ```
+#[derive(Clone)]
+pub struct CacheIdempotencyStore {
+    cache: Arc<dyn CacheStore>,
+}
+
+impl CacheIdempotencyStore {
+    pub fn new(cache: Arc<dyn CacheStore>) -> Self {
+        Self { cache }
+    }
+
+    /// Ready-to-provide store bean: `Arc<dyn IdempotencyStore>`.
+    pub fn shared(cache: Arc<dyn CacheStore>) -> Arc<dyn IdempotencyStore> {
+        Arc::new(Self::new(cache))
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<IdempotencyRecord>, StoreError> {
+        match self.cache.get(&cache_key(key)).await {
+            Some(bytes) => IdempotencyRecord::from_bytes(&bytes).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    async fn set(&self, key: &str, record: &IdempotencyRecord, ttl: Duration) {
+        self.cache
+            .set(&cache_key(key), record.to_bytes().into(), ttl)
+            .await;
+    }
+}
+
+fn cache_key(key: &str) -> String {
+    format!("idempotency:{key}")
+}
+
+impl IdempotencyStore for CacheIdempotencyStore {
+    fn try_begin<'a>(
+        &'a self,
+        key: &'a str,
+        fingerprint: &'a str,
+        ttl: Duration,
+    ) -> StoreFuture<'a, Option<IdempotencyRecord>> {
+        Box::pin(async move {
+            if let Some(record) = self.get(key).await? {
+                return Ok(Some(record));
+            }
+            let in_flight = IdempotencyRecord::InFlight {
+                fingerprint: fingerprint.to_string(),
+            };
+            self.set(key, &in_flight, ttl).await;
+            Ok(None)
+        })
+    }
+
+    fn complete<'a>(
+        &'a self,
+        key: &'a str,
+        response: StoredResponse,
+        ttl: Duration,
+    ) -> StoreFuture<'a, ()> {
+        Box::pin(async move {
+            let Some(record) = self.get(key).await? else {
+                return Ok(());
+            };
+            let completed = IdempotencyRecord::Completed {
+                fingerprint: record.fingerprint().to_string(),
+                response,
+            };
+            self.set(key, &completed, ttl).await;
+            Ok(())
+        })
+    }
+
+    fn release<'a>(&'a self, key: &'a str) -> StoreFuture<'a, ()> {
+        Box::pin(async move {
+            self.cache.remove(&cache_key(key)).await;
+            Ok(())
+        })
+    }
+}
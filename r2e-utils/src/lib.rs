@@ -1,7 +1,11 @@
+pub mod idempotency;
 pub mod interceptors;
+pub mod session;
+pub use idempotency::CacheIdempotencyStore;
 pub use interceptors::{
     log_at_level, Cache, CacheInvalidate, Counted, LogLevel, Logged, MetricTimed, Timed,
 };
+pub use session::CacheSessionStore;
 
 pub mod prelude {
     //! Re-exports of the most commonly used utility interceptors.
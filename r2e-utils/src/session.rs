@@ -0,0 +1,72 @@
+//! [`SessionStore`] on top of the application's
+//! [`CacheStore`](r2e_cache::CacheStore).
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use r2e_cache::CacheStore;
+use r2e_core::session::{SessionRecord, SessionStore, StoreFuture};
+
+/// Keeps sessions in a [`CacheStore`] under `session:<id>`, JSON-encoded,
+/// with the cache entry expiring at the session's next timeout.
+///
+/// With a shared cache backend, every instance of the app sees the same
+/// sessions.
+///
+/// ```ignore
+/// let cache = r2e_cache::InMemoryStore::shared();
+/// AppBuilder::new()
+///     .provide(cache.clone())
+///     .provide(CacheSessionStore::shared(cache))
+/// ```
+#[derive(Clone)]
+pub struct CacheSessionStore {
+    cache: Arc<dyn CacheStore>,
+}
+
+impl CacheSessionStore {
+    pub fn new(cache: Arc<dyn CacheStore>) -> Self {
+        Self { cache }
+    }
+
+    /// Ready-to-provide store bean: `Arc<dyn SessionStore>`.
+    pub fn shared(cache: Arc<dyn CacheStore>) -> Arc<dyn SessionStore> {
+        Arc::new(Self::new(cache))
+    }
+}
+
+fn cache_key(id: &str) -> String {
+    format!("session:{id}")
+}
+
+impl SessionStore for CacheSessionStore {
+    fn load<'a>(&'a self, id: &'a str) -> StoreFuture<'a, Option<SessionRecord>> {
+        Box::pin(async move {
+            match self.cache.get(&cache_key(id)).await {
+                Some(bytes) => SessionRecord::from_bytes(&bytes).map(Some),
+                None => Ok(None),
+            }
+        })
+    }
+
+    fn save<'a>(
+        &'a self,
+        id: &'a str,
+        record: &'a SessionRecord,
+        ttl: Duration,
+    ) -> StoreFuture<'a, ()> {
+        Box::pin(async move {
+            self.cache
+                .set(&cache_key(id), record.to_bytes().into(), ttl)
+                .await;
+            Ok(())
+        })
+    }
+
+    fn delete<'a>(&'a self, id: &'a str) -> StoreFuture<'a, ()> {
+        Box::pin(async move {
+            self.cache.remove(&cache_key(id)).await;
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,40 @@
+use std::time::Duration;
+
+use r2e_core::idempotency::{IdempotencyRecord, IdempotencyStore, StoredResponse};
+use r2e_utils::CacheIdempotencyStore;
+
+#[r2e_core::test]
+async fn cache_store_keeps_records_until_released() {
+    let cache = r2e_cache::InMemoryStore::shared();
+    let store = CacheIdempotencyStore::new(cache.clone());
+    let ttl = Duration::from_secs(60);
+
+    assert_eq!(store.try_begin("ada:k", "f", ttl).await.unwrap(), None);
+    assert_eq!(
+        store.try_begin("ada:k", "f", ttl).await.unwrap(),
+        Some(IdempotencyRecord::InFlight {
+            fingerprint: "f".into()
+        })
+    );
+
+    let response = StoredResponse {
+        status: 201,
+        headers: vec![("location".into(), "/payments/1".into())],
+        body: b"{\"id\":1}".to_vec(),
+    };
+    store
+        .complete("ada:k", response.clone(), ttl)
+        .await
+        .unwrap();
+    assert!(cache.get("idempotency:ada:k").await.is_some());
+    assert_eq!(
+        store.try_begin("ada:k", "g", ttl).await.unwrap(),
+        Some(IdempotencyRecord::Completed {
+            fingerprint: "f".into(),
+            response
+        })
+    );
+
+    store.release("ada:k").await.unwrap();
+    assert_eq!(store.try_begin("ada:k", "g", ttl).await.unwrap(), None);
+}
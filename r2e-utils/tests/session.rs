@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+use r2e_core::session::{SessionRecord, SessionStore};
+use r2e_utils::CacheSessionStore;
+
+#[r2e_core::test]
+async fn cache_store_keeps_sessions_until_deleted() {
+    let cache = r2e_cache::InMemoryStore::shared();
+    let store = CacheSessionStore::new(cache.clone());
+    let ttl = Duration::from_secs(60);
+
+    assert_eq!(store.load("abc").await.unwrap(), None);
+
+    let mut record = SessionRecord::new(1_000);
+    record
+        .data
+        .insert("cart".into(), serde_json::json!(["apple"]));
+    store.save("abc", &record, ttl).await.unwrap();
+    assert!(cache.get("session:abc").await.is_some());
+    assert_eq!(store.load("abc").await.unwrap(), Some(record));
+
+    store.delete("abc").await.unwrap();
+    assert_eq!(store.load("abc").await.unwrap(), None);
+}
+
+#[r2e_core::test]
+async fn cache_entries_expire_with_the_session() {
+    let store = CacheSessionStore::new(r2e_cache::InMemoryStore::shared());
+    store
+        .save("abc", &SessionRecord::new(1_000), Duration::from_millis(50))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(store.load("abc").await.unwrap(), None);
+}
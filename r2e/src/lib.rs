@@ -18,20 +18,28 @@
 //! | `data-diesel` | no      | `r2e-data-diesel`         |
 //! | `sqlx-sqlite` / `sqlx-postgres` / `sqlx-mysql` | no | managed SQLx transactions |
 //! | `diesel-sqlite` / `diesel-postgres` / `diesel-mysql` | no | managed Diesel transactions |
+//! | `diesel-migrations` | no | Diesel embedded migrations applied at boot |
 //! | `scheduler`   | no      | `r2e-scheduler`           |
 //! | `executor`    | no      | `r2e-executor` (managed task pool, à la J2EE `ManagedExecutorService`) |
 //! | `cache`       | no      | `r2e-cache`               |
 //! | `rate-limit`  | no      | `r2e-rate-limit`          |
+//! | `overload`    | no      | `r2e-overload` (adaptive concurrency limit + load shedding) |
 //! | `openapi`     | no      | `r2e-openapi` (also add `schemars = "1"` to your deps) |
+//! | `asyncapi`    | no      | `r2e-asyncapi` (AsyncAPI 3.0 document for event consumers; implies `events`) |
 //! | `prometheus`  | no      | `r2e-prometheus`          |
 //! | `openfga`     | no      | `r2e-openfga`             |
 //! | `events-kafka`    | no  | `r2e-events-kafka` (Apache Kafka backend) |
 //! | `events-pulsar`   | no  | `r2e-events-pulsar` (Apache Pulsar backend) |
 //! | `events-rabbitmq` | no  | `r2e-events-rabbitmq` (RabbitMQ/AMQP backend) |
 //! | `static`      | no      | `r2e-static` (embedded static file serving + SPA fallback) |
+//! | `templates`   | no      | `r2e-templates` (MiniJinja engine for `#[template]` routes) |
+//! | `csv`         | no      | `r2e-core/csv` (`text/csv` codec for `#[produces]` / `#[consumes]`) |
+//! | `cbor`        | no      | `r2e-core/cbor` (`application/cbor` codec for `#[produces]` / `#[consumes]`) |
 //! | `validation`  | no      | `r2e-core/validation`     |
+//! | `tls`         | no      | `r2e-core/tls` (native HTTPS from `server.tls`, **not** in `full`) |
+//! | `mtls`        | no      | `r2e-security/mtls` (`ClientCertIdentity`; implies `tls`) |
 //! | `dev-reload`  | no      | `r2e-devtools` (Subsecond hot-patch, **not** in `full`) |
-//! | `full`        | no      | Bundled framework modules; database/event backends, QUIC, and dev reload stay opt-in |
+//! | `full`        | no      | Bundled framework modules; database/event backends, QUIC, TLS, and dev reload stay opt-in |
 
 // Re-export sub-crates as public modules so they're accessible as
 // `r2e::r2e_core`, `r2e::r2e_events`, etc.
@@ -83,12 +91,18 @@ pub use r2e_executor;
 #[cfg(feature = "cache")]
 pub use r2e_cache;
 
+#[cfg(feature = "overload")]
+pub use r2e_overload;
+
 #[cfg(feature = "oidc")]
 pub use r2e_oidc;
 
 #[cfg(feature = "openapi")]
 pub use r2e_openapi;
 
+#[cfg(feature = "asyncapi")]
+pub use r2e_asyncapi;
+
 #[cfg(feature = "prometheus")]
 pub use r2e_prometheus;
 
@@ -101,6 +115,9 @@ pub use r2e_grpc;
 #[cfg(feature = "static")]
 pub use r2e_static;
 
+#[cfg(feature = "templates")]
+pub use r2e_templates;
+
 #[cfg(feature = "observability")]
 pub use r2e_observability;
 